@@ -0,0 +1,135 @@
+#!/usr/bin/env python3
+"""Fails if the parser in a release librslib.a can still panic.
+
+The device build uses panic_immediate_abort, which inlines every panic into
+an abort and leaves no trace of it in the binary. The library is built for
+the host instead (see rust_panic_check in app/Makefile) so that a panic left
+in the parser is a call to the core panic functions, and shows up as a
+relocation against them in the rslib or stacks_ledger_parser objects. Calls to generic
+core code that panics, such as slice indexing, are followed as well.
+
+usage: check_panics.py <librslib.a>
+"""
+
+import os
+import re
+import subprocess
+import sys
+import tempfile
+
+CRATES = ("rslib-", "stacks_ledger_parser-")
+
+PANICS = re.compile(
+    r"^core::panicking::"
+    r"|^core::option::(unwrap|expect)_failed"
+    r"|^core::result::unwrap_failed"
+    r"|^core::slice::index::\w+_fail"
+    r"|::len_mismatch_fail"
+    r"|^core::str::\w*slice_error_fail"
+    r"|^core::cell::panic_already"
+)
+
+# Code of other crates built into ours, their panics are on paths the
+# parser does not take: base64 and hex are given outputs large enough,
+# serde_json_core only reads the JWT header, ArrayVec only slices below its
+# length and core::fmt only panics on arguments the parser does not format
+ALLOWED = re.compile(
+    r"^<?(&mut )?serde_json_core::"
+    r"|^<?base64::"
+    r"|^<?hex::"
+    r"|^<?arrayvec::"
+    r"|^<?core::fmt::"
+)
+
+# the parser and the FFI exports of rslib, which are not in a module
+OURS = re.compile(r"stacks_ledger_parser::|^[^:<]+$")
+
+SECTION = re.compile(r"^Relocation section '\.rela?\.text\.(?:unlikely\.|hot\.)?(\S+)'")
+
+
+def run(*args, cwd=None):
+    return subprocess.run(args, cwd=cwd, check=True, capture_output=True, text=True).stdout
+
+
+def demangle(names):
+    names = sorted(names)
+    out = subprocess.run(["c++filt"], input="\n".join(names), capture_output=True, text=True)
+    if out.returncode != 0:
+        return {name: name for name in names}
+    # v0 names carry the crate hash, core[c1f1a4ba060b9bfa]::...
+    return {name: re.sub(r"\[[0-9a-f]+\]", "", demangled)
+            for name, demangled in zip(names, out.stdout.splitlines())}
+
+
+def calls(obj, graph):
+    """adds the functions of obj to graph, along with the symbols they refer to"""
+    function = None
+    for line in run("readelf", "-rW", obj).splitlines():
+        section = SECTION.match(line)
+        if section:
+            function = section.group(1)
+            graph.setdefault(function, set())
+            continue
+        if line.startswith("Relocation section"):
+            function = None
+            continue
+        fields = line.split()
+        if function and len(fields) >= 5:
+            symbol = fields[4]
+            # calls to local functions refer to their section
+            for prefix in (".text.unlikely.", ".text.hot.", ".text."):
+                if symbol.startswith(prefix):
+                    symbol = symbol[len(prefix):]
+                    break
+            graph[function].add(symbol)
+
+
+def main():
+    if len(sys.argv) != 2:
+        sys.exit(__doc__.strip().splitlines()[-1])
+    lib = os.path.abspath(sys.argv[1])
+
+    graph = {}
+    with tempfile.TemporaryDirectory() as tmp:
+        members = [m for m in run("ar", "t", lib).splitlines() if m.startswith(CRATES)]
+        if not members:
+            sys.exit("no rslib or stacks_ledger_parser objects in " + lib)
+        run("ar", "x", lib, *members, cwd=tmp)
+        for member in members:
+            calls(os.path.join(tmp, member), graph)
+
+    names = demangle(set(graph).union(*graph.values()))
+
+    # the panics and panicking functions each function calls, found by
+    # walking the calls until nothing changes
+    panics = {f: {s for s in callees if PANICS.search(names[s])} for f, callees in graph.items()}
+    changed = True
+    while changed:
+        changed = False
+        for function, callees in graph.items():
+            for callee in callees:
+                if panics.get(callee) and not ALLOWED.search(names[callee]):
+                    if callee not in panics[function]:
+                        panics[function].add(callee)
+                        changed = True
+
+    # only the functions of ours a panic comes in through are reported,
+    # not every function that calls them
+    found = []
+    for function, callees in panics.items():
+        name = names[function]
+        causes = sorted(names[c] for c in callees if not OURS.search(names[c]))
+        if causes and OURS.search(name) and not ALLOWED.search(name):
+            found.append((name, causes))
+    found.sort()
+    if not found:
+        print("no panics in " + lib)
+        return
+
+    for function, callees in found:
+        print("{}\n    {}".format(function, "\n    ".join(callees)))
+    sys.exit("{} functions can panic".format(len(found)))
+
+
+if __name__ == "__main__":
+    main()
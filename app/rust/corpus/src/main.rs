@@ -0,0 +1,237 @@
+//! Keeps the fuzzing corpus of the parser useful as it grows.
+//!
+//! The corpus of each target lives in parser/fuzz/corpus/<target>, see the
+//! fuzz targets in parser/fuzz. This tool
+//!
+//! - merges inputs from other directories into it, every input named after
+//!   its content so the same input is never kept twice,
+//! - minimizes it, keeping the shortest input of each Outcome of the target,
+//! - turns a crash artifact into a named fixture in
+//!   parser/tests/regressions, which the regressions tests run again.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::process;
+
+use stacks_ledger_parser::hash::{DefaultSha256, Sha256};
+use stacks_ledger_parser::parser::fuzzing::{self, Outcome, Target};
+
+const USAGE: &str = "usage:
+    corpus merge <target> <dir>...              adds the inputs in dirs to the corpus of target
+    corpus minimize <target>                    keeps the shortest input of each outcome
+    corpus fixture <target> <artifact> <name>   writes the regression fixture of a crash";
+
+// bytes of the content hash inputs are named after, as long as the sha1
+// names of libFuzzer
+const NAME_HASH_LEN: usize = 20;
+
+fn fail(msg: &str) -> ! {
+    eprintln!("{}", msg);
+    process::exit(1)
+}
+
+fn rust_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("..")
+}
+
+fn corpus_dir(target: &str) -> PathBuf {
+    rust_dir()
+        .join("parser")
+        .join("fuzz")
+        .join("corpus")
+        .join(target)
+}
+
+fn input_name(input: &[u8]) -> String {
+    let mut hash = [0u8; 32];
+    DefaultSha256::digest(input, &mut hash);
+    hex::encode(&hash[..NAME_HASH_LEN])
+}
+
+// the files in dir, sorted by name
+fn inputs(dir: &Path) -> Vec<PathBuf> {
+    let entries = fs::read_dir(dir)
+        .unwrap_or_else(|e| fail(&format!("can not read {}: {}", dir.display(), e)));
+    let mut paths: Vec<_> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file())
+        .collect();
+    paths.sort();
+    paths
+}
+
+fn read(path: &Path) -> Vec<u8> {
+    fs::read(path).unwrap_or_else(|e| fail(&format!("can not read {}: {}", path.display(), e)))
+}
+
+fn write(path: &Path, data: &[u8]) {
+    fs::write(path, data)
+        .unwrap_or_else(|e| fail(&format!("can not write {}: {}", path.display(), e)))
+}
+
+fn remove(path: &Path) {
+    fs::remove_file(path)
+        .unwrap_or_else(|e| fail(&format!("can not remove {}: {}", path.display(), e)))
+}
+
+// runs the target over input, None if it panics
+fn outcome(run: Target, input: &[u8]) -> Option<Outcome> {
+    panic::catch_unwind(AssertUnwindSafe(|| run(input))).ok()
+}
+
+fn target(name: &str) -> Target {
+    fuzzing::target(name).unwrap_or_else(|| {
+        let names: Vec<_> = fuzzing::TARGETS.iter().map(|(name, _)| *name).collect();
+        fail(&format!(
+            "unknown target {}, one of {}",
+            name,
+            names.join(", ")
+        ))
+    })
+}
+
+/// Copies the inputs in dirs into the corpus of target, and renames the
+/// ones already there, after their content. Duplicates are dropped
+fn merge(target_name: &str, dirs: &[String]) {
+    target(target_name);
+    let corpus = corpus_dir(target_name);
+    fs::create_dir_all(&corpus)
+        .unwrap_or_else(|e| fail(&format!("can not create {}: {}", corpus.display(), e)));
+
+    let mut added = 0;
+    let mut duplicates = 0;
+    for path in inputs(&corpus) {
+        let name = input_name(&read(&path));
+        if path.file_name() != Some(name.as_ref()) {
+            let named = corpus.join(&name);
+            if named.exists() {
+                duplicates += 1;
+            } else {
+                write(&named, &read(&path));
+            }
+            remove(&path);
+        }
+    }
+    for dir in dirs {
+        for path in inputs(Path::new(dir)) {
+            let input = read(&path);
+            let named = corpus.join(input_name(&input));
+            if named.exists() {
+                duplicates += 1;
+            } else {
+                write(&named, &input);
+                added += 1;
+            }
+        }
+    }
+    println!(
+        "{}: {} inputs, {} added, {} duplicates dropped",
+        target_name,
+        inputs(&corpus).len(),
+        added,
+        duplicates
+    );
+}
+
+/// Keeps the shortest input of each outcome of target, inputs that crash
+/// are kept and reported
+fn minimize(target_name: &str) {
+    let run = target(target_name);
+    let corpus = corpus_dir(target_name);
+
+    // silence the panic messages of the crashing inputs, they are listed below
+    panic::set_hook(Box::new(|_| {}));
+
+    let mut shortest: BTreeMap<Outcome, (usize, PathBuf)> = BTreeMap::new();
+    let mut crashes = Vec::new();
+    let mut dropped = Vec::new();
+    for path in inputs(&corpus) {
+        let input = read(&path);
+        let outcome = match outcome(run, &input) {
+            Some(outcome) => outcome,
+            None => {
+                crashes.push(path);
+                continue;
+            }
+        };
+        match shortest.get(&outcome) {
+            Some((len, _)) if *len <= input.len() => dropped.push(path),
+            _ => {
+                if let Some((_, longer)) = shortest.insert(outcome, (input.len(), path)) {
+                    dropped.push(longer);
+                }
+            }
+        }
+    }
+    let _ = panic::take_hook();
+
+    for path in &dropped {
+        remove(path);
+    }
+    println!(
+        "{}: {} outcomes kept, {} inputs dropped",
+        target_name,
+        shortest.len(),
+        dropped.len()
+    );
+    for path in &crashes {
+        println!("crashes: {}", path.display());
+    }
+}
+
+/// Writes the fixture name of a crash artifact of target, the regressions
+/// tests fail until the crash is fixed
+fn fixture(target_name: &str, artifact: &str, name: &str) {
+    let run = target(target_name);
+    let valid = |c: char| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_';
+    if name.is_empty() || !name.chars().all(valid) {
+        fail("a fixture name is made of a-z, 0-9 and _");
+    }
+
+    let artifact = Path::new(artifact);
+    let input = read(artifact);
+    let dir = rust_dir().join("parser").join("tests").join("regressions");
+    fs::create_dir_all(&dir)
+        .unwrap_or_else(|e| fail(&format!("can not create {}: {}", dir.display(), e)));
+    let path = dir.join(format!("{}.json", name));
+    if path.exists() {
+        fail(&format!("{} already exists", path.display()));
+    }
+
+    let artifact_name = artifact
+        .file_name()
+        .map(|file| file.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let json = format!(
+        "{{\n  \"target\": \"{}\",\n  \"artifact\": \"{}\",\n  \"input\": \"{}\"\n}}\n",
+        target_name,
+        artifact_name,
+        hex::encode(&input)
+    );
+    write(&path, json.as_bytes());
+    println!("wrote {}", path.display());
+
+    panic::set_hook(Box::new(|_| {}));
+    let crashes = outcome(run, &input).is_none();
+    let _ = panic::take_hook();
+    if crashes {
+        println!("it still crashes, the regressions tests fail until it is fixed");
+    }
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args
+        .iter()
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .as_slice()
+    {
+        ["merge", target, dirs @ ..] if !dirs.is_empty() => merge(target, &args[2..]),
+        ["minimize", target] => minimize(target),
+        ["fixture", target, artifact, name] => fixture(target, artifact, name),
+        _ => fail(USAGE),
+    }
+}
@@ -0,0 +1,55 @@
+//! Differential tests of the parser against the stacks-core codecs.
+//!
+//! The same bytes are decoded by the parser and by the node's own
+//! transaction and Clarity value codecs, both have to agree on whether they
+//! are valid, on the decoded fields and on the initial sighash. A mismatch
+//! means the device could show or sign something the network reads
+//! differently.
+//!
+//! The parser side comes from its `differential` feature, the tests are in
+//! tests/. This crate is kept out of the app workspace so that its tests are
+//! the only ones that need the stacks-core crates, run them with `cargo test`
+//! in this directory.
+
+use std::fs;
+use std::path::PathBuf;
+
+use stacks_common::codec::StacksMessageCodec;
+
+/// Decodes bytes with the node codec, returning how many were consumed
+pub fn node_decode<T: StacksMessageCodec>(bytes: &[u8]) -> Option<(T, usize)> {
+    let mut reader = bytes;
+    let decoded = T::consensus_deserialize(&mut reader).ok()?;
+    Some((decoded, bytes.len() - reader.len()))
+}
+
+/// Encodes a value with the node codec
+pub fn node_bytes<T: StacksMessageCodec>(value: &T) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    value.consensus_serialize(&mut bytes).unwrap();
+    bytes
+}
+
+/// The raw transactions of the json vectors in parser/tests, by name
+pub fn transaction_vectors() -> Vec<(String, Vec<u8>)> {
+    let mut dir = PathBuf::new();
+    dir.push(env!("CARGO_MANIFEST_DIR"));
+    dir.push("../parser/tests");
+
+    let mut vectors: Vec<_> = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .map(|path| {
+            let str = fs::read_to_string(&path).expect("Error opening json file");
+            let json: serde_json::Value = serde_json::from_str(&str).unwrap();
+            let raw = hex::decode(json["raw"].as_str().unwrap()).unwrap();
+            (
+                path.file_stem().unwrap().to_string_lossy().into_owned(),
+                raw,
+            )
+        })
+        .collect();
+    vectors.sort();
+    vectors
+}
@@ -0,0 +1,251 @@
+use blockstack_lib::chainstate::stacks::{
+    StacksTransaction, TransactionPayload as NodePayload, TransactionVersion as NodeVersion,
+};
+use clarity::vm::types::{PrincipalData, Value as NodeValue};
+use differential::{node_bytes, node_decode, transaction_vectors};
+use stacks_common::address::c32 as node_c32;
+use stacks_ledger_parser::parser::differential::{self as ours, Payload};
+
+fn check_payload(name: &str, ours: &Payload, node: &NodePayload) {
+    match (ours, node) {
+        (
+            Payload::TokenTransfer {
+                amount,
+                memo,
+                recipient,
+            },
+            NodePayload::TokenTransfer(to, node_amount, node_memo),
+        ) => {
+            assert_eq!(amount, node_amount, "{}", name);
+            assert_eq!(&memo[..], &node_memo.0[..], "{}", name);
+            if let PrincipalData::Standard(_) = to {
+                let recipient = recipient.as_ref().unwrap();
+                assert_eq!(&recipient[..], to.to_string().as_bytes(), "{}", name);
+            }
+        }
+        (Payload::SmartContract { name: contract }, NodePayload::SmartContract(node, _)) => {
+            assert_eq!(&contract[..], node.name.as_bytes(), "{}", name);
+        }
+        (
+            Payload::ContractCall {
+                address,
+                contract_name,
+                function_name,
+                args,
+            },
+            NodePayload::ContractCall(node),
+        ) => {
+            assert_eq!(
+                &contract_name[..],
+                node.contract_name.as_bytes(),
+                "{}",
+                name
+            );
+            assert_eq!(
+                &function_name[..],
+                node.function_name.as_bytes(),
+                "{}",
+                name
+            );
+            assert_eq!(
+                &address[..],
+                node.address.to_string().as_bytes(),
+                "{}",
+                name
+            );
+
+            assert_eq!(args.len(), node.function_args.len(), "{}", name);
+            for (arg, node_arg) in args.iter().zip(&node.function_args) {
+                assert_eq!(&arg[..], &node_bytes(node_arg)[..], "{}", name);
+            }
+        }
+        _ => unreachable!("{}: payload types differ", name),
+    }
+}
+
+#[test]
+fn test_transactions_match_node() {
+    for (name, raw) in transaction_vectors() {
+        let ours = ours::transaction(&raw).unwrap();
+        let (node, consumed) = node_decode::<StacksTransaction>(&raw)
+            .unwrap_or_else(|| unreachable!("{}: rejected by the node", name));
+
+        // anything left after the payload is previous signer data
+        assert_eq!(ours.len, consumed, "{}", name);
+
+        let mainnet = match node.version {
+            NodeVersion::Mainnet => true,
+            NodeVersion::Testnet => false,
+        };
+        assert_eq!(ours.mainnet, mainnet, "{}", name);
+        assert_eq!(ours.chain_id, node.chain_id, "{}", name);
+        assert_eq!(ours.anchor_mode, node.anchor_mode as u8, "{}", name);
+        assert_eq!(
+            ours.post_condition_mode, node.post_condition_mode as u8,
+            "{}",
+            name
+        );
+
+        // spending conditions
+        let origin = node.auth.origin();
+        assert_eq!(ours.origin_nonce, origin.nonce(), "{}", name);
+        assert_eq!(ours.origin_fee, origin.tx_fee(), "{}", name);
+        let node_signer = match node.version {
+            NodeVersion::Mainnet => origin.address_mainnet(),
+            NodeVersion::Testnet => origin.address_testnet(),
+        };
+        assert_eq!(
+            &ours.signer[..],
+            node_signer.to_string().as_bytes(),
+            "{}",
+            name
+        );
+
+        let node_sponsor = node.auth.sponsor();
+        assert_eq!(
+            ours.sponsor_nonce,
+            node_sponsor.map(|s| s.nonce()),
+            "{}",
+            name
+        );
+        assert_eq!(
+            ours.sponsor_fee,
+            node_sponsor.map(|s| s.tx_fee()),
+            "{}",
+            name
+        );
+
+        // post conditions, each one re-encoded by the node as we hold it
+        assert_eq!(
+            ours.post_conditions.len(),
+            node.post_conditions.len(),
+            "{}",
+            name
+        );
+        for (raw_condition, condition) in ours.post_conditions.iter().zip(&node.post_conditions) {
+            assert_eq!(&raw_condition[..], &node_bytes(condition)[..], "{}", name);
+        }
+
+        check_payload(&name, &ours.payload, &node.payload);
+
+        // the hash the origin starts signing from
+        assert_eq!(ours.initial_sighash, node.sign_begin().0, "{}", name);
+    }
+}
+
+#[test]
+fn test_truncated_transactions_rejected_by_both() {
+    for (name, raw) in transaction_vectors() {
+        let (_, consumed) = node_decode::<StacksTransaction>(&raw).unwrap();
+        for len in 0..consumed {
+            let truncated = &raw[..len];
+            assert!(
+                node_decode::<StacksTransaction>(truncated).is_none(),
+                "{}: node accepted {} bytes",
+                name,
+                len
+            );
+            assert!(
+                ours::transaction(truncated).is_none(),
+                "{}: parser accepted {} bytes",
+                name,
+                len
+            );
+        }
+    }
+}
+
+// the encoded values of the Clarity value unit tests, plus nested and
+// boundary values
+const VALUES: &[&str] = &[
+    "0c0000000201610000000000000000000000000000000001016303",
+    "0c0000000401610c00000002016100000000000000000000000000000000010162000000000000000000000000000000000101620c000000020161000000000000000000000000000000000101620301630c000000020161000000000000000000000000000000000101620301760000000000000000000000000000000001",
+    "0b00000003000000000000000000000000000000000100000000000000000000000000000000020000000000000000000000000000000003",
+    "0b000000030a000000000000000000000000000000000f090a000000000000000000000000000000000f",
+    "0a0a0a0100000000000000000000000000000001",
+    "020000001600deadbeef00080919558081fa240400010204080907",
+    "0d0000000568656c6c6f",
+    "0e0000002d436f6e73696465722074686520656e636f64696e67206f6620746865206575726f207369676e2c20e282ac3a20",
+    "0516a5d9d331000f5b79578ce56bd157f29a9056f0d6",
+    "0616a5d9d331000f5b79578ce56bd157f29a9056f0d60b6d792d636f6e7472616374",
+    "0700ffffffffffffffffffffffffffffffff",
+    "0801ffffffffffffffffffffffffffffffff",
+    "0b00000000",
+    "03",
+    "04",
+    "09",
+];
+
+// encodings both codecs have to reject
+const INVALID_VALUES: &[&str] = &[
+    // unknown type prefix
+    "0f",
+    // truncated int
+    "00000000000000000000000000000001",
+    // list claiming more items than it holds
+    "0b000000030300",
+    // non ascii byte in an ascii string
+    "0d00000002ff00",
+    // buffer longer than the bytes left
+    "0200000010deadbeef",
+    // tuple key longer than the bytes left
+    "0c000000010561",
+    // some without an inner value
+    "0a",
+];
+
+#[test]
+fn test_clarity_values_match_node() {
+    for encoded in VALUES {
+        let bytes = hex::decode(encoded).unwrap();
+
+        let value = ours::clarity_value(&bytes)
+            .unwrap_or_else(|| unreachable!("{}: rejected by the parser", encoded));
+        let (node, consumed) = node_decode::<NodeValue>(&bytes)
+            .unwrap_or_else(|| unreachable!("{}: rejected by the node", encoded));
+
+        assert_eq!(value.len, consumed, "{}", encoded);
+        assert_eq!(value.bytes, node_bytes(&node), "{}", encoded);
+
+        match node {
+            NodeValue::Int(v) => assert_eq!(value.int, Some(v), "{}", encoded),
+            NodeValue::UInt(v) => assert_eq!(value.uint, Some(v), "{}", encoded),
+            NodeValue::Tuple(ref tuple) => {
+                assert_eq!(value.tuple_len, Some(tuple.data_map.len()), "{}", encoded);
+            }
+            _ => {}
+        }
+    }
+
+    for encoded in INVALID_VALUES {
+        let bytes = hex::decode(encoded).unwrap();
+        assert!(
+            ours::clarity_value(&bytes).is_none(),
+            "{}: accepted by the parser",
+            encoded
+        );
+        assert!(
+            node_decode::<NodeValue>(&bytes).is_none(),
+            "{}: accepted by the node",
+            encoded
+        );
+    }
+}
+
+#[test]
+fn test_c32_addresses_match_node() {
+    let hashes = [
+        [0u8; 20],
+        [0xff; 20],
+        *b"\x00\x00\x01\x02\x03\x04\x05\x06\x07\x08\x09\x0a\x0b\x0c\x0d\x0e\x0f\x10\x11\x12",
+        *b"\xa5\xd9\xd3\x31\x00\x0f\x5b\x79\x57\x8c\xe5\x6b\xd1\x57\xf2\x9a\x90\x56\xf0\xd6",
+    ];
+
+    for version in 0..32u8 {
+        for hash in hashes.iter() {
+            let ours = ours::c32_address(version, hash).unwrap();
+            let node = node_c32::c32_address(version, hash).unwrap();
+            assert_eq!(&ours[..], node.as_bytes(), "version {}", version);
+        }
+    }
+}
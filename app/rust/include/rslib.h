@@ -1,49 +1,316 @@
 #pragma once
 
+// Generated by cbindgen from src/ffi.rs, do not edit. Run `make rust_header` to update it
+
 #include <stdint.h>
 #include "parser_common.h"
 #include "parser_txdef.h"
 
+// Bytes of the scan of a contract code body for what it can do with assets
+#define RS_CODE_SCAN_LEN 21
+
+// Bytes of the settings blob kept in flash
+#define RS_SETTINGS_BLOB_LEN 13
+
+parser_error_t _parser_init(parser_context_t *ctx,
+                            const uint8_t *buffer,
+                            uint16_t bufferSize,
+                            uint16_t *alloc_size);
+
+parser_error_t _read(const parser_context_t *context, parser_tx_t *parser_state);
+
+parser_error_t _getNumItems(const parser_context_t *_ctx,
+                            const parser_tx_t *tx_t,
+                            uint8_t *num_items);
+
+parser_error_t _getItem(const parser_context_t *_ctx,
+                        uint8_t displayIdx,
+                        char *outKey,
+                        uint16_t outKeyLen,
+                        char *outValue,
+                        uint16_t outValueLen,
+                        uint8_t pageIdx,
+                        uint8_t *pageCount,
+                        const parser_tx_t *tx_t);
+
+parser_error_t _getItemKind(const parser_tx_t *tx_t, uint8_t displayIdx, uint8_t *kind);
+
+parser_error_t _getItemSection(const parser_tx_t *tx_t, uint8_t displayIdx, uint8_t *section);
+
+parser_error_t _auth_flag(const parser_tx_t *tx_t, uint8_t *auth_flag);
+
+uint8_t _fee_bytes(const parser_tx_t *tx_t, uint8_t *fee, uint16_t fee_len);
+
+uint8_t _nonce_bytes(const parser_tx_t *tx_t, uint8_t *nonce, uint16_t nonce_len);
+
+parser_error_t _check_pubkey_hash(const parser_tx_t *tx_t,
+                                  const uint8_t *pubKey,
+                                  uint16_t pubKeyLen);
+
+// Checks pubKey, the compressed key of this device, against a multisig
+// origin, account is the hash160 of the last template the key was found
+// in, if any. A key that can not be matched to the origin is warned about
+parser_error_t _check_multisig_signer(const parser_tx_t *tx_t,
+                                      const uint8_t *pubKey,
+                                      uint16_t pubKeyLen,
+                                      const uint8_t *account,
+                                      uint16_t accountLen);
+
+// Writes the index of the auth field of the multisig origin the signature
+// of pubKey, the compressed key of this device, goes in
+parser_error_t _multisig_auth_field_index(const parser_tx_t *tx_t,
+                                          const uint8_t *pubKey,
+                                          uint16_t pubKeyLen,
+                                          uint32_t *index);
+
+// Checks the sponsor is the key whose hash160 is given, after the origin
+// was checked with _check_pubkey_hash, for a device signing as both
+parser_error_t _check_sponsor_pubkey_hash(const parser_tx_t *tx_t,
+                                          const uint8_t *pubKey,
+                                          uint16_t pubKeyLen);
+
+// Writes the auth flag, fee and nonce the origin, role 0, or the sponsor,
+// role 1, appends to the hash it signs. Returns the bytes written, 0 on error
+uint16_t _role_auth_data(const parser_tx_t *tx_t, uint8_t role, uint8_t *buf, uint16_t bufLen);
+
+parser_error_t _check_path(const parser_tx_t *tx_t, const uint32_t *path, uint8_t pathLen);
+
+uint8_t _is_nonstandard_path(const parser_tx_t *tx_t);
+
+parser_error_t _check_network(const parser_tx_t *tx_t, uint8_t testnet);
+
+parser_error_t _check_denylist(const parser_tx_t *tx_t,
+                               const uint8_t *entries,
+                               uint16_t entriesLen);
+
+uint8_t _is_denylisted(const parser_tx_t *tx_t);
+
+// Shows what the code of a contract deploy can do with assets, scan is
+// the scan of the whole code body if part of it was dropped, NULL otherwise
+parser_error_t _check_code(const parser_tx_t *tx_t, const uint8_t *scan, uint16_t scanLen);
+
+// Reviews the arguments of the contract call by the names of the
+// provisioned function signature abi, if it is the one called
+parser_error_t _check_abi(const parser_tx_t *tx_t, const uint8_t *abi, uint16_t abiLen);
+
+// Shows the token amounts of the post conditions after the provisioned
+// token metadata info, for the tokens it describes
+parser_error_t _check_token_info(const parser_tx_t *tx_t, const uint8_t *info, uint16_t infoLen);
+
+// Writes into path the path of the Bitcoin key the rewards of a stack-stx
+// call would be paid to if they go to this device, pathLen is set to its
+// number of levels, 0 if there is none
+parser_error_t _reward_key_path(const parser_tx_t *tx_t,
+                                const uint32_t *signerPath,
+                                uint8_t signerPathLen,
+                                uint32_t *path,
+                                uint8_t *pathLen,
+                                uint8_t maxPathLen);
+
+// Checks the reward address of a stack-stx call against the hash160 of the
+// key at _reward_key_path, keyHash is null if there was no such path
+parser_error_t _check_reward_address(const parser_tx_t *tx_t, const uint8_t *keyHash);
+
+uint16_t _presig_hash_data(const parser_tx_t *tx_t, uint8_t *buf, uint16_t bufLen);
+
+// Returns the error a transaction fails to parse with, or parser_ok,
+// along with the section and offset it was found at
+parser_error_t _parse_diagnostic(const uint8_t *data,
+                                 uint16_t dataLen,
+                                 uint8_t *section,
+                                 uint32_t *offset);
 
-/******* Address c32-chack encoding **************************************************************/
+parser_error_t _initial_sighash(const parser_tx_t *tx_t, uint8_t *out, uint16_t outLen);
+
+// Chains hash, the pre_sig_hash of the first signer of a multisig origin,
+// through the previous signatures up to the one this device signs
+parser_error_t _multisig_presig_hash(const parser_tx_t *tx_t, uint8_t *hash, uint16_t hashLen);
+
+uint16_t _last_block_ptr(const parser_tx_t *tx_t, const uint8_t **block_ptr);
+
+uint8_t _is_multisig(const parser_tx_t *tx_t);
+
+uint8_t _is_order_independent(const parser_tx_t *tx_t);
+
+uint8_t _is_sponsored(const parser_tx_t *tx_t);
+
+// Writes the null terminated sponsor address. Returns its length, 0 if the
+// transaction is not sponsored or out is too short
+uint16_t _sponsor_address(const parser_tx_t *tx_t, uint8_t *out, uint16_t outLen);
+
+// Writes the sponsor fee as big endian bytes. Returns the bytes written,
+// 0 if the transaction is not sponsored
+uint8_t _sponsor_fee_bytes(const parser_tx_t *tx_t, uint8_t *fee, uint16_t fee_len);
+
+parser_error_t _payload_kind(const parser_tx_t *tx_t, uint8_t *kind);
+
+parser_error_t _recipient_hash160(const parser_tx_t *tx_t, uint8_t *out, uint16_t outLen);
+
+// Writes the null terminated address of the token transfer recipient.
+// Returns its length, 0 if there is none or out is too short
+uint16_t _recipient_address(const parser_tx_t *tx_t, uint8_t *out, uint16_t outLen);
+
+// Gets the token transfer amount in uSTX split in two 32-bit halves
+parser_error_t _amount(const parser_tx_t *tx_t, uint32_t *amount_hi, uint32_t *amount_lo);
+
+parser_error_t _num_post_conditions(const parser_tx_t *tx_t, uint8_t *num);
+
+parser_error_t _multisig_threshold(const parser_tx_t *tx_t, uint16_t *threshold);
+
+uint8_t _is_high_fee(const parser_tx_t *tx_t);
+
+transaction_type_t _transaction_type(const parser_tx_t *tx_t);
+
+uint16_t _previous_signer_data(const parser_tx_t *tx_t, const uint8_t **data);
+
+parser_error_t _structured_msg_hash(const parser_tx_t *tx_t, uint8_t *out, uint16_t out_len);
+
+// Length of the SIP018 prefix and domain of a structured message, 0 if it
+// is not one
+uint16_t _structured_msg_header_len(const parser_tx_t *tx_t);
+
+uint32_t _stack_watermark(uint8_t entry);
+
+void _stack_watermark_reset(void);
 
 uint16_t rs_c32_address(const uint8_t *input, uint8_t version, uint8_t *output, uint16_t outLen);
 
-/****************************** others ***********************************************************/
+// Writes the pageIdx page of the c32 address of version and hash160 as
+// addresses being verified are shown, see C32Address::page_full
+parser_error_t rs_c32_page_address(const uint8_t *hash160,
+                                   uint8_t version,
+                                   char *outValue,
+                                   uint16_t outValueLen,
+                                   uint8_t pageIdx,
+                                   uint8_t *pageCount);
+
+// Writes the account discovery entry of the account at index, see
+// stacks_ledger_parser::account. Returns its length, 0 on error
+uint16_t rs_account_entry(uint32_t index,
+                          const uint8_t *pubkey,
+                          uint16_t pubkeyLen,
+                          const uint8_t *hash160,
+                          uint8_t *out,
+                          uint16_t outLen);
+
+// Writes the slot of pubkey in the multisig account of tmpl followed by the
+// key, see stacks_ledger_parser::multisig. Returns its length, 0 on error
+uint16_t rs_multisig_slot(const uint8_t *tmpl,
+                          uint16_t tmplLen,
+                          const uint8_t *pubkey,
+                          uint16_t pubkeyLen,
+                          uint8_t *out,
+                          uint16_t outLen);
+
+// Returns 1 when the multisig accounts of hash mode take the hash of the
+// witness program of their script instead of the script itself
+uint8_t rs_hash_mode_is_segwit(uint8_t mode);
+
+// Writes the Bitcoin address of the key whose hash160 is given, derived at
+// path, see stacks_ledger_parser::btc. Returns its length, 0 on error
+uint16_t rs_btc_address(const uint32_t *path,
+                        uint8_t pathLen,
+                        const uint8_t *hash160,
+                        uint8_t *out,
+                        uint16_t outLen);
+
+// Reads the path sent by the host into path if it is allowed for kind,
+// see stacks_ledger_parser::path, pathLen is set to its number of levels
+parser_error_t rs_read_path(const uint8_t *data,
+                            uint16_t dataLen,
+                            uint8_t kind,
+                            uint32_t *path,
+                            uint8_t *pathLen,
+                            uint8_t maxPathLen);
+
+// Writes the number of settings of blob followed by the id and value of
+// each, see stacks_ledger_parser::settings. Returns its length, 0 on error
+uint16_t rs_settings_entries(const uint8_t *blob, uint16_t blobLen, uint8_t *out, uint16_t outLen);
+
+// Reads the setting id, a setting_id_e, of blob into value
+parser_error_t rs_settings_get(const uint8_t *blob, uint16_t blobLen, uint8_t id, uint64_t *value);
 
-parser_error_t _parser_init(parser_context_t *ctx, const uint8_t *buffer, uint16_t bufferSize, uint16_t *alloc_size);
+// Writes the settings of blob with the setting id set to value as a blob of
+// the current version. Returns its length, 0 on error
+uint16_t rs_settings_set(const uint8_t *blob,
+                         uint16_t blobLen,
+                         uint8_t id,
+                         uint64_t value,
+                         uint8_t *out,
+                         uint16_t outLen);
 
-parser_error_t _read(const parser_context_t *c, parser_tx_t *v);
+// Drops the initial sighash computed so far
+void rs_sighash_stream_reset(void);
 
-parser_error_t _validate(const parser_context_t *ctx, const parser_tx_t *v);
+// Hashes the bytes of the transaction buffer data received since the
+// last call
+void rs_sighash_stream_update(const uint8_t *data, uint32_t dataLen);
 
-parser_error_t _getNumItems(const parser_context_t *ctx, const parser_tx_t *v, uint8_t *num_items);
+// Hashes and drops chunk, part of a contract code body that does not fit
+// the transaction buffer data
+parser_error_t rs_sighash_stream_skip(const uint8_t *data,
+                                      uint32_t dataLen,
+                                      const uint8_t *chunk,
+                                      uint32_t chunkLen);
 
-parser_error_t _getItem(const parser_context_t *ctx,
-                              int8_t displayIdx,
-                              char *outKey, uint16_t outKeyLen,
-                              char *outValue, uint16_t outValueLen,
-                              uint8_t pageIdx, uint8_t *pageCount,
-                              const parser_tx_t *v);
+// Returns how many bytes of the transaction were hashed and dropped
+uint32_t rs_sighash_stream_skipped(void);
 
-/****************************** Getters for the required information for signing*******************/
-transaction_type_t _transaction_type(const parser_tx_t *v);
+// Writes the RS_CODE_SCAN_LEN bytes of the scan of the dropped code body
+// into out, returns 0 if nothing was dropped
+uint16_t rs_sighash_stream_code_scan(uint8_t *out, uint16_t outLen);
 
+// Completes the initial sighash with the last transaction block, found by
+// the parser at offset lastBlock of the transaction buffer data
+parser_error_t rs_sighash_stream_finish(const uint8_t *data,
+                                        uint32_t dataLen,
+                                        uint32_t lastBlock,
+                                        uint16_t lastBlockLen,
+                                        uint8_t *hash,
+                                        uint16_t hashLen);
 
-parser_error_t _auth_flag(const parser_tx_t *v, uint8_t *auth_flag);
+// Checks that abi is a well formed function signature, before it is
+// provisioned
+parser_error_t rs_check_abi(const uint8_t *abi, uint16_t abiLen);
 
-parser_error_t _structured_msg_hash(const parser_tx_t *v,  uint8_t *out, uint16_t out_len);
+// Checks that info is well formed token metadata, before it is provisioned
+parser_error_t rs_check_token_info(const uint8_t *info, uint16_t infoLen);
 
-uint8_t _is_multisig(const parser_tx_t *v);
+// Checks a path already read against the policies of kind
+parser_error_t rs_check_path(const uint32_t *path, uint8_t pathLen, uint8_t kind);
 
-uint8_t _fee_bytes(const parser_tx_t *v, uint8_t *fee, uint16_t fee_len);
-uint8_t _nonce_bytes(const parser_tx_t *v, uint8_t *nonce, uint16_t nonce_len);
+// Whether the path is one of the account paths wallets derive,
+// see stacks_ledger_parser::path
+uint8_t rs_is_standard_path(const uint32_t *path, uint8_t pathLen);
 
-parser_error_t _check_pubkey_hash(const parser_tx_t *v, const uint8_t *pubKey, uint16_t pubKeyLen);
+// Signs the 32 bytes digest with secret, writing the low-S signature as
+// (r, s, v) and DER encoded into der. It is verified against the key of
+// secret before it is returned, returns the DER length or 0 on error
+uint16_t rs_ecdsa_sign(const uint8_t *secret,
+                       const uint8_t *digest,
+                       uint8_t *r,
+                       uint8_t *s,
+                       uint8_t *v,
+                       uint8_t *der,
+                       uint16_t derLen);
 
-uint16_t _presig_hash_data(const parser_tx_t *v, uint8_t *buf, uint16_t bufLen);
+// Returns 1 if the signature (r, s, v) of the 32 bytes digest recovers the
+// uncompressed publicKey and s is low, 0 otherwise
+uint8_t rs_ecdsa_verify(const uint8_t *publicKey,
+                        const uint8_t *digest,
+                        const uint8_t *r,
+                        const uint8_t *s,
+                        uint8_t v);
 
-uint16_t _last_block_ptr(const parser_tx_t *v, uint8_t **block_ptr);
+// Writes the auth field of the signature (r, s, v) by the compressed key of
+// this device, the bytes a coordinator puts in the auth fields of a
+// multisig transaction. Returns its length, 0 if out is too short
+uint16_t rs_signature_auth_field(const uint8_t *rsv, uint8_t *out, uint16_t outLen);
 
-uint16_t _previous_signer_data(const parser_tx_t *v, uint8_t **data);
+// Returns 1 if the 64 bytes at signature are a BIP-340 signature encoding,
+// checked on the signatures the device returns, 0 otherwise
+uint8_t rs_is_schnorr_signature(const uint8_t *signature, uint16_t signatureLen);
 
+// Clears key material, sighashes and signatures once they are no longer
+// needed, unlike MEMZERO these writes can not be optimized away
+void rs_zeroize(void *buffer, uint16_t len);
@@ -0,0 +1,87 @@
+//! Host benchmarks of the parser hot paths over the json vectors in tests/:
+//! reading a transaction, rendering its review items, the c32 encoding of
+//! addresses and the initial sighash. Run them with `cargo bench` in this
+//! directory, criterion compares each run with the previous one so a
+//! refactor can show what it changes.
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+
+use stacks_ledger_parser::hash::{DefaultSha512_256, SHA512_256_LEN};
+use stacks_ledger_parser::parser::{c32::c32_address, ParsedObj, HASH160_LEN};
+
+#[path = "../src/parser/vectors.rs"]
+mod vectors;
+
+use vectors::transaction_vectors;
+
+// the key and value buffers of the app UI
+const KEY_LEN: usize = 40;
+const VALUE_LEN: usize = 40;
+
+fn read(c: &mut Criterion) {
+    let mut group = c.benchmark_group("read");
+    for (name, bytes) in transaction_vectors() {
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                let mut obj = ParsedObj::from_bytes(black_box(&bytes)).unwrap();
+                obj.read(&bytes).unwrap();
+                obj
+            })
+        });
+    }
+    group.finish();
+}
+
+// every page of every item, as the app requests them during a review
+fn items(c: &mut Criterion) {
+    let mut group = c.benchmark_group("items");
+    for (name, bytes) in transaction_vectors() {
+        let mut obj = ParsedObj::from_bytes(&bytes).unwrap();
+        obj.read(&bytes).unwrap();
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                let mut key = [0u8; KEY_LEN];
+                let mut value = [0u8; VALUE_LEN];
+                for idx in 0..obj.num_items().unwrap() {
+                    let mut page_idx = 0;
+                    let mut page_count = 1;
+                    while page_idx < page_count {
+                        page_count = obj.get_item(idx, &mut key, &mut value, page_idx).unwrap();
+                        page_idx += 1;
+                    }
+                }
+                value
+            })
+        });
+    }
+    group.finish();
+}
+
+fn c32(c: &mut Criterion) {
+    let hash160 = [0x5au8; HASH160_LEN];
+    c.bench_function("c32_address", |b| {
+        b.iter(|| c32_address(black_box(22), black_box(&hash160)).unwrap())
+    });
+}
+
+fn sighash(c: &mut Criterion) {
+    let mut group = c.benchmark_group("sighash");
+    for (name, bytes) in transaction_vectors() {
+        let mut obj = ParsedObj::from_bytes(&bytes).unwrap();
+        obj.read(&bytes).unwrap();
+        let tx = obj.transaction().unwrap();
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                let mut out = [0u8; SHA512_256_LEN];
+                tx.initial_sighash::<DefaultSha512_256>(&mut out).unwrap();
+                out
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, read, items, c32, sighash);
+criterion_main!(benches);
@@ -0,0 +1,6 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    stacks_ledger_parser::parser::fuzzing::post_conditions(data);
+});
@@ -0,0 +1,60 @@
+#!/usr/bin/env python3
+"""Builds the seed corpus of the fuzz targets from the unit test vectors.
+
+Run from app/rust/fuzz, the seeds are written to corpus/<target>/.
+"""
+
+import json
+import re
+from pathlib import Path
+
+RUST_DIR = Path(__file__).resolve().parent.parent
+CORPUS_DIR = Path(__file__).resolve().parent / "corpus"
+
+# version, chain id and auth type come before the origin spending condition
+SPENDING_CONDITION_OFFSET = 1 + 4 + 1
+# hash mode, signer, nonce, fee, key encoding and signature
+SINGLESIG_LEN = 1 + 20 + 8 + 8 + 1 + 65
+SINGLESIG_HASH_MODES = (0x00, 0x02)
+STANDARD_AUTH = 0x04
+TRANSACTION_MODES_LEN = 2
+
+
+def write_seed(target, name, data):
+    path = CORPUS_DIR / target
+    path.mkdir(parents=True, exist_ok=True)
+    (path / name).write_bytes(data)
+
+
+def post_conditions_offset(raw):
+    # only singlesig spending conditions have a fixed length
+    offset = SPENDING_CONDITION_OFFSET
+    conditions = 1 if raw[offset - 1] == STANDARD_AUTH else 2
+    for _ in range(conditions):
+        if raw[offset] not in SINGLESIG_HASH_MODES:
+            return None
+        offset += SINGLESIG_LEN
+    return offset + TRANSACTION_MODES_LEN
+
+
+def main():
+    for vector in sorted((RUST_DIR / "tests").glob("*.json")):
+        raw = bytes.fromhex(json.loads(vector.read_text())["raw"])
+        name = vector.stem
+
+        write_seed("transaction", name, raw)
+        write_seed("spending_condition", name, raw[SPENDING_CONDITION_OFFSET:])
+        write_seed("c32_address", name, raw[SPENDING_CONDITION_OFFSET:SPENDING_CONDITION_OFFSET + 21])
+
+        offset = post_conditions_offset(raw)
+        if offset is not None:
+            write_seed("post_conditions", name, raw[offset:])
+
+    # the encoded values in the Clarity value unit tests
+    source = (RUST_DIR / "src" / "parser" / "value.rs").read_text()
+    for idx, encoded in enumerate(re.findall(r'let \w+ = "([0-9a-f]+)";', source)):
+        write_seed("clarity_value", "value_{}".format(idx), bytes.fromhex(encoded))
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,155 @@
+//! Packed entries of the account discovery APDU.
+//!
+//! Wallets find the accounts in use by deriving keys at increasing account
+//! indexes until they reach unused ones. An entry carries all a wallet looks
+//! an account up by, so a discovery loop needs one APDU per account instead
+//! of one per key and network:
+//!
+//! | index (4) | pubkey (33) | hash160 (20) | len (1) | mainnet address | len (1) | testnet address |
+//!
+//! The index is big endian, the addresses are c32check encoded.
+
+use core::convert::TryInto;
+
+use crate::parser::{
+    c32::{
+        c32_address, C32_ADDRESS_VERSION_MAINNET_SINGLESIG, C32_ADDRESS_VERSION_TESTNET_SINGLESIG,
+    },
+    reader::{be_u32, le_u8, take},
+    ParserError, C32_ENCODED_ADDRS_LENGTH, HASH160_LEN,
+};
+
+pub const COMPRESSED_PUBKEY_LEN: usize = 33;
+
+/// Longest entry, addresses are shorter when the hash160 starts with zeros
+pub const ACCOUNT_ENTRY_MAX_LEN: usize =
+    4 + COMPRESSED_PUBKEY_LEN + HASH160_LEN + 2 * (1 + C32_ENCODED_ADDRS_LENGTH);
+
+/// An account as read back from an entry by the host
+#[derive(Clone, Copy, PartialEq)]
+#[cfg_attr(test, derive(Debug))]
+pub struct AccountEntry<'a> {
+    pub index: u32,
+    pub pubkey: &'a [u8; COMPRESSED_PUBKEY_LEN],
+    pub hash160: &'a [u8; HASH160_LEN],
+    pub mainnet_address: &'a [u8],
+    pub testnet_address: &'a [u8],
+}
+
+impl<'a> AccountEntry<'a> {
+    /// Reads the next entry of a response, returns it along with the rest
+    pub fn from_bytes(data: &'a [u8]) -> Result<(&'a [u8], Self), ParserError> {
+        let (rem, index) = be_u32(data)?;
+        let (rem, pubkey) = take(COMPRESSED_PUBKEY_LEN)(rem)?;
+        let (rem, hash160) = take(HASH160_LEN)(rem)?;
+        let (rem, len) = le_u8(rem)?;
+        let (rem, mainnet_address) = take(len)(rem)?;
+        let (rem, len) = le_u8(rem)?;
+        let (rem, testnet_address) = take(len)(rem)?;
+
+        let entry = Self {
+            index,
+            pubkey: pubkey
+                .try_into()
+                .map_err(|_| ParserError::parser_unexpected_buffer_end)?,
+            hash160: hash160
+                .try_into()
+                .map_err(|_| ParserError::parser_unexpected_buffer_end)?,
+            mainnet_address,
+            testnet_address,
+        };
+        Ok((rem, entry))
+    }
+}
+
+/// Writes the entry of the account at index, whose key is pubkey and
+/// hash160 its hash, returns the bytes written
+pub fn write_account_entry(
+    index: u32,
+    pubkey: &[u8],
+    hash160: &[u8],
+    out: &mut [u8],
+) -> Result<usize, ParserError> {
+    if pubkey.len() != COMPRESSED_PUBKEY_LEN {
+        return Err(ParserError::parser_invalid_pubkey_encoding);
+    }
+    let mainnet = c32_address(C32_ADDRESS_VERSION_MAINNET_SINGLESIG, hash160)?;
+    let testnet = c32_address(C32_ADDRESS_VERSION_TESTNET_SINGLESIG, hash160)?;
+
+    let index = index.to_be_bytes();
+    let mainnet_len = [mainnet.len() as u8];
+    let testnet_len = [testnet.len() as u8];
+    let fields: [&[u8]; 7] = [
+        &index,
+        pubkey,
+        hash160,
+        &mainnet_len,
+        &mainnet,
+        &testnet_len,
+        &testnet,
+    ];
+
+    let len = fields.iter().map(|field| field.len()).sum();
+    let mut bytes = out
+        .get_mut(..len)
+        .ok_or(ParserError::parser_unexpected_buffer_end)?
+        .iter_mut();
+    for (dst, src) in (&mut bytes).zip(fields.iter().flat_map(|field| field.iter())) {
+        *dst = *src;
+    }
+    Ok(len)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // the key of m/44'/5757'/5'/0/0 in the zemu tests
+    const PUBKEY: &str = "0252dab95065cd31ae6f8ece65fffd2e904b203268a5923fa85e5db793698d753a";
+    const HASH160: &str = "d386442122c88878ae04c5726762477f4ef09ffe";
+
+    #[test]
+    fn test_account_entry() {
+        let pubkey = hex::decode(PUBKEY).unwrap();
+        let hash160 = hex::decode(HASH160).unwrap();
+
+        let mut out = [0u8; 2 * ACCOUNT_ENTRY_MAX_LEN];
+        let len = write_account_entry(5, &pubkey, &hash160, &mut out).unwrap();
+        let second = write_account_entry(6, &pubkey, &hash160, &mut out[len..]).unwrap();
+        assert_eq!(len, second);
+        assert!(len <= ACCOUNT_ENTRY_MAX_LEN);
+
+        let (rem, entry) = AccountEntry::from_bytes(&out[..2 * len]).unwrap();
+        assert_eq!(entry.index, 5);
+        assert_eq!(&entry.pubkey[..], pubkey.as_slice());
+        assert_eq!(&entry.hash160[..], hash160.as_slice());
+        assert_eq!(
+            entry.mainnet_address,
+            b"SP39RCH114B48GY5E0K2Q4SV28XZMXW4ZZRQXY3V7"
+        );
+        assert_eq!(entry.testnet_address[..2], b"ST"[..]);
+
+        let (rem, entry) = AccountEntry::from_bytes(rem).unwrap();
+        assert_eq!(entry.index, 6);
+        assert!(rem.is_empty());
+    }
+
+    #[test]
+    fn test_account_entry_errors() {
+        let pubkey = hex::decode(PUBKEY).unwrap();
+        let hash160 = hex::decode(HASH160).unwrap();
+
+        let mut out = [0u8; ACCOUNT_ENTRY_MAX_LEN];
+        assert_eq!(
+            write_account_entry(0, &pubkey[1..], &hash160, &mut out),
+            Err(ParserError::parser_invalid_pubkey_encoding)
+        );
+        assert_eq!(
+            write_account_entry(0, &pubkey, &hash160, &mut out[..100]),
+            Err(ParserError::parser_unexpected_buffer_end)
+        );
+
+        let len = write_account_entry(0, &pubkey, &hash160, &mut out).unwrap();
+        assert!(AccountEntry::from_bytes(&out[..len - 1]).is_err());
+    }
+}
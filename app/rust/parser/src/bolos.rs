@@ -0,0 +1,41 @@
+//! Rust interfaces to Ledger SDK APIs.
+pub const SHA256_LEN: usize = 32;
+
+extern "C" {
+    fn _zemu_log_stack(buffer: *const u8);
+    fn _zemu_log(buffer: *const u8);
+}
+
+#[cfg(all(feature = "device", not(test)))]
+pub fn c_zemu_log_stack<S: AsRef<[u8]>>(s: S) {
+    unsafe { _zemu_log_stack(s.as_ref().as_ptr()) }
+}
+#[cfg(any(test, not(feature = "device")))]
+pub fn c_zemu_log_stack<S: AsRef<[u8]>>(_s: S) {}
+
+#[cfg(all(feature = "device", not(test)))]
+pub fn c_zemu_log<S: AsRef<[u8]>>(s: S) {
+    unsafe { _zemu_log(s.as_ref().as_ptr()) }
+}
+#[cfg(any(test, not(feature = "device")))]
+pub fn c_zemu_log<S: AsRef<[u8]>>(_s: S) {}
+
+// extern function that uses the device sdk to compute a hash
+extern "C" {
+    pub fn hash_sha256(in_data: *const u8, in_len: u16, out: *mut u8);
+}
+
+#[derive(Debug)]
+pub struct OutputTooSmall;
+
+pub fn sha256(data: &[u8], output: &mut [u8]) -> Result<(), OutputTooSmall> {
+    use crate::hash::{DefaultSha256, Sha256};
+    use core::convert::TryInto;
+
+    let out = output
+        .get_mut(..SHA256_LEN)
+        .and_then(|out| out.try_into().ok())
+        .ok_or(OutputTooSmall)?;
+    DefaultSha256::digest(data, out);
+    Ok(())
+}
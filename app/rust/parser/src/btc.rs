@@ -0,0 +1,431 @@
+//! Bitcoin addresses PoX rewards are paid to.
+//!
+//! Stackers give the Bitcoin address of their rewards as a
+//! `{hashbytes: (buff 32), version: (buff 1)}` tuple. The review shows it
+//! encoded as Bitcoin wallets show it, and when it pays to a key hash the
+//! app derives the key of the same account on the Bitcoin path of that
+//! address type, so the review also tells whether the rewards go to a key
+//! of this device.
+
+use core::convert::TryFrom;
+
+use arrayvec::ArrayVec;
+
+use crate::parser::{
+    c32::double_sha256_checksum,
+    reader::{be_u32, take},
+    ClarityName, ParserError, Value, ValueId, HASH160_LEN,
+};
+use crate::path::{COIN_BTC, COIN_TESTNET, HARDENED, PURPOSE_BIP44, PURPOSE_BIP84};
+
+/// Levels of the path of a reward key, purpose'/coin'/account'/0/0
+pub const REWARD_PATH_LEN: usize = 5;
+
+/// Room for the longest encoded address, a bech32 one of a 32-byte program
+/// is 62 characters
+pub const BTC_ADDRESS_MAX_LEN: usize = 64;
+
+const BASE58_ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+// version byte, hash and checksum
+const BASE58_DATA_MAX_LEN: usize = 1 + HASH160_LEN + 4;
+// room for the 35 digits of the largest data
+const BASE58_MAX_LEN: usize = 40;
+
+const BECH32_CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+const BECH32_CONST: u32 = 1;
+const BECH32M_CONST: u32 = 0x2bc8_30a3;
+const BECH32_CHECKSUM_LEN: usize = 6;
+// room for the witness version and the program in 5-bit groups
+const BECH32_DATA_MAX_LEN: usize = 64;
+
+pub type BtcAddress = ArrayVec<[u8; BTC_ADDRESS_MAX_LEN]>;
+
+/// How the rewards are locked, the version byte of the PoX address
+#[repr(u8)]
+#[derive(Clone, Copy, PartialEq)]
+#[cfg_attr(test, derive(Debug))]
+pub enum PoxAddressVersion {
+    P2pkh = 0,
+    P2sh = 1,
+    P2shP2wpkh = 2,
+    P2shP2wsh = 3,
+    P2wpkh = 4,
+    P2wsh = 5,
+    P2tr = 6,
+}
+
+impl TryFrom<u8> for PoxAddressVersion {
+    type Error = ParserError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::P2pkh),
+            1 => Ok(Self::P2sh),
+            2 => Ok(Self::P2shP2wpkh),
+            3 => Ok(Self::P2shP2wsh),
+            4 => Ok(Self::P2wpkh),
+            5 => Ok(Self::P2wsh),
+            6 => Ok(Self::P2tr),
+            _ => Err(ParserError::parser_invalid_address_version),
+        }
+    }
+}
+
+impl PoxAddressVersion {
+    fn hash_len(self) -> usize {
+        match self {
+            Self::P2wsh | Self::P2tr => 32,
+            _ => HASH160_LEN,
+        }
+    }
+
+    /// The purpose of the path wallets derive keys of this address type
+    /// at, for the types that pay to the hash of a single key
+    pub fn key_purpose(self) -> Option<u32> {
+        match self {
+            Self::P2pkh => Some(PURPOSE_BIP44),
+            Self::P2wpkh => Some(PURPOSE_BIP84),
+            _ => None,
+        }
+    }
+}
+
+/// A PoX reward address as given to the pox contract
+#[derive(Clone, Copy, PartialEq)]
+#[cfg_attr(test, derive(Debug))]
+pub struct PoxAddress<'a> {
+    pub version: PoxAddressVersion,
+    pub hashbytes: &'a [u8],
+}
+
+impl<'a> PoxAddress<'a> {
+    /// Reads the address from its clarity tuple
+    pub fn from_value(value: &Value<'a>) -> Result<Self, ParserError> {
+        if value.value_id()? != ValueId::Tuple {
+            return Err(ParserError::parser_unexpected_type);
+        }
+        let (mut rem, num_fields) = be_u32(value.payload())?;
+
+        let mut version = None;
+        let mut hashbytes = None;
+        for _ in 0..num_fields {
+            let (next, name) = ClarityName::from_bytes(rem)?;
+            let (next, field) = Value::from_bytes::<1>(next)?;
+            let field = Some(buffer(&field)?);
+            match name.name() {
+                b"version" => version = field,
+                b"hashbytes" => hashbytes = field,
+                _ => return Err(ParserError::parser_unexpected_value),
+            }
+            rem = next;
+        }
+
+        let version = match version {
+            Some([version]) => PoxAddressVersion::try_from(*version)?,
+            _ => return Err(ParserError::parser_invalid_address_version),
+        };
+        let hashbytes = hashbytes
+            .filter(|hash| hash.len() == version.hash_len())
+            .ok_or(ParserError::parser_invalid_address)?;
+        Ok(Self { version, hashbytes })
+    }
+
+    /// Whether the rewards are paid to the key whose hash160 is given
+    pub fn pays_to_key(&self, hash160: &[u8]) -> bool {
+        self.version.key_purpose().is_some() && self.hashbytes == hash160
+    }
+
+    /// The path of the key of account the address would pay to if it is
+    /// of this device, purpose'/coin'/account'/0/0
+    pub fn key_path(&self, account: u32, mainnet: bool) -> Option<[u32; REWARD_PATH_LEN]> {
+        let purpose = self.version.key_purpose()?;
+        let coin = if mainnet { COIN_BTC } else { COIN_TESTNET };
+        Some([purpose, coin, account | HARDENED, 0, 0])
+    }
+
+    pub fn encode(&self, mainnet: bool) -> Result<BtcAddress, ParserError> {
+        match self.version {
+            PoxAddressVersion::P2pkh => {
+                base58check(if mainnet { 0x00 } else { 0x6f }, self.hashbytes)
+            }
+            PoxAddressVersion::P2sh
+            | PoxAddressVersion::P2shP2wpkh
+            | PoxAddressVersion::P2shP2wsh => {
+                base58check(if mainnet { 0x05 } else { 0xc4 }, self.hashbytes)
+            }
+            PoxAddressVersion::P2wpkh | PoxAddressVersion::P2wsh => {
+                segwit_address(mainnet, 0, self.hashbytes)
+            }
+            PoxAddressVersion::P2tr => segwit_address(mainnet, 1, self.hashbytes),
+        }
+    }
+}
+
+// the content of a buffer value
+fn buffer<'a>(value: &Value<'a>) -> Result<&'a [u8], ParserError> {
+    if value.value_id()? != ValueId::Buffer {
+        return Err(ParserError::parser_unexpected_type);
+    }
+    let (rem, len) = be_u32(value.payload())?;
+    let (_, data) = take(len)(rem)?;
+    Ok(data)
+}
+
+/// The address of the key whose hash160 is given, derived at path, a
+/// P2PKH one for m/44' paths and a P2WPKH one for m/84' paths
+pub fn key_address(path: &[u32], hash160: &[u8]) -> Result<BtcAddress, ParserError> {
+    let (version, mainnet) = match path {
+        [PURPOSE_BIP44, COIN_BTC, ..] => (PoxAddressVersion::P2pkh, true),
+        [PURPOSE_BIP44, COIN_TESTNET, ..] => (PoxAddressVersion::P2pkh, false),
+        [PURPOSE_BIP84, COIN_BTC, ..] => (PoxAddressVersion::P2wpkh, true),
+        [PURPOSE_BIP84, COIN_TESTNET, ..] => (PoxAddressVersion::P2wpkh, false),
+        _ => return Err(ParserError::parser_invalid_path),
+    };
+    if hash160.len() != HASH160_LEN {
+        return Err(ParserError::parser_invalid_address);
+    }
+    let address = PoxAddress {
+        version,
+        hashbytes: hash160,
+    };
+    address.encode(mainnet)
+}
+
+#[inline(never)]
+fn base58check(version: u8, hash: &[u8]) -> Result<BtcAddress, ParserError> {
+    let mut data = ArrayVec::<[u8; BASE58_DATA_MAX_LEN]>::new();
+    data.try_push(version)
+        .map_err(|_| ParserError::parser_unexpected_buffer_end)?;
+    data.try_extend_from_slice(hash)
+        .map_err(|_| ParserError::parser_unexpected_buffer_end)?;
+    let checksum = double_sha256_checksum(&data);
+    data.try_extend_from_slice(&checksum)
+        .map_err(|_| ParserError::parser_unexpected_buffer_end)?;
+
+    // base 58 digits of the data, least significant first
+    let mut digits = ArrayVec::<[u8; BASE58_MAX_LEN]>::new();
+    for byte in data.iter() {
+        let mut carry = u32::from(*byte);
+        for digit in digits.iter_mut() {
+            carry += u32::from(*digit) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits
+                .try_push((carry % 58) as u8)
+                .map_err(|_| ParserError::parser_unexpected_buffer_end)?;
+            carry /= 58;
+        }
+    }
+
+    // each leading zero byte is encoded as a '1'
+    let zeros = data.iter().take_while(|byte| **byte == 0).count();
+    let mut out = BtcAddress::new();
+    for _ in 0..zeros {
+        out.try_push(b'1')
+            .map_err(|_| ParserError::parser_unexpected_buffer_end)?;
+    }
+    for digit in digits.iter().rev() {
+        let c = BASE58_ALPHABET
+            .get(*digit as usize)
+            .ok_or(ParserError::parser_unexpected_error)?;
+        out.try_push(*c)
+            .map_err(|_| ParserError::parser_unexpected_buffer_end)?;
+    }
+    Ok(out)
+}
+
+fn bech32_polymod(values: impl Iterator<Item = u8>) -> u32 {
+    const GENERATOR: [u32; 5] = [
+        0x3b6a_57b2,
+        0x2650_8e6d,
+        0x1ea1_19fa,
+        0x3d42_33dd,
+        0x2a14_62b3,
+    ];
+    values.fold(1, |chk, value| {
+        let top = chk >> 25;
+        let chk = ((chk & 0x1ff_ffff) << 5) ^ u32::from(value);
+        GENERATOR
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| (top >> i) & 1 == 1)
+            .fold(chk, |chk, (_, g)| chk ^ g)
+    })
+}
+
+/// bech32 for witness version 0, bech32m for the later ones
+#[inline(never)]
+fn segwit_address(
+    mainnet: bool,
+    witness_version: u8,
+    program: &[u8],
+) -> Result<BtcAddress, ParserError> {
+    let hrp: &[u8] = if mainnet { b"bc" } else { b"tb" };
+
+    let mut data = ArrayVec::<[u8; BECH32_DATA_MAX_LEN]>::new();
+    data.try_push(witness_version)
+        .map_err(|_| ParserError::parser_unexpected_buffer_end)?;
+    // the program regrouped from 8 into 5 bits, the last group zero padded
+    let mut acc = 0u32;
+    let mut bits = 0u32;
+    for byte in program {
+        acc = ((acc << 8) | u32::from(*byte)) & 0xfff;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            data.try_push(((acc >> bits) & 0x1f) as u8)
+                .map_err(|_| ParserError::parser_unexpected_buffer_end)?;
+        }
+    }
+    if bits > 0 {
+        data.try_push(((acc << (5 - bits)) & 0x1f) as u8)
+            .map_err(|_| ParserError::parser_unexpected_buffer_end)?;
+    }
+
+    let constant = if witness_version == 0 {
+        BECH32_CONST
+    } else {
+        BECH32M_CONST
+    };
+    let expanded_hrp = hrp
+        .iter()
+        .map(|c| c >> 5)
+        .chain(core::iter::once(0))
+        .chain(hrp.iter().map(|c| c & 0x1f));
+    let checksum = bech32_polymod(
+        expanded_hrp
+            .chain(data.iter().copied())
+            .chain([0u8; BECH32_CHECKSUM_LEN].iter().copied()),
+    ) ^ constant;
+    let checksum = (0..BECH32_CHECKSUM_LEN as u32)
+        .rev()
+        .map(|i| ((checksum >> (5 * i)) & 0x1f) as u8);
+
+    let mut out = BtcAddress::new();
+    out.try_extend_from_slice(hrp)
+        .map_err(|_| ParserError::parser_unexpected_buffer_end)?;
+    out.try_push(b'1')
+        .map_err(|_| ParserError::parser_unexpected_buffer_end)?;
+    for group in data.iter().copied().chain(checksum) {
+        let c = BECH32_CHARSET
+            .get(group as usize)
+            .ok_or(ParserError::parser_unexpected_error)?;
+        out.try_push(*c)
+            .map_err(|_| ParserError::parser_unexpected_buffer_end)?;
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // the hash160 of the generator point, compressed
+    const KEY_HASH: &str = "751e76e8199196d454941c45d1b3a323f1433bd6";
+
+    fn pox_tuple(version: u8, hashbytes: &[u8]) -> std::vec::Vec<u8> {
+        let mut bytes = std::vec![0x0c, 0, 0, 0, 2];
+        bytes.push(9);
+        bytes.extend_from_slice(b"hashbytes");
+        bytes.push(0x02);
+        bytes.extend_from_slice(&(hashbytes.len() as u32).to_be_bytes());
+        bytes.extend_from_slice(hashbytes);
+        bytes.push(7);
+        bytes.extend_from_slice(b"version");
+        bytes.extend_from_slice(&[0x02, 0, 0, 0, 1, version]);
+        bytes
+    }
+
+    fn encode(version: u8, hash: &str, mainnet: bool) -> std::string::String {
+        let hash = hex::decode(hash).unwrap();
+        let bytes = pox_tuple(version, &hash);
+        let (_, value) = Value::from_bytes::<2>(&bytes).unwrap();
+        let address = PoxAddress::from_value(&value).unwrap();
+        std::string::String::from_utf8(address.encode(mainnet).unwrap().to_vec()).unwrap()
+    }
+
+    #[test]
+    fn test_base58_addresses() {
+        assert_eq!(
+            encode(0, KEY_HASH, true),
+            "1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH"
+        );
+        assert_eq!(
+            encode(0, KEY_HASH, false),
+            "mrCDrCybB6J1vRfbwM5hemdJz73FwDBC8r"
+        );
+        assert_eq!(
+            encode(1, KEY_HASH, true),
+            "3CNHUhP3uyB9EUtRLsmvFUmvGdjGdkTxJw"
+        );
+        assert_eq!(
+            encode(2, KEY_HASH, false),
+            "2N3vVYSK5XRgVSGWy21PnsRmBUywSQNdCsf"
+        );
+        // leading zero bytes
+        assert_eq!(
+            encode(0, "0000000000000000000000000000000000000000", true),
+            "1111111111111111111114oLvT2"
+        );
+    }
+
+    #[test]
+    fn test_segwit_addresses() {
+        // BIP-173 and BIP-350 vectors
+        assert_eq!(
+            encode(4, KEY_HASH, true),
+            "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"
+        );
+        assert_eq!(
+            encode(
+                5,
+                "1863143c14c5166804bd19203356da136c985678cd4d27a1b8c6329604903262",
+                false
+            ),
+            "tb1qrp33g0q5c5txsp9arysrx4k6zdkfs4nce4xj0gdcccefvpysxf3q0sl5k7"
+        );
+        assert_eq!(
+            encode(
+                6,
+                "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+                true
+            ),
+            "bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqzk5jj0"
+        );
+    }
+
+    #[test]
+    fn test_pox_address_errors() {
+        let hash = hex::decode(KEY_HASH).unwrap();
+        for (version, hash) in [(7u8, &hash[..]), (5, &hash[..]), (0, &hash[1..])].iter() {
+            let bytes = pox_tuple(*version, hash);
+            let (_, value) = Value::from_bytes::<2>(&bytes).unwrap();
+            assert!(PoxAddress::from_value(&value).is_err());
+        }
+    }
+
+    #[test]
+    fn test_reward_key() {
+        let hash = hex::decode(KEY_HASH).unwrap();
+        let bytes = pox_tuple(4, &hash);
+        let (_, value) = Value::from_bytes::<2>(&bytes).unwrap();
+        let address = PoxAddress::from_value(&value).unwrap();
+
+        let path = address.key_path(3, true).unwrap();
+        assert_eq!(path, [PURPOSE_BIP84, COIN_BTC, 3 | HARDENED, 0, 0]);
+        assert!(address.pays_to_key(&hash));
+        assert_eq!(
+            &key_address(&path, &hash).unwrap()[..],
+            b"bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"
+        );
+
+        // script hashes can not be checked against a key
+        let bytes = pox_tuple(1, &hash);
+        let (_, value) = Value::from_bytes::<2>(&bytes).unwrap();
+        let address = PoxAddress::from_value(&value).unwrap();
+        assert_eq!(address.key_path(3, true), None);
+        assert!(!address.pays_to_key(&hash));
+    }
+}
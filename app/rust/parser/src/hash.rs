@@ -0,0 +1,172 @@
+//! Hash functions used for the transaction sighash and the SIP-018 message hash.
+//!
+//! On device they are backed by the C implementations linked into the app,
+//! on host builds by pure Rust ones. The code that composes the hashes is
+//! generic over these traits, so it is the same on both sides.
+
+use crate::bolos::SHA256_LEN;
+
+pub const SHA512_256_LEN: usize = 32;
+
+/// Incremental SHA-512/256
+pub trait Sha512_256: Sized {
+    fn new() -> Self;
+
+    fn update(&mut self, data: &[u8]);
+
+    fn finalize(self, out: &mut [u8; SHA512_256_LEN]);
+
+    fn digest(data: &[u8], out: &mut [u8; SHA512_256_LEN]) {
+        let mut hasher = Self::new();
+        hasher.update(data);
+        hasher.finalize(out);
+    }
+}
+
+/// One-shot SHA-256
+pub trait Sha256 {
+    fn digest(data: &[u8], out: &mut [u8; SHA256_LEN]);
+}
+
+#[cfg(all(feature = "device", not(test)))]
+pub type DefaultSha512_256 = device::DeviceSha512_256;
+#[cfg(all(feature = "device", not(test)))]
+pub type DefaultSha256 = device::DeviceSha256;
+
+#[cfg(any(test, not(feature = "device")))]
+pub type DefaultSha512_256 = soft::SoftSha512_256;
+#[cfg(any(test, not(feature = "device")))]
+pub type DefaultSha256 = soft::SoftSha256;
+
+#[cfg(all(feature = "device", not(test)))]
+mod device {
+    use super::{Sha256, Sha512_256, SHA256_LEN, SHA512_256_LEN};
+    use crate::bolos::hash_sha256;
+    use crate::zeroize::{Zeroize, Zeroizing};
+
+    // the full SHA-512 digest written by SHA512_256_finish
+    const SHA512_DIGEST_LENGTH: usize = 64;
+
+    // mirrors sha512_256_ctx in deps/sha512/sha512.h
+    #[repr(C)]
+    #[derive(Clone)]
+    struct sha512_256_ctx {
+        total: [u64; 2],
+        state: [u64; 8],
+        buffer: [u8; 128],
+    }
+
+    impl Zeroize for sha512_256_ctx {
+        fn zeroize(&mut self) {
+            self.total.zeroize();
+            self.state.zeroize();
+            self.buffer.zeroize();
+        }
+    }
+
+    extern "C" {
+        fn SHA512_256_init(ctx: *mut sha512_256_ctx);
+        fn SHA512_256_starts(ctx: *mut sha512_256_ctx);
+        fn SHA512_256_update(ctx: *mut sha512_256_ctx, input: *const u8, ilen: usize);
+        fn SHA512_256_finish(ctx: *mut sha512_256_ctx, out: *mut u8);
+    }
+
+    #[derive(Clone)]
+    pub struct DeviceSha512_256(sha512_256_ctx);
+
+    impl Sha512_256 for DeviceSha512_256 {
+        fn new() -> Self {
+            let mut ctx = sha512_256_ctx {
+                total: [0; 2],
+                state: [0; 8],
+                buffer: [0; 128],
+            };
+            unsafe {
+                SHA512_256_init(&mut ctx);
+                SHA512_256_starts(&mut ctx);
+            }
+            Self(ctx)
+        }
+
+        fn update(&mut self, data: &[u8]) {
+            unsafe { SHA512_256_update(&mut self.0, data.as_ptr(), data.len()) }
+        }
+
+        fn finalize(mut self, out: &mut [u8; SHA512_256_LEN]) {
+            let mut digest = Zeroizing::new([0u8; SHA512_DIGEST_LENGTH]);
+            unsafe { SHA512_256_finish(&mut self.0, digest.as_mut_ptr()) }
+            out.iter_mut()
+                .zip(digest.iter())
+                .for_each(|(out, byte)| *out = *byte);
+        }
+    }
+
+    // the context holds the state of the hashed sighash data
+    impl Drop for DeviceSha512_256 {
+        fn drop(&mut self) {
+            self.0.zeroize()
+        }
+    }
+
+    pub struct DeviceSha256;
+
+    impl Sha256 for DeviceSha256 {
+        fn digest(data: &[u8], out: &mut [u8; SHA256_LEN]) {
+            unsafe { hash_sha256(data.as_ptr(), data.len() as _, out.as_mut_ptr()) }
+        }
+    }
+}
+
+#[cfg(not(target_os = "none"))]
+mod soft {
+    use super::{Sha256, Sha512_256, SHA256_LEN, SHA512_256_LEN};
+    use sha2::Digest;
+
+    #[derive(Clone)]
+    pub struct SoftSha512_256(sha2::Sha512Trunc256);
+
+    impl Sha512_256 for SoftSha512_256 {
+        fn new() -> Self {
+            Self(sha2::Sha512Trunc256::new())
+        }
+
+        fn update(&mut self, data: &[u8]) {
+            Digest::update(&mut self.0, data);
+        }
+
+        fn finalize(self, out: &mut [u8; SHA512_256_LEN]) {
+            out.copy_from_slice(self.0.finalize().as_slice());
+        }
+    }
+
+    pub struct SoftSha256;
+
+    impl Sha256 for SoftSha256 {
+        fn digest(data: &[u8], out: &mut [u8; SHA256_LEN]) {
+            out.copy_from_slice(sha2::Sha256::digest(data).as_slice());
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_sha512_256_incremental() {
+        // NIST test vector for "abc"
+        let expected =
+            hex::decode("53048e2681941ef99b2e29b76b4c7dabe4c2d0c634fc6d46e0e2f13107e7af23")
+                .unwrap();
+
+        let mut out = [0u8; SHA512_256_LEN];
+        DefaultSha512_256::digest(b"abc", &mut out);
+        assert_eq!(&out[..], &expected[..]);
+
+        let mut hasher = DefaultSha512_256::new();
+        hasher.update(b"a");
+        hasher.update(b"bc");
+        hasher.finalize(&mut out);
+        assert_eq!(&out[..], &expected[..]);
+    }
+}
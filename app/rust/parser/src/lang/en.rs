@@ -0,0 +1,128 @@
+use super::Labels;
+
+pub const LABELS: Labels = Labels {
+    origin: "Origin",
+    nonce: "Nonce",
+    fee: "Fee (uSTX)",
+    fee_stx: "Fee (STX)",
+    high_fee: "High fee (uSTX)",
+    high_fee_stx: "High fee (STX)",
+    sponsored: "Sponsored tx",
+    fee_paid_by: "Fee paid by ",
+    fee_paid_by_device: "Fee paid by this device, ",
+    sponsor_fee: "Sponsor fee (uSTX)",
+    sponsor_fee_stx: "Sponsor fee (STX)",
+    signatures: "Signatures present",
+    of: " of ",
+    duplicate_keys: "Repeated cosigner key, fewer signers needed",
+    key_path: "Key path",
+    nonstandard_path: "Non-standard, check it",
+    network: "Network",
+    mainnet_tx: "Mainnet tx, testnet key",
+    testnet_tx: "Testnet tx, mainnet key",
+    denylisted: "Blocked contract",
+    denylisted_msg: "Listed as malicious",
+    reward_owner: "Rewards paid to",
+    reward_device: "This device",
+    reward_unknown: "Unknown, check it",
+    multisig_account: "Multisig account",
+    multisig_signer_unknown: "Key of this device not listed, check it",
+
+    amount: "Amount uSTX",
+    amount_stx: "Amount STX",
+    recipient: "To",
+    memo: "Memo",
+    malformed_memo: "Memo mixes text and control bytes",
+
+    contract_address: "Contract address",
+    contract_name: "Contract name",
+    deploy_contract_name: "Contract Name",
+    clarity_version: "Clarity version",
+    old_clarity_version: "Old Clarity version",
+    old_clarity_version_msg: " - older than the one of the network epoch, check the deploy tool",
+    function_name: "Function name",
+    argument: "arg",
+    caution: "Caution",
+    privileged_function: "Admin-style function, check it carefully",
+    stacked: "stacked uSTX",
+    stacked_stx: "stacked STX",
+    delegated: "delegated uSTX",
+    delegated_stx: "delegated STX",
+    buffer_value: "is Buffer",
+    list_value: "is List",
+    tuple_value: "is Tuple",
+    reward_address: "BTC reward address",
+    capabilities: "Capabilities",
+    can_move: "Can move ",
+    and: " and ",
+    stx_assets: "STX",
+    ft_assets: "fungible tokens",
+    nft_assets: "NFTs",
+    acts_as_contract: "Can act as the contract",
+
+    ustx_unit: " (uSTX)",
+    stx_unit: " (STX)",
+    city: "City",
+    mining_commit: "Mining commit",
+    total_commit: "Total commit",
+    block_commit: "Block +",
+    stacked_tokens: "Stacked tokens",
+    lock_cycles: "Lock cycles",
+    mined_block: "Mined block",
+    reward_cycle: "Reward cycle",
+    vault_id: "Vault ID",
+    collateral: "Collateral",
+    collateral_type: "Collateral type",
+    collateral_token: "Collateral token",
+    collateral_added: "Collateral added",
+    collateral_withdrawn: "Collateral withdrawn",
+    usda_minted: "USDA minted",
+    usda_repaid: "USDA repaid",
+    token_in: "Token in",
+    token_out: "Token out",
+    via_token: "Via token",
+    amount_in: "Amount in",
+    min_received: "Min received",
+    no_minimum: "None, any amount accepted",
+    none_value: "None",
+    stx_deposited: "STX deposited",
+    referrer: "Referrer",
+    stacking_pool: "Stacking pool",
+    ststx_withdrawn: "stSTX withdrawn",
+    withdrawal_nft: "Withdrawal NFT id",
+    token_id: "Token ID",
+    price_stx: "Price (STX)",
+    royalty_to: "Royalty to",
+
+    principal: "Principal",
+    origin_principal: "Origin",
+    fungible_code: "Fungi. Code",
+    non_fungible_code: "NonFungi. Code",
+    ustx_amount: "uSTX amount",
+    stx_amount: "STX amount",
+    token_amount: "Token amount",
+    raw_units: " (raw units)",
+    token_amounts: "Token amounts",
+    raw_units_notice: "Raw units, decimals unknown",
+    sats_unit: "sats",
+    btc_unit: "BTC",
+    asset_name: "Asset name",
+    sent_eq: "SentEq",
+    sent_gt: "SentGt",
+    sent_ge: "SentGe",
+    sent_lt: "SentLt",
+    sent_le: "SentLe",
+    sent: "Sent",
+    not_sent: "NotSent",
+
+    raw_auth: "Auth (hex)",
+    raw_payload: "Payload (hex)",
+    raw_post_condition: "PC ",
+    raw_suffix: " (hex)",
+
+    sign_message: "Sign Message",
+    message_hash: "Message Hash",
+    jwt_hash: "JWT hash:",
+    auth_domain: "App domain",
+    auth_challenge: "Challenge",
+};
@@ -0,0 +1,130 @@
+// device fonts are ASCII only, accents are left out
+use super::Labels;
+
+pub const LABELS: Labels = Labels {
+    origin: "Origen",
+    nonce: "Nonce",
+    fee: "Comision (uSTX)",
+    fee_stx: "Comision (STX)",
+    high_fee: "Comision alta (uSTX)",
+    high_fee_stx: "Comision alta (STX)",
+    sponsored: "Tx patrocinada",
+    fee_paid_by: "Comision pagada por ",
+    fee_paid_by_device: "Comision pagada por este dispositivo, ",
+    sponsor_fee: "Comision sponsor (uSTX)",
+    sponsor_fee_stx: "Comision sponsor (STX)",
+    signatures: "Firmas presentes",
+    of: " de ",
+    duplicate_keys: "Clave de cofirmante repetida, menos firmantes",
+    key_path: "Ruta de clave",
+    nonstandard_path: "No estandar, verifiquela",
+    network: "Red",
+    mainnet_tx: "Tx de mainnet, clave testnet",
+    testnet_tx: "Tx de testnet, clave mainnet",
+    denylisted: "Contrato bloqueado",
+    denylisted_msg: "Listado como malicioso",
+    reward_owner: "Recompensas para",
+    reward_device: "Este dispositivo",
+    reward_unknown: "Desconocido, verifiquelo",
+    multisig_account: "Cuenta multifirma",
+    multisig_signer_unknown: "Clave de este dispositivo no listada, verifiquela",
+
+    amount: "Monto uSTX",
+    amount_stx: "Monto STX",
+    recipient: "Para",
+    memo: "Memo",
+    malformed_memo: "El memo mezcla texto y bytes de control",
+
+    contract_address: "Direccion contrato",
+    contract_name: "Nombre contrato",
+    deploy_contract_name: "Nombre contrato",
+    clarity_version: "Version de Clarity",
+    old_clarity_version: "Version de Clarity antigua",
+    old_clarity_version_msg:
+        " - anterior a la de la epoca de la red, verifique la herramienta de despliegue",
+    function_name: "Funcion",
+    argument: "arg",
+    caution: "Precaucion",
+    privileged_function: "Funcion de administracion, revisela con cuidado",
+    stacked: "uSTX en stacking",
+    stacked_stx: "STX en stacking",
+    delegated: "uSTX delegados",
+    delegated_stx: "STX delegados",
+    buffer_value: "es Buffer",
+    list_value: "es List",
+    tuple_value: "es Tuple",
+    reward_address: "Direccion BTC recompensa",
+    capabilities: "Capacidades",
+    can_move: "Puede mover ",
+    and: " y ",
+    stx_assets: "STX",
+    ft_assets: "tokens fungibles",
+    nft_assets: "NFTs",
+    acts_as_contract: "Puede actuar como el contrato",
+
+    ustx_unit: " (uSTX)",
+    stx_unit: " (STX)",
+    city: "Ciudad",
+    mining_commit: "Compromiso de minado",
+    total_commit: "Compromiso total",
+    block_commit: "Bloque +",
+    stacked_tokens: "Tokens en stacking",
+    lock_cycles: "Ciclos bloqueados",
+    mined_block: "Bloque minado",
+    reward_cycle: "Ciclo de recompensa",
+    vault_id: "ID de boveda",
+    collateral: "Colateral",
+    collateral_type: "Tipo de colateral",
+    collateral_token: "Token colateral",
+    collateral_added: "Colateral agregado",
+    collateral_withdrawn: "Colateral retirado",
+    usda_minted: "USDA emitido",
+    usda_repaid: "USDA pagado",
+    token_in: "Token entrada",
+    token_out: "Token salida",
+    via_token: "Token intermedio",
+    amount_in: "Monto entrada",
+    min_received: "Minimo a recibir",
+    no_minimum: "Ninguno, acepta cualquier monto",
+    none_value: "Ninguno",
+    stx_deposited: "STX depositados",
+    referrer: "Referido por",
+    stacking_pool: "Pool de stacking",
+    ststx_withdrawn: "stSTX retirados",
+    withdrawal_nft: "ID NFT de retiro",
+    token_id: "ID del token",
+    price_stx: "Precio (STX)",
+    royalty_to: "Regalias para",
+
+    principal: "Principal",
+    origin_principal: "Origen",
+    fungible_code: "Cod. Fungible",
+    non_fungible_code: "Cod. NoFungible",
+    ustx_amount: "Monto uSTX",
+    stx_amount: "Monto STX",
+    token_amount: "Monto token",
+    raw_units: " (unidades base)",
+    token_amounts: "Montos token",
+    raw_units_notice: "Unidades base, decimales desconocidos",
+    sats_unit: "sats",
+    btc_unit: "BTC",
+    asset_name: "Nombre activo",
+    sent_eq: "EnviaIgual",
+    sent_gt: "EnviaMayor",
+    sent_ge: "EnviaMayorIg",
+    sent_lt: "EnviaMenor",
+    sent_le: "EnviaMenorIg",
+    sent: "Enviado",
+    not_sent: "NoEnviado",
+
+    raw_auth: "Auth (hex)",
+    raw_payload: "Payload (hex)",
+    raw_post_condition: "PC ",
+    raw_suffix: " (hex)",
+
+    sign_message: "Firmar mensaje",
+    message_hash: "Hash mensaje",
+    jwt_hash: "Hash JWT:",
+    auth_domain: "Dominio app",
+    auth_challenge: "Desafio",
+};
@@ -0,0 +1,149 @@
+//! User-facing labels shown during review.
+//!
+//! Every language is a module exporting a `LABELS` table, English is used
+//! unless another language is selected with its `lang-<code>` cargo feature.
+//! Labels are limited by the screen width of the smallest device, so
+//! translations should keep them about as short as the English ones.
+
+mod en;
+#[cfg(feature = "lang-es")]
+mod es;
+
+#[cfg(not(feature = "lang-es"))]
+pub use en::LABELS;
+#[cfg(feature = "lang-es")]
+pub use es::LABELS;
+
+pub struct Labels {
+    // origin items
+    pub origin: &'static str,
+    pub nonce: &'static str,
+    pub fee: &'static str,
+    pub fee_stx: &'static str,
+    pub high_fee: &'static str,
+    pub high_fee_stx: &'static str,
+    pub sponsored: &'static str,
+    pub fee_paid_by: &'static str,
+    pub fee_paid_by_device: &'static str,
+    pub sponsor_fee: &'static str,
+    pub sponsor_fee_stx: &'static str,
+    pub signatures: &'static str,
+    pub of: &'static str,
+    pub duplicate_keys: &'static str,
+    pub key_path: &'static str,
+    pub nonstandard_path: &'static str,
+    pub network: &'static str,
+    pub mainnet_tx: &'static str,
+    pub testnet_tx: &'static str,
+    pub denylisted: &'static str,
+    pub denylisted_msg: &'static str,
+    pub reward_owner: &'static str,
+    pub reward_device: &'static str,
+    pub reward_unknown: &'static str,
+    pub multisig_account: &'static str,
+    pub multisig_signer_unknown: &'static str,
+
+    // token transfer items
+    pub amount: &'static str,
+    pub amount_stx: &'static str,
+    pub recipient: &'static str,
+    pub memo: &'static str,
+    pub malformed_memo: &'static str,
+
+    // contract call and deploy items
+    pub contract_address: &'static str,
+    pub contract_name: &'static str,
+    pub deploy_contract_name: &'static str,
+    pub clarity_version: &'static str,
+    pub old_clarity_version: &'static str,
+    pub old_clarity_version_msg: &'static str,
+    pub function_name: &'static str,
+    pub argument: &'static str,
+    pub caution: &'static str,
+    pub privileged_function: &'static str,
+    pub stacked: &'static str,
+    pub stacked_stx: &'static str,
+    pub delegated: &'static str,
+    pub delegated_stx: &'static str,
+    pub buffer_value: &'static str,
+    pub list_value: &'static str,
+    pub tuple_value: &'static str,
+    pub reward_address: &'static str,
+    pub capabilities: &'static str,
+    pub can_move: &'static str,
+    pub and: &'static str,
+    pub stx_assets: &'static str,
+    pub ft_assets: &'static str,
+    pub nft_assets: &'static str,
+    pub acts_as_contract: &'static str,
+
+    // items of the calls contract recognizers know
+    pub ustx_unit: &'static str,
+    pub stx_unit: &'static str,
+    pub city: &'static str,
+    pub mining_commit: &'static str,
+    pub total_commit: &'static str,
+    pub block_commit: &'static str,
+    pub stacked_tokens: &'static str,
+    pub lock_cycles: &'static str,
+    pub mined_block: &'static str,
+    pub reward_cycle: &'static str,
+    pub vault_id: &'static str,
+    pub collateral: &'static str,
+    pub collateral_type: &'static str,
+    pub collateral_token: &'static str,
+    pub collateral_added: &'static str,
+    pub collateral_withdrawn: &'static str,
+    pub usda_minted: &'static str,
+    pub usda_repaid: &'static str,
+    pub token_in: &'static str,
+    pub token_out: &'static str,
+    pub via_token: &'static str,
+    pub amount_in: &'static str,
+    pub min_received: &'static str,
+    pub no_minimum: &'static str,
+    pub none_value: &'static str,
+    pub stx_deposited: &'static str,
+    pub referrer: &'static str,
+    pub stacking_pool: &'static str,
+    pub ststx_withdrawn: &'static str,
+    pub withdrawal_nft: &'static str,
+    pub token_id: &'static str,
+    pub price_stx: &'static str,
+    pub royalty_to: &'static str,
+
+    // post condition items
+    pub principal: &'static str,
+    pub origin_principal: &'static str,
+    pub fungible_code: &'static str,
+    pub non_fungible_code: &'static str,
+    pub ustx_amount: &'static str,
+    pub stx_amount: &'static str,
+    pub token_amount: &'static str,
+    pub raw_units: &'static str,
+    pub token_amounts: &'static str,
+    pub raw_units_notice: &'static str,
+    pub sats_unit: &'static str,
+    pub btc_unit: &'static str,
+    pub asset_name: &'static str,
+    pub sent_eq: &'static str,
+    pub sent_gt: &'static str,
+    pub sent_ge: &'static str,
+    pub sent_lt: &'static str,
+    pub sent_le: &'static str,
+    pub sent: &'static str,
+    pub not_sent: &'static str,
+
+    // raw hex items
+    pub raw_auth: &'static str,
+    pub raw_payload: &'static str,
+    pub raw_post_condition: &'static str,
+    pub raw_suffix: &'static str,
+
+    // messages
+    pub sign_message: &'static str,
+    pub message_hash: &'static str,
+    pub jwt_hash: &'static str,
+    pub auth_domain: &'static str,
+    pub auth_challenge: &'static str,
+}
@@ -0,0 +1,185 @@
+//! Parser of the transactions, messages and tokens signed by the Stacks
+//! Ledger app.
+//!
+//! This is the same decoding and review logic the device runs. Without
+//! features it builds for any host, the `device` feature links it against
+//! the C side of the app instead: stack canary, review settings, hashing and
+//! number formatting. The app itself is the rslib crate one level up.
+
+#![no_std]
+#![no_builtins]
+#![macro_use]
+#![allow(dead_code)]
+#![deny(unused_crate_dependencies)]
+
+extern crate no_std_compat as std;
+
+// the host backend of signature::ecdsa, device builds use the app's one
+#[cfg(all(feature = "device", not(test), not(target_os = "none")))]
+use k256 as _;
+
+// only used by the benches
+#[cfg(test)]
+use criterion as _;
+
+#[cfg(any(test, not(feature = "device")))]
+use core::cell::Cell;
+
+pub mod account;
+mod bolos;
+pub mod btc;
+pub mod hash;
+mod lang;
+pub mod multisig;
+pub mod parser;
+pub mod path;
+pub mod settings;
+pub mod signature;
+pub mod stack;
+pub mod trace;
+pub mod zeroize;
+mod zxformat;
+
+fn debug(_msg: &str) {}
+
+extern "C" {
+    fn check_canary();
+    fn pic(link_address: u32) -> u32;
+    fn app_mode_expert() -> u8;
+    fn app_settings_raw_hex() -> u8;
+    fn app_settings_fee_threshold() -> u64;
+    fn app_settings_hide_nonce() -> u8;
+    fn app_settings_hide_empty_memo() -> u8;
+    fn app_settings_stx_units() -> u8;
+    fn app_settings_short_addresses() -> u8;
+    fn app_settings_grouped_addresses() -> u8;
+}
+
+pub(crate) fn canary() {
+    stack::mark();
+    #[cfg(all(feature = "device", not(test)))]
+    unsafe {
+        check_canary();
+    }
+}
+
+#[cfg(all(feature = "device", not(test)))]
+pub fn is_expert_mode() -> bool {
+    unsafe { app_mode_expert() > 0 }
+}
+
+#[cfg(any(test, not(feature = "device")))]
+pub fn is_expert_mode() -> bool {
+    true
+}
+
+/// Whether raw hex items are appended to the transaction review,
+/// this is only enabled along with expert mode
+#[cfg(all(feature = "device", not(test)))]
+pub fn is_raw_hex_mode() -> bool {
+    unsafe { app_settings_raw_hex() > 0 }
+}
+
+#[cfg(any(test, not(feature = "device")))]
+pub fn is_raw_hex_mode() -> bool {
+    false
+}
+
+/// The fee in uSTX above which the fee is shown as a warning
+#[cfg(all(feature = "device", not(test)))]
+pub fn fee_threshold() -> u64 {
+    unsafe { app_settings_fee_threshold() }
+}
+
+#[cfg(any(test, not(feature = "device")))]
+pub fn fee_threshold() -> u64 {
+    // 1 STX
+    1_000_000
+}
+
+/// Whether the nonce item is left out of the review,
+/// it is always shown in expert mode
+#[cfg(all(feature = "device", not(test)))]
+pub fn is_nonce_hidden() -> bool {
+    unsafe { app_settings_hide_nonce() > 0 }
+}
+
+#[cfg(any(test, not(feature = "device")))]
+pub fn is_nonce_hidden() -> bool {
+    hidden_items::NONCE.with(Cell::get)
+}
+
+/// Whether all-zero memos are left out of the review,
+/// they are always shown in expert mode
+#[cfg(all(feature = "device", not(test)))]
+pub fn is_empty_memo_hidden() -> bool {
+    unsafe { app_settings_hide_empty_memo() > 0 }
+}
+
+#[cfg(any(test, not(feature = "device")))]
+pub fn is_empty_memo_hidden() -> bool {
+    hidden_items::EMPTY_MEMO.with(Cell::get)
+}
+
+// on host the items left out are kept per thread,
+// tests run in parallel and each one hides its own
+#[cfg(any(test, not(feature = "device")))]
+mod hidden_items {
+    extern crate std as host;
+    use core::cell::Cell;
+
+    host::thread_local! {
+        pub static NONCE: Cell<bool> = const { Cell::new(false) };
+        pub static EMPTY_MEMO: Cell<bool> = const { Cell::new(false) };
+    }
+}
+
+/// Leaves the nonce and all-zero memos out of the reviews
+/// of the calling thread, as the device settings do
+#[cfg(test)]
+pub(crate) fn hide_items(nonce: bool, empty_memo: bool) {
+    hidden_items::NONCE.with(|cell| cell.set(nonce));
+    hidden_items::EMPTY_MEMO.with(|cell| cell.set(empty_memo));
+}
+
+/// Whether amounts are shown in STX, by default they are shown in uSTX
+#[cfg(all(feature = "device", not(test)))]
+pub fn is_stx_units() -> bool {
+    unsafe { app_settings_stx_units() > 0 }
+}
+
+#[cfg(any(test, not(feature = "device")))]
+pub fn is_stx_units() -> bool {
+    false
+}
+
+/// Whether addresses are shown abbreviated, their head and tail,
+/// they are always shown in full in expert mode
+#[cfg(all(feature = "device", not(test)))]
+pub fn is_short_address() -> bool {
+    unsafe { app_settings_short_addresses() > 0 }
+}
+
+#[cfg(any(test, not(feature = "device")))]
+pub fn is_short_address() -> bool {
+    false
+}
+
+/// Whether addresses shown in full are split in groups of 4 characters,
+/// substituted characters are easier to spot that way
+#[cfg(all(feature = "device", not(test)))]
+pub fn is_grouped_address() -> bool {
+    unsafe { app_settings_grouped_addresses() > 0 }
+}
+
+#[cfg(any(test, not(feature = "device")))]
+pub fn is_grouped_address() -> bool {
+    false
+}
+
+#[macro_export]
+macro_rules! check_canary {
+    () => {
+        $crate::canary();
+    };
+}
@@ -0,0 +1,216 @@
+//! Multisig templates of the slot export APDU.
+//!
+//! The coordinator of a multisig account assembles the spending condition of
+//! its transactions, with the keys in the order of the account. Given the
+//! template of the account, the device tells which slot its key takes, so
+//! signing requests are assembled without matching keys by hand:
+//!
+//! | hash mode (1) | threshold (2) | num keys (1) | pubkeys (33 each) |
+//!
+//! The threshold is big endian, the keys are compressed ones in the order of
+//! the account. The reply is the slot of the key of the device followed by
+//! the key:
+//!
+//! | slot (1) | pubkey (33) |
+
+use core::convert::TryFrom;
+
+use crate::account::COMPRESSED_PUBKEY_LEN;
+use crate::parser::{
+    reader::{be_u16, le_u8, take},
+    HashMode, ParserError,
+};
+
+pub const MULTISIG_SLOT_LEN: usize = 1 + COMPRESSED_PUBKEY_LEN;
+
+/// The keys and threshold of a multisig account
+#[derive(Clone, Copy, PartialEq)]
+#[cfg_attr(test, derive(Debug))]
+pub struct MultisigTemplate<'a> {
+    pub hash_mode: HashMode,
+    pub threshold: u16,
+    pubkeys: &'a [u8],
+}
+
+impl<'a> MultisigTemplate<'a> {
+    pub fn from_bytes(data: &'a [u8]) -> Result<Self, ParserError> {
+        let (rem, mode) = le_u8(data)?;
+        let (rem, threshold) = be_u16(rem)?;
+        let (rem, num_keys) = le_u8(rem)?;
+        let (rem, pubkeys) = take(num_keys as usize * COMPRESSED_PUBKEY_LEN)(rem)?;
+        if !rem.is_empty() {
+            return Err(ParserError::parser_unexpected_characters);
+        }
+
+        let hash_mode = HashMode::try_from(mode)?;
+        if !hash_mode.is_multisig() {
+            return Err(ParserError::parser_invalid_hash_mode);
+        }
+        if threshold == 0 || threshold > num_keys as u16 {
+            return Err(ParserError::parser_value_out_of_range);
+        }
+
+        let template = Self {
+            hash_mode,
+            threshold,
+            pubkeys,
+        };
+        if template
+            .pubkeys()
+            .any(|key| !matches!(key.first(), Some(0x02) | Some(0x03)))
+        {
+            return Err(ParserError::parser_invalid_pubkey_encoding);
+        }
+        // a repeated key would take two slots
+        if template
+            .pubkeys()
+            .enumerate()
+            .any(|(i, key)| template.pubkeys().skip(i + 1).any(|other| other == key))
+        {
+            return Err(ParserError::parser_unexpected_value);
+        }
+        Ok(template)
+    }
+
+    pub fn num_keys(&self) -> usize {
+        self.pubkeys.len() / COMPRESSED_PUBKEY_LEN
+    }
+
+    pub fn pubkeys(&self) -> impl Iterator<Item = &'a [u8]> {
+        self.pubkeys.chunks_exact(COMPRESSED_PUBKEY_LEN)
+    }
+
+    /// The slot of pubkey in the account
+    pub fn slot(&self, pubkey: &[u8]) -> Result<u8, ParserError> {
+        self.pubkeys()
+            .position(|key| key == pubkey)
+            .and_then(|slot| u8::try_from(slot).ok())
+            .ok_or(ParserError::parser_signer_mismatch)
+    }
+}
+
+/// Writes the slot of pubkey in the account of the template followed by
+/// the key, returns the bytes written
+pub fn write_multisig_slot(
+    template: &[u8],
+    pubkey: &[u8],
+    out: &mut [u8],
+) -> Result<usize, ParserError> {
+    if pubkey.len() != COMPRESSED_PUBKEY_LEN {
+        return Err(ParserError::parser_invalid_pubkey_encoding);
+    }
+    let slot = MultisigTemplate::from_bytes(template)?.slot(pubkey)?;
+
+    let (head, key) = out
+        .get_mut(..MULTISIG_SLOT_LEN)
+        .ok_or(ParserError::parser_unexpected_buffer_end)?
+        .split_at_mut(1);
+    head.iter_mut().for_each(|b| *b = slot);
+    key.copy_from_slice(pubkey);
+    Ok(MULTISIG_SLOT_LEN)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::prelude::v1::*;
+
+    // the key of m/44'/5757'/5'/0/0 in the zemu tests
+    const PUBKEY: &str = "0252dab95065cd31ae6f8ece65fffd2e904b203268a5923fa85e5db793698d753a";
+    const COSIGNERS: [&str; 2] = [
+        "03a0a4d5f71d4c6bd1ba01c36d6ef1f07d2aea8e6ee5e49ce4bd0b9be8d7b2c1ef",
+        "02f5ec7c3ab8b5b5b34b1d3e0fbd5dd9d2a14c7e4aa0b86c0cc6f6b0a6e2f3b5c1",
+    ];
+
+    fn template(mode: u8, threshold: u16, keys: &[&str]) -> Vec<u8> {
+        let mut data = vec![mode];
+        data.extend_from_slice(&threshold.to_be_bytes());
+        data.push(keys.len() as u8);
+        for key in keys {
+            data.extend_from_slice(&hex::decode(key).unwrap());
+        }
+        data
+    }
+
+    #[test]
+    fn test_multisig_slot() {
+        let pubkey = hex::decode(PUBKEY).unwrap();
+        let data = template(0x01, 2, &[COSIGNERS[0], PUBKEY, COSIGNERS[1]]);
+
+        let parsed = MultisigTemplate::from_bytes(&data).unwrap();
+        assert_eq!(parsed.hash_mode, HashMode::P2SH);
+        assert_eq!(parsed.threshold, 2);
+        assert_eq!(parsed.num_keys(), 3);
+        assert_eq!(parsed.slot(&pubkey), Ok(1));
+
+        let mut out = [0u8; MULTISIG_SLOT_LEN];
+        assert_eq!(
+            write_multisig_slot(&data, &pubkey, &mut out),
+            Ok(MULTISIG_SLOT_LEN)
+        );
+        assert_eq!(out[0], 1);
+        assert_eq!(&out[1..], pubkey.as_slice());
+
+        // every multisig mode, with the key in the first slot
+        for mode in [0x03, 0x05, 0x07] {
+            let data = template(mode, 1, &[PUBKEY, COSIGNERS[0]]);
+            write_multisig_slot(&data, &pubkey, &mut out).unwrap();
+            assert_eq!(out[0], 0);
+        }
+    }
+
+    #[test]
+    fn test_multisig_slot_errors() {
+        let pubkey = hex::decode(PUBKEY).unwrap();
+        let mut out = [0u8; MULTISIG_SLOT_LEN];
+        let slot = |data: &[u8]| write_multisig_slot(data, &pubkey, &mut [0u8; MULTISIG_SLOT_LEN]);
+
+        // singlesig and unknown hash modes
+        for mode in [0x00, 0x02, 0x04] {
+            assert_eq!(
+                slot(&template(mode, 1, &[PUBKEY])),
+                Err(ParserError::parser_invalid_hash_mode)
+            );
+        }
+
+        // thresholds no signer set can meet
+        for threshold in [0, 3] {
+            assert_eq!(
+                slot(&template(0x01, threshold, &[PUBKEY, COSIGNERS[0]])),
+                Err(ParserError::parser_value_out_of_range)
+            );
+        }
+
+        // the key of the device is not in the account, or in it twice
+        assert_eq!(
+            slot(&template(0x01, 1, &COSIGNERS)),
+            Err(ParserError::parser_signer_mismatch)
+        );
+        assert_eq!(
+            slot(&template(0x01, 1, &[PUBKEY, COSIGNERS[0], PUBKEY])),
+            Err(ParserError::parser_unexpected_value)
+        );
+
+        // uncompressed prefixes, truncated or trailing keys
+        let mut uncompressed = template(0x01, 1, &[PUBKEY]);
+        uncompressed[4] = 0x04;
+        assert_eq!(
+            slot(&uncompressed),
+            Err(ParserError::parser_invalid_pubkey_encoding)
+        );
+        let data = template(0x01, 1, &[PUBKEY]);
+        assert!(slot(&data[..data.len() - 1]).is_err());
+        let mut trailing = data.clone();
+        trailing.push(0);
+        assert_eq!(
+            slot(&trailing),
+            Err(ParserError::parser_unexpected_characters)
+        );
+
+        // the reply does not fit
+        assert_eq!(
+            write_multisig_slot(&data, &pubkey, &mut out[..MULTISIG_SLOT_LEN - 1]),
+            Err(ParserError::parser_unexpected_buffer_end)
+        );
+    }
+}
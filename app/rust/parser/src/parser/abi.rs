@@ -0,0 +1,199 @@
+use core::convert::TryFrom;
+
+use super::error::ParserError;
+use super::reader::{le_u8, take};
+use super::value::ValueId;
+use super::{ClarityName, ContractName, MAX_NUM_ARGS, STACKS_ADDR_LEN};
+
+// Argument names are shown as review keys, longer ones are refused
+pub const ABI_MAX_NAME_LEN: usize = 32;
+
+// The type and name of an argument
+type Argument<'a> = (ValueId, &'a [u8]);
+
+/// The signature of a contract function provisioned by the host, so that
+/// the arguments of a call to it are reviewed by name. It is serialized as
+/// the contract principal, as in a contract call payload, the length
+/// prefixed function name, the number of arguments and, for each of them,
+/// its type followed by its length prefixed name.
+///
+/// A type is the id of the values it admits, the first one of the family
+/// for booleans, principals, responses and optionals
+#[derive(Clone, Copy, PartialEq)]
+#[cfg_attr(test, derive(Debug))]
+pub struct FunctionAbi<'a>(&'a [u8]);
+
+impl<'a> FunctionAbi<'a> {
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, ParserError> {
+        let abi = Self(bytes);
+        let (num_args, mut rem) = abi.arguments()?;
+        if num_args as u32 > MAX_NUM_ARGS {
+            return Err(ParserError::parser_value_out_of_range);
+        }
+        for _ in 0..num_args {
+            let (next, _) = Self::argument(rem)?;
+            rem = next;
+        }
+        if !rem.is_empty() {
+            return Err(ParserError::parser_unexpected_characters);
+        }
+        Ok(abi)
+    }
+
+    /// The serialized contract principal, the address
+    /// followed by the length prefixed contract name
+    pub fn contract_id(&self) -> Result<&'a [u8], ParserError> {
+        let (rem, _) = take(STACKS_ADDR_LEN)(self.0)?;
+        let (rem, _) = ContractName::from_bytes(rem)?;
+        self.0
+            .get(..self.0.len() - rem.len())
+            .ok_or(ParserError::parser_unexpected_buffer_end)
+    }
+
+    pub fn function_name(&self) -> Result<&'a [u8], ParserError> {
+        let contract_id = self.contract_id()?;
+        let rem = self
+            .0
+            .get(contract_id.len()..)
+            .ok_or(ParserError::parser_unexpected_buffer_end)?;
+        let (_, name) = ClarityName::from_bytes(rem)?;
+        Ok(name.0)
+    }
+
+    pub fn num_args(&self) -> Result<u8, ParserError> {
+        self.arguments().map(|(num_args, _)| num_args)
+    }
+
+    /// The type and name of the argument at
+    pub fn argument_at(&self, at: usize) -> Result<Argument<'a>, ParserError> {
+        let (num_args, mut rem) = self.arguments()?;
+        for idx in 0..num_args as usize {
+            let (next, argument) = Self::argument(rem)?;
+            if idx == at {
+                return Ok(argument);
+            }
+            rem = next;
+        }
+        Err(ParserError::parser_display_idx_out_of_range)
+    }
+
+    /// Whether a value with id can be passed as an argument of type ty
+    pub fn admits(ty: ValueId, id: ValueId) -> bool {
+        match ty {
+            ValueId::BoolTrue => matches!(id, ValueId::BoolTrue | ValueId::BoolFalse),
+            ValueId::StandardPrincipal => {
+                matches!(id, ValueId::StandardPrincipal | ValueId::ContractPrincipal)
+            }
+            ValueId::ResponseOk => matches!(id, ValueId::ResponseOk | ValueId::ResponseErr),
+            ValueId::OptionalNone => matches!(id, ValueId::OptionalNone | ValueId::OptionalSome),
+            ty => ty == id,
+        }
+    }
+
+    // the number of arguments and the bytes of the first one
+    fn arguments(&self) -> Result<(u8, &'a [u8]), ParserError> {
+        let function_name = self.function_name()?;
+        let rem = self
+            .0
+            .get(self.contract_id()?.len() + 1 + function_name.len()..)
+            .ok_or(ParserError::parser_unexpected_buffer_end)?;
+        let (rem, num_args) = le_u8(rem)?;
+        Ok((num_args, rem))
+    }
+
+    fn argument(bytes: &'a [u8]) -> Result<(&'a [u8], Argument<'a>), ParserError> {
+        let (rem, ty) = le_u8(bytes)?;
+        let ty = ValueId::try_from(ty)?;
+        if matches!(
+            ty,
+            ValueId::BoolFalse
+                | ValueId::ContractPrincipal
+                | ValueId::ResponseErr
+                | ValueId::OptionalSome
+        ) {
+            return Err(ParserError::parser_unexpected_type);
+        }
+
+        let (rem, name) = ClarityName::from_bytes(rem)?;
+        if name.0.is_empty() || name.0.len() > ABI_MAX_NAME_LEN || !name.0.is_ascii() {
+            return Err(ParserError::parser_invalid_argument_id);
+        }
+        Ok((rem, (ty, name.0)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::prelude::v1::*;
+
+    // (transfer (amount uint) (recipient principal)) of a token contract
+    fn transfer_abi() -> Vec<u8> {
+        let mut abi = vec![22];
+        abi.extend_from_slice(&[0x11; 20]);
+        abi.push(5);
+        abi.extend_from_slice(b"token");
+        abi.push(8);
+        abi.extend_from_slice(b"transfer");
+        abi.push(2);
+        abi.push(ValueId::UInt as u8);
+        abi.push(6);
+        abi.extend_from_slice(b"amount");
+        abi.push(ValueId::StandardPrincipal as u8);
+        abi.push(9);
+        abi.extend_from_slice(b"recipient");
+        abi
+    }
+
+    #[test]
+    fn test_function_abi() {
+        let bytes = transfer_abi();
+        let abi = FunctionAbi::from_bytes(&bytes).unwrap();
+
+        assert_eq!(abi.contract_id().unwrap(), &bytes[..27]);
+        assert_eq!(abi.function_name().unwrap(), b"transfer");
+        assert_eq!(abi.num_args().unwrap(), 2);
+        assert_eq!(abi.argument_at(0).unwrap(), (ValueId::UInt, &b"amount"[..]));
+        assert_eq!(
+            abi.argument_at(1).unwrap(),
+            (ValueId::StandardPrincipal, &b"recipient"[..])
+        );
+        assert!(abi.argument_at(2).is_err());
+
+        assert!(FunctionAbi::admits(
+            ValueId::StandardPrincipal,
+            ValueId::ContractPrincipal
+        ));
+        assert!(!FunctionAbi::admits(ValueId::UInt, ValueId::Int));
+    }
+
+    #[test]
+    fn test_function_abi_errors() {
+        let bytes = transfer_abi();
+
+        // missing the name of the last argument
+        assert!(FunctionAbi::from_bytes(&bytes[..bytes.len() - 10]).is_err());
+
+        // trailing bytes
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert!(FunctionAbi::from_bytes(&trailing).is_err());
+
+        // only the first type of a family is a type
+        let mut ty = bytes.clone();
+        ty[37] = ValueId::ContractPrincipal as u8;
+        assert_eq!(
+            FunctionAbi::from_bytes(&ty),
+            Err(ParserError::parser_unexpected_type)
+        );
+
+        // names longer than a review key
+        let mut long = bytes[..46].to_vec();
+        long.push(ABI_MAX_NAME_LEN as u8 + 1);
+        long.extend_from_slice(&[b'a'; ABI_MAX_NAME_LEN + 1]);
+        assert_eq!(
+            FunctionAbi::from_bytes(&long),
+            Err(ParserError::parser_invalid_argument_id)
+        );
+    }
+}
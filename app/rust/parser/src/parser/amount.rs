@@ -0,0 +1,96 @@
+//! Totals of uSTX amounts.
+//!
+//! Every amount a transaction carries is an u64 of uSTX, but a figure
+//! derived from several of them, the amount along with the fee or the sum
+//! of the amounts of some post conditions, can go past u64::MAX. Totals
+//! are kept in an u128 instead, which no count of u64 amounts a
+//! transaction fits can overflow, and are only narrowed back with a check
+//! or written out as digits, so a summary never wraps around to a smaller
+//! figure than the one the user agrees to.
+
+use core::convert::TryFrom;
+
+use numtoa::NumToA;
+
+use super::ParserError;
+use crate::zxformat;
+
+// The digits of u128::MAX
+const U128_DIGITS: usize = 39;
+
+/// A sum of uSTX amounts
+#[derive(Clone, Copy, Default, PartialEq)]
+#[cfg_attr(test, derive(Debug))]
+pub struct UstxTotal(u128);
+
+impl UstxTotal {
+    /// The total of amounts
+    pub fn of(amounts: impl IntoIterator<Item = u64>) -> Self {
+        amounts
+            .into_iter()
+            .fold(Self::default(), |total, amount| total.plus(amount))
+    }
+
+    /// The total along with amount, it saturates rather than wrapping
+    /// although it would take 2^64 amounts to get there
+    pub fn plus(self, amount: u64) -> Self {
+        Self(self.0.saturating_add(u128::from(amount)))
+    }
+
+    pub fn value(&self) -> u128 {
+        self.0
+    }
+
+    /// The total if it fits an u64
+    pub fn to_u64(&self) -> Option<u64> {
+        u64::try_from(self.0).ok()
+    }
+
+    /// Writes the total in the unit amounts are shown in,
+    /// returning the number of bytes written
+    pub fn write(&self, out: &mut [u8]) -> Result<usize, ParserError> {
+        let mut digits = [0u8; U128_DIGITS];
+        let digits = self.0.numtoa(10, &mut digits);
+        zxformat::stx_digits_to_str(out, digits)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn written(total: UstxTotal) -> std::string::String {
+        let mut out = [0u8; zxformat::MAX_STR_BUFF_LEN + 20];
+        let len = total.write(&mut out).unwrap();
+        std::string::String::from_utf8(out[..len].to_vec()).unwrap()
+    }
+
+    #[test]
+    fn test_total_past_u64_max() {
+        let total = UstxTotal::of([u64::MAX, 1]);
+        assert_eq!(total.value(), u128::from(u64::MAX) + 1);
+        assert_eq!(total.to_u64(), None);
+        assert_eq!(written(total), "18446744073709551616");
+
+        let total = UstxTotal::of([u64::MAX - 1, 1]);
+        assert_eq!(total.to_u64(), Some(u64::MAX));
+        assert_eq!(written(total), "18446744073709551615");
+
+        let total = UstxTotal::of([u64::MAX; 3]);
+        assert_eq!(total.value(), 3 * u128::from(u64::MAX));
+        assert_eq!(written(total), "55340232221128654845");
+    }
+
+    #[test]
+    fn test_total_saturates() {
+        let total = UstxTotal(u128::MAX - 1).plus(u64::MAX);
+        assert_eq!(total.value(), u128::MAX);
+        assert_eq!(written(total), "340282366920938463463374607431768211455");
+    }
+
+    #[test]
+    fn test_empty_total() {
+        assert_eq!(UstxTotal::of([]), UstxTotal::default());
+        assert_eq!(written(UstxTotal::default()), "0");
+    }
+}
@@ -0,0 +1,249 @@
+//! Stacks Connect authentication challenges.
+//!
+//! A dApp logs a user in by having the key of their identity or account sign
+//! a challenge it picked, bound to the domain of the app. The challenge is
+//! serialized after a header of its own, so its signature can not be taken
+//! for the signature of a message, a token or a transaction:
+//!
+//! `0x15` `Stacks Connect Auth:\n`, the length prefixed domain of the app,
+//! and the length prefixed challenge.
+//!
+//! The domain is what the user checks, so only lowercase hostnames, with an
+//! optional port, are taken. The challenge is random bytes nobody can check
+//! by heart, only both ends of it are shown.
+
+use core::fmt::Write;
+
+use super::error::ParserError;
+use super::reader::{le_u8, take};
+use crate::lang::LABELS;
+use crate::zxformat::{pageString, Writer};
+
+const AUTH_HEADER: &[u8] = b"\x15Stacks Connect Auth:\n";
+
+// The longest hostname, a port can follow it
+pub const AUTH_HOSTNAME_MAX_LEN: usize = 253;
+
+pub const AUTH_CHALLENGE_MIN_LEN: usize = 16;
+pub const AUTH_CHALLENGE_MAX_LEN: usize = 64;
+
+// The bytes of the challenge shown at each end
+const CHALLENGE_SHOWN_LEN: usize = 4;
+
+#[repr(C)]
+#[derive(Copy, Clone, PartialEq)]
+#[cfg_attr(test, derive(Debug))]
+pub struct AuthChallenge<'a> {
+    domain: &'a [u8],
+    challenge: &'a [u8],
+}
+
+impl<'a> AuthChallenge<'a> {
+    pub fn from_bytes(data: &'a [u8]) -> Result<Self, ParserError> {
+        if !Self::is_auth(data) {
+            return Err(ParserError::parser_unexpected_type);
+        }
+        let rem = data
+            .get(AUTH_HEADER.len()..)
+            .ok_or(ParserError::parser_unexpected_buffer_end)?;
+        let (rem, len) = le_u8(rem)?;
+        let (rem, domain) = take(len as usize)(rem)?;
+        let (rem, len) = le_u8(rem)?;
+        let (rem, challenge) = take(len as usize)(rem)?;
+        if !rem.is_empty() {
+            return Err(ParserError::parser_unexpected_characters);
+        }
+
+        if !Self::is_domain(domain)
+            || !(AUTH_CHALLENGE_MIN_LEN..=AUTH_CHALLENGE_MAX_LEN).contains(&challenge.len())
+        {
+            return Err(ParserError::parser_invalid_auth_challenge);
+        }
+        Ok(Self { domain, challenge })
+    }
+
+    pub fn read(&mut self, data: &'a [u8]) -> Result<(), ParserError> {
+        *self = Self::from_bytes(data)?;
+        Ok(())
+    }
+
+    pub fn is_auth(data: &[u8]) -> bool {
+        data.starts_with(AUTH_HEADER)
+    }
+
+    // a lowercase hostname, optionally followed by a port
+    fn is_domain(domain: &[u8]) -> bool {
+        let mut parts = domain.splitn(2, |c| *c == b':');
+        let host = parts.next().unwrap_or_default();
+        let port = parts.next();
+
+        let host_ok = !host.is_empty()
+            && host.len() <= AUTH_HOSTNAME_MAX_LEN
+            && host.split(|c| *c == b'.').all(|label| {
+                !label.is_empty()
+                    && !label.starts_with(b"-")
+                    && !label.ends_with(b"-")
+                    && label
+                        .iter()
+                        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || *c == b'-')
+            });
+        let port_ok = match port {
+            Some(port) => (1..=5).contains(&port.len()) && port.iter().all(u8::is_ascii_digit),
+            None => true,
+        };
+        host_ok && port_ok
+    }
+
+    pub fn domain(&self) -> &'a [u8] {
+        self.domain
+    }
+
+    pub fn challenge(&self) -> &'a [u8] {
+        self.challenge
+    }
+
+    pub fn num_items(&self) -> u8 {
+        2
+    }
+
+    pub fn get_item(
+        &mut self,
+        display_idx: u8,
+        out_key: &mut [u8],
+        out_value: &mut [u8],
+        page_idx: u8,
+    ) -> Result<u8, ParserError> {
+        let mut writer_key = Writer::new(out_key);
+        match display_idx {
+            0 => {
+                writer_key
+                    .write_str(LABELS.auth_domain)
+                    .map_err(|_| ParserError::parser_unexpected_buffer_end)?;
+                pageString(out_value, self.domain, page_idx)
+            }
+            1 => {
+                writer_key
+                    .write_str(LABELS.auth_challenge)
+                    .map_err(|_| ParserError::parser_unexpected_buffer_end)?;
+                let mut shown = [0u8; 4 * CHALLENGE_SHOWN_LEN + 3];
+                let len = self.truncated_challenge(&mut shown)?;
+                let shown = shown
+                    .get(..len)
+                    .ok_or(ParserError::parser_unexpected_buffer_end)?;
+                pageString(out_value, shown, page_idx)
+            }
+            _ => Err(ParserError::parser_display_idx_out_of_range),
+        }
+    }
+
+    // the hex of both ends of the challenge, joined by an ellipsis
+    fn truncated_challenge(&self, out: &mut [u8]) -> Result<usize, ParserError> {
+        let len = self.challenge.len();
+        let head = self.challenge.get(..CHALLENGE_SHOWN_LEN);
+        let tail = len
+            .checked_sub(CHALLENGE_SHOWN_LEN)
+            .and_then(|at| self.challenge.get(at..));
+        let (head, tail) = head
+            .zip(tail)
+            .ok_or(ParserError::parser_invalid_auth_challenge)?;
+
+        let mut writer = Writer::new(out);
+        head.iter()
+            .try_for_each(|b| write!(writer, "{:02x}", b))
+            .and_then(|_| writer.write_str("..."))
+            .and_then(|_| tail.iter().try_for_each(|b| write!(writer, "{:02x}", b)))
+            .map_err(|_| ParserError::parser_unexpected_buffer_end)?;
+        Ok(writer.offset)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::prelude::v1::*;
+
+    fn auth(domain: &str, challenge: &[u8]) -> Vec<u8> {
+        let mut data = AUTH_HEADER.to_vec();
+        data.push(domain.len() as u8);
+        data.extend_from_slice(domain.as_bytes());
+        data.push(challenge.len() as u8);
+        data.extend_from_slice(challenge);
+        data
+    }
+
+    fn item(auth: &mut AuthChallenge, idx: u8) -> (String, String) {
+        let mut key = [0u8; 30];
+        let mut value = [0u8; 40];
+        auth.get_item(idx, &mut key, &mut value, 0).unwrap();
+        let text = |bytes: &[u8]| {
+            let len = bytes.iter().position(|b| *b == 0).unwrap();
+            String::from_utf8(bytes[..len].to_vec()).unwrap()
+        };
+        (text(&key), text(&value))
+    }
+
+    #[test]
+    fn test_auth_challenge() {
+        let challenge: Vec<u8> = (0..32).collect();
+        let data = auth("app.alexlab.co", &challenge);
+        assert!(AuthChallenge::is_auth(&data));
+
+        let mut parsed = AuthChallenge::from_bytes(&data).unwrap();
+        assert_eq!(parsed.domain(), b"app.alexlab.co");
+        assert_eq!(parsed.challenge(), &challenge[..]);
+        assert_eq!(parsed.num_items(), 2);
+        assert_eq!(
+            item(&mut parsed, 0),
+            ("App domain".into(), "app.alexlab.co".into())
+        );
+        assert_eq!(
+            item(&mut parsed, 1),
+            ("Challenge".into(), "00010203...1c1d1e1f".into())
+        );
+
+        let local = auth("localhost:3000", &challenge[..16]);
+        assert!(AuthChallenge::from_bytes(&local).is_ok());
+    }
+
+    #[test]
+    fn test_auth_challenge_errors() {
+        let challenge = [0xab; 32];
+
+        // domains that could pass for another one
+        for domain in [
+            "App.alexlab.co",
+            "app.alexlab.co.",
+            "app..co",
+            "-app.co",
+            "app.co:",
+            "app.co:123456",
+            "https://app.co",
+            "app.co/login",
+            "аpp.co",
+            "",
+        ] {
+            assert_eq!(
+                AuthChallenge::from_bytes(&auth(domain, &challenge)),
+                Err(ParserError::parser_invalid_auth_challenge),
+                "{}",
+                domain
+            );
+        }
+
+        // challenges too short to be random or longer than the ones taken
+        assert!(AuthChallenge::from_bytes(&auth("app.co", &challenge[..15])).is_err());
+        assert!(AuthChallenge::from_bytes(&auth("app.co", &[0; 65])).is_err());
+
+        // trailing bytes, or another header
+        let mut trailing = auth("app.co", &challenge);
+        trailing.push(0);
+        assert_eq!(
+            AuthChallenge::from_bytes(&trailing),
+            Err(ParserError::parser_unexpected_characters)
+        );
+        let mut header = auth("app.co", &challenge);
+        header[1] = b's';
+        assert!(!AuthChallenge::is_auth(&header));
+        assert!(AuthChallenge::from_bytes(&header).is_err());
+    }
+}
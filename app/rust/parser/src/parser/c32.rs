@@ -0,0 +1,292 @@
+use core::convert::TryInto;
+
+use crate::bolos::SHA256_LEN;
+use crate::hash::{DefaultSha256, Sha256};
+use crate::parser::{
+    error::ParserError,
+    parser_common::{C32_ENCODED_ADDRS_LENGTH, HASH160_LEN},
+};
+use crate::{is_grouped_address, is_short_address, zxformat};
+
+pub const C32_ADDRESS_VERSION_MAINNET_SINGLESIG: u8 = 22;
+pub const C32_ADDRESS_VERSION_MAINNET_MULTISIG: u8 = 20;
+pub const C32_ADDRESS_VERSION_TESTNET_SINGLESIG: u8 = 26;
+pub const C32_ADDRESS_VERSION_TESTNET_MULTISIG: u8 = 21;
+
+const C32_CHARACTERS: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+// The characters kept on each side of an abbreviated address, the
+// prefix and version are part of the head
+const SHORT_HEAD_LEN: usize = 8;
+const SHORT_TAIL_LEN: usize = 6;
+const SHORT_ELLIPSIS: &[u8] = b"...";
+
+// Characters between the spaces of an address shown in groups
+const GROUP_LEN: usize = 4;
+
+#[inline(never)]
+pub(crate) fn double_sha256_checksum(data: &[u8]) -> [u8; 4] {
+    let mut first = [0u8; SHA256_LEN];
+    DefaultSha256::digest(data, &mut first);
+    let mut second = [0u8; SHA256_LEN];
+    DefaultSha256::digest(&first, &mut second);
+    let [a, b, c, d, ..] = second;
+    [a, b, c, d]
+}
+
+/// A c32check encoded address whose characters are computed as they
+/// are written, so it can be paged straight into the output buffer
+/// without being encoded into a temporary one first.
+#[derive(Clone, Copy, PartialEq)]
+#[cfg_attr(test, derive(Debug))]
+pub struct C32Address {
+    // the 20-byte hash followed by the 4-byte checksum
+    check_data: [u8; C32_CHECK_DATA_LEN],
+    version: u8,
+    // leading zero bytes, each one is encoded as a '0'
+    zeros: u8,
+    // characters needed to encode the rest of the data
+    digits: u8,
+}
+
+const C32_CHECK_DATA_LEN: usize = HASH160_LEN + 4;
+
+#[allow(clippy::len_without_is_empty)]
+impl C32Address {
+    #[inline(never)]
+    pub fn new(version: u8, data: &[u8]) -> Result<Self, ParserError> {
+        if version >= 32 {
+            return Err(ParserError::parser_invalid_address_version);
+        }
+        let data: &[u8; HASH160_LEN] = data
+            .try_into()
+            .map_err(|_| ParserError::parser_invalid_address)?;
+
+        // the checksum covers the version and the hash
+        let mut versioned = [version; 1 + HASH160_LEN];
+        for (dst, src) in versioned.iter_mut().skip(1).zip(data) {
+            *dst = *src;
+        }
+        let checksum = double_sha256_checksum(&versioned);
+
+        let mut check_data = [0u8; C32_CHECK_DATA_LEN];
+        for (dst, src) in check_data.iter_mut().zip(data.iter().chain(&checksum)) {
+            *dst = *src;
+        }
+
+        let zeros = check_data.iter().take_while(|v| **v == 0).count();
+        let bits = check_data
+            .get(zeros)
+            .map(|v| (C32_CHECK_DATA_LEN - zeros) * 8 - v.leading_zeros() as usize)
+            .unwrap_or(0);
+
+        Ok(Self {
+            check_data,
+            version,
+            zeros: zeros as u8,
+            digits: bits.div_ceil(5) as u8,
+        })
+    }
+
+    /// Whether this is the address of version and the 20-byte hash data
+    pub fn is_of(&self, version: u8, data: &[u8]) -> bool {
+        self.version == version && self.check_data.get(..HASH160_LEN) == Some(data)
+    }
+
+    /// The length of the encoded address, including the 'S' prefix
+    pub fn len(&self) -> usize {
+        2 + self.zeros as usize + self.digits as usize
+    }
+
+    /// Returns the 5-bit digit at position idx counting from the least significant
+    fn digit(&self, idx: usize) -> u8 {
+        let bit = idx * 5;
+        let byte = match (C32_CHECK_DATA_LEN - 1).checked_sub(bit / 8) {
+            Some(byte) => byte,
+            None => return 0,
+        };
+        let shift = bit % 8;
+        let at = |byte: usize| self.check_data.get(byte).copied().unwrap_or(0) as u16;
+        let mut value = at(byte) >> shift;
+        if shift > 3 && byte > 0 {
+            value |= at(byte - 1) << (8 - shift);
+        }
+        (value & 0x1f) as u8
+    }
+
+    /// Returns the encoded character at pos, pos must be lower than len()
+    pub fn char_at(&self, pos: usize) -> u8 {
+        let zeros = self.zeros as usize;
+        let digit = match pos {
+            0 => return b'S',
+            1 => self.version,
+            p if p < 2 + zeros => 0,
+            p => match self.len().checked_sub(1 + p) {
+                Some(idx) => self.digit(idx),
+                None => 0,
+            },
+        };
+        // version and digits are 5-bit values
+        C32_CHARACTERS[(digit & 0x1f) as usize]
+    }
+
+    /// Writes the page_idx page of the address into out_value
+    pub fn page(&self, out_value: &mut [u8], page_idx: u8) -> Result<u8, ParserError> {
+        self.page_between(&[], &[], out_value, page_idx)
+    }
+
+    /// Writes the page_idx page of prefix + address + suffix into out_value,
+    /// the address is abbreviated if the user chose so outside expert mode,
+    /// and otherwise shown in groups of 4 characters unless they chose not to
+    pub fn page_between(
+        &self,
+        prefix: &[u8],
+        suffix: &[&[u8]],
+        out_value: &mut [u8],
+        page_idx: u8,
+    ) -> Result<u8, ParserError> {
+        let short = is_short_address();
+        let grouped = is_grouped_address();
+        self.page_styled(prefix, suffix, short, grouped, out_value, page_idx)
+    }
+
+    /// Writes the page_idx page of the address as addresses being verified
+    /// are shown, never abbreviated and in groups of 4 characters unless
+    /// the user chose not to
+    pub fn page_full(&self, out_value: &mut [u8], page_idx: u8) -> Result<u8, ParserError> {
+        let grouped = is_grouped_address();
+        self.page_styled(&[], &[], false, grouped, out_value, page_idx)
+    }
+
+    // the number of characters shown of the address
+    fn shown_len(&self, short: bool, grouped: bool) -> usize {
+        let short_len = SHORT_HEAD_LEN + SHORT_ELLIPSIS.len() + SHORT_TAIL_LEN;
+        if short && self.len() > short_len {
+            short_len
+        } else if grouped {
+            self.len() + self.len().saturating_sub(1) / GROUP_LEN
+        } else {
+            self.len()
+        }
+    }
+
+    // the character shown at pos, the head and tail of the address around
+    // an ellipsis if it is abbreviated, or a space between its groups
+    fn shown_char_at(&self, pos: usize, shown_len: usize) -> u8 {
+        if shown_len > self.len() {
+            return match pos % (GROUP_LEN + 1) {
+                GROUP_LEN => b' ',
+                at => self.char_at(pos / (GROUP_LEN + 1) * GROUP_LEN + at),
+            };
+        }
+        if shown_len == self.len() || pos < SHORT_HEAD_LEN {
+            return self.char_at(pos);
+        }
+        match SHORT_ELLIPSIS.get(pos - SHORT_HEAD_LEN) {
+            Some(dot) => *dot,
+            None => self.char_at(self.len() - (shown_len - pos)),
+        }
+    }
+
+    #[inline(never)]
+    fn page_styled(
+        &self,
+        prefix: &[u8],
+        suffix: &[&[u8]],
+        short: bool,
+        grouped: bool,
+        out_value: &mut [u8],
+        page_idx: u8,
+    ) -> Result<u8, ParserError> {
+        let shown_len = self.shown_len(short, grouped);
+        let address_end = prefix.len() + shown_len;
+        let len = address_end + suffix.iter().map(|part| part.len()).sum::<usize>();
+
+        zxformat::pageWith(out_value, len, page_idx, |mut pos| {
+            if pos < prefix.len() {
+                return prefix[pos];
+            }
+            if pos < address_end {
+                return self.shown_char_at(pos - prefix.len(), shown_len);
+            }
+            pos -= address_end;
+            for part in suffix {
+                if pos < part.len() {
+                    return part[pos];
+                }
+                pos -= part.len();
+            }
+            0
+        })
+    }
+}
+
+#[inline(never)]
+pub fn c32_address(
+    version: u8,
+    data: &[u8],
+) -> Result<arrayvec::ArrayVec<[u8; C32_ENCODED_ADDRS_LENGTH]>, ParserError> {
+    let address = C32Address::new(version, data)?;
+    Ok((0..address.len()).map(|pos| address.char_at(pos)).collect())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_c32_address_pages() {
+        let hash = hex::decode("a46ff88886c2ef9762d970b4d2c63678835bd39d").unwrap();
+        let address = c32_address(C32_ADDRESS_VERSION_MAINNET_SINGLESIG, &hash).unwrap();
+        assert_eq!(&address[..], b"SP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ7");
+
+        // leading zero bytes are kept
+        let zeros = [0u8; HASH160_LEN];
+        let address = c32_address(C32_ADDRESS_VERSION_TESTNET_SINGLESIG, &zeros).unwrap();
+        assert_eq!(&address[..], b"ST000000000000000000002AMW42H");
+
+        let c32 = C32Address::new(C32_ADDRESS_VERSION_MAINNET_SINGLESIG, &hash).unwrap();
+        let expected = b"Paid by SP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ7.contract";
+        let mut paged = std::vec::Vec::new();
+        let mut out = [0u8; 10];
+        let mut page_idx = 0;
+        loop {
+            let pages = c32
+                .page_between(b"Paid by ", &[b".", b"contract"], &mut out, page_idx)
+                .unwrap();
+            let len = out.iter().position(|v| *v == 0).unwrap();
+            paged.extend_from_slice(&out[..len]);
+            page_idx += 1;
+            if page_idx == pages {
+                break;
+            }
+        }
+        assert_eq!(&paged[..], &expected[..]);
+
+        // the head and tail of an abbreviated address
+        let mut out = [0u8; 40];
+        c32.page_styled(&[], &[b".", b"contract"], true, true, &mut out, 0)
+            .unwrap();
+        let len = out.iter().position(|v| *v == 0).unwrap();
+        assert_eq!(&out[..len], b"SP2J6ZY4...RV9EJ7.contract");
+
+        // in groups of 4 characters, the contract name is left as it is
+        let mut out = [0u8; 80];
+        c32.page_styled(&[], &[b".", b"contract"], false, true, &mut out, 0)
+            .unwrap();
+        let len = out.iter().position(|v| *v == 0).unwrap();
+        assert_eq!(
+            &out[..len],
+            &b"SP2J 6ZY4 8GV1 EZ5V 2V5R B9MP 66SW 86PY KKNR V9EJ 7.contract"[..]
+        );
+        let c32 = C32Address::new(C32_ADDRESS_VERSION_TESTNET_SINGLESIG, &zeros).unwrap();
+        c32.page_styled(&[], &[], false, true, &mut out, 0).unwrap();
+        let len = out.iter().position(|v| *v == 0).unwrap();
+        assert_eq!(&out[..len], b"ST00 0000 0000 0000 0000 002A MW42 H");
+        c32.page_full(&mut out, 0).unwrap();
+        let len = out.iter().position(|v| *v == 0).unwrap();
+        assert_eq!(&out[..len], b"ST000000000000000000002AMW42H");
+
+        assert!(C32Address::new(32, &hash).is_err());
+    }
+}
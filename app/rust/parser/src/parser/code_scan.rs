@@ -0,0 +1,249 @@
+use core::fmt::Write;
+
+use super::error::ParserError;
+use crate::lang::LABELS;
+use crate::zxformat;
+
+// What the code of a contract being deployed can do with assets
+pub const MOVES_STX: u8 = 0x01;
+pub const MOVES_FT: u8 = 0x02;
+pub const MOVES_NFT: u8 = 0x04;
+pub const ACTS_AS_CONTRACT: u8 = 0x08;
+
+// The expressions looked for, each with the capability it gives
+const EXPRESSIONS: [(&[u8], u8); 5] = [
+    (b"stx-transfer?", MOVES_STX),
+    (b"stx-transfer-memo?", MOVES_STX),
+    (b"ft-transfer?", MOVES_FT),
+    (b"nft-transfer?", MOVES_NFT),
+    (b"as-contract", ACTS_AS_CONTRACT),
+];
+
+// The longest expression and the byte before it
+const TAIL_LEN: usize = 19;
+
+/// A lexical scan of a code body for the expressions that move assets.
+/// It is fed the code in pieces, as it might not fit the transaction
+/// buffer, and keeps the last bytes of the previous piece so that an
+/// expression split between two of them is found. Comments and strings
+/// are scanned as well, an expression is never missed but might not
+/// be actually used.
+///
+/// Its bytes can be kept by the caller between pieces, all zeroes
+/// being a scan that was not fed anything yet
+#[repr(C)]
+#[derive(Clone, Copy, PartialEq, Default)]
+#[cfg_attr(test, derive(Debug))]
+pub struct CodeScan {
+    capabilities: u8,
+    len: u8,
+    tail: [u8; TAIL_LEN],
+}
+
+pub const CODE_SCAN_LEN: usize = core::mem::size_of::<CodeScan>();
+
+// Whether byte can be part of a Clarity name, so that ft-transfer?
+// is not taken from nft-transfer?
+fn is_name_byte(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || b"-_!?+<>=/*".contains(&byte)
+}
+
+impl CodeScan {
+    /// A scan that was not fed anything yet
+    pub const fn new() -> Self {
+        Self {
+            capabilities: 0,
+            len: 0,
+            tail: [0; TAIL_LEN],
+        }
+    }
+
+    pub fn update(&mut self, code: &[u8]) {
+        for byte in code.iter().copied() {
+            let len = (self.len as usize).min(TAIL_LEN);
+            if len < TAIL_LEN {
+                if let Some(slot) = self.tail.get_mut(len) {
+                    *slot = byte;
+                }
+                self.len = len as u8 + 1;
+            } else {
+                self.tail.rotate_left(1);
+                if let Some(last) = self.tail.last_mut() {
+                    *last = byte;
+                }
+            }
+
+            // every expression ends with one of these
+            if byte != b'?' && byte != b't' {
+                continue;
+            }
+            let window = self.tail.get(..self.len as usize).unwrap_or_default();
+            for (expression, capability) in EXPRESSIONS.iter() {
+                if !window.ends_with(expression) {
+                    continue;
+                }
+                let before = window.len() - expression.len();
+                let bounded = match before.checked_sub(1).and_then(|at| window.get(at)) {
+                    Some(byte) => !is_name_byte(*byte),
+                    None => true,
+                };
+                if bounded {
+                    self.capabilities |= capability;
+                }
+            }
+        }
+    }
+
+    pub fn capabilities(&self) -> u8 {
+        self.capabilities
+    }
+
+    /// Reads a scan kept by the caller as bytes
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ParserError> {
+        if bytes.len() != CODE_SCAN_LEN {
+            return Err(ParserError::parser_unexpected_buffer_end);
+        }
+        let (capabilities, rest) = bytes
+            .split_first()
+            .ok_or(ParserError::parser_unexpected_buffer_end)?;
+        let (len, rest) = rest
+            .split_first()
+            .ok_or(ParserError::parser_unexpected_buffer_end)?;
+        if *len as usize > TAIL_LEN {
+            return Err(ParserError::parser_value_out_of_range);
+        }
+        let mut tail = [0u8; TAIL_LEN];
+        tail.iter_mut()
+            .zip(rest.iter())
+            .for_each(|(dst, src)| *dst = *src);
+        Ok(Self {
+            capabilities: *capabilities,
+            len: *len,
+            tail,
+        })
+    }
+
+    /// Writes the scan as bytes, see from_bytes
+    pub fn write(&self, out: &mut [u8]) -> Result<(), ParserError> {
+        let out = out
+            .get_mut(..CODE_SCAN_LEN)
+            .ok_or(ParserError::parser_unexpected_buffer_end)?;
+        let head = [self.capabilities, self.len];
+        out.iter_mut()
+            .zip(head.iter().chain(self.tail.iter()))
+            .for_each(|(dst, src)| *dst = *src);
+        Ok(())
+    }
+}
+
+/// Writes the review summary of capabilities, "Can move STX and NFTs"
+pub fn page_capabilities(
+    capabilities: u8,
+    out_value: &mut [u8],
+    page_idx: u8,
+) -> Result<u8, ParserError> {
+    let assets = [
+        (MOVES_STX, LABELS.stx_assets),
+        (MOVES_FT, LABELS.ft_assets),
+        (MOVES_NFT, LABELS.nft_assets),
+    ];
+    let moved = assets.iter().filter(|(flag, _)| capabilities & flag != 0);
+    let num_moved = moved.clone().count();
+
+    let mut summary = [0u8; 100];
+    let mut writer = zxformat::Writer::new(&mut summary);
+    for (idx, (_, asset)) in moved.enumerate() {
+        let separator = match idx {
+            0 => LABELS.can_move,
+            idx if idx + 1 == num_moved => LABELS.and,
+            _ => ", ",
+        };
+        writer
+            .write_str(separator)
+            .and_then(|_| writer.write_str(asset))
+            .map_err(|_| ParserError::parser_unexpected_buffer_end)?;
+    }
+    if capabilities & ACTS_AS_CONTRACT != 0 {
+        let separator = if num_moved > 0 { ". " } else { "" };
+        writer
+            .write_str(separator)
+            .and_then(|_| writer.write_str(LABELS.acts_as_contract))
+            .map_err(|_| ParserError::parser_unexpected_buffer_end)?;
+    }
+
+    let len = summary
+        .iter()
+        .position(|b| *b == 0)
+        .unwrap_or(summary.len());
+    zxformat::pageString(out_value, summary.get(..len).unwrap_or_default(), page_idx)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::prelude::v1::*;
+
+    fn scan(pieces: &[&[u8]]) -> u8 {
+        let mut scan = CodeScan::default();
+        for piece in pieces {
+            scan.update(piece);
+        }
+        scan.capabilities()
+    }
+
+    #[test]
+    fn test_code_scan() {
+        let code = b"(define-public (pay (to principal)) (stx-transfer? u10 tx-sender to))";
+        assert_eq!(scan(&[code]), MOVES_STX);
+
+        // nft-transfer? is not ft-transfer?
+        assert_eq!(scan(&[b"(nft-transfer? nft u1 tx-sender to)"]), MOVES_NFT);
+        assert_eq!(scan(&[b"(ft-transfer? token u1 tx-sender to)"]), MOVES_FT);
+        assert_eq!(scan(&[b"(as-contract (get-balance))"]), ACTS_AS_CONTRACT);
+        assert_eq!(scan(&[b"(define-read-only (my-stx-transfer?) true)"]), 0);
+
+        // expressions split between pieces
+        assert_eq!(
+            scan(&[
+                b"(begin (stx-tr",
+                b"ansfer-m",
+                b"emo? u1 a b 0x00) (as-",
+                b"contract"
+            ]),
+            MOVES_STX | ACTS_AS_CONTRACT
+        );
+
+        // the bytes of a scan are kept between pieces
+        let mut first = CodeScan::default();
+        first.update(b"(nft-trans");
+        let mut bytes = [0u8; CODE_SCAN_LEN];
+        first.write(&mut bytes).unwrap();
+        let mut second = CodeScan::from_bytes(&bytes).unwrap();
+        second.update(b"fer? nft u1 a b)");
+        assert_eq!(second.capabilities(), MOVES_NFT);
+
+        bytes[1] = TAIL_LEN as u8 + 1;
+        assert!(CodeScan::from_bytes(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_capabilities_summary() {
+        let summary = |capabilities| {
+            let mut out = [0u8; 100];
+            page_capabilities(capabilities, &mut out, 0).unwrap();
+            let len = out.iter().position(|b| *b == 0).unwrap();
+            String::from_utf8(out[..len].to_vec()).unwrap()
+        };
+
+        assert_eq!(summary(MOVES_STX), "Can move STX");
+        assert_eq!(
+            summary(MOVES_STX | MOVES_FT),
+            "Can move STX and fungible tokens"
+        );
+        assert_eq!(
+            summary(MOVES_STX | MOVES_FT | MOVES_NFT | ACTS_AS_CONTRACT),
+            "Can move STX, fungible tokens and NFTs. Can act as the contract"
+        );
+        assert_eq!(summary(ACTS_AS_CONTRACT), "Can act as the contract");
+    }
+}
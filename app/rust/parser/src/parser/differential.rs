@@ -0,0 +1,143 @@
+//! Decoded fields for the differential tests in app/rust/differential.
+//!
+//! Those tests decode the same bytes with this parser and with the
+//! stacks-core codecs, which are too heavy a dependency for the tests of
+//! this crate. The parser types are private, so each function here decodes
+//! its input as the app does and returns the fields they compare as plain
+//! data.
+
+use std::vec::Vec;
+
+use super::transaction_payload::TransactionPayload;
+use super::{c32, Transaction, TransactionVersion, Value, MAX_DEPTH};
+use crate::hash::DefaultSha512_256;
+
+/// The fields of a payload
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Payload {
+    TokenTransfer {
+        amount: u64,
+        memo: Vec<u8>,
+        /// c32 address of a standard principal recipient
+        recipient: Option<Vec<u8>>,
+    },
+    SmartContract {
+        name: Vec<u8>,
+    },
+    ContractCall {
+        address: Vec<u8>,
+        contract_name: Vec<u8>,
+        function_name: Vec<u8>,
+        /// each argument as it is encoded
+        args: Vec<Vec<u8>>,
+    },
+}
+
+/// The fields of a parsed transaction
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Fields {
+    /// bytes the transaction takes, anything after them is previous signer data
+    pub len: usize,
+    pub mainnet: bool,
+    pub chain_id: u32,
+    pub anchor_mode: u8,
+    pub post_condition_mode: u8,
+    pub origin_nonce: u64,
+    pub origin_fee: u64,
+    /// c32 address of the origin
+    pub signer: Vec<u8>,
+    pub sponsor_nonce: Option<u64>,
+    pub sponsor_fee: Option<u64>,
+    /// each post condition as it is encoded
+    pub post_conditions: Vec<Vec<u8>>,
+    pub payload: Payload,
+    pub initial_sighash: [u8; 32],
+}
+
+/// The fields of a Clarity value
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ValueFields {
+    /// bytes the value takes
+    pub len: usize,
+    pub bytes: Vec<u8>,
+    pub int: Option<i128>,
+    pub uint: Option<u128>,
+    pub tuple_len: Option<usize>,
+}
+
+fn payload(tx: &Transaction) -> Option<Payload> {
+    let payload = &tx.payload;
+    let fields = match payload {
+        TransactionPayload::TokenTransfer(_) => Payload::TokenTransfer {
+            amount: payload.amount()?,
+            memo: payload.memo()?.to_vec(),
+            recipient: payload.recipient_address().map(|addr| addr.to_vec()),
+        },
+        TransactionPayload::SmartContract(contract) => Payload::SmartContract {
+            name: contract.contract_name().ok()?.name().to_vec(),
+        },
+        TransactionPayload::ContractCall(call) => {
+            let args = call.function_args().ok()?;
+            let args = (0..args.num_args().ok()? as usize)
+                .map(|idx| args.argument_at(idx).map(|arg| arg.bytes().to_vec()))
+                .collect::<Result<_, _>>()
+                .ok()?;
+            Payload::ContractCall {
+                address: payload.contract_address()?.to_vec(),
+                contract_name: call.contract_name().ok()?.name().to_vec(),
+                function_name: call.function_name().ok()?.to_vec(),
+                args,
+            }
+        }
+    };
+    Some(fields)
+}
+
+/// Parses a transaction, None if the parser rejects it
+pub fn transaction(bytes: &[u8]) -> Option<Fields> {
+    let tx = Transaction::from_bytes(bytes).ok()?;
+
+    let mut initial_sighash = [0u8; 32];
+    tx.initial_sighash::<DefaultSha512_256>(&mut initial_sighash)
+        .ok()?;
+
+    Some(Fields {
+        len: bytes.len() - tx.remainder.len(),
+        mainnet: tx.version == TransactionVersion::Mainnet,
+        chain_id: tx.chain_id,
+        anchor_mode: tx.transaction_modes[0],
+        post_condition_mode: tx.transaction_modes[1],
+        origin_nonce: tx.origin_nonce(),
+        origin_fee: tx.origin_fee(),
+        signer: tx
+            .transaction_auth
+            .origin()
+            .signer_address(tx.version)
+            .ok()?
+            .to_vec(),
+        sponsor_nonce: tx.sponsor_nonce(),
+        sponsor_fee: tx.sponsor_fee(),
+        post_conditions: tx.post_conditions.iter().map(|pc| pc.to_vec()).collect(),
+        payload: payload(&tx)?,
+        initial_sighash,
+    })
+}
+
+/// Parses a Clarity value, None if the parser rejects it
+pub fn clarity_value(bytes: &[u8]) -> Option<ValueFields> {
+    let (rem, value) = Value::from_bytes::<MAX_DEPTH>(bytes).ok()?;
+    Some(ValueFields {
+        len: bytes.len() - rem.len(),
+        bytes: value.bytes().to_vec(),
+        int: value.int(),
+        uint: value.uint(),
+        tuple_len: value.tuple().and_then(|tuple| tuple.num_elements().ok()),
+    })
+}
+
+/// The c32 address of a hash160, None if the version is out of range
+pub fn c32_address(version: u8, hash: &[u8; 20]) -> Option<Vec<u8>> {
+    c32::c32_address(version, hash)
+        .ok()
+        .map(|addr| addr.to_vec())
+}
@@ -0,0 +1,367 @@
+//! Display and serde::Serialize of the parsed types, for host builds with
+//! the std feature.
+//!
+//! Transactions, payloads and post conditions are rendered as the items the
+//! device shows, with the pages of each item joined back together, so a dump
+//! reads the same as the review on screen. Clarity values are rendered in
+//! Clarity syntax.
+
+use core::fmt;
+use std::prelude::v1::*;
+
+use serde::ser::{Error as _, SerializeSeq, SerializeStruct};
+use serde::{Serialize, Serializer};
+
+use super::transaction_payload::TransactionPayload;
+use super::{
+    ParseDiagnostic, ParseSection, ParserError, PrincipalData, Scratch, Transaction,
+    TransactionVersion, Value, ValueId, MAX_DEPTH,
+};
+use crate::parser::reader::be_u32;
+use crate::parser::transaction::PostConditions;
+
+// large enough for most values to fit in a single page
+const KEY_LEN: usize = 64;
+const VALUE_LEN: usize = 256;
+
+/// An item as shown on the device
+#[derive(Clone, PartialEq, Debug, Serialize)]
+pub struct Item {
+    pub title: String,
+    pub value: String,
+}
+
+impl fmt::Display for Item {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.title, self.value)
+    }
+}
+
+// the bytes written to out up to the null terminator
+fn c_str(out: &[u8]) -> String {
+    let len = out.iter().position(|b| *b == 0).unwrap_or(out.len());
+    String::from_utf8_lossy(&out[..len]).into_owned()
+}
+
+// Reads every page of an item, get_page returns the number of pages
+fn page_item(
+    mut get_page: impl FnMut(&mut [u8], &mut [u8], u8) -> Result<u8, ParserError>,
+) -> Result<Item, ParserError> {
+    let mut key = [0u8; KEY_LEN];
+    let mut value = [0u8; VALUE_LEN];
+    let mut item = Item {
+        title: String::new(),
+        value: String::new(),
+    };
+
+    let mut page_idx = 0;
+    loop {
+        key.iter_mut().for_each(|b| *b = 0);
+        value.iter_mut().for_each(|b| *b = 0);
+        let pages = get_page(&mut key, &mut value, page_idx)?;
+        if page_idx == 0 {
+            item.title = c_str(&key);
+        }
+        item.value.push_str(&c_str(&value));
+
+        page_idx += 1;
+        if page_idx >= pages {
+            return Ok(item);
+        }
+    }
+}
+
+impl<'a> Transaction<'a> {
+    /// The items of the review, in the order they are shown
+    pub fn items(&self) -> Result<Vec<Item>, ParserError> {
+        let mut tx = self.clone();
+        let mut scratch = Scratch::new();
+        let mut items = Vec::new();
+
+        for idx in 0..tx.num_items()? {
+            items.push(page_item(|key, value, page| {
+                scratch.reset();
+                tx.get_item(idx, key, value, page, &scratch)
+            })?);
+        }
+        Ok(items)
+    }
+}
+
+impl<'a> TransactionPayload<'a> {
+    /// The items of the payload, in the order they are shown
+    pub fn items(&self) -> Result<Vec<Item>, ParserError> {
+        let num_items = self.num_items()?;
+        let mut scratch = Scratch::new();
+
+        (0..num_items)
+            .map(|idx| {
+                page_item(|key, value, page| {
+                    scratch.reset();
+                    self.get_items(idx, key, value, page, num_items, &scratch)
+                })
+            })
+            .collect()
+    }
+}
+
+impl<'a> PostConditions<'a> {
+    /// The items of all the post conditions, in the order they are shown
+    pub fn items(&self) -> Result<Vec<Item>, ParserError> {
+        (0..self.num_items())
+            .map(|idx| page_item(|key, value, page| self.get_items(idx, key, value, page)))
+            .collect()
+    }
+}
+
+impl fmt::Display for ParseDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let section = match self.section {
+            ParseSection::Header => "header",
+            ParseSection::Auth => "auth",
+            ParseSection::PostConditions => "post conditions",
+            ParseSection::Payload => "payload",
+        };
+        write!(
+            f,
+            "{:?} in the {} at byte {}",
+            self.error, section, self.offset
+        )
+    }
+}
+
+fn fmt_items(items: Result<Vec<Item>, ParserError>, f: &mut fmt::Formatter) -> fmt::Result {
+    for item in items.map_err(|_| fmt::Error)? {
+        writeln!(f, "{}", item)?;
+    }
+    Ok(())
+}
+
+fn serialize_items<S: Serializer>(
+    items: Result<Vec<Item>, ParserError>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let items = items.map_err(|e| S::Error::custom(format!("{:?}", e)))?;
+    let mut seq = serializer.serialize_seq(Some(items.len()))?;
+    for item in items.iter() {
+        seq.serialize_element(item)?;
+    }
+    seq.end()
+}
+
+impl<'a> fmt::Display for Transaction<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_items(self.items(), f)
+    }
+}
+
+impl<'a> fmt::Display for TransactionPayload<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_items(self.items(), f)
+    }
+}
+
+impl<'a> fmt::Display for PostConditions<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_items(self.items(), f)
+    }
+}
+
+impl<'a> Serialize for Transaction<'a> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let items = self
+            .items()
+            .map_err(|e| S::Error::custom(format!("{:?}", e)))?;
+        let network = match self.version {
+            TransactionVersion::Mainnet => "mainnet",
+            TransactionVersion::Testnet => "testnet",
+        };
+
+        let mut tx = serializer.serialize_struct("Transaction", 5)?;
+        tx.serialize_field("network", network)?;
+        tx.serialize_field("chain_id", &self.chain_id)?;
+        tx.serialize_field("sponsored", &!self.transaction_auth.is_standard_auth())?;
+        tx.serialize_field("multisig", &self.is_multisig())?;
+        tx.serialize_field("items", &items)?;
+        tx.end()
+    }
+}
+
+impl<'a> Serialize for TransactionPayload<'a> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_items(self.items(), serializer)
+    }
+}
+
+impl<'a> Serialize for PostConditions<'a> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_items(self.items(), serializer)
+    }
+}
+
+// Clarity string literal, with the escapes Clarity accepts
+fn fmt_string(prefix: &str, s: &str, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{}\"", prefix)?;
+    for c in s.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\t' => f.write_str("\\t")?,
+            '\r' => f.write_str("\\r")?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+    f.write_str("\"")
+}
+
+fn fmt_principal(principal: &PrincipalData, f: &mut fmt::Formatter) -> fmt::Result {
+    let address = principal.c32_address().map_err(|_| fmt::Error)?;
+    f.write_str("'")?;
+    for pos in 0..address.len() {
+        write!(f, "{}", address.char_at(pos) as char)?;
+    }
+    if let Some(name) = principal.contract_name() {
+        write!(f, ".{}", String::from_utf8_lossy(name.name()))?;
+    }
+    Ok(())
+}
+
+impl<'a> fmt::Display for Value<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let payload = self.payload();
+        let id = self.value_id().map_err(|_| fmt::Error)?;
+        match id {
+            ValueId::Int => write!(f, "{}", self.int().ok_or(fmt::Error)?),
+            ValueId::UInt => write!(f, "u{}", self.uint().ok_or(fmt::Error)?),
+            ValueId::BoolTrue => f.write_str("true"),
+            ValueId::BoolFalse => f.write_str("false"),
+            ValueId::OptionalNone => f.write_str("none"),
+            ValueId::OptionalSome | ValueId::ResponseOk | ValueId::ResponseErr => {
+                let wrapper = match id {
+                    ValueId::OptionalSome => "some",
+                    ValueId::ResponseOk => "ok",
+                    _ => "err",
+                };
+                let (_, inner) = Value::from_bytes::<MAX_DEPTH>(payload).map_err(|_| fmt::Error)?;
+                write!(f, "({} {})", wrapper, inner)
+            }
+            ValueId::Buffer => write!(f, "0x{}", hex::encode(&payload[4..])),
+            ValueId::StandardPrincipal => {
+                let (_, principal) =
+                    PrincipalData::standard_from_bytes(payload).map_err(|_| fmt::Error)?;
+                fmt_principal(&principal, f)
+            }
+            ValueId::ContractPrincipal => {
+                let (_, principal) = PrincipalData::contract_principal_from_bytes(payload)
+                    .map_err(|_| fmt::Error)?;
+                fmt_principal(&principal, f)
+            }
+            ValueId::List => {
+                let (mut rem, num_items) = be_u32(payload).map_err(|_| fmt::Error)?;
+                f.write_str("(list")?;
+                for _ in 0..num_items {
+                    let (next, item) =
+                        Value::from_bytes::<MAX_DEPTH>(rem).map_err(|_| fmt::Error)?;
+                    write!(f, " {}", item)?;
+                    rem = next;
+                }
+                f.write_str(")")
+            }
+            ValueId::Tuple => {
+                let tuple = self.tuple().ok_or(fmt::Error)?;
+                f.write_str("(tuple")?;
+                for (name, value) in tuple.iter() {
+                    write!(f, " ({} {})", String::from_utf8_lossy(name.name()), value)?;
+                }
+                f.write_str(")")
+            }
+            ValueId::StringAscii => fmt_string("", &String::from_utf8_lossy(&payload[4..]), f),
+            ValueId::StringUtf8 => fmt_string("u", &String::from_utf8_lossy(&payload[4..]), f),
+        }
+    }
+}
+
+impl<'a> Serialize for Value<'a> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_clarity_value_display() {
+        let cases = [
+            ("0100000000000000000000000000000064", "u100"),
+            ("00ffffffffffffffffffffffffffffffff", "-1"),
+            (
+                "0a0a0a0100000000000000000000000000000001",
+                "(some (some (some u1)))",
+            ),
+            ("080200000000", "(err 0x)"),
+            ("020000000400deadbe", "0x00deadbe"),
+            ("0d00000008736f6d6520227122", "\"some \\\"q\\\"\""),
+            ("0e00000003e282ac", "u\"\u{20ac}\""),
+            ("0b000000020309", "(list true none)"),
+            (
+                "0c0000000201610000000000000000000000000000000001016303",
+                "(tuple (a 1) (c true))",
+            ),
+            (
+                "0616a5d9d331000f5b79578ce56bd157f29a9056f0d60b6d792d636f6e7472616374",
+                "'SP2JXKMSH007NPYAQHKJPQMAQYAD90NQGTVJVQ02B.my-contract",
+            ),
+        ];
+
+        for (encoded, expected) in cases.iter() {
+            let bytes = hex::decode(encoded).unwrap();
+            let (_, value) = Value::from_bytes::<MAX_DEPTH>(&bytes).unwrap();
+            assert_eq!(value.to_string(), *expected);
+            assert_eq!(
+                serde_json::to_string(&value).unwrap(),
+                serde_json::to_string(expected).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_transaction_json() {
+        let input_path = {
+            let mut r = PathBuf::new();
+            r.push(env!("CARGO_MANIFEST_DIR"));
+            r.push("tests");
+            r.push("stx_token_transfer");
+            r.set_extension("json");
+            r
+        };
+        let str = std::fs::read_to_string(input_path).expect("Error opening json file");
+        let json: serde_json::Value = serde_json::from_str(&str).unwrap();
+        let bytes = hex::decode(json["raw"].as_str().unwrap()).unwrap();
+        let tx = Transaction::from_bytes(&bytes).unwrap();
+
+        let dump = serde_json::to_value(&tx).unwrap();
+        assert_eq!(dump["network"], "mainnet");
+        assert_eq!(dump["sponsored"], false);
+
+        // every item as shown on the device, pages joined
+        let items = tx.items().unwrap();
+        assert_eq!(dump["items"].as_array().unwrap().len(), items.len());
+        assert!(items
+            .iter()
+            .any(|item| item.value == json["recipient"].as_str().unwrap()));
+        assert!(items
+            .iter()
+            .any(|item| item.value == json["sender"].as_str().unwrap()));
+
+        let text = tx.to_string();
+        assert_eq!(text.lines().count(), items.len());
+        assert_eq!(
+            tx.payload.items().unwrap().len(),
+            tx.payload.num_items().unwrap() as usize
+        );
+    }
+}
@@ -60,6 +60,15 @@ pub enum ParserError {
     parser_crypto_error,
     parser_invalid_token_transfer_principal,
     parser_recursion_limit,
+    parser_signer_mismatch,
+    parser_network_mismatch,
+    parser_denylisted_contract,
+    parser_limit_exceeded,
+    parser_invalid_path,
+    parser_trailing_bytes,
+    parser_too_many_signatures,
+    parser_fee_too_high,
+    parser_invalid_auth_challenge,
 }
 
 impl From<ErrorKind> for ParserError {
@@ -0,0 +1,251 @@
+//! Entry points for the cargo-fuzz targets in fuzz/.
+//!
+//! Most of the parser types are private to this crate, these functions run
+//! each of them over arbitrary bytes the same way the app does: parse, then
+//! format every item page by page. Any panic is a bug, parsing errors are not.
+//!
+//! Each one returns the Outcome of the input, which the corpus tool in
+//! app/rust/corpus uses to minimize a corpus, and the crashes it turns into
+//! fixtures are run through them again by the regressions tests.
+
+use super::c32::{self, C32Address};
+use super::post_condition::TransactionPostCondition;
+use super::spending_condition::TransactionSpendingCondition;
+use super::transaction::PostConditions;
+use super::{ParsedObj, ParserError, TransactionVersion, Value, HASH160_LEN, MAX_DEPTH};
+use crate::signature::ecdsa::{
+    self, DefaultSecp256k1, Secp256k1, DIGEST_LEN, SECRET_KEY_LEN, UNCOMPRESSED_PUBKEY_LEN,
+};
+use crate::signature::{is_canonical, MAX_DER_LEN, SCALAR_LEN};
+use crate::zxformat;
+use core::convert::TryInto;
+
+// same sizes as the C side buffers
+const KEY_LEN: usize = 40;
+const VALUE_LEN: usize = 40;
+
+/// What a target did with an input: the errors, counts and lengths it saw,
+/// hashed together. Inputs with the same outcome took the same paths as far
+/// as the target can tell
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+pub struct Outcome(u64);
+
+impl Outcome {
+    // FNV-1a offset basis and prime
+    const START: Outcome = Outcome(0xcbf2_9ce4_8422_2325);
+    const PRIME: u64 = 0x0100_0000_01b3;
+
+    fn with(self, value: u64) -> Self {
+        let hash = value.to_le_bytes().iter().fold(self.0, |hash, b| {
+            (hash ^ *b as u64).wrapping_mul(Self::PRIME)
+        });
+        Outcome(hash)
+    }
+
+    fn error(self, e: ParserError) -> Self {
+        self.with(1).with(e as u64)
+    }
+}
+
+/// Runs a fuzz target over an input
+pub type Target = fn(&[u8]) -> Outcome;
+
+/// The fuzz targets in fuzz/fuzz_targets, by name
+pub const TARGETS: [(&str, Target); 6] = [
+    ("transaction", transaction),
+    ("spending_condition", spending_condition),
+    ("post_conditions", post_conditions),
+    ("clarity_value", clarity_value),
+    ("c32_address", c32_address),
+    ("ecdsa_sign", ecdsa_sign),
+];
+
+pub fn target(name: &str) -> Option<Target> {
+    TARGETS
+        .iter()
+        .find(|(target, _)| *target == name)
+        .map(|(_, run)| *run)
+}
+
+// Pages through every item, returns the number of items shown
+fn page_all(
+    num_items: u8,
+    mut get_item: impl FnMut(u8, &mut [u8], &mut [u8], u8) -> Result<u8, ParserError>,
+) -> u8 {
+    let mut key = [0u8; KEY_LEN];
+    let mut value = [0u8; VALUE_LEN];
+    let mut shown = 0;
+
+    for display_idx in 0..num_items {
+        let mut page_idx = 0;
+        while let Ok(pages) = get_item(display_idx, &mut key, &mut value, page_idx) {
+            page_idx += 1;
+            if page_idx >= pages {
+                shown += 1;
+                break;
+            }
+        }
+    }
+    shown
+}
+
+/// Parses a transaction, message or JWT and formats all of its items
+pub fn transaction(data: &[u8]) -> Outcome {
+    let outcome = Outcome::START;
+    let mut obj = match ParsedObj::from_bytes(data) {
+        Ok(obj) => obj,
+        Err(e) => return outcome.error(e),
+    };
+    let outcome = outcome.with(obj.get_type() as u64);
+    if let Err(e) = obj.read(data) {
+        return outcome.error(e);
+    }
+    let num_items = match obj.num_items() {
+        Ok(num) => num,
+        Err(e) => return outcome.error(e),
+    };
+
+    let shown = page_all(num_items, |idx, key, value, page| {
+        obj.get_item(idx, key, value, page)
+    });
+    let mut outcome = outcome.with(num_items as u64).with(shown as u64);
+    for idx in 0..num_items {
+        if let Ok(kind) = obj.item_kind(idx) {
+            outcome = outcome.with(kind as u64);
+        }
+        let _ = obj.item_section(idx);
+    }
+    outcome
+}
+
+/// Parses a spending condition and renders its signer, fee and nonce
+pub fn spending_condition(data: &[u8]) -> Outcome {
+    let outcome = Outcome::START;
+    let (rem, condition) = match TransactionSpendingCondition::from_bytes(data) {
+        Ok(res) => res,
+        Err(e) => return outcome.error(e.into()),
+    };
+
+    for chain in [TransactionVersion::Mainnet, TransactionVersion::Testnet] {
+        let _ = condition.signer_address(chain);
+    }
+    let mut out = [0u8; zxformat::MAX_STR_BUFF_LEN];
+    let _ = condition.nonce_str(&mut out);
+    let _ = condition.fee_str(&mut out);
+    let signatures = condition.num_signatures();
+    let required = condition.required_signatures();
+
+    let mut buf = [0u8; 256];
+    let _ = condition.init_sighash(&mut buf);
+
+    outcome
+        .with(rem.len() as u64)
+        .with(signatures.map_or(0, |n| n as u64 + 1))
+        .with(required.map_or(0, |n| n as u64 + 1))
+}
+
+/// Parses a list of post conditions and formats all of their items
+pub fn post_conditions(data: &[u8]) -> Outcome {
+    let outcome = Outcome::START;
+    let (rem, conditions) = match PostConditions::from_bytes(data) {
+        Ok(res) => res,
+        Err(e) => return outcome.error(e.into()),
+    };
+
+    // every condition found while parsing can be parsed again on its own
+    for raw in conditions.iter() {
+        assert!(TransactionPostCondition::from_bytes(raw).is_ok());
+    }
+
+    let num_items = conditions.num_items();
+    let shown = page_all(num_items, |idx, key, value, page| {
+        conditions.get_items(idx, key, value, page)
+    });
+    outcome
+        .with(rem.len() as u64)
+        .with(num_items as u64)
+        .with(shown as u64)
+}
+
+/// Parses a Clarity value, checking its length agrees with the parsed bytes
+pub fn clarity_value(data: &[u8]) -> Outcome {
+    let outcome = Outcome::START;
+    let (rem, value) = match Value::from_bytes::<MAX_DEPTH>(data) {
+        Ok(res) => res,
+        Err(e) => return outcome.error(e.into()),
+    };
+    assert_eq!(value.bytes().len() + rem.len(), data.len());
+
+    let _ = value.uint();
+    let _ = value.int();
+    let _ = value.string_ascii();
+    let _ = value.string_utf8();
+    if let Some(tuple) = value.tuple() {
+        assert_eq!(Ok(tuple.iter().count()), tuple.num_elements());
+    }
+    outcome
+        .with(value.value_id().map_or(0, |id| id as u64 + 1))
+        .with(value.bytes().len() as u64)
+}
+
+/// Encodes a version byte and a hash160 as a c32 address, checking that
+/// paging it out gives the same characters as encoding it at once
+pub fn c32_address(data: &[u8]) -> Outcome {
+    let outcome = Outcome::START;
+    let (version, hash) = match data.split_first() {
+        Some((version, hash)) if hash.len() >= HASH160_LEN => (*version, &hash[..HASH160_LEN]),
+        _ => return outcome,
+    };
+
+    let encoded = match c32::c32_address(version, hash) {
+        Ok(encoded) => encoded,
+        Err(e) => {
+            assert!(C32Address::new(version, hash).is_err());
+            return outcome.error(e);
+        }
+    };
+
+    let address = C32Address::new(version, hash).unwrap();
+    let mut page = [0u8; VALUE_LEN];
+    let mut paged = arrayvec::ArrayVec::<[u8; 64]>::new();
+    let mut page_idx = 0;
+    loop {
+        let pages = address.page(&mut page, page_idx).unwrap();
+        let len = page.iter().position(|v| *v == 0).unwrap_or(page.len());
+        paged.try_extend_from_slice(&page[..len]).unwrap();
+        page_idx += 1;
+        if page_idx >= pages {
+            break;
+        }
+    }
+    assert_eq!(&paged[..], &encoded[..]);
+    outcome.with(version as u64).with(encoded.len() as u64)
+}
+
+/// Signs a digest with a secret key the way the device does, any valid
+/// key must give a signature that passes the checks of signature::ecdsa
+pub fn ecdsa_sign(data: &[u8]) -> Outcome {
+    let outcome = Outcome::START;
+    let secret: &[u8; SECRET_KEY_LEN] = match data.get(..SECRET_KEY_LEN) {
+        Some(secret) => secret.try_into().unwrap(),
+        None => return outcome,
+    };
+    let digest: &[u8; DIGEST_LEN] = match data.get(SECRET_KEY_LEN..SECRET_KEY_LEN + DIGEST_LEN) {
+        Some(digest) => digest.try_into().unwrap(),
+        None => return outcome,
+    };
+
+    let mut public_key = [0u8; UNCOMPRESSED_PUBKEY_LEN];
+    let valid = DefaultSecp256k1::public_key(secret, &mut public_key);
+
+    let mut r = [0u8; SCALAR_LEN];
+    let mut s = [0u8; SCALAR_LEN];
+    let mut v = 0u8;
+    let mut der = [0u8; MAX_DER_LEN];
+    let signed = ecdsa::sign::<DefaultSecp256k1>(secret, digest, &mut r, &mut s, &mut v, &mut der);
+    assert_eq!(signed.is_some(), valid);
+    if let Some(len) = signed {
+        assert!(is_canonical(&r, &s, v, &der[..len]));
+    }
+    outcome.with(valid as u64)
+}
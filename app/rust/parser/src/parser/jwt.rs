@@ -1,8 +1,10 @@
 #![allow(clippy::missing_safety_doc)]
 use super::error::ParserError;
+use crate::lang::LABELS;
 use crate::zxformat::{pageString, Writer};
 
-use crate::bolos::{sha256, SHA256_LEN};
+use crate::bolos::SHA256_LEN;
+use crate::hash::{DefaultSha256, Sha256};
 use core::{fmt::Write, str};
 use hex::encode_to_slice;
 
@@ -57,7 +59,10 @@ impl<'a> JwtHeader<'a> {
         let mut header_bytes = [0u8; MAX_BASE64_HEADER_LEN];
 
         let len = decode_data(data, header_bytes.as_mut())?;
-        let header: Header = serde_json_core::from_slice(&header_bytes[..len])
+        let header_bytes = header_bytes
+            .get(..len)
+            .ok_or(ParserError::parser_unexpected_buffer_end)?;
+        let header: Header = serde_json_core::from_slice(header_bytes)
             .map(|(h, _)| h)
             .map_err(|_| ParserError::parser_invalid_jwt)?;
 
@@ -105,8 +110,7 @@ impl<'a> Jwt<'a> {
     }
 
     pub fn get_hash(&self, output: &mut [u8; SHA256_LEN]) {
-        // wont panic as output is the right len
-        sha256(self.jwt_data, output.as_mut()).unwrap()
+        DefaultSha256::digest(self.jwt_data, output)
     }
 
     pub fn num_items(&self) -> u8 {
@@ -128,7 +132,7 @@ impl<'a> Jwt<'a> {
         let mut writer_key = Writer::new(out_key);
 
         writer_key
-            .write_str("JWT hash:")
+            .write_str(LABELS.jwt_hash)
             .map_err(|_| ParserError::parser_unexpected_buffer_end)?;
 
         let mut out_data = [0u8; SHA256_LEN];
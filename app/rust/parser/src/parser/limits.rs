@@ -0,0 +1,83 @@
+//! Limits on what the parser accepts, in one place.
+//!
+//! The post conditions and contract call arguments are indexed in fixed
+//! size arrays kept in the parser state, and clarity values are parsed
+//! recursively on the stack, so going past any of these is refused with
+//! `parser_limit_exceeded` or `parser_recursion_limit` up front. The
+//! defaults depend on the device profile, the `nanos` feature selects the
+//! ones of the device with the least memory.
+
+use super::error::ParserError;
+
+// Nesting depth of the clarity values in contract call arguments and post
+// conditions, lower than for messages as the ram usage there is higher
+pub const TX_DEPTH_LIMIT: u8 = 3;
+
+// Nesting depth of the clarity values in a structured message
+#[cfg(not(feature = "nanos"))]
+pub const MAX_DEPTH: u8 = 20;
+#[cfg(feature = "nanos")]
+pub const MAX_DEPTH: u8 = 10;
+
+// Post conditions in a transaction
+pub const NUM_SUPPORTED_POST_CONDITIONS: usize = 16;
+
+// Distinct principals of the post conditions whose
+// encoded address is kept while they are reviewed
+#[cfg(not(feature = "nanos"))]
+pub const NUM_INTERNED_PRINCIPALS: usize = 8;
+#[cfg(feature = "nanos")]
+pub const NUM_INTERNED_PRINCIPALS: usize = 4;
+
+// Contract call arguments shown outside of expert mode,
+// only the offsets of this many are cached
+pub const MAX_NUM_ARGS: u32 = 10;
+
+// The fee a spending condition can set in uSTX, the 1.818 billion STX
+// there will ever be. Above it the host most likely got the unit of the
+// fee wrong, so the transaction is refused rather than reviewed
+pub const MAX_FEE: u64 = 1_818_000_000 * 1_000_000;
+
+// Review items of a transaction, the C side takes
+// the index of the item to display as an int8_t
+pub const MAX_NUM_ITEMS: u8 = i8::MAX as u8;
+
+/// Returns the number of items as long as it is within the limit
+pub fn check_num_items(num_items: u8) -> Result<u8, ParserError> {
+    if num_items > MAX_NUM_ITEMS {
+        return Err(ParserError::parser_limit_exceeded);
+    }
+    Ok(num_items)
+}
+
+/// Maps the error of a section to a generic one,
+/// except when it was refused for going past a limit
+pub fn or_limit(fallback: ParserError) -> impl Fn(nom::Err<ParserError>) -> ParserError {
+    move |e| match ParserError::from(e) {
+        e @ ParserError::parser_limit_exceeded | e @ ParserError::parser_recursion_limit => e,
+        _ => fallback,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_check_num_items() {
+        assert_eq!(check_num_items(MAX_NUM_ITEMS), Ok(MAX_NUM_ITEMS));
+        assert_eq!(
+            check_num_items(MAX_NUM_ITEMS + 1),
+            Err(ParserError::parser_limit_exceeded)
+        );
+    }
+
+    #[test]
+    fn test_or_limit() {
+        let fallback = ParserError::parser_invalid_transaction_payload;
+        let limit = nom::Err::Error(ParserError::parser_limit_exceeded);
+        let other = nom::Err::Error(ParserError::parser_unexpected_value);
+        assert_eq!(or_limit(fallback)(limit), ParserError::parser_limit_exceeded);
+        assert_eq!(or_limit(fallback)(other), fallback);
+    }
+}
@@ -1,8 +1,9 @@
 #![allow(clippy::missing_safety_doc)]
+use super::reader::take;
 use super::{error::ParserError, read_varint};
+use crate::lang::LABELS;
 use crate::zxformat::{pageString, Writer};
 use core::fmt::Write;
-use nom::bytes::complete::take;
 
 // The lenght of \x17Stacks Signed Message:
 const BYTE_STRING_HEADER_LEN: usize = "\x17Stacks Signed Message:\n".as_bytes().len();
@@ -68,8 +69,8 @@ impl<'a> ByteString<'a> {
         let (rem, len) =
             read_varint(data).map_err(|_| ParserError::parser_invalid_bytestr_message)?;
 
-        let (_, message_content) = take::<_, _, ParserError>(len as usize)(rem)
-            .map_err(|_| ParserError::parser_invalid_bytestr_message)?;
+        let (_, message_content) =
+            take(len as usize)(rem).map_err(|_| ParserError::parser_invalid_bytestr_message)?;
 
         if !message_content.is_ascii() {
             return Err(ParserError::parser_invalid_bytestr_message);
@@ -82,7 +83,10 @@ impl<'a> ByteString<'a> {
         if !Self::contain_header(data) {
             return Err(ParserError::parser_invalid_bytestr_message);
         }
-        let message = Self::get_msg(&data[BYTE_STRING_HEADER_LEN..])?;
+        let message = data
+            .get(BYTE_STRING_HEADER_LEN..)
+            .ok_or(ParserError::parser_invalid_bytestr_message)?;
+        let message = Self::get_msg(message)?;
         Ok(Self(message))
     }
 
@@ -137,7 +141,7 @@ impl<'a> ByteString<'a> {
         }
 
         writer_key
-            .write_str("Sign Message")
+            .write_str(LABELS.sign_message)
             .map_err(|_| ParserError::parser_unexpected_buffer_end)?;
 
         pageString(out_value, &msg[..copy_len], page_idx)
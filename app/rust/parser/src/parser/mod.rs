@@ -0,0 +1,75 @@
+mod abi;
+mod amount;
+mod auth_challenge;
+pub mod c32;
+mod code_scan;
+#[cfg(any(test, feature = "differential"))]
+pub mod differential;
+#[cfg(any(test, feature = "std"))]
+mod display;
+mod error;
+#[cfg(any(test, fuzzing, feature = "fuzzing"))]
+pub mod fuzzing;
+mod jwt;
+mod limits;
+mod message;
+mod parsed_obj;
+mod parser_common;
+mod post_condition;
+mod principal;
+mod recognizers;
+pub(crate) mod reader;
+#[cfg(test)]
+mod regressions;
+#[cfg(test)]
+mod robustness;
+#[cfg(test)]
+mod roundtrip;
+mod scratch;
+mod sighash_stream;
+// the snapshots are taken with every recognizer enabled
+#[cfg(all(
+    test,
+    feature = "pox",
+    feature = "admin-functions",
+    feature = "citycoins",
+    feature = "arkadiko",
+    feature = "alex",
+    feature = "stacking-dao",
+    feature = "nft-marketplace"
+))]
+mod snapshots;
+mod spending_condition;
+mod structured_msg;
+mod token_info;
+mod transaction;
+mod transaction_auth;
+mod transaction_payload;
+mod utils;
+mod value;
+#[cfg(test)]
+mod vectors;
+pub use abi::FunctionAbi;
+pub use amount::UstxTotal;
+pub use auth_challenge::AuthChallenge;
+pub use code_scan::{CodeScan, CODE_SCAN_LEN};
+#[cfg(any(test, feature = "std"))]
+pub use display::Item;
+pub use error::ParserError;
+pub use jwt::Jwt;
+pub use limits::*;
+pub use message::{ByteString, Message};
+pub use parsed_obj::{ParsedObj, Tag};
+pub use parser_common::*;
+pub use post_condition::{FungibleConditionCode, TransactionPostCondition};
+pub use principal::*;
+pub use scratch::Scratch;
+pub use sighash_stream::SighashStream;
+pub use spending_condition::{signature_auth_field, SIGNATURE_AUTH_FIELD_LEN};
+pub use structured_msg::{Domain, StructuredMsg};
+pub use token_info::TokenInfo;
+pub use transaction::{Transaction, AUTH_DATA_LEN};
+pub use transaction_auth::TransactionAuth;
+pub use transaction_payload::TransactionPayloadId;
+pub use utils::*;
+pub use value::{Int128, Tuple, UInt128, Value, ValueId};
@@ -0,0 +1,2348 @@
+#![allow(non_camel_case_types, non_snake_case, clippy::missing_safety_doc)]
+
+use super::{error::ParserError, transaction::Transaction, Message};
+use super::{AuthChallenge, ItemKind, ItemSection, Jwt, ParseDiagnostic, Scratch, StructuredMsg};
+
+use crate::stack::{self, EntryPoint};
+use core::mem::ManuallyDrop;
+
+#[repr(u8)]
+#[derive(Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(test, derive(Debug))]
+pub enum Tag {
+    Transaction,
+    Message,
+    Jwt,
+    StructuredMsg,
+    AuthChallenge,
+    Invalid,
+}
+
+// safety: this is memory allocated in C and last the application lifetime
+// and is initialized once which means that once the object is initialized with an especific
+// union variant such variant wont be changed.
+#[repr(C)]
+pub union Obj<'a> {
+    tx: ManuallyDrop<Transaction<'a>>,
+    msg: ManuallyDrop<Message<'a>>,
+    structured_msg: ManuallyDrop<StructuredMsg<'a>>,
+    jwt: ManuallyDrop<Jwt<'a>>,
+    auth: ManuallyDrop<AuthChallenge<'a>>,
+}
+
+#[repr(C)]
+pub struct ParsedObj<'a> {
+    tag: Tag,
+    obj: Obj<'a>,
+    // where transaction items are formatted
+    scratch: Scratch,
+}
+
+impl<'a> ParsedObj<'a> {
+    pub fn from_bytes(data: &'a [u8]) -> Result<Self, ParserError> {
+        if data.is_empty() {
+            return Err(ParserError::parser_no_data);
+        }
+        // we expect a transaction
+        let tag;
+
+        if Message::is_message(data) {
+            tag = Tag::Message;
+        } else if Jwt::is_jwt(data) {
+            tag = Tag::Jwt;
+        } else if StructuredMsg::is_msg(data) {
+            tag = Tag::StructuredMsg;
+        } else if AuthChallenge::is_auth(data) {
+            tag = Tag::AuthChallenge;
+        } else {
+            tag = Tag::Transaction;
+        }
+
+        let obj = Obj::from_bytes(data, tag)?;
+        Ok(Self {
+            tag,
+            obj,
+            scratch: Scratch::new(),
+        })
+    }
+
+    pub fn read(&mut self, data: &'a [u8]) -> Result<(), ParserError> {
+        let _stack = stack::enter(EntryPoint::Read);
+        if data.is_empty() {
+            return Err(ParserError::parser_no_data);
+        }
+
+        // we expect a transaction
+        self.tag = Tag::Invalid;
+
+        unsafe {
+            if Message::is_message(data) {
+                self.tag = Tag::Message;
+                self.obj.read_msg(data)
+            } else if Jwt::is_jwt(data) {
+                self.tag = Tag::Jwt;
+                self.obj.read_jwt(data)
+            } else if StructuredMsg::is_msg(data) {
+                self.tag = Tag::StructuredMsg;
+                self.obj.read_structured_msg(data)
+            } else if AuthChallenge::is_auth(data) {
+                self.tag = Tag::AuthChallenge;
+                self.obj.read_auth(data)
+            } else {
+                self.tag = Tag::Transaction;
+                self.obj.read_tx(data)
+            }
+        }
+    }
+
+    /// Where the transaction in data fails to parse, see Transaction::diagnose.
+    /// Messages, JWTs, structured messages and authentication challenges are
+    /// not split in sections, None is returned for them
+    pub fn diagnose(data: &'a [u8]) -> Option<ParseDiagnostic> {
+        if Message::is_message(data)
+            || Jwt::is_jwt(data)
+            || StructuredMsg::is_msg(data)
+            || AuthChallenge::is_auth(data)
+        {
+            return None;
+        }
+        Transaction::diagnose(data)
+    }
+
+    pub fn num_items(&mut self) -> Result<u8, ParserError> {
+        let _stack = stack::enter(EntryPoint::NumItems);
+        unsafe {
+            match self.tag {
+                Tag::Transaction => self.obj.transaction().num_items(),
+                Tag::Message => Ok(self.obj.message().num_items()),
+                Tag::StructuredMsg => Ok(self.obj.structured_msg().num_items()),
+                Tag::Jwt => Ok(self.obj.jwt().num_items()),
+                Tag::AuthChallenge => Ok(self.obj.auth().num_items()),
+                _ => Err(ParserError::parser_unexpected_error),
+            }
+        }
+    }
+
+    pub fn get_item(
+        &mut self,
+        display_idx: u8,
+        key: &mut [u8],
+        value: &mut [u8],
+        page_idx: u8,
+    ) -> Result<u8, ParserError> {
+        let _stack = stack::enter(EntryPoint::GetItem);
+        self.scratch.reset();
+        unsafe {
+            match self.tag {
+                Tag::Transaction => self.obj.transaction().get_item(
+                    display_idx,
+                    key,
+                    value,
+                    page_idx,
+                    &self.scratch,
+                ),
+                Tag::Message => self
+                    .obj
+                    .message()
+                    .get_item(display_idx, key, value, page_idx),
+                Tag::StructuredMsg => {
+                    self.obj
+                        .structured_msg()
+                        .get_item(display_idx, key, value, page_idx)
+                }
+                Tag::Jwt => self.obj.jwt().get_item(display_idx, key, value, page_idx),
+                Tag::AuthChallenge => self.obj.auth().get_item(display_idx, key, value, page_idx),
+                _ => Err(ParserError::parser_unexpected_error),
+            }
+        }
+    }
+
+    pub fn item_kind(&mut self, display_idx: u8) -> Result<ItemKind, ParserError> {
+        if self.tag == Tag::Transaction {
+            return unsafe { self.obj.transaction().item_kind(display_idx) };
+        }
+
+        if display_idx >= self.num_items()? {
+            return Err(ParserError::parser_display_idx_out_of_range);
+        }
+        Ok(ItemKind::Text)
+    }
+
+    pub fn item_section(&mut self, display_idx: u8) -> Result<ItemSection, ParserError> {
+        if self.tag == Tag::Transaction {
+            return unsafe { self.obj.transaction().item_section(display_idx) };
+        }
+
+        if display_idx >= self.num_items()? {
+            return Err(ParserError::parser_display_idx_out_of_range);
+        }
+        Ok(ItemSection::Details)
+    }
+
+    pub fn is_transaction(&self) -> bool {
+        matches!(self.tag, Tag::Transaction)
+    }
+
+    // For now we support only ByteString messages
+    // but this later new data types could be to added
+    pub fn is_message(&self) -> bool {
+        matches!(self.tag, Tag::Message)
+    }
+
+    pub fn is_jwt(&self) -> bool {
+        matches!(self.tag, Tag::Jwt)
+    }
+
+    pub fn get_type(&mut self) -> Tag {
+        self.tag
+    }
+
+    #[inline(always)]
+    pub fn transaction(&mut self) -> Option<&mut Transaction<'a>> {
+        unsafe {
+            if self.tag == Tag::Transaction {
+                Some(self.obj.transaction())
+            } else {
+                None
+            }
+        }
+    }
+
+    pub fn message(&mut self) -> Option<&mut Message<'a>> {
+        unsafe {
+            if self.tag == Tag::Message {
+                Some(self.obj.message())
+            } else {
+                None
+            }
+        }
+    }
+
+    pub fn jwt(&mut self) -> Option<&mut Jwt<'a>> {
+        unsafe {
+            if self.tag == Tag::Jwt {
+                Some(self.obj.jwt())
+            } else {
+                None
+            }
+        }
+    }
+
+    pub fn structured_msg(&mut self) -> Option<&mut StructuredMsg<'a>> {
+        unsafe {
+            if self.tag == Tag::StructuredMsg {
+                Some(self.obj.structured_msg())
+            } else {
+                None
+            }
+        }
+    }
+
+    pub fn auth_challenge(&mut self) -> Option<&mut AuthChallenge<'a>> {
+        unsafe {
+            if self.tag == Tag::AuthChallenge {
+                Some(self.obj.auth())
+            } else {
+                None
+            }
+        }
+    }
+
+    #[cfg(any(test, fuzzing))]
+    pub fn validate(tx: &mut Self) -> Result<(), ParserError> {
+        use std::*;
+        let mut key = [0u8; 100];
+        let mut value = [0u8; 100];
+        let mut page_idx = 0;
+        let mut display_idx = 0;
+
+        let num_items = tx.num_items()?;
+        while display_idx < num_items {
+            let pages = tx.get_item(display_idx, &mut key, &mut value, page_idx)?;
+            let k = string::String::from_utf8_lossy(key.as_ref());
+            let v = string::String::from_utf8_lossy(value.as_ref());
+            println!("key: {}", k);
+            println!("value: {}", v);
+            key.iter_mut().for_each(|v| *v = 0);
+            value.iter_mut().for_each(|v| *v = 0);
+            page_idx += 1;
+            if page_idx >= pages {
+                page_idx = 0;
+                display_idx += 1;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'a> Obj<'a> {
+    pub fn from_bytes(data: &'a [u8], tag: Tag) -> Result<Self, ParserError> {
+        match tag {
+            Tag::Transaction => Ok(Self {
+                tx: ManuallyDrop::new(Transaction::from_bytes(data)?),
+            }),
+            Tag::Message => Ok(Self {
+                msg: ManuallyDrop::new(Message::from_bytes(data)?),
+            }),
+            Tag::StructuredMsg => Ok(Self {
+                structured_msg: ManuallyDrop::new(StructuredMsg::from_bytes(data)?),
+            }),
+            Tag::Jwt => Ok(Self {
+                jwt: ManuallyDrop::new(Jwt::from_bytes(data)?),
+            }),
+            Tag::AuthChallenge => Ok(Self {
+                auth: ManuallyDrop::new(AuthChallenge::from_bytes(data)?),
+            }),
+            _ => Err(ParserError::parser_unexpected_type),
+        }
+    }
+    pub unsafe fn read_tx(&mut self, data: &'a [u8]) -> Result<(), ParserError> {
+        (&mut *self.tx).read(data)
+    }
+
+    pub unsafe fn read_msg(&mut self, data: &'a [u8]) -> Result<(), ParserError> {
+        (&mut *self.msg).read(data)
+    }
+
+    pub unsafe fn read_structured_msg(&mut self, data: &'a [u8]) -> Result<(), ParserError> {
+        (&mut *self.structured_msg).read(data)
+    }
+
+    pub unsafe fn read_jwt(&mut self, data: &'a [u8]) -> Result<(), ParserError> {
+        (&mut *self.jwt).read(data)
+    }
+
+    pub unsafe fn read_auth(&mut self, data: &'a [u8]) -> Result<(), ParserError> {
+        self.auth.read(data)
+    }
+
+    #[inline(always)]
+    pub unsafe fn transaction(&mut self) -> &mut Transaction<'a> {
+        &mut *self.tx
+    }
+
+    pub unsafe fn message(&mut self) -> &mut Message<'a> {
+        &mut *self.msg
+    }
+
+    pub unsafe fn structured_msg(&mut self) -> &mut StructuredMsg<'a> {
+        &mut *self.structured_msg
+    }
+
+    pub unsafe fn jwt(&mut self) -> &mut Jwt<'a> {
+        &mut *self.jwt
+    }
+
+    pub unsafe fn auth(&mut self) -> &mut AuthChallenge<'a> {
+        &mut self.auth
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde::{de::DeserializeOwned, Deserialize, Serialize};
+    use std::prelude::v1::*;
+
+    use super::*;
+
+    use crate::parser::*;
+    use std::path::PathBuf;
+
+    #[derive(Serialize, Deserialize)]
+    struct StxTransaction {
+        raw: String,
+        recipient: String,
+        sender: String,
+        nonce: u64,
+        amount: u64,
+        fee: u32,
+        post_condition_principal: Option<String>,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct SmartContractTx {
+        raw: String,
+        sender: String,
+        sponsor_addrs: Option<String>,
+        fee: u64,
+        nonce: u64,
+        contract_name: String,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct ContractCallTx {
+        raw: String,
+        sender: String,
+        sponsor_addrs: Option<String>,
+        fee: u64,
+        nonce: u64,
+        contract_name: String,
+        function_name: String,
+        num_args: u32,
+        post_condition_principal: Option<String>,
+        post_condition_asset_name: Option<String>,
+    }
+
+    // the transaction bytes of a fixture in tests/ and the fixture itself
+    fn parsed_fixture<T: DeserializeOwned>(name: &str) -> (Vec<u8>, T) {
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("tests");
+        path.push(name);
+        path.set_extension("json");
+        let str = std::fs::read_to_string(path).expect("Error opening json file");
+        let json: serde_json::Value = serde_json::from_str(&str).unwrap();
+        let bytes = hex::decode(json["raw"].as_str().unwrap()).unwrap();
+        (bytes, serde_json::from_value(json).unwrap())
+    }
+
+    #[test]
+    fn read_message() {
+        let blob = "17537461636b73205369676e6564204d6573736167653a0a0b48656c6c6f20576f726c64";
+        let blob = hex::decode(blob).unwrap();
+        let mut parsed_obj = ParsedObj::from_bytes(&blob).expect("Invalid input data");
+        ParsedObj::validate(&mut parsed_obj).unwrap();
+    }
+
+    #[test]
+    fn read_jwt() {
+        let data = "eyJ0eXAiOiJKV1QiLCJhbGciOiJFUzI1NksifQ==.eyJpc3N1ZWRfYXQiOjE0NDA3MTM0MTQuODUsImNoYWxsZW5nZSI6IjdjZDllZDVlLWJiMGUtNDllYS1hMzIzLWYyOGJkZTNhMDU0OSIsImlzc3VlciI6InhwdWI2NjFNeU13QXFSYmNGUVZyUXI0UTRrUGphUDRKaldhZjM5ZkJWS2pQZEs2b0dCYXlFNDZHQW1Lem81VURQUWRMU005RHVmWmlQOGVhdXk1NlhOdUhpY0J5U3ZacDdKNXdzeVFWcGkyYXh6WiIsImJsb2NrY2hhaW5pZCI6InJ5YW4ifQ==";
+        let mut parsed_obj =
+            ParsedObj::from_bytes(data.as_bytes()).expect("Invalid jwt input data");
+        ParsedObj::validate(&mut parsed_obj).unwrap();
+        parsed_obj.read(data.as_bytes()).unwrap();
+        assert!(parsed_obj.tag == Tag::Jwt);
+    }
+
+    #[test]
+    fn read_auth_challenge() {
+        let mut data = b"\x15Stacks Connect Auth:\n\x0eapp.alexlab.co\x10".to_vec();
+        data.extend_from_slice(&[0x5a; 16]);
+        let mut parsed_obj = ParsedObj::from_bytes(&data).expect("Invalid auth input data");
+        ParsedObj::validate(&mut parsed_obj).unwrap();
+        parsed_obj.read(&data).unwrap();
+        assert!(parsed_obj.tag == Tag::AuthChallenge);
+        assert_eq!(parsed_obj.num_items().unwrap(), 2);
+        assert_eq!(
+            parsed_obj.auth_challenge().unwrap().domain(),
+            b"app.alexlab.co"
+        );
+        assert!(ParsedObj::diagnose(&data).is_none());
+    }
+
+    #[test]
+    fn test_token_stx_transfer() {
+        let input_path = {
+            let mut r = PathBuf::new();
+            r.push(env!("CARGO_MANIFEST_DIR"));
+            r.push("tests");
+            r.push("stx_token_transfer");
+            r.set_extension("json");
+            r
+        };
+        let str = std::fs::read_to_string(input_path).expect("Error opening json file");
+        let json: StxTransaction = serde_json::from_str(&str).unwrap();
+
+        let bytes = hex::decode(&json.raw).unwrap();
+        let mut transaction = ParsedObj::from_bytes(&bytes).unwrap();
+        transaction.read(&bytes).unwrap();
+        ParsedObj::validate(&mut transaction).unwrap();
+        let transaction = transaction.transaction().unwrap();
+
+        assert!(transaction.transaction_auth.is_standard_auth());
+        assert!(!transaction.is_sponsored());
+        assert!(transaction.sponsor_address().is_err());
+
+        let spending_condition = transaction.transaction_auth.origin();
+
+        assert_eq!(json.nonce, spending_condition.nonce().unwrap());
+        assert_eq!(json.fee, spending_condition.fee().unwrap() as u32);
+
+        let origin = spending_condition
+            .signer_address(transaction.version)
+            .unwrap();
+        let origin = core::str::from_utf8(&origin[0..origin.len()]).unwrap();
+        assert_eq!(&json.sender, origin);
+
+        let recipient = transaction.payload_recipient_address().unwrap();
+        let addr_len = recipient.len();
+        let address = core::str::from_utf8(&recipient[0..addr_len]).unwrap();
+        assert_eq!(&json.recipient, address);
+        //assert!(ParsedObj::validate(&mut transaction).is_ok());
+    }
+
+    #[test]
+    fn test_token_stx_transfer_item_sections() {
+        let (bytes, _) = parsed_fixture::<StxTransaction>("stx_token_transfer");
+        let mut parsed_obj = ParsedObj::from_bytes(&bytes).unwrap();
+        parsed_obj.read(&bytes).unwrap();
+
+        let sections = [
+            ItemSection::Details,
+            ItemSection::Details,
+            ItemSection::Fees,
+            ItemSection::Amounts,
+            ItemSection::Recipient,
+            ItemSection::Details,
+        ];
+        for (idx, section) in sections.iter().enumerate() {
+            assert_eq!(parsed_obj.item_section(idx as u8).unwrap(), *section);
+        }
+
+        let num_items = parsed_obj.num_items().unwrap();
+        for idx in sections.len() as u8..num_items {
+            assert_eq!(
+                parsed_obj.item_section(idx).unwrap(),
+                ItemSection::PostConditions
+            );
+        }
+        assert!(parsed_obj.item_section(num_items).is_err());
+    }
+
+    #[test]
+    fn test_token_stx_transfer_item_kinds() {
+        let (bytes, _) = parsed_fixture::<StxTransaction>("stx_token_transfer");
+        let mut parsed_obj = ParsedObj::from_bytes(&bytes).unwrap();
+        parsed_obj.read(&bytes).unwrap();
+
+        let kinds = [
+            ItemKind::Address,
+            ItemKind::Text,
+            ItemKind::Amount,
+            ItemKind::Amount,
+            ItemKind::Address,
+            ItemKind::Memo,
+        ];
+        for (idx, kind) in kinds.iter().enumerate() {
+            assert_eq!(parsed_obj.item_kind(idx as u8).unwrap(), *kind);
+        }
+        assert!(parsed_obj.item_kind(kinds.len() as u8).is_err());
+    }
+
+    #[test]
+    fn test_hidden_nonce() {
+        let (bytes, json) = parsed_fixture::<StxTransaction>("stx_token_transfer");
+        let mut parsed_obj = ParsedObj::from_bytes(&bytes).unwrap();
+        parsed_obj.read(&bytes).unwrap();
+        let num_items = parsed_obj.num_items().unwrap();
+
+        crate::hide_items(true, false);
+        assert_eq!(parsed_obj.num_items().unwrap(), num_items - 1);
+
+        // the fee takes the place of the nonce, the rest moves up by one
+        let kinds = [
+            ItemKind::Address,
+            ItemKind::Amount,
+            ItemKind::Amount,
+            ItemKind::Address,
+            ItemKind::Memo,
+        ];
+        for (idx, kind) in kinds.iter().enumerate() {
+            assert_eq!(parsed_obj.item_kind(idx as u8).unwrap(), *kind);
+        }
+        assert!(parsed_obj.item_kind(kinds.len() as u8).is_err());
+
+        let mut key = [0u8; 20];
+        let mut value = [0u8; 20];
+        parsed_obj.get_item(1, &mut key, &mut value, 0).unwrap();
+        let fee = json.fee.to_string();
+        assert_eq!(&value[..fee.len() + 1], [fee.as_bytes(), b"\0"].concat());
+        assert_eq!(parsed_obj.item_section(1).unwrap(), ItemSection::Fees);
+        assert!(parsed_obj
+            .get_item(num_items - 1, &mut key, &mut value, 0)
+            .is_err());
+    }
+
+    #[test]
+    fn test_hidden_empty_memo() {
+        let (bytes, _) = parsed_fixture::<StxTransaction>("stx_token_transfer");
+        let num_items = |bytes: &[u8]| {
+            let mut parsed_obj = ParsedObj::from_bytes(bytes).unwrap();
+            parsed_obj.read(bytes).unwrap();
+            let kinds = (0..parsed_obj.num_items().unwrap())
+                .map(|idx| parsed_obj.item_kind(idx).unwrap())
+                .collect::<Vec<_>>();
+            (parsed_obj.num_items().unwrap(), kinds)
+        };
+
+        // the fixture memo is all zeros, it is the last item
+        let (shown, kinds) = num_items(&bytes);
+        assert_eq!(kinds.last(), Some(&ItemKind::Memo));
+
+        crate::hide_items(false, true);
+        let (hidden, hidden_kinds) = num_items(&bytes);
+        assert_eq!(hidden, shown - 1);
+        assert_eq!(hidden_kinds[..], kinds[..kinds.len() - 1]);
+
+        // a memo with text is still shown
+        let mut memo = bytes.clone();
+        let len = memo.len();
+        memo[len - 34..len - 32].copy_from_slice(b"hi");
+        assert_eq!(num_items(&memo), (shown, kinds));
+
+        // both hidden
+        crate::hide_items(true, true);
+        assert_eq!(num_items(&bytes).0, shown - 2);
+    }
+
+    #[test]
+    fn test_postcondition_item_kinds() {
+        let (bytes, _) = parsed_fixture::<StxTransaction>("stx_token_transfer_postcondition");
+        let mut parsed_obj = ParsedObj::from_bytes(&bytes).unwrap();
+        parsed_obj.read(&bytes).unwrap();
+
+        // the STX post condition follows the origin and payload items:
+        // principal, condition code and amount
+        let num_items = parsed_obj.num_items().unwrap();
+        assert_eq!(
+            parsed_obj.item_kind(num_items - 3).unwrap(),
+            ItemKind::Address
+        );
+        assert_eq!(parsed_obj.item_kind(num_items - 2).unwrap(), ItemKind::Text);
+        assert_eq!(
+            parsed_obj.item_kind(num_items - 1).unwrap(),
+            ItemKind::Amount
+        );
+        assert_eq!(
+            parsed_obj.item_section(num_items - 1).unwrap(),
+            ItemSection::PostConditions
+        );
+    }
+
+    #[test]
+    fn test_high_fee_warning() {
+        let (mut bytes, _) = parsed_fixture::<StxTransaction>("stx_token_transfer");
+        // version, chain_id, auth type, hash mode, signer and nonce come before the fee
+        let fee_offset = 1 + 4 + 1 + 1 + 20 + 8;
+        bytes[fee_offset..fee_offset + 8].copy_from_slice(&2_000_000u64.to_be_bytes());
+
+        let mut parsed_obj = ParsedObj::from_bytes(&bytes).unwrap();
+        parsed_obj.read(&bytes).unwrap();
+
+        let mut key = [0u8; 20];
+        let mut value = [0u8; 20];
+        parsed_obj.get_item(2, &mut key, &mut value, 0).unwrap();
+        assert_eq!(&key[..15], b"High fee (uSTX)");
+        assert_eq!(&value[..7], b"2000000");
+        assert_eq!(parsed_obj.item_kind(2).unwrap(), ItemKind::Warning);
+        assert!(parsed_obj.transaction().unwrap().is_high_fee());
+    }
+
+    #[test]
+    fn test_fee_bump_high_fee() {
+        use crate::hash::{DefaultSha512_256, SHA512_256_LEN};
+        use core::convert::TryInto;
+
+        let (bytes, _) = parsed_fixture::<StxTransaction>("stx_token_transfer");
+        let initial_sighash = |bytes: &[u8]| {
+            let mut parsed_obj = ParsedObj::from_bytes(bytes).unwrap();
+            parsed_obj.read(bytes).unwrap();
+            let mut hash = [0u8; SHA512_256_LEN];
+            let tx = parsed_obj.transaction().unwrap();
+            tx.initial_sighash::<DefaultSha512_256>(&mut hash).unwrap();
+            (hash, tx.is_high_fee())
+        };
+        let (signed, high_fee) = initial_sighash(&bytes);
+        assert!(!high_fee);
+
+        // the bump raises the fee above the threshold along with the nonce
+        let nonce_offset = 1 + 4 + 1 + 1 + 20;
+        let mut bumped = bytes.clone();
+        let nonce = u64::from_be_bytes(bytes[nonce_offset..nonce_offset + 8].try_into().unwrap());
+        bumped[nonce_offset..nonce_offset + 8].copy_from_slice(&(nonce + 1).to_be_bytes());
+        bumped[nonce_offset + 8..nonce_offset + 16].copy_from_slice(&2_000_000u64.to_be_bytes());
+
+        // it is still the signed transaction, but its fee is a warning
+        let (bumped_sighash, high_fee) = initial_sighash(&bumped);
+        assert_eq!(bumped_sighash, signed);
+        assert!(high_fee);
+    }
+
+    #[test]
+    fn test_raw_sections() {
+        let (bytes, _) = parsed_fixture::<StxTransaction>("stx_token_transfer_postcondition");
+        let mut parsed_obj = ParsedObj::from_bytes(&bytes).unwrap();
+        parsed_obj.read(&bytes).unwrap();
+        let transaction = parsed_obj.transaction().unwrap();
+
+        // version + chain_id, auth, modes, post conditions length,
+        // post conditions and payload make up the whole transaction
+        let conditions: Vec<&[u8]> = transaction.post_conditions.iter().collect();
+        let mut raw = std::vec::Vec::new();
+        raw.extend_from_slice(&bytes[..5]);
+        raw.extend_from_slice(transaction.raw_auth());
+        raw.extend_from_slice(transaction.transaction_modes);
+        raw.extend_from_slice(&(conditions.len() as u32).to_be_bytes());
+        conditions.iter().for_each(|c| raw.extend_from_slice(c));
+        raw.extend_from_slice(transaction.raw_payload());
+
+        assert_eq!(transaction.raw_auth()[0], 0x04);
+        assert_eq!(raw, bytes);
+
+        // the same sections when the transaction is parsed in one go
+        let parsed = Transaction::from_bytes(&bytes).unwrap();
+        assert_eq!(parsed.raw_auth(), transaction.raw_auth());
+        assert_eq!(parsed.raw_payload(), transaction.raw_payload());
+    }
+
+    #[test]
+    fn test_multisig_token_transfer() {
+        let input_path = {
+            let mut r = PathBuf::new();
+            r.push(env!("CARGO_MANIFEST_DIR"));
+            r.push("tests");
+            r.push("stx_multisig_token_transfer");
+            r.set_extension("json");
+            r
+        };
+        let str = std::fs::read_to_string(input_path).expect("Error opening json file");
+        let json: StxTransaction = serde_json::from_str(&str).unwrap();
+
+        let bytes = hex::decode(&json.raw).unwrap();
+        let mut transaction = ParsedObj::from_bytes(&bytes).unwrap();
+        transaction.read(&bytes).unwrap();
+        ParsedObj::validate(&mut transaction).unwrap();
+        let transaction = transaction.transaction().unwrap();
+
+        assert!(transaction.transaction_auth.is_standard_auth());
+
+        let spending_condition = transaction.transaction_auth.origin();
+
+        assert_eq!(json.nonce, spending_condition.nonce().unwrap());
+        assert_eq!(json.fee, spending_condition.fee().unwrap() as u32);
+
+        let origin = spending_condition
+            .signer_address(transaction.version)
+            .unwrap();
+        let origin = core::str::from_utf8(&origin[0..origin.len()]).unwrap();
+        assert_eq!(&json.sender, origin);
+
+        let recipient = transaction.payload_recipient_address().unwrap();
+        let addr_len = recipient.len();
+        let address = core::str::from_utf8(&recipient[0..addr_len]).unwrap();
+        assert_eq!(&json.recipient, address);
+
+        assert_eq!(
+            transaction.payload_id(),
+            TransactionPayloadId::TokenTransfer
+        );
+        assert_eq!(transaction.payload.amount(), Some(2_500_000));
+        assert_eq!(transaction.multisig_threshold(), Some(2));
+        let hash160 = transaction.payload.recipient_hash160().unwrap();
+        // a testnet singlesig recipient
+        let encoded =
+            c32::c32_address(c32::C32_ADDRESS_VERSION_TESTNET_SINGLESIG, hash160).unwrap();
+        assert_eq!(&encoded[..], json.recipient.as_bytes());
+        //assert!(ParsedObj::validate(&mut transaction).is_ok());
+    }
+
+    #[test]
+    fn test_token_stx_transfer_testnet() {
+        let input_path = {
+            let mut r = PathBuf::new();
+            r.push(env!("CARGO_MANIFEST_DIR"));
+            r.push("tests");
+            r.push("stx_token_transfer_testnet");
+            r.set_extension("json");
+            r
+        };
+        let str = std::fs::read_to_string(input_path).expect("Error opening json file");
+        let json: StxTransaction = serde_json::from_str(&str).unwrap();
+
+        let bytes = hex::decode(&json.raw).unwrap();
+        let mut transaction = ParsedObj::from_bytes(&bytes).unwrap();
+        transaction.read(&bytes).unwrap();
+        ParsedObj::validate(&mut transaction).unwrap();
+        let transaction = transaction.transaction().unwrap();
+
+        assert!(transaction.transaction_auth.is_standard_auth());
+
+        let spending_condition = transaction.transaction_auth.origin();
+
+        assert_eq!(json.nonce, spending_condition.nonce().unwrap());
+        assert_eq!(json.fee, spending_condition.fee().unwrap() as u32);
+
+        let origin = spending_condition
+            .signer_address(TransactionVersion::Mainnet)
+            .unwrap();
+        let origin = core::str::from_utf8(&origin[0..origin.len()]).unwrap();
+        assert_eq!(&json.sender, origin);
+
+        let recipient = transaction.payload_recipient_address().unwrap();
+        let addr_len = recipient.len();
+        let address = core::str::from_utf8(&recipient[0..addr_len]).unwrap();
+        assert_eq!(&json.recipient, address);
+        //assert!(ParsedObj::validate(&mut transaction).is_ok());
+    }
+
+    #[test]
+    fn test_token_stx_transfer_with_postcondition() {
+        let input_path = {
+            let mut r = PathBuf::new();
+            r.push(env!("CARGO_MANIFEST_DIR"));
+            r.push("tests");
+            r.push("stx_token_transfer_postcondition");
+            r.set_extension("json");
+            r
+        };
+        let str = std::fs::read_to_string(input_path).expect("Error opening json file");
+        let json: StxTransaction = serde_json::from_str(&str).unwrap();
+
+        let bytes = hex::decode(&json.raw).unwrap();
+        let mut transaction = ParsedObj::from_bytes(&bytes).unwrap();
+        transaction.read(&bytes).unwrap();
+        ParsedObj::validate(&mut transaction).unwrap();
+        let transaction = transaction.transaction().unwrap();
+
+        assert!(transaction.transaction_auth.is_standard_auth());
+
+        let spending_condition = transaction.transaction_auth.origin();
+
+        assert_eq!(json.nonce, spending_condition.nonce().unwrap());
+        assert_eq!(json.fee, spending_condition.fee().unwrap() as u32);
+
+        let origin = spending_condition
+            .signer_address(TransactionVersion::Mainnet)
+            .unwrap();
+        let origin = core::str::from_utf8(&origin[0..origin.len()]).unwrap();
+        assert_eq!(&json.sender, origin);
+
+        let recipient = transaction.payload_recipient_address().unwrap();
+        let addr_len = recipient.len();
+        let address = core::str::from_utf8(&recipient[0..addr_len]).unwrap();
+        assert_eq!(&json.recipient, address);
+
+        // Check postconditions
+        assert_eq!(1, transaction.post_conditions.num_conditions());
+        let conditions: Vec<&[u8]> = transaction.post_conditions.iter().collect();
+        let post_condition = TransactionPostCondition::from_bytes(conditions[0])
+            .unwrap()
+            .1;
+        assert!(post_condition.is_stx());
+        let condition_code = post_condition.fungible_condition_code().unwrap();
+        assert_eq!(condition_code, FungibleConditionCode::SentGe);
+        let stx_condition_amount = post_condition.amount_stx().unwrap();
+        assert_eq!(12345, stx_condition_amount);
+        assert!(!post_condition.is_origin_principal());
+        //assert!(ParsedObj::validate(&mut transaction).is_ok());
+    }
+
+    #[test]
+    fn test_standard_smart_contract_tx() {
+        let input_path = {
+            let mut r = PathBuf::new();
+            r.push(env!("CARGO_MANIFEST_DIR"));
+            r.push("tests");
+            r.push("standard_smart_contract");
+            r.set_extension("json");
+            r
+        };
+        let str = std::fs::read_to_string(input_path).expect("Error opening json file");
+        let json: SmartContractTx = serde_json::from_str(&str).unwrap();
+        let bytes = hex::decode(&json.raw).unwrap();
+        let mut transaction = ParsedObj::from_bytes(&bytes).unwrap();
+        transaction.read(&bytes).unwrap();
+        ParsedObj::validate(&mut transaction).unwrap();
+        let transaction = transaction.transaction().unwrap();
+
+        assert!(transaction.transaction_auth.is_standard_auth());
+        assert!(transaction.payload.is_smart_contract_payload());
+        let contract_name = transaction.payload.contract_name().unwrap();
+        let contract_name = core::str::from_utf8(contract_name.name()).unwrap();
+        assert_eq!(json.contract_name, contract_name);
+
+        let spending_condition = transaction.transaction_auth.origin();
+
+        assert_eq!(json.nonce, spending_condition.nonce().unwrap());
+        assert_eq!(json.fee as u32, spending_condition.fee().unwrap() as u32);
+
+        let origin = spending_condition
+            .signer_address(transaction.version)
+            .unwrap();
+        let origin = core::str::from_utf8(&origin[0..origin.len()]).unwrap();
+        assert_eq!(json.sender, origin);
+        //assert!(ParsedObj::validate(&mut transaction).is_ok());
+
+        // the code body follows the contract name and the code length
+        let offset = Transaction::code_body_offset(&bytes).unwrap();
+        let name_end =
+            contract_name.as_ptr() as usize - bytes.as_ptr() as usize + contract_name.len();
+        assert_eq!(offset, name_end + 4);
+
+        // and it is known as soon as those are received
+        assert!(Transaction::code_body_offset(&bytes[..offset - 1]).is_err());
+        assert_eq!(
+            Transaction::code_body_offset(&bytes[..offset]).unwrap(),
+            offset
+        );
+    }
+
+    #[test]
+    fn test_versioned_smart_contract_tx() {
+        use super::super::transaction_payload::TransactionPayload;
+        use crate::lang::LABELS;
+
+        let (bytes, _) = parsed_fixture::<SmartContractTx>("standard_smart_contract");
+        let mut parsed_obj = ParsedObj::from_bytes(&bytes).unwrap();
+        parsed_obj.read(&bytes).unwrap();
+        let num_items = parsed_obj.num_items().unwrap();
+        let body_offset = Transaction::code_body_offset(&bytes).unwrap();
+
+        // the payload id comes right before the contract name
+        let id_offset = match parsed_obj.transaction().unwrap().payload {
+            TransactionPayload::SmartContract(ref contract) => {
+                bytes.len() - contract.raw().len() - 1
+            }
+            _ => unreachable!("expected a contract deploy"),
+        };
+        assert_eq!(bytes[id_offset], TransactionPayloadId::SmartContract as u8);
+        let versioned = |version: u8| {
+            let id = TransactionPayloadId::VersionedSmartContract as u8;
+            [&bytes[..id_offset], &[id, version], &bytes[id_offset + 1..]].concat()
+        };
+
+        // the version follows the contract name, older ones are a warning
+        for (version, kind) in [
+            (3, ItemKind::Text),
+            (4, ItemKind::Text),
+            (1, ItemKind::Warning),
+        ] {
+            let bytes = versioned(version);
+            let mut parsed_obj = ParsedObj::from_bytes(&bytes).unwrap();
+            parsed_obj.read(&bytes).unwrap();
+            let tx = parsed_obj.transaction().unwrap();
+            assert_eq!(
+                tx.payload_id(),
+                TransactionPayloadId::VersionedSmartContract
+            );
+            assert_eq!(
+                Transaction::code_body_offset(&bytes).unwrap(),
+                body_offset + 1
+            );
+
+            assert_eq!(parsed_obj.num_items().unwrap(), num_items + 1);
+            let idx = (0..num_items)
+                .find(|idx| parsed_obj.item_kind(*idx).unwrap() == ItemKind::ContractName)
+                .unwrap()
+                + 1;
+            assert_eq!(parsed_obj.item_kind(idx).unwrap(), kind);
+            let mut key = [0u8; 30];
+            let mut value = [0u8; 100];
+            parsed_obj.get_item(idx, &mut key, &mut value, 0).unwrap();
+            let key_len = key.iter().position(|b| *b == 0).unwrap();
+            let expected_key = match kind {
+                ItemKind::Warning => LABELS.old_clarity_version,
+                _ => LABELS.clarity_version,
+            };
+            assert_eq!(&key[..key_len], expected_key.as_bytes());
+            assert_eq!(value[0], b'0' + version);
+        }
+
+        // nodes do not take versions they do not know
+        for version in [0, 5] {
+            let bytes = versioned(version);
+            assert!(ParsedObj::from_bytes(&bytes).is_err());
+            let diagnostic = ParsedObj::diagnose(&bytes).unwrap();
+            assert_eq!(diagnostic.section, ParseSection::Payload);
+            assert_eq!(
+                diagnostic.error,
+                ParserError::parser_invalid_transaction_payload
+            );
+        }
+    }
+
+    #[test]
+    fn test_sponsored_smart_contract_tx() {
+        let input_path = {
+            let mut r = PathBuf::new();
+            r.push(env!("CARGO_MANIFEST_DIR"));
+            r.push("tests");
+            r.push("sponsored_smart_contract");
+            r.set_extension("json");
+            r
+        };
+        let str = std::fs::read_to_string(input_path).expect("Error opening json file");
+        let json: SmartContractTx = serde_json::from_str(&str).unwrap();
+        let bytes = hex::decode(&json.raw).unwrap();
+        let mut transaction = ParsedObj::from_bytes(&bytes).unwrap();
+        transaction.read(&bytes).unwrap();
+        ParsedObj::validate(&mut transaction).unwrap();
+        let transaction = transaction.transaction().unwrap();
+
+        assert!(!transaction.transaction_auth.is_standard_auth());
+        assert!(transaction.payload.is_smart_contract_payload());
+        let contract_name = transaction.payload.contract_name().unwrap();
+        let contract_name = core::str::from_utf8(contract_name.name()).unwrap();
+        assert_eq!(json.contract_name, contract_name);
+
+        let spending_condition = transaction.transaction_auth.origin();
+        let spending_condition_s = transaction.transaction_auth.sponsor().unwrap();
+
+        assert_eq!(json.nonce, spending_condition.nonce().unwrap());
+        assert_eq!(json.fee as u32, spending_condition.fee().unwrap() as u32);
+
+        let origin = spending_condition
+            .signer_address(transaction.version)
+            .unwrap();
+        let origin = core::str::from_utf8(&origin[0..origin.len()]).unwrap();
+        assert_eq!(json.sender, origin);
+
+        let sponsor_addrs = spending_condition_s
+            .signer_address(transaction.version)
+            .unwrap();
+        let sponsor_addrs = core::str::from_utf8(&sponsor_addrs[0..sponsor_addrs.len()]).unwrap();
+        assert_eq!(json.sponsor_addrs.unwrap(), sponsor_addrs);
+    }
+
+    #[test]
+    fn test_smart_contract_capabilities() {
+        use super::super::transaction_payload::TransactionPayload;
+
+        let (bytes, _) = parsed_fixture::<SmartContractTx>("standard_smart_contract");
+        let mut parsed_obj = ParsedObj::from_bytes(&bytes).unwrap();
+        parsed_obj.read(&bytes).unwrap();
+        let num_items = parsed_obj.num_items().unwrap();
+
+        // nothing in this code moves assets
+        let tx = parsed_obj.transaction().unwrap();
+        assert_eq!(tx.check_code(None), ParserError::parser_ok);
+        assert_eq!(parsed_obj.num_items().unwrap(), num_items);
+
+        let tx = parsed_obj.transaction().unwrap();
+        let body_offset = match tx.payload {
+            TransactionPayload::SmartContract(ref contract) => {
+                bytes.len() - contract.raw().len() + contract.code_body_offset().unwrap()
+            }
+            _ => unreachable!("expected a contract deploy"),
+        };
+
+        let capabilities = |parsed_obj: &mut ParsedObj| {
+            let num_items = parsed_obj.num_items().unwrap();
+            (0..num_items).find_map(|idx| {
+                let mut key = [0u8; 30];
+                let mut value = [0u8; 100];
+                parsed_obj.get_item(idx, &mut key, &mut value, 0).unwrap();
+                let len = value.iter().position(|b| *b == 0).unwrap();
+                let value = String::from_utf8(value[..len].to_vec()).unwrap();
+                key.starts_with(b"Capabilities").then_some(value)
+            })
+        };
+
+        let code =
+            b"(define-public (drain (to principal)) (as-contract (stx-transfer? u1 tx-sender to)))";
+        let mut deploy = bytes[..body_offset - 4].to_vec();
+        deploy.extend_from_slice(&(code.len() as u32).to_be_bytes());
+        deploy.extend_from_slice(code);
+        let mut parsed_obj = ParsedObj::from_bytes(&deploy).unwrap();
+        parsed_obj.read(&deploy).unwrap();
+        assert_eq!(capabilities(&mut parsed_obj), None);
+
+        let tx = parsed_obj.transaction().unwrap();
+        assert_eq!(tx.check_code(None), ParserError::parser_ok);
+        assert_eq!(parsed_obj.num_items().unwrap(), num_items + 1);
+        assert_eq!(
+            capabilities(&mut parsed_obj).unwrap(),
+            "Can move STX. Can act as the contract"
+        );
+
+        // the scan of a body that was partly dropped replaces the one of
+        // the part in the buffer
+        let mut scan = CodeScan::default();
+        scan.update(b"(nft-transfer? nft u1 tx-sender to)");
+        let tx = parsed_obj.transaction().unwrap();
+        assert_eq!(tx.check_code(Some(scan)), ParserError::parser_ok);
+        assert_eq!(capabilities(&mut parsed_obj).unwrap(), "Can move NFTs");
+    }
+
+    #[test]
+    fn test_standard_contract_call_tx() {
+        let input_path = {
+            let mut r = PathBuf::new();
+            r.push(env!("CARGO_MANIFEST_DIR"));
+            r.push("tests");
+            r.push("contract_call_testnet");
+            r.set_extension("json");
+            r
+        };
+        let str = std::fs::read_to_string(input_path).expect("Error opening json file");
+        let json: ContractCallTx = serde_json::from_str(&str).unwrap();
+        let bytes = hex::decode(&json.raw).unwrap();
+        let mut transaction = ParsedObj::from_bytes(&bytes).unwrap();
+        transaction.read(&bytes).unwrap();
+        ParsedObj::validate(&mut transaction).unwrap();
+        let transaction = transaction.transaction().unwrap();
+
+        assert!(transaction.transaction_auth.is_standard_auth());
+        assert!(transaction.payload.is_contract_call_payload());
+        let contract_name = transaction.payload.contract_name().unwrap();
+        let contract_name = core::str::from_utf8(contract_name.name()).unwrap();
+        assert_eq!(json.contract_name, contract_name);
+
+        let function_name =
+            core::str::from_utf8(transaction.payload.function_name().unwrap()).unwrap();
+        assert_eq!(json.function_name, function_name);
+
+        let num_args = transaction.payload.num_args().unwrap();
+        assert_eq!(json.num_args, num_args);
+
+        let origin = transaction.transaction_auth.origin();
+
+        assert_eq!(json.nonce, origin.nonce().unwrap());
+        assert_eq!(json.fee as u32, origin.fee().unwrap() as u32);
+
+        let origin_addr = origin.signer_address(transaction.version).unwrap();
+        let origin_addr = core::str::from_utf8(&origin_addr[..origin_addr.len()]).unwrap();
+        assert_eq!(json.sender, origin_addr);
+    }
+
+    #[test]
+    fn test_signer_address_cached() {
+        let (bytes, json) = parsed_fixture::<ContractCallTx>("contract_call_testnet");
+        let mut transaction = ParsedObj::from_bytes(&bytes).unwrap();
+        transaction.read(&bytes).unwrap();
+        let transaction = transaction.transaction().unwrap();
+
+        // the first call encodes it, the next ones reuse it
+        assert_eq!(transaction.signer_address.get(), None);
+        let address = transaction.signer_address().unwrap();
+        assert_eq!(transaction.signer_address.get(), Some(address));
+        assert_eq!(transaction.signer_address().unwrap(), address);
+
+        let encoded: std::vec::Vec<u8> =
+            (0..address.len()).map(|pos| address.char_at(pos)).collect();
+        assert_eq!(json.sender.as_bytes(), &encoded[..]);
+
+        // checking who signs drops it, the signer may have changed
+        let origin = transaction
+            .transaction_auth
+            .origin()
+            .pub_key_hash()
+            .to_vec();
+        assert_eq!(
+            transaction.check_signer_pk_hash(&origin),
+            ParserError::parser_ok
+        );
+        assert_eq!(transaction.signer_address.get(), None);
+
+        // and so does reading the transaction again
+        transaction.signer_address().unwrap();
+        transaction.read(&bytes).unwrap();
+        assert_eq!(transaction.signer_address.get(), None);
+        assert_eq!(transaction.signer_address().unwrap(), address);
+    }
+
+    #[test]
+    fn test_contract_call_argument_offsets() {
+        use super::super::transaction_payload::{ArgOffsets, TransactionPayload};
+        use core::cell::Cell;
+
+        let (bytes, json) = parsed_fixture::<ContractCallTx>("contract_call_testnet");
+        let mut transaction = ParsedObj::from_bytes(&bytes).unwrap();
+        transaction.read(&bytes).unwrap();
+        let transaction = transaction.transaction().unwrap();
+
+        let args = match transaction.payload {
+            TransactionPayload::ContractCall(ref call) => call.function_args().unwrap(),
+            _ => unreachable!("expected a contract call"),
+        };
+
+        // arguments are found in any order, whether their offset
+        // is already known or not
+        let cache = Cell::new(ArgOffsets::default());
+        let num_args = json.num_args as usize;
+        for at in (0..num_args).rev().chain(0..num_args) {
+            assert_eq!(
+                args.argument_at_cached(at, &cache).unwrap(),
+                args.argument_at(at).unwrap()
+            );
+        }
+        assert!(args.argument_at_cached(num_args, &cache).is_err());
+    }
+
+    #[test]
+    fn test_standard_contract_call_tx_with_fungible_post_condition() {
+        let input_path = {
+            let mut r = PathBuf::new();
+            r.push(env!("CARGO_MANIFEST_DIR"));
+            r.push("tests");
+            r.push("contract_call_with_fungible_postcondition");
+            r.set_extension("json");
+            r
+        };
+        let str = std::fs::read_to_string(input_path).expect("Error opening json file");
+        let json: ContractCallTx = serde_json::from_str(&str).unwrap();
+        let bytes = hex::decode(&json.raw).unwrap();
+        let mut transaction = ParsedObj::from_bytes(&bytes).unwrap();
+        transaction.read(&bytes).unwrap();
+        ParsedObj::validate(&mut transaction).unwrap();
+        let transaction = transaction.transaction().unwrap();
+
+        assert!(transaction.transaction_auth.is_standard_auth());
+        assert!(transaction.payload.is_contract_call_payload());
+        let contract_name = transaction.payload.contract_name().unwrap();
+        let contract_name = core::str::from_utf8(contract_name.name()).unwrap();
+        assert_eq!(json.contract_name, contract_name);
+
+        let function_name =
+            core::str::from_utf8(transaction.payload.function_name().unwrap()).unwrap();
+        assert_eq!(json.function_name, function_name);
+
+        let num_args = transaction.payload.num_args().unwrap();
+        assert_eq!(json.num_args, num_args);
+
+        let origin = transaction.transaction_auth.origin();
+
+        assert_eq!(json.nonce, origin.nonce().unwrap());
+        assert_eq!(json.fee as u32, origin.fee().unwrap() as u32);
+
+        let origin_addr = origin.signer_address(transaction.version).unwrap();
+        let origin_addr = core::str::from_utf8(&origin_addr[..origin_addr.len()]).unwrap();
+        assert_eq!(json.sender, origin_addr);
+
+        let post_conditions: Vec<&[u8]> = transaction.post_conditions.iter().collect();
+        assert_eq!(post_conditions.len(), 1);
+        let condition = TransactionPostCondition::from_bytes(post_conditions[0])
+            .unwrap()
+            .1;
+        assert!(condition.is_fungible());
+        let addr = condition.get_principal_address().unwrap();
+        let principal_addr = core::str::from_utf8(&addr[..addr.len()]).unwrap();
+        assert_eq!(json.post_condition_principal, Some(principal_addr.into()));
+    }
+
+    #[test]
+    fn test_sponsored_banner() {
+        let (bytes, json) = parsed_fixture::<ContractCallTx>("sponsored_contract_call_testnet");
+        let mut parsed_obj = ParsedObj::from_bytes(&bytes).unwrap();
+        parsed_obj.read(&bytes).unwrap();
+
+        let mut key = [0u8; 30];
+        let mut value = [0u8; 60];
+        parsed_obj.get_item(0, &mut key, &mut value, 0).unwrap();
+        assert_eq!(&key[..12], b"Sponsored tx");
+        let banner = std::format!("Fee paid by {}", json.sponsor_addrs.unwrap());
+        assert_eq!(&value[..banner.len()], banner.as_bytes());
+
+        // banner, origin, nonce and the sponsor fee
+        key.iter_mut().for_each(|v| *v = 0);
+        parsed_obj.get_item(3, &mut key, &mut value, 0).unwrap();
+        assert_eq!(&key[..18], b"Sponsor fee (uSTX)");
+        assert_eq!(parsed_obj.item_section(3).unwrap(), ItemSection::Fees);
+        assert_eq!(parsed_obj.item_kind(0).unwrap(), ItemKind::Text);
+        assert_eq!(parsed_obj.item_kind(1).unwrap(), ItemKind::Address);
+
+        // the same data the FFI getters expose
+        let tx = parsed_obj.transaction().unwrap();
+        assert!(tx.is_sponsored());
+        let sponsor = tx.sponsor_address().unwrap();
+        assert_eq!(&sponsor[..], &banner.as_bytes()["Fee paid by ".len()..]);
+        assert_eq!(tx.sponsor_fee(), Some(0));
+    }
+
+    #[test]
+    fn test_multisig_signatures_present() {
+        let (bytes, _) = parsed_fixture::<StxTransaction>("stx_multisig_token_transfer");
+        let mut parsed_obj = ParsedObj::from_bytes(&bytes).unwrap();
+        parsed_obj.read(&bytes).unwrap();
+
+        let (present, required) = parsed_obj
+            .transaction()
+            .unwrap()
+            .transaction_auth
+            .origin_signatures()
+            .unwrap();
+
+        // origin, signatures, nonce and fee
+        let mut key = [0u8; 30];
+        let mut value = [0u8; 30];
+        parsed_obj.get_item(1, &mut key, &mut value, 0).unwrap();
+        assert_eq!(&key[..18], b"Signatures present");
+        let expected = std::format!("{} of {}", present, required);
+        assert_eq!(&value[..expected.len()], expected.as_bytes());
+        assert_eq!(parsed_obj.item_kind(1).unwrap(), ItemKind::Text);
+    }
+
+    #[test]
+    fn test_multisig_field_checks() {
+        let (appended, _) = parsed_fixture::<StxTransaction>("stx_multisig_token_transfer");
+        let unsigned = &appended[..appended.len() - 98];
+
+        // version, chain id, auth type, hash mode, hash, nonce and fee
+        const FIELDS: usize = 1 + 4 + 1 + 1 + 20 + 8 + 8;
+        // the number of fields, the signature field and the required count
+        const REQUIRED: usize = FIELDS + 4 + 66;
+        let check = |bytes: &[u8]| {
+            let mut parsed_obj = ParsedObj::from_bytes(bytes).unwrap();
+            parsed_obj.read(bytes).unwrap();
+            parsed_obj
+                .transaction()
+                .unwrap()
+                .check_signer_pk_hash(&[0; 20])
+        };
+        assert_eq!(check(unsigned), ParserError::parser_ok);
+
+        // one signature while none is required
+        let mut too_many = unsigned.to_vec();
+        too_many[REQUIRED + 1] = 0;
+        assert_eq!(check(&too_many), ParserError::parser_too_many_signatures);
+
+        // the key of who did not sign before the signature, as when the
+        // first key of the account does not sign
+        let mut key_first = unsigned[..FIELDS + 4].to_vec();
+        key_first[FIELDS + 3] = 2;
+        key_first.push(0x00);
+        key_first.extend_from_slice(&[0x02; 33]);
+        key_first.extend_from_slice(&unsigned[FIELDS + 4..]);
+        assert_eq!(check(&key_first), ParserError::parser_ok);
+    }
+
+    #[test]
+    fn test_multisig_duplicate_keys() {
+        let (appended, _) = parsed_fixture::<StxTransaction>("stx_multisig_token_transfer");
+        let unsigned = &appended[..appended.len() - 98];
+
+        // version, chain id, auth type, hash mode, hash, nonce and fee
+        const FIELDS: usize = 1 + 4 + 1 + 1 + 20 + 8 + 8;
+        // the number of fields and the signature field
+        const SIGNED: usize = FIELDS + 4 + 66;
+        // the transaction with keys of who did not sign after the signature
+        let with_keys = |keys: &[[u8; 33]]| {
+            let mut bytes = unsigned[..SIGNED].to_vec();
+            bytes[FIELDS + 3] += keys.len() as u8;
+            for key in keys {
+                bytes.push(0x00);
+                bytes.extend_from_slice(key);
+            }
+            bytes.extend_from_slice(&unsigned[SIGNED..]);
+            bytes
+        };
+        let num_items = |bytes: &[u8]| {
+            let mut parsed_obj = ParsedObj::from_bytes(bytes).unwrap();
+            parsed_obj.read(bytes).unwrap();
+            parsed_obj.num_items().unwrap()
+        };
+
+        let distinct = with_keys(&[[0x02; 33], [0x03; 33]]);
+        let repeated = with_keys(&[[0x02; 33], [0x02; 33]]);
+        assert_eq!(num_items(&repeated), num_items(&distinct) + 1);
+
+        // origin, signatures and the caution after them
+        let mut parsed_obj = ParsedObj::from_bytes(&repeated).unwrap();
+        parsed_obj.read(&repeated).unwrap();
+        let mut key = [0u8; 30];
+        let mut value = [0u8; 60];
+        parsed_obj.get_item(2, &mut key, &mut value, 0).unwrap();
+        assert_eq!(&key[..7], b"Caution");
+        assert_eq!(&value[..14], b"Repeated cosig");
+        assert_eq!(parsed_obj.item_kind(2).unwrap(), ItemKind::Warning);
+    }
+
+    #[test]
+    fn test_fee_ceiling() {
+        let (bytes, _) = parsed_fixture::<StxTransaction>("stx_token_transfer");
+
+        // version, chain id, auth type, hash mode, hash and nonce
+        const FEE: usize = 1 + 4 + 1 + 1 + 20 + 8;
+        let with_fee = |fee: u64| {
+            let mut bytes = bytes.clone();
+            bytes[FEE..FEE + 8].copy_from_slice(&fee.to_be_bytes());
+            bytes
+        };
+        let read = |bytes: &[u8]| {
+            let mut parsed_obj = ParsedObj::from_bytes(bytes)?;
+            parsed_obj.read(bytes)
+        };
+
+        // every STX there will ever be is still reviewed, as a high fee
+        assert_eq!(read(&with_fee(MAX_FEE)), Ok(()));
+
+        // 2 billion STX, a fee of 2_000 STX converted to uSTX twice
+        let too_high = with_fee(2_000 * 1_000_000 * 1_000_000);
+        assert_eq!(read(&too_high), Err(ParserError::parser_fee_too_high));
+        let diagnostic = ParsedObj::diagnose(&too_high).unwrap();
+        assert_eq!(diagnostic.section, ParseSection::Auth);
+        assert_eq!(diagnostic.error, ParserError::parser_fee_too_high);
+    }
+
+    #[test]
+    fn test_partially_signed_multisig() {
+        use crate::hash::{DefaultSha512_256, SHA512_256_LEN};
+        use sha2::Digest;
+
+        let (appended, _) = parsed_fixture::<StxTransaction>("stx_multisig_token_transfer");
+
+        // version, chain id, auth type, hash mode, hash, nonce and fee
+        const FIELDS: usize = 1 + 4 + 1 + 1 + 20 + 8 + 8;
+        // the number of fields, the signature field and the required count
+        const REQUIRED: usize = FIELDS + 4 + 66;
+        assert_eq!(&appended[REQUIRED..REQUIRED + 2], &[0, 2]);
+        let previous = &appended[appended.len() - 98..];
+        // a compressed signature field, whose key encoding is compressed
+        assert_eq!(appended[FIELDS + 4], 0x02);
+        assert_eq!(previous[32], 0x00);
+        assert_eq!(&previous[33..], &appended[FIELDS + 5..REQUIRED]);
+
+        // the presig of the first signer, standard auth, fee and nonce 0
+        let first_presig = |tx: &Transaction| {
+            let mut initial = [0u8; SHA512_256_LEN];
+            tx.initial_sighash::<DefaultSha512_256>(&mut initial)
+                .unwrap();
+            let mut data = initial.to_vec();
+            data.extend_from_slice(&[0x04; 1]);
+            data.extend_from_slice(&[0; 16]);
+            let mut presig = [0u8; SHA512_256_LEN];
+            presig.copy_from_slice(&sha2::Sha512Trunc256::digest(&data));
+            presig
+        };
+        let chained = |bytes: &[u8]| {
+            let mut parsed_obj = ParsedObj::from_bytes(bytes).unwrap();
+            parsed_obj.read(bytes).unwrap();
+            let tx = parsed_obj.transaction().unwrap();
+            assert_eq!(tx.check_signer_pk_hash(&[0; 20]), ParserError::parser_ok);
+            let mut presig = first_presig(tx);
+            tx.multisig_presig_hash::<DefaultSha512_256>(&mut presig)
+                .map(|_| presig)
+        };
+
+        // the previous signer data the host appends is the signature in the
+        // auth fields, and its post_sig_hash is the one stacks-core computes
+        let expected = {
+            let bytes = &appended[..appended.len() - 98];
+            let mut parsed_obj = ParsedObj::from_bytes(bytes).unwrap();
+            parsed_obj.read(bytes).unwrap();
+            let mut data = first_presig(parsed_obj.transaction().unwrap()).to_vec();
+            data.extend_from_slice(&previous[32..]);
+            assert_eq!(&sha2::Sha512Trunc256::digest(&data)[..], &previous[..32]);
+
+            let mut data = previous[..32].to_vec();
+            data.extend_from_slice(&[0x04; 1]);
+            data.extend_from_slice(&[0; 16]);
+            sha2::Sha512Trunc256::digest(&data)
+        };
+        assert_eq!(&chained(&appended).unwrap()[..], &expected[..]);
+
+        // as stacks.js serializes it, without the appended data
+        let stacks_js = appended[..appended.len() - 98].to_vec();
+        assert_eq!(&chained(&stacks_js).unwrap()[..], &expected[..]);
+
+        // the key of a signer that did not sign leaves the hash as it is
+        let mut skipped = stacks_js[..REQUIRED].to_vec();
+        skipped[FIELDS + 3] = 2;
+        skipped.push(0x00);
+        skipped.extend_from_slice(&[0x02; 33]);
+        skipped.extend_from_slice(&stacks_js[REQUIRED..]);
+        assert_eq!(&chained(&skipped).unwrap()[..], &expected[..]);
+
+        // appended data that is not the last signature
+        let mut mismatch = appended.clone();
+        let last = mismatch.len() - 1;
+        mismatch[last] ^= 0x01;
+        assert_eq!(
+            chained(&mismatch),
+            Err(ParserError::parser_invalid_signature)
+        );
+
+        // already signed by as many keys as required
+        let mut signed = stacks_js;
+        signed[REQUIRED + 1] = 1;
+        let mut parsed_obj = ParsedObj::from_bytes(&signed).unwrap();
+        parsed_obj.read(&signed).unwrap();
+        let tx = parsed_obj.transaction().unwrap();
+        assert_eq!(
+            tx.check_signer_pk_hash(&[0; 20]),
+            ParserError::parser_unexpected_number_items
+        );
+    }
+
+    #[test]
+    fn test_order_independent_multisig() {
+        use crate::hash::{DefaultSha512_256, SHA512_256_LEN};
+
+        let (mut appended, _) = parsed_fixture::<StxTransaction>("stx_multisig_token_transfer");
+
+        // version, chain id and auth type
+        const HASH_MODE: usize = 1 + 4 + 1;
+        // the hash mode, hash, nonce and fee
+        const FIELDS: usize = HASH_MODE + 1 + 20 + 8 + 8;
+        // the number of fields and the signature field
+        const SIGNED: usize = FIELDS + 4 + 66;
+        assert_eq!(appended[HASH_MODE], HashMode::P2SH as u8);
+        appended[HASH_MODE] = HashMode::P2SHNonSequential as u8;
+
+        // the signature and then the keys of who did not sign
+        let mut unsigned = appended[..SIGNED].to_vec();
+        unsigned[FIELDS + 3] = 3;
+        for key in [[0x02; 33], [0x03; 33]] {
+            unsigned.push(0x00);
+            unsigned.extend_from_slice(&key);
+        }
+        unsigned.extend_from_slice(&appended[SIGNED..appended.len() - 98]);
+
+        let mut parsed_obj = ParsedObj::from_bytes(&unsigned).unwrap();
+        parsed_obj.read(&unsigned).unwrap();
+        let tx = parsed_obj.transaction().unwrap();
+        assert_eq!(tx.check_signer_pk_hash(&[0; 20]), ParserError::parser_ok);
+
+        // every signer signs the pre_sig_hash of the first one
+        let mut presig = [0x5a; SHA512_256_LEN];
+        tx.multisig_presig_hash::<DefaultSha512_256>(&mut presig)
+            .unwrap();
+        assert_eq!(presig, [0x5a; SHA512_256_LEN]);
+
+        // and its signature replaces the field of its key, in any order
+        assert_eq!(tx.multisig_auth_field_index(&[0x03; 33]), Ok(2));
+        assert_eq!(tx.multisig_auth_field_index(&[0x02; 33]), Ok(1));
+
+        // previous signer data has nothing to chain
+        let mut parsed_obj = ParsedObj::from_bytes(&appended).unwrap();
+        parsed_obj.read(&appended).unwrap();
+        let tx = parsed_obj.transaction().unwrap();
+        assert_eq!(
+            tx.multisig_presig_hash::<DefaultSha512_256>(&mut presig),
+            Err(ParserError::parser_invalid_auth_type)
+        );
+    }
+
+    #[test]
+    fn test_initial_sighash_auth_from_partial_bytes() {
+        let (bytes, _) = parsed_fixture::<ContractCallTx>("sponsored_contract_call_testnet");
+        let mut parsed_obj = ParsedObj::from_bytes(&bytes).unwrap();
+        parsed_obj.read(&bytes).unwrap();
+        let tx = parsed_obj.transaction().unwrap();
+
+        let mut expected = [0u8; 185];
+        let expected_len = tx
+            .transaction_auth
+            .initial_sighash_auth(&mut expected)
+            .unwrap();
+        let auth_end = tx.last_transaction_block().as_ptr() as usize - bytes.as_ptr() as usize;
+
+        // the auth can not be hashed until it is fully received
+        let mut buf = [0u8; 185];
+        for len in 0..auth_end {
+            assert!(Transaction::initial_sighash_auth_from_bytes(&bytes[..len], &mut buf).is_err());
+        }
+
+        for len in [auth_end, auth_end + 1, bytes.len()] {
+            let (end, written) =
+                Transaction::initial_sighash_auth_from_bytes(&bytes[..len], &mut buf).unwrap();
+            assert_eq!(end, auth_end);
+            assert_eq!(&buf[..written], &expected[..expected_len]);
+        }
+    }
+
+    #[test]
+    fn test_initial_sighash() {
+        use crate::hash::{DefaultSha512_256, SHA512_256_LEN};
+        use sha2::Digest;
+
+        let bytes_str = "0000000001040061e115b4463fb27425e80fa8e3e2616b4e5a17e40000000000000011000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000003020000000200021661e115b4463fb27425e80fa8e3e2616b4e5a17e40500000000000f4240010316e685b016b3b6cd9ebf35f38e5ae29392e2acd51d0a616c65782d7661756c7416e685b016b3b6cd9ebf35f38e5ae29392e2acd51d176167653030302d676f7665726e616e63652d746f6b656e04616c657803000000001a6e83360216e685b016b3b6cd9ebf35f38e5ae29392e2acd51d11737761702d68656c7065722d76312d30330b737761702d68656c706572000000040616e685b016b3b6cd9ebf35f38e5ae29392e2acd51d0a746f6b656e2d777374780616e685b016b3b6cd9ebf35f38e5ae29392e2acd51d176167653030302d676f7665726e616e63652d746f6b656e0100000000000000000000000005f5e1000a010000000000000000000000001a6e8336";
+        let bytes = hex::decode(bytes_str).unwrap();
+        let mut parsed_obj = ParsedObj::from_bytes(&bytes).unwrap();
+        parsed_obj.read(&bytes).unwrap();
+        let tx = parsed_obj.transaction().unwrap();
+
+        let mut hash = [0u8; SHA512_256_LEN];
+        tx.initial_sighash::<DefaultSha512_256>(&mut hash).unwrap();
+
+        // singlesig origin: nonce, fee, key encoding and signature are cleared
+        let mut cleared = bytes.clone();
+        cleared[27..27 + 82].iter_mut().for_each(|v| *v = 0);
+        let expected = sha2::Sha512Trunc256::digest(&cleared);
+        assert_eq!(&hash[..], expected.as_slice());
+    }
+
+    #[test]
+    fn test_num_items_limit() {
+        let bytes_str = "0000000001040061e115b4463fb27425e80fa8e3e2616b4e5a17e40000000000000011000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000003020000000200021661e115b4463fb27425e80fa8e3e2616b4e5a17e40500000000000f4240010316e685b016b3b6cd9ebf35f38e5ae29392e2acd51d0a616c65782d7661756c7416e685b016b3b6cd9ebf35f38e5ae29392e2acd51d176167653030302d676f7665726e616e63652d746f6b656e04616c657803000000001a6e83360216e685b016b3b6cd9ebf35f38e5ae29392e2acd51d11737761702d68656c7065722d76312d30330b737761702d68656c706572000000040616e685b016b3b6cd9ebf35f38e5ae29392e2acd51d0a746f6b656e2d777374780616e685b016b3b6cd9ebf35f38e5ae29392e2acd51d176167653030302d676f7665726e616e63652d746f6b656e0100000000000000000000000005f5e1000a010000000000000000000000001a6e8336";
+        let bytes = hex::decode(bytes_str).unwrap();
+
+        // replaces the arguments of the call with num_args booleans
+        let function = b"\x0bswap-helper";
+        let args_offset = bytes
+            .windows(function.len())
+            .position(|w| w == function)
+            .unwrap()
+            + function.len();
+        let with_args = |num_args: u32| {
+            let mut tx = bytes[..args_offset].to_vec();
+            tx.extend_from_slice(&num_args.to_be_bytes());
+            tx.resize(tx.len() + num_args as usize, 0x03);
+            tx
+        };
+
+        let tx = with_args(100);
+        let mut parsed_obj = ParsedObj::from_bytes(&tx).unwrap();
+        parsed_obj.read(&tx).unwrap();
+        assert!(parsed_obj.num_items().unwrap() <= MAX_NUM_ITEMS);
+
+        // the C side can not address more items
+        let tx = with_args(MAX_NUM_ITEMS as u32);
+        let mut parsed_obj = ParsedObj::from_bytes(&tx).unwrap();
+        parsed_obj.read(&tx).unwrap();
+        assert_eq!(
+            parsed_obj.num_items(),
+            Err(ParserError::parser_limit_exceeded)
+        );
+
+        // more arguments than items fit in an u8
+        let tx = with_args(300);
+        let mut parsed_obj = ParsedObj::from_bytes(&tx).unwrap();
+        parsed_obj.read(&tx).unwrap();
+        assert_eq!(
+            parsed_obj.num_items(),
+            Err(ParserError::parser_limit_exceeded)
+        );
+    }
+
+    #[test]
+    fn test_sponsored_initial_sighash() {
+        use crate::hash::{DefaultSha512_256, SHA512_256_LEN};
+        use sha2::Digest;
+
+        let (bytes, _) = parsed_fixture::<ContractCallTx>("sponsored_contract_call_testnet");
+        let mut parsed_obj = ParsedObj::from_bytes(&bytes).unwrap();
+        parsed_obj.read(&bytes).unwrap();
+        let tx = parsed_obj.transaction().unwrap();
+        assert!(!tx.transaction_auth.is_multisig());
+
+        let mut hash = [0u8; SHA512_256_LEN];
+        tx.initial_sighash::<DefaultSha512_256>(&mut hash).unwrap();
+
+        // the singlesig origin nonce, fee, key encoding and signature are
+        // cleared and the sponsor replaced by the all zeroes sentinel
+        let mut cleared = bytes.clone();
+        cleared[27..27 + 82 + 103].iter_mut().for_each(|v| *v = 0);
+        let expected = sha2::Sha512Trunc256::digest(&cleared);
+        assert_eq!(&hash[..], &expected[..]);
+    }
+
+    #[test]
+    fn test_check_signer() {
+        let (bytes, _) = parsed_fixture::<ContractCallTx>("sponsored_contract_call_testnet");
+        let mut parsed_obj = ParsedObj::from_bytes(&bytes).unwrap();
+        parsed_obj.read(&bytes).unwrap();
+        let tx = parsed_obj.transaction().unwrap();
+
+        let origin = tx.transaction_auth.origin().pub_key_hash().to_vec();
+        let sponsor = tx
+            .transaction_auth
+            .sponsor()
+            .unwrap()
+            .pub_key_hash()
+            .to_vec();
+
+        // the fee shown is the one of the signer
+        assert_eq!(tx.check_signer_pk_hash(&origin), ParserError::parser_ok);
+        assert_eq!(tx.fee(), Some(tx.origin_fee()));
+        assert_eq!(tx.check_signer_pk_hash(&sponsor), ParserError::parser_ok);
+        assert_eq!(tx.fee(), tx.sponsor_fee());
+
+        // a key that is neither of them can not produce a valid signature
+        let other = [0x42u8; 20];
+        assert_eq!(
+            tx.check_signer_pk_hash(&other),
+            ParserError::parser_signer_mismatch
+        );
+        assert_eq!(tx.fee(), None);
+    }
+
+    #[test]
+    fn test_check_multisig_signer() {
+        let (appended, _) = parsed_fixture::<StxTransaction>("stx_multisig_token_transfer");
+        let unsigned = &appended[..appended.len() - 98];
+
+        // version, chain id, auth type, hash mode, hash, nonce and fee
+        const FIELDS: usize = 1 + 4 + 1 + 1 + 20 + 8 + 8;
+        let (key, other_key) = ([0x02; 33], [0x03; 33]);
+        // the key of this device listed before the signature
+        let mut listed = unsigned[..FIELDS + 4].to_vec();
+        listed[FIELDS + 3] = 2;
+        listed.push(0x00);
+        listed.extend_from_slice(&key);
+        listed.extend_from_slice(&unsigned[FIELDS + 4..]);
+
+        // whether the review warns about the key, given whether the template
+        // of the account was checked
+        let check = |bytes: &[u8], pubkey: &[u8], template: bool| {
+            let mut parsed_obj = ParsedObj::from_bytes(bytes).unwrap();
+            parsed_obj.read(bytes).unwrap();
+            let tx = parsed_obj.transaction().unwrap();
+            // any key passes the hash check of a multisig origin
+            assert_eq!(tx.check_signer_pk_hash(&[0x42; 20]), ParserError::parser_ok);
+            let num_items = parsed_obj.num_items().unwrap();
+
+            let tx = parsed_obj.transaction().unwrap();
+            let origin = tx.transaction_auth.origin().pub_key_hash().to_vec();
+            let account = if template {
+                Some(origin.as_slice())
+            } else {
+                None
+            };
+            assert_eq!(
+                tx.check_multisig_signer(pubkey, account),
+                ParserError::parser_ok
+            );
+            let warned = parsed_obj.num_items().unwrap() == num_items + 1;
+            if warned {
+                let mut key = [0u8; 30];
+                let mut value = [0u8; 100];
+                parsed_obj.get_item(0, &mut key, &mut value, 0).unwrap();
+                assert_eq!(&key[..16], b"Multisig account");
+                assert_eq!(parsed_obj.item_kind(0).unwrap(), ItemKind::Warning);
+            }
+            warned
+        };
+
+        // the auth fields list the key, or the template of the account was checked
+        assert!(!check(&listed, &key, false));
+        assert!(!check(unsigned, &key, true));
+        // nothing tells the key is one of the account
+        assert!(check(unsigned, &key, false));
+        assert!(check(&listed, &other_key, false));
+    }
+
+    #[test]
+    fn test_device_sponsor() {
+        let (bytes, json) = parsed_fixture::<ContractCallTx>("sponsored_contract_call_testnet");
+        let mut parsed_obj = ParsedObj::from_bytes(&bytes).unwrap();
+        parsed_obj.read(&bytes).unwrap();
+        let tx = parsed_obj.transaction().unwrap();
+
+        let origin = tx.transaction_auth.origin().pub_key_hash().to_vec();
+        let sponsor = tx
+            .transaction_auth
+            .sponsor()
+            .unwrap()
+            .pub_key_hash()
+            .to_vec();
+
+        // the sponsor key is only checked along with the origin one
+        assert_eq!(
+            tx.check_sponsor_pk_hash(&sponsor),
+            ParserError::parser_invalid_auth_type
+        );
+        assert_eq!(tx.check_signer_pk_hash(&origin), ParserError::parser_ok);
+        assert_eq!(
+            tx.check_sponsor_pk_hash(&origin),
+            ParserError::parser_signer_mismatch
+        );
+        assert_eq!(tx.check_sponsor_pk_hash(&sponsor), ParserError::parser_ok);
+
+        // each role signs with its own flag, fee and nonce
+        use crate::parser::transaction::TransactionAuthFlags;
+        let mut data = [0u8; AUTH_DATA_LEN];
+        assert_eq!(
+            tx.role_auth_data(SignerId::Origin, &mut data),
+            Ok(AUTH_DATA_LEN)
+        );
+        assert_eq!(data[0], TransactionAuthFlags::Standard as u8);
+        assert_eq!(&data[1..9], &tx.origin_fee().to_be_bytes());
+        assert_eq!(&data[9..], &tx.origin_nonce().to_be_bytes());
+        assert_eq!(
+            tx.role_auth_data(SignerId::Sponsor, &mut data),
+            Ok(AUTH_DATA_LEN)
+        );
+        assert_eq!(data[0], TransactionAuthFlags::Sponsored as u8);
+        assert_eq!(&data[1..9], &tx.sponsor_fee().unwrap().to_be_bytes());
+        assert_eq!(&data[9..], &tx.sponsor_nonce().unwrap().to_be_bytes());
+        assert!(tx
+            .role_auth_data(SignerId::Sponsor, &mut data[1..])
+            .is_err());
+
+        let mut key = [0u8; 30];
+        let mut value = [0u8; 100];
+        parsed_obj.get_item(0, &mut key, &mut value, 0).unwrap();
+        assert_eq!(&key[..12], b"Sponsored tx");
+        let banner = std::format!("Fee paid by this device, {}", json.sponsor_addrs.unwrap());
+        assert_eq!(&value[..banner.len()], banner.as_bytes());
+    }
+
+    #[test]
+    fn test_network_mismatch_warning() {
+        let (bytes, _) = parsed_fixture::<StxTransaction>("stx_token_transfer");
+        let mut parsed_obj = ParsedObj::from_bytes(&bytes).unwrap();
+        parsed_obj.read(&bytes).unwrap();
+        let num_items = parsed_obj.num_items().unwrap();
+
+        // a mainnet transaction signed with a mainnet path
+        let tx = parsed_obj.transaction().unwrap();
+        assert_eq!(tx.version, TransactionVersion::Mainnet);
+        assert_eq!(tx.check_network(false), ParserError::parser_ok);
+        assert_eq!(parsed_obj.num_items().unwrap(), num_items);
+
+        // with a testnet path the review starts with a warning
+        let tx = parsed_obj.transaction().unwrap();
+        assert_eq!(tx.check_network(true), ParserError::parser_network_mismatch);
+        assert_eq!(parsed_obj.num_items().unwrap(), num_items + 1);
+        assert_eq!(parsed_obj.item_kind(0).unwrap(), ItemKind::Warning);
+
+        let mut key = [0u8; 30];
+        let mut value = [0u8; 30];
+        parsed_obj.get_item(0, &mut key, &mut value, 0).unwrap();
+        assert_eq!(&key[..7], b"Network");
+        assert_eq!(&value[..23], b"Mainnet tx, testnet key");
+
+        // the origin follows the warning
+        parsed_obj.get_item(1, &mut key, &mut value, 0).unwrap();
+        assert_eq!(&key[..6], b"Origin");
+
+        // parsing again clears the warning
+        parsed_obj.read(&bytes).unwrap();
+        assert_eq!(parsed_obj.num_items().unwrap(), num_items);
+    }
+
+    #[test]
+    fn test_nonstandard_path_warning() {
+        use crate::path::{COIN_STX, HARDENED, PURPOSE_BIP44};
+
+        let (bytes, _) = parsed_fixture::<StxTransaction>("stx_token_transfer");
+        let mut parsed_obj = ParsedObj::from_bytes(&bytes).unwrap();
+        parsed_obj.read(&bytes).unwrap();
+        let num_items = parsed_obj.num_items().unwrap();
+
+        let tx = parsed_obj.transaction().unwrap();
+        tx.check_path(&[PURPOSE_BIP44, COIN_STX, HARDENED, 0, 7]);
+        assert!(!tx.is_nonstandard_path());
+        assert_eq!(parsed_obj.num_items().unwrap(), num_items);
+
+        // a change address, the review starts with a warning
+        let tx = parsed_obj.transaction().unwrap();
+        tx.check_path(&[PURPOSE_BIP44, COIN_STX, HARDENED, 1, 7]);
+        assert!(tx.is_nonstandard_path());
+        assert_eq!(parsed_obj.num_items().unwrap(), num_items + 1);
+        assert_eq!(parsed_obj.item_kind(0).unwrap(), ItemKind::Warning);
+
+        let mut key = [0u8; 30];
+        let mut value = [0u8; 30];
+        parsed_obj.get_item(0, &mut key, &mut value, 0).unwrap();
+        assert_eq!(&key[..8], b"Key path");
+        assert_eq!(&value[..22], b"Non-standard, check it");
+
+        // it comes before the network warning
+        let tx = parsed_obj.transaction().unwrap();
+        tx.check_network(true);
+        parsed_obj.get_item(1, &mut key, &mut value, 0).unwrap();
+        assert_eq!(&key[..7], b"Network");
+        parsed_obj.get_item(2, &mut key, &mut value, 0).unwrap();
+        assert_eq!(&key[..6], b"Origin");
+
+        // parsing again clears the warning
+        parsed_obj.read(&bytes).unwrap();
+        assert_eq!(parsed_obj.num_items().unwrap(), num_items);
+    }
+
+    #[test]
+    fn test_denylisted_contract_warning() {
+        use crate::hash::{DefaultSha512_256, Sha512_256, SHA512_256_LEN};
+
+        let (bytes, _) = parsed_fixture::<ContractCallTx>("sponsored_contract_call_testnet");
+        let mut parsed_obj = ParsedObj::from_bytes(&bytes).unwrap();
+        parsed_obj.read(&bytes).unwrap();
+        let num_items = parsed_obj.num_items().unwrap();
+
+        let tx = parsed_obj.transaction().unwrap();
+        let contract_id = tx.payload.contract_id().unwrap().to_vec();
+        let mut digest = [0u8; SHA512_256_LEN];
+        DefaultSha512_256::digest(&contract_id, &mut digest);
+
+        // other contracts are not flagged
+        let other = [0x42u8; DENYLIST_ENTRY_LEN];
+        assert_eq!(
+            tx.check_denylist::<DefaultSha512_256>(&other),
+            ParserError::parser_ok
+        );
+        assert!(!tx.is_denylisted());
+        assert_eq!(parsed_obj.num_items().unwrap(), num_items);
+
+        let mut entries = other.to_vec();
+        entries.extend_from_slice(&digest[..DENYLIST_ENTRY_LEN]);
+        let tx = parsed_obj.transaction().unwrap();
+        assert_eq!(
+            tx.check_denylist::<DefaultSha512_256>(&entries),
+            ParserError::parser_denylisted_contract
+        );
+        assert!(tx.is_denylisted());
+
+        // the warning goes before the sponsored banner
+        assert_eq!(parsed_obj.num_items().unwrap(), num_items + 1);
+        assert_eq!(parsed_obj.item_kind(0).unwrap(), ItemKind::Warning);
+        let mut key = [0u8; 30];
+        let mut value = [0u8; 30];
+        parsed_obj.get_item(0, &mut key, &mut value, 0).unwrap();
+        assert_eq!(&key[..16], b"Blocked contract");
+        assert_eq!(&value[..19], b"Listed as malicious");
+        parsed_obj.get_item(1, &mut key, &mut value, 0).unwrap();
+        assert_eq!(&key[..12], b"Sponsored tx");
+    }
+
+    #[test]
+    fn test_contract_call_abi_names() {
+        use crate::parser::ValueId;
+
+        let (bytes, _) = parsed_fixture::<ContractCallTx>("contract_call_testnet");
+        let mut parsed_obj = ParsedObj::from_bytes(&bytes).unwrap();
+        parsed_obj.read(&bytes).unwrap();
+        let num_items = parsed_obj.num_items().unwrap();
+
+        let tx = parsed_obj.transaction().unwrap();
+        let contract_id = tx.payload.contract_id().unwrap().to_vec();
+        let abi_of = |types: [ValueId; 4]| {
+            let mut abi = contract_id.clone();
+            abi.push(9);
+            abi.extend_from_slice(b"stack-stx");
+            abi.push(4);
+            let names: [&[u8]; 4] = [
+                b"amount-ustx",
+                b"pox-addr",
+                b"start-burn-ht",
+                b"lock-period",
+            ];
+            for (ty, name) in types.iter().zip(names.iter()) {
+                abi.push(*ty as u8);
+                abi.push(name.len() as u8);
+                abi.extend_from_slice(name);
+            }
+            abi
+        };
+        let matching = abi_of([
+            ValueId::UInt,
+            ValueId::StandardPrincipal,
+            ValueId::UInt,
+            ValueId::UInt,
+        ]);
+        let mismatching = abi_of([
+            ValueId::UInt,
+            ValueId::StandardPrincipal,
+            ValueId::Int,
+            ValueId::UInt,
+        ]);
+
+        let keys = |parsed_obj: &mut ParsedObj| {
+            let mut keys = Vec::new();
+            for idx in 0..num_items {
+                let mut key = [0u8; 30];
+                let mut value = [0u8; 30];
+                parsed_obj.get_item(idx, &mut key, &mut value, 0).unwrap();
+                let len = key.iter().position(|b| *b == 0).unwrap();
+                keys.push(String::from_utf8(key[..len].to_vec()).unwrap());
+            }
+            keys
+        };
+
+        // an argument of another type, the positions are shown
+        let tx = parsed_obj.transaction().unwrap();
+        assert_eq!(tx.check_abi(&mismatching), ParserError::parser_ok);
+        let shown = keys(&mut parsed_obj);
+        assert!(shown.iter().any(|key| key == "arg2"));
+        assert!(!shown.iter().any(|key| key == "start-burn-ht"));
+
+        // the amount keeps its stacking label
+        let tx = parsed_obj.transaction().unwrap();
+        assert_eq!(tx.check_abi(&matching), ParserError::parser_ok);
+        let shown = keys(&mut parsed_obj);
+        assert_eq!(parsed_obj.num_items().unwrap(), num_items);
+        assert!(!shown.iter().any(|key| key.starts_with("arg")));
+        for name in &["pox-addr", "start-burn-ht", "lock-period"] {
+            assert!(shown.iter().any(|key| key == name));
+        }
+
+        // malformed signatures are refused
+        let tx = parsed_obj.transaction().unwrap();
+        assert_ne!(
+            tx.check_abi(&matching[..matching.len() - 1]),
+            ParserError::parser_ok
+        );
+    }
+
+    #[test]
+    fn test_btc_pegged_token_amounts() {
+        let (bytes, _) =
+            parsed_fixture::<ContractCallTx>("contract_call_with_fungible_postcondition");
+        let mut parsed_obj = ParsedObj::from_bytes(&bytes).unwrap();
+        parsed_obj.read(&bytes).unwrap();
+        let num_items = parsed_obj.num_items().unwrap();
+
+        let items = |parsed_obj: &mut ParsedObj| {
+            let mut items = Vec::new();
+            for idx in 0..parsed_obj.num_items().unwrap() {
+                let mut key = [0u8; 30];
+                let mut value = [0u8; 64];
+                parsed_obj.get_item(idx, &mut key, &mut value, 0).unwrap();
+                let text = |bytes: &[u8]| {
+                    let len = bytes.iter().position(|b| *b == 0).unwrap();
+                    String::from_utf8(bytes[..len].to_vec()).unwrap()
+                };
+                items.push((text(&key), text(&value)));
+            }
+            items
+        };
+        let token_info = |asset: &[u8]| {
+            let mut info = vec![1];
+            info.extend_from_slice(asset);
+            info.push(0x01);
+            info
+        };
+
+        let tx = parsed_obj.transaction().unwrap();
+        let condition = tx.post_conditions.iter().next().unwrap();
+        let (_, condition) = TransactionPostCondition::from_bytes(condition).unwrap();
+        let asset = condition.asset_info().unwrap().to_vec();
+        assert_eq!(asset.last(), Some(&b's'));
+
+        // metadata of other tokens leaves the amount in raw units
+        let mut other = asset.clone();
+        *other.last_mut().unwrap() = b'x';
+        let other = token_info(&other);
+        let tx = parsed_obj.transaction().unwrap();
+        assert_eq!(tx.check_token_info(&other), ParserError::parser_ok);
+        assert_eq!(parsed_obj.num_items().unwrap(), num_items);
+        let shown = items(&mut parsed_obj);
+        assert!(shown.contains(&("Token amount".into(), "100 (raw units)".into())));
+        assert!(shown.iter().any(|(key, _)| key == "Token amounts"));
+
+        // a pegged token is shown in sats and BTC, and the notice on raw
+        // units is gone along with the last amount in them
+        let pegged = token_info(&asset);
+        let tx = parsed_obj.transaction().unwrap();
+        assert_eq!(tx.check_token_info(&pegged), ParserError::parser_ok);
+        assert_eq!(parsed_obj.num_items().unwrap(), num_items - 1);
+        let shown = items(&mut parsed_obj);
+        assert!(shown.contains(&("Token amount".into(), "100 sats (0.00000100 BTC)".into())));
+        assert!(!shown.iter().any(|(key, _)| key == "Token amounts"));
+
+        // malformed metadata is refused
+        let tx = parsed_obj.transaction().unwrap();
+        assert_ne!(
+            tx.check_token_info(&pegged[..pegged.len() - 1]),
+            ParserError::parser_ok
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "admin-functions")]
+    fn test_contract_call_privileged_function() {
+        let (bytes, _) = parsed_fixture::<ContractCallTx>("contract_call_testnet");
+        let keys = |bytes: &[u8]| {
+            let mut parsed_obj = ParsedObj::from_bytes(bytes).unwrap();
+            parsed_obj.read(bytes).unwrap();
+            let mut keys = Vec::new();
+            for idx in 0..parsed_obj.num_items().unwrap() {
+                let mut key = [0u8; 30];
+                let mut value = [0u8; 100];
+                parsed_obj.get_item(idx, &mut key, &mut value, 0).unwrap();
+                let len = key.iter().position(|b| *b == 0).unwrap();
+                keys.push(String::from_utf8(key[..len].to_vec()).unwrap());
+            }
+            keys
+        };
+
+        let shown = keys(&bytes);
+        assert!(!shown.iter().any(|key| key == "Caution"));
+
+        // the same call to a function named as a minting one
+        let at = bytes
+            .windows(9)
+            .position(|window| window == b"stack-stx")
+            .unwrap();
+        let mut minting = bytes.clone();
+        minting[at..at + 9].copy_from_slice(b"mint-many");
+        let flagged = keys(&minting);
+        assert_eq!(flagged.len(), shown.len() + 1);
+        let caution = flagged.iter().position(|key| key == "Caution").unwrap();
+        assert_eq!(flagged[caution - 1], "Function name");
+        assert_eq!(flagged[caution + 1], "arg0");
+    }
+
+    #[test]
+    #[cfg(feature = "pox")]
+    fn test_stack_stx_reward_address() {
+        use crate::path::{COIN_TESTNET, HARDENED, PURPOSE_BIP44, PURPOSE_BIP84};
+
+        // pox-4 stack-stx on testnet, rewards paid to a P2WPKH address
+        let raw = "80800000000400d386442122c88878ae04c5726762477f4ef09ffe000000000000000300000000000000b4000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000030200000000021a000000000000000000000000000000000000000005706f782d3409737461636b2d737478000000040100000000000000000000001d1a94a2000c00000002096861736862797465730200000014751e76e8199196d454941c45d1b3a323f1433bd60776657273696f6e02000000010401000000000000000000000000000000640100000000000000000000000000000006";
+        let key_hash = hex::decode("751e76e8199196d454941c45d1b3a323f1433bd6").unwrap();
+        let bytes = hex::decode(raw).unwrap();
+        let mut parsed_obj = ParsedObj::from_bytes(&bytes).unwrap();
+        parsed_obj.read(&bytes).unwrap();
+        let num_items = parsed_obj.num_items().unwrap();
+
+        // the pox-addr argument is shown as a Bitcoin address
+        let mut key = [0u8; 30];
+        let mut value = [0u8; 64];
+        let idx = (0..num_items)
+            .find(|idx| {
+                parsed_obj.get_item(*idx, &mut key, &mut value, 0).unwrap();
+                key.starts_with(b"BTC reward address")
+            })
+            .unwrap();
+        assert_eq!(&value[..42], b"tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx");
+        assert_eq!(parsed_obj.item_kind(idx).unwrap(), ItemKind::Address);
+
+        // the key of the signing account, on the P2WPKH path
+        let tx = parsed_obj.transaction().unwrap();
+        let signer_path = [PURPOSE_BIP44, COIN_TESTNET, 5 | HARDENED, 0, 0];
+        assert_eq!(
+            tx.reward_key_path(&signer_path),
+            Some([PURPOSE_BIP84, COIN_TESTNET, 5 | HARDENED, 0, 0])
+        );
+        assert_eq!(
+            tx.reward_key_path(&[PURPOSE_BIP44, COIN_TESTNET, 5, 1, 0]),
+            None
+        );
+
+        tx.check_reward_address(Some(&key_hash));
+        assert_eq!(parsed_obj.num_items().unwrap(), num_items + 1);
+        assert_eq!(parsed_obj.item_kind(0).unwrap(), ItemKind::Text);
+        parsed_obj.get_item(0, &mut key, &mut value, 0).unwrap();
+        assert_eq!(&key[..15], b"Rewards paid to");
+        assert_eq!(&value[..11], b"This device");
+
+        // paid to another key
+        let tx = parsed_obj.transaction().unwrap();
+        tx.check_reward_address(Some(&[0x42; 20]));
+        assert_eq!(parsed_obj.item_kind(0).unwrap(), ItemKind::Warning);
+        parsed_obj.get_item(0, &mut key, &mut value, 0).unwrap();
+        assert_eq!(&value[..17], b"Unknown, check it");
+
+        // parsing again clears it
+        parsed_obj.read(&bytes).unwrap();
+        assert_eq!(parsed_obj.num_items().unwrap(), num_items);
+    }
+
+    #[test]
+    fn test_sponsored_contract_call_tx() {
+        let input_path = {
+            let mut r = PathBuf::new();
+            r.push(env!("CARGO_MANIFEST_DIR"));
+            r.push("tests");
+            r.push("sponsored_contract_call_testnet.json");
+            r.set_extension("json");
+            r
+        };
+        let str = std::fs::read_to_string(input_path).expect("Error opening json file");
+        let json: ContractCallTx = serde_json::from_str(&str).unwrap();
+        let bytes = hex::decode(&json.raw).unwrap();
+        let mut transaction = ParsedObj::from_bytes(&bytes).unwrap();
+        transaction.read(&bytes).unwrap();
+        ParsedObj::validate(&mut transaction).unwrap();
+        let transaction = transaction.transaction().unwrap();
+
+        assert!(!transaction.transaction_auth.is_standard_auth());
+        assert!(transaction.payload.is_contract_call_payload());
+        let contract_name = transaction.payload.contract_name().unwrap();
+        let contract_name = core::str::from_utf8(contract_name.name()).unwrap();
+        assert_eq!(json.contract_name, contract_name);
+
+        let function_name =
+            core::str::from_utf8(transaction.payload.function_name().unwrap()).unwrap();
+        assert_eq!(json.function_name, function_name);
+
+        // Test number of cuntion args
+        let num_args = transaction.payload.num_args().unwrap();
+        assert_eq!(json.num_args, num_args);
+
+        let origin = transaction.transaction_auth.origin();
+        let sponsor = transaction.transaction_auth.sponsor().unwrap();
+
+        // test Fee, Nonce of origin
+        assert_eq!(json.nonce, origin.nonce().unwrap());
+        assert_eq!(json.fee as u32, origin.fee().unwrap() as u32);
+
+        // Test origin and sponsor addresses
+        let origin_addr = origin.signer_address(transaction.version).unwrap();
+        let origin_addr = core::str::from_utf8(&origin_addr[..origin_addr.len()]).unwrap();
+        assert_eq!(json.sender, origin_addr);
+
+        let sponsor_addrs = sponsor.signer_address(transaction.version).unwrap();
+        let sponsor_addrs = core::str::from_utf8(&sponsor_addrs[..sponsor_addrs.len()]).unwrap();
+        assert_eq!(json.sponsor_addrs.unwrap(), sponsor_addrs);
+    }
+
+    #[test]
+    fn test_standard_contract_call_tx_with_7_postconditions() {
+        let input_path = {
+            let mut r = PathBuf::new();
+            r.push(env!("CARGO_MANIFEST_DIR"));
+            r.push("tests");
+            r.push("contract_call_with_7_postconditions");
+            r.set_extension("json");
+            r
+        };
+        let str = std::fs::read_to_string(input_path).expect("Error opening json file");
+        let json: ContractCallTx = serde_json::from_str(&str).unwrap();
+        let bytes = hex::decode(&json.raw).unwrap();
+        let mut transaction = ParsedObj::from_bytes(&bytes).unwrap();
+        transaction.read(&bytes).unwrap();
+        ParsedObj::validate(&mut transaction).unwrap();
+        let transaction = transaction.transaction().unwrap();
+
+        assert!(transaction.transaction_auth.is_standard_auth());
+        assert!(transaction.payload.is_contract_call_payload());
+        let contract_name = transaction.payload.contract_name().unwrap();
+        let contract_name = core::str::from_utf8(contract_name.name()).unwrap();
+        assert_eq!(json.contract_name, contract_name);
+
+        let function_name =
+            core::str::from_utf8(transaction.payload.function_name().unwrap()).unwrap();
+        assert_eq!(json.function_name, function_name);
+
+        let num_args = transaction.payload.num_args().unwrap();
+        assert_eq!(json.num_args, num_args);
+
+        let origin = transaction.transaction_auth.origin();
+
+        assert_eq!(json.nonce, origin.nonce().unwrap());
+        assert_eq!(json.fee as u32, origin.fee().unwrap() as u32);
+
+        let origin_addr = origin.signer_address(transaction.version).unwrap();
+        let origin_addr = core::str::from_utf8(&origin_addr[..origin_addr.len()]).unwrap();
+        assert_eq!(json.sender, origin_addr);
+
+        assert_eq!(transaction.payload_id(), TransactionPayloadId::ContractCall);
+        assert_eq!(transaction.multisig_threshold(), None);
+        assert_eq!(transaction.num_post_conditions(), 7);
+
+        let post_conditions: Vec<&[u8]> = transaction.post_conditions.iter().collect();
+        assert_eq!(post_conditions.len(), 7);
+        let condition = TransactionPostCondition::from_bytes(post_conditions[0])
+            .unwrap()
+            .1;
+        assert!(condition.is_fungible());
+        let addr = condition.get_principal_address().unwrap();
+        let principal_addr = core::str::from_utf8(&addr[..addr.len()]).unwrap();
+        assert_eq!(json.post_condition_principal, Some(principal_addr.into()));
+    }
+
+    #[test]
+    fn parse_contract_call_tx() {
+        let bytes_str = "0000000001040061e115b4463fb27425e80fa8e3e2616b4e5a17e40000000000000011000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000003020000000200021661e115b4463fb27425e80fa8e3e2616b4e5a17e40500000000000f4240010316e685b016b3b6cd9ebf35f38e5ae29392e2acd51d0a616c65782d7661756c7416e685b016b3b6cd9ebf35f38e5ae29392e2acd51d176167653030302d676f7665726e616e63652d746f6b656e04616c657803000000001a6e83360216e685b016b3b6cd9ebf35f38e5ae29392e2acd51d11737761702d68656c7065722d76312d30330b737761702d68656c706572000000040616e685b016b3b6cd9ebf35f38e5ae29392e2acd51d0a746f6b656e2d777374780616e685b016b3b6cd9ebf35f38e5ae29392e2acd51d176167653030302d676f7665726e616e63652d746f6b656e0100000000000000000000000005f5e1000a010000000000000000000000001a6e8336";
+        let bytes = hex::decode(&bytes_str).unwrap();
+
+        let mut transaction = ParsedObj::from_bytes(&bytes).unwrap();
+        transaction.read(&bytes).unwrap();
+        ParsedObj::validate(&mut transaction).unwrap();
+
+        let transaction = transaction.transaction().unwrap();
+        assert_eq!(transaction.origin_fee(), 0);
+    }
+
+    #[test]
+    fn parse_structured_msg() {
+        let input = "5349503031380c0000000308636861696e2d69640100000000000000000000000000025983046e616d650d00000006537461636b730776657273696f6e0d00000005312e302e300c00000008016100ffffffffffffffffffffffffffffffff01620200000008616263646566676808636861696e2d69640100000000000000000000000000000001016d0b0000000400ffffffffffffffffffffffffffffffff00ffffffffffffffffffffffffffffffff00ffffffffffffffffffffffffffffffff00ffffffffffffffffffffffffffffffff046e616d650c0000000308636861696e2d69640100000000000000000000000000025983046e616d650d00000006537461636b730776657273696f6e0d00000005312e302e30057475706c650c0000000308636861696e2d69640100000000000000000000000000025983046e616d650c0000000308636861696e2d69640100000000000000000000000000025983046e616d650d00000006537461636b730776657273696f6e0d00000005312e302e300776657273696f6e0d00000005312e302e30067475706c65320c0000000308636861696e2d69640100000000000000000000000000025983046e616d650d00000006537461636b730776657273696f6e0d00000005312e302e300776657273696f6e0b000000020c0000000308636861696e2d69640100000000000000000000000000025983046e616d650c0000000308636861696e2d69640100000000000000000000000000025983046e616d650d00000006537461636b730776657273696f6e0d00000005312e302e300776657273696f6e0b000000050c0000000308636861696e2d69640100000000000000000000000000025983046e616d650d00000006537461636b730776657273696f6e0d00000005312e302e300c0000000308636861696e2d69640100000000000000000000000000025983046e616d650d00000006537461636b730776657273696f6e0d00000005312e302e300c0000000308636861696e2d69640100000000000000000000000000025983046e616d650d00000006537461636b730776657273696f6e0d00000005312e302e300c0000000308636861696e2d69640100000000000000000000000000025983046e616d650d00000006537461636b730776657273696f6e0d00000005312e302e300c0000000308636861696e2d69640100000000000000000000000000025983046e616d650d00000006537461636b730776657273696f6e0d00000005312e302e300c0000000308636861696e2d69640100000000000000000000000000025983046e616d650c0000000308636861696e2d69640100000000000000000000000000025983046e616d650d00000006537461636b730776657273696f6e0d00000005312e302e300776657273696f6e0b000000050c0000000308636861696e2d69640100000000000000000000000000025983046e616d650d00000006537461636b730776657273696f6e0d00000005312e302e300c0000000308636861696e2d69640100000000000000000000000000025983046e616d650d00000006537461636b730776657273696f6e0d00000005312e302e300c0000000308636861696e2d69640100000000000000000000000000025983046e616d650d00000006537461636b730776657273696f6e0d00000005312e302e300c0000000308636861696e2d69640100000000000000000000000000025983046e616d650d00000006537461636b730776657273696f6e0d00000005312e302e300c0000000308636861696e2d69640100000000000000000000000000025983046e616d650d00000006537461636b730776657273696f6e0d00000005312e302e30";
+        let bytes = hex::decode(input).unwrap();
+        let mut msg = ParsedObj::from_bytes(&bytes).unwrap();
+        msg.read(&bytes).unwrap();
+        ParsedObj::validate(&mut msg).unwrap();
+    }
+
+    #[test]
+    fn test_parse_diagnostic() {
+        let bytes_str = "0000000001040061e115b4463fb27425e80fa8e3e2616b4e5a17e40000000000000011000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000003020000000200021661e115b4463fb27425e80fa8e3e2616b4e5a17e40500000000000f4240010316e685b016b3b6cd9ebf35f38e5ae29392e2acd51d0a616c65782d7661756c7416e685b016b3b6cd9ebf35f38e5ae29392e2acd51d176167653030302d676f7665726e616e63652d746f6b656e04616c657803000000001a6e83360216e685b016b3b6cd9ebf35f38e5ae29392e2acd51d11737761702d68656c7065722d76312d30330b737761702d68656c706572000000040616e685b016b3b6cd9ebf35f38e5ae29392e2acd51d0a746f6b656e2d777374780616e685b016b3b6cd9ebf35f38e5ae29392e2acd51d176167653030302d676f7665726e616e63652d746f6b656e0100000000000000000000000005f5e1000a010000000000000000000000001a6e8336";
+        let bytes = hex::decode(bytes_str).unwrap();
+        assert_eq!(ParsedObj::diagnose(&bytes), None);
+
+        let diagnose = |at: usize, byte: u8| {
+            let mut bytes = bytes.clone();
+            bytes[at] = byte;
+            let diagnostic = ParsedObj::diagnose(&bytes).unwrap();
+            (diagnostic.section, diagnostic.offset, diagnostic.error)
+        };
+
+        // the auth type
+        assert_eq!(
+            diagnose(5, 0x07),
+            (ParseSection::Auth, 5, ParserError::parser_invalid_auth_type)
+        );
+        // the hash mode of the origin, 0x05 and 0x07 are order independent
+        assert_eq!(
+            diagnose(6, 0x04),
+            (ParseSection::Auth, 6, ParserError::parser_invalid_hash_mode)
+        );
+        // the number of post conditions
+        assert_eq!(
+            diagnose(114, 0xff),
+            (
+                ParseSection::PostConditions,
+                111,
+                ParserError::parser_limit_exceeded
+            )
+        );
+        // the type of the second post condition
+        assert_eq!(
+            diagnose(147, 0x07),
+            (
+                ParseSection::PostConditions,
+                147,
+                ParserError::parser_invalid_post_condition
+            )
+        );
+        // the payload type
+        assert_eq!(
+            diagnose(240, 0x0f),
+            (
+                ParseSection::Payload,
+                240,
+                ParserError::parser_invalid_transaction_payload
+            )
+        );
+
+        // a truncated transaction fails where the bytes run out
+        let diagnostic = ParsedObj::diagnose(&bytes[..113]).unwrap();
+        assert_eq!(diagnostic.section, ParseSection::PostConditions);
+        assert_eq!(diagnostic.offset, 111);
+        assert_eq!(diagnostic.error, ParserError::parser_unexpected_buffer_end);
+
+        // bytes after the payload fail where they start
+        let mut trailing = bytes.clone();
+        trailing.push(0x00);
+        let diagnostic = ParsedObj::diagnose(&trailing).unwrap();
+        assert_eq!(diagnostic.section, ParseSection::Payload);
+        assert_eq!(diagnostic.offset as usize, bytes.len());
+        assert_eq!(diagnostic.error, ParserError::parser_trailing_bytes);
+    }
+
+    #[test]
+    fn test_trailing_bytes() {
+        let vector = |name: &str| {
+            let mut r = PathBuf::new();
+            r.push(env!("CARGO_MANIFEST_DIR"));
+            r.push("tests");
+            r.push(name);
+            r.set_extension("json");
+            let str = std::fs::read_to_string(r).expect("Error opening json file");
+            let json: StxTransaction = serde_json::from_str(&str).unwrap();
+            hex::decode(&json.raw).unwrap()
+        };
+        let read = |bytes: &[u8]| {
+            let mut parsed_obj = ParsedObj::from_bytes(bytes)?;
+            parsed_obj.read(bytes)
+        };
+        let append = |bytes: &[u8], len: usize| {
+            let mut appended = bytes.to_vec();
+            appended.resize(bytes.len() + len, 0);
+            appended
+        };
+
+        // nothing may follow a singlesig transaction, it would be signed unseen
+        let singlesig = vector("stx_token_transfer");
+        assert_eq!(read(&singlesig), Ok(()));
+        for len in [1, 98] {
+            assert_eq!(
+                read(&append(&singlesig, len)),
+                Err(ParserError::parser_trailing_bytes)
+            );
+        }
+
+        // a multisig one may be followed by the previous signer data only
+        let appended = vector("stx_multisig_token_transfer");
+        let unsigned = &appended[..appended.len() - 98];
+        assert_eq!(read(&appended), Ok(()));
+        assert_eq!(read(unsigned), Ok(()));
+        for len in [1, 97, 99] {
+            assert_eq!(
+                read(&append(unsigned, len)),
+                Err(ParserError::parser_trailing_bytes)
+            );
+        }
+    }
+}
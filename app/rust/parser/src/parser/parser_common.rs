@@ -0,0 +1,540 @@
+#![allow(clippy::upper_case_acronyms)]
+use core::convert::{TryFrom, TryInto};
+
+use crate::parser::reader::{le_u8, take};
+
+use crate::parser::c32::{self, C32Address};
+use crate::parser::error::ParserError;
+use crate::parser::principal::PrincipalTable;
+
+// The max len for asset, contract and clarity names
+pub const MAX_STRING_LEN: u8 = 128;
+pub const HASH160_LEN: usize = 20;
+pub const STACKS_ADDR_LEN: usize = HASH160_LEN + 1;
+
+// The conversion constant between microSTX to STX
+pub const STX_DECIMALS: u8 = 6;
+
+pub const C32_ENCODED_ADDRS_LENGTH: usize = 48;
+
+pub const SIGNATURE_LEN: usize = 65;
+pub const TOKEN_TRANSFER_MEMO_LEN: usize = 34;
+
+// The bytes of the contract principal digest
+// kept for each entry of the contract denylist
+pub const DENYLIST_ENTRY_LEN: usize = 20;
+
+/// Stacks transaction versions
+#[repr(u8)]
+#[derive(Clone, PartialEq, Copy)]
+#[cfg_attr(test, derive(Debug))]
+pub enum TransactionVersion {
+    Mainnet = 0x00,
+    Testnet = 0x80,
+}
+
+impl TryFrom<u8> for TransactionVersion {
+    type Error = ParserError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0x00 => Ok(Self::Mainnet),
+            0x80 => Ok(Self::Testnet),
+            _ => Err(ParserError::parser_unexpected_value),
+        }
+    }
+}
+
+impl TransactionVersion {
+    #[inline(never)]
+    pub fn from_bytes(bytes: &[u8]) -> Result<(&[u8], Self), nom::Err<ParserError>> {
+        let (rem, version) = le_u8(bytes)?;
+        let tx_version = Self::try_from(version)?;
+        Ok((rem, tx_version))
+    }
+}
+
+#[repr(u8)]
+#[derive(Clone, PartialEq, Copy)]
+#[cfg_attr(test, derive(Debug))]
+pub enum AssetInfoId {
+    STX = 0,
+    FungibleAsset = 1,
+    NonfungibleAsset = 2,
+}
+
+impl TryFrom<u8> for AssetInfoId {
+    type Error = ParserError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        let s = match value {
+            0 => AssetInfoId::STX,
+            1 => AssetInfoId::FungibleAsset,
+            2 => AssetInfoId::NonfungibleAsset,
+            _ => return Err(ParserError::parser_unexpected_value),
+        };
+        Ok(s)
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, PartialEq)]
+#[cfg_attr(test, derive(Debug))]
+pub struct AssetInfo<'a> {
+    pub address: StacksAddress<'a>,
+    pub contract_name: ContractName<'a>,
+    pub asset_name: ClarityName<'a>,
+}
+
+impl<'a> AssetInfo<'a> {
+    #[inline(never)]
+    pub fn from_bytes(bytes: &'a [u8]) -> nom::IResult<&[u8], Self, ParserError> {
+        let address = StacksAddress::from_bytes(bytes)?;
+        let (raw, contract_name) = ContractName::from_bytes(address.0)?;
+        let asset_name = ClarityName::from_bytes(raw)?;
+        Ok((
+            asset_name.0,
+            Self {
+                address: address.1,
+                contract_name,
+                asset_name: asset_name.1,
+            },
+        ))
+    }
+
+    #[inline(never)]
+    pub fn read_as_bytes(bytes: &'a [u8]) -> nom::IResult<&[u8], &[u8], ParserError> {
+        let (raw, _) = StacksAddress::from_bytes(bytes)?;
+        let (raw1, _) = ContractName::from_bytes(raw)?;
+        let (raw2, _) = ClarityName::from_bytes(raw1)?;
+        let len = bytes.len() - raw2.len();
+        let (left, inner) = take(len)(bytes)?;
+        Ok((left, inner))
+    }
+
+    pub fn asset_name(&self) -> &[u8] {
+        self.asset_name.0
+    }
+}
+
+#[repr(u8)]
+#[derive(Clone, PartialEq, Copy)]
+#[cfg_attr(test, derive(Debug))]
+// Flag used to know if the signer is valid and
+// who is
+pub enum SignerId {
+    Origin,
+    Sponsor,
+    Invalid,
+}
+
+#[repr(u8)]
+#[derive(Clone, PartialEq, Copy)]
+#[cfg_attr(test, derive(Debug))]
+// The review section an item belongs to, large screen devices
+// use it to group items instead of showing a flat list
+pub enum ItemSection {
+    Details = 0,
+    Amounts,
+    Recipient,
+    Fees,
+    PostConditions,
+}
+
+#[repr(u8)]
+#[derive(Clone, PartialEq, Copy)]
+#[cfg_attr(test, derive(Debug))]
+// The part of a transaction a parse error was found in, the anchor
+// and post condition modes belong to the post conditions
+pub enum ParseSection {
+    Header = 0,
+    Auth,
+    PostConditions,
+    Payload,
+}
+
+/// Where parsing a transaction failed: the section, the offset of the
+/// first byte of the field or element that could not be parsed and the
+/// error found there
+#[repr(C)]
+#[derive(Clone, PartialEq, Copy)]
+#[cfg_attr(test, derive(Debug))]
+pub struct ParseDiagnostic {
+    pub error: ParserError,
+    pub section: ParseSection,
+    pub offset: u32,
+}
+
+#[repr(u8)]
+#[derive(Clone, PartialEq, Copy)]
+#[cfg_attr(test, derive(Debug))]
+// What an item value holds, the UI uses it to
+// choose how to format or present each item
+pub enum ItemKind {
+    Text = 0,
+    Amount,
+    Address,
+    ContractName,
+    Warning,
+    RawHex,
+    Memo,
+}
+
+// tag address hash modes as "singlesig" or "multisig" so we can't accidentally construct an
+// invalid spending condition. A new mode is a variant here and a row of HASH_MODES
+#[repr(u8)]
+#[derive(Clone, Copy, PartialEq)]
+#[cfg_attr(test, derive(Debug))]
+pub enum HashMode {
+    P2PKH = 0x00,
+    P2SH = 0x01,
+    P2WPKH = 0x02,
+    P2WSH = 0x03,
+    // SIP-027 order independent multisig, every signer signs the same hash
+    P2SHNonSequential = 0x05,
+    P2WSHNonSequential = 0x07,
+}
+
+/// What the parser needs to know of a hash mode
+#[derive(Clone, Copy, PartialEq)]
+#[cfg_attr(test, derive(Debug))]
+pub struct HashModeInfo {
+    pub mode: HashMode,
+    /// The auth fields and threshold of a multisig account follow the signer
+    pub multisig: bool,
+    /// The signers of a multisig account sign one after the other, each
+    /// signing the hash the previous signatures lead to
+    pub sequential: bool,
+    /// The account only takes compressed public keys
+    pub segwit: bool,
+    pub version_mainnet: u8,
+    pub version_testnet: u8,
+}
+
+impl HashModeInfo {
+    const fn singlesig(mode: HashMode, segwit: bool) -> Self {
+        Self {
+            mode,
+            multisig: false,
+            sequential: false,
+            segwit,
+            version_mainnet: c32::C32_ADDRESS_VERSION_MAINNET_SINGLESIG,
+            version_testnet: c32::C32_ADDRESS_VERSION_TESTNET_SINGLESIG,
+        }
+    }
+
+    const fn multisig(mode: HashMode, sequential: bool, segwit: bool) -> Self {
+        Self {
+            mode,
+            multisig: true,
+            sequential,
+            segwit,
+            version_mainnet: c32::C32_ADDRESS_VERSION_MAINNET_MULTISIG,
+            version_testnet: c32::C32_ADDRESS_VERSION_TESTNET_MULTISIG,
+        }
+    }
+}
+
+// One row per hash mode, the modes not in it are refused when read
+pub const HASH_MODES: [HashModeInfo; 6] = [
+    HashModeInfo::singlesig(HashMode::P2PKH, false),
+    HashModeInfo::multisig(HashMode::P2SH, true, false),
+    HashModeInfo::singlesig(HashMode::P2WPKH, true),
+    HashModeInfo::multisig(HashMode::P2WSH, true, true),
+    HashModeInfo::multisig(HashMode::P2SHNonSequential, false, false),
+    HashModeInfo::multisig(HashMode::P2WSHNonSequential, false, true),
+];
+
+impl TryFrom<u8> for HashMode {
+    type Error = ParserError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        HASH_MODES
+            .iter()
+            .find(|info| info.mode as u8 == value)
+            .map(|info| info.mode)
+            .ok_or(ParserError::parser_invalid_hash_mode)
+    }
+}
+
+impl HashMode {
+    /// The row of the mode in HASH_MODES, every mode has one
+    pub fn info(self) -> HashModeInfo {
+        HASH_MODES
+            .iter()
+            .find(|info| info.mode == self)
+            .copied()
+            .unwrap_or(HASH_MODES[0])
+    }
+
+    pub fn is_multisig(self) -> bool {
+        self.info().multisig
+    }
+
+    pub fn is_sequential(self) -> bool {
+        self.info().sequential
+    }
+
+    pub fn is_segwit(self) -> bool {
+        self.info().segwit
+    }
+
+    pub fn to_version_mainnet(self) -> u8 {
+        self.info().version_mainnet
+    }
+
+    pub fn to_version_testnet(self) -> u8 {
+        self.info().version_testnet
+    }
+}
+
+// contract name with valid charactes being
+// ^[a-zA-Z]([a-zA-Z0-9]|[-_])*$
+#[repr(C)]
+#[derive(Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(test, derive(Debug))]
+pub struct ContractName<'a>(ClarityName<'a>);
+
+impl<'a> ContractName<'a> {
+    #[inline(never)]
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<(&[u8], Self), nom::Err<ParserError>> {
+        let (rem, name) = ClarityName::from_bytes(bytes)?;
+
+        // we are using the ClarityName inner type to wrap-up the parsing which is the same for
+        // Contract names, only their grammar is narrower
+        if !is_contract_name(name.0) {
+            return Err(ParserError::parser_invalid_contract_name.into());
+        }
+
+        Ok((rem, Self(name)))
+    }
+
+    pub fn name(&'a self) -> &'a [u8] {
+        self.0.name()
+    }
+
+    pub fn len(&self) -> usize {
+        self.name().len()
+    }
+}
+
+// ^[a-zA-Z]([a-zA-Z0-9]|[-_])*$ and at most MAX_STRING_LEN bytes
+fn is_contract_name(name: &[u8]) -> bool {
+    match name.split_first() {
+        Some((first, rest)) if name.len() <= MAX_STRING_LEN as usize => {
+            first.is_ascii_alphabetic()
+                && rest
+                    .iter()
+                    .all(|c| c.is_ascii_alphanumeric() || b"-_".contains(c))
+        }
+        _ => false,
+    }
+}
+
+// ^[a-zA-Z]([a-zA-Z0-9]|[-_!?+<>=/*])*$|^[-+=/*]$|^[<>]=?$
+// and at most MAX_STRING_LEN bytes
+pub(crate) fn is_clarity_name(name: &[u8]) -> bool {
+    if name.len() > MAX_STRING_LEN as usize {
+        return false;
+    }
+    match name {
+        [c] if b"-+=/*<>".contains(c) => true,
+        [c, b'='] if b"<>".contains(c) => true,
+        [first, rest @ ..] => {
+            first.is_ascii_alphabetic()
+                && rest
+                    .iter()
+                    .all(|c| c.is_ascii_alphanumeric() || b"-_!?+<>=/*".contains(c))
+        }
+        [] => false,
+    }
+}
+
+// A clarity value used in tuples
+#[repr(C)]
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(test, derive(Debug))]
+pub struct ClarityName<'a>(pub &'a [u8]);
+
+impl<'a> ClarityName<'a> {
+    pub const MAX_LEN: u8 = 128;
+
+    #[inline(never)]
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<(&[u8], Self), nom::Err<ParserError>> {
+        let (rem, name) = Self::read_as_bytes(bytes)?;
+        // Omit the first byte as it is the encoded length
+        Ok((rem, Self(name.get(1..).unwrap_or_default())))
+    }
+
+    #[inline(never)]
+    pub fn read_as_bytes(bytes: &'a [u8]) -> Result<(&[u8], &[u8]), nom::Err<ParserError>> {
+        let (_, len) = le_u8(bytes)?;
+
+        if len > Self::MAX_LEN {
+            return Err(ParserError::parser_value_out_of_range.into());
+        }
+
+        // include the 1-byte len
+        take(len + 1)(bytes)
+    }
+
+    /// Reads a name that has to follow the Clarity name grammar,
+    /// as function names do
+    #[inline(never)]
+    pub fn from_bytes_checked(bytes: &'a [u8]) -> Result<(&'a [u8], Self), nom::Err<ParserError>> {
+        let (rem, name) = Self::from_bytes(bytes)?;
+        if !is_clarity_name(name.0) {
+            return Err(ParserError::parser_invalid_clarity_name.into());
+        }
+        Ok((rem, name))
+    }
+
+    pub fn name(&'a self) -> &'a [u8] {
+        self.0
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, PartialEq)]
+#[cfg_attr(test, derive(Debug))]
+// we take HASH160_LEN + 1-byte hash mode
+pub struct StacksAddress<'a>(pub &'a [u8; STACKS_ADDR_LEN]);
+
+impl<'a> StacksAddress<'a> {
+    #[inline(never)]
+    pub fn from_bytes(bytes: &'a [u8]) -> nom::IResult<&[u8], Self, ParserError> {
+        let (raw, address) = take(STACKS_ADDR_LEN)(bytes)?;
+        let address = address
+            .try_into()
+            .map_err(|_| ParserError::parser_unexpected_buffer_end)?;
+        Ok((raw, Self(address)))
+    }
+
+    pub fn encoded_address(
+        &self,
+    ) -> Result<arrayvec::ArrayVec<[u8; C32_ENCODED_ADDRS_LENGTH]>, ParserError> {
+        c32::c32_address(self.0[0], &self.0[1..])
+    }
+
+    pub fn c32_address(&self) -> Result<C32Address, ParserError> {
+        C32Address::new(self.0[0], &self.0[1..])
+    }
+
+    /// Returns the encoded address, taken from principals if it was encoded before
+    pub fn interned_address(&self, principals: &PrincipalTable) -> Result<C32Address, ParserError> {
+        principals.address(self.0[0], &self.0[1..])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::vec::Vec;
+
+    // the name length prefixed, as it is serialized
+    fn encoded(name: &[u8]) -> Vec<u8> {
+        let mut bytes = std::vec![name.len() as u8];
+        bytes.extend_from_slice(name);
+        bytes
+    }
+
+    #[test]
+    fn test_hash_mode_table() {
+        // a mode is read back as itself and found in its own row
+        for info in HASH_MODES.iter() {
+            assert_eq!(HashMode::try_from(info.mode as u8), Ok(info.mode));
+            assert_eq!(info.mode.info(), *info);
+            assert!(info.multisig || !info.sequential);
+        }
+        let known = (0..=u8::MAX)
+            .filter(|b| HashMode::try_from(*b).is_ok())
+            .count();
+        assert_eq!(known, HASH_MODES.len());
+        assert_eq!(
+            HashMode::try_from(0x04),
+            Err(ParserError::parser_invalid_hash_mode)
+        );
+
+        assert!(!HashMode::P2WPKH.is_multisig() && HashMode::P2WPKH.is_segwit());
+        assert!(HashMode::P2SH.is_sequential() && !HashMode::P2SHNonSequential.is_sequential());
+        assert_eq!(
+            HashMode::P2WSHNonSequential.to_version_mainnet(),
+            c32::C32_ADDRESS_VERSION_MAINNET_MULTISIG
+        );
+        assert_eq!(
+            HashMode::P2PKH.to_version_testnet(),
+            c32::C32_ADDRESS_VERSION_TESTNET_SINGLESIG
+        );
+    }
+
+    #[test]
+    fn test_contract_name_grammar() {
+        let longest = [b'a'; MAX_STRING_LEN as usize];
+        for name in [&b"a"[..], b"alex-vault", b"pox_4", b"A1", &longest] {
+            let bytes = encoded(name);
+            let (rem, parsed) = ContractName::from_bytes(&bytes).unwrap();
+            assert!(rem.is_empty());
+            assert_eq!(parsed.name(), name);
+        }
+
+        for name in [
+            &b""[..],
+            b"1abc",
+            b"-abc",
+            b"ab.c",
+            b"ab?",
+            "caf\u{e9}".as_bytes(),
+        ] {
+            assert_eq!(
+                ContractName::from_bytes(&encoded(name)),
+                Err(nom::Err::Error(ParserError::parser_invalid_contract_name))
+            );
+        }
+
+        let mut too_long = encoded(&longest);
+        too_long[0] += 1;
+        too_long.push(b'a');
+        assert!(ContractName::from_bytes(&too_long).is_err());
+    }
+
+    #[test]
+    fn test_clarity_name_grammar() {
+        for name in [
+            &b"transfer"[..],
+            b"stack-stx",
+            b"is-ok?",
+            b"set!",
+            b"a->b",
+            b"x/y*z",
+            b"+",
+            b"<=",
+            b">",
+        ] {
+            let bytes = encoded(name);
+            assert_eq!(
+                ClarityName::from_bytes_checked(&bytes).unwrap().1.name(),
+                name
+            );
+        }
+
+        for name in [
+            &b""[..],
+            b"2x",
+            b"-x",
+            b"<<",
+            b"=>",
+            b"a b",
+            b"a.b",
+            b"na\xefve",
+        ] {
+            assert_eq!(
+                ClarityName::from_bytes_checked(&encoded(name)),
+                Err(nom::Err::Error(ParserError::parser_invalid_clarity_name))
+            );
+        }
+    }
+}
@@ -1,16 +1,16 @@
+use super::reader::{be_u64, le_u8, take};
 use arrayvec::ArrayVec;
 use core::{convert::TryFrom, fmt::Write};
-use nom::{
-    bytes::complete::take,
-    number::complete::{be_u64, le_u8},
-};
 
 use super::error::ParserError;
 
 use super::parser_common::{
-    AssetInfo, ContractName, StacksAddress, C32_ENCODED_ADDRS_LENGTH, HASH160_LEN, STX_DECIMALS,
-    TX_DEPTH_LIMIT,
+    AssetInfo, ContractName, ItemKind, StacksAddress, C32_ENCODED_ADDRS_LENGTH, HASH160_LEN,
 };
+use super::limits::TX_DEPTH_LIMIT;
+use super::principal::PrincipalTable;
+use super::token_info::{TokenInfo, BTC_DECIMALS};
+use crate::lang::LABELS;
 use crate::parser::value::Value;
 use crate::zxformat;
 
@@ -104,7 +104,9 @@ impl<'a> PostConditionPrincipal<'a> {
     pub fn origin_address(
     ) -> Result<arrayvec::ArrayVec<[u8; C32_ENCODED_ADDRS_LENGTH]>, ParserError> {
         let mut output: ArrayVec<[_; C32_ENCODED_ADDRS_LENGTH]> = ArrayVec::new();
-        output.try_extend_from_slice(b"Origin".as_ref()).unwrap();
+        output
+            .try_extend_from_slice(LABELS.origin_principal.as_bytes())
+            .map_err(|_| ParserError::parser_unexpected_buffer_end)?;
         Ok(output)
     }
 
@@ -119,6 +121,24 @@ impl<'a> PostConditionPrincipal<'a> {
         }
     }
 
+    /// Writes the page_idx page of the principal address into out_value,
+    /// the address is taken from principals if it was encoded before
+    pub fn page_address(
+        &self,
+        principals: &PrincipalTable,
+        out_value: &mut [u8],
+        page_idx: u8,
+    ) -> Result<u8, ParserError> {
+        match self {
+            Self::Origin => {
+                zxformat::pageString(out_value, LABELS.origin_principal.as_bytes(), page_idx)
+            }
+            Self::Standard(ref address) | Self::Contract(ref address, _) => address
+                .interned_address(principals)?
+                .page(out_value, page_idx),
+        }
+    }
+
     pub fn get_contract_name(&'a self) -> Option<&'a [u8]> {
         match self {
             Self::Contract(_, name) => Some(name.name()),
@@ -152,11 +172,11 @@ impl FungibleConditionCode {
 
     pub fn to_str(self) -> &'static str {
         match self {
-            FungibleConditionCode::SentEq => "SentEq",
-            FungibleConditionCode::SentGt => "SentGt",
-            FungibleConditionCode::SentGe => "SentGe",
-            FungibleConditionCode::SentLt => "SentLt",
-            FungibleConditionCode::SentLe => "SentLe",
+            FungibleConditionCode::SentEq => LABELS.sent_eq,
+            FungibleConditionCode::SentGt => LABELS.sent_gt,
+            FungibleConditionCode::SentGe => LABELS.sent_ge,
+            FungibleConditionCode::SentLt => LABELS.sent_lt,
+            FungibleConditionCode::SentLe => LABELS.sent_le,
         }
     }
 }
@@ -180,8 +200,8 @@ impl NonfungibleConditionCode {
 
     pub fn to_str(self) -> &'static str {
         match self {
-            Self::Sent => "Sent",
-            Self::NotSent => "NotSent",
+            Self::Sent => LABELS.sent,
+            Self::NotSent => LABELS.not_sent,
         }
     }
 }
@@ -225,26 +245,48 @@ impl<'a> TransactionPostCondition<'a> {
         let (raw, cond_type) = le_u8(bytes)?;
         let principal = PostConditionPrincipal::read_as_bytes(raw)?;
         let principal_len = raw.len() - principal.0.len();
+        let cond_type = PostConditionType::try_from(cond_type)?;
 
-        match PostConditionType::try_from(cond_type)? {
+        let (raw, condition) = match cond_type {
             PostConditionType::STX => {
                 let len = principal_len + 1 + 8;
                 let (raw, inner) = take(len)(raw)?;
-                Ok((raw, Self::STX(inner)))
+                (raw, Self::STX(inner))
             }
             PostConditionType::FungibleToken => {
                 let asset = AssetInfo::read_as_bytes(principal.0)?;
                 let len = principal_len + asset.1.len() + 1 + 8;
                 let (raw, inner) = take(len)(raw)?;
-                Ok((raw, Self::Fungible(inner)))
+                (raw, Self::Fungible(inner))
             }
             PostConditionType::NonFungibleToken => {
                 let asset = AssetInfo::read_as_bytes(principal.0)?;
                 let value_len = Value::value_len::<TX_DEPTH_LIMIT>(asset.0)?;
                 let len = principal_len + asset.1.len() + value_len + 1;
                 let (raw, inner) = take(len)(raw)?;
-                Ok((raw, Self::Nonfungible(inner)))
+                (raw, Self::Nonfungible(inner))
             }
+        };
+        Self::check_code(cond_type, condition.get_inner_bytes())?;
+        Ok((raw, condition))
+    }
+
+    // The condition code is before the 8-byte amount of stx and fungible
+    // conditions and it is the last byte of non fungible ones
+    fn check_code(cond_type: PostConditionType, condition: &[u8]) -> Result<(), ParserError> {
+        match cond_type {
+            PostConditionType::STX | PostConditionType::FungibleToken => condition
+                .len()
+                .checked_sub(9)
+                .and_then(|at| condition.get(at))
+                .and_then(|code| FungibleConditionCode::from_u8(*code))
+                .map(|_| ())
+                .ok_or(ParserError::parser_invalid_fungible_code),
+            PostConditionType::NonFungibleToken => condition
+                .last()
+                .and_then(|code| NonfungibleConditionCode::from_u8(*code))
+                .map(|_| ())
+                .ok_or(ParserError::parser_invalid_non_fungible_code),
         }
     }
 
@@ -252,7 +294,8 @@ impl<'a> TransactionPostCondition<'a> {
         let cond_type = le_u8(bytes)?;
         let (raw, _) = PostConditionPrincipal::read_as_bytes(cond_type.0)?;
         let mut len = bytes.len() - raw.len();
-        len += match PostConditionType::try_from(cond_type.1)? {
+        let cond_type = PostConditionType::try_from(cond_type.1)?;
+        len += match cond_type {
             PostConditionType::STX => {
                 // We take 9-bytes which comprises the 8-byte amount + 1-byte fungible code
                 9usize
@@ -269,13 +312,15 @@ impl<'a> TransactionPostCondition<'a> {
             }
         };
         crate::check_canary!();
-        take(len)(bytes)
+        let (rem, condition) = take(len)(bytes)?;
+        Self::check_code(cond_type, condition)?;
+        Ok((rem, condition))
     }
 
     pub fn is_origin_principal(&self) -> bool {
         match self {
             Self::STX(principal) | Self::Fungible(principal) | Self::Nonfungible(principal) => {
-                principal[0] == PostConditionPrincipalId::Origin as u8
+                principal.first() == Some(&(PostConditionPrincipalId::Origin as u8))
             }
         }
     }
@@ -283,7 +328,7 @@ impl<'a> TransactionPostCondition<'a> {
     pub fn is_standard_principal(&self) -> bool {
         match self {
             Self::STX(principal) | Self::Fungible(principal) | Self::Nonfungible(principal) => {
-                principal[0] == PostConditionPrincipalId::Standard as u8
+                principal.first() == Some(&(PostConditionPrincipalId::Standard as u8))
             }
         }
     }
@@ -291,7 +336,7 @@ impl<'a> TransactionPostCondition<'a> {
     pub fn is_contract_principal(&self) -> bool {
         match self {
             Self::STX(principal) | Self::Fungible(principal) | Self::Nonfungible(principal) => {
-                principal[0] == PostConditionPrincipalId::Origin as u8
+                principal.first() == Some(&(PostConditionPrincipalId::Contract as u8))
             }
         }
     }
@@ -323,64 +368,55 @@ impl<'a> TransactionPostCondition<'a> {
 
     pub fn tokens_amount(&self) -> Option<u64> {
         match *self {
-            Self::STX(inner) | Self::Fungible(inner) => {
-                let at = inner.len() - 8;
-                be_u64::<'a, ParserError>(&inner[at..])
-                    .map(|res| res.1)
-                    .ok()
-            }
+            Self::STX(inner) | Self::Fungible(inner) => Self::amount_at_end(inner),
             _ => None,
         }
     }
 
     pub fn amount_stx(&self) -> Option<u64> {
         match self {
-            Self::STX(inner) => {
-                let at = inner.len() - 8;
-                be_u64::<'a, ParserError>(&inner[at..])
-                    .map(|res| res.1)
-                    .ok()
-            }
+            Self::STX(inner) => Self::amount_at_end(inner),
             _ => None,
         }
     }
 
-    pub fn tokens_amount_str(&self) -> Option<ArrayVec<[u8; zxformat::MAX_STR_BUFF_LEN]>> {
-        let mut output = ArrayVec::from([0u8; zxformat::MAX_STR_BUFF_LEN]);
+    // the 8-byte amount closes stx and fungible conditions
+    fn amount_at_end(inner: &[u8]) -> Option<u64> {
+        let at = inner.len().checked_sub(8)?;
+        be_u64(inner.get(at..)?).map(|res| res.1).ok()
+    }
 
+    /// Formats the token amount into out, returning the part written
+    pub fn tokens_amount_str<'b>(&self, out: &'b mut [u8]) -> Option<&'b [u8]> {
         let amount = self.tokens_amount()?;
-        let len = zxformat::u64_to_str(output.as_mut(), amount).ok()? as usize;
-        unsafe {
-            output.set_len(len);
-        }
-        Some(output)
+        let len = zxformat::u64_to_str(out, amount).ok()?;
+        let out: &'b [u8] = out;
+        out.get(..len)
     }
 
-    pub fn amount_stx_str(&self) -> Option<ArrayVec<[u8; zxformat::MAX_STR_BUFF_LEN]>> {
+    /// Formats the STX amount into out, returning the part written
+    pub fn amount_stx_str<'b>(&self, out: &'b mut [u8]) -> Option<&'b [u8]> {
         let amount = self.amount_stx()?;
-        let mut output = ArrayVec::from([0u8; zxformat::MAX_STR_BUFF_LEN]);
-        let len =
-            zxformat::fpu64_to_str_check_test(output.as_mut(), amount, STX_DECIMALS).ok()? as usize;
-        unsafe {
-            output.set_len(len);
-        }
-        Some(output)
+        let len = zxformat::stx_amount_to_str(out, amount).ok()?;
+        let out: &'b [u8] = out;
+        out.get(..len)
     }
 
     pub fn fungible_condition_code(&self) -> Option<FungibleConditionCode> {
         let code = match self {
-            Self::STX(inner) | Self::Fungible(inner) => inner[inner.len() - 9], // TODO: comment this 9
+            // the code is followed by the 8-byte amount
+            Self::STX(inner) | Self::Fungible(inner) => inner.get(inner.len().checked_sub(9)?)?,
             _ => return None,
         };
-        FungibleConditionCode::from_u8(code)
+        FungibleConditionCode::from_u8(*code)
     }
 
     pub fn non_fungible_condition_code(&self) -> Option<NonfungibleConditionCode> {
         let code = match self {
-            Self::Nonfungible(inner) => inner[inner.len() - 1],
+            Self::Nonfungible(inner) => inner.last()?,
             _ => return None,
         };
-        NonfungibleConditionCode::from_u8(code)
+        NonfungibleConditionCode::from_u8(*code)
     }
 
     pub fn asset_name(&self) -> Option<&[u8]> {
@@ -395,6 +431,27 @@ impl<'a> TransactionPostCondition<'a> {
         }
     }
 
+    /// The serialized asset of token conditions, its contract principal
+    /// followed by the length prefixed asset name
+    pub fn asset_info(&self) -> Option<&'a [u8]> {
+        match *self {
+            Self::Fungible(inner) | Self::Nonfungible(inner) => {
+                let (rem, _) = PostConditionPrincipal::read_as_bytes(inner).ok()?;
+                AssetInfo::read_as_bytes(rem).map(|res| res.1).ok()
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether the token of a fungible condition is
+    /// provisioned as pegged to bitcoin, see TokenInfo
+    pub fn is_btc_pegged(&self, token_info: Option<&TokenInfo>) -> bool {
+        match (self, token_info, self.asset_info()) {
+            (Self::Fungible(..), Some(info), Some(asset)) => info.is_btc_pegged(asset),
+            _ => false,
+        }
+    }
+
     pub fn num_items(&self) -> u8 {
         match self {
             Self::STX(..) | Self::Nonfungible(..) => 3,
@@ -402,8 +459,27 @@ impl<'a> TransactionPostCondition<'a> {
         }
     }
 
+    /// Returns the number of items of an unparsed condition, which
+    /// only depends on its type. Invalid conditions have no items
+    pub fn num_items_from_bytes(bytes: &[u8]) -> u8 {
+        match bytes.first().map(|t| PostConditionType::try_from(*t)) {
+            Some(Ok(PostConditionType::STX)) | Some(Ok(PostConditionType::NonFungibleToken)) => 3,
+            Some(Ok(PostConditionType::FungibleToken)) => 4,
+            _ => 0,
+        }
+    }
+
+    pub fn item_kind(&self, display_idx: u8) -> ItemKind {
+        match (self, display_idx % self.num_items()) {
+            (_, 0) => ItemKind::Address,
+            (Self::STX(..), 2) | (Self::Fungible(..), 3) => ItemKind::Amount,
+            _ => ItemKind::Text,
+        }
+    }
+
     pub fn write_principal_address(
         &self,
+        principals: &PrincipalTable,
         out_key: &mut [u8],
         out_value: &mut [u8],
         page_idx: u8,
@@ -411,16 +487,23 @@ impl<'a> TransactionPostCondition<'a> {
         // The post condition principal address
         let mut writer_key = zxformat::Writer::new(out_key);
         writer_key
-            .write_str("Principal")
+            .write_str(LABELS.principal)
             .map_err(|_| ParserError::parser_unexpected_buffer_end)?;
-        let addr = self.get_principal_address()?;
-        let rs = zxformat::pageString(out_value, addr.as_ref(), page_idx);
+        let rs = match self {
+            Self::STX(principal) | Self::Fungible(principal) | Self::Nonfungible(principal) => {
+                let (_, principal) = PostConditionPrincipal::from_bytes(principal)
+                    .map_err(|_| ParserError::parser_invalid_post_condition)?;
+                principal.page_address(principals, out_value, page_idx)
+            }
+        };
         crate::check_canary!();
         rs
     }
 
     pub fn get_items(
         &self,
+        principals: &PrincipalTable,
+        token_info: Option<&TokenInfo>,
         display_idx: u8,
         out_key: &mut [u8],
         out_value: &mut [u8],
@@ -428,11 +511,13 @@ impl<'a> TransactionPostCondition<'a> {
     ) -> Result<u8, ParserError> {
         let index = display_idx % self.num_items();
         if index == 0 {
-            self.write_principal_address(out_key, out_value, page_idx)
+            self.write_principal_address(principals, out_key, out_value, page_idx)
         } else {
             match self {
                 Self::STX(..) => self.get_stx_items(index, out_key, out_value, page_idx),
-                Self::Fungible(..) => self.get_fungible_items(index, out_key, out_value, page_idx),
+                Self::Fungible(..) => {
+                    self.get_fungible_items(token_info, index, out_key, out_value, page_idx)
+                }
                 Self::Nonfungible(..) => {
                     self.get_non_fungible_items(index, out_key, out_value, page_idx)
                 }
@@ -453,7 +538,7 @@ impl<'a> TransactionPostCondition<'a> {
                 // PostCondition code
                 1 => {
                     writer_key
-                        .write_str("Fungi. Code")
+                        .write_str(LABELS.fungible_code)
                         .map_err(|_| ParserError::parser_unexpected_buffer_end)?;
                     let code = self
                         .fungible_condition_code()
@@ -463,10 +548,13 @@ impl<'a> TransactionPostCondition<'a> {
                 // Amount in stx
                 2 => {
                     writer_key
-                        .write_str("STX amount")
+                        .write_str(zxformat::stx_label(LABELS.ustx_amount, LABELS.stx_amount))
                         .map_err(|_| ParserError::parser_unexpected_buffer_end)?;
-                    let amount = self.amount_stx_str().unwrap();
-                    zxformat::pageString(out_value, amount.as_ref(), page_idx)
+                    let mut buf = [0u8; zxformat::MAX_STR_BUFF_LEN];
+                    let amount = self
+                        .amount_stx_str(&mut buf)
+                        .ok_or(ParserError::parser_invalid_post_condition)?;
+                    zxformat::pageString(out_value, amount, page_idx)
                 }
                 _ => Err(ParserError::parser_display_idx_out_of_range),
             },
@@ -476,6 +564,7 @@ impl<'a> TransactionPostCondition<'a> {
 
     pub fn get_fungible_items(
         &self,
+        token_info: Option<&TokenInfo>,
         display_idx: u8,
         out_key: &mut [u8],
         out_value: &mut [u8],
@@ -488,7 +577,7 @@ impl<'a> TransactionPostCondition<'a> {
                     // Asset-name
                     1 => {
                         writer_key
-                            .write_str("Asset name")
+                            .write_str(LABELS.asset_name)
                             .map_err(|_| ParserError::parser_unexpected_buffer_end)?;
                         let name = self
                             .asset_name()
@@ -499,7 +588,7 @@ impl<'a> TransactionPostCondition<'a> {
                     // Fungible code
                     2 => {
                         writer_key
-                            .write_str("Fungi. Code")
+                            .write_str(LABELS.fungible_code)
                             .map_err(|_| ParserError::parser_unexpected_buffer_end)?;
                         let code = self
                             .fungible_condition_code()
@@ -510,13 +599,29 @@ impl<'a> TransactionPostCondition<'a> {
                     // Amount of tokens
                     3 => {
                         writer_key
-                            .write_str("Token amount")
+                            .write_str(LABELS.token_amount)
                             .map_err(|_| ParserError::parser_unexpected_buffer_end)?;
-                        let token = self
-                            .tokens_amount_str()
-                            .ok_or(ParserError::parser_unexpected_value)?;
+                        if self.is_btc_pegged(token_info) {
+                            return self.page_sats(out_value, page_idx);
+                        }
+                        // the decimals of the token are not known, the
+                        // amount is followed by a note saying so
+                        let mut amount = [0u8; zxformat::MAX_STR_BUFF_LEN + 20];
+                        let digits = self
+                            .tokens_amount_str(&mut amount)
+                            .ok_or(ParserError::parser_unexpected_value)?
+                            .len();
                         crate::check_canary!();
-                        zxformat::pageString(out_value, &token[..token.len()], page_idx)
+                        let len = digits + LABELS.raw_units.len();
+                        amount
+                            .iter_mut()
+                            .skip(digits)
+                            .zip(LABELS.raw_units.as_bytes())
+                            .for_each(|(dst, src)| *dst = *src);
+                        let amount = amount
+                            .get(..len)
+                            .ok_or(ParserError::parser_unexpected_buffer_end)?;
+                        zxformat::pageString(out_value, amount, page_idx)
                     }
                     _ => Err(ParserError::parser_display_idx_out_of_range),
                 }
@@ -525,6 +630,41 @@ impl<'a> TransactionPostCondition<'a> {
         }
     }
 
+    // An amount of bridged bitcoin in sats and in BTC, either figure alone
+    // is easily taken for the other, 10^8 times more or less
+    fn page_sats(&self, out_value: &mut [u8], page_idx: u8) -> Result<u8, ParserError> {
+        let amount = self
+            .tokens_amount()
+            .ok_or(ParserError::parser_unexpected_value)?;
+        let mut sats = [0u8; zxformat::MAX_STR_BUFF_LEN];
+        let sats_len = zxformat::u64_to_str(&mut sats, amount)?;
+        let mut btc = [0u8; zxformat::MAX_STR_BUFF_LEN];
+        let btc_len = zxformat::fpu64_to_str(&mut btc, amount, BTC_DECIMALS)?;
+        fn digits(buf: &[u8], len: usize) -> Result<&str, ParserError> {
+            buf.get(..len)
+                .and_then(|digits| core::str::from_utf8(digits).ok())
+                .ok_or(ParserError::parser_unexpected_buffer_end)
+        }
+
+        let mut value = [0u8; 2 * zxformat::MAX_STR_BUFF_LEN + 16];
+        let mut writer = zxformat::Writer::new(&mut value);
+        write!(
+            writer,
+            "{} {} ({} {})",
+            digits(&sats, sats_len)?,
+            LABELS.sats_unit,
+            digits(&btc, btc_len)?,
+            LABELS.btc_unit
+        )
+        .map_err(|_| ParserError::parser_unexpected_buffer_end)?;
+        let len = writer.offset;
+        crate::check_canary!();
+        let value = value
+            .get(..len)
+            .ok_or(ParserError::parser_unexpected_buffer_end)?;
+        zxformat::pageString(out_value, value, page_idx)
+    }
+
     pub fn get_non_fungible_items(
         &self,
         display_idx: u8,
@@ -539,7 +679,7 @@ impl<'a> TransactionPostCondition<'a> {
                     // Asset-name
                     1 => {
                         writer_key
-                            .write_str("Asset name")
+                            .write_str(LABELS.asset_name)
                             .map_err(|_| ParserError::parser_unexpected_buffer_end)?;
                         let name = self
                             .asset_name()
@@ -549,7 +689,7 @@ impl<'a> TransactionPostCondition<'a> {
                     // Fungible code
                     2 => {
                         writer_key
-                            .write_str("NonFungi. Code")
+                            .write_str(LABELS.non_fungible_code)
                             .map_err(|_| ParserError::parser_unexpected_buffer_end)?;
                         let code = self
                             .non_fungible_condition_code()
@@ -563,7 +703,6 @@ impl<'a> TransactionPostCondition<'a> {
         }
     }
 
-    #[cfg(test)]
     pub fn get_inner_bytes(&self) -> &[u8] {
         match self {
             Self::STX(inner) | Self::Fungible(inner) | Self::Nonfungible(inner) => inner,
@@ -0,0 +1,213 @@
+use core::cell::Cell;
+
+use super::reader::take;
+
+use super::c32::{self, C32Address};
+use super::limits::NUM_INTERNED_PRINCIPALS;
+use super::{ContractName, ParserError, C32_ENCODED_ADDRS_LENGTH, HASH160_LEN};
+
+#[repr(C)]
+#[derive(Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(test, derive(Debug))]
+pub struct StandardPrincipal<'a>(pub &'a [u8]);
+
+impl<'a> StandardPrincipal<'a> {
+    // The required amount of bytes in order to parse this object
+    pub const BYTES_LEN: usize = HASH160_LEN + 1;
+
+    #[inline(never)]
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<(&[u8], Self), nom::Err<ParserError>> {
+        let (raw, address) = take(Self::BYTES_LEN)(bytes)?;
+        Ok((raw, Self(address)))
+    }
+
+    pub fn version(&self) -> u8 {
+        self.0.first().copied().unwrap_or_default()
+    }
+
+    pub fn raw_address(&self) -> &'a [u8] {
+        self.0.get(1..).unwrap_or_default()
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(test, derive(Debug))]
+pub struct ContractPrincipal<'a>(StandardPrincipal<'a>, ContractName<'a>);
+impl<'a> ContractPrincipal<'a> {
+    #[inline(never)]
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<(&[u8], Self), nom::Err<ParserError>> {
+        let (rem, address) = StandardPrincipal::from_bytes(bytes)?;
+        let (rem, name) = ContractName::from_bytes(rem)?;
+        Ok((rem, Self(address, name)))
+    }
+
+    pub fn read_as_bytes(bytes: &'a [u8]) -> Result<(&[u8], &[u8]), nom::Err<ParserError>> {
+        let (rem, _) = Self::from_bytes(bytes)?;
+        let len = bytes.len() - rem.len();
+        let (rem, self_bytes) = take(len)(bytes)?;
+        Ok((rem, self_bytes))
+    }
+
+    pub fn version(&self) -> u8 {
+        // safe to unwrap as this was checked when parsing
+        self.0.version()
+    }
+
+    pub fn raw_address(&self) -> &'a [u8] {
+        // safe to unwrap as slice contains enough data
+        // checked at parsing stage
+        self.0.raw_address()
+    }
+
+    pub fn contract_name(&self) -> ContractName<'a> {
+        self.1
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Eq, PartialEq)]
+#[cfg_attr(test, derive(Debug))]
+pub enum PrincipalData<'a> {
+    Standard(StandardPrincipal<'a>),
+    Contract(ContractPrincipal<'a>),
+}
+
+impl<'a> PrincipalData<'a> {
+    #[inline(never)]
+    pub fn standard_from_bytes(bytes: &'a [u8]) -> Result<(&[u8], Self), nom::Err<ParserError>> {
+        StandardPrincipal::from_bytes(bytes).map(|(r, p)| (r, Self::Standard(p)))
+    }
+
+    #[inline(never)]
+    pub fn contract_principal_from_bytes(
+        bytes: &'a [u8],
+    ) -> Result<(&[u8], Self), nom::Err<ParserError>> {
+        ContractPrincipal::from_bytes(bytes).map(|(r, p)| (r, Self::Contract(p)))
+    }
+
+    pub fn version(&self) -> u8 {
+        match self {
+            Self::Standard(ref p) => p.version(),
+            Self::Contract(ref p) => p.version(),
+        }
+    }
+
+    // returns principal address without the address version byte
+    pub fn raw_address(&self) -> &[u8] {
+        match self {
+            Self::Standard(ref p) => p.raw_address(),
+            Self::Contract(ref p) => p.raw_address(),
+        }
+    }
+
+    #[inline(never)]
+    pub fn encoded_address(
+        &self,
+    ) -> Result<arrayvec::ArrayVec<[u8; C32_ENCODED_ADDRS_LENGTH]>, ParserError> {
+        let version = self.version();
+        let address = self.raw_address();
+        c32::c32_address(version, address)
+    }
+
+    pub fn c32_address(&self) -> Result<C32Address, ParserError> {
+        C32Address::new(self.version(), self.raw_address())
+    }
+
+    pub fn contract_name(&self) -> Option<ContractName<'a>> {
+        match self {
+            Self::Standard(..) => None,
+            Self::Contract(p) => Some(p.contract_name()),
+        }
+    }
+}
+
+/// The encoded addresses of the last principals shown. Post conditions
+/// tend to repeat the same principals, each one is encoded once and
+/// the following items take it from here
+#[derive(Clone, Default, PartialEq)]
+#[cfg_attr(test, derive(Debug))]
+pub struct PrincipalTable {
+    addresses: [Cell<Option<C32Address>>; NUM_INTERNED_PRINCIPALS],
+    // the slot taken by the next address, the oldest one once it is full
+    next: Cell<u8>,
+}
+
+impl PrincipalTable {
+    /// Returns the encoded address of version and the 20-byte hash data,
+    /// it is only encoded if it is not in the table already
+    #[inline(never)]
+    pub fn address(&self, version: u8, data: &[u8]) -> Result<C32Address, ParserError> {
+        let interned = self
+            .addresses
+            .iter()
+            .filter_map(Cell::get)
+            .find(|address| address.is_of(version, data));
+        if let Some(address) = interned {
+            return Ok(address);
+        }
+
+        let address = C32Address::new(version, data)?;
+        let next = self.next.get() as usize % NUM_INTERNED_PRINCIPALS;
+        if let Some(slot) = self.addresses.get(next) {
+            slot.set(Some(address));
+        }
+        self.next.set(((next + 1) % NUM_INTERNED_PRINCIPALS) as u8);
+        Ok(address)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn interned(table: &PrincipalTable) -> usize {
+        table.addresses.iter().filter_map(Cell::get).count()
+    }
+
+    #[test]
+    fn test_principal_table_encodes_each_address_once() {
+        let table = PrincipalTable::default();
+        let hash = [7u8; HASH160_LEN];
+
+        let first = table.address(26, &hash).unwrap();
+        let again = table.address(26, &hash).unwrap();
+        assert_eq!(first, again);
+        assert_eq!(first, C32Address::new(26, &hash).unwrap());
+        assert_eq!(interned(&table), 1);
+
+        // the same hash under another version is another principal
+        let mainnet = table.address(22, &hash).unwrap();
+        assert_eq!(mainnet, C32Address::new(22, &hash).unwrap());
+        assert_eq!(interned(&table), 2);
+    }
+
+    #[test]
+    fn test_principal_table_replaces_oldest() {
+        let table = PrincipalTable::default();
+        for byte in 0..=NUM_INTERNED_PRINCIPALS as u8 {
+            let hash = [byte; HASH160_LEN];
+            let address = table.address(22, &hash).unwrap();
+            assert!(address.is_of(22, &hash));
+        }
+        assert_eq!(interned(&table), NUM_INTERNED_PRINCIPALS);
+
+        // the first one was replaced by the last, the second is still there
+        let first = [0u8; HASH160_LEN];
+        let second = [1u8; HASH160_LEN];
+        let kept = |data: &[u8]| {
+            table
+                .addresses
+                .iter()
+                .filter_map(Cell::get)
+                .any(|a| a.is_of(22, data))
+        };
+        assert!(!kept(&first));
+        assert!(kept(&second));
+
+        assert_eq!(
+            table.address(32, &first),
+            Err(ParserError::parser_invalid_address_version)
+        );
+    }
+}
@@ -0,0 +1,154 @@
+//! Compact replacements for the nom byte and number parsers.
+//!
+//! The nom versions are generic over the input and error types, and each
+//! instantiation ends up as a separate copy in the binary. These ones only
+//! work on byte slices with our ParserError, so a single copy of each is
+//! shared across all the parser modules while keeping the nom result type.
+
+use core::convert::TryFrom;
+
+use nom::{error::ErrorKind, IResult, ToUsize};
+
+use super::error::ParserError;
+
+#[inline(never)]
+fn take_bytes(input: &[u8], count: usize) -> IResult<&[u8], &[u8], ParserError> {
+    match (input.get(..count), input.get(count..)) {
+        (Some(taken), Some(rem)) => Ok((rem, taken)),
+        _ => Err(nom::Err::Error(ErrorKind::Eof.into())),
+    }
+}
+
+/// Returns a parser that takes the first count bytes of the input
+pub fn take<C: ToUsize>(count: C) -> impl Fn(&[u8]) -> IResult<&[u8], &[u8], ParserError> {
+    let count = count.to_usize();
+    move |input: &[u8]| take_bytes(input, count)
+}
+
+/// Returns the bytes of input a parser consumed, rem being what it left
+pub fn consumed<'a>(input: &'a [u8], rem: &[u8]) -> &'a [u8] {
+    input
+        .get(..input.len().saturating_sub(rem.len()))
+        .unwrap_or_default()
+}
+
+/// The bytes a parser consumed and what it parsed from them
+pub type Consumed<'a, O> = (&'a [u8], O);
+
+/// Returns a parser that also returns the bytes parser consumed
+pub fn with_consumed<'a, O>(
+    parser: impl Fn(&'a [u8]) -> IResult<&'a [u8], O, ParserError>,
+) -> impl Fn(&'a [u8]) -> IResult<&'a [u8], Consumed<'a, O>, ParserError> {
+    move |input: &'a [u8]| {
+        let (rem, out) = parser(input)?;
+        Ok((rem, (consumed(input, rem), out)))
+    }
+}
+
+macro_rules! number_reader {
+    ($(#[$doc:meta])* $name:ident, $ty:ty, $from:ident) => {
+        $(#[$doc])*
+        #[inline(never)]
+        pub fn $name(input: &[u8]) -> IResult<&[u8], $ty, ParserError> {
+            const LEN: usize = core::mem::size_of::<$ty>();
+            let (rem, bytes) = take_bytes(input, LEN)?;
+            let array = <[u8; LEN]>::try_from(bytes)
+                .map_err(|_| nom::Err::Error(ErrorKind::Eof.into()))?;
+            Ok((rem, <$ty>::$from(array)))
+        }
+    };
+}
+
+number_reader!(
+    /// Reads one byte
+    le_u8,
+    u8,
+    from_le_bytes
+);
+number_reader!(
+    /// Reads a little-endian u16
+    le_u16,
+    u16,
+    from_le_bytes
+);
+number_reader!(
+    /// Reads a little-endian u32
+    le_u32,
+    u32,
+    from_le_bytes
+);
+number_reader!(
+    /// Reads a little-endian u64
+    le_u64,
+    u64,
+    from_le_bytes
+);
+number_reader!(
+    /// Reads a big-endian u16
+    be_u16,
+    u16,
+    from_be_bytes
+);
+number_reader!(
+    /// Reads a big-endian u32
+    be_u32,
+    u32,
+    from_be_bytes
+);
+number_reader!(
+    /// Reads a big-endian u64
+    be_u64,
+    u64,
+    from_be_bytes
+);
+number_reader!(
+    /// Reads a big-endian u128
+    be_u128,
+    u128,
+    from_be_bytes
+);
+number_reader!(
+    /// Reads a big-endian i128
+    be_i128,
+    i128,
+    from_be_bytes
+);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_readers_match_nom() {
+        let input = [1u8, 2, 3, 4, 5, 6, 7, 8, 9];
+
+        assert_eq!(
+            take(3usize)(&input),
+            nom::bytes::complete::take::<_, _, ParserError>(3usize)(&input[..])
+        );
+        assert_eq!(le_u16(&input), nom::number::complete::le_u16(&input[..]));
+        assert_eq!(be_u32(&input), nom::number::complete::be_u32(&input[..]));
+        assert_eq!(be_u64(&input), nom::number::complete::be_u64(&input[..]));
+
+        // not enough bytes
+        assert_eq!(
+            take(10usize)(&input),
+            Err(nom::Err::Error(ParserError::parser_unexpected_buffer_end))
+        );
+        assert_eq!(
+            be_u128(&input),
+            Err(nom::Err::Error(ParserError::parser_unexpected_buffer_end))
+        );
+    }
+
+    #[test]
+    fn test_with_consumed() {
+        let input = [1u8, 2, 3, 4, 5];
+        assert_eq!(
+            with_consumed(be_u16)(&input),
+            Ok((&input[2..], (&input[..2], 0x0102)))
+        );
+        assert_eq!(consumed(&input, &input[5..]), &input[..]);
+        assert!(with_consumed(be_u64)(&input).is_err());
+    }
+}
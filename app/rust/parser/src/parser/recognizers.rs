@@ -0,0 +1,303 @@
+//! Contract recognizers.
+//!
+//! A recognizer knows the functions of some well known contracts and how
+//! each of their arguments reads best: an amount with the decimals of its
+//! token, an id, a list of amounts shown one by one. Calls to them are then
+//! reviewed with labels of their own instead of as raw Clarity values, see
+//! TransactionContractCall in transaction_payload.rs. Every recognizer is
+//! behind a cargo feature of its own, so builds for devices with little
+//! flash can leave it out.
+//!
+//! A call is only recognized when the contract, the function and the number
+//! of arguments are the known ones and every argument has the type its
+//! format reads, any other call is shown as usual.
+
+use core::convert::TryFrom;
+
+use super::reader::be_u32;
+use super::{ParserError, Value, ValueId, TX_DEPTH_LIMIT};
+
+#[cfg(feature = "alex")]
+mod alex;
+#[cfg(feature = "arkadiko")]
+mod arkadiko;
+#[cfg(feature = "citycoins")]
+mod citycoins;
+#[cfg(feature = "nft-marketplace")]
+mod nft_marketplace;
+#[cfg(feature = "stacking-dao")]
+mod stacking_dao;
+
+// The longest list of amounts shown one by one, as long as the lists of
+// the contracts known, longer ones would not fit the items of a review
+pub const MAX_LIST_AMOUNTS: u32 = 200;
+
+/// A deployment of a known contract
+#[derive(PartialEq)]
+#[cfg_attr(test, derive(Debug))]
+pub struct Contract {
+    /// The c32 address of the deployer
+    pub address: &'static str,
+    pub name: &'static str,
+}
+
+/// How an argument of a known function is shown
+#[derive(Clone, Copy, PartialEq)]
+#[cfg_attr(test, derive(Debug))]
+pub enum ArgFormat {
+    /// A principal, shown as the principal arguments are
+    Principal,
+    /// An optional principal
+    OptionalPrincipal,
+    /// An ascii string, shown as it is
+    Text,
+    /// An uint shown as it is, ids, block heights and counts
+    Number,
+    /// An uint amount of uSTX, shown in the unit amounts are shown in
+    Ustx,
+    /// An uint amount of a token of the decimals given
+    Token(u8),
+    /// An uint amount of a token whose decimals are not known
+    RawUnits,
+    /// An optional uint amount of a token of the decimals given, the least
+    /// a swap is to return, none is flagged as any amount is then accepted
+    Minimum(u8),
+    /// A list of uSTX amounts, shown as their total and then one item each,
+    /// labelled with the prefix given and the position of the amount
+    UstxList(&'static str),
+}
+
+/// An argument of a known function, along with the label it is shown with
+#[derive(PartialEq)]
+#[cfg_attr(test, derive(Debug))]
+pub struct Arg {
+    pub label: &'static str,
+    pub format: ArgFormat,
+}
+
+/// A function of known contracts, with how each of its arguments is shown,
+/// None for the ones shown as usual
+#[derive(PartialEq)]
+#[cfg_attr(test, derive(Debug))]
+pub struct KnownFunction {
+    /// The contracts exposing it, any contract if there are none, for the
+    /// functions a standard has every contract implement
+    pub contracts: &'static [Contract],
+    pub name: &'static str,
+    pub args: &'static [Option<Arg>],
+}
+
+// The functions of every recognizer built in
+const RECOGNIZERS: &[&[KnownFunction]] = &[
+    #[cfg(feature = "citycoins")]
+    citycoins::FUNCTIONS,
+    #[cfg(feature = "arkadiko")]
+    arkadiko::FUNCTIONS,
+    #[cfg(feature = "alex")]
+    alex::FUNCTIONS,
+    #[cfg(feature = "stacking-dao")]
+    stacking_dao::FUNCTIONS,
+    #[cfg(feature = "nft-marketplace")]
+    nft_marketplace::FUNCTIONS,
+];
+
+impl ArgFormat {
+    /// Whether value has the type the format reads
+    pub fn admits(&self, value: &Value) -> bool {
+        match (self, value.value_id()) {
+            (Self::Principal, Ok(ValueId::StandardPrincipal))
+            | (Self::Principal, Ok(ValueId::ContractPrincipal))
+            | (Self::Text, Ok(ValueId::StringAscii))
+            | (Self::OptionalPrincipal, Ok(ValueId::OptionalNone)) => true,
+            (Self::OptionalPrincipal, Ok(ValueId::OptionalSome)) => {
+                some_value(value).is_ok_and(|some| {
+                    matches!(
+                        some.value_id(),
+                        Ok(ValueId::StandardPrincipal) | Ok(ValueId::ContractPrincipal)
+                    )
+                })
+            }
+            (Self::Number, Ok(ValueId::UInt))
+            | (Self::Ustx, Ok(ValueId::UInt))
+            | (Self::Token(_), Ok(ValueId::UInt))
+            | (Self::RawUnits, Ok(ValueId::UInt)) => true,
+            (Self::Minimum(_), Ok(ValueId::OptionalNone)) => true,
+            (Self::Minimum(_), Ok(ValueId::OptionalSome)) => {
+                some_value(value).is_ok_and(|some| some.value_id() == Ok(ValueId::UInt))
+            }
+            // the total of the amounts has to be computed, see UstxTotal
+            (Self::UstxList(_), Ok(ValueId::List)) => {
+                list_len(value).is_ok_and(|len| len <= MAX_LIST_AMOUNTS)
+                    && list_items(value).all(|item| {
+                        item.is_ok_and(|item| {
+                            item.uint()
+                                .is_some_and(|amount| u64::try_from(amount).is_ok())
+                        })
+                    })
+            }
+            _ => false,
+        }
+    }
+
+    /// The items value is shown in
+    pub fn num_items(&self, value: &Value) -> Result<u8, ParserError> {
+        match self {
+            Self::UstxList(_) => {
+                let len = u8::try_from(list_len(value)?)
+                    .map_err(|_| ParserError::parser_limit_exceeded)?;
+                len.checked_add(1).ok_or(ParserError::parser_limit_exceeded)
+            }
+            _ => Ok(1),
+        }
+    }
+
+    /// Whether the format shows an amount
+    pub fn is_amount(&self) -> bool {
+        matches!(
+            self,
+            Self::Ustx | Self::Token(_) | Self::RawUnits | Self::Minimum(_) | Self::UstxList(_)
+        )
+    }
+}
+
+/// The value an optional one holds
+pub fn some_value<'a>(optional: &Value<'a>) -> Result<Value<'a>, ParserError> {
+    if optional.value_id()? != ValueId::OptionalSome {
+        return Err(ParserError::parser_unexpected_type);
+    }
+    let (_, value) = Value::from_bytes::<TX_DEPTH_LIMIT>(optional.payload())?;
+    Ok(value)
+}
+
+/// The number of items of a list value
+pub fn list_len(list: &Value) -> Result<u32, ParserError> {
+    if list.value_id()? != ValueId::List {
+        return Err(ParserError::parser_unexpected_type);
+    }
+    be_u32(list.payload())
+        .map(|(_, len)| len)
+        .map_err(|e| e.into())
+}
+
+/// The items of a list value
+pub fn list_items<'a>(list: &Value<'a>) -> impl Iterator<Item = Result<Value<'a>, ParserError>> {
+    let len = list_len(list).unwrap_or_default();
+    let mut items = list.payload().get(4..).unwrap_or_default();
+    (0..len).map(move |_| {
+        let (rem, item) = Value::from_bytes::<TX_DEPTH_LIMIT>(items)?;
+        items = rem;
+        Ok(item)
+    })
+}
+
+/// The known function called, if any. A call to it still has to have the
+/// arguments the function takes, see TransactionContractCall::recognize
+pub fn known_function(
+    address: &[u8],
+    contract_name: &[u8],
+    function_name: &[u8],
+) -> Option<&'static KnownFunction> {
+    RECOGNIZERS
+        .iter()
+        .flat_map(|functions| functions.iter())
+        .find(|function| {
+            function.name.as_bytes() == function_name
+                && (function.contracts.is_empty()
+                    || function.contracts.iter().any(|contract| {
+                        contract.address.as_bytes() == address
+                            && contract.name.as_bytes() == contract_name
+                    }))
+        })
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use std::prelude::v1::*;
+
+    use super::super::transaction_payload::TransactionPayload;
+    use super::super::Item;
+
+    pub fn uint(value: u128) -> Vec<u8> {
+        let mut bytes = vec![0x01];
+        bytes.extend_from_slice(&value.to_be_bytes());
+        bytes
+    }
+
+    pub fn list(items: &[Vec<u8>]) -> Vec<u8> {
+        let mut bytes = vec![0x0b];
+        bytes.extend_from_slice(&(items.len() as u32).to_be_bytes());
+        items.iter().for_each(|item| bytes.extend_from_slice(item));
+        bytes
+    }
+
+    pub fn ascii(text: &str) -> Vec<u8> {
+        let mut bytes = vec![0x0d];
+        bytes.extend_from_slice(&(text.len() as u32).to_be_bytes());
+        bytes.extend_from_slice(text.as_bytes());
+        bytes
+    }
+
+    pub fn some(value: Vec<u8>) -> Vec<u8> {
+        let mut bytes = vec![0x0a];
+        bytes.extend(value);
+        bytes
+    }
+
+    pub fn standard_principal(hash160: &str) -> Vec<u8> {
+        let mut bytes = vec![0x05, 22];
+        bytes.extend_from_slice(&hex::decode(hash160).unwrap());
+        bytes
+    }
+
+    pub fn contract_principal(hash160: &str, name: &str) -> Vec<u8> {
+        let mut bytes = vec![0x06, 22];
+        bytes.extend_from_slice(&hex::decode(hash160).unwrap());
+        bytes.push(name.len() as u8);
+        bytes.extend_from_slice(name.as_bytes());
+        bytes
+    }
+
+    pub fn tuple(fields: &[(&str, Vec<u8>)]) -> Vec<u8> {
+        let mut bytes = vec![0x0c];
+        bytes.extend_from_slice(&(fields.len() as u32).to_be_bytes());
+        for (key, value) in fields {
+            bytes.push(key.len() as u8);
+            bytes.extend_from_slice(key.as_bytes());
+            bytes.extend_from_slice(value);
+        }
+        bytes
+    }
+
+    /// The payload of a call to contract, deployed by the mainnet
+    /// address of hash160, as a hex string
+    pub fn contract_call(
+        hash160: &str,
+        contract: &str,
+        function: &str,
+        args: &[Vec<u8>],
+    ) -> Vec<u8> {
+        let mut bytes = vec![0x02, 22];
+        bytes.extend_from_slice(&hex::decode(hash160).unwrap());
+        bytes.push(contract.len() as u8);
+        bytes.extend_from_slice(contract.as_bytes());
+        bytes.push(function.len() as u8);
+        bytes.extend_from_slice(function.as_bytes());
+        bytes.extend_from_slice(&(args.len() as u32).to_be_bytes());
+        args.iter().for_each(|arg| bytes.extend_from_slice(arg));
+        bytes
+    }
+
+    /// The items of the arguments of a contract call payload, the ones
+    /// after the contract and function names
+    pub fn arg_items(payload: &[u8]) -> Vec<Item> {
+        let (_, parsed) = TransactionPayload::from_bytes(payload).unwrap();
+        parsed.items().unwrap().split_off(3)
+    }
+
+    pub fn item(title: &str, value: &str) -> Item {
+        Item {
+            title: title.into(),
+            value: value.into(),
+        }
+    }
+}
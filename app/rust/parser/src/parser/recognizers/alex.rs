@@ -0,0 +1,185 @@
+//! ALEX swaps.
+//!
+//! The swap helpers of ALEX take the token swapped, the tokens the swap
+//! goes through and the one received, the amount swapped and the least
+//! amount to receive, the slippage floor, which is optional. Every amount
+//! is in the 8 decimals fixed point of ALEX, whatever the token. The pool
+//! factors each hop takes are shown as usual.
+
+use super::{Arg, ArgFormat, Contract, KnownFunction};
+use crate::lang::LABELS;
+
+const ALEX_DECIMALS: u8 = 8;
+
+const AMM_POOLS: &[Contract] = &[
+    Contract {
+        address: "SP102V8P0F7JX67ARQ77WEA3D3CFB5XW39REDT0AM",
+        name: "amm-swap-pool-v1-1",
+    },
+    Contract {
+        address: "SP102V8P0F7JX67ARQ77WEA3D3CFB5XW39REDT0AM",
+        name: "amm-pool-v2-01",
+    },
+];
+
+const SWAP_HELPER: &[Contract] = &[Contract {
+    address: "SP3K8BC0PPEVCV7NZ6QSRWPQ2JE9E5B6N3PA0KBR9",
+    name: "swap-helper-v1-03",
+}];
+
+const TOKEN_IN: Option<Arg> = Some(Arg {
+    label: LABELS.token_in,
+    format: ArgFormat::Principal,
+});
+
+const VIA_TOKEN: Option<Arg> = Some(Arg {
+    label: LABELS.via_token,
+    format: ArgFormat::Principal,
+});
+
+const TOKEN_OUT: Option<Arg> = Some(Arg {
+    label: LABELS.token_out,
+    format: ArgFormat::Principal,
+});
+
+const AMOUNT_IN: Option<Arg> = Some(Arg {
+    label: LABELS.amount_in,
+    format: ArgFormat::Token(ALEX_DECIMALS),
+});
+
+const MIN_RECEIVED: Option<Arg> = Some(Arg {
+    label: LABELS.min_received,
+    format: ArgFormat::Minimum(ALEX_DECIMALS),
+});
+
+pub(super) const FUNCTIONS: &[KnownFunction] = &[
+    KnownFunction {
+        contracts: AMM_POOLS,
+        name: "swap-helper",
+        args: &[TOKEN_IN, TOKEN_OUT, None, AMOUNT_IN, MIN_RECEIVED],
+    },
+    KnownFunction {
+        contracts: AMM_POOLS,
+        name: "swap-helper-a",
+        args: &[
+            TOKEN_IN,
+            VIA_TOKEN,
+            TOKEN_OUT,
+            None,
+            None,
+            AMOUNT_IN,
+            MIN_RECEIVED,
+        ],
+    },
+    KnownFunction {
+        contracts: AMM_POOLS,
+        name: "swap-helper-b",
+        args: &[
+            TOKEN_IN,
+            VIA_TOKEN,
+            VIA_TOKEN,
+            TOKEN_OUT,
+            None,
+            None,
+            None,
+            AMOUNT_IN,
+            MIN_RECEIVED,
+        ],
+    },
+    // the helper of the first pools, which takes no factors
+    KnownFunction {
+        contracts: SWAP_HELPER,
+        name: "swap-helper",
+        args: &[TOKEN_IN, TOKEN_OUT, AMOUNT_IN, MIN_RECEIVED],
+    },
+];
+
+#[cfg(test)]
+mod test {
+    use std::prelude::v1::*;
+
+    use super::super::test::{arg_items, contract_call, contract_principal, item, some, uint};
+    use crate::parser::transaction_payload::TransactionPayload;
+    use crate::parser::ItemKind;
+
+    // SP102V8P0F7JX67ARQ77WEA3D3CFB5XW39REDT0AM, the deployer of the pools
+    const ALEX: &str = "402da2c079e5d31d58b9cfc7286d1b1eb2f7834e";
+
+    fn swap(min_received: Vec<u8>) -> Vec<u8> {
+        let args = [
+            contract_principal(ALEX, "token-wstx"),
+            contract_principal(ALEX, "token-abtc"),
+            uint(100_000_000),
+            uint(2_500_000_000),
+            min_received,
+        ];
+        contract_call(ALEX, "amm-pool-v2-01", "swap-helper", &args)
+    }
+
+    #[test]
+    fn test_swap_helper() {
+        let payload = swap(some(uint(12_345)));
+        assert_eq!(
+            arg_items(&payload),
+            vec![
+                item(
+                    "Token in",
+                    "SP102V8P0F7JX67ARQ77WEA3D3CFB5XW39REDT0AM.token-wstx"
+                ),
+                item(
+                    "Token out",
+                    "SP102V8P0F7JX67ARQ77WEA3D3CFB5XW39REDT0AM.token-abtc"
+                ),
+                item("arg2", "100000000"),
+                item("Amount in", "25.00000000"),
+                item("Min received", "0.00012345"),
+            ]
+        );
+        let (_, parsed) = TransactionPayload::from_bytes(&payload).unwrap();
+        assert_eq!(parsed.item_kind(7), ItemKind::Amount);
+    }
+
+    #[test]
+    fn test_swap_without_minimum() {
+        // any amount received is accepted, which is flagged
+        let payload = swap(vec![0x09]);
+        let items = arg_items(&payload);
+        assert_eq!(items[4], item("Min received", "None, any amount accepted"));
+        let (_, parsed) = TransactionPayload::from_bytes(&payload).unwrap();
+        assert_eq!(parsed.item_kind(7), ItemKind::Warning);
+
+        // a minimum of another type is shown as usual
+        let payload = swap(some(contract_principal(ALEX, "token-abtc")));
+        assert_eq!(arg_items(&payload)[0].title, "arg0");
+    }
+
+    #[test]
+    fn test_multi_hop_swap() {
+        let args = [
+            contract_principal(ALEX, "token-wstx"),
+            contract_principal(ALEX, "token-alex"),
+            contract_principal(ALEX, "token-abtc"),
+            uint(100_000_000),
+            uint(100_000_000),
+            uint(1),
+            vec![0x09],
+        ];
+        let payload = contract_call(ALEX, "amm-swap-pool-v1-1", "swap-helper-a", &args);
+        let titles: Vec<_> = arg_items(&payload)
+            .into_iter()
+            .map(|item| item.title)
+            .collect();
+        assert_eq!(
+            titles,
+            [
+                "Token in",
+                "Via token",
+                "Token out",
+                "arg3",
+                "arg4",
+                "Amount in",
+                "Min received"
+            ]
+        );
+    }
+}
@@ -0,0 +1,237 @@
+//! Arkadiko vaults.
+//!
+//! A vault locks collateral to mint USDA, the stablecoin of Arkadiko, of 6
+//! decimals. Opening one takes the collateral and the USDA minted against
+//! it, the other operations take the id of the vault. Collateral amounts
+//! are in the base units of the collateral token, whose decimals are not
+//! known, so they are shown in raw units.
+
+use super::{Arg, ArgFormat, Contract, KnownFunction};
+use crate::lang::LABELS;
+
+const USDA_DECIMALS: u8 = 6;
+
+const FREDDIE: &[Contract] = &[Contract {
+    address: "SP2C2YFP12AJZB4MABJBAJ55XECVS7E4PMMZ89YZR",
+    name: "arkadiko-freddie-v1-1",
+}];
+
+const VAULT_ID: Option<Arg> = Some(Arg {
+    label: LABELS.vault_id,
+    format: ArgFormat::Number,
+});
+
+// the token of the collateral, the other traits are shown as usual
+const COLLATERAL_TOKEN: Option<Arg> = Some(Arg {
+    label: LABELS.collateral_token,
+    format: ArgFormat::Principal,
+});
+
+pub(super) const FUNCTIONS: &[KnownFunction] = &[
+    // opens a vault
+    KnownFunction {
+        contracts: FREDDIE,
+        name: "collateralize-and-mint",
+        args: &[
+            Some(Arg {
+                label: LABELS.collateral,
+                format: ArgFormat::RawUnits,
+            }),
+            Some(Arg {
+                label: LABELS.usda_minted,
+                format: ArgFormat::Token(USDA_DECIMALS),
+            }),
+            None,
+            Some(Arg {
+                label: LABELS.collateral_type,
+                format: ArgFormat::Text,
+            }),
+            None,
+            COLLATERAL_TOKEN,
+            None,
+            None,
+        ],
+    },
+    KnownFunction {
+        contracts: FREDDIE,
+        name: "deposit",
+        args: &[
+            VAULT_ID,
+            Some(Arg {
+                label: LABELS.collateral_added,
+                format: ArgFormat::RawUnits,
+            }),
+            None,
+            COLLATERAL_TOKEN,
+            None,
+        ],
+    },
+    KnownFunction {
+        contracts: FREDDIE,
+        name: "withdraw",
+        args: &[
+            VAULT_ID,
+            Some(Arg {
+                label: LABELS.collateral_withdrawn,
+                format: ArgFormat::RawUnits,
+            }),
+            None,
+            COLLATERAL_TOKEN,
+            None,
+            None,
+        ],
+    },
+    KnownFunction {
+        contracts: FREDDIE,
+        name: "mint",
+        args: &[
+            VAULT_ID,
+            Some(Arg {
+                label: LABELS.usda_minted,
+                format: ArgFormat::Token(USDA_DECIMALS),
+            }),
+            None,
+            None,
+            None,
+        ],
+    },
+    // repays USDA
+    KnownFunction {
+        contracts: FREDDIE,
+        name: "burn",
+        args: &[
+            VAULT_ID,
+            Some(Arg {
+                label: LABELS.usda_repaid,
+                format: ArgFormat::Token(USDA_DECIMALS),
+            }),
+            None,
+            COLLATERAL_TOKEN,
+            None,
+        ],
+    },
+];
+
+#[cfg(test)]
+mod test {
+    use std::prelude::v1::*;
+
+    use super::super::test::{
+        arg_items, ascii, contract_call, contract_principal, item, tuple, uint,
+    };
+    use crate::parser::transaction_payload::TransactionPayload;
+
+    // SP2C2YFP12AJZB4MABJBAJ55XECVS7E4PMMZ89YZR, the Arkadiko deployer
+    const ARKADIKO: &str = "982f3ec112a5f5928a5c96a914bd733793b896a5";
+
+    fn traits(names: &[&str]) -> Vec<Vec<u8>> {
+        names
+            .iter()
+            .map(|name| contract_principal(ARKADIKO, name))
+            .collect()
+    }
+
+    #[test]
+    fn test_open_vault() {
+        let pox_settings = tuple(&[("auto-payoff", vec![0x03]), ("stack-pox", vec![0x03])]);
+        let mut args = vec![
+            uint(1_500_000_000),
+            uint(500_250_000),
+            pox_settings,
+            ascii("STX-A"),
+        ];
+        args.extend(traits(&[
+            "arkadiko-stx-reserve-v1-1",
+            "arkadiko-token",
+            "arkadiko-collateral-types-v3-1",
+            "arkadiko-oracle-v2-2",
+        ]));
+        let payload = contract_call(
+            ARKADIKO,
+            "arkadiko-freddie-v1-1",
+            "collateralize-and-mint",
+            &args,
+        );
+        let items = arg_items(&payload);
+        assert_eq!(
+            items[..4].to_vec(),
+            vec![
+                item("Collateral (raw units)", "1500000000"),
+                item("USDA minted", "500.250000"),
+                item("arg2", "is Tuple"),
+                item("Collateral type", "STX-A"),
+            ]
+        );
+        assert_eq!(
+            items[5],
+            item(
+                "Collateral token",
+                "SP2C2YFP12AJZB4MABJBAJ55XECVS7E4PMMZ89YZR.arkadiko-token"
+            )
+        );
+        assert_eq!(items[4].title, "arg4");
+    }
+
+    #[test]
+    fn test_vault_operations() {
+        let vault_call = |function: &str, amount: u128, traits: Vec<Vec<u8>>| {
+            let mut args = vec![uint(42), uint(amount)];
+            args.extend(traits);
+            let payload = contract_call(ARKADIKO, "arkadiko-freddie-v1-1", function, &args);
+            arg_items(&payload)[..2].to_vec()
+        };
+        let reserve = || {
+            traits(&[
+                "arkadiko-stx-reserve-v1-1",
+                "arkadiko-token",
+                "arkadiko-collateral-types-v3-1",
+            ])
+        };
+        let with_oracle = || {
+            let mut args = reserve();
+            args.extend(traits(&["arkadiko-oracle-v2-2"]));
+            args
+        };
+
+        assert_eq!(
+            vault_call("deposit", 25_000_000, reserve()),
+            vec![
+                item("Vault ID", "42"),
+                item("Collateral added (raw units)", "25000000")
+            ]
+        );
+        assert_eq!(
+            vault_call("withdraw", 7, with_oracle()),
+            vec![
+                item("Vault ID", "42"),
+                item("Collateral withdrawn (raw units)", "7")
+            ]
+        );
+        assert_eq!(
+            vault_call("burn", 100_000_000, reserve()),
+            vec![item("Vault ID", "42"), item("USDA repaid", "100.000000")]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "admin-functions")]
+    fn test_vault_mint() {
+        // minting USDA is not an admin-style function
+        let mut args = vec![uint(42), uint(1)];
+        args.extend(traits(&[
+            "arkadiko-stx-reserve-v1-1",
+            "arkadiko-collateral-types-v3-1",
+            "arkadiko-oracle-v2-2",
+        ]));
+        let payload = contract_call(ARKADIKO, "arkadiko-freddie-v1-1", "mint", &args);
+        let (_, parsed) = TransactionPayload::from_bytes(&payload).unwrap();
+        let items = parsed.items().unwrap();
+        assert_eq!(items[3], item("Vault ID", "42"));
+        assert_eq!(items[4], item("USDA minted", "0.000001"));
+
+        // the mint of another contract still is
+        let payload = contract_call(ARKADIKO, "arkadiko-token", "mint", &[uint(1)]);
+        let (_, parsed) = TransactionPayload::from_bytes(&payload).unwrap();
+        assert_eq!(parsed.items().unwrap()[3].title, "Caution");
+    }
+}
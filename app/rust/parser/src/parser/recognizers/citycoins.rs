@@ -0,0 +1,271 @@
+//! CityCoins mining and stacking.
+//!
+//! Miners commit uSTX to blocks, mine-many takes a list with the commit of
+//! each block from the current one on, and stackers lock city tokens for a
+//! number of reward cycles. The core contracts of each city and the mining
+//! and stacking extensions of the CityCoins DAO, which take the name of the
+//! city first, are known.
+
+use super::{Arg, ArgFormat, Contract, KnownFunction};
+use crate::lang::LABELS;
+
+const CORE_CONTRACTS: &[Contract] = &[
+    Contract {
+        address: "SP466FNC0P7JWTNM2R9T199QRZN1MYEDTAR0KP27",
+        name: "miamicoin-core-v1",
+    },
+    Contract {
+        address: "SP1H1733V5MZ3SZ9XRW9FKYGEZT0JDGEB8Y634C7R",
+        name: "miamicoin-core-v2",
+    },
+    Contract {
+        address: "SP2H8PY27SEZ03MWRKS5XABZYQN17ETGQS3527SA5",
+        name: "newyorkcitycoin-core-v1",
+    },
+    Contract {
+        address: "SPSCWDV3RKV5ZRN1FQD84YE1NQFEDJ9R1F4DYQ11",
+        name: "newyorkcitycoin-core-v2",
+    },
+];
+
+const DAO_ADDRESS: &str = "SP8A9HZ3PKST0S42VM9523Z9NV42SZ026V4K39WH";
+
+const DAO_MINING: &[Contract] = &[
+    Contract {
+        address: DAO_ADDRESS,
+        name: "ccd006-citycoin-mining",
+    },
+    Contract {
+        address: DAO_ADDRESS,
+        name: "ccd006-citycoin-mining-v2",
+    },
+];
+
+const DAO_STACKING: &[Contract] = &[Contract {
+    address: DAO_ADDRESS,
+    name: "ccd007-citycoin-stacking",
+}];
+
+const CITY: Option<Arg> = Some(Arg {
+    label: LABELS.city,
+    format: ArgFormat::Text,
+});
+
+const COMMITS: Option<Arg> = Some(Arg {
+    label: LABELS.total_commit,
+    format: ArgFormat::UstxList(LABELS.block_commit),
+});
+
+const STACKED: Option<Arg> = Some(Arg {
+    label: LABELS.stacked_tokens,
+    format: ArgFormat::RawUnits,
+});
+
+const LOCK_CYCLES: Option<Arg> = Some(Arg {
+    label: LABELS.lock_cycles,
+    format: ArgFormat::Number,
+});
+
+const MINED_BLOCK: Option<Arg> = Some(Arg {
+    label: LABELS.mined_block,
+    format: ArgFormat::Number,
+});
+
+const REWARD_CYCLE: Option<Arg> = Some(Arg {
+    label: LABELS.reward_cycle,
+    format: ArgFormat::Number,
+});
+
+pub(super) const FUNCTIONS: &[KnownFunction] = &[
+    KnownFunction {
+        contracts: CORE_CONTRACTS,
+        name: "mine-tokens",
+        // the memo is shown as usual
+        args: &[
+            Some(Arg {
+                label: LABELS.mining_commit,
+                format: ArgFormat::Ustx,
+            }),
+            None,
+        ],
+    },
+    KnownFunction {
+        contracts: CORE_CONTRACTS,
+        name: "mine-many",
+        args: &[COMMITS],
+    },
+    KnownFunction {
+        contracts: CORE_CONTRACTS,
+        name: "stack-tokens",
+        args: &[STACKED, LOCK_CYCLES],
+    },
+    KnownFunction {
+        contracts: CORE_CONTRACTS,
+        name: "claim-mining-reward",
+        args: &[MINED_BLOCK],
+    },
+    KnownFunction {
+        contracts: CORE_CONTRACTS,
+        name: "claim-stacking-reward",
+        args: &[REWARD_CYCLE],
+    },
+    KnownFunction {
+        contracts: DAO_MINING,
+        name: "mine",
+        args: &[CITY, COMMITS],
+    },
+    KnownFunction {
+        contracts: DAO_MINING,
+        name: "claim-mining-reward",
+        args: &[CITY, MINED_BLOCK],
+    },
+    KnownFunction {
+        contracts: DAO_STACKING,
+        name: "stack",
+        args: &[CITY, STACKED, LOCK_CYCLES],
+    },
+    KnownFunction {
+        contracts: DAO_STACKING,
+        name: "claim-stacking-reward",
+        args: &[CITY, REWARD_CYCLE],
+    },
+];
+
+#[cfg(test)]
+mod test {
+    use std::prelude::v1::*;
+
+    use super::super::test::{arg_items, ascii, contract_call, item, list, uint};
+    use crate::parser::transaction_payload::TransactionPayload;
+    use crate::parser::{ItemKind, ItemSection};
+
+    // SP1H1733V5MZ3SZ9XRW9FKYGEZT0JDGEB8Y634C7R, miamicoin-core-v2
+    const MIA_CORE: &str = "62138c7b2d3e3cfd3dc712f9fa0efe8126c1cb47";
+    // SP8A9HZ3PKST0S42VM9523Z9NV42SZ026V4K39WH, the CityCoins DAO
+    const DAO: &str = "10a4c7e3b4f3a06482dd12510fe9aec82cfc0236";
+
+    #[test]
+    fn test_mine_many() {
+        let commits = list(&[uint(1_000_000), uint(2_500_000), uint(u64::MAX as u128)]);
+        let payload = contract_call(MIA_CORE, "miamicoin-core-v2", "mine-many", &[commits]);
+        assert_eq!(
+            arg_items(&payload),
+            vec![
+                item("Total commit (uSTX)", "18446744073713051615"),
+                item("Block +0 (uSTX)", "1000000"),
+                item("Block +1 (uSTX)", "2500000"),
+                item("Block +2 (uSTX)", "18446744073709551615"),
+            ]
+        );
+
+        let (_, parsed) = TransactionPayload::from_bytes(&payload).unwrap();
+        assert_eq!(parsed.num_items().unwrap(), 7);
+        for idx in 3..7 {
+            assert_eq!(parsed.item_kind(idx), ItemKind::Amount);
+            assert_eq!(parsed.item_section(idx), ItemSection::Amounts);
+        }
+    }
+
+    #[test]
+    fn test_stacking_and_claims() {
+        let payload = contract_call(
+            MIA_CORE,
+            "miamicoin-core-v2",
+            "stack-tokens",
+            &[uint(500_000_000), uint(3)],
+        );
+        assert_eq!(
+            arg_items(&payload),
+            vec![
+                item("Stacked tokens (raw units)", "500000000"),
+                item("Lock cycles", "3"),
+            ]
+        );
+
+        let payload = contract_call(
+            MIA_CORE,
+            "miamicoin-core-v2",
+            "claim-mining-reward",
+            &[uint(58_917)],
+        );
+        assert_eq!(arg_items(&payload), vec![item("Mined block", "58917")]);
+
+        let payload = contract_call(
+            DAO,
+            "ccd007-citycoin-stacking",
+            "stack",
+            &[ascii("mia"), uint(1_000), uint(12)],
+        );
+        assert_eq!(
+            arg_items(&payload),
+            vec![
+                item("City", "mia"),
+                item("Stacked tokens (raw units)", "1000"),
+                item("Lock cycles", "12"),
+            ]
+        );
+
+        let commits = list(&[uint(10), uint(20)]);
+        let payload = contract_call(
+            DAO,
+            "ccd006-citycoin-mining-v2",
+            "mine",
+            &[ascii("nyc"), commits],
+        );
+        assert_eq!(
+            arg_items(&payload),
+            vec![
+                item("City", "nyc"),
+                item("Total commit (uSTX)", "30"),
+                item("Block +0 (uSTX)", "10"),
+                item("Block +1 (uSTX)", "20"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unknown_calls() {
+        // another contract, arguments of other types or count, and lists
+        // longer than the contracts take are shown as usual
+        let cases = [
+            contract_call(
+                MIA_CORE,
+                "miamicoin-core-v3",
+                "stack-tokens",
+                &[uint(1), uint(3)],
+            ),
+            contract_call(
+                DAO,
+                "miamicoin-core-v2",
+                "stack-tokens",
+                &[uint(1), uint(3)],
+            ),
+            contract_call(MIA_CORE, "miamicoin-core-v2", "stack-tokens", &[uint(1)]),
+            contract_call(
+                MIA_CORE,
+                "miamicoin-core-v2",
+                "stack-tokens",
+                &[uint(1), ascii("3")],
+            ),
+            contract_call(
+                MIA_CORE,
+                "miamicoin-core-v2",
+                "mine-many",
+                &[list(&[uint(1), uint(u64::MAX as u128 + 1)])],
+            ),
+            contract_call(
+                MIA_CORE,
+                "miamicoin-core-v2",
+                "mine-many",
+                &[list(&vec![uint(1); 201])],
+            ),
+        ];
+        for payload in cases.iter() {
+            let (_, parsed) = TransactionPayload::from_bytes(payload).unwrap();
+            assert!(
+                matches!(parsed, TransactionPayload::ContractCall(ref call) if call.known_function().is_none())
+            );
+            assert_eq!(arg_items(payload)[0].title, "arg0");
+        }
+    }
+}
@@ -0,0 +1,108 @@
+//! NFT marketplace functions.
+//!
+//! Collections that trade on marketplaces like Gamma implement the same
+//! marketplace functions, listing one of their tokens for a price in uSTX,
+//! unlisting it and buying it. A listing and a purchase take the commission
+//! contract, which pays the marketplace fee and the royalty of the creator.
+//! They are recognized in any contract, which is the collection, so only
+//! the names and types of the arguments tell them: what a contract does on
+//! a purchase is what its post conditions limit, as for any other call.
+
+use super::{Arg, ArgFormat, KnownFunction};
+use crate::lang::LABELS;
+use crate::parser::STX_DECIMALS;
+
+const TOKEN_ID: Option<Arg> = Some(Arg {
+    label: LABELS.token_id,
+    format: ArgFormat::Number,
+});
+
+const ROYALTY_TO: Option<Arg> = Some(Arg {
+    label: LABELS.royalty_to,
+    format: ArgFormat::Principal,
+});
+
+pub(super) const FUNCTIONS: &[KnownFunction] = &[
+    KnownFunction {
+        contracts: &[],
+        name: "list-in-ustx",
+        args: &[
+            TOKEN_ID,
+            Some(Arg {
+                label: LABELS.price_stx,
+                format: ArgFormat::Token(STX_DECIMALS),
+            }),
+            ROYALTY_TO,
+        ],
+    },
+    KnownFunction {
+        contracts: &[],
+        name: "unlist-in-ustx",
+        args: &[TOKEN_ID],
+    },
+    KnownFunction {
+        contracts: &[],
+        name: "buy-in-ustx",
+        args: &[TOKEN_ID, ROYALTY_TO],
+    },
+];
+
+#[cfg(test)]
+mod test {
+    use std::prelude::v1::*;
+
+    use super::super::test::{arg_items, contract_call, contract_principal, item, uint};
+
+    // SPNWZ5V2TPWGQGVDR6T7B6RQ4XMGZ4PXTEE0VQ0S, a collection deployer
+    const COLLECTION: &str = "2bcf9762d5b90bc36dc1b4759b1727690f92ddd3";
+
+    fn commission() -> Vec<u8> {
+        contract_principal(COLLECTION, "commission-gamma")
+    }
+
+    #[test]
+    fn test_listing() {
+        let args = [uint(1_337), uint(42_500_000), commission()];
+        let payload = contract_call(COLLECTION, "bitcoin-monkeys", "list-in-ustx", &args);
+        assert_eq!(
+            arg_items(&payload),
+            vec![
+                item("Token ID", "1337"),
+                item("Price (STX)", "42.500000"),
+                item(
+                    "Royalty to",
+                    "SPNWZ5V2TPWGQGVDR6T7B6RQ4XMGZ4PXTEE0VQ0S.commission-gamma"
+                ),
+            ]
+        );
+
+        let payload = contract_call(
+            COLLECTION,
+            "bitcoin-monkeys",
+            "unlist-in-ustx",
+            &[uint(1_337)],
+        );
+        assert_eq!(arg_items(&payload), vec![item("Token ID", "1337")]);
+    }
+
+    #[test]
+    fn test_purchase() {
+        // the collection is the contract called
+        let payload = contract_call(
+            COLLECTION,
+            "bitcoin-monkeys",
+            "buy-in-ustx",
+            &[uint(7), commission()],
+        );
+        assert_eq!(arg_items(&payload)[0], item("Token ID", "7"));
+
+        // a purchase with other arguments is shown as usual
+        let payload = contract_call(
+            COLLECTION,
+            "bitcoin-monkeys",
+            "buy-in-ustx",
+            &[uint(7), uint(1)],
+        );
+        assert_eq!(arg_items(&payload)[0].title, "arg0");
+    }
+}
@@ -0,0 +1,181 @@
+//! StackingDAO liquid stacking.
+//!
+//! Deposits of STX mint stSTX, which is of 6 decimals as STX, and can name
+//! the principal that referred the depositor. Withdrawals first lock stSTX
+//! in exchange for an NFT, which is then redeemed for STX once the cycle
+//! ends. The STX deposited is always shown in STX, as users of liquid
+//! stacking compare it with the stSTX they get. The contracts the core ones
+//! call into, passed as traits, are shown as usual.
+
+use super::{Arg, ArgFormat, Contract, KnownFunction};
+use crate::lang::LABELS;
+use crate::parser::STX_DECIMALS;
+
+const CORE_V1: &[Contract] = &[Contract {
+    address: "SP4SZE494VC2YC5JYG7AYFQ44F5Q4PYV7DVMDPBG",
+    name: "stacking-dao-core-v1",
+}];
+
+const CORE_V2: &[Contract] = &[Contract {
+    address: "SP4SZE494VC2YC5JYG7AYFQ44F5Q4PYV7DVMDPBG",
+    name: "stacking-dao-core-v2",
+}];
+
+const STX_DEPOSITED: Option<Arg> = Some(Arg {
+    label: LABELS.stx_deposited,
+    format: ArgFormat::Token(STX_DECIMALS),
+});
+
+const REFERRER: Option<Arg> = Some(Arg {
+    label: LABELS.referrer,
+    format: ArgFormat::OptionalPrincipal,
+});
+
+const STSTX_WITHDRAWN: Option<Arg> = Some(Arg {
+    label: LABELS.ststx_withdrawn,
+    format: ArgFormat::Token(STX_DECIMALS),
+});
+
+const WITHDRAWAL_NFT: Option<Arg> = Some(Arg {
+    label: LABELS.withdrawal_nft,
+    format: ArgFormat::Number,
+});
+
+pub(super) const FUNCTIONS: &[KnownFunction] = &[
+    KnownFunction {
+        contracts: CORE_V1,
+        name: "deposit",
+        args: &[None, STX_DEPOSITED, REFERRER],
+    },
+    KnownFunction {
+        contracts: CORE_V1,
+        name: "init-withdraw",
+        args: &[None, STSTX_WITHDRAWN],
+    },
+    KnownFunction {
+        contracts: CORE_V1,
+        name: "withdraw",
+        args: &[None, WITHDRAWAL_NFT],
+    },
+    KnownFunction {
+        contracts: CORE_V2,
+        name: "deposit",
+        args: &[
+            None,
+            None,
+            None,
+            None,
+            STX_DEPOSITED,
+            REFERRER,
+            Some(Arg {
+                label: LABELS.stacking_pool,
+                format: ArgFormat::OptionalPrincipal,
+            }),
+        ],
+    },
+    KnownFunction {
+        contracts: CORE_V2,
+        name: "init-withdraw",
+        args: &[None, None, STSTX_WITHDRAWN],
+    },
+    KnownFunction {
+        contracts: CORE_V2,
+        name: "withdraw",
+        args: &[None, None, None, WITHDRAWAL_NFT],
+    },
+];
+
+#[cfg(test)]
+mod test {
+    use std::prelude::v1::*;
+
+    use super::super::test::{
+        arg_items, contract_call, contract_principal, item, some, standard_principal, uint,
+    };
+    use crate::parser::transaction_payload::TransactionPayload;
+    use crate::parser::ItemKind;
+
+    // SP4SZE494VC2YC5JYG7AYFQ44F5Q4PYV7DVMDPBG, the StackingDAO deployer
+    const STACKING_DAO: &str = "099fb88926d82f30b2f40eaf3ee423cb725bdb3b";
+
+    fn reserve() -> Vec<u8> {
+        contract_principal(STACKING_DAO, "reserve-v1")
+    }
+
+    #[test]
+    fn test_deposit() {
+        let referrer = some(standard_principal(&"00".repeat(20)));
+        let args = [reserve(), uint(1_250_500_000), referrer];
+        let payload = contract_call(STACKING_DAO, "stacking-dao-core-v1", "deposit", &args);
+        assert_eq!(
+            arg_items(&payload)[1..].to_vec(),
+            vec![
+                item("STX deposited", "1250.500000"),
+                item("Referrer", "SP000000000000000000002Q6VF78"),
+            ]
+        );
+        let (_, parsed) = TransactionPayload::from_bytes(&payload).unwrap();
+        assert_eq!(parsed.item_kind(5), ItemKind::Address);
+
+        // without a referrer
+        let args = [reserve(), uint(1), vec![0x09]];
+        let payload = contract_call(STACKING_DAO, "stacking-dao-core-v1", "deposit", &args);
+        assert_eq!(arg_items(&payload)[2], item("Referrer", "None"));
+        let (_, parsed) = TransactionPayload::from_bytes(&payload).unwrap();
+        assert_eq!(parsed.item_kind(5), ItemKind::Text);
+
+        // a referrer of another type is shown as usual
+        let args = [reserve(), uint(1), some(uint(1))];
+        let payload = contract_call(STACKING_DAO, "stacking-dao-core-v1", "deposit", &args);
+        assert_eq!(arg_items(&payload)[1].title, "arg1");
+    }
+
+    #[test]
+    fn test_deposit_v2() {
+        let mut args: Vec<_> = [
+            "reserve-v1",
+            "commission-v2",
+            "staking-v0",
+            "direct-helpers-v1",
+        ]
+        .iter()
+        .map(|name| contract_principal(STACKING_DAO, name))
+        .collect();
+        args.extend([
+            uint(5_000_000),
+            vec![0x09],
+            some(contract_principal(STACKING_DAO, "stacking-pool-v1")),
+        ]);
+        let payload = contract_call(STACKING_DAO, "stacking-dao-core-v2", "deposit", &args);
+        assert_eq!(
+            arg_items(&payload)[4..].to_vec(),
+            vec![
+                item("STX deposited", "5.000000"),
+                item("Referrer", "None"),
+                item(
+                    "Stacking pool",
+                    "SP4SZE494VC2YC5JYG7AYFQ44F5Q4PYV7DVMDPBG.stacking-pool-v1"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_withdraw() {
+        let payload = contract_call(
+            STACKING_DAO,
+            "stacking-dao-core-v1",
+            "init-withdraw",
+            &[reserve(), uint(99_000_001)],
+        );
+        assert_eq!(arg_items(&payload)[1], item("stSTX withdrawn", "99.000001"));
+
+        let payload = contract_call(
+            STACKING_DAO,
+            "stacking-dao-core-v1",
+            "withdraw",
+            &[reserve(), uint(731)],
+        );
+        assert_eq!(arg_items(&payload)[1], item("Withdrawal NFT id", "731"));
+    }
+}
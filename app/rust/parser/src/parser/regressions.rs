@@ -0,0 +1,50 @@
+//! Reproductions of the crashes found by fuzzing.
+//!
+//! Every tests/regressions/<name>.json holds the target and the input of a
+//! crash artifact, written by `corpus fixture`, see app/rust/corpus. Each one
+//! goes through its fuzz target again, as cargo fuzz would run the artifact,
+//! so a crash that was fixed stays fixed.
+
+use std::path::PathBuf;
+use std::string::String;
+use std::vec::Vec;
+
+use super::fuzzing;
+
+// the name, target and input of every fixture
+fn fixtures() -> Vec<(String, String, Vec<u8>)> {
+    let mut dir = PathBuf::new();
+    dir.push(env!("CARGO_MANIFEST_DIR"));
+    dir.push("tests");
+    dir.push("regressions");
+
+    let mut fixtures: Vec<_> = std::fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .map(|path| {
+            let str = std::fs::read_to_string(&path).unwrap();
+            let json: serde_json::Value = serde_json::from_str(&str).unwrap();
+            (
+                path.file_stem().unwrap().to_string_lossy().into_owned(),
+                String::from(json["target"].as_str().unwrap()),
+                hex::decode(json["input"].as_str().unwrap()).unwrap(),
+            )
+        })
+        .collect();
+    fixtures.sort();
+    fixtures
+}
+
+#[test]
+fn regressions() {
+    let fixtures = fixtures();
+    assert!(!fixtures.is_empty());
+
+    for (name, target, input) in fixtures {
+        let run = fuzzing::target(&target)
+            .unwrap_or_else(|| panic!("{} has an unknown target {}", name, target));
+        std::println!("{} ({})", name, target);
+        run(&input);
+    }
+}
@@ -0,0 +1,131 @@
+//! Bit-flip and truncation robustness tests.
+//!
+//! Every vector in tests/ is mutated one bit at a time and truncated at
+//! every length, and each result goes through the same entry points the
+//! app calls: parsing, counting and rendering the items and the hashes
+//! computed while the transaction is still being received. Bad input has
+//! to end in an error, never in a panic or a loop, and the diagnostic has
+//! to point at a failure exactly when parsing fails. Overflow checks are
+//! only on in debug builds, so these also run with --release.
+
+use std::sync::mpsc;
+use std::time::Duration;
+use std::vec::Vec;
+
+use super::vectors::transaction_vectors;
+use super::{Jwt, Message, ParsedObj, ParserError, StructuredMsg, Tag, Transaction};
+use crate::hash::DefaultSha512_256;
+
+// a whole vector with all its mutations takes well under a second
+const VECTOR_TIMEOUT: Duration = Duration::from_secs(60);
+
+fn review(bytes: &[u8]) -> Result<(), ParserError> {
+    let mut obj = ParsedObj::from_bytes(bytes)?;
+    obj.read(bytes)?;
+
+    if obj.get_type() == Tag::Transaction {
+        let tx = obj.transaction().unwrap();
+        let mut hash = [0u8; 32];
+        tx.initial_sighash::<DefaultSha512_256>(&mut hash)?;
+    }
+
+    let mut key = [0u8; 40];
+    let mut value = [0u8; 40];
+    for idx in 0..obj.num_items()? {
+        obj.item_kind(idx)?;
+        obj.item_section(idx)?;
+        let mut page_idx = 0;
+        let mut page_count = 1;
+        while page_idx < page_count {
+            page_count = obj.get_item(idx, &mut key, &mut value, page_idx)?;
+            page_idx += 1;
+        }
+    }
+    Ok(())
+}
+
+// the diagnostic of a transaction has to agree with parsing it
+fn check_diagnostic(name: &str, bytes: &[u8]) {
+    if Message::is_message(bytes) || Jwt::is_jwt(bytes) || StructuredMsg::is_msg(bytes) {
+        return;
+    }
+    let read = ParsedObj::from_bytes(bytes).and_then(|mut obj| obj.read(bytes));
+    let diagnostic = ParsedObj::diagnose(bytes);
+    assert_eq!(
+        read.is_err(),
+        diagnostic.is_some(),
+        "{} {:x?}: {:?} {:?}",
+        name,
+        bytes,
+        read,
+        diagnostic
+    );
+}
+
+// the hashing and code offsets the app computes before the whole
+// transaction arrives
+fn partial(bytes: &[u8]) {
+    let mut auth = [0u8; 128];
+    let _ = Transaction::initial_sighash_auth_from_bytes(bytes, &mut auth);
+    let _ = Transaction::code_body_offset(bytes);
+}
+
+// the bytes that can not be missing: code bodies that do not fit the
+// buffer are hashed and dropped, and multisig transactions are followed
+// by the data of the previous signer
+fn required_len(bytes: &[u8]) -> usize {
+    let tx = Transaction::from_bytes(bytes).unwrap();
+    let end = bytes.len() - tx.remainder.len();
+    Transaction::code_body_offset(bytes).map_or(end, |body| body.min(end))
+}
+
+// runs f over every vector on its own thread, failing the test
+// if any of them does not finish in time
+fn for_each_vector(f: fn(&str, Vec<u8>)) {
+    for (name, bytes) in transaction_vectors() {
+        let (done, wait) = mpsc::channel();
+        let thread_name = name.clone();
+        std::thread::spawn(move || {
+            f(&thread_name, bytes);
+            let _ = done.send(());
+        });
+        match wait.recv_timeout(VECTOR_TIMEOUT) {
+            Ok(()) => {}
+            Err(mpsc::RecvTimeoutError::Timeout) => panic!("{} did not finish, it loops", name),
+            Err(mpsc::RecvTimeoutError::Disconnected) => panic!("{} panicked", name),
+        }
+    }
+}
+
+#[test]
+fn test_bit_flips_do_not_panic() {
+    for_each_vector(|name, mut bytes| {
+        for at in 0..bytes.len() {
+            for bit in 0..8 {
+                bytes[at] ^= 1 << bit;
+                // a flip might still be a valid transaction,
+                // the result does not matter as long as there is one
+                let _ = review(&bytes);
+                partial(&bytes);
+                check_diagnostic(name, &bytes);
+                bytes[at] ^= 1 << bit;
+            }
+        }
+    });
+}
+
+#[test]
+fn test_truncations_are_rejected() {
+    for_each_vector(|name, bytes| {
+        let required = required_len(&bytes);
+        for len in 0..bytes.len() {
+            let truncated = &bytes[..len];
+            let result = review(truncated);
+            if len < required {
+                assert!(result.is_err(), "{} truncated at {}", name, len);
+            }
+            partial(truncated);
+            check_diagnostic(name, truncated);
+        }
+    });
+}
@@ -0,0 +1,389 @@
+//! Round trip property tests.
+//!
+//! Spending conditions, post conditions and payloads are generated as plain
+//! models, serialized, parsed and serialized again from what the parsed
+//! objects expose, the bytes have to be the same. The parser is zero-copy so
+//! a field read at the wrong offset or an encoding that is accepted but not
+//! canonical shows up as a difference between both serializations.
+
+use std::vec::Vec;
+
+use proptest::collection::vec;
+use proptest::prelude::*;
+use proptest::sample::select;
+
+use super::post_condition::{PostConditionPrincipal, TransactionPostCondition};
+use super::spending_condition::{SpendingConditionSignature, TransactionSpendingCondition};
+use super::transaction_payload::TransactionPayload;
+use super::{AssetInfo, HashModeInfo, PrincipalData, Value, HASH_MODES, TX_DEPTH_LIMIT};
+
+// contract, function and asset names
+fn name() -> impl Strategy<Value = Vec<u8>> {
+    "[a-zA-Z][a-zA-Z0-9_-]{0,39}".prop_map(|name| name.into_bytes())
+}
+
+fn hash160() -> impl Strategy<Value = Vec<u8>> {
+    vec(any::<u8>(), 20)
+}
+
+fn push_name(out: &mut Vec<u8>, name: &[u8]) {
+    out.push(name.len() as u8);
+    out.extend_from_slice(name);
+}
+
+fn clarity_value() -> impl Strategy<Value = Vec<u8>> {
+    let leaf = prop_oneof![
+        (0u8..=1, vec(any::<u8>(), 16)).prop_map(|(id, int)| [&[id][..], &int].concat()),
+        vec(any::<u8>(), 0..32).prop_map(|buf| {
+            let mut out = std::vec![0x02];
+            out.extend_from_slice(&(buf.len() as u32).to_be_bytes());
+            out.extend_from_slice(&buf);
+            out
+        }),
+        (0x03u8..=0x04).prop_map(|b| std::vec![b]),
+        (any::<u8>(), hash160()).prop_map(|(version, hash)| [&[0x05, version][..], &hash].concat()),
+        (any::<u8>(), hash160(), name()).prop_map(|(version, hash, name)| {
+            let mut out = [&[0x06, version][..], &hash].concat();
+            push_name(&mut out, &name);
+            out
+        }),
+        Just(std::vec![0x09]),
+        "[ -~]{0,32}".prop_map(|s| {
+            let mut out = std::vec![0x0d];
+            out.extend_from_slice(&(s.len() as u32).to_be_bytes());
+            out.extend_from_slice(s.as_bytes());
+            out
+        }),
+    ];
+    // arguments nested deeper than TX_DEPTH_LIMIT are not shown by the device
+    leaf.prop_recursive(2, 16, 4, |inner| {
+        prop_oneof![
+            (0x07u8..=0x08, inner.clone()).prop_map(|(id, v)| [&[id][..], &v].concat()),
+            inner.clone().prop_map(|v| [&[0x0a][..], &v].concat()),
+            vec(inner, 0..4).prop_map(|items| {
+                let mut out = std::vec![0x0b];
+                out.extend_from_slice(&(items.len() as u32).to_be_bytes());
+                items.iter().for_each(|item| out.extend_from_slice(item));
+                out
+            }),
+        ]
+    })
+}
+
+fn spending_condition() -> impl Strategy<Value = Vec<u8>> {
+    let modes = |multisig: bool| {
+        let modes: Vec<HashModeInfo> = HASH_MODES
+            .iter()
+            .filter(|info| info.multisig == multisig)
+            .copied()
+            .collect();
+        select(modes)
+    };
+
+    let singlesig = modes(false)
+        // segwit keys are always compressed
+        .prop_flat_map(|info| (Just(info.mode as u8), 0u8..=u8::from(!info.segwit)))
+        .prop_flat_map(|(mode, encoding)| (Just(mode), Just(encoding), vec(any::<u8>(), 65)))
+        .prop_map(|(mode, encoding, signature)| (mode, [&[encoding][..], &signature].concat()));
+
+    let field = (0u8..=3).prop_flat_map(|id| {
+        let len = if id < 2 { 33 } else { 65 };
+        vec(any::<u8>(), len).prop_map(move |field| [&[id][..], &field].concat())
+    });
+    let multisig = (
+        modes(true).prop_map(|info| info.mode as u8),
+        vec(field, 0..5),
+        any::<u16>(),
+    )
+        .prop_map(|(mode, fields, required)| {
+            let mut out = (fields.len() as u32).to_be_bytes().to_vec();
+            fields.iter().for_each(|field| out.extend_from_slice(field));
+            out.extend_from_slice(&required.to_be_bytes());
+            (mode, out)
+        });
+
+    (
+        prop_oneof![singlesig, multisig],
+        hash160(),
+        any::<u64>(),
+        any::<u64>(),
+    )
+        .prop_map(|((mode, signature), hash, nonce, fee)| {
+            let mut out = std::vec![mode];
+            out.extend_from_slice(&hash);
+            out.extend_from_slice(&nonce.to_be_bytes());
+            out.extend_from_slice(&fee.to_be_bytes());
+            out.extend_from_slice(&signature);
+            out
+        })
+}
+
+fn post_condition_principal() -> impl Strategy<Value = Vec<u8>> {
+    prop_oneof![
+        Just(std::vec![0x01]),
+        (any::<u8>(), hash160()).prop_map(|(version, hash)| [&[0x02, version][..], &hash].concat()),
+        (any::<u8>(), hash160(), name()).prop_map(|(version, hash, name)| {
+            let mut out = [&[0x03, version][..], &hash].concat();
+            push_name(&mut out, &name);
+            out
+        }),
+    ]
+}
+
+fn asset_info() -> impl Strategy<Value = Vec<u8>> {
+    (any::<u8>(), hash160(), name(), name()).prop_map(|(version, hash, contract, asset)| {
+        let mut out = [&[version][..], &hash].concat();
+        push_name(&mut out, &contract);
+        push_name(&mut out, &asset);
+        out
+    })
+}
+
+fn post_condition() -> impl Strategy<Value = Vec<u8>> {
+    prop_oneof![
+        (post_condition_principal(), 1u8..=5, any::<u64>()).prop_map(
+            |(principal, code, amount)| {
+                [&[0x00][..], &principal, &[code], &amount.to_be_bytes()].concat()
+            }
+        ),
+        (
+            post_condition_principal(),
+            asset_info(),
+            1u8..=5,
+            any::<u64>()
+        )
+            .prop_map(|(principal, asset, code, amount)| {
+                [
+                    &[0x01][..],
+                    &principal,
+                    &asset,
+                    &[code],
+                    &amount.to_be_bytes(),
+                ]
+                .concat()
+            }),
+        (
+            post_condition_principal(),
+            asset_info(),
+            clarity_value(),
+            0x10u8..=0x11
+        )
+            .prop_map(|(principal, asset, value, code)| {
+                [&[0x02][..], &principal, &asset, &value, &[code]].concat()
+            }),
+    ]
+}
+
+fn payload() -> impl Strategy<Value = Vec<u8>> {
+    let recipient = prop_oneof![
+        (any::<u8>(), hash160()).prop_map(|(version, hash)| [&[0x05, version][..], &hash].concat()),
+        (any::<u8>(), hash160(), name()).prop_map(|(version, hash, name)| {
+            let mut out = [&[0x06, version][..], &hash].concat();
+            push_name(&mut out, &name);
+            out
+        }),
+    ];
+    prop_oneof![
+        (recipient, any::<u64>(), vec(any::<u8>(), 34)).prop_map(|(recipient, amount, memo)| {
+            [&[0x00][..], &recipient, &amount.to_be_bytes(), &memo].concat()
+        }),
+        (prop::option::of(1..=4u8), name(), "[ -~\n]{0,64}").prop_map(|(version, name, code)| {
+            let mut out = match version {
+                Some(version) => std::vec![0x06, version],
+                None => std::vec![0x01],
+            };
+            push_name(&mut out, &name);
+            out.extend_from_slice(&(code.len() as u32).to_be_bytes());
+            out.extend_from_slice(code.as_bytes());
+            out
+        }),
+        (
+            any::<u8>(),
+            hash160(),
+            name(),
+            name(),
+            vec(clarity_value(), 0..5)
+        )
+            .prop_map(|(version, hash, contract, function, args)| {
+                let mut out = [&[0x02, version][..], &hash].concat();
+                push_name(&mut out, &contract);
+                push_name(&mut out, &function);
+                out.extend_from_slice(&(args.len() as u32).to_be_bytes());
+                args.iter().for_each(|arg| out.extend_from_slice(arg));
+                out
+            }),
+    ]
+}
+
+fn serialize_spending_condition(condition: &TransactionSpendingCondition) -> Vec<u8> {
+    let signer = &condition.signer;
+    let mut out = std::vec![signer.hash_mode().unwrap() as u8];
+    out.extend_from_slice(signer.pub_key_hash());
+    out.extend_from_slice(&signer.nonce().unwrap().to_be_bytes());
+    out.extend_from_slice(&signer.fee().unwrap().to_be_bytes());
+
+    match condition.signature() {
+        SpendingConditionSignature::Singlesig(sig) => {
+            out.push(sig.key_encoding().unwrap() as u8);
+            out.extend_from_slice(sig.signature());
+        }
+        SpendingConditionSignature::Multisig(sig) => {
+            out.extend_from_slice(&sig.num_fields().unwrap().to_be_bytes());
+            for (id, field) in sig.auth_fields() {
+                out.push(id);
+                out.extend_from_slice(field);
+            }
+            out.extend_from_slice(&sig.required_signatures().unwrap().to_be_bytes());
+        }
+    }
+    out
+}
+
+fn serialize_post_condition(condition: &TransactionPostCondition) -> Vec<u8> {
+    let inner = condition.get_inner_bytes();
+    let (rem, principal) = PostConditionPrincipal::from_bytes(inner).unwrap();
+
+    let mut out = Vec::new();
+    if condition.is_stx() {
+        out.push(0x00);
+    } else if condition.is_fungible() {
+        out.push(0x01);
+    } else {
+        out.push(0x02);
+    }
+
+    match principal {
+        PostConditionPrincipal::Origin => out.push(0x01),
+        PostConditionPrincipal::Standard(address) => {
+            out.push(0x02);
+            out.extend_from_slice(address.0);
+        }
+        PostConditionPrincipal::Contract(address, name) => {
+            out.push(0x03);
+            out.extend_from_slice(address.0);
+            push_name(&mut out, name.name());
+        }
+    }
+    assert_eq!(condition.is_origin_principal(), principal.is_origin());
+    assert_eq!(condition.is_standard_principal(), principal.is_standard());
+    assert_eq!(condition.is_contract_principal(), principal.is_contract());
+
+    if condition.is_stx() {
+        out.push(condition.fungible_condition_code().unwrap() as u8);
+        out.extend_from_slice(&condition.amount_stx().unwrap().to_be_bytes());
+        return out;
+    }
+
+    let (rem, asset) = AssetInfo::from_bytes(rem).unwrap();
+    out.extend_from_slice(asset.address.0);
+    push_name(&mut out, asset.contract_name.name());
+    push_name(&mut out, asset.asset_name());
+    assert_eq!(condition.asset_name(), Some(asset.asset_name()));
+
+    if condition.is_fungible() {
+        out.push(condition.fungible_condition_code().unwrap() as u8);
+        out.extend_from_slice(&condition.tokens_amount().unwrap().to_be_bytes());
+    } else {
+        let (_, value) = Value::from_bytes::<TX_DEPTH_LIMIT>(rem).unwrap();
+        out.extend_from_slice(value.bytes());
+        out.push(condition.non_fungible_condition_code().unwrap() as u8);
+    }
+    out
+}
+
+fn serialize_payload(payload: &TransactionPayload) -> Vec<u8> {
+    let mut out = std::vec![payload.id() as u8];
+    match payload {
+        TransactionPayload::TokenTransfer(token) => {
+            match token.recipient().unwrap() {
+                PrincipalData::Standard(principal) => {
+                    out.extend_from_slice(&[0x05, principal.version()]);
+                    out.extend_from_slice(principal.raw_address());
+                }
+                PrincipalData::Contract(principal) => {
+                    out.extend_from_slice(&[0x06, principal.version()]);
+                    out.extend_from_slice(principal.raw_address());
+                    push_name(&mut out, principal.contract_name().name());
+                }
+            }
+            assert_eq!(
+                token.raw_address(),
+                token.recipient().unwrap().raw_address()
+            );
+            out.extend_from_slice(&token.amount().unwrap().to_be_bytes());
+            out.extend_from_slice(token.memo());
+        }
+        TransactionPayload::SmartContract(contract) => {
+            out.extend(contract.clarity_version());
+            push_name(&mut out, contract.contract_name().unwrap().name());
+            let body = &contract.raw()[contract.code_body_offset().unwrap()..];
+            out.extend_from_slice(&(body.len() as u32).to_be_bytes());
+            out.extend_from_slice(body);
+        }
+        TransactionPayload::ContractCall(call) => {
+            out.extend_from_slice(call.address().unwrap().0);
+            push_name(&mut out, call.contract_name().unwrap().name());
+            push_name(&mut out, call.function_name().unwrap());
+            let args = call.function_args().unwrap();
+            let num_args = args.num_args().unwrap();
+            out.extend_from_slice(&num_args.to_be_bytes());
+            for idx in 0..num_args as usize {
+                out.extend_from_slice(args.argument_at(idx).unwrap().bytes());
+            }
+        }
+    }
+    out
+}
+
+proptest! {
+    #[test]
+    fn spending_condition_round_trip(bytes in spending_condition(), trailing in vec(any::<u8>(), 0..8)) {
+        let input = [&bytes[..], &trailing].concat();
+        let (rem, condition) = TransactionSpendingCondition::from_bytes(&input).unwrap();
+        prop_assert_eq!(rem, &trailing[..]);
+        prop_assert_eq!(serialize_spending_condition(&condition), bytes);
+    }
+
+    #[test]
+    fn mutated_spending_condition_is_canonical(
+        mut bytes in spending_condition(),
+        at in any::<prop::sample::Index>(),
+        flip in 1u8..,
+    ) {
+        let at = at.index(bytes.len());
+        bytes[at] ^= flip;
+        if let Ok((rem, condition)) = TransactionSpendingCondition::from_bytes(&bytes) {
+            let consumed = &bytes[..bytes.len() - rem.len()];
+            prop_assert_eq!(serialize_spending_condition(&condition), consumed);
+        }
+    }
+
+    #[test]
+    fn post_condition_round_trip(bytes in post_condition(), trailing in vec(any::<u8>(), 0..8)) {
+        let input = [&bytes[..], &trailing].concat();
+        let (rem, condition) = TransactionPostCondition::from_bytes(&input).unwrap();
+        prop_assert_eq!(rem, &trailing[..]);
+        prop_assert_eq!(serialize_post_condition(&condition), bytes);
+    }
+
+    #[test]
+    fn mutated_post_condition_is_canonical(
+        mut bytes in post_condition(),
+        at in any::<prop::sample::Index>(),
+        flip in 1u8..,
+    ) {
+        let at = at.index(bytes.len());
+        bytes[at] ^= flip;
+        if let Ok((rem, condition)) = TransactionPostCondition::from_bytes(&bytes) {
+            let consumed = &bytes[..bytes.len() - rem.len()];
+            prop_assert_eq!(serialize_post_condition(&condition), consumed);
+        }
+    }
+
+    #[test]
+    fn payload_round_trip(bytes in payload()) {
+        let (rem, payload) = TransactionPayload::from_bytes(&bytes).unwrap();
+        prop_assert!(rem.is_empty());
+        prop_assert_eq!(serialize_payload(&payload), bytes);
+    }
+}
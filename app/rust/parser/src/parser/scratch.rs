@@ -0,0 +1,102 @@
+use core::cell::{Cell, UnsafeCell};
+
+use super::{ClarityName, ParserError, C32_ENCODED_ADDRS_LENGTH};
+
+// Enough for the biggest value formatted at once,
+// a contract principal: address + '.' + contract_name
+pub const SCRATCH_LEN: usize = C32_ENCODED_ADDRS_LENGTH + ClarityName::MAX_LEN as usize + 1;
+
+/// Buffer owned by the parser state where items are formatted before
+/// being paged out, instead of using temporary buffers on the stack
+/// of the deepest display paths.
+///
+/// Space is handed out in order and released all at once by reset,
+/// which is done before formatting every item.
+#[repr(C)]
+pub struct Scratch {
+    buf: UnsafeCell<[u8; SCRATCH_LEN]>,
+    used: Cell<usize>,
+}
+
+impl Scratch {
+    pub const fn new() -> Self {
+        Self {
+            buf: UnsafeCell::new([0; SCRATCH_LEN]),
+            used: Cell::new(0),
+        }
+    }
+
+    /// Releases all the space handed out so far
+    pub fn reset(&mut self) {
+        self.used.set(0);
+    }
+
+    /// Returns a zeroed slice of len bytes
+    #[allow(clippy::mut_from_ref)]
+    pub fn alloc(&self, len: usize) -> Result<&mut [u8], ParserError> {
+        let start = self.used.get();
+        let end = start
+            .checked_add(len)
+            .filter(|end| *end <= SCRATCH_LEN)
+            .ok_or(ParserError::parser_unexpected_buffer_end)?;
+        self.used.set(end);
+
+        // safety: the returned slices never overlap, the space is only
+        // released by reset which needs them to be gone already
+        let slice =
+            unsafe { core::slice::from_raw_parts_mut(self.buf.get().cast::<u8>().add(start), len) };
+        slice.iter_mut().for_each(|v| *v = 0);
+        Ok(slice)
+    }
+
+    /// Allocates the space for all the parts and copies them one after another
+    pub fn concat(&self, parts: &[&[u8]]) -> Result<&[u8], ParserError> {
+        let len = parts.iter().map(|part| part.len()).sum();
+        let out = self.alloc(len)?;
+        let mut offset = 0;
+        for part in parts {
+            let end = offset + part.len();
+            out.get_mut(offset..end)
+                .ok_or(ParserError::parser_unexpected_buffer_end)?
+                .copy_from_slice(part);
+            offset = end;
+        }
+        Ok(out)
+    }
+}
+
+impl Default for Scratch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_scratch_alloc() {
+        let mut scratch = Scratch::new();
+        {
+            let first = scratch.alloc(10).unwrap();
+            first.iter_mut().for_each(|v| *v = 0xff);
+            let second = scratch.alloc(SCRATCH_LEN - 10).unwrap();
+            assert!(second.iter().all(|v| *v == 0));
+            assert_eq!(first, &[0xff; 10]);
+            assert!(scratch.alloc(1).is_err());
+        }
+
+        scratch.reset();
+        assert_eq!(scratch.alloc(SCRATCH_LEN).unwrap().len(), SCRATCH_LEN);
+        assert!(scratch.alloc(usize::MAX).is_err());
+    }
+
+    #[test]
+    fn test_scratch_concat() {
+        let scratch = Scratch::new();
+        let joined = scratch.concat(&[&b"SP000"[..], b".", b"contract"]).unwrap();
+        assert_eq!(joined, b"SP000.contract");
+        assert!(scratch.concat(&[&[0; SCRATCH_LEN]]).is_err());
+    }
+}
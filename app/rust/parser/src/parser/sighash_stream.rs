@@ -0,0 +1,291 @@
+//! Initial sighash of a transaction, computed while it is received.
+//!
+//! The header and auth fields are hashed once they are complete, cleared as
+//! for the initial sighash, and the rest of the transaction as it arrives.
+//! Only the code body of a smart contract deploy can be hashed and dropped
+//! when it does not fit the transaction buffer, every other payload is shown
+//! and has to be resident to be reviewed. The body is scanned for what it
+//! can do with assets as it is dropped.
+
+use super::code_scan::CodeScan;
+use super::transaction::MULTISIG_PREVIOUS_SIGNER_DATA_LEN;
+use super::transaction_auth::INITIAL_SIGHASH_AUTH_LEN;
+use super::{ParserError, Transaction};
+use crate::hash::{Sha512_256, SHA512_256_LEN};
+use crate::zeroize::Zeroizing;
+
+// version, chain id, auth type, origin hash mode and signer
+const FIRST_BLOCK_LEN: usize = 27;
+
+pub struct SighashStream<H> {
+    // None until the header and auth fields are hashed
+    hasher: Option<H>,
+    // offset at which the last transaction block starts
+    body_offset: usize,
+    // bytes of the buffer hashed so far
+    hashed: usize,
+    // bytes hashed without ever being in the buffer
+    skipped: usize,
+    // what the dropped part of the code body can do with assets
+    code_scan: CodeScan,
+}
+
+impl<H: Sha512_256 + Clone> SighashStream<H> {
+    pub const fn new() -> Self {
+        Self {
+            hasher: None,
+            body_offset: 0,
+            hashed: 0,
+            skipped: 0,
+            code_scan: CodeScan::new(),
+        }
+    }
+
+    /// Drops the hash computed so far
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    /// Hashes the bytes of data received since the last call, data being
+    /// the transaction buffer
+    pub fn update(&mut self, data: &[u8]) {
+        if self.hasher.is_none() && self.start(data).is_err() {
+            // not received yet, or this is not a transaction at all
+            return;
+        }
+
+        // hold back the bytes that could be the previous signer data,
+        // they are hashed on finish if they are part of the transaction
+        let end = data.len().saturating_sub(MULTISIG_PREVIOUS_SIGNER_DATA_LEN);
+        self.hash_to(data, end);
+    }
+
+    fn start(&mut self, data: &[u8]) -> Result<(), ParserError> {
+        let mut auth = Zeroizing::new([0u8; INITIAL_SIGHASH_AUTH_LEN]);
+        let (auth_end, auth_len) =
+            Transaction::initial_sighash_auth_from_bytes(data, &mut auth[..])?;
+        let first_block = data
+            .get(..FIRST_BLOCK_LEN)
+            .filter(|_| auth_end >= FIRST_BLOCK_LEN)
+            .ok_or(ParserError::parser_unexpected_buffer_end)?;
+        let auth = auth
+            .get(..auth_len)
+            .ok_or(ParserError::parser_unexpected_buffer_end)?;
+
+        let mut hasher = H::new();
+        hasher.update(first_block);
+        hasher.update(auth);
+        self.hasher = Some(hasher);
+        self.body_offset = auth_end;
+        self.hashed = auth_end;
+        Ok(())
+    }
+
+    // hashes the bytes of data from where the last call stopped up to end
+    fn hash_to(&mut self, data: &[u8], end: usize) {
+        if let (Some(hasher), Some(bytes)) = (self.hasher.as_mut(), data.get(self.hashed..end)) {
+            hasher.update(bytes);
+            self.hashed = end;
+        }
+    }
+
+    /// Hashes a chunk that does not fit the transaction buffer data and
+    /// drops it. This is only possible within the code body of a smart
+    /// contract deploy, once everything before it is in the buffer, and
+    /// every chunk after the first one dropped has to be dropped as well
+    pub fn skip(&mut self, data: &[u8], chunk: &[u8]) -> Result<(), ParserError> {
+        if self.hasher.is_none() || data.len() < self.hashed {
+            return Err(ParserError::parser_no_data);
+        }
+        if self.skipped > 0 && data.len() != self.hashed {
+            return Err(ParserError::parser_unexpected_buffer_end);
+        }
+
+        // everything up to the code body has to stay for the review
+        let body = Transaction::code_body_offset(data)?;
+        let resident = data
+            .get(body..)
+            .ok_or(ParserError::parser_unexpected_buffer_end)?;
+
+        // the part of the code body in the buffer is scanned before the
+        // first chunk dropped, an expression might be split between them
+        if self.skipped == 0 {
+            self.code_scan.update(resident);
+        }
+        self.code_scan.update(chunk);
+
+        // the code body swallows the rest of the transaction, so there is no
+        // previous signer data to hold back, the whole buffer is hashed first
+        // to keep the order
+        self.hash_to(data, data.len());
+        if let Some(hasher) = self.hasher.as_mut() {
+            hasher.update(chunk);
+        }
+        self.skipped += chunk.len();
+        Ok(())
+    }
+
+    /// Bytes of the transaction that were hashed and dropped
+    pub fn skipped(&self) -> usize {
+        self.skipped
+    }
+
+    /// The scan of the whole code body, None if nothing was dropped and the
+    /// body can be scanned from the buffer
+    pub fn code_scan(&self) -> Option<&CodeScan> {
+        if self.skipped == 0 {
+            return None;
+        }
+        Some(&self.code_scan)
+    }
+
+    /// Completes the initial sighash with the last transaction block found
+    /// by the parser, at offset last_block of data. Fails if the streamed
+    /// hash does not cover the same blocks, in that case the hash has to be
+    /// computed from the whole buffer
+    pub fn finish(
+        &self,
+        data: &[u8],
+        last_block: usize,
+        last_block_len: usize,
+        out: &mut [u8; SHA512_256_LEN],
+    ) -> Result<(), ParserError> {
+        let hasher = self.hasher.as_ref().ok_or(ParserError::parser_no_data)?;
+        if last_block != self.body_offset {
+            return Err(ParserError::parser_no_data);
+        }
+        let rest = last_block
+            .checked_add(last_block_len)
+            .and_then(|end| data.get(self.hashed..end))
+            .ok_or(ParserError::parser_no_data)?;
+
+        // finish over a copy, the hash might be requested more than once
+        let mut hasher = hasher.clone();
+        hasher.update(rest);
+        hasher.finalize(out);
+        Ok(())
+    }
+}
+
+impl<H: Sha512_256 + Clone> Default for SighashStream<H> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::code_scan::MOVES_STX;
+    use super::super::vectors::transaction_vectors;
+    use super::*;
+    use crate::hash::DefaultSha512_256;
+    use std::vec::Vec;
+
+    // the initial sighash of a transaction fully in the buffer
+    fn buffered_sighash(bytes: &[u8]) -> [u8; SHA512_256_LEN] {
+        let tx = Transaction::from_bytes(bytes).unwrap();
+        let mut hash = [0u8; SHA512_256_LEN];
+        tx.initial_sighash::<DefaultSha512_256>(&mut hash).unwrap();
+        hash
+    }
+
+    fn streamed_sighash(
+        stream: &SighashStream<DefaultSha512_256>,
+        buffer: &[u8],
+    ) -> [u8; SHA512_256_LEN] {
+        let tx = Transaction::from_bytes(buffer).unwrap();
+        let last_block = tx.last_transaction_block();
+        let offset = last_block.as_ptr() as usize - buffer.as_ptr() as usize;
+        let mut hash = [0u8; SHA512_256_LEN];
+        stream
+            .finish(buffer, offset, last_block.len(), &mut hash)
+            .unwrap();
+        hash
+    }
+
+    #[test]
+    fn test_streamed_sighash_matches_buffered() {
+        for (name, bytes) in transaction_vectors() {
+            for chunk_len in [1, 7, 250] {
+                let mut stream = SighashStream::<DefaultSha512_256>::new();
+                let mut received = 0;
+                while received < bytes.len() {
+                    received = (received + chunk_len).min(bytes.len());
+                    stream.update(&bytes[..received]);
+                }
+                assert_eq!(stream.skipped(), 0, "{}", name);
+                assert!(stream.code_scan().is_none(), "{}", name);
+                assert_eq!(
+                    streamed_sighash(&stream, &bytes),
+                    buffered_sighash(&bytes),
+                    "{} in chunks of {}",
+                    name,
+                    chunk_len
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_skipped_code_body_matches_buffered() {
+        let (_, deploy) = transaction_vectors()
+            .into_iter()
+            .find(|(name, _)| name == "standard_smart_contract")
+            .unwrap();
+
+        const BUFFER_LEN: usize = 600;
+        const CHUNK_LEN: usize = 250;
+
+        // the same deploy with a code body far larger than the buffer, an
+        // stx-transfer? split between two dropped chunks
+        let body_offset = Transaction::code_body_offset(&deploy).unwrap();
+        let mut code = Vec::new();
+        while code.len() < 3000 {
+            code.extend_from_slice(b"(define-read-only (get-one) (ok u1))\n");
+        }
+        while !(body_offset + code.len() + 5).is_multiple_of(CHUNK_LEN) {
+            code.push(b' ');
+        }
+        code.extend_from_slice(b"(stx-transfer? u1 tx-sender 'SP000000000000000000002Q6VF78)");
+        let bytes = [
+            &deploy[..body_offset - 4],
+            &(code.len() as u32).to_be_bytes(),
+            &code,
+        ]
+        .concat();
+
+        let mut stream = SighashStream::<DefaultSha512_256>::new();
+        let mut buffer = Vec::new();
+        for chunk in bytes.chunks(CHUNK_LEN) {
+            if stream.skipped() == 0 && buffer.len() + chunk.len() <= BUFFER_LEN {
+                buffer.extend_from_slice(chunk);
+                stream.update(&buffer);
+            } else {
+                stream.skip(&buffer, chunk).unwrap();
+            }
+        }
+
+        assert_eq!(buffer.len() + stream.skipped(), bytes.len());
+        assert_eq!(streamed_sighash(&stream, &buffer), buffered_sighash(&bytes));
+        assert_eq!(stream.code_scan().unwrap().capabilities(), MOVES_STX);
+
+        // the buffer has to end where the hashed bytes do
+        buffer.push(b' ');
+        assert!(stream.skip(&buffer, b" ").is_err());
+    }
+
+    #[test]
+    fn test_skip_outside_code_body() {
+        let chunk = [0u8; 16];
+        for (name, bytes) in transaction_vectors() {
+            let mut stream = SighashStream::<DefaultSha512_256>::new();
+
+            // nothing to drop before the auth fields are hashed
+            assert!(stream.skip(&bytes[..10], &chunk).is_err(), "{}", name);
+
+            stream.update(&bytes);
+            let deploy = Transaction::code_body_offset(&bytes).is_ok();
+            assert_eq!(stream.skip(&bytes, &chunk).is_ok(), deploy, "{}", name);
+        }
+    }
+}
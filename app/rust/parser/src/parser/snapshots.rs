@@ -0,0 +1,111 @@
+//! Snapshots of the review items of the test vectors.
+//!
+//! Every vector in tests/ is parsed as the device does and the ordered
+//! items are rendered page by page, along with their kind, into
+//! tests/snapshots/<vector>.txt. Any change in the wording, order or
+//! paging of the review shows up as a snapshot diff, run the tests with
+//! UPDATE_SNAPSHOTS=1 to accept it.
+
+use std::fmt::Write;
+use std::path::PathBuf;
+use std::string::String;
+use std::vec::Vec;
+
+use super::vectors::transaction_vectors;
+use super::ParsedObj;
+
+// the key and value buffers of the C++ UI dump in tests/cpp
+const KEY_LEN: usize = 40;
+const VALUE_LEN: usize = 40;
+
+fn c_str(buf: &[u8]) -> &str {
+    let len = buf.iter().position(|b| *b == 0).unwrap_or(buf.len());
+    core::str::from_utf8(&buf[..len]).unwrap()
+}
+
+// one line per page: index, kind, title and value
+fn render(bytes: &[u8]) -> String {
+    let mut out = String::new();
+    let mut obj = match ParsedObj::from_bytes(bytes) {
+        Ok(obj) => obj,
+        Err(e) => return std::format!("error: {:?}\n", e),
+    };
+    if let Err(e) = obj.read(bytes) {
+        return std::format!("error: {:?}\n", e);
+    }
+    let num_items = obj.num_items().unwrap();
+
+    for idx in 0..num_items {
+        let kind = obj.item_kind(idx).unwrap();
+        let mut page_idx = 0;
+        let mut page_count = 1;
+        while page_idx < page_count {
+            let mut key = [0u8; KEY_LEN];
+            let mut value = [0u8; VALUE_LEN];
+            match obj.get_item(idx, &mut key, &mut value, page_idx) {
+                Ok(count) => page_count = count,
+                Err(e) => {
+                    writeln!(
+                        out,
+                        "{} | {:?} | {} : error: {:?}",
+                        idx,
+                        kind,
+                        c_str(&key),
+                        e
+                    )
+                    .unwrap();
+                    break;
+                }
+            }
+            let title = if page_count > 1 {
+                std::format!("{} [{}/{}]", c_str(&key), page_idx + 1, page_count)
+            } else {
+                String::from(c_str(&key))
+            };
+            writeln!(out, "{} | {:?} | {} : {}", idx, kind, title, c_str(&value)).unwrap();
+            page_idx += 1;
+        }
+    }
+    out
+}
+
+#[test]
+fn test_review_items_match_snapshots() {
+    let mut dir = PathBuf::new();
+    dir.push(env!("CARGO_MANIFEST_DIR"));
+    dir.push("tests");
+    dir.push("snapshots");
+
+    let update = std::env::var_os("UPDATE_SNAPSHOTS").is_some();
+    let mut mismatches = Vec::new();
+
+    for (name, bytes) in transaction_vectors() {
+        let rendered = render(&bytes);
+        let path = dir.join(std::format!("{}.txt", name));
+
+        if update {
+            std::fs::create_dir_all(&dir).unwrap();
+            std::fs::write(&path, &rendered).unwrap();
+            continue;
+        }
+
+        match std::fs::read_to_string(&path) {
+            Ok(expected) if expected == rendered => {}
+            Ok(expected) => {
+                std::println!("{} expected:\n{}", name, expected);
+                std::println!("{} got:\n{}", name, rendered);
+                mismatches.push(name);
+            }
+            Err(_) => {
+                std::println!("{} has no snapshot, got:\n{}", name, rendered);
+                mismatches.push(name);
+            }
+        }
+    }
+
+    assert!(
+        mismatches.is_empty(),
+        "review items changed for {:?}, run with UPDATE_SNAPSHOTS=1 to accept them",
+        mismatches
+    );
+}
@@ -1,30 +1,23 @@
-use core::convert::TryFrom;
+use core::convert::{TryFrom, TryInto};
 
-use nom::{
-    bytes::complete::take,
-    number::complete::{be_u16, be_u32, be_u64},
-};
-
-use arrayvec::ArrayVec;
+use super::reader::{be_u16, be_u32, be_u64, take};
 
-use crate::parser::c32;
+use crate::hash::{Sha512_256, SHA512_256_LEN};
+use crate::parser::c32::{self, C32Address};
 use crate::parser::error::ParserError;
 use crate::parser::parser_common::{
     HashMode, TransactionVersion, C32_ENCODED_ADDRS_LENGTH, SIGNATURE_LEN,
 };
 use crate::{check_canary, zxformat};
 
-// this includes:
-// 16-byte origin fee and nonce
-// 66-byte origin signature
-const STANDARD_SINGLESIG_AUTH_LEN: usize = 82;
+// 8-byte nonce and 8-byte fee, cleared in the initial sighash
+const FEE_NONCE_LEN: usize = 16;
 
 // according to the docs, de vector of fields should be cleared
 // so:
-// 16-byte origin fee and nonce
 // 4-byte num auth fields
 // 2-byte num signatures required
-const STANDARD_MULTISIG_AUTH_LEN: usize = 22;
+const CLEARED_MULTISIG_LEN: usize = 6;
 
 // This includes:
 // - 1-byte hash mode
@@ -36,6 +29,55 @@ const SPENDING_CONDITION_SIGNER_LEN: usize = 37;
 // we take 65-byte signature + 1-byte signature public-key encoding type
 const SINGLE_SPENDING_CONDITION_LEN: usize = 66;
 
+// What is known of a signature once it is chained:
+// 32-byte post_sig_hash
+// 1-byte pubkey encoding
+// 65-byte signature
+pub const SIGNED_FIELD_LEN: usize = SHA512_256_LEN + SINGLE_SPENDING_CONDITION_LEN;
+
+// 1-byte auth field id and the 65-byte recoverable signature
+pub const SIGNATURE_AUTH_FIELD_LEN: usize = 1 + SIGNATURE_LEN;
+
+/// The auth field of a signature by a compressed key, the exact bytes a
+/// coordinator puts in the auth fields of a multisig transaction. rsv is
+/// the signature as the device returns it, stacks-core takes the recovery
+/// id first
+pub fn signature_auth_field(rsv: &[u8; SIGNATURE_LEN], out: &mut [u8; SIGNATURE_AUTH_FIELD_LEN]) {
+    let (v, rs) = rsv.split_last().unwrap_or((&0, &[]));
+    let head = [TransactionAuthFieldID::SignatureCompressed as u8, *v];
+    out.iter_mut()
+        .zip(head.iter().chain(rs.iter()))
+        .for_each(|(dst, src)| *dst = *src);
+}
+
+/// The post_sig_hash of a signature over presig, the hash the next
+/// signer of a multisig account appends its auth data to
+pub fn post_sig_hash<H: Sha512_256>(
+    presig: &[u8; SHA512_256_LEN],
+    encoding: u8,
+    signature: &[u8],
+    out: &mut [u8; SHA512_256_LEN],
+) {
+    let mut hasher = H::new();
+    hasher.update(presig);
+    hasher.update(&[encoding]);
+    hasher.update(signature);
+    hasher.finalize(out);
+}
+
+/// The pre_sig_hash a signer signs, after the post_sig_hash of the
+/// previous one, with its auth flag, fee and nonce
+pub fn next_presig_hash<H: Sha512_256>(
+    post_sig: &[u8; SHA512_256_LEN],
+    auth_data: &[u8],
+    out: &mut [u8; SHA512_256_LEN],
+) {
+    let mut hasher = H::new();
+    hasher.update(post_sig);
+    hasher.update(auth_data);
+    hasher.finalize(out);
+}
+
 #[repr(u8)]
 #[derive(Clone, PartialEq, Copy)]
 #[cfg_attr(test, derive(Debug))]
@@ -49,10 +91,7 @@ impl TransactionPublicKeyEncoding {
     // BIPs 141 and 143 make it very clear that P2WPKH scripts may be only derived
     // from compressed public-keys
     fn is_valid_hash_mode(self, mode: HashMode) -> bool {
-        if mode == HashMode::P2WPKH && self != Self::Compressed {
-            return false;
-        }
-        true
+        self == Self::Compressed || !mode.is_segwit()
     }
 }
 
@@ -84,8 +123,10 @@ pub struct SpendingConditionSigner<'a> {
 impl<'a> SpendingConditionSigner<'a> {
     #[inline(never)]
     pub fn from_bytes(bytes: &'a [u8]) -> nom::IResult<&[u8], Self, ParserError> {
-        let (raw, _) = take(SPENDING_CONDITION_SIGNER_LEN)(bytes)?;
-        let data = arrayref::array_ref!(bytes, 0, SPENDING_CONDITION_SIGNER_LEN);
+        let (raw, data) = take(SPENDING_CONDITION_SIGNER_LEN)(bytes)?;
+        let data = data
+            .try_into()
+            .map_err(|_| ParserError::parser_unexpected_buffer_end)?;
         Ok((raw, Self { data }))
     }
 
@@ -93,68 +134,61 @@ impl<'a> SpendingConditionSigner<'a> {
         HashMode::try_from(self.data[0])
     }
 
-    fn to_mainnet_address(
-        &self,
-        mode: HashMode,
-    ) -> Result<arrayvec::ArrayVec<[u8; C32_ENCODED_ADDRS_LENGTH]>, ParserError> {
-        c32::c32_address(mode.to_version_mainnet(), &self.data[1..21])
-    }
-
-    fn to_testnet_address(
-        &self,
-        mode: HashMode,
-    ) -> Result<arrayvec::ArrayVec<[u8; C32_ENCODED_ADDRS_LENGTH]>, ParserError> {
-        c32::c32_address(mode.to_version_testnet(), &self.data[1..21])
+    fn address_version(&self, chain: TransactionVersion) -> Result<u8, ParserError> {
+        let mode = self.hash_mode()?;
+        if chain == TransactionVersion::Testnet {
+            Ok(mode.to_version_testnet())
+        } else {
+            Ok(mode.to_version_mainnet())
+        }
     }
 
     pub fn signer_address(
         &self,
         chain: TransactionVersion,
     ) -> Result<arrayvec::ArrayVec<[u8; C32_ENCODED_ADDRS_LENGTH]>, ParserError> {
-        let mode = self.hash_mode()?;
-        if chain == TransactionVersion::Testnet {
-            self.to_testnet_address(mode)
-        } else {
-            self.to_mainnet_address(mode)
-        }
+        c32::c32_address(self.address_version(chain)?, self.pub_key_hash())
     }
 
+    pub fn c32_signer_address(&self, chain: TransactionVersion) -> Result<C32Address, ParserError> {
+        C32Address::new(self.address_version(chain)?, self.pub_key_hash())
+    }
+
+    // the hash mode is followed by the 20-byte hash, the nonce and the fee
     pub fn pub_key_hash(&self) -> &[u8] {
-        &self.data[1..21]
+        self.data.get(1..21).unwrap_or_default()
     }
 
     pub fn nonce(&self) -> Result<u64, ParserError> {
-        be_u64::<'a, ParserError>(&self.data[21..])
+        be_u64(self.data.get(21..).unwrap_or_default())
             .map(|res| res.1)
             .map_err(|_| ParserError::parser_unexpected_value)
     }
 
     pub fn fee(&self) -> Result<u64, ParserError> {
-        be_u64::<'a, ParserError>(&self.data[29..])
+        be_u64(self.data.get(29..).unwrap_or_default())
             .map(|res| res.1)
             .map_err(|_| ParserError::parser_unexpected_value)
     }
 
+    /// Formats the nonce into out, returning the part written
     #[inline(never)]
-    pub fn nonce_str(&self) -> Result<ArrayVec<[u8; zxformat::MAX_STR_BUFF_LEN]>, ParserError> {
-        let mut output = ArrayVec::from([0u8; zxformat::MAX_STR_BUFF_LEN]);
+    pub fn nonce_str<'b>(&self, out: &'b mut [u8]) -> Result<&'b [u8], ParserError> {
         let nonce = self.nonce()?;
-        let len = zxformat::u64_to_str(&mut output[..zxformat::MAX_STR_BUFF_LEN], nonce)? as usize;
-        unsafe {
-            output.set_len(len);
-        }
-        Ok(output)
+        let len = zxformat::u64_to_str(out, nonce)?;
+        let out: &'b [u8] = out;
+        out.get(..len)
+            .ok_or(ParserError::parser_unexpected_buffer_end)
     }
 
+    /// Formats the fee into out, returning the part written
     #[inline(never)]
-    pub fn fee_str(&self) -> Result<ArrayVec<[u8; zxformat::MAX_STR_BUFF_LEN]>, ParserError> {
-        let mut output = ArrayVec::from([0u8; zxformat::MAX_STR_BUFF_LEN]);
+    pub fn fee_str<'b>(&self, out: &'b mut [u8]) -> Result<&'b [u8], ParserError> {
         let fee = self.fee()?;
-        let len = zxformat::u64_to_str(output.as_mut(), fee)? as usize;
-        unsafe {
-            output.set_len(len);
-        }
-        Ok(output)
+        let len = zxformat::stx_amount_to_str(out, fee)?;
+        let out: &'b [u8] = out;
+        out.get(..len)
+            .ok_or(ParserError::parser_unexpected_buffer_end)
     }
 }
 
@@ -181,10 +215,12 @@ pub enum SpendingConditionSignature<'a> {
 }
 
 impl<'a> SpendingConditionSignature<'a> {
-    fn clear_signature(&mut self) {
+    /// Writes into out the signature as it is cleared for the initial
+    /// sighash, returning the bytes written
+    fn write_cleared(&self, out: &mut [u8]) -> Result<usize, ParserError> {
         match self {
-            Self::Singlesig(ref mut singlesig) => singlesig.clear_signature(),
-            Self::Multisig(ref mut multisig) => multisig.clear_signature(),
+            Self::Singlesig(ref singlesig) => singlesig.write_cleared(out),
+            Self::Multisig(ref multisig) => multisig.write_cleared(out),
         }
     }
 
@@ -209,8 +245,10 @@ impl<'a> SinglesigSpendingCondition<'a> {
     pub fn from_bytes(bytes: &'a [u8]) -> nom::IResult<&[u8], Self, ParserError> {
         // we take 65-byte signature + 1-byte signature public-key encoding type
         let len = SIGNATURE_LEN as usize + 1;
-        let (raw, _) = take(len)(bytes)?;
-        let data = arrayref::array_ref!(bytes, 0, SINGLE_SPENDING_CONDITION_LEN);
+        let (raw, data) = take(len)(bytes)?;
+        let data = data
+            .try_into()
+            .map_err(|_| ParserError::parser_unexpected_buffer_end)?;
         check_canary!();
         Ok((raw, Self(data)))
     }
@@ -227,15 +265,21 @@ impl<'a> SinglesigSpendingCondition<'a> {
         }
     }
 
-    fn clear_signature(&mut self) {
-        let ptr = self.0.as_ptr();
-        unsafe {
-            let ptr = ptr as *mut u8;
-            // Set the signature encoding type to Compressed
-            ptr.write_bytes(TransactionPublicKeyEncoding::Compressed as u8, 1);
-            // zeroize the signature
-            ptr.write_bytes(0, SIGNATURE_LEN);
-        }
+    /// The 65-byte recoverable signature, after the key encoding
+    pub fn signature(&self) -> &'a [u8] {
+        self.0.get(1..).unwrap_or_default()
+    }
+
+    fn write_cleared(&self, out: &mut [u8]) -> Result<usize, ParserError> {
+        let (encoding, signature) = out
+            .get_mut(..SINGLE_SPENDING_CONDITION_LEN)
+            .and_then(|out| out.split_first_mut())
+            .ok_or(ParserError::parser_no_data)?;
+        // Set the signature encoding type to Compressed
+        *encoding = TransactionPublicKeyEncoding::Compressed as u8;
+        // zeroize the signature
+        signature.iter_mut().for_each(|v| *v = 0);
+        Ok(SINGLE_SPENDING_CONDITION_LEN)
     }
 }
 
@@ -266,39 +310,186 @@ impl<'a> MultisigSpendingCondition<'a> {
     }
 
     pub fn required_signatures(&self) -> Result<u16, ParserError> {
-        if self.0.len() < 2 {
-            return Err(ParserError::parser_unexpected_buffer_end);
-        }
-        let idx = self.0.len() - 2;
-        be_u16::<'a, ParserError>((self.0[idx..]).as_ref())
+        let count = self
+            .0
+            .len()
+            .checked_sub(2)
+            .and_then(|idx| self.0.get(idx..))
+            .ok_or(ParserError::parser_unexpected_buffer_end)?;
+        be_u16(count)
             .map(|num| num.1)
             .map_err(|_| ParserError::parser_unexpected_value)
     }
 
     pub fn num_fields(&self) -> Result<u32, ParserError> {
-        be_u32::<'a, ParserError>((self.0[..4]).as_ref())
+        be_u32(self.0)
             .map(|num| num.1)
             .map_err(|_| ParserError::parser_unexpected_value)
     }
 
-    fn clear_signature(&mut self) {
-        let ptr = self.0.as_ptr();
-        // clear all the multisig data except for the last 2-bytes
-        // which are the signature count
-        let len = self.0.len() - 2;
-        unsafe {
-            let ptr = ptr as *mut u8;
-            // zeroize the auth fields
-            ptr.write_bytes(0, len);
+    /// Returns the number of auth fields that are signatures,
+    /// the rest of them are public keys of signers that have not signed yet
+    pub fn num_signatures(&self) -> Result<u32, ParserError> {
+        let num_fields = self.num_fields()?;
+        let mut offset = 4usize;
+        let mut signatures = 0;
+        for _ in 0..num_fields {
+            match self.0.get(offset) {
+                Some(0x00) | Some(0x01) => offset += 33 + 1,
+                Some(0x02) | Some(0x03) => {
+                    offset += 65 + 1;
+                    signatures += 1;
+                }
+                _ => return Err(ParserError::parser_unexpected_value),
+            }
+        }
+        Ok(signatures)
+    }
+
+    /// The auth fields in order, as their id along with
+    /// the public key or signature they hold
+    pub fn auth_fields(&self) -> impl Iterator<Item = (u8, &'a [u8])> {
+        let num_fields = self.num_fields().unwrap_or(0);
+        let mut fields = self.0.get(4..).unwrap_or_default();
+        (0..num_fields).map_while(move |_| {
+            let (id, rem) = fields.split_first()?;
+            let len = match id {
+                0x00 | 0x01 => 33,
+                _ => 65,
+            };
+            let field = rem.get(..len)?;
+            fields = rem.get(len..)?;
+            Some((*id, field))
+        })
+    }
+
+    /// Whether a public key is in more than one auth field, its holder then
+    /// counts as several of the required signers. The keys of who already
+    /// signed are only in their signatures and are not compared
+    pub fn has_duplicate_keys(&self) -> bool {
+        let keys = || {
+            self.auth_fields()
+                .filter(|(id, _)| {
+                    *id == TransactionAuthFieldID::PublicKeyCompressed as u8
+                        || *id == TransactionAuthFieldID::PublicKeyUncompressed as u8
+                })
+                .map(|(_, key)| key)
+        };
+        keys()
+            .enumerate()
+            .any(|(at, key)| keys().skip(at + 1).any(|other| other == key))
+    }
+
+    /// Whether pubkey is in an auth field, as the key of a signer that
+    /// did not sign
+    pub fn lists_key(&self, pubkey: &[u8]) -> bool {
+        self.auth_fields().any(|(id, key)| {
+            (id == TransactionAuthFieldID::PublicKeyCompressed as u8
+                || id == TransactionAuthFieldID::PublicKeyUncompressed as u8)
+                && key == pubkey
+        })
+    }
+
+    /// Checks the auth fields of a transaction signed mid-round, as stacks.js
+    /// serializes them: one field per previous key in order, a signature or
+    /// the key of who did not sign, with fewer signatures than required so
+    /// there is room for one more. Keys and signatures can come in any
+    /// order, stacks-core only chains the sighash through the signatures.
+    /// Segwit accounts only take compressed keys
+    pub fn check_partially_signed(&self, hash_mode: HashMode) -> Result<(), ParserError> {
+        let signatures = self.num_signatures()?;
+        let required = u32::from(self.required_signatures()?);
+        if signatures > required {
+            return Err(ParserError::parser_too_many_signatures);
+        }
+        if signatures == required {
+            return Err(ParserError::parser_unexpected_number_items);
+        }
+
+        let num_fields = self.num_fields()?;
+        let mut fields = 0;
+        for (id, _) in self.auth_fields() {
+            let uncompressed = id == TransactionAuthFieldID::PublicKeyUncompressed as u8
+                || id == TransactionAuthFieldID::SignatureUncompressed as u8;
+            if uncompressed && hash_mode.is_segwit() {
+                return Err(ParserError::parser_invalid_pubkey_encoding);
+            }
+            fields += 1;
+        }
+        if fields != num_fields {
+            return Err(ParserError::parser_unexpected_number_items);
+        }
+        Ok(())
+    }
+
+    /// The index of the auth field a signature by pubkey goes in: the field
+    /// holding the key, or a new one after the last field when no key is
+    /// listed yet, as signers append their fields in turn. The key can be
+    /// listed after keys of who did not sign, in any hash mode
+    pub fn auth_field_index(&self, pubkey: &[u8]) -> Result<u32, ParserError> {
+        let mut keys = (0u32..).zip(self.auth_fields()).filter(|(_, (id, _))| {
+            *id == TransactionAuthFieldID::PublicKeyCompressed as u8
+                || *id == TransactionAuthFieldID::PublicKeyUncompressed as u8
+        });
+        let first_key = match keys.next() {
+            Some(first) => first,
+            None => return self.num_fields(),
+        };
+        core::iter::once(first_key)
+            .chain(keys)
+            .find(|(_, (_, key))| *key == pubkey)
+            .map(|(idx, _)| idx)
+            .ok_or(ParserError::parser_signer_mismatch)
+    }
+
+    /// Chains presig, the pre_sig_hash of the first signer, through every
+    /// signature in the auth fields the way stacks-core verifies them, so it
+    /// ends as the pre_sig_hash of the next signer. Keys of who did not sign
+    /// leave it as it is. Returns the last signature along with its
+    /// post_sig_hash, if any
+    pub fn chain_presig_hash<H: Sha512_256>(
+        &self,
+        presig: &mut [u8; SHA512_256_LEN],
+        auth_data: &[u8],
+    ) -> Result<Option<[u8; SIGNED_FIELD_LEN]>, ParserError> {
+        let mut last = None;
+        for (id, field) in self.auth_fields() {
+            let encoding = match id {
+                x if x == TransactionAuthFieldID::SignatureCompressed as u8 => {
+                    TransactionPublicKeyEncoding::Compressed
+                }
+                x if x == TransactionAuthFieldID::SignatureUncompressed as u8 => {
+                    TransactionPublicKeyEncoding::Uncompressed
+                }
+                _ => continue,
+            };
+
+            let mut signed = [0u8; SIGNED_FIELD_LEN];
+            let (post_sig, rest) = signed.split_at_mut(SHA512_256_LEN);
+            let post_sig: &mut [u8; SHA512_256_LEN] = post_sig
+                .try_into()
+                .map_err(|_| ParserError::parser_unexpected_buffer_end)?;
+            post_sig_hash::<H>(presig, encoding as u8, field, post_sig);
+            next_presig_hash::<H>(post_sig, auth_data, presig);
+
+            rest.iter_mut()
+                .zip(core::iter::once(encoding as u8).chain(field.iter().copied()))
+                .for_each(|(dst, src)| *dst = src);
+            last = Some(signed);
         }
+        Ok(last)
     }
 
-    // If it is a multisig sponsor
-    // then clear it as a singlesig spending condition
-    fn clear_as_singlesig(&mut self) {
-        // TODO: check if it involves shrinking
-        // the general transaction buffer
-        todo!();
+    fn write_cleared(&self, out: &mut [u8]) -> Result<usize, ParserError> {
+        let count = self.required_signatures()?.to_be_bytes();
+        // an empty vector of auth fields followed by the signatures count
+        let cleared = [0u8; 4].iter().chain(count.iter());
+        out.get_mut(..CLEARED_MULTISIG_LEN)
+            .ok_or(ParserError::parser_no_data)?
+            .iter_mut()
+            .zip(cleared)
+            .for_each(|(out, byte)| *out = *byte);
+        Ok(CLEARED_MULTISIG_LEN)
     }
 }
 
@@ -307,18 +498,15 @@ impl<'a> TransactionSpendingCondition<'a> {
     pub fn from_bytes(bytes: &'a [u8]) -> nom::IResult<&[u8], Self, ParserError> {
         let (raw, signer) = SpendingConditionSigner::from_bytes(bytes)?;
         let hash_mode = signer.hash_mode()?;
-        let (leftover, signature) = match hash_mode {
-            HashMode::P2PKH | HashMode::P2WPKH => {
-                let (raw, sig) = SinglesigSpendingCondition::from_bytes(raw)?;
-                if !sig.key_encoding()?.is_valid_hash_mode(hash_mode) {
-                    return Err(nom::Err::Error(ParserError::parser_invalid_pubkey_encoding));
-                }
-                (raw, SpendingConditionSignature::Singlesig(sig))
-            }
-            HashMode::P2WSH | HashMode::P2SH => {
-                let sig = MultisigSpendingCondition::from_bytes(raw)?;
-                (sig.0, SpendingConditionSignature::Multisig(sig.1))
+        let (leftover, signature) = if hash_mode.is_multisig() {
+            let sig = MultisigSpendingCondition::from_bytes(raw)?;
+            (sig.0, SpendingConditionSignature::Multisig(sig.1))
+        } else {
+            let (raw, sig) = SinglesigSpendingCondition::from_bytes(raw)?;
+            if !sig.key_encoding()?.is_valid_hash_mode(hash_mode) {
+                return Err(nom::Err::Error(ParserError::parser_invalid_pubkey_encoding));
             }
+            (raw, SpendingConditionSignature::Singlesig(sig))
         };
         Ok((leftover, Self { signer, signature }))
     }
@@ -331,18 +519,27 @@ impl<'a> TransactionSpendingCondition<'a> {
         self.signer.signer_address(chain)
     }
 
+    /// The signer address, encoded as it is paged out
+    pub fn c32_signer_address(&self, chain: TransactionVersion) -> Result<C32Address, ParserError> {
+        self.signer.c32_signer_address(chain)
+    }
+
     pub fn signer_pub_key_hash(&self) -> &[u8] {
         self.signer.pub_key_hash()
     }
 
     #[inline(never)]
-    pub fn nonce_str(&self) -> Result<ArrayVec<[u8; zxformat::MAX_STR_BUFF_LEN]>, ParserError> {
-        self.signer.nonce_str()
+    pub fn nonce_str<'b>(&self, out: &'b mut [u8]) -> Result<&'b [u8], ParserError> {
+        self.signer.nonce_str(out)
     }
 
     #[inline(never)]
-    pub fn fee_str(&self) -> Result<ArrayVec<[u8; zxformat::MAX_STR_BUFF_LEN]>, ParserError> {
-        self.signer.fee_str()
+    pub fn fee_str<'b>(&self, out: &'b mut [u8]) -> Result<&'b [u8], ParserError> {
+        self.signer.fee_str(out)
+    }
+
+    pub fn signature(&self) -> &SpendingConditionSignature<'a> {
+        &self.signature
     }
 
     pub fn nonce(&self) -> u64 {
@@ -361,6 +558,17 @@ impl<'a> TransactionSpendingCondition<'a> {
         matches!(self.signature, SpendingConditionSignature::Multisig(..))
     }
 
+    pub fn hash_mode(&self) -> Result<HashMode, ParserError> {
+        self.signer.hash_mode()
+    }
+
+    pub fn multisig(&self) -> Option<&MultisigSpendingCondition<'a>> {
+        match self.signature {
+            SpendingConditionSignature::Multisig(ref sig) => Some(sig),
+            _ => None,
+        }
+    }
+
     pub fn num_auth_fields(&self) -> Option<u32> {
         match self.signature {
             SpendingConditionSignature::Multisig(ref sig) => sig.num_fields().ok(),
@@ -375,32 +583,28 @@ impl<'a> TransactionSpendingCondition<'a> {
         }
     }
 
-    pub fn init_sighash(&self, buf: &mut [u8]) -> Result<usize, ParserError> {
-        let buf_len = buf.len();
-
-        if self.is_singlesig() && buf_len >= STANDARD_SINGLESIG_AUTH_LEN {
-            // fills:
-            // 16-byte origins fee and nonce
-            // 66-byte origins signature and key encoding
-            buf.iter_mut()
-                .take(STANDARD_SINGLESIG_AUTH_LEN)
-                .for_each(|v| *v = 0);
-
-            return Ok(STANDARD_SINGLESIG_AUTH_LEN);
-        } else if self.is_multisig() && buf_len >= STANDARD_MULTISIG_AUTH_LEN {
-            // fills with zeroes
-            // 16-byte fee and nonce
-            // 4-byte num auth fields
-            buf.iter_mut().take(20).for_each(|v| *v = 0);
-
-            // append the signatures count at the end 2-bytes
-            let count = self
-                .required_signatures()
-                .ok_or(ParserError::parser_no_data)?;
-            buf[20..STANDARD_MULTISIG_AUTH_LEN].copy_from_slice(&count.to_be_bytes());
-            return Ok(STANDARD_MULTISIG_AUTH_LEN);
+    pub fn num_signatures(&self) -> Option<u32> {
+        match self.signature {
+            SpendingConditionSignature::Multisig(ref sig) => sig.num_signatures().ok(),
+            _ => None,
         }
-        Err(ParserError::parser_no_data)
+    }
+
+    /// Writes into buf the spending condition, after its signer, as it is
+    /// hashed for the initial sighash: the fee, nonce and signatures cleared.
+    /// The transaction bytes are never written to, buf is owned by the caller
+    /// that computes the sighash.
+    pub fn init_sighash(&self, buf: &mut [u8]) -> Result<usize, ParserError> {
+        buf.get_mut(..FEE_NONCE_LEN)
+            .ok_or(ParserError::parser_no_data)?
+            .iter_mut()
+            .for_each(|v| *v = 0);
+
+        let signature = buf
+            .get_mut(FEE_NONCE_LEN..)
+            .ok_or(ParserError::parser_no_data)?;
+        let len = self.signature.write_cleared(signature)?;
+        Ok(FEE_NONCE_LEN + len)
     }
 }
 
@@ -1337,10 +1541,20 @@ mod test {
 
         assert_eq!(2, decoded.required_signatures().unwrap());
         assert_eq!(3, decoded.num_auth_fields().unwrap());
+        assert_eq!(2, decoded.num_signatures().unwrap());
 
         assert_eq!(123, decoded.nonce());
         assert_eq!(456, decoded.fee());
 
+        // both signatures are there, nothing is left to sign
+        assert_eq!(
+            decoded
+                .multisig()
+                .unwrap()
+                .check_partially_signed(HashMode::P2SH),
+            Err(ParserError::parser_unexpected_number_items)
+        );
+
         let spending_condition_p2sh_compressed_bytes = vec![
             // hash mode
             HashMode::P2SH as u8,
@@ -1571,6 +1785,7 @@ mod test {
         .unwrap();
         assert_eq!(2, decoded.required_signatures().unwrap());
         assert_eq!(3, decoded.num_auth_fields().unwrap());
+        assert_eq!(2, decoded.num_signatures().unwrap());
 
         assert_eq!(456, decoded.nonce());
         assert_eq!(567, decoded.fee());
@@ -1807,8 +2022,86 @@ mod test {
                 .unwrap();
         assert_eq!(2, decoded.required_signatures().unwrap());
         assert_eq!(3, decoded.num_auth_fields().unwrap());
+        assert_eq!(2, decoded.num_signatures().unwrap());
 
         assert_eq!(456, decoded.nonce());
         assert_eq!(567, decoded.fee());
     }
+
+    // a 2-of-3 multisig condition with the auth fields given, each either
+    // a compressed key or a signature
+    fn multisig(hash_mode: HashMode, fields: &[Result<[u8; 33], [u8; 65]>]) -> Vec<u8> {
+        let mut bytes = vec![hash_mode as u8];
+        bytes.extend_from_slice(&[0x11; 20]);
+        bytes.extend_from_slice(&[0; 16]);
+        bytes.extend_from_slice(&(fields.len() as u32).to_be_bytes());
+        for field in fields {
+            match field {
+                Ok(key) => {
+                    bytes.push(TransactionAuthFieldID::PublicKeyCompressed as u8);
+                    bytes.extend_from_slice(key);
+                }
+                Err(signature) => {
+                    bytes.push(TransactionAuthFieldID::SignatureCompressed as u8);
+                    bytes.extend_from_slice(signature);
+                }
+            }
+        }
+        bytes.extend_from_slice(&2u16.to_be_bytes());
+        bytes
+    }
+
+    #[test]
+    fn test_auth_field_index() {
+        let (key_a, key_b) = ([0x02; 33], [0x03; 33]);
+        let signed = Err([0xfe; 65]);
+
+        let index = |mode: HashMode, fields: &[Result<[u8; 33], [u8; 65]>], key: &[u8]| {
+            let bytes = multisig(mode, fields);
+            let (_, decoded) = TransactionSpendingCondition::from_bytes(&bytes).unwrap();
+            decoded.multisig().unwrap().auth_field_index(key)
+        };
+
+        // the field of the key is replaced, wherever it is
+        let fields = [signed, Ok(key_a), Ok(key_b)];
+        assert_eq!(index(HashMode::P2SHNonSequential, &fields, &key_b), Ok(2));
+        assert_eq!(index(HashMode::P2WSHNonSequential, &fields, &key_a), Ok(1));
+        assert_eq!(index(HashMode::P2SH, &fields, &key_a), Ok(1));
+
+        // in order too, after a key of who did not sign
+        assert_eq!(index(HashMode::P2SH, &fields, &key_b), Ok(2));
+        assert_eq!(index(HashMode::P2WSH, &fields, &key_b), Ok(2));
+
+        // a key that is not listed, or no key listed yet
+        assert_eq!(
+            index(HashMode::P2SHNonSequential, &fields, &[0x02; 32]),
+            Err(ParserError::parser_signer_mismatch)
+        );
+        assert_eq!(index(HashMode::P2SH, &[signed], &key_a), Ok(1));
+        assert_eq!(index(HashMode::P2SHNonSequential, &[], &key_a), Ok(0));
+    }
+
+    #[test]
+    fn test_signature_auth_field() {
+        let mut rsv = [0u8; SIGNATURE_LEN];
+        rsv[..32].copy_from_slice(&[0xaa; 32]);
+        rsv[32..64].copy_from_slice(&[0xbb; 32]);
+        rsv[64] = 1;
+
+        let mut field = [0u8; SIGNATURE_AUTH_FIELD_LEN];
+        signature_auth_field(&rsv, &mut field);
+        assert_eq!(field[0], TransactionAuthFieldID::SignatureCompressed as u8);
+        assert_eq!(field[1], 1);
+        assert_eq!(&field[2..34], &[0xaa; 32]);
+        assert_eq!(&field[34..], &[0xbb; 32]);
+
+        // the field reads back as the signature of a multisig condition
+        let mut bytes = multisig(HashMode::P2SHNonSequential, &[]);
+        bytes.truncate(bytes.len() - 6);
+        bytes.extend_from_slice(&1u32.to_be_bytes());
+        bytes.extend_from_slice(&field);
+        bytes.extend_from_slice(&2u16.to_be_bytes());
+        let (_, decoded) = TransactionSpendingCondition::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.num_signatures(), Some(1));
+    }
 }
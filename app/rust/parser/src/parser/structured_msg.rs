@@ -6,10 +6,14 @@ use crate::{
 };
 use core::fmt::Write;
 
-use nom::bytes::complete::tag;
+use nom::error::ErrorKind;
 
 use super::{error::ParserError, Tuple, Value, ValueId, MAX_DEPTH};
-use crate::bolos::{sha256, SHA256_LEN};
+use crate::bolos::SHA256_LEN;
+use crate::hash::{DefaultSha256, Sha256};
+use crate::lang::LABELS;
+use crate::zeroize::Zeroizing;
+use core::convert::TryInto;
 use hex::encode_to_slice;
 
 #[repr(C)]
@@ -31,7 +35,7 @@ impl<'a> Domain<'a> {
 
         let mut items = 0;
         for (key, value) in tuple.iter() {
-            let value_id = value.value_id();
+            let value_id = value.value_id()?;
             match (key.name(), value_id) {
                 (b"name", ValueId::StringAscii) => {}
                 (b"version", ValueId::StringAscii) => {}
@@ -41,16 +45,17 @@ impl<'a> Domain<'a> {
             items += 1;
         }
 
-        if items != tuple.num_elements() || items != Self::LEN {
+        if items != tuple.num_elements()? || items != Self::LEN {
             return Err(ParserError::parser_invalid_structured_msg.into());
         }
 
         Ok((rem, Self(value)))
     }
 
-    fn tuple(&'a self) -> Tuple<'a> {
-        // wont panic as this was checked by the parser
-        self.0.tuple().unwrap()
+    fn tuple(&'a self) -> Result<Tuple<'a>, ParserError> {
+        self.0
+            .tuple()
+            .ok_or(ParserError::parser_invalid_structured_msg)
     }
 
     // This returns the domain bytes
@@ -60,7 +65,7 @@ impl<'a> Domain<'a> {
     }
 
     pub fn num_items(&self) -> usize {
-        3
+        Self::LEN
     }
 
     pub fn get_item(
@@ -74,23 +79,24 @@ impl<'a> Domain<'a> {
 
         let mut buff = [0; 39];
 
-        if let Some((key, value)) = self.tuple().iter().nth(display_idx as usize) {
+        if let Some((key, value)) = self.tuple()?.iter().nth(display_idx as usize) {
             let name = key.name();
             let m = out_key
                 .get_mut(0..name.len())
                 .ok_or(ParserError::parser_unexpected_buffer_end)?;
             m.copy_from_slice(name);
 
-            let id = value.value_id();
-
-            if id == ValueId::UInt {
-                // wont panic as this was checked by the parser
-                let chain_id = value.uint().unwrap();
+            if value.value_id()? == ValueId::UInt {
+                let chain_id = value
+                    .uint()
+                    .ok_or(ParserError::parser_invalid_structured_msg)?;
                 let num = chain_id.numtoa_str(10, &mut buff).as_bytes();
 
                 pageString(out_value, num, page_idx)
             } else {
-                let string = value.string_ascii().unwrap();
+                let string = value
+                    .string_ascii()
+                    .ok_or(ParserError::parser_invalid_structured_msg)?;
 
                 pageString(out_value, string.content(), page_idx)
             }
@@ -151,7 +157,11 @@ impl<'a> StructuredMsg<'a> {
 
     fn parse_prefix(data: &'a [u8]) -> Result<&'a [u8], nom::Err<ParserError>> {
         let prefix = Self::prefix();
-        tag(prefix)(data).map(|(rem, _)| rem)
+        // not strip_prefix, its indexing is left out of line in some builds
+        data.get(..prefix.len())
+            .filter(|head| *head == &prefix[..])
+            .and_then(|_| data.get(prefix.len()..))
+            .ok_or_else(|| ParserError::from(ErrorKind::Tag).into())
     }
 
     // Checks if the input data comes with a prefix as specified by SIP018
@@ -167,57 +177,65 @@ impl<'a> StructuredMsg<'a> {
         Ok(())
     }
 
-    // returns the message content
-    fn msg(&self) -> &[u8] {
-        // skip header and domain
-        let data = &self.0[Self::PREFIX_LEN..];
-        // read out the domain, wont panic as data was previously parsed
-        Domain::from_bytes(data).map(|(msg, _)| msg).unwrap()
+    // returns the domain and the message content that follows it
+    fn domain_and_msg(&self) -> Result<(Domain<'a>, &'a [u8]), ParserError> {
+        // skip header
+        let data = Self::parse_prefix(self.0)?;
+        let (msg, domain) = Domain::from_bytes(data)?;
+        Ok((domain, msg))
+    }
+
+    /// Length of the prefix and the domain before the message, the part
+    /// shared by messages signed under the same domain
+    pub fn header_len(&self) -> Result<usize, ParserError> {
+        let (_, msg) = self.domain_and_msg()?;
+        self.0
+            .len()
+            .checked_sub(msg.len())
+            .ok_or(ParserError::parser_unexpected_error)
     }
 
-    fn domain(&self) -> Domain<'a> {
-        // skip header and domain
-        let data = &self.0[Self::PREFIX_LEN..];
-        // read out the domain, wont panic as data was previously parsed
-        Domain::from_bytes(data).map(|(_, d)| d).unwrap()
+    fn domain(&self) -> Result<Domain<'a>, ParserError> {
+        self.domain_and_msg().map(|(domain, _)| domain)
     }
 
     #[inline(never)]
     pub fn get_hash(&self, out: &mut [u8]) -> Result<(), ParserError> {
-        if out.len() < SHA256_LEN {
-            return Err(ParserError::parser_unexpected_buffer_end);
-        }
-        // get prefix
-        let prefix = Self::prefix();
-        let prefix_len = prefix.len();
-        // construct buffer to hold prefix, domain_hash and msg_hash
-        let mut to_hash = [0; StructuredMsg::PREFIX_LEN + SHA256_LEN * 2];
-        // copy prefix
-        to_hash[..prefix_len].copy_from_slice(&prefix[..]);
+        let out = out
+            .get_mut(..SHA256_LEN)
+            .and_then(|out| out.try_into().ok())
+            .ok_or(ParserError::parser_unexpected_buffer_end)?;
+        self.hash_with::<DefaultSha256>(out)
+    }
+
+    // sha256(prefix || sha256(domain) || sha256(message))
+    fn hash_with<H: Sha256>(&self, out: &mut [u8; SHA256_LEN]) -> Result<(), ParserError> {
+        let (domain, msg) = self.domain_and_msg()?;
 
         // 1. get domain hash
-        // wont panic as out has the right len
-        let domain = self.domain();
-        sha256(
-            domain.bytes(),
-            &mut to_hash[prefix_len..prefix_len + SHA256_LEN],
-        )
-        .unwrap();
-        // 1. get msg hash
-        // wont panic as out has the right len
-        let len = to_hash.len();
-        sha256(self.msg(), &mut to_hash[len - SHA256_LEN..]).unwrap();
+        let mut domain_hash = Zeroizing::new([0; SHA256_LEN]);
+        H::digest(domain.bytes(), &mut domain_hash);
+        // 2. get msg hash
+        let mut msg_hash = Zeroizing::new([0; SHA256_LEN]);
+        H::digest(msg, &mut msg_hash);
 
-        // compute msg hash
-        // wont panic as out is SHA256_LEN bytes len
-        sha256(&to_hash[..], &mut out[0..SHA256_LEN]).unwrap();
+        // construct buffer to hold prefix, domain_hash and msg_hash
+        let mut to_hash = Zeroizing::new([0; StructuredMsg::PREFIX_LEN + SHA256_LEN * 2]);
+        let prefix = Self::prefix();
+        let parts = prefix.iter().chain(domain_hash.iter()).chain(msg_hash.iter());
+        to_hash
+            .iter_mut()
+            .zip(parts)
+            .for_each(|(out, byte)| *out = *byte);
 
+        // compute msg hash
+        H::digest(&to_hash[..], out);
         Ok(())
     }
 
     pub fn num_items(&self) -> u8 {
         // domain name, version, chain_id and msg_hash
-        self.domain().num_items() as u8 + 1
+        Domain::LEN as u8 + 1
     }
 
     pub fn get_item(
@@ -229,19 +247,18 @@ impl<'a> StructuredMsg<'a> {
     ) -> Result<u8, ParserError> {
         match display_idx {
             0..=2 => {
-                let mut domain = self.domain();
+                let mut domain = self.domain()?;
                 domain.get_item(display_idx, out_key, out_value, page_idx)
             }
             3 => {
                 let mut writer_key = Writer::new(out_key);
                 writer_key
-                    .write_str("Message Hash")
+                    .write_str(LABELS.message_hash)
                     .map_err(|_| ParserError::parser_unexpected_buffer_end)?;
 
                 // 1. get prefix hash
                 let mut hash = [0; SHA256_LEN];
-                // wont panic as we are passing an output buffer longer enough
-                self.get_hash(&mut hash).unwrap();
+                self.hash_with::<DefaultSha256>(&mut hash)?;
 
                 // buffer to store the hex hash
                 let mut hex = [0; SHA256_LEN * 2];
@@ -286,6 +303,15 @@ mod test {
         msg.read(&bytes).unwrap();
     }
 
+    #[test]
+    fn header_len_covers_prefix_and_domain() {
+        let header = "5349503031380c0000000308636861696e2d69640100000000000000000000000000025903046e616d650d00000006537461636b730776657273696f6e0d00000005312e302e30";
+        let mut bytes = hex::decode(header).unwrap();
+        bytes.extend_from_slice(&hex::decode("0d0000000568656c6c6f").unwrap());
+        let msg = StructuredMsg::from_bytes(&bytes).unwrap();
+        assert_eq!(msg.header_len().unwrap(), header.len() / 2);
+    }
+
     #[test]
     fn parse_complex_structured_msg() {
         let input = "5349503031380c0000000308636861696e2d69640100000000000000000000000000025983046e616d650d00000006537461636b730776657273696f6e0d00000005312e302e300c00000008016100ffffffffffffffffffffffffffffffff01620200000008616263646566676808636861696e2d69640100000000000000000000000000000001016d0b0000000400ffffffffffffffffffffffffffffffff00ffffffffffffffffffffffffffffffff00ffffffffffffffffffffffffffffffff00ffffffffffffffffffffffffffffffff046e616d650c0000000308636861696e2d69640100000000000000000000000000025983046e616d650d00000006537461636b730776657273696f6e0d00000005312e302e30057475706c650c0000000308636861696e2d69640100000000000000000000000000025983046e616d650c0000000308636861696e2d69640100000000000000000000000000025983046e616d650d00000006537461636b730776657273696f6e0d00000005312e302e300776657273696f6e0d00000005312e302e30067475706c65320c0000000308636861696e2d69640100000000000000000000000000025983046e616d650d00000006537461636b730776657273696f6e0d00000005312e302e300776657273696f6e0b000000020c0000000308636861696e2d69640100000000000000000000000000025983046e616d650c0000000308636861696e2d69640100000000000000000000000000025983046e616d650d00000006537461636b730776657273696f6e0d00000005312e302e300776657273696f6e0b000000050c0000000308636861696e2d69640100000000000000000000000000025983046e616d650d00000006537461636b730776657273696f6e0d00000005312e302e300c0000000308636861696e2d69640100000000000000000000000000025983046e616d650d00000006537461636b730776657273696f6e0d00000005312e302e300c0000000308636861696e2d69640100000000000000000000000000025983046e616d650d00000006537461636b730776657273696f6e0d00000005312e302e300c0000000308636861696e2d69640100000000000000000000000000025983046e616d650d00000006537461636b730776657273696f6e0d00000005312e302e300c0000000308636861696e2d69640100000000000000000000000000025983046e616d650d00000006537461636b730776657273696f6e0d00000005312e302e300c0000000308636861696e2d69640100000000000000000000000000025983046e616d650c0000000308636861696e2d69640100000000000000000000000000025983046e616d650d00000006537461636b730776657273696f6e0d00000005312e302e300776657273696f6e0b000000050c0000000308636861696e2d69640100000000000000000000000000025983046e616d650d00000006537461636b730776657273696f6e0d00000005312e302e300c0000000308636861696e2d69640100000000000000000000000000025983046e616d650d00000006537461636b730776657273696f6e0d00000005312e302e300c0000000308636861696e2d69640100000000000000000000000000025983046e616d650d00000006537461636b730776657273696f6e0d00000005312e302e300c0000000308636861696e2d69640100000000000000000000000000025983046e616d650d00000006537461636b730776657273696f6e0d00000005312e302e300c0000000308636861696e2d69640100000000000000000000000000025983046e616d650d00000006537461636b730776657273696f6e0d00000005312e302e30";
@@ -0,0 +1,148 @@
+use super::error::ParserError;
+use super::parser_common::AssetInfo;
+use super::reader::le_u8;
+
+// Tokens a single provisioning describes at most
+pub const TOKEN_INFO_MAX_ENTRIES: u8 = 8;
+
+/// The token is pegged to bitcoin and its amounts are satoshis
+pub const TOKEN_FLAG_BTC_PEGGED: u8 = 0x01;
+
+// Flags the app knows about, entries with any other are refused
+const TOKEN_FLAGS: u8 = TOKEN_FLAG_BTC_PEGGED;
+
+// The decimals of bitcoin, satoshis per BTC
+pub const BTC_DECIMALS: u8 = 8;
+
+// The serialized asset and the flags of a token
+type Entry<'a> = (&'a [u8], u8);
+
+/// Metadata of fungible tokens provisioned by the host, so that amounts of
+/// them read as what they are worth. It is serialized as the number of
+/// tokens and, for each of them, its asset as in a post condition, the
+/// contract principal followed by the length prefixed asset name, and a
+/// byte of flags
+#[derive(Clone, Copy, PartialEq)]
+#[cfg_attr(test, derive(Debug))]
+pub struct TokenInfo<'a>(&'a [u8]);
+
+impl<'a> TokenInfo<'a> {
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, ParserError> {
+        let (mut rem, num_tokens) = le_u8(bytes)?;
+        if num_tokens == 0 || num_tokens > TOKEN_INFO_MAX_ENTRIES {
+            return Err(ParserError::parser_value_out_of_range);
+        }
+        for _ in 0..num_tokens {
+            let (next, (_, flags)) = Self::entry(rem)?;
+            if flags & !TOKEN_FLAGS != 0 {
+                return Err(ParserError::parser_unexpected_value);
+            }
+            rem = next;
+        }
+        if !rem.is_empty() {
+            return Err(ParserError::parser_unexpected_characters);
+        }
+        Ok(Self(bytes))
+    }
+
+    /// The flags of the token whose serialized asset info is asset,
+    /// none for the tokens not described
+    pub fn flags(&self, asset: &[u8]) -> u8 {
+        self.entries()
+            .find(|(entry, _)| *entry == asset)
+            .map_or(0, |(_, flags)| flags)
+    }
+
+    pub fn is_btc_pegged(&self, asset: &[u8]) -> bool {
+        self.flags(asset) & TOKEN_FLAG_BTC_PEGGED != 0
+    }
+
+    // the asset and flags of every token, the bytes were checked when read
+    fn entries(&self) -> impl Iterator<Item = Entry<'a>> {
+        let num_tokens = self.0.first().copied().unwrap_or_default();
+        let mut rem = self.0.get(1..).unwrap_or_default();
+        (0..num_tokens).map_while(move |_| {
+            let (next, entry) = Self::entry(rem).ok()?;
+            rem = next;
+            Some(entry)
+        })
+    }
+
+    fn entry(bytes: &'a [u8]) -> Result<(&'a [u8], Entry<'a>), ParserError> {
+        let (rem, asset) = AssetInfo::read_as_bytes(bytes)?;
+        let (rem, flags) = le_u8(rem)?;
+        Ok((rem, (asset, flags)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::prelude::v1::*;
+
+    // SP3DX3H4FEYZJZ586MFBS25ZW3HZDMEW92260R2PR.Wrapped-Bitcoin::wrapped-bitcoin
+    fn xbtc_asset() -> Vec<u8> {
+        let mut asset = vec![22];
+        asset.extend_from_slice(&hex::decode("dbd1c48f77bf2f9506a3d79117fc1c7eda3b8910").unwrap());
+        asset.push(15);
+        asset.extend_from_slice(b"Wrapped-Bitcoin");
+        asset.push(15);
+        asset.extend_from_slice(b"wrapped-bitcoin");
+        asset
+    }
+
+    #[test]
+    fn test_token_info() {
+        let mut other = xbtc_asset();
+        *other.last_mut().unwrap() = b'x';
+
+        let mut bytes = vec![2];
+        bytes.extend_from_slice(&xbtc_asset());
+        bytes.push(TOKEN_FLAG_BTC_PEGGED);
+        bytes.extend_from_slice(&other);
+        bytes.push(0);
+        let info = TokenInfo::from_bytes(&bytes).unwrap();
+
+        assert!(info.is_btc_pegged(&xbtc_asset()));
+        assert!(!info.is_btc_pegged(&other));
+        assert_eq!(info.flags(&other[..other.len() - 1]), 0);
+    }
+
+    #[test]
+    fn test_token_info_errors() {
+        let mut bytes = vec![1];
+        bytes.extend_from_slice(&xbtc_asset());
+        bytes.push(TOKEN_FLAG_BTC_PEGGED);
+        assert!(TokenInfo::from_bytes(&bytes).is_ok());
+
+        // missing the flags
+        assert!(TokenInfo::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+
+        // trailing bytes
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert_eq!(
+            TokenInfo::from_bytes(&trailing),
+            Err(ParserError::parser_unexpected_characters)
+        );
+
+        // flags the app does not know about
+        let mut unknown = bytes.clone();
+        *unknown.last_mut().unwrap() = 0x02;
+        assert_eq!(
+            TokenInfo::from_bytes(&unknown),
+            Err(ParserError::parser_unexpected_value)
+        );
+
+        // no tokens, or more than can be provisioned
+        assert!(TokenInfo::from_bytes(&[0]).is_err());
+        let mut many = vec![TOKEN_INFO_MAX_ENTRIES + 1];
+        for _ in 0..=TOKEN_INFO_MAX_ENTRIES {
+            many.extend_from_slice(&bytes[1..]);
+        }
+        assert_eq!(
+            TokenInfo::from_bytes(&many),
+            Err(ParserError::parser_value_out_of_range)
+        );
+    }
+}
@@ -0,0 +1,1552 @@
+use core::cell::Cell;
+use core::convert::{TryFrom, TryInto};
+use core::fmt::Write;
+use nom::{branch::permutation, combinator::iterator};
+
+use super::reader::{be_u32, consumed, le_u8, take, with_consumed, Consumed};
+
+use numtoa::NumToA;
+
+use crate::parser::{
+    abi::FunctionAbi,
+    c32::C32Address,
+    code_scan::CodeScan,
+    error::ParserError,
+    limits::{check_num_items, or_limit, MAX_FEE, NUM_SUPPORTED_POST_CONDITIONS},
+    parser_common::{
+        ItemKind, ItemSection, ParseDiagnostic, ParseSection, SignerId, TransactionVersion,
+        C32_ENCODED_ADDRS_LENGTH, DENYLIST_ENTRY_LEN, HASH160_LEN,
+    },
+    post_condition::{PostConditionType, TransactionPostCondition},
+    principal::PrincipalTable,
+    scratch::Scratch,
+    spending_condition::{
+        next_presig_hash, post_sig_hash, SpendingConditionSigner, TransactionSpendingCondition,
+        SIGNED_FIELD_LEN,
+    },
+    token_info::TokenInfo,
+    transaction_auth::{TransactionAuth, INITIAL_SIGHASH_AUTH_LEN},
+    transaction_payload::{TransactionPayload, TransactionPayloadId},
+};
+
+use crate::btc::REWARD_PATH_LEN;
+use crate::hash::{Sha512_256, SHA512_256_LEN};
+use crate::lang::LABELS;
+use crate::path::{is_standard_path, HARDENED};
+use crate::trace::{self, Event};
+use crate::zeroize::Zeroizing;
+use crate::{check_canary, fee_threshold, is_nonce_hidden, is_raw_hex_mode, zxformat};
+
+// In multisig transactions the remainder should contain:
+// 32-byte previous signer post_sig_hash
+// 1-byte pubkey type
+// 65-bytes vrs
+pub(super) const MULTISIG_PREVIOUS_SIGNER_DATA_LEN: usize = 98;
+
+// Nothing may follow the payload but the previous signer data of a multisig
+// origin, any other bytes would be hashed and signed without being shown
+fn check_trailing_bytes(rem: &[u8], multisig: bool) -> Result<(), ParserError> {
+    match rem.len() {
+        0 => Ok(()),
+        MULTISIG_PREVIOUS_SIGNER_DATA_LEN if multisig => Ok(()),
+        _ => Err(ParserError::parser_trailing_bytes),
+    }
+}
+
+// What a signer appends to the hash it signs:
+// 1-byte auth flag
+// 8-byte fee
+// 8-byte nonce
+pub const AUTH_DATA_LEN: usize = 1 + 8 + 8;
+
+// The items shown before the payload ones
+#[derive(Clone, Copy, PartialEq)]
+enum OriginItem {
+    Path,
+    Network,
+    MultisigSigner,
+    Denylisted,
+    RewardOwner,
+    Sponsored,
+    Signer,
+    Signatures,
+    DuplicateKeys,
+    Nonce,
+    Fee,
+}
+
+// Whether the PoX rewards of a stack-stx call go to a key of this device
+#[derive(Clone, Copy, PartialEq)]
+#[cfg_attr(test, derive(Debug))]
+enum RewardOwner {
+    // not a stack-stx call, or not checked yet
+    Unchecked,
+    Device,
+    Unknown,
+}
+
+#[repr(u8)]
+#[derive(Clone, PartialEq, Copy)]
+#[cfg_attr(test, derive(Debug))]
+pub enum TransactionAuthFlags {
+    Standard = 0x04,
+    Sponsored = 0x05,
+}
+
+#[repr(u8)]
+#[derive(Clone, PartialEq, Copy)]
+#[cfg_attr(test, derive(Debug))]
+pub enum TransactionPostConditionMode {
+    Allow = 0x01, // allow any other changes not specified
+    Deny = 0x02,  // deny any other changes not specified
+}
+
+impl TransactionPostConditionMode {
+    #[inline(never)]
+    fn from_u8(v: u8) -> Option<Self> {
+        match v {
+            1 => Some(Self::Allow),
+            2 => Some(Self::Deny),
+            _ => None,
+        }
+    }
+
+    #[inline(never)]
+    fn from_bytes(bytes: &[u8]) -> nom::IResult<&[u8], Self, ParserError> {
+        let mode = le_u8(bytes)?;
+        let tx_mode = Self::from_u8(mode.1).ok_or(ParserError::parser_unexpected_error)?;
+        check_canary!();
+        Ok((mode.0, tx_mode))
+    }
+}
+
+#[repr(u8)]
+#[derive(Clone, PartialEq, Copy)]
+#[cfg_attr(test, derive(Debug))]
+pub enum TransactionAnchorMode {
+    OnChainOnly = 1,  // must be included in a StacksBlock
+    OffChainOnly = 2, // must be included in a StacksMicroBlock
+    Any = 3,          // either
+}
+
+impl TransactionAnchorMode {
+    #[inline(never)]
+    fn from_u8(v: u8) -> Option<Self> {
+        match v {
+            1 => Some(Self::OnChainOnly),
+            2 => Some(Self::OffChainOnly),
+            3 => Some(Self::Any),
+            _ => None,
+        }
+    }
+
+    #[inline(never)]
+    fn from_bytes(bytes: &[u8]) -> nom::IResult<&[u8], Self, ParserError> {
+        let mode = le_u8(bytes)?;
+        let tx_mode = Self::from_u8(mode.1).ok_or(ParserError::parser_unexpected_error)?;
+        check_canary!();
+        Ok((mode.0, tx_mode))
+    }
+}
+
+// Only the raw bytes of the post conditions are kept, along with the
+// offset and first item of each condition found while parsing, so the
+// condition an item belongs to is parsed again without walking the others.
+// Token amounts are shown in base units, the decimals of a token are not
+// known, so a notice telling it comes before the conditions if any has one.
+// Tokens provisioned as pegged to bitcoin are shown in sats and BTC instead
+#[repr(C)]
+#[derive(Clone, PartialEq)]
+#[cfg_attr(test, derive(Debug))]
+pub struct PostConditions<'a> {
+    conditions: &'a [u8],
+    offsets: [u16; NUM_SUPPORTED_POST_CONDITIONS],
+    first_items: [u8; NUM_SUPPORTED_POST_CONDITIONS],
+    num_conditions: u8,
+    num_items: u8,
+    raw_units: bool,
+    // the encoded addresses of the principals shown so far
+    principals: PrincipalTable,
+    token_info: Option<TokenInfo<'a>>,
+}
+
+impl<'a> PostConditions<'a> {
+    #[inline(never)]
+    pub(crate) fn from_bytes(bytes: &'a [u8]) -> nom::IResult<&'a [u8], Self, ParserError> {
+        let (raw, len) = be_u32(bytes)?;
+        if len > NUM_SUPPORTED_POST_CONDITIONS as u32 {
+            return Err(nom::Err::Error(ParserError::parser_limit_exceeded));
+        }
+
+        let mut offsets = [0u16; NUM_SUPPORTED_POST_CONDITIONS];
+        let mut first_items = [0u8; NUM_SUPPORTED_POST_CONDITIONS];
+        let mut num_conditions = 0u8;
+        let mut num_items = 0u8;
+        let mut raw_units = false;
+        let mut offset = 0usize;
+
+        let mut iter = iterator(raw, TransactionPostCondition::read_as_bytes);
+        let slots = offsets.iter_mut().zip(first_items.iter_mut());
+        for (bytes, (condition_offset, first_item)) in (&mut iter).take(len as _).zip(slots) {
+            *condition_offset = offset as u16;
+            *first_item = num_items;
+            offset += bytes.len();
+            num_conditions += 1;
+            raw_units |= bytes.first() == Some(&(PostConditionType::FungibleToken as u8));
+            num_items = num_items
+                .checked_add(TransactionPostCondition::num_items_from_bytes(bytes))
+                .ok_or(ParserError::parser_limit_exceeded)?;
+        }
+        let (rem, _) = iter.finish()?;
+        if num_conditions as u32 != len {
+            return Err(nom::Err::Error(ParserError::parser_post_condition_failed));
+        }
+        num_items
+            .checked_add(raw_units as u8)
+            .ok_or(ParserError::parser_limit_exceeded)?;
+        let conditions = raw
+            .get(..offset)
+            .ok_or(ParserError::parser_unexpected_buffer_end)?;
+        check_canary!();
+        Ok((
+            rem,
+            Self {
+                conditions,
+                offsets,
+                first_items,
+                num_conditions,
+                num_items,
+                raw_units,
+                principals: PrincipalTable::default(),
+                token_info: None,
+            },
+        ))
+    }
+
+    /// Shows the amounts of the tokens info describes after what they are,
+    /// the notice only stays if some other token has an amount
+    pub fn set_token_info(&mut self, info: TokenInfo<'a>) {
+        let raw_units = self.iter().any(|bytes| {
+            TransactionPostCondition::from_bytes(bytes).is_ok_and(|(_, condition)| {
+                condition.is_fungible() && !condition.is_btc_pegged(Some(&info))
+            })
+        });
+        self.token_info = Some(info);
+        self.raw_units = raw_units;
+    }
+
+    /// Returns the raw bytes of the condition at idx
+    pub fn condition(&self, idx: u8) -> Option<&'a [u8]> {
+        if idx >= self.num_conditions {
+            return None;
+        }
+        let start = *self.offsets.get(idx as usize)? as usize;
+        let end = if idx + 1 < self.num_conditions {
+            *self.offsets.get(idx as usize + 1)? as usize
+        } else {
+            self.conditions.len()
+        };
+        self.conditions.get(start..end)
+    }
+
+    /// Returns an iterator over the raw bytes of each post condition
+    pub fn iter(&self) -> impl Iterator<Item = &'a [u8]> + '_ {
+        (0..self.num_conditions).filter_map(move |idx| self.condition(idx))
+    }
+
+    /// Returns the raw bytes of all the post conditions
+    pub fn raw(&self) -> &'a [u8] {
+        self.conditions
+    }
+
+    pub fn num_conditions(&self) -> u8 {
+        self.num_conditions
+    }
+
+    pub fn num_items(&self) -> u8 {
+        // the sum was checked while parsing
+        self.num_items + self.raw_units as u8
+    }
+
+    // Finds the condition the item at idx belongs to. Returns the condition
+    // along with the index of the item within it
+    fn condition_at(&self, idx: u8) -> Result<(TransactionPostCondition<'a>, u8), ParserError> {
+        let idx = idx
+            .checked_sub(self.raw_units as u8)
+            .ok_or(ParserError::parser_display_idx_out_of_range)?;
+        if idx >= self.num_items {
+            return Err(ParserError::parser_display_idx_out_of_range);
+        }
+
+        let (condition_idx, first_item) = self
+            .first_items
+            .get(..self.num_conditions as usize)
+            .ok_or(ParserError::parser_post_condition_failed)?
+            .iter()
+            .enumerate()
+            .rfind(|(_, first)| **first <= idx)
+            .ok_or(ParserError::parser_display_idx_out_of_range)?;
+
+        // only the condition being displayed is parsed
+        let bytes = self
+            .condition(condition_idx as u8)
+            .ok_or(ParserError::parser_post_condition_failed)?;
+        let (_, condition) = TransactionPostCondition::from_bytes(bytes)
+            .map_err(|_| ParserError::parser_post_condition_failed)?;
+        Ok((condition, idx - first_item))
+    }
+
+    /// Gets the item at idx, where idx is relative
+    /// to the post conditions items
+    #[inline(never)]
+    pub fn get_items(
+        &self,
+        idx: u8,
+        out_key: &mut [u8],
+        out_value: &mut [u8],
+        page_idx: u8,
+    ) -> Result<u8, ParserError> {
+        if self.raw_units && idx == 0 {
+            let mut writer_key = zxformat::Writer::new(out_key);
+            writer_key
+                .write_str(LABELS.token_amounts)
+                .map_err(|_| ParserError::parser_unexpected_buffer_end)?;
+            return zxformat::pageString(out_value, LABELS.raw_units_notice.as_bytes(), page_idx);
+        }
+        let (condition, item_idx) = self.condition_at(idx)?;
+        condition.get_items(
+            &self.principals,
+            self.token_info.as_ref(),
+            item_idx,
+            out_key,
+            out_value,
+            page_idx,
+        )
+    }
+
+    /// Returns the kind of the item at idx, where idx is relative
+    /// to the post conditions items
+    pub fn item_kind(&self, idx: u8) -> Result<ItemKind, ParserError> {
+        if self.raw_units && idx == 0 {
+            return Ok(ItemKind::Text);
+        }
+        self.condition_at(idx)
+            .map(|(condition, item_idx)| condition.item_kind(item_idx))
+    }
+}
+
+pub type TxTuple<'a> = (
+    TransactionVersion, // version number
+    u32,                // chainId
+    Consumed<'a, TransactionAuth<'a>>,
+    &'a [u8],
+    PostConditions<'a>,
+    Consumed<'a, TransactionPayload<'a>>,
+);
+
+impl<'a> TryFrom<(&'a [u8], TxTuple<'a>)> for Transaction<'a> {
+    type Error = ParserError;
+
+    fn try_from(raw: (&'a [u8], TxTuple<'a>)) -> Result<Self, Self::Error> {
+        let transaction_modes = (raw.1)
+            .3
+            .get(..2)
+            .and_then(|modes| modes.try_into().ok())
+            .ok_or(ParserError::parser_unexpected_buffer_end)?;
+        let (raw_auth, transaction_auth) = (raw.1).2;
+        let (raw_payload, payload) = (raw.1).5;
+        Ok(Self {
+            version: (raw.1).0,
+            chain_id: (raw.1).1,
+            transaction_auth,
+            transaction_modes,
+            post_conditions: (raw.1).4,
+            payload,
+            // At this point the signer is unknown
+            signer: SignerId::Invalid,
+            nonstandard_path: false,
+            network_mismatch: false,
+            unknown_multisig_signer: false,
+            denylisted: false,
+            reward_owner: RewardOwner::Unchecked,
+            device_sponsor: false,
+            signer_address: Cell::new(None),
+            raw_auth,
+            raw_payload,
+            remainder: raw.0,
+        })
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, PartialEq)]
+#[cfg_attr(test, derive(Debug))]
+pub struct Transaction<'a> {
+    pub version: TransactionVersion,
+    pub chain_id: u32,
+    pub transaction_auth: TransactionAuth<'a>,
+    pub transaction_modes: &'a [u8; 2],
+    pub post_conditions: PostConditions<'a>,
+    pub payload: TransactionPayload<'a>,
+    signer: SignerId,
+    // Whether the derivation path is not one wallets use,
+    // set when the path is checked
+    nonstandard_path: bool,
+    // Whether the derivation path is of the other network,
+    // set when the network is checked
+    network_mismatch: bool,
+    // Whether the key of this device could not be matched to the multisig
+    // origin it signs for, set when the multisig signer is checked
+    unknown_multisig_signer: bool,
+    // Whether the contract called is in the provisioned denylist,
+    // set when the denylist is checked
+    denylisted: bool,
+    // Whether the PoX rewards go to a key of this device,
+    // set when the reward address is checked
+    reward_owner: RewardOwner,
+    // Whether the sponsor is also a key of this device, signing in the
+    // same session as the origin, set when the sponsor key is checked
+    device_sponsor: bool,
+    // The address of the signer, encoded the first time it is shown
+    // as its checksum takes two SHA-256 rounds on every page
+    pub(crate) signer_address: Cell<Option<C32Address>>,
+    // The bytes of the authorization, from its type byte,
+    // and of the payload, shown as hex in expert mode
+    raw_auth: &'a [u8],
+    raw_payload: &'a [u8],
+    // If this is a multisig transaction this field should content
+    // the previous signer's post_sig_hash, pubkey type(compressed/uncom..), and the signature(vrs)
+    // with them, we can construct the pre_sig_hash for the current signer
+    // we would ideally verify it, but we can lend such responsability to the application
+    // which has more resources
+    // If this is not a multisig transaction, this field should be an empty array
+    pub remainder: &'a [u8],
+}
+
+impl<'a> Transaction<'a> {
+    fn update_remainder(&mut self, data: &'a [u8]) {
+        self.remainder = data;
+    }
+
+    #[inline(never)]
+    pub fn read(&mut self, data: &'a [u8]) -> Result<(), ParserError> {
+        self.update_remainder(data);
+        self.read_section(ParseSection::Header, Self::read_header)?;
+        self.read_section(ParseSection::Auth, Self::read_auth)?;
+        self.read_section(ParseSection::PostConditions, |tx| {
+            tx.read_transaction_modes()?;
+            tx.read_post_conditions()
+        })?;
+        self.read_section(ParseSection::Payload, Self::read_payload)?;
+
+        let is_token_transfer = self.payload.is_token_transfer_payload();
+        let is_standard_auth = self.transaction_auth.is_standard_auth();
+
+        if is_token_transfer && !is_standard_auth {
+            let e = ParserError::parser_invalid_transaction_payload;
+            trace::emit(Event::Error(ParseSection::Payload, e));
+            return Err(e);
+        }
+
+        if let Err(e) = check_trailing_bytes(self.remainder, self.is_multisig()) {
+            trace::emit(Event::Error(ParseSection::Payload, e));
+            return Err(e);
+        }
+
+        // At this point we do not know who the signer is
+        // nor its derivation path
+        // nor if it is one of the keys of a multisig origin
+        // nor if the contract called is denylisted
+        // nor who the rewards are paid to
+        // nor whether this device is the sponsor too
+        self.signer = SignerId::Invalid;
+        self.nonstandard_path = false;
+        self.network_mismatch = false;
+        self.unknown_multisig_signer = false;
+        self.denylisted = false;
+        self.reward_owner = RewardOwner::Unchecked;
+        self.device_sponsor = false;
+        self.signer_address.set(None);
+        Ok(())
+    }
+
+    // reads a section with read, reporting it to the parse trace
+    fn read_section(
+        &mut self,
+        section: ParseSection,
+        read: fn(&mut Self) -> Result<(), ParserError>,
+    ) -> Result<(), ParserError> {
+        trace::emit(Event::Enter(section));
+        let len = self.remainder.len();
+        if let Err(e) = read(self) {
+            trace::emit(Event::Error(section, e));
+            return Err(e);
+        }
+        let consumed = len.saturating_sub(self.remainder.len());
+        trace::emit(Event::Consumed(section, consumed));
+        Ok(())
+    }
+
+    #[inline(never)]
+    fn read_header(&mut self) -> Result<(), ParserError> {
+        let (next_data, version) = TransactionVersion::from_bytes(self.remainder)
+            .map_err(|_| ParserError::parser_unexpected_value)?;
+
+        let (next_data, chain_id) =
+            be_u32(next_data).map_err(|_| ParserError::parser_unexpected_value)?;
+
+        self.version = version;
+        self.chain_id = chain_id;
+        check_canary!();
+
+        self.update_remainder(next_data);
+
+        Ok(())
+    }
+
+    #[inline(never)]
+    fn read_auth(&mut self) -> Result<(), ParserError> {
+        let (next_data, auth) = TransactionAuth::from_bytes(self.remainder)
+            .map_err(|_| ParserError::parser_invalid_auth_type)?;
+        auth.check_fees()?;
+        self.transaction_auth = auth;
+        self.raw_auth = consumed(self.remainder, next_data);
+        self.update_remainder(next_data);
+        check_canary!();
+        Ok(())
+    }
+
+    #[inline(never)]
+    fn read_transaction_modes(&mut self) -> Result<(), ParserError> {
+        // two modes are included here,
+        // anchor mode and postcondition mode
+        let (raw, modes) =
+            take(2usize)(self.remainder).map_err(|_| ParserError::parser_unexpected_buffer_end)?;
+        self.transaction_modes = modes
+            .try_into()
+            .map_err(|_| ParserError::parser_unexpected_buffer_end)?;
+        self.update_remainder(raw);
+        check_canary!();
+        Ok(())
+    }
+
+    #[inline(never)]
+    fn read_post_conditions(&mut self) -> Result<(), ParserError> {
+        let (raw, conditions) = PostConditions::from_bytes(self.remainder)
+            .map_err(or_limit(ParserError::parser_post_condition_failed))?;
+        self.post_conditions = conditions;
+        self.update_remainder(raw);
+        check_canary!();
+        Ok(())
+    }
+
+    #[inline(never)]
+    fn read_payload(&mut self) -> Result<(), ParserError> {
+        let (raw, payload) = TransactionPayload::from_bytes(self.remainder)
+            .map_err(or_limit(ParserError::parser_invalid_transaction_payload))?;
+        self.payload = payload;
+        self.raw_payload = consumed(self.remainder, raw);
+        self.update_remainder(raw);
+        check_canary!();
+        Ok(())
+    }
+
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, ParserError> {
+        match permutation((
+            TransactionVersion::from_bytes,
+            be_u32,
+            with_consumed(TransactionAuth::from_bytes),
+            take(2usize),
+            PostConditions::from_bytes,
+            with_consumed(TransactionPayload::from_bytes),
+        ))(bytes)
+        {
+            Ok(tx) => {
+                // Note that if a transaction contains a token-transfer payload,
+                // it MUST have only a standard authorization field. It cannot be sponsored.
+                let ((_, auth), (_, payload)) = (&(tx.1).2, &(tx.1).5);
+                if payload.is_token_transfer_payload() && !auth.is_standard_auth() {
+                    return Err(ParserError::parser_invalid_transaction_payload);
+                }
+                Self::try_from(tx)
+            }
+            Err(_e) => Err(ParserError::parser_unexpected_error),
+        }
+    }
+
+    /// Parses data the same way read does and returns where it failed, or
+    /// None if it is a valid transaction. Integrators use it to find the
+    /// field a malformed transaction is rejected at.
+    pub fn diagnose(data: &'a [u8]) -> Option<ParseDiagnostic> {
+        Self::diagnose_sections(data).err()
+    }
+
+    fn diagnose_sections(data: &'a [u8]) -> Result<(), ParseDiagnostic> {
+        // the diagnostic of a parser that failed on rem
+        let fail = |section, rem: &[u8], error| ParseDiagnostic {
+            error,
+            section,
+            offset: (data.len() - rem.len()) as u32,
+        };
+        let at =
+            |section, rem: &'a [u8]| move |e: nom::Err<ParserError>| fail(section, rem, e.into());
+
+        let (rem, _) =
+            TransactionVersion::from_bytes(data).map_err(at(ParseSection::Header, data))?;
+        let (rem, _) = be_u32(rem).map_err(at(ParseSection::Header, rem))?;
+
+        let (mut raw, flag) = le_u8(rem).map_err(at(ParseSection::Auth, rem))?;
+        let num_conditions = match flag {
+            0x04 => 1,
+            0x05 => 2,
+            _ => {
+                let e = ParserError::parser_invalid_auth_type;
+                return Err(fail(ParseSection::Auth, rem, e));
+            }
+        };
+        let mut multisig = false;
+        for i in 0..num_conditions {
+            let (next, condition) = TransactionSpendingCondition::from_bytes(raw)
+                .map_err(at(ParseSection::Auth, raw))?;
+            if i == 0 {
+                multisig = condition.is_multisig();
+            }
+            if condition.fee() > MAX_FEE {
+                let e = ParserError::parser_fee_too_high;
+                return Err(fail(ParseSection::Auth, raw, e));
+            }
+            raw = next;
+        }
+
+        let (mut rem, _) = take(2usize)(raw).map_err(at(ParseSection::PostConditions, raw))?;
+        let (raw, len) = be_u32(rem).map_err(at(ParseSection::PostConditions, rem))?;
+        if len > NUM_SUPPORTED_POST_CONDITIONS as u32 {
+            let e = ParserError::parser_limit_exceeded;
+            return Err(fail(ParseSection::PostConditions, rem, e));
+        }
+        rem = raw;
+        for _ in 0..len {
+            rem = TransactionPostCondition::read_as_bytes(rem)
+                .map_err(at(ParseSection::PostConditions, rem))?
+                .0;
+        }
+
+        let (trailing, payload) =
+            TransactionPayload::from_bytes(rem).map_err(at(ParseSection::Payload, rem))?;
+        if payload.is_token_transfer_payload() && flag != 0x04 {
+            let e = ParserError::parser_invalid_transaction_payload;
+            return Err(fail(ParseSection::Payload, rem, e));
+        }
+        check_trailing_bytes(trailing, multisig)
+            .map_err(|e| fail(ParseSection::Payload, trailing, e))
+    }
+
+    pub fn payload_recipient_address(
+        &self,
+    ) -> Option<arrayvec::ArrayVec<[u8; C32_ENCODED_ADDRS_LENGTH]>> {
+        self.payload.recipient_address()
+    }
+
+    pub fn num_items(&self) -> Result<u8, ParserError> {
+        let raw_items = if is_raw_hex_mode() {
+            self.num_raw_items()
+        } else {
+            0
+        };
+
+        self.num_review_items()?
+            .checked_add(raw_items)
+            .ok_or(ParserError::parser_limit_exceeded)
+            .and_then(check_num_items)
+    }
+
+    // path warning + network warning + multisig signer warning + denylist
+    // warning + reward owner + sponsored banner + origin + signatures count +
+    // duplicate keys warning + nonce + fee-rate, the warnings are only shown
+    // when the path is non-standard or of the other network or the key can
+    // not be matched to the multisig origin or the contract called is
+    // denylisted, the reward owner for stack-stx calls, the banner for
+    // sponsored transactions, the signatures count for multisig ones, the
+    // duplicate keys one when their auth fields repeat a key and the nonce
+    // can be hidden
+    fn num_origin_items(&self) -> u8 {
+        let path = self.nonstandard_path as u8;
+        let network = self.network_mismatch as u8;
+        let multisig_signer = self.unknown_multisig_signer as u8;
+        let denylisted = self.denylisted as u8;
+        let reward = (self.reward_owner != RewardOwner::Unchecked) as u8;
+        let sponsored = !self.transaction_auth.is_standard_auth() as u8;
+        let signatures = self.transaction_auth.is_multisig() as u8;
+        let duplicate = self.transaction_auth.origin_has_duplicate_keys() as u8;
+        let nonce = !is_nonce_hidden() as u8;
+        2 + path
+            + network
+            + multisig_signer
+            + denylisted
+            + reward
+            + sponsored
+            + signatures
+            + duplicate
+            + nonce
+    }
+
+    // maps a display index to one of the origin items
+    fn origin_item(&self, display_idx: u8) -> Result<OriginItem, ParserError> {
+        let mut idx = display_idx;
+        if self.nonstandard_path {
+            if idx == 0 {
+                return Ok(OriginItem::Path);
+            }
+            idx -= 1;
+        }
+
+        if self.network_mismatch {
+            if idx == 0 {
+                return Ok(OriginItem::Network);
+            }
+            idx -= 1;
+        }
+
+        if self.unknown_multisig_signer {
+            if idx == 0 {
+                return Ok(OriginItem::MultisigSigner);
+            }
+            idx -= 1;
+        }
+
+        if self.denylisted {
+            if idx == 0 {
+                return Ok(OriginItem::Denylisted);
+            }
+            idx -= 1;
+        }
+
+        if self.reward_owner != RewardOwner::Unchecked {
+            if idx == 0 {
+                return Ok(OriginItem::RewardOwner);
+            }
+            idx -= 1;
+        }
+
+        if !self.transaction_auth.is_standard_auth() {
+            if idx == 0 {
+                return Ok(OriginItem::Sponsored);
+            }
+            idx -= 1;
+        }
+
+        if idx == 0 {
+            return Ok(OriginItem::Signer);
+        }
+        idx -= 1;
+
+        if self.transaction_auth.is_multisig() {
+            if idx == 0 {
+                return Ok(OriginItem::Signatures);
+            }
+            idx -= 1;
+        }
+
+        if self.transaction_auth.origin_has_duplicate_keys() {
+            if idx == 0 {
+                return Ok(OriginItem::DuplicateKeys);
+            }
+            idx -= 1;
+        }
+
+        match (idx, is_nonce_hidden()) {
+            (0, false) => Ok(OriginItem::Nonce),
+            (0, true) | (1, false) => Ok(OriginItem::Fee),
+            _ => Err(ParserError::parser_display_idx_out_of_range),
+        }
+    }
+
+    // the human-readable items, raw hex items are shown after them
+    fn num_review_items(&self) -> Result<u8, ParserError> {
+        // origin items + payload + post-conditions
+        self.num_origin_items()
+            .checked_add(self.payload.num_items()?)
+            .and_then(|res| res.checked_add(self.post_conditions.num_items()))
+            .ok_or(ParserError::parser_limit_exceeded)
+    }
+
+    // auth + payload + one per post-condition
+    fn num_raw_items(&self) -> u8 {
+        2 + self.post_conditions.num_conditions()
+    }
+
+    /// Returns the raw bytes of the authorization section,
+    /// including the auth type byte
+    pub fn raw_auth(&self) -> &[u8] {
+        self.raw_auth
+    }
+
+    /// Returns the raw bytes of the payload
+    pub fn raw_payload(&self) -> &[u8] {
+        self.raw_payload
+    }
+
+    #[inline(never)]
+    fn get_raw_item(
+        &self,
+        raw_idx: u8,
+        out_key: &mut [u8],
+        out_value: &mut [u8],
+        page_idx: u8,
+    ) -> Result<u8, ParserError> {
+        let mut writer_key = zxformat::Writer::new(out_key);
+
+        let raw = match raw_idx {
+            0 => {
+                writer_key
+                    .write_str(LABELS.raw_auth)
+                    .map_err(|_| ParserError::parser_unexpected_buffer_end)?;
+                self.raw_auth()
+            }
+            1 => {
+                writer_key
+                    .write_str(LABELS.raw_payload)
+                    .map_err(|_| ParserError::parser_unexpected_buffer_end)?;
+                self.raw_payload()
+            }
+            idx => {
+                let condition_idx = idx - 2;
+                let condition = self
+                    .post_conditions
+                    .condition(condition_idx)
+                    .ok_or(ParserError::parser_display_idx_out_of_range)?;
+
+                let mut num_buff = [0u8; 3];
+                let num_str = (condition_idx + 1).numtoa_str(10, &mut num_buff);
+                writer_key
+                    .write_str(LABELS.raw_post_condition)
+                    .and_then(|_| writer_key.write_str(num_str))
+                    .and_then(|_| writer_key.write_str(LABELS.raw_suffix))
+                    .map_err(|_| ParserError::parser_unexpected_buffer_end)?;
+                condition
+            }
+        };
+
+        zxformat::pageHex(out_value, raw, page_idx)
+    }
+
+    // the spending condition of who is signing this transaction
+    fn signer_condition(&self) -> Result<&SpendingConditionSigner<'_>, ParserError> {
+        #[cfg(any(test, not(feature = "device")))]
+        let origin = self.transaction_auth.origin();
+
+        #[cfg(all(feature = "device", not(test)))]
+        let origin = match self.signer {
+            SignerId::Origin => self.transaction_auth.origin(),
+            SignerId::Sponsor => self
+                .transaction_auth
+                .sponsor()
+                .ok_or(ParserError::parser_invalid_auth_type)?,
+            _ => return Err(ParserError::parser_invalid_auth_type),
+        };
+
+        Ok(origin)
+    }
+
+    /// The address of who is signing this transaction, encoded once
+    pub fn signer_address(&self) -> Result<C32Address, ParserError> {
+        if let Some(address) = self.signer_address.get() {
+            return Ok(address);
+        }
+        let address = self.signer_condition()?.c32_signer_address(self.version)?;
+        self.signer_address.set(Some(address));
+        Ok(address)
+    }
+
+    /// Whether the fee paid by the signer is above the configured threshold
+    pub fn is_high_fee(&self) -> bool {
+        self.signer_condition()
+            .and_then(|signer| signer.fee())
+            .map(|fee| fee > fee_threshold())
+            .unwrap_or(false)
+    }
+
+    fn get_origin_items(
+        &self,
+        display_idx: u8,
+        out_key: &mut [u8],
+        out_value: &mut [u8],
+        page_idx: u8,
+        scratch: &Scratch,
+    ) -> Result<u8, ParserError> {
+        let mut writer_key = zxformat::Writer::new(out_key);
+
+        let origin = self.signer_condition()?;
+
+        match self.origin_item(display_idx)? {
+            // The signing key is not at a path wallets use
+            OriginItem::Path => {
+                writer_key
+                    .write_str(LABELS.key_path)
+                    .map_err(|_| ParserError::parser_unexpected_buffer_end)?;
+                zxformat::pageString(out_value, LABELS.nonstandard_path.as_bytes(), page_idx)
+            }
+            // The transaction is for the other network than the signing key
+            OriginItem::Network => {
+                writer_key
+                    .write_str(LABELS.network)
+                    .map_err(|_| ParserError::parser_unexpected_buffer_end)?;
+                let value = match self.version {
+                    TransactionVersion::Mainnet => LABELS.mainnet_tx,
+                    TransactionVersion::Testnet => LABELS.testnet_tx,
+                };
+                zxformat::pageString(out_value, value.as_bytes(), page_idx)
+            }
+            // The key of this device is not known to be one of the account
+            OriginItem::MultisigSigner => {
+                writer_key
+                    .write_str(LABELS.multisig_account)
+                    .map_err(|_| ParserError::parser_unexpected_buffer_end)?;
+                zxformat::pageString(
+                    out_value,
+                    LABELS.multisig_signer_unknown.as_bytes(),
+                    page_idx,
+                )
+            }
+            // The contract called is known to be malicious
+            OriginItem::Denylisted => {
+                writer_key
+                    .write_str(LABELS.denylisted)
+                    .map_err(|_| ParserError::parser_unexpected_buffer_end)?;
+                zxformat::pageString(out_value, LABELS.denylisted_msg.as_bytes(), page_idx)
+            }
+            // Whether the stacking rewards go to a key of this device
+            OriginItem::RewardOwner => {
+                writer_key
+                    .write_str(LABELS.reward_owner)
+                    .map_err(|_| ParserError::parser_unexpected_buffer_end)?;
+                let value = match self.reward_owner {
+                    RewardOwner::Device => LABELS.reward_device,
+                    _ => LABELS.reward_unknown,
+                };
+                zxformat::pageString(out_value, value.as_bytes(), page_idx)
+            }
+            // Who pays the fee on behalf of the origin
+            OriginItem::Sponsored => {
+                writer_key
+                    .write_str(LABELS.sponsored)
+                    .map_err(|_| ParserError::parser_unexpected_buffer_end)?;
+                let sponsor = self
+                    .transaction_auth
+                    .sponsor()
+                    .ok_or(ParserError::parser_invalid_auth_type)?;
+                let sponsor_address = sponsor.c32_signer_address(self.version)?;
+                let paid_by = if self.device_sponsor {
+                    LABELS.fee_paid_by_device
+                } else {
+                    LABELS.fee_paid_by
+                };
+                sponsor_address.page_between(paid_by.as_bytes(), &[], out_value, page_idx)
+            }
+            // The address of who signed this transaction
+            OriginItem::Signer => {
+                writer_key
+                    .write_str(LABELS.origin)
+                    .map_err(|_| ParserError::parser_unexpected_buffer_end)?;
+                self.signer_address()?.page(out_value, page_idx)
+            }
+            // How many cosigners already signed
+            OriginItem::Signatures => {
+                writer_key
+                    .write_str(LABELS.signatures)
+                    .map_err(|_| ParserError::parser_unexpected_buffer_end)?;
+                let (present, required) = self
+                    .transaction_auth
+                    .origin_signatures()
+                    .ok_or(ParserError::parser_invalid_auth_type)?;
+
+                let present = present.numtoa(10, scratch.alloc(10)?);
+                let required = required.numtoa(10, scratch.alloc(5)?);
+                let count = scratch.concat(&[present, LABELS.of.as_bytes(), required])?;
+                zxformat::pageString(out_value, count, page_idx)
+            }
+            // The same key is in more than one auth field
+            OriginItem::DuplicateKeys => {
+                writer_key
+                    .write_str(LABELS.caution)
+                    .map_err(|_| ParserError::parser_unexpected_buffer_end)?;
+                zxformat::pageString(out_value, LABELS.duplicate_keys.as_bytes(), page_idx)
+            }
+            // The signer nonce
+            OriginItem::Nonce => {
+                writer_key
+                    .write_str(LABELS.nonce)
+                    .map_err(|_| ParserError::parser_unexpected_buffer_end)?;
+                let nonce = origin.nonce_str(scratch.alloc(zxformat::MAX_STR_BUFF_LEN)?)?;
+                zxformat::pageString(out_value, nonce, page_idx)
+            }
+            // The signer fee-rate, or the sponsor one
+            // which is the fee actually paid
+            OriginItem::Fee => {
+                let sponsor = self.transaction_auth.sponsor();
+                let key = match sponsor {
+                    _ if self.is_high_fee() => {
+                        zxformat::stx_label(LABELS.high_fee, LABELS.high_fee_stx)
+                    }
+                    Some(_) => zxformat::stx_label(LABELS.sponsor_fee, LABELS.sponsor_fee_stx),
+                    None => zxformat::stx_label(LABELS.fee, LABELS.fee_stx),
+                };
+                writer_key
+                    .write_str(key)
+                    .map_err(|_| ParserError::parser_unexpected_buffer_end)?;
+                let fee = sponsor
+                    .unwrap_or(origin)
+                    .fee_str(scratch.alloc(zxformat::MAX_STR_BUFF_LEN)?)?;
+                zxformat::pageString(out_value, fee, page_idx)
+            }
+        }
+    }
+
+    #[inline(always)]
+    fn get_other_items(
+        &mut self,
+        display_idx: u8,
+        out_key: &mut [u8],
+        out_value: &mut [u8],
+        page_idx: u8,
+        scratch: &Scratch,
+    ) -> Result<u8, ParserError> {
+        let num_items = self.num_review_items()?;
+        let post_conditions_items = self.post_conditions.num_items();
+
+        if display_idx >= (num_items - post_conditions_items) {
+            if post_conditions_items == 0 {
+                return Err(ParserError::parser_display_idx_out_of_range);
+            }
+            self.post_conditions.get_items(
+                display_idx - (num_items - post_conditions_items),
+                out_key,
+                out_value,
+                page_idx,
+            )
+        } else {
+            self.payload.get_items(
+                display_idx,
+                out_key,
+                out_value,
+                page_idx,
+                num_items - post_conditions_items, // we need to display the payload in order
+                scratch,
+            )
+        }
+    }
+
+    pub fn get_item(
+        &mut self,
+        display_idx: u8,
+        out_key: &mut [u8],
+        out_value: &mut [u8],
+        page_idx: u8,
+        scratch: &Scratch,
+    ) -> Result<u8, ParserError> {
+        if display_idx >= self.num_items()? {
+            return Err(ParserError::parser_display_idx_out_of_range);
+        }
+
+        let review_items = self.num_review_items()?;
+
+        if display_idx < self.num_origin_items() {
+            self.get_origin_items(display_idx, out_key, out_value, page_idx, scratch)
+        } else if display_idx < review_items {
+            self.get_other_items(display_idx, out_key, out_value, page_idx, scratch)
+        } else {
+            self.get_raw_item(display_idx - review_items, out_key, out_value, page_idx)
+        }
+    }
+
+    pub fn item_section(&self, display_idx: u8) -> Result<ItemSection, ParserError> {
+        let num_items = self.num_items()?;
+        if display_idx >= num_items {
+            return Err(ParserError::parser_display_idx_out_of_range);
+        }
+
+        let review_items = self.num_review_items()?;
+        let origin_items = self.num_origin_items();
+        let payload_end = review_items - self.post_conditions.num_items();
+
+        match display_idx {
+            idx if idx < origin_items => match self.origin_item(idx)? {
+                OriginItem::Fee => Ok(ItemSection::Fees),
+                _ => Ok(ItemSection::Details),
+            },
+            idx if idx < payload_end => Ok(self.payload.item_section(idx - origin_items)),
+            idx if idx < review_items => Ok(ItemSection::PostConditions),
+            // raw hex items
+            _ => Ok(ItemSection::Details),
+        }
+    }
+
+    pub fn item_kind(&self, display_idx: u8) -> Result<ItemKind, ParserError> {
+        let num_items = self.num_items()?;
+        if display_idx >= num_items {
+            return Err(ParserError::parser_display_idx_out_of_range);
+        }
+
+        let review_items = self.num_review_items()?;
+        let origin_items = self.num_origin_items();
+        let payload_end = review_items - self.post_conditions.num_items();
+
+        match display_idx {
+            idx if idx < origin_items => match self.origin_item(idx)? {
+                OriginItem::Signer => Ok(ItemKind::Address),
+                OriginItem::Sponsored | OriginItem::Signatures | OriginItem::Nonce => {
+                    Ok(ItemKind::Text)
+                }
+                OriginItem::Path
+                | OriginItem::Network
+                | OriginItem::MultisigSigner
+                | OriginItem::Denylisted
+                | OriginItem::DuplicateKeys => Ok(ItemKind::Warning),
+                OriginItem::RewardOwner if self.reward_owner == RewardOwner::Device => {
+                    Ok(ItemKind::Text)
+                }
+                OriginItem::RewardOwner => Ok(ItemKind::Warning),
+                OriginItem::Fee if self.is_high_fee() => Ok(ItemKind::Warning),
+                OriginItem::Fee => Ok(ItemKind::Amount),
+            },
+            idx if idx < payload_end => Ok(self.payload.item_kind(idx - origin_items)),
+            idx if idx < review_items => self.post_conditions.item_kind(idx - payload_end),
+            _ => Ok(ItemKind::RawHex),
+        }
+    }
+
+    pub fn origin_fee(&self) -> u64 {
+        self.transaction_auth.origin_fee()
+    }
+
+    pub fn origin_nonce(&self) -> u64 {
+        self.transaction_auth.origin_nonce()
+    }
+
+    pub fn sponsor_fee(&self) -> Option<u64> {
+        self.transaction_auth.sponsor_fee()
+    }
+
+    pub fn sponsor_nonce(&self) -> Option<u64> {
+        self.transaction_auth.sponsor_nonce()
+    }
+
+    // Returns the transaction nonce according to
+    // who the signer is. The signer could be the Origin, a sponsor
+    // or Invalid that happens when its credentials are not present
+    // in the transaction
+    pub fn nonce(&self) -> Option<u64> {
+        match self.signer {
+            SignerId::Origin => Some(self.origin_nonce()),
+            SignerId::Sponsor => self.sponsor_nonce(),
+            SignerId::Invalid => None,
+        }
+    }
+
+    // Returns the transaction fee according to
+    // who the signer is. The signer could be the Origin, Sponsor
+    // or Invalid, the later that happens when its credentials are not present
+    // in the transaction
+    pub fn fee(&self) -> Option<u64> {
+        match self.signer {
+            SignerId::Origin => Some(self.origin_fee()),
+            SignerId::Sponsor => self.sponsor_fee(),
+            SignerId::Invalid => None,
+        }
+    }
+
+    pub fn auth_flag(&self) -> TransactionAuthFlags {
+        if self.transaction_auth.is_standard_auth() {
+            return TransactionAuthFlags::Standard;
+        }
+        TransactionAuthFlags::Sponsored
+    }
+
+    /// Checks if we can sign this transaction.
+    /// If this is a singlesig transaction we should be either the origin or sponsor,
+    /// otherwise the signature would not be valid for it.
+    /// A multisig origin passes the check, its key is checked by
+    /// check_multisig_signer.
+    pub fn check_signer_pk_hash(&mut self, signer_pk: &[u8]) -> ParserError {
+        self.signer = self.transaction_auth.check_signer(signer_pk);
+        self.signer_address.set(None);
+        self.unknown_multisig_signer = false;
+        if self.signer == SignerId::Invalid {
+            return ParserError::parser_signer_mismatch;
+        }
+
+        // a multisig origin must still have room for this signature
+        let origin = self.transaction_auth.origin_condition();
+        if let Some(multisig) = origin.multisig() {
+            if let Err(e) = origin
+                .hash_mode()
+                .and_then(|mode| multisig.check_partially_signed(mode))
+            {
+                self.signer = SignerId::Invalid;
+                return e;
+            }
+        }
+        ParserError::parser_ok
+    }
+
+    /// Checks pubkey, the compressed key of this device, against the multisig
+    /// origin it signs for, after check_signer_pk_hash. The hash of the origin
+    /// is that of all its keys, so the key is only known to be one of them
+    /// when the auth fields list it or when account, the hash160 of the last
+    /// multisig template the key was found in, is the origin one. Otherwise
+    /// the review starts with a warning the user has to go through
+    pub fn check_multisig_signer(&mut self, pubkey: &[u8], account: Option<&[u8]>) -> ParserError {
+        self.unknown_multisig_signer = false;
+        if self.signer != SignerId::Origin {
+            return ParserError::parser_ok;
+        }
+        let origin = self.transaction_auth.origin_condition();
+        if let Some(multisig) = origin.multisig() {
+            let known_account = account == Some(origin.signer_pub_key_hash());
+            self.unknown_multisig_signer = !known_account && !multisig.lists_key(pubkey);
+        }
+        ParserError::parser_ok
+    }
+
+    /// Chains presig, the pre_sig_hash of the first signer of a multisig
+    /// origin, up to the one this device signs. The signatures already in the
+    /// auth fields, as stacks.js serializes partially signed transactions,
+    /// are chained in order so the device can sign at any position. The
+    /// previous signer data some hosts append after the transaction must be
+    /// the last of them, or is chained on its own when there is none. In the
+    /// order independent modes every signer signs the pre_sig_hash of the
+    /// first one, there is nothing to chain
+    pub fn multisig_presig_hash<H: Sha512_256>(
+        &self,
+        presig: &mut [u8; SHA512_256_LEN],
+    ) -> Result<(), ParserError> {
+        let origin = self.transaction_auth.origin_condition();
+        let multisig = origin
+            .multisig()
+            .ok_or(ParserError::parser_invalid_auth_type)?;
+        let hash_mode = origin.hash_mode()?;
+        multisig.check_partially_signed(hash_mode)?;
+
+        if !hash_mode.is_sequential() {
+            return match self.previous_signer_data() {
+                None => Ok(()),
+                Some(_) => Err(ParserError::parser_invalid_auth_type),
+            };
+        }
+
+        let mut auth_data = [0u8; AUTH_DATA_LEN];
+        self.role_auth_data(SignerId::Origin, &mut auth_data)?;
+
+        let last = multisig.chain_presig_hash::<H>(presig, &auth_data)?;
+        match (self.previous_signer_data(), last) {
+            (None, _) => Ok(()),
+            (Some(data), Some(last)) if data == &last[..] => Ok(()),
+            (Some(_), Some(_)) => Err(ParserError::parser_invalid_signature),
+            (Some(data), None) => {
+                let data = data
+                    .get(..SIGNED_FIELD_LEN)
+                    .ok_or(ParserError::parser_unexpected_buffer_end)?;
+                let (post_sig, signature) = data.split_at(SHA512_256_LEN);
+                let (encoding, signature) = signature
+                    .split_first()
+                    .ok_or(ParserError::parser_unexpected_buffer_end)?;
+                let mut expected = [0u8; SHA512_256_LEN];
+                post_sig_hash::<H>(presig, *encoding, signature, &mut expected);
+                if post_sig != &expected[..] {
+                    return Err(ParserError::parser_invalid_signature);
+                }
+                next_presig_hash::<H>(&expected, &auth_data, presig);
+                Ok(())
+            }
+        }
+    }
+
+    /// The index of the auth field of the multisig origin the signature of
+    /// pubkey, the compressed key of this device, goes in
+    pub fn multisig_auth_field_index(&self, pubkey: &[u8]) -> Result<u32, ParserError> {
+        let origin = self.transaction_auth.origin_condition();
+        let multisig = origin
+            .multisig()
+            .ok_or(ParserError::parser_invalid_auth_type)?;
+        multisig.auth_field_index(pubkey)
+    }
+
+    /// Checks that the sponsor is the key whose hash160 is sponsor_pk, for
+    /// a device that signs as both the origin and the sponsor. The review
+    /// then tells the fee is paid by this device
+    pub fn check_sponsor_pk_hash(&mut self, sponsor_pk: &[u8]) -> ParserError {
+        if self.signer != SignerId::Origin || self.transaction_auth.is_multisig() {
+            return ParserError::parser_invalid_auth_type;
+        }
+        match self.transaction_auth.sponsor() {
+            Some(sponsor) if sponsor.pub_key_hash() == sponsor_pk => {
+                self.device_sponsor = true;
+                ParserError::parser_ok
+            }
+            Some(_) => ParserError::parser_signer_mismatch,
+            None => ParserError::parser_invalid_auth_type,
+        }
+    }
+
+    /// Writes the auth flag, fee and nonce a signer of role appends to the
+    /// hash it signs: the origin signs with the standard flag and the sponsor
+    /// with the sponsored one, each with the fee and nonce of its own
+    /// spending condition. Returns the bytes written
+    pub fn role_auth_data(&self, role: SignerId, out: &mut [u8]) -> Result<usize, ParserError> {
+        let (flag, fee, nonce) = match role {
+            SignerId::Origin => (
+                TransactionAuthFlags::Standard,
+                self.origin_fee(),
+                self.origin_nonce(),
+            ),
+            SignerId::Sponsor => (
+                TransactionAuthFlags::Sponsored,
+                self.sponsor_fee()
+                    .ok_or(ParserError::parser_invalid_auth_type)?,
+                self.sponsor_nonce()
+                    .ok_or(ParserError::parser_invalid_auth_type)?,
+            ),
+            SignerId::Invalid => return Err(ParserError::parser_invalid_auth_type),
+        };
+
+        let (fee, nonce) = (fee.to_be_bytes(), nonce.to_be_bytes());
+        let data = core::iter::once(flag as u8)
+            .chain(fee.iter().copied())
+            .chain(nonce.iter().copied());
+        let mut len = 0;
+        for (o, b) in out.iter_mut().zip(data) {
+            *o = b;
+            len += 1;
+        }
+        if len != AUTH_DATA_LEN {
+            return Err(ParserError::parser_unexpected_buffer_end);
+        }
+        Ok(len)
+    }
+
+    /// Checks the derivation path of the signing key against the ones wallets
+    /// use, the review starts with a warning if it is not one of them
+    pub fn check_path(&mut self, path: &[u32]) {
+        self.nonstandard_path = !is_standard_path(path);
+    }
+
+    /// Whether the derivation path of the signing key is not a standard one
+    pub fn is_nonstandard_path(&self) -> bool {
+        self.nonstandard_path
+    }
+
+    /// Checks the transaction version against the network of the derivation
+    /// path. A mismatch is kept, so the review starts with a warning if the
+    /// app is configured to sign it anyway
+    pub fn check_network(&mut self, testnet_path: bool) -> ParserError {
+        let testnet_tx = self.version == TransactionVersion::Testnet;
+        self.network_mismatch = testnet_tx != testnet_path;
+        if self.network_mismatch {
+            return ParserError::parser_network_mismatch;
+        }
+        ParserError::parser_ok
+    }
+
+    /// Checks the contract called against a provisioned denylist, each
+    /// entry is the first DENYLIST_ENTRY_LEN bytes of the SHA-512/256 of a
+    /// serialized contract principal. A listed contract is kept, so the
+    /// review starts with a warning if the app is configured to sign it anyway
+    pub fn check_denylist<H: Sha512_256>(&mut self, entries: &[u8]) -> ParserError {
+        self.denylisted = match self.payload.contract_id() {
+            Some(contract_id) => {
+                let mut digest = [0u8; SHA512_256_LEN];
+                H::digest(contract_id, &mut digest);
+                let listed = digest.get(..DENYLIST_ENTRY_LEN);
+                entries
+                    .chunks_exact(DENYLIST_ENTRY_LEN)
+                    .any(|entry| Some(entry) == listed)
+            }
+            None => false,
+        };
+        if self.denylisted {
+            return ParserError::parser_denylisted_contract;
+        }
+        ParserError::parser_ok
+    }
+
+    /// Whether the contract called was found in the denylist
+    pub fn is_denylisted(&self) -> bool {
+        self.denylisted
+    }
+
+    /// Shows what the code of a contract deploy can do with assets. scan is
+    /// the scan of the whole code body if part of it was hashed and dropped
+    pub fn check_code(&mut self, scan: Option<CodeScan>) -> ParserError {
+        match self.payload.scan_code(scan) {
+            Ok(_) => ParserError::parser_ok,
+            Err(e) => e,
+        }
+    }
+
+    /// Reviews the arguments of the contract call by the names given in abi,
+    /// a provisioned function signature, if it is the one of the function
+    /// called. The signature of any other function is left unused
+    pub fn check_abi(&mut self, abi: &'a [u8]) -> ParserError {
+        let applied = FunctionAbi::from_bytes(abi).and_then(|abi| self.payload.set_abi(abi));
+        match applied {
+            Ok(_) => ParserError::parser_ok,
+            Err(e) => e,
+        }
+    }
+
+    /// Shows the amounts of the post conditions on the tokens info, provisioned
+    /// token metadata, describes after what they are, see TokenInfo
+    pub fn check_token_info(&mut self, info: &'a [u8]) -> ParserError {
+        match TokenInfo::from_bytes(info) {
+            Ok(info) => {
+                self.post_conditions.set_token_info(info);
+                ParserError::parser_ok
+            }
+            Err(e) => e,
+        }
+    }
+
+    /// The path of the Bitcoin key the rewards of a stack-stx call would be
+    /// paid to if they go to this device, the one of the same account as the
+    /// signing key. None if the signing path is not a standard one or the
+    /// rewards are not paid to a key hash
+    pub fn reward_key_path(&self, signer_path: &[u32]) -> Option<[u32; REWARD_PATH_LEN]> {
+        let account = match signer_path {
+            [_, _, account, ..] if is_standard_path(signer_path) => *account & !HARDENED,
+            _ => return None,
+        };
+        let mainnet = self.version == TransactionVersion::Mainnet;
+        self.payload
+            .reward_address()
+            .and_then(|address| address.key_path(account, mainnet))
+    }
+
+    /// Checks the reward address of a stack-stx call against the hash160 of
+    /// the key at reward_key_path, if there is one. The review tells whether
+    /// the rewards go to this device
+    pub fn check_reward_address(&mut self, key_hash: Option<&[u8]>) {
+        self.reward_owner = match self.payload.reward_address() {
+            Some(address) if key_hash.is_some_and(|hash| address.pays_to_key(hash)) => {
+                RewardOwner::Device
+            }
+            Some(_) => RewardOwner::Unknown,
+            None => RewardOwner::Unchecked,
+        };
+    }
+
+    // returns a slice of the last block to be used in the presighash calculation
+    /// Parses the header and auth of a transaction that might still be
+    /// partially received, writing into buf the auth fields used for the
+    /// initial transaction hash.
+    /// Returns the offset at which the auth ends and the bytes written
+    pub fn initial_sighash_auth_from_bytes(
+        data: &[u8],
+        buf: &mut [u8],
+    ) -> Result<(usize, usize), ParserError> {
+        let (rem, _) = TransactionVersion::from_bytes(data)
+            .map_err(|_| ParserError::parser_unexpected_value)?;
+        let (rem, _) = be_u32(rem).map_err(|_| ParserError::parser_unexpected_value)?;
+        let (rem, auth) =
+            TransactionAuth::from_bytes(rem).map_err(|_| ParserError::parser_invalid_auth_type)?;
+        let len = auth.initial_sighash_auth(buf)?;
+        Ok((data.len() - rem.len(), len))
+    }
+
+    /// Returns the offset of the smart contract code body in a transaction
+    /// that might still be partially received. The code body is not shown,
+    /// so the bytes from there on only need to be hashed.
+    pub fn code_body_offset(data: &'a [u8]) -> Result<usize, ParserError> {
+        let tx = Self::from_bytes(data)?;
+        match tx.payload {
+            TransactionPayload::SmartContract(ref contract) => {
+                let body = contract.code_body_offset()?;
+                Ok(contract.raw().as_ptr() as usize - data.as_ptr() as usize + body)
+            }
+            _ => Err(ParserError::parser_unexpected_type),
+        }
+    }
+
+    /// Computes the initial sighash, the hash of the transaction with the
+    /// auth fields cleared, which does not depend on the fee and nonce
+    pub fn initial_sighash<H: Sha512_256>(
+        &self,
+        out: &mut [u8; SHA512_256_LEN],
+    ) -> Result<(), ParserError> {
+        let mut hasher = H::new();
+
+        // version, chain_id, auth type, origin hash mode and signer
+        hasher.update(&[self.version as u8]);
+        hasher.update(&self.chain_id.to_be_bytes());
+        let signer = self
+            .raw_auth()
+            .get(..2 + HASH160_LEN)
+            .ok_or(ParserError::parser_unexpected_buffer_end)?;
+        hasher.update(signer);
+
+        let mut auth = Zeroizing::new([0u8; INITIAL_SIGHASH_AUTH_LEN]);
+        let len = self.transaction_auth.initial_sighash_auth(&mut auth[..])?;
+        hasher.update(
+            auth.get(..len)
+                .ok_or(ParserError::parser_unexpected_buffer_end)?,
+        );
+
+        hasher.update(self.last_transaction_block());
+        hasher.finalize(out);
+        Ok(())
+    }
+
+    pub fn last_transaction_block(&self) -> &[u8] {
+        unsafe {
+            let len =
+                (self.remainder.as_ptr() as usize - self.transaction_modes.as_ptr() as usize) as _;
+            core::slice::from_raw_parts(self.transaction_modes.as_ptr(), len)
+        }
+    }
+
+    pub fn previous_signer_data(&self) -> Option<&[u8]> {
+        if self.is_multisig() {
+            return self.remainder.get(..MULTISIG_PREVIOUS_SIGNER_DATA_LEN);
+        }
+        None
+    }
+
+    pub fn is_multisig(&self) -> bool {
+        self.transaction_auth.is_multisig()
+    }
+
+    /// Whether the origin is an order independent multisig account, whose
+    /// signers all sign the same pre_sig_hash
+    pub fn is_order_independent(&self) -> bool {
+        let origin = self.transaction_auth.origin_condition();
+        origin.is_multisig() && origin.hash_mode().is_ok_and(|mode| !mode.is_sequential())
+    }
+
+    pub fn is_sponsored(&self) -> bool {
+        !self.transaction_auth.is_standard_auth()
+    }
+
+    /// The address of the sponsor in the network of the transaction
+    pub fn sponsor_address(
+        &self,
+    ) -> Result<arrayvec::ArrayVec<[u8; C32_ENCODED_ADDRS_LENGTH]>, ParserError> {
+        self.transaction_auth
+            .sponsor()
+            .ok_or(ParserError::parser_invalid_auth_type)?
+            .signer_address(self.version)
+    }
+
+    /// The number of signatures the origin multisig account requires
+    pub fn multisig_threshold(&self) -> Option<u16> {
+        self.transaction_auth
+            .origin_signatures()
+            .map(|(_, required)| required)
+    }
+
+    pub fn payload_id(&self) -> TransactionPayloadId {
+        self.payload.id()
+    }
+
+    pub fn num_post_conditions(&self) -> u8 {
+        self.post_conditions.num_conditions()
+    }
+}
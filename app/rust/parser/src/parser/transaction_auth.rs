@@ -1,10 +1,11 @@
-use nom::number::complete::le_u8;
+use super::reader::le_u8;
 
 use crate::check_canary;
 use crate::parser::{
     error::ParserError,
     parser_common::SignerId,
     spending_condition::{SpendingConditionSigner, TransactionSpendingCondition},
+    MAX_FEE,
 };
 
 // The sponsor sentinel length that includes:
@@ -13,6 +14,10 @@ use crate::parser::{
 // 66-byte signature and signature encoding
 const SPONSOR_SENTINEL_LEN: usize = 21 + 16 + 66;
 
+// The longest auth fields used for the initial transaction hash,
+// those of a sponsored singlesig origin
+pub const INITIAL_SIGHASH_AUTH_LEN: usize = 82 + SPONSOR_SENTINEL_LEN;
+
 /// A Transaction's Authorization structure
 ///
 /// this structure contains the address of the origin account,
@@ -85,6 +90,45 @@ impl<'a> TransactionAuth<'a> {
         }
     }
 
+    /// The spending condition of the origin, with its signatures
+    pub fn origin_condition(&self) -> &TransactionSpendingCondition<'a> {
+        match self {
+            Self::Standard(ref origin) | Self::Sponsored(ref origin, _) => origin,
+        }
+    }
+
+    /// Returns the signatures present and required
+    /// if the origin is a multisig account
+    pub fn origin_signatures(&self) -> Option<(u32, u16)> {
+        let origin = match self {
+            Self::Standard(ref origin) | Self::Sponsored(ref origin, _) => origin,
+        };
+        Some((origin.num_signatures()?, origin.required_signatures()?))
+    }
+
+    /// Whether the origin is a multisig account whose
+    /// auth fields repeat a public key
+    pub fn origin_has_duplicate_keys(&self) -> bool {
+        self.origin_condition()
+            .multisig()
+            .is_some_and(|multisig| multisig.has_duplicate_keys())
+    }
+
+    /// Refuses a fee above MAX_FEE in any of the spending conditions
+    pub fn check_fees(&self) -> Result<(), ParserError> {
+        let sponsor = match self {
+            Self::Sponsored(_, ref sponsor) => Some(sponsor),
+            _ => None,
+        };
+        let conditions = core::iter::once(self.origin_condition()).chain(sponsor);
+        for condition in conditions {
+            if condition.fee() > MAX_FEE {
+                return Err(ParserError::parser_fee_too_high);
+            }
+        }
+        Ok(())
+    }
+
     pub fn num_spending_conditions(&self) -> u8 {
         if self.is_standard_auth() {
             1
@@ -121,6 +165,9 @@ impl<'a> TransactionAuth<'a> {
         }
     }
 
+    /// Who signer_pk, the hash160 of the device key, signs this transaction as.
+    /// The hash of a multisig origin is that of all its keys, so it can not be
+    /// checked against one of them
     pub fn check_signer(&self, signer_pk: &[u8]) -> SignerId {
         match self {
             Self::Standard(ref origin) => {
@@ -144,8 +191,10 @@ impl<'a> TransactionAuth<'a> {
         match self {
             Self::Standard(ref origin) => origin.init_sighash(buf),
             Self::Sponsored(ref origin, _) => {
+                // the cleared origin followed by the sponsor sentinel
                 let len = origin.init_sighash(buf)?;
-                TransactionAuth::write_sponsor_sentinel(&mut buf[len..])
+                let sentinel = buf.get_mut(len..).ok_or(ParserError::parser_no_data)?;
+                Ok(len + TransactionAuth::write_sponsor_sentinel(sentinel)?)
             }
         }
     }
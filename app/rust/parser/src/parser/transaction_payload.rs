@@ -0,0 +1,1538 @@
+use core::cell::Cell;
+use core::convert::TryFrom;
+use core::fmt::Write;
+use nom::sequence::tuple;
+
+use super::reader::{be_u32, be_u64, le_u8, take};
+
+use numtoa::NumToA;
+
+use super::code_scan::page_capabilities;
+use super::recognizers::{self, Arg, ArgFormat, KnownFunction};
+use super::{
+    ClarityName, CodeScan, ContractName, FunctionAbi, ItemKind, ItemSection, PrincipalData,
+    Scratch, StacksAddress, UstxTotal, C32_ENCODED_ADDRS_LENGTH, HASH160_LEN, MAX_NUM_ARGS,
+    TOKEN_TRANSFER_MEMO_LEN, TX_DEPTH_LIMIT,
+};
+use crate::parser::error::ParserError;
+
+use crate::parser::c32::{self, C32Address};
+
+use super::value::{Value, ValueId};
+use crate::btc::PoxAddress;
+use crate::lang::LABELS;
+use crate::{check_canary, is_empty_memo_hidden, is_expert_mode, zxformat};
+
+// The pox-addr argument of stack-stx
+const REWARD_ADDRESS_ARG: usize = 1;
+
+// The items in contract_call transactions are
+// contract_address, contract_name and function_name
+pub const CONTRACT_CALL_BASE_ITEMS: u8 = 3;
+
+// Function names of admin-style operations, a call to one of them, or to
+// one whose name has it between dashes like mint-many, is flagged
+const PRIVILEGED_FUNCTIONS: [&[u8]; 7] = [
+    b"set-owner",
+    b"set-contract-owner",
+    b"transfer-ownership",
+    b"set-admin",
+    b"mint",
+    b"set-token-uri",
+    b"upgrade",
+];
+
+// Whether name is, or has as dash separated words, one of PRIVILEGED_FUNCTIONS
+fn is_privileged_name(name: &[u8]) -> bool {
+    PRIVILEGED_FUNCTIONS.iter().any(|pattern| {
+        (0..name.len()).any(|at| {
+            let end = at + pattern.len();
+            let starts = at == 0 || name.get(at - 1) == Some(&b'-');
+            let ends = end == name.len() || name.get(end) == Some(&b'-');
+            name.get(at..end) == Some(*pattern) && starts && ends
+        })
+    })
+}
+
+// writes the parts of a label one after the other
+fn write_label(out_key: &mut [u8], parts: &[&str]) -> Result<(), ParserError> {
+    let mut writer = zxformat::Writer::new(out_key);
+    parts
+        .iter()
+        .try_for_each(|part| writer.write_str(part))
+        .map_err(|_| ParserError::parser_unexpected_buffer_end)
+}
+
+// the digits of an uint value
+fn uint_digits<'s>(value: &Value, scratch: &'s Scratch) -> Result<&'s [u8], ParserError> {
+    let value = value.uint().ok_or(ParserError::parser_unexpected_type)?;
+    Ok(value.numtoa(10, scratch.alloc(I128_STR_LEN)?))
+}
+
+// digits with the decimals given, the leading zero and
+// the decimal point take two more bytes than the digits
+fn decimal_str<'s>(
+    digits: &[u8],
+    decimals: u8,
+    scratch: &'s Scratch,
+) -> Result<&'s [u8], ParserError> {
+    let out = scratch.alloc(I128_STR_LEN + 2)?;
+    let len = zxformat::fpstr_to_str(out, digits, decimals)?;
+    let out: &'s [u8] = out;
+    out.get(..len)
+        .ok_or(ParserError::parser_unexpected_buffer_end)
+}
+
+pub const MAX_STRING_ASCII_TO_SHOW: usize = 60;
+
+// The digits of i128::MIN along with its sign
+const I128_STR_LEN: usize = 40;
+
+#[repr(u8)]
+#[derive(Clone, PartialEq)]
+#[cfg_attr(test, derive(Debug))]
+pub enum TokenTranferPrincipal {
+    Standard = 0x05,
+    Contract = 0x06,
+}
+
+impl TokenTranferPrincipal {
+    fn from_u8(v: u8) -> Result<Self, ParserError> {
+        match v {
+            5 => Ok(Self::Standard),
+            6 => Ok(Self::Contract),
+            _ => Err(ParserError::parser_invalid_token_transfer_principal),
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, PartialEq)]
+#[cfg_attr(test, derive(Debug))]
+pub struct StxTokenTransfer<'a>(&'a [u8]);
+
+impl<'a> StxTokenTransfer<'a> {
+    #[inline(never)]
+    fn from_bytes(bytes: &'a [u8]) -> nom::IResult<&[u8], Self, ParserError> {
+        let id = le_u8(bytes)?;
+        let (raw, _) = match TokenTranferPrincipal::from_u8(id.1)? {
+            TokenTranferPrincipal::Standard => PrincipalData::standard_from_bytes(id.0)?,
+            TokenTranferPrincipal::Contract => PrincipalData::contract_principal_from_bytes(id.0)?,
+        };
+        // Besides principal we take the 8-bytes amount of stx and the memo,
+        // which is exactly TOKEN_TRANSFER_MEMO_LEN bytes
+        let (raw, _) = be_u64(raw)?;
+        let (raw, _) = take(TOKEN_TRANSFER_MEMO_LEN)(raw)?;
+        let len = bytes.len() - raw.len();
+        let (raw, data) = take(len)(bytes)?;
+        Ok((raw, Self(data)))
+    }
+
+    pub fn memo(&self) -> &[u8] {
+        // the memo closes the payload
+        self.0
+            .len()
+            .checked_sub(TOKEN_TRANSFER_MEMO_LEN)
+            .and_then(|at| self.0.get(at..))
+            .unwrap_or_default()
+    }
+
+    pub fn has_empty_memo(&self) -> bool {
+        self.memo().iter().all(|b| *b == 0)
+    }
+
+    /// Whether the memo mixes text with control bytes, as hosts that
+    /// serialize it wrongly tend to produce, the review then shows a
+    /// caution item after it
+    pub fn has_malformed_memo(&self) -> bool {
+        // the zeros padding the memo are not part of it
+        let memo = self.memo();
+        let len = memo.iter().rposition(|b| *b != 0).map_or(0, |at| at + 1);
+        let text = memo.get(..len).unwrap_or_default();
+        text.iter().any(u8::is_ascii_graphic) && text.iter().any(u8::is_ascii_control)
+    }
+
+    pub fn amount(&self) -> Result<u64, ParserError> {
+        let at = self.0.len() - TOKEN_TRANSFER_MEMO_LEN - 8;
+        let amount = self.0.get(at..).ok_or(ParserError::parser_no_data)?;
+        be_u64(amount)
+            .map(|res| res.1)
+            .map_err(|_| ParserError::parser_unexpected_buffer_end)
+    }
+
+    pub fn recipient(&self) -> Result<PrincipalData<'a>, ParserError> {
+        let (id, principal) = self.0.split_first().ok_or(ParserError::parser_no_data)?;
+        let (_, recipient) = match TokenTranferPrincipal::from_u8(*id)? {
+            TokenTranferPrincipal::Standard => PrincipalData::standard_from_bytes(principal)?,
+            TokenTranferPrincipal::Contract => {
+                PrincipalData::contract_principal_from_bytes(principal)?
+            }
+        };
+        Ok(recipient)
+    }
+
+    pub fn raw_address(&self) -> &[u8] {
+        // Skips the principal-id and hash_mode
+        self.0.get(2..22).unwrap_or_default()
+    }
+
+    pub fn encoded_address(
+        &self,
+    ) -> Result<arrayvec::ArrayVec<[u8; C32_ENCODED_ADDRS_LENGTH]>, ParserError> {
+        // Skips the principal-id at [0] and uses hash_mode and the follow 20-bytes
+        let version = self.0.get(1).ok_or(ParserError::parser_no_data)?;
+        c32::c32_address(
+            *version,
+            self.0
+                .get(2..22)
+                .ok_or(ParserError::parser_invalid_address)?,
+        )
+    }
+
+    pub fn c32_address(&self) -> Result<C32Address, ParserError> {
+        let version = self.0.get(1).ok_or(ParserError::parser_no_data)?;
+        C32Address::new(
+            *version,
+            self.0
+                .get(2..22)
+                .ok_or(ParserError::parser_invalid_address)?,
+        )
+    }
+
+    /// Formats the amount into out, returning the part written
+    pub fn amount_stx<'b>(&self, out: &'b mut [u8]) -> Result<&'b [u8], ParserError> {
+        let amount = self.amount()?;
+        let len = zxformat::stx_amount_to_str(out, amount)?;
+        check_canary!();
+        let out: &'b [u8] = out;
+        out.get(..len)
+            .ok_or(ParserError::parser_unexpected_buffer_end)
+    }
+
+    fn get_token_transfer_items(
+        &self,
+        display_idx: u8,
+        out_key: &mut [u8],
+        out_value: &mut [u8],
+        page_idx: u8,
+    ) -> Result<u8, ParserError> {
+        let mut writer_key = zxformat::Writer::new(out_key);
+
+        match display_idx {
+            // Fomatting the amount in stx
+            0 => {
+                writer_key
+                    .write_str(zxformat::stx_label(LABELS.amount, LABELS.amount_stx))
+                    .map_err(|_| ParserError::parser_unexpected_buffer_end)?;
+                let mut buf = [0u8; zxformat::MAX_STR_BUFF_LEN];
+                let amount = self.amount_stx(&mut buf)?;
+                check_canary!();
+                zxformat::pageString(out_value, amount, page_idx)
+            }
+            // Recipient address
+            1 => {
+                writer_key
+                    .write_str(LABELS.recipient)
+                    .map_err(|_| ParserError::parser_unexpected_buffer_end)?;
+                let recipient = self.c32_address()?;
+                check_canary!();
+                recipient.page(out_value, page_idx)
+            }
+            2 => {
+                writer_key
+                    .write_str(LABELS.memo)
+                    .map_err(|_| ParserError::parser_unexpected_buffer_end)?;
+                check_canary!();
+                zxformat::pageString(out_value, self.memo(), page_idx)
+            }
+            // Caution for memos that mix text and control bytes
+            3 => {
+                writer_key
+                    .write_str(LABELS.caution)
+                    .map_err(|_| ParserError::parser_unexpected_buffer_end)?;
+                zxformat::pageString(out_value, LABELS.malformed_memo.as_bytes(), page_idx)
+            }
+            _ => Err(ParserError::parser_display_idx_out_of_range),
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, PartialEq)]
+#[cfg_attr(test, derive(Debug))]
+pub struct Arguments<'a>(&'a [u8]);
+
+impl<'a> Arguments<'a> {
+    #[inline(never)]
+    fn from_bytes(bytes: &'a [u8]) -> Result<(&[u8], Self), nom::Err<ParserError>> {
+        check_canary!();
+
+        let (_, num_args) = be_u32(bytes)?;
+
+        if num_args > MAX_NUM_ARGS && !is_expert_mode() {
+            return Err(ParserError::parser_limit_exceeded.into());
+        }
+        // walk the arguments to find where they end, what follows them is
+        // the tx.remainder, which has to be empty or the previous signer data
+        // of a multisig transaction, see Transaction::read
+        let mut leftover = bytes
+            .get(4..)
+            .ok_or(ParserError::parser_unexpected_buffer_end)?;
+        for _ in 0..num_args {
+            leftover = Value::from_bytes::<TX_DEPTH_LIMIT>(leftover)?.0;
+        }
+        let (raw, args) = take(bytes.len() - leftover.len())(bytes)?;
+        Ok((raw, Self(args)))
+    }
+
+    pub fn num_args(&self) -> Result<u32, ParserError> {
+        be_u32(self.0)
+            .map(|res| res.1)
+            .map_err(|_| ParserError::parser_unexpected_error)
+    }
+
+    pub fn argument_at(&self, at: usize) -> Result<Value<'a>, ParserError> {
+        check_canary!();
+
+        let mut idx = 0;
+        let num_args = self.num_args()?;
+
+        // skip the first 4-bytes
+        let mut leftover = self
+            .0
+            .get(4..)
+            .ok_or(ParserError::parser_unexpected_buffer_end)?;
+
+        while idx < num_args as usize {
+            let (bytes, value) = Value::from_bytes::<TX_DEPTH_LIMIT>(leftover)
+                .map_err(|_| ParserError::parser_invalid_argument_id)?;
+
+            leftover = bytes;
+            if idx == at {
+                return Ok(value);
+            }
+            idx += 1;
+        }
+        Err(ParserError::parser_display_idx_out_of_range)
+    }
+
+    /// Same as argument_at but starts walking from the closest argument
+    /// whose offset is already known, recording the offsets of the ones
+    /// walked so that displaying them again does not parse the previous ones
+    pub fn argument_at_cached(
+        &self,
+        at: usize,
+        cache: &Cell<ArgOffsets>,
+    ) -> Result<Value<'a>, ParserError> {
+        check_canary!();
+
+        let num_args = self.num_args()? as usize;
+        if at >= num_args {
+            return Err(ParserError::parser_display_idx_out_of_range);
+        }
+
+        // the first argument comes after the 4-byte number of arguments
+        let mut offsets = cache.get();
+        let (mut idx, mut offset) = match (offsets.known as usize).min(at + 1) {
+            0 => (0, 4),
+            known => {
+                let offset = offsets
+                    .offsets
+                    .get(known - 1)
+                    .ok_or(ParserError::parser_unexpected_error)?;
+                (known - 1, *offset as usize)
+            }
+        };
+
+        let mut leftover = self
+            .0
+            .get(offset..)
+            .ok_or(ParserError::parser_unexpected_buffer_end)?;
+
+        loop {
+            let (bytes, value) = Value::from_bytes::<TX_DEPTH_LIMIT>(leftover)
+                .map_err(|_| ParserError::parser_invalid_argument_id)?;
+
+            // in expert mode there might be more arguments than offsets
+            if idx == offsets.known as usize {
+                if let Some(slot) = offsets.offsets.get_mut(idx) {
+                    *slot = offset as u16;
+                    offsets.known += 1;
+                }
+            }
+
+            if idx == at {
+                cache.set(offsets);
+                return Ok(value);
+            }
+
+            offset += leftover.len() - bytes.len();
+            idx += 1;
+            leftover = bytes;
+        }
+    }
+}
+
+/// The byte offsets of the contract call arguments found so far
+#[derive(Clone, Copy, PartialEq, Default)]
+#[cfg_attr(test, derive(Debug))]
+pub struct ArgOffsets {
+    offsets: [u16; MAX_NUM_ARGS as usize],
+    known: u8,
+}
+
+/// A transaction that calls into a smart contract, along with the
+/// offsets of its arguments, the signature naming them, if provisioned,
+/// and the function called, if a recognizer knows it
+#[repr(C)]
+#[derive(Clone, PartialEq)]
+#[cfg_attr(test, derive(Debug))]
+pub struct TransactionContractCall<'a>(
+    &'a [u8],
+    Cell<ArgOffsets>,
+    Cell<Option<FunctionAbi<'a>>>,
+    Cell<Option<&'static KnownFunction>>,
+);
+
+impl<'a> TransactionContractCall<'a> {
+    #[inline(never)]
+    fn from_bytes(bytes: &'a [u8]) -> nom::IResult<&[u8], Self, ParserError> {
+        let (raw, _) = StacksAddress::from_bytes(bytes)?;
+        // get contract name and function name.
+        let (raw2, _) = tuple((ContractName::from_bytes, ClarityName::from_bytes_checked))(raw)?;
+        let (leftover, _) = Arguments::from_bytes(raw2)?;
+        let len = bytes.len() - leftover.len();
+        let (_, data) = take(len)(bytes)?;
+        check_canary!();
+        let call = Self(
+            data,
+            Cell::new(ArgOffsets::default()),
+            Cell::new(None),
+            Cell::new(None),
+        );
+        call.3.set(call.recognize().unwrap_or_default());
+        Ok((leftover, call))
+    }
+
+    pub fn address(&self) -> Result<StacksAddress<'a>, ParserError> {
+        StacksAddress::from_bytes(self.0)
+            .map(|(_, address)| address)
+            .map_err(|e| e.into())
+    }
+
+    // the contract name and what follows it
+    fn after_address(&self) -> Result<&'a [u8], ParserError> {
+        self.0
+            .get(HASH160_LEN + 1..)
+            .ok_or(ParserError::parser_unexpected_buffer_end)
+    }
+
+    pub fn contract_name(&self) -> Result<ContractName<'a>, ParserError> {
+        ContractName::from_bytes(self.after_address()?)
+            .map(|(_, name)| name)
+            .map_err(|e| e.into())
+    }
+
+    /// The serialized contract principal, the address
+    /// followed by the length prefixed contract name
+    pub fn contract_id(&self) -> Result<&'a [u8], ParserError> {
+        let (rem, _) = ContractName::from_bytes(self.after_address()?)?;
+        self.0
+            .get(..self.0.len() - rem.len())
+            .ok_or(ParserError::parser_unexpected_buffer_end)
+    }
+
+    pub fn function_name(&self) -> Result<&[u8], ParserError> {
+        ContractName::from_bytes(self.after_address()?)
+            .and_then(|b| ClarityName::from_bytes(b.0))
+            .map(|res| (res.1).0)
+            .map_err(|_| ParserError::parser_unexpected_error)
+    }
+
+    pub fn function_args(&self) -> Result<Arguments<'a>, ParserError> {
+        ContractName::from_bytes(self.after_address()?)
+            .and_then(|b| ClarityName::from_bytes(b.0))
+            .and_then(|c| Arguments::from_bytes(c.0))
+            .map(|res| res.1)
+            .map_err(|_| ParserError::parser_invalid_argument_id)
+    }
+
+    pub fn num_args(&self) -> Result<u32, ParserError> {
+        self.function_args().and_then(|args| args.num_args())
+    }
+
+    /// Names the arguments after abi if it is the signature of the function
+    /// called and every argument has the type it gives. Returns whether it is
+    pub fn set_abi(&self, abi: FunctionAbi<'a>) -> Result<bool, ParserError> {
+        self.2.set(None);
+
+        let args = self.function_args()?;
+        if abi.contract_id()? != self.contract_id()?
+            || abi.function_name()? != self.function_name()?
+            || abi.num_args()? as u32 != args.num_args()?
+        {
+            return Ok(false);
+        }
+
+        for at in 0..abi.num_args()? as usize {
+            let (ty, _) = abi.argument_at(at)?;
+            let value = args.argument_at_cached(at, &self.1)?;
+            if !FunctionAbi::admits(ty, value.value_id()?) {
+                return Ok(false);
+            }
+        }
+
+        self.2.set(Some(abi));
+        Ok(true)
+    }
+
+    // the name of the argument at arg_num, if a signature was set
+    fn argument_name(&self, arg_num: usize) -> Option<&'a str> {
+        let (_, name) = self.2.get()?.argument_at(arg_num).ok()?;
+        core::str::from_utf8(name).ok()
+    }
+
+    #[inline(never)]
+    pub fn contract_address(
+        &self,
+    ) -> Result<arrayvec::ArrayVec<[u8; C32_ENCODED_ADDRS_LENGTH]>, ParserError> {
+        let address = self.address()?;
+        c32::c32_address(address.0[0], &address.0[1..])
+    }
+
+    pub fn c32_contract_address(&self) -> Result<C32Address, ParserError> {
+        let address = self.address()?;
+        C32Address::new(address.0[0], &address.0[1..])
+    }
+
+    // Whether the contract called is one of the stacking boot contracts
+    fn is_pox_contract(&self) -> Result<bool, ParserError> {
+        if cfg!(not(feature = "pox")) {
+            return Ok(false);
+        }
+        let addr = self.contract_address()?;
+        let addr = addr.as_ref();
+        let contract_name = self.contract_name()?;
+        Ok((addr == "SP000000000000000000002Q6VF78".as_bytes()
+            || addr == "ST000000000000000000002AMW42H".as_bytes())
+            && matches!(
+                contract_name.name(),
+                b"pox" | b"pox-2" | b"pox-3" | b"pox-4"
+            ))
+    }
+
+    /// Whether the function called looks like an admin-style operation,
+    /// the review then shows a caution item after its name. The functions
+    /// a recognizer knows are not, like the mint of a vault
+    pub fn is_privileged(&self) -> bool {
+        cfg!(feature = "admin-functions")
+            && self.known_function().is_none()
+            && self
+                .function_name()
+                .map(is_privileged_name)
+                .unwrap_or_default()
+    }
+
+    // the items before the arguments, the caution one included
+    fn base_items(&self) -> u8 {
+        CONTRACT_CALL_BASE_ITEMS + self.is_privileged() as u8
+    }
+
+    // The function called if a recognizer knows it and every argument
+    // has the type it is shown as, see recognizers.rs
+    fn recognize(&self) -> Result<Option<&'static KnownFunction>, ParserError> {
+        let known = match recognizers::known_function(
+            self.contract_address()?.as_ref(),
+            self.contract_name()?.name(),
+            self.function_name()?,
+        ) {
+            Some(known) => known,
+            None => return Ok(None),
+        };
+
+        let args = self.function_args()?;
+        if args.num_args()? as usize != known.args.len() {
+            return Ok(None);
+        }
+        for (at, arg) in known.args.iter().enumerate() {
+            if let Some(arg) = arg {
+                if !arg.format.admits(&args.argument_at_cached(at, &self.1)?) {
+                    return Ok(None);
+                }
+            }
+        }
+        Ok(Some(known))
+    }
+
+    /// The function called, if a recognizer knows it. Its arguments are
+    /// then shown as the recognizer tells
+    pub fn known_function(&self) -> Option<&'static KnownFunction> {
+        self.3.get()
+    }
+
+    // how the recognizer shows the argument at arg_num, if it does
+    fn known_arg(&self, arg_num: usize) -> Option<&'static Arg> {
+        self.known_function()?.args.get(arg_num)?.as_ref()
+    }
+
+    // the items the argument at arg_num is shown in
+    fn arg_items(&self, arg_num: usize) -> Result<u8, ParserError> {
+        match self.known_arg(arg_num) {
+            Some(arg) => {
+                let value = self.function_args()?.argument_at_cached(arg_num, &self.1)?;
+                arg.format.num_items(&value)
+            }
+            None => Ok(1),
+        }
+    }
+
+    // The argument the item at display_idx shows, along with which of its
+    // items it is, the arguments of known functions can take several
+    fn arg_item(&self, display_idx: u8) -> Result<(usize, u8), ParserError> {
+        let mut idx = display_idx
+            .checked_sub(self.base_items())
+            .ok_or(ParserError::parser_display_idx_out_of_range)?;
+        if self.known_function().is_none() {
+            return Ok((idx as usize, 0));
+        }
+        for arg_num in 0..self.num_args()? as usize {
+            let items = self.arg_items(arg_num)?;
+            if idx < items {
+                return Ok((arg_num, idx));
+            }
+            idx -= items;
+        }
+        Err(ParserError::parser_display_idx_out_of_range)
+    }
+
+    // Returns the label for the amount argument if this is a stacking contract call
+    fn stacking_label(&self) -> Result<Option<&'static str>, ParserError> {
+        if self.is_pox_contract()? {
+            let name = self.function_name()?;
+            if name == "stack-stx".as_bytes() {
+                return Ok(Some(zxformat::stx_label(
+                    LABELS.stacked,
+                    LABELS.stacked_stx,
+                )));
+            } else if name == "delegate-stx".as_bytes() {
+                return Ok(Some(zxformat::stx_label(
+                    LABELS.delegated,
+                    LABELS.delegated_stx,
+                )));
+            }
+        }
+        Ok(None)
+    }
+
+    /// The Bitcoin address the rewards of a stack-stx call are paid to
+    pub fn reward_address(&self) -> Option<PoxAddress<'a>> {
+        if !self.is_pox_contract().ok()? || self.function_name().ok()? != b"stack-stx" {
+            return None;
+        }
+        let value = self
+            .function_args()
+            .and_then(|args| args.argument_at_cached(REWARD_ADDRESS_ARG, &self.1))
+            .ok()?;
+        PoxAddress::from_value(&value).ok()
+    }
+
+    // the reward address of a stack-stx call if it is argument arg_num
+    fn reward_address_at(&self, arg_num: usize) -> Option<PoxAddress<'a>> {
+        if arg_num != REWARD_ADDRESS_ARG {
+            return None;
+        }
+        self.reward_address()
+    }
+
+    // replaces the argument label
+    fn relabel(key: &mut [u8], label: &str) -> Result<(), ParserError> {
+        key.iter_mut().for_each(|v| *v = 0);
+        let mut writer = zxformat::Writer::new(key);
+        writer
+            .write_str(label)
+            .map_err(|_| ParserError::parser_unexpected_buffer_end)
+    }
+
+    // change label if it is a stacking contract call
+    fn label_stacking_value(&self, key: &mut [u8]) -> Result<(), ParserError> {
+        if let Some(label) = self.stacking_label()? {
+            Self::relabel(key, label)?;
+        }
+        Ok(())
+    }
+
+    fn item_kind(&self, display_idx: u8) -> ItemKind {
+        match display_idx {
+            0 => ItemKind::Address,
+            1 => ItemKind::ContractName,
+            2 => ItemKind::Text,
+            idx if idx < self.base_items() => ItemKind::Warning,
+            idx => {
+                let arg_num = match self.arg_item(idx) {
+                    Ok((arg_num, _)) => arg_num,
+                    Err(_) => return ItemKind::Text,
+                };
+                if let Some(arg) = self.known_arg(arg_num) {
+                    let value_id = self
+                        .function_args()
+                        .and_then(|args| args.argument_at_cached(arg_num, &self.1))
+                        .and_then(|value| value.value_id());
+                    return match arg.format {
+                        ArgFormat::Principal => ItemKind::Address,
+                        ArgFormat::OptionalPrincipal if value_id == Ok(ValueId::OptionalSome) => {
+                            ItemKind::Address
+                        }
+                        // any amount is accepted
+                        ArgFormat::Minimum(_) if value_id == Ok(ValueId::OptionalNone) => {
+                            ItemKind::Warning
+                        }
+                        format if format.is_amount() => ItemKind::Amount,
+                        _ => ItemKind::Text,
+                    };
+                }
+                let value_id = self
+                    .function_args()
+                    .and_then(|args| args.argument_at_cached(arg_num, &self.1))
+                    .and_then(|value| value.value_id());
+
+                match value_id {
+                    Ok(ValueId::StandardPrincipal) | Ok(ValueId::ContractPrincipal) => {
+                        ItemKind::Address
+                    }
+                    Ok(ValueId::Tuple) if self.reward_address_at(arg_num).is_some() => {
+                        ItemKind::Address
+                    }
+                    Ok(ValueId::UInt)
+                        if arg_num == 0 && matches!(self.stacking_label(), Ok(Some(_))) =>
+                    {
+                        ItemKind::Amount
+                    }
+                    _ => ItemKind::Text,
+                }
+            }
+        }
+    }
+
+    fn item_section(&self, display_idx: u8) -> ItemSection {
+        // the first argument of a stacking call is the amount being locked
+        if display_idx == self.base_items() && matches!(self.stacking_label(), Ok(Some(_))) {
+            return ItemSection::Amounts;
+        }
+        let known_arg = self
+            .arg_item(display_idx)
+            .ok()
+            .and_then(|(arg_num, _)| self.known_arg(arg_num));
+        if known_arg.is_some_and(|arg| arg.format.is_amount()) {
+            return ItemSection::Amounts;
+        }
+        ItemSection::Details
+    }
+
+    fn get_contract_call_args(
+        &self,
+        display_idx: u8,
+        out_key: &mut [u8],
+        out_value: &mut [u8],
+        page_idx: u8,
+        scratch: &Scratch,
+    ) -> Result<u8, ParserError> {
+        let (arg_num, item) = self.arg_item(display_idx)?;
+
+        let args = self.function_args()?;
+
+        let value = args.argument_at_cached(arg_num, &self.1)?;
+
+        if let Some(arg) = self.known_arg(arg_num) {
+            return self.get_known_arg(
+                arg, arg_num, &value, item, out_key, out_value, page_idx, scratch,
+            );
+        }
+
+        if let Some(name) = self.argument_name(arg_num) {
+            let mut writer_key = zxformat::Writer::new(out_key);
+            writer_key
+                .write_str(name)
+                .map_err(|_| ParserError::parser_unexpected_buffer_end)?;
+        } else {
+            let mut writer_key = zxformat::Writer::new(out_key);
+            let arg_num =
+                u8::try_from(arg_num).map_err(|_| ParserError::parser_display_idx_out_of_range)?;
+            let mut arg_num_buff = [0u8; 3];
+            let arg_num_str = arg_num.numtoa_str(10, &mut arg_num_buff);
+
+            writer_key
+                .write_str(LABELS.argument)
+                .map_err(|_| ParserError::parser_unexpected_buffer_end)?;
+            writer_key
+                .write_str(arg_num_str)
+                .map_err(|_| ParserError::parser_unexpected_buffer_end)?;
+        }
+
+        self.page_value(arg_num, &value, out_key, out_value, page_idx, scratch)
+    }
+
+    // Shows value as the argument at arg_num
+    fn page_value(
+        &self,
+        arg_num: usize,
+        value: &Value<'a>,
+        out_key: &mut [u8],
+        out_value: &mut [u8],
+        page_idx: u8,
+        scratch: &Scratch,
+    ) -> Result<u8, ParserError> {
+        // return the value content including the valueID
+        let payload = value.payload();
+
+        match value.value_id()? {
+            ValueId::Int => {
+                let value = value.int().ok_or(ParserError::parser_unexpected_error)?;
+                let buff = scratch.alloc(I128_STR_LEN)?;
+
+                zxformat::pageString(out_value, value.numtoa(10, buff), page_idx)
+            }
+            ValueId::UInt => {
+                let value = value.uint().ok_or(ParserError::parser_unexpected_error)?;
+                let buff = scratch.alloc(I128_STR_LEN)?;
+                let digits = value.numtoa(10, buff);
+
+                // the amount of a stacking call is shown as any other amount
+                if arg_num == 0 && self.stacking_label()?.is_some() {
+                    self.label_stacking_value(out_key)?;
+                    // the leading zero and the decimal point
+                    let amount = scratch.alloc(I128_STR_LEN + 2)?;
+                    let len = zxformat::stx_digits_to_str(amount, digits)?;
+                    let amount = amount
+                        .get(..len)
+                        .ok_or(ParserError::parser_unexpected_buffer_end)?;
+                    return zxformat::pageString(out_value, amount, page_idx);
+                }
+
+                zxformat::pageString(out_value, digits, page_idx)
+            }
+            ValueId::BoolTrue => {
+                zxformat::pageString(out_value, "is bool: true".as_bytes(), page_idx)
+            }
+            ValueId::BoolFalse => {
+                zxformat::pageString(out_value, "is bool: false".as_bytes(), page_idx)
+            }
+            ValueId::OptionalNone => {
+                zxformat::pageString(out_value, "is Option: None".as_bytes(), page_idx)
+            }
+            ValueId::OptionalSome => {
+                zxformat::pageString(out_value, "is Option: Some".as_bytes(), page_idx)
+            }
+            ValueId::ResponseOk => {
+                zxformat::pageString(out_value, "is Result: Ok".as_bytes(), page_idx)
+            }
+            ValueId::ResponseErr => {
+                zxformat::pageString(out_value, "is Result: Err".as_bytes(), page_idx)
+            }
+            ValueId::StandardPrincipal => {
+                let (_, principal) = PrincipalData::standard_from_bytes(payload)?;
+                let address = principal.c32_address()?;
+                address.page(out_value, page_idx)
+            }
+            ValueId::ContractPrincipal => {
+                let (_, principal) = PrincipalData::contract_principal_from_bytes(payload)?;
+                let address = principal.c32_address()?;
+
+                let contract_name = principal
+                    .contract_name()
+                    .ok_or(ParserError::parser_unexpected_type)?;
+
+                // principal_encoded address + '.' + contract_name
+                address.page_between(&[], &[b".", contract_name.name()], out_value, page_idx)
+            }
+            ValueId::Buffer => {
+                zxformat::pageString(out_value, LABELS.buffer_value.as_bytes(), page_idx)
+            }
+            ValueId::List => {
+                zxformat::pageString(out_value, LABELS.list_value.as_bytes(), page_idx)
+            }
+            ValueId::Tuple => match self.reward_address_at(arg_num) {
+                // shown as Bitcoin wallets show it, pox is deployed by an
+                // address of the network it runs on
+                Some(address) => {
+                    Self::relabel(out_key, LABELS.reward_address)?;
+                    let mainnet = self.address()?.0.first()
+                        == Some(&c32::C32_ADDRESS_VERSION_MAINNET_SINGLESIG);
+                    let encoded = address.encode(mainnet)?;
+                    zxformat::pageString(out_value, &encoded, page_idx)
+                }
+                None => zxformat::pageString(out_value, LABELS.tuple_value.as_bytes(), page_idx),
+            },
+            ValueId::StringAscii => {
+                // 4 bytes encode the length of the string
+                let len = if payload.len().saturating_sub(4) > MAX_STRING_ASCII_TO_SHOW {
+                    MAX_STRING_ASCII_TO_SHOW
+                } else {
+                    payload.len()
+                };
+                // omit the first 4-bytes as they are the string length
+                let string = payload
+                    .get(4..len)
+                    .ok_or(ParserError::parser_unexpected_value)?;
+                zxformat::pageString(out_value, string, page_idx)
+            }
+
+            ValueId::StringUtf8 => {
+                zxformat::pageString(out_value, "is StringUtf8".as_bytes(), page_idx)
+            }
+        }
+    }
+
+    // Shows item of the argument at arg_num as the recognizer tells
+    #[allow(clippy::too_many_arguments)]
+    fn get_known_arg(
+        &self,
+        arg: &Arg,
+        arg_num: usize,
+        value: &Value<'a>,
+        item: u8,
+        out_key: &mut [u8],
+        out_value: &mut [u8],
+        page_idx: u8,
+        scratch: &Scratch,
+    ) -> Result<u8, ParserError> {
+        let unit = zxformat::stx_label(LABELS.ustx_unit, LABELS.stx_unit);
+        let digits = |value: &Value| uint_digits(value, scratch);
+        let amount = |digits: &[u8], decimals: u8| decimal_str(digits, decimals, scratch);
+        let stx_decimals = zxformat::stx_decimals();
+
+        match arg.format {
+            ArgFormat::Principal | ArgFormat::Text => {
+                write_label(out_key, &[arg.label])?;
+                self.page_value(arg_num, value, out_key, out_value, page_idx, scratch)
+            }
+            ArgFormat::OptionalPrincipal => {
+                write_label(out_key, &[arg.label])?;
+                match recognizers::some_value(value) {
+                    Ok(principal) => {
+                        self.page_value(arg_num, &principal, out_key, out_value, page_idx, scratch)
+                    }
+                    Err(_) => {
+                        zxformat::pageString(out_value, LABELS.none_value.as_bytes(), page_idx)
+                    }
+                }
+            }
+            ArgFormat::Number => {
+                write_label(out_key, &[arg.label])?;
+                zxformat::pageString(out_value, digits(value)?, page_idx)
+            }
+            ArgFormat::RawUnits => {
+                write_label(out_key, &[arg.label, LABELS.raw_units])?;
+                zxformat::pageString(out_value, digits(value)?, page_idx)
+            }
+            ArgFormat::Token(decimals) => {
+                write_label(out_key, &[arg.label])?;
+                let amount = amount(digits(value)?, decimals)?;
+                zxformat::pageString(out_value, amount, page_idx)
+            }
+            ArgFormat::Minimum(decimals) => {
+                write_label(out_key, &[arg.label])?;
+                match recognizers::some_value(value) {
+                    Ok(minimum) => {
+                        let amount = amount(digits(&minimum)?, decimals)?;
+                        zxformat::pageString(out_value, amount, page_idx)
+                    }
+                    Err(_) => {
+                        zxformat::pageString(out_value, LABELS.no_minimum.as_bytes(), page_idx)
+                    }
+                }
+            }
+            ArgFormat::Ustx => {
+                write_label(out_key, &[arg.label, unit])?;
+                let amount = amount(digits(value)?, stx_decimals)?;
+                zxformat::pageString(out_value, amount, page_idx)
+            }
+            // the total of the amounts comes first
+            ArgFormat::UstxList(prefix) => match item.checked_sub(1) {
+                None => {
+                    write_label(out_key, &[arg.label, unit])?;
+                    let mut total = UstxTotal::default();
+                    for item in recognizers::list_items(value) {
+                        let item = item?.uint().ok_or(ParserError::parser_unexpected_type)?;
+                        let item = u64::try_from(item)
+                            .map_err(|_| ParserError::parser_unexpected_value)?;
+                        total = total.plus(item);
+                    }
+                    let written = scratch.alloc(I128_STR_LEN + 2)?;
+                    let len = total.write(written)?;
+                    let written = written
+                        .get(..len)
+                        .ok_or(ParserError::parser_unexpected_buffer_end)?;
+                    zxformat::pageString(out_value, written, page_idx)
+                }
+                Some(at) => {
+                    let mut at_buff = [0u8; 3];
+                    let at_str = at.numtoa_str(10, &mut at_buff);
+                    write_label(out_key, &[prefix, at_str, unit])?;
+                    let item = recognizers::list_items(value)
+                        .nth(at as usize)
+                        .ok_or(ParserError::parser_display_idx_out_of_range)??;
+                    let amount = amount(digits(&item)?, stx_decimals)?;
+                    zxformat::pageString(out_value, amount, page_idx)
+                }
+            },
+        }
+    }
+
+    pub fn num_items(&self) -> Result<u8, ParserError> {
+        // contract-address, contract-name, function-name,
+        // the caution for admin-style functions + the items of the arguments
+        let num_args =
+            u8::try_from(self.num_args()?).map_err(|_| ParserError::parser_limit_exceeded)?;
+        let arg_items = match self.known_function() {
+            Some(_) => (0..num_args as usize).try_fold(0u8, |items, arg_num| {
+                items
+                    .checked_add(self.arg_items(arg_num)?)
+                    .ok_or(ParserError::parser_limit_exceeded)
+            })?,
+            None => num_args,
+        };
+        arg_items
+            .checked_add(self.base_items())
+            .ok_or(ParserError::parser_limit_exceeded)
+    }
+
+    fn get_base_items(
+        &self,
+        display_idx: u8,
+        out_key: &mut [u8],
+        out_value: &mut [u8],
+        page_idx: u8,
+    ) -> Result<u8, ParserError> {
+        if display_idx >= self.base_items() {
+            return Err(ParserError::parser_display_idx_out_of_range);
+        }
+        let mut writer_key = zxformat::Writer::new(out_key);
+        match display_idx {
+            // Contract-address
+            0 => {
+                writer_key
+                    .write_str(LABELS.contract_address)
+                    .map_err(|_| ParserError::parser_unexpected_buffer_end)?;
+                let address = self.c32_contract_address()?;
+                check_canary!();
+                address.page(out_value, page_idx)
+            }
+            // Contract.name
+            1 => {
+                writer_key
+                    .write_str(LABELS.contract_name)
+                    .map_err(|_| ParserError::parser_unexpected_buffer_end)?;
+                let name = self.contract_name()?;
+                check_canary!();
+                zxformat::pageString(out_value, name.name(), page_idx)
+            }
+            // Function-name
+            2 => {
+                writer_key
+                    .write_str(LABELS.function_name)
+                    .map_err(|_| ParserError::parser_unexpected_buffer_end)?;
+                let name = self.function_name()?;
+                check_canary!();
+                zxformat::pageString(out_value, name, page_idx)
+            }
+            // Caution for admin-style functions
+            3 => {
+                writer_key
+                    .write_str(LABELS.caution)
+                    .map_err(|_| ParserError::parser_unexpected_buffer_end)?;
+                zxformat::pageString(out_value, LABELS.privileged_function.as_bytes(), page_idx)
+            }
+            _ => Err(ParserError::parser_display_idx_out_of_range),
+        }
+    }
+
+    fn get_contract_call_items(
+        &self,
+        display_idx: u8,
+        out_key: &mut [u8],
+        out_value: &mut [u8],
+        page_idx: u8,
+        scratch: &Scratch,
+    ) -> Result<u8, ParserError> {
+        // display_idx was already normalize
+        if display_idx < self.base_items() {
+            self.get_base_items(display_idx, out_key, out_value, page_idx)
+        } else {
+            self.get_contract_call_args(display_idx, out_key, out_value, page_idx, scratch)
+        }
+    }
+}
+
+// The Clarity version of the epoch both networks run, Nakamoto at the time of
+// writing. Older versions still deploy, but declaring one usually means the
+// deploy tool is outdated. The chain id only tells mainnet from testnet, not
+// the epoch, so this is bumped by the app release that follows a network
+// upgrade activating a new Clarity version on mainnet
+const CURRENT_CLARITY_VERSION: u8 = 3;
+// The latest version nodes deserialize, later ones are refused. It is bumped
+// as soon as a stacks-core release knows a new version, which can be before
+// mainnet activates it, so it can be ahead of CURRENT_CLARITY_VERSION
+const MAX_CLARITY_VERSION: u8 = 4;
+
+/// A transaction that instantiates a smart contract, along with the
+/// capabilities found in its code once it is scanned and the Clarity
+/// version of versioned deploys
+#[repr(C)]
+#[derive(Clone, PartialEq)]
+#[cfg_attr(test, derive(Debug))]
+pub struct TransactionSmartContract<'a>(&'a [u8], Cell<u8>, Option<u8>);
+
+impl<'a> TransactionSmartContract<'a> {
+    #[inline(never)]
+    fn from_bytes(bytes: &'a [u8]) -> nom::IResult<&[u8], Self, ParserError> {
+        check_canary!();
+        // the contract name and code length have to be there, the body
+        // might have been hashed and dropped if it did not fit the buffer
+        let (rem, _) = ContractName::from_bytes(bytes)?;
+        be_u32(rem)?;
+
+        // we take "ownership" of bytes here because
+        // it should only contain the contract information and body.
+        // NOTE: the remainder is taken from the end of bytes, as it is used
+        // to calculate the last_tx_block during the transaction signing process
+        let (raw, data) = take(bytes.len())(bytes)?;
+        Ok((raw, Self(data, Cell::new(0), None)))
+    }
+
+    /// Parses a versioned deploy, whose Clarity version comes first
+    #[inline(never)]
+    fn from_versioned_bytes(bytes: &'a [u8]) -> nom::IResult<&'a [u8], Self, ParserError> {
+        let (rem, version) = le_u8(bytes)?;
+        if !(1..=MAX_CLARITY_VERSION).contains(&version) {
+            return Err(ParserError::parser_invalid_transaction_payload.into());
+        }
+        let (raw, contract) = Self::from_bytes(rem)?;
+        Ok((raw, Self(contract.0, contract.1, Some(version))))
+    }
+
+    pub fn contract_name(&'a self) -> Result<ContractName<'a>, ParserError> {
+        ContractName::from_bytes(self.0)
+            .map(|(_, res)| res)
+            .map_err(|e| e.into())
+    }
+
+    pub fn raw(&self) -> &'a [u8] {
+        self.0
+    }
+
+    /// Offset at which the code body starts, after the contract name
+    /// and the code length
+    pub fn code_body_offset(&self) -> Result<usize, ParserError> {
+        let (rem, _) = ContractName::from_bytes(self.0)?;
+        let (rem, _) = be_u32(rem)?;
+        Ok(self.0.len() - rem.len())
+    }
+
+    /// Scans the code body for the expressions that move assets. scan is
+    /// the one of the whole body if part of it was hashed and dropped,
+    /// otherwise the body in the buffer is scanned
+    pub fn scan_code(&self, scan: Option<CodeScan>) -> Result<(), ParserError> {
+        let scan = match scan {
+            Some(scan) => scan,
+            None => {
+                let body = self
+                    .0
+                    .get(self.code_body_offset()?..)
+                    .ok_or(ParserError::parser_unexpected_buffer_end)?;
+                let mut scan = CodeScan::default();
+                scan.update(body);
+                scan
+            }
+        };
+        self.1.set(scan.capabilities());
+        Ok(())
+    }
+
+    /// What the code can do with assets, see code_scan
+    pub fn capabilities(&self) -> u8 {
+        self.1.get()
+    }
+
+    /// The Clarity version declared by a versioned deploy
+    pub fn clarity_version(&self) -> Option<u8> {
+        self.2
+    }
+
+    /// Whether the declared Clarity version is older than the one of
+    /// the epoch the networks run
+    pub fn is_old_clarity_version(&self) -> bool {
+        matches!(self.2, Some(version) if version < CURRENT_CLARITY_VERSION)
+    }
+
+    fn num_items(&self) -> u8 {
+        // the capabilities are only shown if any was found
+        1 + self.2.is_some() as u8 + (self.capabilities() != 0) as u8
+    }
+
+    fn item_kind(&self, display_idx: u8) -> ItemKind {
+        match display_idx {
+            0 => ItemKind::ContractName,
+            1 if self.is_old_clarity_version() => ItemKind::Warning,
+            _ => ItemKind::Text,
+        }
+    }
+
+    #[inline(never)]
+    fn get_contract_items(
+        &self,
+        display_idx: u8,
+        out_key: &mut [u8],
+        out_value: &mut [u8],
+        page_idx: u8,
+    ) -> Result<u8, ParserError> {
+        let mut writer_key = zxformat::Writer::new(out_key);
+
+        match display_idx {
+            0 => {
+                writer_key
+                    .write_str(LABELS.deploy_contract_name)
+                    .map_err(|_| ParserError::parser_unexpected_buffer_end)?;
+                check_canary!();
+                let name = self.contract_name()?;
+                zxformat::pageString(out_value, name.name(), page_idx)
+            }
+            1 if self.2.is_some() => {
+                let (key, note) = if self.is_old_clarity_version() {
+                    (LABELS.old_clarity_version, LABELS.old_clarity_version_msg)
+                } else {
+                    (LABELS.clarity_version, "")
+                };
+                writer_key
+                    .write_str(key)
+                    .map_err(|_| ParserError::parser_unexpected_buffer_end)?;
+                let mut digits = [0u8; 3];
+                let digits = self.2.unwrap_or_default().numtoa(10, &mut digits);
+                let note = note.as_bytes();
+                zxformat::pageWith(out_value, digits.len() + note.len(), page_idx, |pos| {
+                    let at = pos.checked_sub(digits.len());
+                    let value = match at {
+                        Some(at) => note.get(at),
+                        None => digits.get(pos),
+                    };
+                    value.copied().unwrap_or(0)
+                })
+            }
+            _ if self.capabilities() != 0 => {
+                writer_key
+                    .write_str(LABELS.capabilities)
+                    .map_err(|_| ParserError::parser_unexpected_buffer_end)?;
+                page_capabilities(self.capabilities(), out_value, page_idx)
+            }
+            _ => Err(ParserError::parser_value_out_of_range),
+        }
+    }
+}
+
+#[repr(u8)]
+#[derive(Clone, PartialEq, Copy)]
+#[cfg_attr(test, derive(Debug))]
+pub enum TransactionPayloadId {
+    TokenTransfer = 0,
+    SmartContract = 1,
+    ContractCall = 2,
+    VersionedSmartContract = 6,
+}
+
+impl TransactionPayloadId {
+    fn from_u8(v: u8) -> Result<Self, ParserError> {
+        match v {
+            0 => Ok(Self::TokenTransfer),
+            1 => Ok(Self::SmartContract),
+            2 => Ok(Self::ContractCall),
+            6 => Ok(Self::VersionedSmartContract),
+            _ => Err(ParserError::parser_invalid_transaction_payload),
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, PartialEq)]
+#[cfg_attr(test, derive(Debug))]
+pub enum TransactionPayload<'a> {
+    TokenTransfer(StxTokenTransfer<'a>),
+    SmartContract(TransactionSmartContract<'a>),
+    ContractCall(TransactionContractCall<'a>),
+}
+
+impl<'a> TransactionPayload<'a> {
+    #[inline(never)]
+    pub fn from_bytes(bytes: &'a [u8]) -> nom::IResult<&[u8], Self, ParserError> {
+        let id = le_u8(bytes)?;
+        let res = match TransactionPayloadId::from_u8(id.1)? {
+            TransactionPayloadId::TokenTransfer => {
+                let token = StxTokenTransfer::from_bytes(id.0)?;
+                (token.0, Self::TokenTransfer(token.1))
+            }
+            TransactionPayloadId::SmartContract => {
+                let contract = TransactionSmartContract::from_bytes(id.0)?;
+                (contract.0, Self::SmartContract(contract.1))
+            }
+            TransactionPayloadId::ContractCall => {
+                let call = TransactionContractCall::from_bytes(id.0)?;
+                (call.0, Self::ContractCall(call.1))
+            }
+            TransactionPayloadId::VersionedSmartContract => {
+                let contract = TransactionSmartContract::from_versioned_bytes(id.0)?;
+                (contract.0, Self::SmartContract(contract.1))
+            }
+        };
+        Ok(res)
+    }
+
+    pub fn id(&self) -> TransactionPayloadId {
+        match self {
+            Self::TokenTransfer(_) => TransactionPayloadId::TokenTransfer,
+            Self::SmartContract(contract) if contract.clarity_version().is_some() => {
+                TransactionPayloadId::VersionedSmartContract
+            }
+            Self::SmartContract(_) => TransactionPayloadId::SmartContract,
+            Self::ContractCall(_) => TransactionPayloadId::ContractCall,
+        }
+    }
+
+    pub fn is_token_transfer_payload(&self) -> bool {
+        matches!(self, &Self::TokenTransfer(_))
+    }
+
+    pub fn is_smart_contract_payload(&self) -> bool {
+        matches!(self, &Self::SmartContract(_))
+    }
+    pub fn is_contract_call_payload(&self) -> bool {
+        matches!(self, &Self::ContractCall(_))
+    }
+
+    pub fn contract_name(&'a self) -> Option<ContractName<'a>> {
+        match self {
+            Self::SmartContract(contract) => contract.contract_name().ok(),
+            Self::ContractCall(contract) => contract.contract_name().ok(),
+            _ => None,
+        }
+    }
+
+    pub fn function_name(&self) -> Option<&[u8]> {
+        match self {
+            Self::ContractCall(ref contract) => contract.function_name().ok(),
+            _ => None,
+        }
+    }
+
+    /// The serialized principal of the contract called
+    pub fn contract_id(&self) -> Option<&'a [u8]> {
+        match self {
+            Self::ContractCall(ref contract) => contract.contract_id().ok(),
+            _ => None,
+        }
+    }
+
+    /// The Bitcoin address PoX rewards are paid to, for stack-stx calls
+    pub fn reward_address(&self) -> Option<PoxAddress<'a>> {
+        match self {
+            Self::ContractCall(ref contract) => contract.reward_address(),
+            _ => None,
+        }
+    }
+
+    pub fn num_args(&self) -> Option<u32> {
+        match self {
+            Self::ContractCall(ref contract) => contract.num_args().ok(),
+            _ => None,
+        }
+    }
+
+    pub fn amount(&self) -> Option<u64> {
+        match self {
+            Self::TokenTransfer(ref token) => token.amount().ok(),
+            _ => None,
+        }
+    }
+
+    pub fn memo(&self) -> Option<&[u8]> {
+        match self {
+            Self::TokenTransfer(ref token) => Some(token.memo()),
+            _ => None,
+        }
+    }
+
+    pub fn recipient_address(&self) -> Option<arrayvec::ArrayVec<[u8; C32_ENCODED_ADDRS_LENGTH]>> {
+        match self {
+            Self::TokenTransfer(ref token) => token.encoded_address().ok(),
+            _ => None,
+        }
+    }
+    /// The hash160 of the token transfer recipient, for contract
+    /// principals this is the hash of the deployer
+    pub fn recipient_hash160(&self) -> Option<&[u8]> {
+        match self {
+            Self::TokenTransfer(ref token) => Some(token.raw_address()),
+            _ => None,
+        }
+    }
+
+    pub fn contract_address(&self) -> Option<arrayvec::ArrayVec<[u8; C32_ENCODED_ADDRS_LENGTH]>> {
+        match self {
+            Self::ContractCall(ref call) => call.contract_address().ok(),
+            _ => None,
+        }
+    }
+
+    /// Scans the code of a contract deploy, see TransactionSmartContract::scan_code
+    pub fn scan_code(&self, scan: Option<CodeScan>) -> Result<(), ParserError> {
+        match self {
+            Self::SmartContract(ref contract) => contract.scan_code(scan),
+            _ => Ok(()),
+        }
+    }
+
+    /// Names the arguments of a contract call after abi, see
+    /// TransactionContractCall::set_abi
+    pub fn set_abi(&self, abi: FunctionAbi<'a>) -> Result<bool, ParserError> {
+        match self {
+            Self::ContractCall(ref call) => call.set_abi(abi),
+            _ => Ok(false),
+        }
+    }
+
+    pub fn num_items(&self) -> Result<u8, ParserError> {
+        match self {
+            // the memo is the last item so it can be left out
+            Self::TokenTransfer(ref token) if token.has_empty_memo() && is_empty_memo_hidden() => {
+                Ok(2)
+            }
+            Self::TokenTransfer(ref token) => Ok(3 + token.has_malformed_memo() as u8),
+            Self::SmartContract(ref contract) => Ok(contract.num_items()),
+            Self::ContractCall(ref call) => call.num_items(),
+        }
+    }
+
+    /// Returns the kind of the payload item at display_idx,
+    /// the index is relative to the payload items
+    pub fn item_kind(&self, display_idx: u8) -> ItemKind {
+        match self {
+            Self::TokenTransfer(_) => match display_idx {
+                0 => ItemKind::Amount,
+                1 => ItemKind::Address,
+                2 => ItemKind::Memo,
+                _ => ItemKind::Warning,
+            },
+            Self::SmartContract(ref contract) => contract.item_kind(display_idx),
+            Self::ContractCall(ref call) => call.item_kind(display_idx),
+        }
+    }
+
+    /// Returns the section of the payload item at display_idx,
+    /// the index is relative to the payload items
+    pub fn item_section(&self, display_idx: u8) -> ItemSection {
+        match self {
+            Self::TokenTransfer(_) => match display_idx {
+                0 => ItemSection::Amounts,
+                1 => ItemSection::Recipient,
+                _ => ItemSection::Details,
+            },
+            Self::SmartContract(_) => ItemSection::Details,
+            Self::ContractCall(ref call) => call.item_section(display_idx),
+        }
+    }
+
+    pub fn get_items(
+        &self,
+        display_idx: u8,
+        out_key: &mut [u8],
+        out_value: &mut [u8],
+        page_idx: u8,
+        total_items: u8,
+        scratch: &Scratch,
+    ) -> Result<u8, ParserError> {
+        let num_items = self.num_items()?;
+        let idx = total_items
+            .checked_sub(display_idx)
+            .and_then(|from_end| num_items.checked_sub(from_end))
+            .ok_or(ParserError::parser_display_idx_out_of_range)?;
+        match self {
+            Self::TokenTransfer(ref token) => {
+                token.get_token_transfer_items(idx, out_key, out_value, page_idx)
+            }
+            Self::SmartContract(ref contract) => {
+                contract.get_contract_items(idx, out_key, out_value, page_idx)
+            }
+            Self::ContractCall(ref call) => {
+                call.get_contract_call_items(idx, out_key, out_value, page_idx, scratch)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::prelude::v1::*;
+
+    #[test]
+    fn test_privileged_function_names() {
+        for name in &["mint", "mint-many", "set-owner", "batch-mint", "upgrade-to"] {
+            assert!(is_privileged_name(name.as_bytes()));
+        }
+        for name in &["transfer", "minter", "get-owner", "set-owners", "stack-stx"] {
+            assert!(!is_privileged_name(name.as_bytes()));
+        }
+    }
+
+    #[test]
+    fn test_transaction_payload_tokens() {
+        let bytes: Vec<u8> = vec![
+            0, 5, 1, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255,
+            255, 255, 255, 255, 255, 0, 0, 0, 0, 0, 0, 0, 123, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        ];
+
+        let parsed = TransactionPayload::from_bytes(&bytes).unwrap().1;
+        assert_eq!(parsed.amount(), Some(123));
+
+        assert!(matches!(
+            parsed,
+            TransactionPayload::TokenTransfer(ref token) if token.has_empty_memo()
+        ));
+    }
+
+    #[test]
+    fn test_token_transfer_memo() {
+        let payload = |memo: &[u8]| {
+            let mut bytes = vec![0, 5, 22];
+            bytes.extend_from_slice(&[0x5a; HASH160_LEN]);
+            bytes.extend_from_slice(&123u64.to_be_bytes());
+            bytes.extend_from_slice(memo);
+            bytes.resize(bytes.len() + TOKEN_TRANSFER_MEMO_LEN - memo.len(), 0);
+            bytes
+        };
+        let malformed = |memo: &[u8]| {
+            let bytes = payload(memo);
+            let parsed = TransactionPayload::from_bytes(&bytes).unwrap().1;
+            matches!(
+                parsed,
+                TransactionPayload::TokenTransfer(ref token) if token.has_malformed_memo()
+            )
+        };
+
+        // text, binary ids and the empty memo are shown as they are
+        assert!(!malformed(b""));
+        assert!(!malformed(b"invoice 42"));
+        assert!(!malformed(&[0x01, 0x02, 0x9f, 0x10]));
+        // a length prefix or a nul in the middle of text is flagged
+        assert!(malformed(b"\x0ainvoice 42"));
+        assert!(malformed(b"invoice\x0042"));
+
+        let bytes = payload(b"\x0ainvoice 42");
+        let parsed = TransactionPayload::from_bytes(&bytes).unwrap().1;
+        assert_eq!(parsed.num_items().unwrap(), 4);
+        assert_eq!(parsed.item_kind(2), ItemKind::Memo);
+        assert_eq!(parsed.item_kind(3), ItemKind::Warning);
+
+        // the memo is exactly TOKEN_TRANSFER_MEMO_LEN bytes
+        let bytes = payload(b"");
+        assert!(TransactionPayload::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+    }
+}
@@ -1,13 +1,10 @@
 use core::convert::TryFrom;
 
-use nom::{
-    bytes::complete::take,
-    number::complete::{be_u32, le_u8},
-};
+use super::reader::{be_u32, le_u8, take};
 
 use crate::{bolos::c_zemu_log_stack, check_canary};
 
-use super::{ClarityName, ContractPrincipal, ParserError, StandardPrincipal};
+use super::{is_clarity_name, ClarityName, ContractPrincipal, ParserError, StandardPrincipal};
 
 // Big ints size in bytes
 pub const BIG_INT_SIZE: usize = core::mem::size_of::<u128>();
@@ -73,16 +70,18 @@ impl<'a> Value<'a> {
         take(len)(bytes).map(|(rem, v)| (rem, Self(v)))
     }
 
-    pub fn value_id(&self) -> ValueId {
-        // should not panic as Value was already parsed
-        ValueId::try_from(self.0[0]).unwrap()
+    pub fn value_id(&self) -> Result<ValueId, ParserError> {
+        let id = self
+            .0
+            .first()
+            .ok_or(ParserError::parser_unexpected_buffer_end)?;
+        ValueId::try_from(*id)
     }
 
     // returns the bytes that represent the Value data
     // removing the value_id which is the first byte
     pub fn payload(&self) -> &'a [u8] {
-        // wont panic as Value was already parsed
-        &self.0[1..]
+        self.0.get(1..).unwrap_or_default()
     }
 
     // return all bytes this value holds including the value_id
@@ -126,8 +125,10 @@ impl<'a> Value<'a> {
     ) -> Result<usize, nom::Err<ParserError>> {
         Self::check_recursion_limit::<MAX_DEPTH>(*depth)?;
 
+        // an empty item would let a list or tuple claim up to u32::MAX items
+        // without consuming any byte
         if bytes.is_empty() {
-            return Ok(0);
+            return Err(ParserError::parser_unexpected_buffer_end.into());
         }
 
         // get value_id
@@ -191,6 +192,12 @@ impl<'a> Value<'a> {
             let (rem, key) = ClarityName::read_as_bytes(remain)?;
             let key_len = key.len();
 
+            // keys label the fields they are shown with, one out of the
+            // Clarity grammar could pass for another key or hide the path
+            if !is_clarity_name(key.get(1..).unwrap_or_default()) {
+                return Err(ParserError::parser_invalid_clarity_name.into());
+            }
+
             // check for a nested tuple
             if let Ok(true) = ValueId::from_bytes(rem).map(|(_, id)| id == ValueId::Tuple) {
                 *depth += 1;
@@ -265,42 +272,77 @@ mod test {
         let encoded = "0c0000000201610000000000000000000000000000000001016303";
         let bytes = hex::decode(encoded).unwrap();
         let (_, value) = Value::from_bytes::<10>(&bytes).unwrap();
-        assert!(matches!(value.value_id(), ValueId::Tuple));
+        assert_eq!(value.value_id(), Ok(ValueId::Tuple));
         assert_eq!(bytes.len(), value.0.len());
 
         let encoded2 = "0c0000000d016100000000000000000000000000000000010162000000000000000000000000000000000101630000000000000000000000000000000001016400000000000000000000000000000000010165000000000000000000000000000000000101660000000000000000000000000000000001016700000000000000000000000000000000010168000000000000000000000000000000000101690000000000000000000000000000000001016a0000000000000000000000000000000001016b00000000000000000000000000000000010171000000000000000000000000000000000101760000000000000000000000000000000001";
         let bytes = hex::decode(encoded2).unwrap();
         let (_, value) = Value::from_bytes::<10>(&bytes).unwrap();
-        assert!(matches!(value.value_id(), ValueId::Tuple));
+        assert_eq!(value.value_id(), Ok(ValueId::Tuple));
         assert_eq!(bytes.len(), value.0.len());
 
         // Tuple containing 3 tuples inside
         let encoded3 = "0c0000000401610c00000002016100000000000000000000000000000000010162000000000000000000000000000000000101620c000000020161000000000000000000000000000000000101620301630c000000020161000000000000000000000000000000000101620301760000000000000000000000000000000001";
         let bytes = hex::decode(encoded3).unwrap();
         let (_, value) = Value::from_bytes::<10>(&bytes).unwrap();
-        assert!(matches!(value.value_id(), ValueId::Tuple));
+        assert_eq!(value.value_id(), Ok(ValueId::Tuple));
         assert_eq!(bytes.len(), value.0.len());
     }
 
+    #[test]
+    fn test_tuple_key_names() {
+        // a tuple with one key set to true
+        let tuple = |key: &[u8]| {
+            let mut bytes = std::vec![0x0c, 0, 0, 0, 1, key.len() as u8];
+            bytes.extend_from_slice(key);
+            bytes.push(0x03);
+            bytes
+        };
+
+        for key in [&b"amount"[..], b"is-owner?", b"+"] {
+            let bytes = tuple(key);
+            assert!(Value::from_bytes::<10>(&bytes).is_ok());
+        }
+
+        // empty, with a control byte, the cyrillic a or a right-to-left override
+        for key in [
+            &b""[..],
+            b"to\n",
+            "\u{430}mount".as_bytes(),
+            "a\u{202e}b".as_bytes(),
+        ] {
+            let bytes = tuple(key);
+            assert_eq!(
+                Value::from_bytes::<10>(&bytes).map(|_| ()),
+                Err(nom::Err::Error(ParserError::parser_invalid_clarity_name))
+            );
+        }
+    }
+
     #[test]
     fn test_list_value() {
         // simple list with 3-ints
         let encoded = "0b00000003000000000000000000000000000000000100000000000000000000000000000000020000000000000000000000000000000003";
         let bytes = hex::decode(encoded).unwrap();
         let (_, value) = Value::from_bytes::<10>(&bytes).unwrap();
-        assert!(matches!(value.value_id(), ValueId::List));
+        assert_eq!(value.value_id(), Ok(ValueId::List));
         assert_eq!(bytes.len(), value.0.len());
 
         let three_nested_list = "0b000000030b000000030000000000000000000000000000000001000000000000000000000000000000000200000000000000000000000000000000030b000000030000000000000000000000000000000001000000000000000000000000000000000200000000000000000000000000000000030b00000003000000000000000000000000000000000100000000000000000000000000000000020000000000000000000000000000000003";
         let bytes = hex::decode(three_nested_list).unwrap();
         let (_, value) = Value::from_bytes::<10>(&bytes).unwrap();
-        assert!(matches!(value.value_id(), ValueId::List));
+        assert_eq!(value.value_id(), Ok(ValueId::List));
         assert_eq!(bytes.len(), value.0.len());
 
         let four_nested_list = "0b000000040b000000030000000000000000000000000000000001000000000000000000000000000000000200000000000000000000000000000000030b000000030000000000000000000000000000000001000000000000000000000000000000000200000000000000000000000000000000030b000000030000000000000000000000000000000001000000000000000000000000000000000200000000000000000000000000000000030b00000003000000000000000000000000000000000100000000000000000000000000000000020000000000000000000000000000000003";
         let bytes = hex::decode(four_nested_list).unwrap();
         // should fail, too depth value(4-recursion levels)
         Value::value_len::<2>(&bytes).unwrap_err();
+
+        // claims more items than the bytes left
+        let truncated = "0b030b000003";
+        let bytes = hex::decode(truncated).unwrap();
+        Value::value_len::<10>(&bytes).unwrap_err();
     }
 
     #[test]
@@ -310,13 +352,13 @@ mod test {
             "0b000000030a000000000000000000000000000000000f090a000000000000000000000000000000000f";
         let bytes = hex::decode(encoded).unwrap();
         let (_, value) = Value::from_bytes::<10>(&bytes).unwrap();
-        assert!(matches!(value.value_id(), ValueId::List));
+        assert_eq!(value.value_id(), Ok(ValueId::List));
         assert_eq!(bytes.len(), value.0.len());
 
         let three_depth = "0a0a0a0100000000000000000000000000000001";
         let bytes = hex::decode(three_depth).unwrap();
         let (_, value) = Value::from_bytes::<10>(&bytes).unwrap();
-        assert!(matches!(value.value_id(), ValueId::OptionalSome));
+        assert_eq!(value.value_id(), Ok(ValueId::OptionalSome));
         assert_eq!(bytes.len(), value.0.len());
 
         let five_depth = "0a0a0a0a0a0100000000000000000000000000000001";
@@ -331,7 +373,7 @@ mod test {
         let encoded = "020000001600deadbeef00080919558081fa240400010204080907";
         let bytes = hex::decode(encoded).unwrap();
         let (_, value) = Value::from_bytes::<10>(&bytes).unwrap();
-        assert!(matches!(value.value_id(), ValueId::Buffer));
+        assert_eq!(value.value_id(), Ok(ValueId::Buffer));
         assert_eq!(bytes.len(), value.0.len());
     }
 
@@ -341,7 +383,7 @@ mod test {
         let encoded = "0d0000006d31323334353637383930717766706261727374677a786364767a603c3637383930302d6a6c75793b6d6e656f6b682c2e2f6f6b682c2e2f3e3f7b7d7b7d5b5d5b5d313233343561727374677a78636476617172667374676e65696f613b7975657374726569616f697265736864727374677a78636476617172667374676e65696f613b7975657374726569616f697265736864";
         let bytes = hex::decode(encoded).unwrap();
         let (_, value) = Value::from_bytes::<10>(&bytes).unwrap();
-        assert!(matches!(value.value_id(), ValueId::StringAscii));
+        assert_eq!(value.value_id(), Ok(ValueId::StringAscii));
     }
 
     #[test]
@@ -350,6 +392,17 @@ mod test {
         let encoded = "0e0000002d436f6e73696465722074686520656e636f64696e67206f6620746865206575726f207369676e2c20e282ac3a20";
         let bytes = hex::decode(encoded).unwrap();
         let (_, value) = Value::from_bytes::<10>(&bytes).unwrap();
-        assert!(matches!(value.value_id(), ValueId::StringUtf8));
+        assert_eq!(value.value_id(), Ok(ValueId::StringUtf8));
+    }
+
+    #[test]
+    fn test_empty_value() {
+        let value = Value(&[]);
+        assert_eq!(
+            value.value_id(),
+            Err(ParserError::parser_unexpected_buffer_end)
+        );
+        assert!(value.payload().is_empty());
+        assert!(value.tuple().is_none());
     }
 }
@@ -1,6 +1,5 @@
-use core::convert::TryFrom;
 
-use nom::number::complete::{be_i128, be_u128};
+use crate::parser::reader::{be_i128, be_u128};
 
 use crate::parser::error::ParserError;
 
@@ -29,7 +28,7 @@ pub struct UInt128(u128);
 
 impl Int128 {
     pub(crate) fn new(value: &super::Value) -> Result<Self, ParserError> {
-        if !matches!(value.value_id(), ValueId::Int) {
+        if value.value_id()? != ValueId::Int {
             return Err(ParserError::parser_unexpected_type.into());
         }
 
@@ -44,12 +43,13 @@ impl Int128 {
             return Err(ParserError::parser_unexpected_buffer_end.into());
         }
 
-        if !matches!(ValueId::try_from(data[0])?, ValueId::Int) {
+        let (rem, id) = ValueId::from_bytes(data)?;
+        if id != ValueId::Int {
             return Err(ParserError::parser_unexpected_type.into());
         }
 
         // check the number is parsed
-        let (rem, n) = be_i128(&data[1..])?;
+        let (rem, n) = be_i128(rem)?;
         Ok((rem, Self(n)))
     }
 
@@ -60,7 +60,7 @@ impl Int128 {
 
 impl UInt128 {
     pub(crate) fn new(value: &super::Value) -> Result<Self, ParserError> {
-        if !matches!(value.value_id(), ValueId::UInt) {
+        if value.value_id()? != ValueId::UInt {
             return Err(ParserError::parser_unexpected_type.into());
         }
 
@@ -75,12 +75,13 @@ impl UInt128 {
             return Err(ParserError::parser_unexpected_buffer_end.into());
         }
 
-        if !matches!(ValueId::try_from(data[0])?, ValueId::UInt) {
+        let (rem, id) = ValueId::from_bytes(data)?;
+        if id != ValueId::UInt {
             return Err(ParserError::parser_unexpected_type.into());
         }
 
         // check the number is parsed
-        let (rem, n) = be_u128(&data[1..])?;
+        let (rem, n) = be_u128(rem)?;
         Ok((rem, Self(n)))
     }
 
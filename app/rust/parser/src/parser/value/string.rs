@@ -1,5 +1,5 @@
 use super::{ParserError, Value, ValueId};
-use nom::{bytes::complete::take, number::complete::be_u32};
+use crate::parser::reader::{be_u32, take};
 
 // Represent a clarity value string
 #[repr(C)]
@@ -55,7 +55,7 @@ impl<'a> String<'a> {
 
 impl<'a> StringAscii<'a> {
     pub(crate) fn new(value: &Value<'a>) -> Result<StringAscii<'a>, ParserError> {
-        if !matches!(value.value_id(), ValueId::StringAscii) {
+        if value.value_id()? != ValueId::StringAscii {
             return Err(ParserError::parser_unexpected_type.into());
         }
 
@@ -88,7 +88,7 @@ impl<'a> StringAscii<'a> {
 
 impl<'a> StringUtf8<'a> {
     pub(crate) fn new(value: &Value<'a>) -> Result<Self, ParserError> {
-        if !matches!(value.value_id(), ValueId::StringUtf8) {
+        if value.value_id()? != ValueId::StringUtf8 {
             return Err(ParserError::parser_unexpected_type.into());
         }
 
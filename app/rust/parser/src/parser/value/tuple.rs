@@ -1,5 +1,5 @@
+use crate::parser::reader::be_u32;
 use crate::parser::{ClarityName, ParserError, Value, ValueId, MAX_DEPTH};
-use nom::number::complete::be_u32;
 
 // This type is meant to get from the Value type
 #[repr(C)]
@@ -11,7 +11,7 @@ impl<'a> Tuple<'a> {
     // Takes in a Value type containing a full-parsed clarity tuple,
     // That is wrapped-up here to better access/handle tuple fields/operations
     pub(crate) fn new(value: &'a Value) -> Result<Tuple<'a>, ParserError> {
-        if !matches!(value.value_id(), ValueId::Tuple) {
+        if value.value_id()? != ValueId::Tuple {
             return Err(ParserError::parser_unexpected_type);
         }
 
@@ -19,17 +19,15 @@ impl<'a> Tuple<'a> {
         Ok(Self(value.payload()))
     }
 
-    pub fn num_elements(&self) -> usize {
-        // This wont panic as this type was already parsed.
-        // and is wrapped-up here to better access its fields.
-        be_u32::<ParserError>(self.0)
+    pub fn num_elements(&self) -> Result<usize, ParserError> {
+        be_u32(self.0)
             .map(|(_, len)| len as usize)
-            .unwrap()
+            .map_err(|e| e.into())
     }
 
     // Skip the bytes that indicates the number of elements in this tuple
     pub fn payload(&'a self) -> &'a [u8] {
-        &self.0[4..]
+        self.0.get(4..).unwrap_or_default()
     }
 
     // Returns an iterator over Tuple items which consists of:
@@ -54,17 +52,16 @@ impl<'a> Iterator for TupleIter<'a> {
     type Item = (ClarityName<'a>, Value<'a>);
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.read < self.data.len() {
-            // We unwrap here as all the inner fields of this tuple were already parsed
-            let (rem, name) = ClarityName::from_bytes(&self.data[self.read..]).unwrap();
-            // limit recursion to MAX_DEPTH
-            let (rem, value) = Value::from_bytes::<MAX_DEPTH>(rem).unwrap();
-            self.read = self.data.len() - rem.len();
-
-            Some((name, value))
-        } else {
-            None
-        }
+        let data = self.data.get(self.read..).filter(|data| !data.is_empty())?;
+
+        // the fields were parsed with the tuple, a field that fails to parse
+        // again ends the iteration
+        let (rem, name) = ClarityName::from_bytes(data).ok()?;
+        // limit recursion to MAX_DEPTH
+        let (rem, value) = Value::from_bytes::<MAX_DEPTH>(rem).ok()?;
+        self.read = self.data.len() - rem.len();
+
+        Some((name, value))
     }
 }
 
@@ -97,7 +94,7 @@ mod test {
             assert!(names.contains(&name.name().to_vec()));
         }
 
-        assert_eq!(count, tuple.num_elements());
+        assert_eq!(count, tuple.num_elements().unwrap());
     }
 
     #[test]
@@ -120,6 +117,6 @@ mod test {
             count += 1;
             assert!(names.contains(&name.name().to_vec()));
         }
-        assert_eq!(count, tuple.num_elements());
+        assert_eq!(count, tuple.num_elements().unwrap());
     }
 }
@@ -0,0 +1,30 @@
+//! The json vectors in tests/, shared by the test modules that
+//! run over the whole corpus.
+
+use std::path::PathBuf;
+use std::string::String;
+use std::vec::Vec;
+
+// the raw transactions of the json vectors in tests/
+pub(super) fn transaction_vectors() -> Vec<(String, Vec<u8>)> {
+    let mut dir = PathBuf::new();
+    dir.push(env!("CARGO_MANIFEST_DIR"));
+    dir.push("tests");
+
+    let mut vectors: Vec<_> = std::fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .map(|path| {
+            let str = std::fs::read_to_string(&path).expect("Error opening json file");
+            let json: serde_json::Value = serde_json::from_str(&str).unwrap();
+            let raw = hex::decode(json["raw"].as_str().unwrap()).unwrap();
+            (
+                path.file_stem().unwrap().to_string_lossy().into_owned(),
+                raw,
+            )
+        })
+        .collect();
+    vectors.sort();
+    vectors
+}
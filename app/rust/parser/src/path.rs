@@ -0,0 +1,419 @@
+//! Key derivation paths requested by the host.
+//!
+//! The host sends a path as its levels, each a little endian u32 with the
+//! hardened ones including HARDENED. Keys are only derived at the paths
+//! allowed by the policies of what they are requested for, see POLICIES.
+//!
+//! Wallets derive Stacks accounts at `m/44'/5757'/account'/0/index`, or
+//! `m/44'/1'/account'/0/index` on testnet. Other account paths are still
+//! derived, but most often come from a host bug, and funds sent to them
+//! are hard to find again, so the review warns about them.
+
+use core::convert::{TryFrom, TryInto};
+
+use crate::parser::ParserError;
+
+pub const HARDENED: u32 = 0x8000_0000;
+
+pub const PURPOSE_BIP44: u32 = 44 | HARDENED;
+pub const PURPOSE_BIP84: u32 = 84 | HARDENED;
+pub const PURPOSE_BIP86: u32 = 86 | HARDENED;
+pub const PURPOSE_IDENTITY: u32 = 888 | HARDENED;
+pub const COIN_STX: u32 = 5757 | HARDENED;
+pub const COIN_TESTNET: u32 = 1 | HARDENED;
+pub const COIN_BTC: u32 = HARDENED;
+
+/// Deepest path any policy can allow
+pub const MAX_PATH_LEN: usize = 10;
+
+const LEVEL_LEN: usize = 4;
+
+/// Change level of the standard paths, receiving addresses only
+const CHANGE_EXTERNAL: u32 = 0;
+
+/// What the key is requested for, each has its own paths
+#[repr(u8)]
+#[derive(Clone, Copy, PartialEq)]
+#[cfg_attr(test, derive(Debug))]
+pub enum PathKind {
+    /// Addresses and transaction signing
+    Account = 0,
+    /// Authentication and profile signing
+    Identity = 1,
+    /// Bitcoin addresses of PoX rewards, see crate::btc
+    Bitcoin = 2,
+    /// BIP-340 signing, see crate::signature::schnorr
+    Schnorr = 3,
+}
+
+impl TryFrom<u8> for PathKind {
+    type Error = ParserError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Account),
+            1 => Ok(Self::Identity),
+            2 => Ok(Self::Bitcoin),
+            3 => Ok(Self::Schnorr),
+            _ => Err(ParserError::parser_unexpected_value),
+        }
+    }
+}
+
+/// A family of paths keys of a kind can be derived at
+struct PathPolicy {
+    kind: PathKind,
+    /// the first levels of the path
+    prefix: &'static [u32],
+    min_len: usize,
+    max_len: usize,
+    /// the levels that have to be hardened, bit i for level i
+    hardened: u16,
+}
+
+impl PathPolicy {
+    fn must_be_hardened(&self, level: usize) -> bool {
+        let level = u32::try_from(level).unwrap_or(u32::MAX);
+        self.hardened.checked_shr(level).unwrap_or(0) & 1 == 1
+    }
+
+    fn allows(&self, kind: PathKind, path: &[u32]) -> bool {
+        self.kind == kind
+            && (self.min_len..=self.max_len).contains(&path.len())
+            && path.starts_with(self.prefix)
+            && path
+                .iter()
+                .enumerate()
+                .all(|(level, index)| !self.must_be_hardened(level) || is_hardened(*index))
+    }
+}
+
+/// Paths are refused unless one of these allows them, a new family of
+/// paths is a new entry here
+const POLICIES: [PathPolicy; 12] = [
+    // m/44'/5757'/account/change/index
+    PathPolicy {
+        kind: PathKind::Account,
+        prefix: &[PURPOSE_BIP44, COIN_STX],
+        min_len: 5,
+        max_len: 5,
+        hardened: 0,
+    },
+    // m/44'/1'/account/change/index
+    PathPolicy {
+        kind: PathKind::Account,
+        prefix: &[PURPOSE_BIP44, COIN_TESTNET],
+        min_len: 5,
+        max_len: 5,
+        hardened: 0,
+    },
+    // m/5757'/..., the scheme of early wallets
+    PathPolicy {
+        kind: PathKind::Account,
+        prefix: &[COIN_STX],
+        min_len: 5,
+        max_len: 5,
+        hardened: 0,
+    },
+    // m/888'/0'/identity, the keys of legacy authentication and profiles,
+    // hosts send the identity index both hardened and not
+    PathPolicy {
+        kind: PathKind::Identity,
+        prefix: &[PURPOSE_IDENTITY, HARDENED],
+        min_len: 3,
+        max_len: 3,
+        hardened: 0,
+    },
+    // m/44'/0'/account'/change/index, P2PKH Bitcoin addresses
+    PathPolicy {
+        kind: PathKind::Bitcoin,
+        prefix: &[PURPOSE_BIP44, COIN_BTC],
+        min_len: 5,
+        max_len: 5,
+        hardened: 0b111,
+    },
+    // m/44'/1'/account'/change/index, P2PKH Bitcoin addresses on testnet
+    PathPolicy {
+        kind: PathKind::Bitcoin,
+        prefix: &[PURPOSE_BIP44, COIN_TESTNET],
+        min_len: 5,
+        max_len: 5,
+        hardened: 0b111,
+    },
+    // m/84'/0'/account'/change/index, P2WPKH Bitcoin addresses
+    PathPolicy {
+        kind: PathKind::Bitcoin,
+        prefix: &[PURPOSE_BIP84, COIN_BTC],
+        min_len: 5,
+        max_len: 5,
+        hardened: 0b111,
+    },
+    // m/84'/1'/account'/change/index, P2WPKH Bitcoin addresses on testnet
+    PathPolicy {
+        kind: PathKind::Bitcoin,
+        prefix: &[PURPOSE_BIP84, COIN_TESTNET],
+        min_len: 5,
+        max_len: 5,
+        hardened: 0b111,
+    },
+    // m/44'/5757'/account/change/index, Stacks keys with Schnorr duties,
+    // like the ones of sBTC signers
+    PathPolicy {
+        kind: PathKind::Schnorr,
+        prefix: &[PURPOSE_BIP44, COIN_STX],
+        min_len: 5,
+        max_len: 5,
+        hardened: 0,
+    },
+    // m/44'/1'/account/change/index, the same on testnet
+    PathPolicy {
+        kind: PathKind::Schnorr,
+        prefix: &[PURPOSE_BIP44, COIN_TESTNET],
+        min_len: 5,
+        max_len: 5,
+        hardened: 0,
+    },
+    // m/86'/0'/account'/change/index, Taproot keys
+    PathPolicy {
+        kind: PathKind::Schnorr,
+        prefix: &[PURPOSE_BIP86, COIN_BTC],
+        min_len: 5,
+        max_len: 5,
+        hardened: 0b111,
+    },
+    // m/86'/1'/account'/change/index, Taproot keys on testnet
+    PathPolicy {
+        kind: PathKind::Schnorr,
+        prefix: &[PURPOSE_BIP86, COIN_TESTNET],
+        min_len: 5,
+        max_len: 5,
+        hardened: 0b111,
+    },
+];
+
+pub fn is_hardened(index: u32) -> bool {
+    index & HARDENED != 0
+}
+
+/// Checks path against the policies of kind
+pub fn check_path_policy(path: &[u32], kind: PathKind) -> Result<(), ParserError> {
+    if POLICIES.iter().any(|policy| policy.allows(kind, path)) {
+        return Ok(());
+    }
+    Err(ParserError::parser_invalid_path)
+}
+
+/// Reads the path sent by the host into out if the policies of kind allow
+/// it, out is left as it was otherwise. Returns the number of levels
+pub fn read_path(data: &[u8], kind: PathKind, out: &mut [u32]) -> Result<usize, ParserError> {
+    if !data.chunks_exact(LEVEL_LEN).remainder().is_empty() {
+        return Err(ParserError::parser_unexpected_buffer_end);
+    }
+    let len = data.len() / LEVEL_LEN;
+    if len > MAX_PATH_LEN {
+        return Err(ParserError::parser_invalid_path);
+    }
+
+    let mut path = [0u32; MAX_PATH_LEN];
+    for (index, level) in path.iter_mut().zip(data.chunks_exact(LEVEL_LEN)) {
+        let bytes = level
+            .try_into()
+            .map_err(|_| ParserError::parser_unexpected_buffer_end)?;
+        *index = u32::from_le_bytes(bytes);
+    }
+    let path = path.get(..len).ok_or(ParserError::parser_invalid_path)?;
+    check_path_policy(path, kind)?;
+
+    let out = out
+        .get_mut(..len)
+        .ok_or(ParserError::parser_unexpected_buffer_end)?;
+    out.iter_mut().zip(path).for_each(|(o, index)| *o = *index);
+    Ok(len)
+}
+
+/// Whether path is one of the account paths wallets derive
+pub fn is_standard_path(path: &[u32]) -> bool {
+    match path {
+        [PURPOSE_BIP44, COIN_STX, account, CHANGE_EXTERNAL, index]
+        | [PURPOSE_BIP44, COIN_TESTNET, account, CHANGE_EXTERNAL, index] => {
+            is_hardened(*account) && !is_hardened(*index)
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_standard_paths() {
+        assert!(is_standard_path(&[PURPOSE_BIP44, COIN_STX, HARDENED, 0, 0]));
+        assert!(is_standard_path(&[PURPOSE_BIP44, COIN_STX, 5 | HARDENED, 0, 12]));
+        assert!(is_standard_path(&[PURPOSE_BIP44, COIN_TESTNET, HARDENED, 0, 3]));
+    }
+
+    #[test]
+    fn test_nonstandard_paths() {
+        // change index
+        assert!(!is_standard_path(&[PURPOSE_BIP44, COIN_STX, HARDENED, 1, 0]));
+        // account not hardened, index hardened
+        assert!(!is_standard_path(&[PURPOSE_BIP44, COIN_STX, 0, 0, 0]));
+        assert!(!is_standard_path(&[PURPOSE_BIP44, COIN_STX, HARDENED, 0, HARDENED]));
+        // the legacy m/5757'/... scheme
+        assert!(!is_standard_path(&[COIN_STX, HARDENED, 0, 0, 0]));
+        // depth
+        assert!(!is_standard_path(&[PURPOSE_BIP44, COIN_STX, HARDENED, 0]));
+        assert!(!is_standard_path(&[PURPOSE_BIP44, COIN_STX, HARDENED, 0, 0, 0]));
+        assert!(!is_standard_path(&[]));
+    }
+
+    fn serialize(path: &[u32]) -> std::vec::Vec<u8> {
+        path.iter().flat_map(|level| level.to_le_bytes()).collect()
+    }
+
+    #[test]
+    fn test_account_policy() {
+        let mut out = [0u32; MAX_PATH_LEN];
+        for path in [
+            [PURPOSE_BIP44, COIN_STX, HARDENED, 0, 0],
+            [PURPOSE_BIP44, COIN_TESTNET, 3, 1, HARDENED],
+            [COIN_STX, HARDENED, 0, 0, 4],
+        ]
+        .iter()
+        {
+            let len = read_path(&serialize(path), PathKind::Account, &mut out).unwrap();
+            assert_eq!(&out[..len], path);
+        }
+
+        // other purposes, coins and depths are refused
+        let mut out = [0u32; MAX_PATH_LEN];
+        for path in [
+            // coin 0'
+            &[PURPOSE_BIP44, HARDENED, HARDENED, 0, 0][..],
+            &[PURPOSE_IDENTITY, HARDENED, HARDENED],
+            &[PURPOSE_BIP44, COIN_STX, HARDENED, 0],
+            &[PURPOSE_BIP44, COIN_STX, HARDENED, 0, 0, 0],
+            &[],
+        ]
+        .iter()
+        {
+            assert_eq!(
+                read_path(&serialize(path), PathKind::Account, &mut out),
+                Err(ParserError::parser_invalid_path)
+            );
+        }
+        // and out is left untouched
+        assert_eq!(out, [0u32; MAX_PATH_LEN]);
+    }
+
+    #[test]
+    fn test_identity_policy() {
+        let path = [PURPOSE_IDENTITY, HARDENED, 2 | HARDENED];
+        let mut out = [0u32; MAX_PATH_LEN];
+        assert_eq!(read_path(&serialize(&path), PathKind::Identity, &mut out), Ok(3));
+        assert_eq!(
+            check_path_policy(&[PURPOSE_IDENTITY, HARDENED, 19], PathKind::Identity),
+            Ok(())
+        );
+        assert_eq!(
+            check_path_policy(&path, PathKind::Account),
+            Err(ParserError::parser_invalid_path)
+        );
+        assert_eq!(
+            check_path_policy(&[PURPOSE_BIP44, COIN_STX, HARDENED, 0, 0], PathKind::Identity),
+            Err(ParserError::parser_invalid_path)
+        );
+    }
+
+    #[test]
+    fn test_bitcoin_policy() {
+        for path in [
+            [PURPOSE_BIP84, COIN_BTC, HARDENED, 0, 0],
+            [PURPOSE_BIP44, COIN_TESTNET, 3 | HARDENED, 1, 7],
+        ]
+        .iter()
+        {
+            assert_eq!(check_path_policy(path, PathKind::Bitcoin), Ok(()));
+        }
+        assert!(
+            check_path_policy(&[PURPOSE_BIP84, COIN_BTC, HARDENED, 0, 0], PathKind::Account).is_err()
+        );
+        for path in [
+            // account not hardened
+            &[PURPOSE_BIP84, COIN_BTC, 0, 0, 0][..],
+            &[PURPOSE_BIP44, COIN_STX, HARDENED, 0, 0],
+            &[PURPOSE_BIP84, COIN_BTC, HARDENED, 0],
+        ]
+        .iter()
+        {
+            assert_eq!(
+                check_path_policy(path, PathKind::Bitcoin),
+                Err(ParserError::parser_invalid_path)
+            );
+        }
+    }
+
+    #[test]
+    fn test_schnorr_policy() {
+        for path in [
+            [PURPOSE_BIP86, COIN_BTC, HARDENED, 0, 0],
+            [PURPOSE_BIP86, COIN_TESTNET, 2 | HARDENED, 1, 3],
+            [PURPOSE_BIP44, COIN_STX, HARDENED, 0, 0],
+        ]
+        .iter()
+        {
+            assert_eq!(check_path_policy(path, PathKind::Schnorr), Ok(()));
+        }
+        // Taproot keys are not Stacks accounts
+        assert!(
+            check_path_policy(&[PURPOSE_BIP86, COIN_BTC, HARDENED, 0, 0], PathKind::Account).is_err()
+        );
+        for path in [
+            &[PURPOSE_BIP86, COIN_BTC, 0, 0, 0][..],
+            &[PURPOSE_BIP84, COIN_BTC, HARDENED, 0, 0],
+            &[PURPOSE_IDENTITY, HARDENED, HARDENED],
+        ]
+        .iter()
+        {
+            assert_eq!(
+                check_path_policy(path, PathKind::Schnorr),
+                Err(ParserError::parser_invalid_path)
+            );
+        }
+    }
+
+    #[test]
+    fn test_read_path_length() {
+        let mut out = [0u32; MAX_PATH_LEN];
+        let data = serialize(&[PURPOSE_BIP44, COIN_STX, HARDENED, 0, 0]);
+        assert_eq!(
+            read_path(&data[..19], PathKind::Account, &mut out),
+            Err(ParserError::parser_unexpected_buffer_end)
+        );
+        assert_eq!(
+            read_path(&serialize(&[HARDENED; MAX_PATH_LEN + 1]), PathKind::Account, &mut out),
+            Err(ParserError::parser_invalid_path)
+        );
+        // out too short for the path
+        assert_eq!(
+            read_path(&data, PathKind::Account, &mut out[..4]),
+            Err(ParserError::parser_unexpected_buffer_end)
+        );
+    }
+
+    #[test]
+    fn test_hardened_levels() {
+        let policy = PathPolicy {
+            kind: PathKind::Account,
+            prefix: &[PURPOSE_BIP44],
+            min_len: 2,
+            max_len: 3,
+            hardened: 0b110,
+        };
+        assert!(policy.allows(PathKind::Account, &[PURPOSE_BIP44, HARDENED, HARDENED]));
+        assert!(policy.allows(PathKind::Account, &[PURPOSE_BIP44, 5 | HARDENED]));
+        assert!(!policy.allows(PathKind::Account, &[PURPOSE_BIP44, HARDENED, 1]));
+        assert!(!policy.allows(PathKind::Identity, &[PURPOSE_BIP44, HARDENED]));
+    }
+}
@@ -0,0 +1,285 @@
+//! Versioned blob of the app settings kept in flash.
+//!
+//! The C side only stores the blob, settings are read and updated through
+//! here so the layout of each version is defined in one place:
+//!
+//! | magic (2) | version (1) | flags (2) | fee threshold (8) |
+//!
+//! The flags and the threshold are little endian. Flash is zeroed on
+//! install, a blob that does not start with the magic reads as the
+//! defaults, and so does a blob of a version this app does not know, left
+//! by a newer one.
+//!
+//! Expert mode is kept by the SDK, not in this blob.
+
+use core::convert::TryFrom;
+
+use crate::parser::{
+    reader::{le_u16, le_u64, le_u8},
+    ParserError,
+};
+
+/// Bytes of flash the blob takes
+pub const SETTINGS_BLOB_LEN: usize = 13;
+
+pub const SETTINGS_VERSION: u8 = 1;
+
+const SETTINGS_MAGIC: &[u8; 2] = b"SX";
+
+// 1 STX, in uSTX
+pub const DEFAULT_FEE_THRESHOLD: u64 = 1_000_000;
+
+/// A setting, the setting_id_e enum of settings.h. All but the fee
+/// threshold are toggles whose value is 0 or 1
+#[repr(u8)]
+#[derive(Clone, Copy, PartialEq)]
+#[cfg_attr(test, derive(Debug))]
+pub enum SettingId {
+    /// Raw hex items at the end of the review, in expert mode
+    RawHex = 0,
+    /// Fee in uSTX above which the fee is shown as a warning
+    FeeThreshold = 1,
+    HideNonce = 2,
+    HideEmptyMemo = 3,
+    /// Refuse transactions for the other network than the path
+    StrictNetwork = 4,
+    /// Refuse calls to denylisted contracts
+    StrictDenylist = 5,
+    /// BIP-340 signing, in expert mode
+    Schnorr = 6,
+    /// Amounts in STX instead of uSTX
+    StxUnits = 7,
+    ShortAddresses = 8,
+    GroupedAddresses = 9,
+}
+
+/// Number of settings, their ids go from 0 to SETTINGS_COUNT - 1
+pub const SETTINGS_COUNT: u8 = 10;
+
+// The id and the big endian value of a setting
+pub const SETTING_ENTRY_LEN: usize = 1 + 8;
+
+impl TryFrom<u8> for SettingId {
+    type Error = ParserError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::RawHex),
+            1 => Ok(Self::FeeThreshold),
+            2 => Ok(Self::HideNonce),
+            3 => Ok(Self::HideEmptyMemo),
+            4 => Ok(Self::StrictNetwork),
+            5 => Ok(Self::StrictDenylist),
+            6 => Ok(Self::Schnorr),
+            7 => Ok(Self::StxUnits),
+            8 => Ok(Self::ShortAddresses),
+            9 => Ok(Self::GroupedAddresses),
+            _ => Err(ParserError::parser_unexpected_value),
+        }
+    }
+}
+
+impl SettingId {
+    // the bit of a toggle in the flags
+    fn flag(self) -> Option<u16> {
+        match self {
+            Self::FeeThreshold => None,
+            toggle => Some(1 << toggle as u8),
+        }
+    }
+}
+
+const DEFAULT_FLAGS: u16 = (1 << SettingId::RawHex as u8)
+    | (1 << SettingId::Schnorr as u8)
+    | (1 << SettingId::GroupedAddresses as u8);
+
+#[derive(Clone, Copy, PartialEq)]
+#[cfg_attr(test, derive(Debug))]
+pub struct Settings {
+    flags: u16,
+    fee_threshold: u64,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            flags: DEFAULT_FLAGS,
+            fee_threshold: DEFAULT_FEE_THRESHOLD,
+        }
+    }
+}
+
+impl Settings {
+    /// Reads the settings of a blob, the defaults if it is still zeroed
+    pub fn from_blob(blob: &[u8]) -> Result<Self, ParserError> {
+        if !blob.starts_with(SETTINGS_MAGIC) {
+            return Ok(Self::default());
+        }
+        let rem = blob
+            .get(SETTINGS_MAGIC.len()..)
+            .ok_or(ParserError::parser_unexpected_buffer_end)?;
+        let (rem, version) = le_u8(rem)?;
+        if version != SETTINGS_VERSION {
+            return Err(ParserError::parser_unexpected_value);
+        }
+        let (rem, flags) = le_u16(rem)?;
+        let (_, fee_threshold) = le_u64(rem)?;
+        let mut settings = Self {
+            flags,
+            ..Self::default()
+        };
+        settings.set(SettingId::FeeThreshold, fee_threshold)?;
+        Ok(settings)
+    }
+
+    /// The settings of blob, the defaults if it can not be read
+    pub fn read(blob: &[u8]) -> Self {
+        Self::from_blob(blob).unwrap_or_default()
+    }
+
+    pub fn get(&self, id: SettingId) -> u64 {
+        match id.flag() {
+            Some(flag) => u64::from(self.flags & flag != 0),
+            None => self.fee_threshold,
+        }
+    }
+
+    /// Sets a toggle to 0 or 1, or the fee threshold,
+    /// a threshold of zero restores the default one
+    pub fn set(&mut self, id: SettingId, value: u64) -> Result<(), ParserError> {
+        match id.flag() {
+            Some(flag) => match value {
+                0 => self.flags &= !flag,
+                1 => self.flags |= flag,
+                _ => return Err(ParserError::parser_value_out_of_range),
+            },
+            None if value == 0 => self.fee_threshold = DEFAULT_FEE_THRESHOLD,
+            None => self.fee_threshold = value,
+        }
+        Ok(())
+    }
+
+    /// Writes every setting as its id followed by its big endian value,
+    /// after the number of settings. Returns the bytes written
+    pub fn write_entries(&self, out: &mut [u8]) -> Result<usize, ParserError> {
+        let len = 1 + SETTINGS_COUNT as usize * SETTING_ENTRY_LEN;
+        let out = out
+            .get_mut(..len)
+            .ok_or(ParserError::parser_unexpected_buffer_end)?;
+        let (count, entries) = out
+            .split_first_mut()
+            .ok_or(ParserError::parser_unexpected_buffer_end)?;
+        *count = SETTINGS_COUNT;
+
+        for (id, entry) in (0..SETTINGS_COUNT).zip(entries.chunks_exact_mut(SETTING_ENTRY_LEN)) {
+            let value = self.get(SettingId::try_from(id)?).to_be_bytes();
+            for (dst, src) in entry
+                .iter_mut()
+                .zip(core::iter::once(&id).chain(value.iter()))
+            {
+                *dst = *src;
+            }
+        }
+        Ok(len)
+    }
+
+    /// Writes the blob of the settings in the current version.
+    /// Returns SETTINGS_BLOB_LEN
+    pub fn write_blob(&self, out: &mut [u8]) -> Result<usize, ParserError> {
+        let version = [SETTINGS_VERSION];
+        let flags = self.flags.to_le_bytes();
+        let fee_threshold = self.fee_threshold.to_le_bytes();
+        let fields: [&[u8]; 4] = [SETTINGS_MAGIC, &version, &flags, &fee_threshold];
+
+        let out = out
+            .get_mut(..SETTINGS_BLOB_LEN)
+            .ok_or(ParserError::parser_unexpected_buffer_end)?;
+        for (dst, src) in out.iter_mut().zip(fields.iter().flat_map(|f| f.iter())) {
+            *dst = *src;
+        }
+        Ok(SETTINGS_BLOB_LEN)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use core::convert::TryInto;
+
+    fn blob(settings: &Settings) -> [u8; SETTINGS_BLOB_LEN] {
+        let mut out = [0u8; SETTINGS_BLOB_LEN];
+        settings.write_blob(&mut out).unwrap();
+        out
+    }
+
+    #[test]
+    fn test_settings_blob() {
+        let mut settings = Settings::default();
+        assert_eq!(settings.get(SettingId::RawHex), 1);
+        assert_eq!(settings.get(SettingId::HideNonce), 0);
+        assert_eq!(settings.get(SettingId::FeeThreshold), DEFAULT_FEE_THRESHOLD);
+
+        settings.set(SettingId::StxUnits, 1).unwrap();
+        settings.set(SettingId::GroupedAddresses, 0).unwrap();
+        settings.set(SettingId::FeeThreshold, 5_000_000).unwrap();
+        let bytes = blob(&settings);
+        assert_eq!(&bytes[..3], b"SX\x01");
+        assert_eq!(Settings::from_blob(&bytes), Ok(settings));
+
+        // zero restores the default threshold, toggles are 0 or 1
+        settings.set(SettingId::FeeThreshold, 0).unwrap();
+        assert_eq!(settings.get(SettingId::FeeThreshold), DEFAULT_FEE_THRESHOLD);
+        assert_eq!(
+            settings.set(SettingId::HideNonce, 2),
+            Err(ParserError::parser_value_out_of_range)
+        );
+
+        // a version this app does not know reads as the defaults
+        let mut newer = bytes;
+        newer[2] = SETTINGS_VERSION + 1;
+        assert!(Settings::from_blob(&newer).is_err());
+        assert_eq!(Settings::read(&newer), Settings::default());
+
+        let mut out = [0u8; SETTINGS_BLOB_LEN - 1];
+        assert!(settings.write_blob(&mut out).is_err());
+    }
+
+    #[test]
+    fn test_settings_entries() {
+        let mut settings = Settings::default();
+        settings.set(SettingId::FeeThreshold, 0x0102).unwrap();
+        settings.set(SettingId::HideNonce, 1).unwrap();
+
+        let mut out = [0u8; 100];
+        let len = settings.write_entries(&mut out).unwrap();
+        assert_eq!(len, 1 + SETTINGS_COUNT as usize * SETTING_ENTRY_LEN);
+        assert_eq!(out[0], SETTINGS_COUNT);
+        for (id, entry) in out[1..len].chunks(SETTING_ENTRY_LEN).enumerate() {
+            let id = SettingId::try_from(id as u8).unwrap();
+            assert_eq!(entry[0], id as u8);
+            let value = u64::from_be_bytes(entry[1..].try_into().unwrap());
+            assert_eq!(value, settings.get(id));
+        }
+        assert_eq!(
+            &out[1 + SETTING_ENTRY_LEN..1 + 2 * SETTING_ENTRY_LEN],
+            &[1, 0, 0, 0, 0, 0, 0, 1, 2]
+        );
+        assert!(SettingId::try_from(SETTINGS_COUNT).is_err());
+
+        assert!(settings.write_entries(&mut out[..len - 1]).is_err());
+    }
+
+    #[test]
+    fn test_settings_zeroed() {
+        // flash is zeroed on install
+        let zeroed = [0u8; SETTINGS_BLOB_LEN];
+        assert_eq!(Settings::from_blob(&zeroed), Ok(Settings::default()));
+        assert_eq!(Settings::read(&[]), Settings::default());
+
+        // written once a setting is changed
+        let mut settings = Settings::read(&zeroed);
+        settings.set(SettingId::HideNonce, 1).unwrap();
+        assert_eq!(Settings::from_blob(&blob(&settings)), Ok(settings));
+        assert!(Settings::from_blob(&blob(&settings)[..SETTINGS_BLOB_LEN - 1]).is_err());
+    }
+}
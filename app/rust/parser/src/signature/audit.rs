@@ -0,0 +1,240 @@
+//! Host computation of the signatures the device is expected to return.
+//!
+//! Given the seed of a test device, a key path and a 32 bytes digest this
+//! derives the same key the device does and signs the digest the same way:
+//! RFC6979 nonces over SHA-256 and low-S. Signing is deterministic, so a
+//! device that returns anything else for that seed is misbehaving.
+//!
+//! Schnorr signatures are also produced here with the auxiliary randomness
+//! given by the caller, device ones use fresh randomness so they can only be
+//! verified, see [`verify_schnorr`].
+
+use arrayvec::ArrayVec;
+use bip32::{ChildNumber, XPrv};
+use core::convert::TryFrom;
+use k256::schnorr::{
+    Signature as SchnorrSignature, SigningKey as SchnorrSigningKey,
+    VerifyingKey as SchnorrVerifyingKey,
+};
+use k256::NonZeroScalar;
+
+use super::schnorr::{SCHNORR_MESSAGE_LEN, SCHNORR_SIGNATURE_LEN, XONLY_PUBKEY_LEN};
+use super::{canonicalize, MAX_DER_LEN, SCALAR_LEN};
+use crate::parser::ParserError;
+
+pub const DIGEST_LEN: usize = 32;
+pub const COMPRESSED_PUBKEY_LEN: usize = 33;
+
+/// A signature laid out as the app returns it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExpectedSignature {
+    pub r: [u8; SCALAR_LEN],
+    pub s: [u8; SCALAR_LEN],
+    /// recovery id, bit 0 is the parity of the nonce point y
+    pub v: u8,
+    pub der: ArrayVec<[u8; MAX_DER_LEN]>,
+}
+
+impl ExpectedSignature {
+    /// The signature in the order it follows the post_sighash in the
+    /// response to a sign APDU
+    pub fn rsv(&self) -> [u8; 2 * SCALAR_LEN + 1] {
+        let mut out = [0u8; 2 * SCALAR_LEN + 1];
+        let (r, rest) = out.split_at_mut(SCALAR_LEN);
+        let (s, v) = rest.split_at_mut(SCALAR_LEN);
+        r.copy_from_slice(&self.r);
+        s.copy_from_slice(&self.s);
+        v[0] = self.v;
+        out
+    }
+}
+
+/// Derives the key at path, components are given as in the APDUs,
+/// with the hardened bit set
+fn derive(seed: &[u8], path: &[u32]) -> Result<XPrv, ParserError> {
+    XPrv::new(seed)
+        .and_then(|root| {
+            path.iter()
+                .try_fold(root, |key, index| key.derive_child(ChildNumber::from(*index)))
+        })
+        .map_err(|_| ParserError::parser_crypto_error)
+}
+
+/// Compressed public key of the key at path
+pub fn public_key(seed: &[u8], path: &[u32]) -> Result<[u8; COMPRESSED_PUBKEY_LEN], ParserError> {
+    Ok(derive(seed, path)?.public_key().to_bytes())
+}
+
+/// The signature of digest the device with seed returns for the key at path
+pub fn expected_signature(
+    seed: &[u8],
+    path: &[u32],
+    digest: &[u8; DIGEST_LEN],
+) -> Result<ExpectedSignature, ParserError> {
+    let key = derive(seed, path)?;
+    let (signature, recovery_id) = key
+        .private_key()
+        .sign_prehash_recoverable(digest)
+        .map_err(|_| ParserError::parser_crypto_error)?;
+
+    let mut r = [0u8; SCALAR_LEN];
+    let mut s = [0u8; SCALAR_LEN];
+    r.copy_from_slice(&signature.r().to_bytes());
+    s.copy_from_slice(&signature.s().to_bytes());
+    let mut v = recovery_id.to_byte();
+
+    // k256 already returns low-S signatures, encoding through the device
+    // code keeps both DER encodings the same
+    let mut der = [0u8; MAX_DER_LEN];
+    let der_len =
+        canonicalize(&r, &mut s, &mut v, &mut der).ok_or(ParserError::parser_crypto_error)?;
+
+    let mut out = ExpectedSignature {
+        r,
+        s,
+        v,
+        der: ArrayVec::new(),
+    };
+    der.iter()
+        .take(der_len)
+        .try_for_each(|b| out.der.try_push(*b))
+        .map_err(|_| ParserError::parser_crypto_error)?;
+    Ok(out)
+}
+
+/// x-only public key of the key at path, the one Schnorr signatures verify against
+pub fn xonly_public_key(seed: &[u8], path: &[u32]) -> Result<[u8; XONLY_PUBKEY_LEN], ParserError> {
+    let key = derive(seed, path)?;
+    let signing_key = SchnorrSigningKey::from(*key.private_key().as_nonzero_scalar());
+    Ok(signing_key.verifying_key().to_bytes().into())
+}
+
+fn sign_schnorr(
+    secret: NonZeroScalar,
+    message: &[u8; SCHNORR_MESSAGE_LEN],
+    aux_rand: &[u8; SCALAR_LEN],
+) -> Result<[u8; SCHNORR_SIGNATURE_LEN], ParserError> {
+    SchnorrSigningKey::from(secret)
+        .sign_raw(message, aux_rand)
+        .map(|signature| signature.to_bytes())
+        .map_err(|_| ParserError::parser_crypto_error)
+}
+
+/// BIP-340 signature of message with the key at path and the given
+/// auxiliary randomness
+pub fn schnorr_signature(
+    seed: &[u8],
+    path: &[u32],
+    message: &[u8; SCHNORR_MESSAGE_LEN],
+    aux_rand: &[u8; SCALAR_LEN],
+) -> Result<[u8; SCHNORR_SIGNATURE_LEN], ParserError> {
+    let key = derive(seed, path)?;
+    sign_schnorr(*key.private_key().as_nonzero_scalar(), message, aux_rand)
+}
+
+/// Whether signature is a valid BIP-340 signature of message by the x-only key
+pub fn verify_schnorr(
+    xonly_key: &[u8; XONLY_PUBKEY_LEN],
+    message: &[u8; SCHNORR_MESSAGE_LEN],
+    signature: &[u8; SCHNORR_SIGNATURE_LEN],
+) -> bool {
+    match (
+        SchnorrVerifyingKey::from_bytes(xonly_key),
+        SchnorrSignature::try_from(&signature[..]),
+    ) {
+        (Ok(key), Ok(signature)) => key.verify_raw(message, &signature).is_ok(),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::signature::is_low_s;
+    use core::convert::TryInto;
+    use k256::ecdsa::{RecoveryId, Signature, VerifyingKey};
+
+    // seed of the default mnemonic of the zemu tests, "equip will roof matter
+    // pink blind book anxiety banner elbow sun young"
+    const SEED: &str = "ed2f664e65b5ef0dd907ae15a2788cfc98e41970bc9fcb46f5900f6919862075\
+                        e721f37212304a56505dab99b001cc8907ef093b7c5016a46b50c01cc3ec1cac";
+
+    const HARDENED: u32 = 0x8000_0000;
+    const PATH: [u32; 5] = [44 | HARDENED, 5757 | HARDENED, 5 | HARDENED, 0, 0];
+
+    #[test]
+    fn test_public_key() {
+        let seed = hex::decode(SEED).unwrap();
+        let pubkey = public_key(&seed, &PATH).unwrap();
+        // the key of SP39RCH114B48GY5E0K2Q4SV28XZMXW4ZZRQXY3V7 in the zemu tests
+        assert_eq!(
+            hex::encode(pubkey),
+            "0252dab95065cd31ae6f8ece65fffd2e904b203268a5923fa85e5db793698d753a"
+        );
+    }
+
+    #[test]
+    fn test_expected_signature() {
+        let seed = hex::decode(SEED).unwrap();
+        let digest = [0x5au8; DIGEST_LEN];
+
+        let expected = expected_signature(&seed, &PATH, &digest).unwrap();
+        assert_eq!(expected, expected_signature(&seed, &PATH, &digest).unwrap());
+        assert!(is_low_s(&expected.s));
+
+        let signature = Signature::from_der(&expected.der).unwrap();
+        assert_eq!(signature.r().to_bytes().as_slice(), &expected.r);
+        assert_eq!(signature.s().to_bytes().as_slice(), &expected.s);
+
+        let rsv = expected.rsv();
+        let compact = Signature::from_slice(&rsv[..2 * SCALAR_LEN]).unwrap();
+        let recovery_id = RecoveryId::from_byte(rsv[2 * SCALAR_LEN]).unwrap();
+        let recovered = VerifyingKey::recover_from_prehash(&digest, &compact, recovery_id).unwrap();
+        assert_eq!(
+            recovered.to_encoded_point(true).as_bytes(),
+            &public_key(&seed, &PATH).unwrap()
+        );
+
+        let other = expected_signature(&seed, &PATH, &[0xa5u8; DIGEST_LEN]).unwrap();
+        assert_ne!(expected.r, other.r);
+    }
+
+    #[test]
+    fn test_schnorr_vector() {
+        // test vector 0 of BIP-340
+        let mut secret = [0u8; SCALAR_LEN];
+        secret[SCALAR_LEN - 1] = 3;
+        let secret = Option::from(NonZeroScalar::from_repr(secret.into())).unwrap();
+
+        let signature = sign_schnorr(secret, &[0; 32], &[0; 32]).unwrap();
+        assert_eq!(
+            hex::encode(signature),
+            "e907831f80848d1069a5371b402410364bdf1c5f8307b0084c55f1ce2dca8215\
+             25f66a4a85ea8b71e482a74f382d2ce5ebeee8fdb2172f477df4900d310536c0"
+        );
+
+        let key = hex::decode("f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9")
+            .unwrap();
+        let key: &[u8; XONLY_PUBKEY_LEN] = key.as_slice().try_into().unwrap();
+        assert!(verify_schnorr(key, &[0; 32], &signature));
+        assert!(!verify_schnorr(key, &[1; 32], &signature));
+    }
+
+    #[test]
+    fn test_schnorr_signature() {
+        let seed = hex::decode(SEED).unwrap();
+        let message = [0x5au8; SCHNORR_MESSAGE_LEN];
+
+        let signature = schnorr_signature(&seed, &PATH, &message, &[0x11; 32]).unwrap();
+        assert!(crate::signature::schnorr::is_well_formed(&signature));
+        assert_ne!(
+            signature,
+            schnorr_signature(&seed, &PATH, &message, &[0x22; 32]).unwrap()
+        );
+
+        // the x-only key is the compressed one without its parity byte
+        let xonly = xonly_public_key(&seed, &PATH).unwrap();
+        assert_eq!(&public_key(&seed, &PATH).unwrap()[1..], &xonly);
+        assert!(verify_schnorr(&xonly, &message, &signature));
+    }
+}
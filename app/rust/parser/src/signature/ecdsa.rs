@@ -0,0 +1,434 @@
+//! The secp256k1 ECDSA operations of the signing path.
+//!
+//! On device they are backed by the cx library through the C side of the
+//! app, on host builds by the pure Rust k256. [`sign`] composes them into
+//! the signature the app returns and checks it before it leaves the device,
+//! the code is generic over [`Secp256k1`] so unit tests and fuzzing run the
+//! same signing, verification and recovery paths off-device.
+
+use super::{canonicalize, is_canonical, is_low_s, is_valid_scalar, SCALAR_LEN};
+
+pub const SECRET_KEY_LEN: usize = 32;
+pub const DIGEST_LEN: usize = 32;
+pub const UNCOMPRESSED_PUBKEY_LEN: usize = 65;
+
+/// secp256k1 ECDSA over 32 bytes digests, they are signed as they are
+pub trait Secp256k1 {
+    /// Writes the uncompressed public key of secret into out
+    fn public_key(secret: &[u8; SECRET_KEY_LEN], out: &mut [u8; UNCOMPRESSED_PUBKEY_LEN]) -> bool;
+
+    /// Signs digest with an RFC6979 nonce, writing r and s. Returns the
+    /// recovery id, s is not necessarily low
+    fn sign(
+        secret: &[u8; SECRET_KEY_LEN],
+        digest: &[u8; DIGEST_LEN],
+        r: &mut [u8; SCALAR_LEN],
+        s: &mut [u8; SCALAR_LEN],
+    ) -> Option<u8>;
+
+    /// Whether the DER encoded signature of digest verifies against public_key
+    fn verify(
+        public_key: &[u8; UNCOMPRESSED_PUBKEY_LEN],
+        digest: &[u8; DIGEST_LEN],
+        der: &[u8],
+    ) -> bool;
+
+    /// The recovery id with which verifiers get public_key back from the
+    /// signature (r, s) of digest, None if there is none
+    fn recovery_id(
+        public_key: &[u8; UNCOMPRESSED_PUBKEY_LEN],
+        digest: &[u8; DIGEST_LEN],
+        r: &[u8; SCALAR_LEN],
+        s: &[u8; SCALAR_LEN],
+    ) -> Option<u8>;
+}
+
+#[cfg(all(feature = "device", not(test)))]
+pub type DefaultSecp256k1 = device::DeviceSecp256k1;
+
+#[cfg(any(test, not(feature = "device")))]
+pub type DefaultSecp256k1 = soft::SoftSecp256k1;
+
+/// Signs digest with secret into (r, s, v) and der, in the low-S form, and
+/// returns the DER length. A fault while signing must not hand out an
+/// invalid, malleable or wrongly recoverable signature, so None is returned
+/// unless the signature agrees with its encodings, verifies against the key
+/// of secret and recovers it with v
+pub fn sign<E: Secp256k1>(
+    secret: &[u8; SECRET_KEY_LEN],
+    digest: &[u8; DIGEST_LEN],
+    r: &mut [u8; SCALAR_LEN],
+    s: &mut [u8; SCALAR_LEN],
+    v: &mut u8,
+    der: &mut [u8],
+) -> Option<usize> {
+    let mut public_key = [0u8; UNCOMPRESSED_PUBKEY_LEN];
+    if !E::public_key(secret, &mut public_key) {
+        return None;
+    }
+
+    *v = E::sign(secret, digest, r, s)?;
+    let len = canonicalize(r, s, v, der)?;
+    let der = der.get(..len)?;
+
+    let checked = is_canonical(r, s, *v, der)
+        && E::verify(&public_key, digest, der)
+        && E::recovery_id(&public_key, digest, r, s) == Some(*v);
+    if checked {
+        Some(len)
+    } else {
+        None
+    }
+}
+
+/// Whether (r, s, v) is a signature of digest by public_key the way the
+/// network takes it: r a valid scalar, s low and v recovering public_key
+pub fn verify<E: Secp256k1>(
+    public_key: &[u8; UNCOMPRESSED_PUBKEY_LEN],
+    digest: &[u8; DIGEST_LEN],
+    r: &[u8; SCALAR_LEN],
+    s: &[u8; SCALAR_LEN],
+    v: u8,
+) -> bool {
+    // recovering the key is a verification in itself, the nonce point
+    // computed from the key must have r as x
+    is_valid_scalar(r)
+        && is_low_s(s)
+        && v <= 1
+        && E::recovery_id(public_key, digest, r, s) == Some(v)
+}
+
+#[cfg(all(feature = "device", not(test)))]
+mod device {
+    use super::{Secp256k1, DIGEST_LEN, SCALAR_LEN, SECRET_KEY_LEN, UNCOMPRESSED_PUBKEY_LEN};
+
+    // implemented with the cx library in app/src/crypto.c
+    extern "C" {
+        fn crypto_secp256k1_public_key(secret: *const u8, publicKey: *mut u8) -> bool;
+        fn crypto_secp256k1_sign(
+            secret: *const u8,
+            digest: *const u8,
+            r: *mut u8,
+            s: *mut u8,
+            v: *mut u8,
+        ) -> bool;
+        fn crypto_secp256k1_verify(
+            publicKey: *const u8,
+            digest: *const u8,
+            der: *const u8,
+            derLen: u16,
+        ) -> bool;
+        fn crypto_secp256k1_nonce_point(
+            publicKey: *const u8,
+            digest: *const u8,
+            r: *const u8,
+            s: *const u8,
+            point: *mut u8,
+        ) -> bool;
+    }
+
+    pub struct DeviceSecp256k1;
+
+    impl Secp256k1 for DeviceSecp256k1 {
+        fn public_key(
+            secret: &[u8; SECRET_KEY_LEN],
+            out: &mut [u8; UNCOMPRESSED_PUBKEY_LEN],
+        ) -> bool {
+            unsafe { crypto_secp256k1_public_key(secret.as_ptr(), out.as_mut_ptr()) }
+        }
+
+        fn sign(
+            secret: &[u8; SECRET_KEY_LEN],
+            digest: &[u8; DIGEST_LEN],
+            r: &mut [u8; SCALAR_LEN],
+            s: &mut [u8; SCALAR_LEN],
+        ) -> Option<u8> {
+            let mut v = 0u8;
+            let signed = unsafe {
+                crypto_secp256k1_sign(
+                    secret.as_ptr(),
+                    digest.as_ptr(),
+                    r.as_mut_ptr(),
+                    s.as_mut_ptr(),
+                    &mut v,
+                )
+            };
+            if signed {
+                Some(v)
+            } else {
+                None
+            }
+        }
+
+        fn verify(
+            public_key: &[u8; UNCOMPRESSED_PUBKEY_LEN],
+            digest: &[u8; DIGEST_LEN],
+            der: &[u8],
+        ) -> bool {
+            unsafe {
+                crypto_secp256k1_verify(
+                    public_key.as_ptr(),
+                    digest.as_ptr(),
+                    der.as_ptr(),
+                    der.len() as u16,
+                )
+            }
+        }
+
+        // the nonce point R = (z * G + r * Q) / s has r as x, the parity
+        // of its y is the recovery id
+        fn recovery_id(
+            public_key: &[u8; UNCOMPRESSED_PUBKEY_LEN],
+            digest: &[u8; DIGEST_LEN],
+            r: &[u8; SCALAR_LEN],
+            s: &[u8; SCALAR_LEN],
+        ) -> Option<u8> {
+            let mut point = [0u8; UNCOMPRESSED_PUBKEY_LEN];
+            let computed = unsafe {
+                crypto_secp256k1_nonce_point(
+                    public_key.as_ptr(),
+                    digest.as_ptr(),
+                    r.as_ptr(),
+                    s.as_ptr(),
+                    point.as_mut_ptr(),
+                )
+            };
+            if !computed || point.get(1..1 + SCALAR_LEN)? != r {
+                return None;
+            }
+            point.last().map(|y| y & 1)
+        }
+    }
+}
+
+#[cfg(any(test, not(feature = "device")))]
+mod soft {
+    use super::{Secp256k1, DIGEST_LEN, SCALAR_LEN, SECRET_KEY_LEN, UNCOMPRESSED_PUBKEY_LEN};
+    use k256::ecdsa::signature::hazmat::PrehashVerifier;
+    use k256::ecdsa::{RecoveryId, Signature, SigningKey, VerifyingKey};
+
+    pub struct SoftSecp256k1;
+
+    impl Secp256k1 for SoftSecp256k1 {
+        fn public_key(
+            secret: &[u8; SECRET_KEY_LEN],
+            out: &mut [u8; UNCOMPRESSED_PUBKEY_LEN],
+        ) -> bool {
+            let key = match SigningKey::from_slice(secret) {
+                Ok(key) => key,
+                Err(_) => return false,
+            };
+            let point = key.verifying_key().to_encoded_point(false);
+            out.copy_from_slice(point.as_bytes());
+            true
+        }
+
+        fn sign(
+            secret: &[u8; SECRET_KEY_LEN],
+            digest: &[u8; DIGEST_LEN],
+            r: &mut [u8; SCALAR_LEN],
+            s: &mut [u8; SCALAR_LEN],
+        ) -> Option<u8> {
+            let key = SigningKey::from_slice(secret).ok()?;
+            let (signature, recovery_id) = key.sign_prehash_recoverable(digest).ok()?;
+            r.copy_from_slice(&signature.r().to_bytes());
+            s.copy_from_slice(&signature.s().to_bytes());
+            Some(recovery_id.to_byte())
+        }
+
+        fn verify(
+            public_key: &[u8; UNCOMPRESSED_PUBKEY_LEN],
+            digest: &[u8; DIGEST_LEN],
+            der: &[u8],
+        ) -> bool {
+            match (
+                VerifyingKey::from_sec1_bytes(public_key),
+                Signature::from_der(der),
+            ) {
+                (Ok(key), Ok(signature)) => key.verify_prehash(digest, &signature).is_ok(),
+                _ => false,
+            }
+        }
+
+        fn recovery_id(
+            public_key: &[u8; UNCOMPRESSED_PUBKEY_LEN],
+            digest: &[u8; DIGEST_LEN],
+            r: &[u8; SCALAR_LEN],
+            s: &[u8; SCALAR_LEN],
+        ) -> Option<u8> {
+            let key = VerifyingKey::from_sec1_bytes(public_key).ok()?;
+            let signature = Signature::from_scalars(*r, *s).ok()?;
+            RecoveryId::trial_recovery_from_prehash(&key, digest, &signature)
+                .map(|id| id.to_byte())
+                .ok()
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::soft::SoftSecp256k1;
+    use super::*;
+    use crate::signature::{MAX_DER_LEN, SECP256K1_ORDER};
+
+    const SECRET: [u8; SECRET_KEY_LEN] = [0x11; SECRET_KEY_LEN];
+    const DIGEST: [u8; DIGEST_LEN] = [0x42; DIGEST_LEN];
+
+    fn sign_with<E: Secp256k1>(
+        secret: &[u8; SECRET_KEY_LEN],
+    ) -> Option<([u8; SCALAR_LEN], [u8; SCALAR_LEN], u8, usize)> {
+        let mut r = [0u8; SCALAR_LEN];
+        let mut s = [0u8; SCALAR_LEN];
+        let mut v = 0u8;
+        let mut der = [0u8; MAX_DER_LEN];
+        let len = sign::<E>(secret, &DIGEST, &mut r, &mut s, &mut v, &mut der)?;
+        Some((r, s, v, len))
+    }
+
+    #[test]
+    fn test_sign_is_checked_and_deterministic() {
+        let (r, s, v, len) = sign_with::<DefaultSecp256k1>(&SECRET).unwrap();
+        assert!(is_low_s(&s));
+        assert!(v <= 1);
+
+        let mut public_key = [0u8; UNCOMPRESSED_PUBKEY_LEN];
+        assert!(DefaultSecp256k1::public_key(&SECRET, &mut public_key));
+        assert_eq!(
+            DefaultSecp256k1::recovery_id(&public_key, &DIGEST, &r, &s),
+            Some(v)
+        );
+
+        // RFC6979 nonces, the same key and digest give the same signature
+        assert_eq!(sign_with::<DefaultSecp256k1>(&SECRET), Some((r, s, v, len)));
+
+        // not a valid secret
+        assert_eq!(sign_with::<DefaultSecp256k1>(&[0u8; SECRET_KEY_LEN]), None);
+    }
+
+    #[test]
+    fn test_verify() {
+        let (r, s, v, _) = sign_with::<DefaultSecp256k1>(&SECRET).unwrap();
+        let mut public_key = [0u8; UNCOMPRESSED_PUBKEY_LEN];
+        assert!(DefaultSecp256k1::public_key(&SECRET, &mut public_key));
+        assert!(verify::<DefaultSecp256k1>(&public_key, &DIGEST, &r, &s, v));
+
+        // another recovery id gives back another key
+        assert!(!verify::<DefaultSecp256k1>(
+            &public_key,
+            &DIGEST,
+            &r,
+            &s,
+            v ^ 1
+        ));
+        assert!(!verify::<DefaultSecp256k1>(&public_key, &DIGEST, &r, &s, 2));
+
+        // the high-S twin verifies with ECDSA but is refused as malleable
+        let mut high = [0u8; SCALAR_LEN];
+        let mut borrow = 0u16;
+        for (x, (n, low)) in high.iter_mut().zip(SECP256K1_ORDER.iter().zip(&s)).rev() {
+            let diff = (*n as u16).wrapping_sub(*low as u16).wrapping_sub(borrow);
+            *x = diff as u8;
+            borrow = (diff >> 8) & 1;
+        }
+        assert!(!is_low_s(&high));
+        assert!(!verify::<DefaultSecp256k1>(
+            &public_key,
+            &DIGEST,
+            &r,
+            &high,
+            v ^ 1
+        ));
+
+        // another digest or another key
+        let other = [0x43; DIGEST_LEN];
+        assert!(!verify::<DefaultSecp256k1>(&public_key, &other, &r, &s, v));
+        let mut other_key = [0u8; UNCOMPRESSED_PUBKEY_LEN];
+        assert!(DefaultSecp256k1::public_key(
+            &[0x22; SECRET_KEY_LEN],
+            &mut other_key
+        ));
+        assert!(!verify::<DefaultSecp256k1>(&other_key, &DIGEST, &r, &s, v));
+    }
+
+    // a backend that returns the wrong recovery id
+    struct FlippedRecoveryId;
+
+    impl Secp256k1 for FlippedRecoveryId {
+        fn public_key(
+            secret: &[u8; SECRET_KEY_LEN],
+            out: &mut [u8; UNCOMPRESSED_PUBKEY_LEN],
+        ) -> bool {
+            SoftSecp256k1::public_key(secret, out)
+        }
+
+        fn sign(
+            secret: &[u8; SECRET_KEY_LEN],
+            digest: &[u8; DIGEST_LEN],
+            r: &mut [u8; SCALAR_LEN],
+            s: &mut [u8; SCALAR_LEN],
+        ) -> Option<u8> {
+            SoftSecp256k1::sign(secret, digest, r, s).map(|v| v ^ 1)
+        }
+
+        fn verify(
+            public_key: &[u8; UNCOMPRESSED_PUBKEY_LEN],
+            digest: &[u8; DIGEST_LEN],
+            der: &[u8],
+        ) -> bool {
+            SoftSecp256k1::verify(public_key, digest, der)
+        }
+
+        fn recovery_id(
+            public_key: &[u8; UNCOMPRESSED_PUBKEY_LEN],
+            digest: &[u8; DIGEST_LEN],
+            r: &[u8; SCALAR_LEN],
+            s: &[u8; SCALAR_LEN],
+        ) -> Option<u8> {
+            SoftSecp256k1::recovery_id(public_key, digest, r, s)
+        }
+    }
+
+    // a backend that signs with another key than the one of the secret
+    struct OtherKey;
+
+    impl Secp256k1 for OtherKey {
+        fn public_key(
+            secret: &[u8; SECRET_KEY_LEN],
+            out: &mut [u8; UNCOMPRESSED_PUBKEY_LEN],
+        ) -> bool {
+            SoftSecp256k1::public_key(secret, out)
+        }
+
+        fn sign(
+            _secret: &[u8; SECRET_KEY_LEN],
+            digest: &[u8; DIGEST_LEN],
+            r: &mut [u8; SCALAR_LEN],
+            s: &mut [u8; SCALAR_LEN],
+        ) -> Option<u8> {
+            SoftSecp256k1::sign(&[0x22; SECRET_KEY_LEN], digest, r, s)
+        }
+
+        fn verify(
+            public_key: &[u8; UNCOMPRESSED_PUBKEY_LEN],
+            digest: &[u8; DIGEST_LEN],
+            der: &[u8],
+        ) -> bool {
+            SoftSecp256k1::verify(public_key, digest, der)
+        }
+
+        fn recovery_id(
+            public_key: &[u8; UNCOMPRESSED_PUBKEY_LEN],
+            digest: &[u8; DIGEST_LEN],
+            r: &[u8; SCALAR_LEN],
+            s: &[u8; SCALAR_LEN],
+        ) -> Option<u8> {
+            SoftSecp256k1::recovery_id(public_key, digest, r, s)
+        }
+    }
+
+    #[test]
+    fn test_sign_fails_closed() {
+        assert_eq!(sign_with::<FlippedRecoveryId>(&SECRET), None);
+        assert_eq!(sign_with::<OtherKey>(&SECRET), None);
+    }
+}
@@ -0,0 +1,252 @@
+//! Canonical form of the ECDSA secp256k1 signatures produced by the app.
+//!
+//! The device signs with deterministic RFC6979 nonces over SHA-256, so the
+//! same key and digest always give the same signature. The signature is
+//! then brought to its low-S form, `s <= n / 2`: the high-S one is equally
+//! valid but malleable, and verifiers like libsecp256k1 reject it. Flipping
+//! `s` negates the nonce point, so the recovery id parity flips with it.
+//!
+//! With the `audit` feature, [`audit`] computes the signature a device with
+//! a given seed must return, for integrators to cross-check in CI.
+//!
+//! The operations on the curve are behind [`ecdsa::Secp256k1`], backed by
+//! the cx library on device and by k256 on host builds.
+//!
+//! BIP-340 Schnorr signatures, used for Bitcoin side duties, are in
+//! [`schnorr`].
+
+#[cfg(feature = "audit")]
+pub mod audit;
+pub mod ecdsa;
+pub mod schnorr;
+
+pub const SCALAR_LEN: usize = 32;
+
+/// Longest DER encoding of a signature, with both integers padded
+pub const MAX_DER_LEN: usize = 2 + 2 * (2 + 1 + SCALAR_LEN);
+
+/// Order n of the secp256k1 group, big endian
+pub const SECP256K1_ORDER: [u8; SCALAR_LEN] = [
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xfe,
+    0xba, 0xae, 0xdc, 0xe6, 0xaf, 0x48, 0xa0, 0x3b, 0xbf, 0xd2, 0x5e, 0x8c, 0xd0, 0x36, 0x41, 0x41,
+];
+
+/// n / 2, the largest low-S value
+pub const SECP256K1_HALF_ORDER: [u8; SCALAR_LEN] = [
+    0x7f, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0x5d, 0x57, 0x6e, 0x73, 0x57, 0xa4, 0x50, 0x1d, 0xdf, 0xe9, 0x2f, 0x46, 0x68, 0x1b, 0x20, 0xa0,
+];
+
+/// Bit of the recovery id that holds the parity of the nonce point y
+const RECOVERY_ID_PARITY: u8 = 0x01;
+
+fn is_valid_scalar(x: &[u8; SCALAR_LEN]) -> bool {
+    x.iter().any(|b| *b != 0) && *x < SECP256K1_ORDER
+}
+
+/// Whether s is a valid low-S value, big endian arrays compare as numbers
+pub fn is_low_s(s: &[u8; SCALAR_LEN]) -> bool {
+    is_valid_scalar(s) && *s <= SECP256K1_HALF_ORDER
+}
+
+/// Replaces a high s by n - s and flips the parity of the recovery id,
+/// returns whether the signature was changed
+pub fn normalize_s(s: &mut [u8; SCALAR_LEN], recovery_id: &mut u8) -> bool {
+    if *s <= SECP256K1_HALF_ORDER {
+        return false;
+    }
+
+    let mut borrow = 0u16;
+    for (x, n) in s.iter_mut().zip(SECP256K1_ORDER.iter()).rev() {
+        let diff = (*n as u16).wrapping_sub(*x as u16).wrapping_sub(borrow);
+        *x = diff as u8;
+        borrow = (diff >> 8) & 1;
+    }
+    *recovery_id ^= RECOVERY_ID_PARITY;
+    true
+}
+
+/// Writes a DER INTEGER with the minimal encoding of an unsigned scalar
+fn write_der_integer(x: &[u8; SCALAR_LEN], out: &mut [u8]) -> Option<usize> {
+    let start = x.iter().position(|b| *b != 0).unwrap_or(SCALAR_LEN - 1);
+    let value = x.get(start..)?;
+    let pad = value.first().map(|b| b & 0x80 != 0)? as usize;
+    let len = value.len() + pad;
+
+    let mut bytes = out.iter_mut();
+    *bytes.next()? = 0x02;
+    *bytes.next()? = len as u8;
+    if pad == 1 {
+        *bytes.next()? = 0x00;
+    }
+    for b in value {
+        *bytes.next()? = *b;
+    }
+    Some(2 + len)
+}
+
+/// DER encoding of the signature (r, s), returns the number of bytes written
+/// or None if out is too small
+pub fn der_encode(r: &[u8; SCALAR_LEN], s: &[u8; SCALAR_LEN], out: &mut [u8]) -> Option<usize> {
+    let body = out.get_mut(2..)?;
+    let r_len = write_der_integer(r, body)?;
+    let s_len = write_der_integer(s, body.get_mut(r_len..)?)?;
+    let len = r_len + s_len;
+
+    *out.get_mut(0)? = 0x30;
+    *out.get_mut(1)? = len as u8;
+    Some(2 + len)
+}
+
+/// Brings a signature to its canonical low-S form and writes it DER
+/// encoded into der, returns the encoded length or None if r or s are
+/// not valid scalars or der is too small
+pub fn canonicalize(
+    r: &[u8; SCALAR_LEN],
+    s: &mut [u8; SCALAR_LEN],
+    recovery_id: &mut u8,
+    der: &mut [u8],
+) -> Option<usize> {
+    if !is_valid_scalar(r) || !is_valid_scalar(s) {
+        return None;
+    }
+    normalize_s(s, recovery_id);
+    der_encode(r, s, der)
+}
+
+/// Whether (r, s, recovery_id) and der are the same canonical signature:
+/// r a valid scalar, s low, a recovery id whose x was not reduced and der
+/// the encoding of r and s. Checked on every signature the device produced
+/// before it is returned, along with its verification against the key
+pub fn is_canonical(
+    r: &[u8; SCALAR_LEN],
+    s: &[u8; SCALAR_LEN],
+    recovery_id: u8,
+    der: &[u8],
+) -> bool {
+    let mut expected = [0u8; MAX_DER_LEN];
+    is_valid_scalar(r)
+        && is_low_s(s)
+        && recovery_id & !RECOVERY_ID_PARITY == 0
+        && der_encode(r, s, &mut expected).and_then(|len| expected.get(..len)) == Some(der)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn scalar(hex_str: &str) -> [u8; SCALAR_LEN] {
+        let mut out = [0u8; SCALAR_LEN];
+        hex::decode_to_slice(hex_str, &mut out).unwrap();
+        out
+    }
+
+    fn small(x: u8) -> [u8; SCALAR_LEN] {
+        let mut out = [0u8; SCALAR_LEN];
+        out[SCALAR_LEN - 1] = x;
+        out
+    }
+
+    #[test]
+    fn test_half_order() {
+        // n is odd, so n / 2 doubled plus one is n
+        let mut doubled = [0u8; SCALAR_LEN];
+        let mut carry = 1u16;
+        for (d, h) in doubled.iter_mut().zip(SECP256K1_HALF_ORDER.iter()).rev() {
+            let sum = ((*h as u16) << 1) + carry;
+            *d = sum as u8;
+            carry = sum >> 8;
+        }
+        assert_eq!(doubled, SECP256K1_ORDER);
+    }
+
+    #[test]
+    fn test_normalize_s() {
+        let mut s = SECP256K1_HALF_ORDER;
+        let mut v = 0;
+        assert!(is_low_s(&s));
+        assert!(!normalize_s(&mut s, &mut v));
+        assert_eq!(v, 0);
+
+        // n - (n/2 + 1) = n/2
+        let mut s = SECP256K1_HALF_ORDER;
+        s[SCALAR_LEN - 1] += 1;
+        assert!(!is_low_s(&s));
+        assert!(normalize_s(&mut s, &mut v));
+        assert_eq!(s, SECP256K1_HALF_ORDER);
+        assert_eq!(v, 1);
+
+        // n - (n - 1) = 1, the x reduced bit is kept
+        let mut s = SECP256K1_ORDER;
+        s[SCALAR_LEN - 1] -= 1;
+        let mut v = 3;
+        assert!(normalize_s(&mut s, &mut v));
+        assert_eq!(s, small(1));
+        assert_eq!(v, 2);
+
+        assert!(!is_low_s(&[0u8; SCALAR_LEN]));
+        assert!(!is_low_s(&SECP256K1_ORDER));
+    }
+
+    #[test]
+    fn test_der_encode() {
+        let r = scalar("e5b0c0c6e13e7b4e6bc41b6e1e7f5ab2f1d3ac0a53f5c6d0b4e0d6b8a6a3c5e1");
+        let s = scalar("00000f3d8b41d93bdc8b8de7e4f1b0d7c2d5b2f4bc1e8a67e0d93f2c6a4b8e12");
+        let mut der = [0u8; MAX_DER_LEN];
+        let len = der_encode(&r, &s, &mut der).unwrap();
+
+        // r gets a zero pad byte, the leading zeros of s are dropped
+        let expected = hex::decode(
+            "3043022100e5b0c0c6e13e7b4e6bc41b6e1e7f5ab2f1d3ac0a53f5c6d0b4e0d6b8a6a3c5e1\
+             021e0f3d8b41d93bdc8b8de7e4f1b0d7c2d5b2f4bc1e8a67e0d93f2c6a4b8e12",
+        )
+        .unwrap();
+        assert_eq!(&der[..len], expected.as_slice());
+
+        let mut short = [0u8; 40];
+        assert_eq!(der_encode(&r, &s, &mut short), None);
+    }
+
+    #[test]
+    fn test_canonicalize() {
+        let r = scalar("6ad5d5a6e3d5b8d4e8f4e6e7d8c6b5a4f3e2d1c0b9a8f7e6d5c4b3a29181f0e1");
+        let mut s = SECP256K1_ORDER;
+        s[SCALAR_LEN - 1] -= 2;
+        let mut v = 0;
+        let mut der = [0u8; MAX_DER_LEN];
+        let len = canonicalize(&r, &mut s, &mut v, &mut der).unwrap();
+
+        assert_eq!(s, small(2));
+        assert_eq!(v, 1);
+        assert_eq!(
+            hex::encode(&der[..len]),
+            "302502206ad5d5a6e3d5b8d4e8f4e6e7d8c6b5a4f3e2d1c0b9a8f7e6d5c4b3a29181f0e1020102"
+        );
+
+        let mut zero = [0u8; SCALAR_LEN];
+        assert_eq!(canonicalize(&r, &mut zero, &mut v, &mut der), None);
+    }
+
+    #[test]
+    fn test_is_canonical() {
+        let r = scalar("6ad5d5a6e3d5b8d4e8f4e6e7d8c6b5a4f3e2d1c0b9a8f7e6d5c4b3a29181f0e1");
+        let mut s = SECP256K1_ORDER;
+        s[SCALAR_LEN - 1] -= 2;
+        let high = s;
+        let mut v = 0;
+        let mut der = [0u8; MAX_DER_LEN];
+        let len = canonicalize(&r, &mut s, &mut v, &mut der).unwrap();
+        assert!(is_canonical(&r, &s, v, &der[..len]));
+
+        // any field that does not agree with the others is refused
+        assert!(!is_canonical(&r, &high, v, &der[..len]));
+        assert!(!is_canonical(&r, &s, v | 2, &der[..len]));
+        assert!(!is_canonical(&r, &s, v, &der[..len - 1]));
+        assert!(!is_canonical(&r, &small(3), v, &der[..len]));
+        assert!(!is_canonical(&SECP256K1_ORDER, &s, v, &der[..len]));
+
+        let mut flipped = der;
+        flipped[len - 1] ^= 1;
+        assert!(!is_canonical(&r, &s, v, &flipped[..len]));
+    }
+}
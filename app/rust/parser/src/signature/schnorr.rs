@@ -0,0 +1,88 @@
+//! BIP-340 Schnorr signatures over secp256k1.
+//!
+//! The device signs a 32 bytes message with the untweaked key of the path,
+//! the nonce comes from fresh auxiliary randomness as BIP-340 recommends.
+//! Keys are x-only: the compressed key without its parity byte, the signer
+//! negates the secret when needed so its point has an even y. A signature is
+//! the x coordinate of the nonce point followed by s.
+//!
+//! With the `audit` feature, [`super::audit`] signs the same way on the host
+//! so tooling can produce and check signatures without a device.
+
+use core::convert::TryInto;
+
+use super::{is_valid_scalar, SCALAR_LEN};
+
+pub const SCHNORR_MESSAGE_LEN: usize = 32;
+pub const XONLY_PUBKEY_LEN: usize = 32;
+pub const SCHNORR_SIGNATURE_LEN: usize = 2 * SCALAR_LEN;
+
+/// Prime p of the secp256k1 field, big endian
+pub const SECP256K1_FIELD_PRIME: [u8; SCALAR_LEN] = [
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xfe, 0xff, 0xff, 0xfc, 0x2f,
+];
+
+/// The x-only key of a compressed public key
+pub fn xonly_public_key(compressed: &[u8]) -> Option<&[u8]> {
+    match compressed.split_first() {
+        Some((0x02 | 0x03, x)) if x.len() == XONLY_PUBKEY_LEN => Some(x),
+        _ => None,
+    }
+}
+
+/// Whether signature is encoded as BIP-340 verifiers expect it: r is a
+/// field element and s a non-zero scalar. It does not verify it
+pub fn is_well_formed(signature: &[u8]) -> bool {
+    if signature.len() != SCHNORR_SIGNATURE_LEN {
+        return false;
+    }
+    let (r, s) = signature.split_at(SCALAR_LEN);
+    let r: Result<&[u8; SCALAR_LEN], _> = r.try_into();
+    let s: Result<&[u8; SCALAR_LEN], _> = s.try_into();
+    match (r, s) {
+        (Ok(r), Ok(s)) => *r < SECP256K1_FIELD_PRIME && is_valid_scalar(s),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // test vector 0 of BIP-340
+    const SIGNATURE: &str = "e907831f80848d1069a5371b402410364bdf1c5f8307b0084c55f1ce2dca8215\
+                             25f66a4a85ea8b71e482a74f382d2ce5ebeee8fdb2172f477df4900d310536c0";
+
+    #[test]
+    fn test_xonly_public_key() {
+        let compressed =
+            hex::decode("0252dab95065cd31ae6f8ece65fffd2e904b203268a5923fa85e5db793698d753a")
+                .unwrap();
+        assert_eq!(xonly_public_key(&compressed), Some(&compressed[1..]));
+
+        let mut odd = compressed.clone();
+        odd[0] = 0x03;
+        assert_eq!(xonly_public_key(&odd), Some(&compressed[1..]));
+
+        let mut uncompressed = compressed.clone();
+        uncompressed[0] = 0x04;
+        assert_eq!(xonly_public_key(&uncompressed), None);
+        assert_eq!(xonly_public_key(&compressed[..32]), None);
+    }
+
+    #[test]
+    fn test_is_well_formed() {
+        let signature = hex::decode(SIGNATURE).unwrap();
+        assert!(is_well_formed(&signature));
+        assert!(!is_well_formed(&signature[1..]));
+
+        let mut r_not_in_field = signature.clone();
+        r_not_in_field[..SCALAR_LEN].copy_from_slice(&SECP256K1_FIELD_PRIME);
+        assert!(!is_well_formed(&r_not_in_field));
+
+        let mut zero_s = signature;
+        zero_s[SCALAR_LEN..].iter_mut().for_each(|b| *b = 0);
+        assert!(!is_well_formed(&zero_s));
+    }
+}
@@ -0,0 +1,180 @@
+//! Stack usage high-water mark of the parser entry points.
+//!
+//! Every check_canary!() also records how deep the stack is at that point,
+//! so the usage of the deepest paths (Clarity values nesting, c32 encoding)
+//! can be read back from a device build and regressions are caught before
+//! they overflow the Nano S stack.
+
+#[repr(u8)]
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(test, derive(Debug))]
+pub enum EntryPoint {
+    Read = 0,
+    NumItems = 1,
+    GetItem = 2,
+}
+
+pub const NUM_ENTRY_POINTS: usize = 3;
+
+#[derive(Clone, Copy)]
+struct Watermark {
+    // stack address when the current entry point was called, 0 if none
+    base: usize,
+    // lowest stack address seen since then
+    lowest: usize,
+    // the most stack ever used by each entry point
+    high: [u32; NUM_ENTRY_POINTS],
+}
+
+const EMPTY: Watermark = Watermark {
+    base: 0,
+    lowest: 0,
+    high: [0; NUM_ENTRY_POINTS],
+};
+
+#[cfg(all(feature = "device", not(test)))]
+fn with_watermark<R>(f: impl FnOnce(&mut Watermark) -> R) -> R {
+    static mut WATERMARK: Watermark = EMPTY;
+    // safety: the app is single threaded and f does not reenter
+    unsafe { f(&mut *core::ptr::addr_of_mut!(WATERMARK)) }
+}
+
+// tests run in parallel, each one measures its own stack
+#[cfg(any(test, not(feature = "device")))]
+fn with_watermark<R>(f: impl FnOnce(&mut Watermark) -> R) -> R {
+    extern crate std as host;
+    use core::cell::Cell;
+
+    host::thread_local! {
+        static WATERMARK: Cell<Watermark> = const { Cell::new(EMPTY) };
+    }
+    WATERMARK.with(|cell| {
+        let mut watermark = cell.get();
+        let res = f(&mut watermark);
+        cell.set(watermark);
+        res
+    })
+}
+
+// the address of a local is close enough to the stack pointer
+#[inline(always)]
+fn stack_pointer() -> usize {
+    let marker = 0u8;
+    core::hint::black_box(&marker) as *const u8 as usize
+}
+
+/// Records the current stack depth, the stack grows downwards
+#[inline(always)]
+pub fn mark() {
+    let sp = stack_pointer();
+    with_watermark(|w| {
+        if w.base != 0 && sp < w.lowest {
+            w.lowest = sp;
+        }
+    });
+}
+
+/// Measures the stack used until the returned guard is dropped
+#[inline(always)]
+pub fn enter(entry: EntryPoint) -> EntryGuard {
+    let sp = stack_pointer();
+    with_watermark(|w| {
+        let guard = EntryGuard {
+            entry,
+            outer_base: w.base,
+            outer_lowest: w.lowest,
+        };
+        w.base = sp;
+        w.lowest = sp;
+        guard
+    })
+}
+
+pub struct EntryGuard {
+    entry: EntryPoint,
+    // the measure of the entry point this one was called from, if any
+    outer_base: usize,
+    outer_lowest: usize,
+}
+
+impl Drop for EntryGuard {
+    fn drop(&mut self) {
+        with_watermark(|w| {
+            let used = w.base.saturating_sub(w.lowest) as u32;
+            let high = &mut w.high[self.entry as usize];
+            if used > *high {
+                *high = used;
+            }
+
+            // the outer entry point keeps measuring
+            w.lowest = w.lowest.min(self.outer_lowest);
+            w.base = self.outer_base;
+        });
+    }
+}
+
+/// The most stack used by the entry point since the last reset
+pub fn watermark(entry: EntryPoint) -> u32 {
+    with_watermark(|w| w.high[entry as usize])
+}
+
+pub fn reset() {
+    with_watermark(|w| w.high = [0; NUM_ENTRY_POINTS]);
+}
+
+impl EntryPoint {
+    pub fn from_u8(v: u8) -> Option<Self> {
+        match v {
+            0 => Some(Self::Read),
+            1 => Some(Self::NumItems),
+            2 => Some(Self::GetItem),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[inline(never)]
+    fn deep(depth: usize) -> usize {
+        let buf = core::hint::black_box([depth as u8; 64]);
+        if depth == 0 {
+            crate::canary();
+            return buf[0] as usize;
+        }
+        deep(depth - 1) + buf[1] as usize
+    }
+
+    fn measure(depth: usize) -> u32 {
+        reset();
+        {
+            let _guard = enter(EntryPoint::GetItem);
+            deep(depth);
+        }
+        watermark(EntryPoint::GetItem)
+    }
+
+    #[test]
+    fn test_stack_watermark() {
+        let shallow = measure(1);
+        let deeper = measure(10);
+        assert!(shallow > 0);
+        assert!(deeper >= shallow + 9 * 64);
+
+        // an inner entry point also counts for the outer one
+        reset();
+        {
+            let _outer = enter(EntryPoint::GetItem);
+            let _inner = enter(EntryPoint::Read);
+            deep(10);
+        }
+        assert!(watermark(EntryPoint::GetItem) >= watermark(EntryPoint::Read));
+        assert!(watermark(EntryPoint::Read) > 0);
+        assert_eq!(watermark(EntryPoint::NumItems), 0);
+
+        reset();
+        assert_eq!(watermark(EntryPoint::GetItem), 0);
+    }
+}
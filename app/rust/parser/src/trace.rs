@@ -0,0 +1,109 @@
+//! Parse trace of emulator builds.
+//!
+//! With the `trace` feature Transaction::read reports every section it
+//! enters, the bytes the section took and the error it failed with, one
+//! line per event through zemu_log. APP_TESTING builds have it, Speculos
+//! prints the lines on its stderr through semihosting, so the field a
+//! malformed transaction is rejected at shows up without adding printfs to
+//! the C side. Without the feature emit does nothing.
+
+use core::fmt::Write;
+
+use crate::parser::{ParseSection, ParserError};
+use crate::zxformat::Writer;
+
+/// Longest line of an event, null terminator included
+pub const TRACE_LINE_LEN: usize = 80;
+
+#[derive(Clone, Copy, PartialEq)]
+#[cfg_attr(test, derive(Debug))]
+pub enum Event {
+    /// The parser starts reading a section
+    Enter(ParseSection),
+    /// A section was read, it took that many bytes
+    Consumed(ParseSection, usize),
+    /// A section could not be read
+    Error(ParseSection, ParserError),
+}
+
+fn section_name(section: ParseSection) -> &'static str {
+    match section {
+        ParseSection::Header => "header",
+        ParseSection::Auth => "auth",
+        ParseSection::PostConditions => "post conditions",
+        ParseSection::Payload => "payload",
+    }
+}
+
+impl Event {
+    /// Writes the line of the event into out, null terminated.
+    /// Returns its length without the terminator, None if out is too short
+    pub fn write_line(&self, out: &mut [u8]) -> Option<usize> {
+        let mut writer = Writer::new(out);
+        let written = match self {
+            Event::Enter(section) => writeln!(writer, "parse {}: enter", section_name(*section)),
+            Event::Consumed(section, len) => {
+                writeln!(writer, "parse {}: {} bytes", section_name(*section), len)
+            }
+            Event::Error(section, e) => {
+                writeln!(writer, "parse {}: error {:?}", section_name(*section), e)
+            }
+        };
+        written.ok()?;
+        let len = writer.offset;
+        *out.get_mut(len)? = 0;
+        Some(len)
+    }
+}
+
+#[cfg(feature = "trace")]
+pub fn emit(event: Event) {
+    let mut line = [0u8; TRACE_LINE_LEN];
+    if event.write_line(&mut line).is_some() {
+        crate::bolos::c_zemu_log(line);
+    }
+}
+
+#[cfg(not(feature = "trace"))]
+#[inline(always)]
+pub fn emit(_event: Event) {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn line(event: Event) -> std::string::String {
+        let mut out = [0xffu8; TRACE_LINE_LEN];
+        let len = event.write_line(&mut out).unwrap();
+        assert_eq!(out[len], 0);
+        std::string::String::from_utf8(out[..len].to_vec()).unwrap()
+    }
+
+    #[test]
+    fn test_event_lines() {
+        assert_eq!(
+            line(Event::Enter(ParseSection::Auth)),
+            "parse auth: enter\n"
+        );
+        assert_eq!(
+            line(Event::Consumed(ParseSection::PostConditions, 74)),
+            "parse post conditions: 74 bytes\n"
+        );
+        assert_eq!(
+            line(Event::Error(
+                ParseSection::PostConditions,
+                ParserError::parser_invalid_post_condition_principal
+            )),
+            "parse post conditions: error parser_invalid_post_condition_principal\n"
+        );
+    }
+
+    #[test]
+    fn test_short_buffer() {
+        let event = Event::Enter(ParseSection::Header);
+        let mut out = [0u8; 21];
+        // the line fits but not its terminator
+        assert_eq!(event.write_line(&mut out[..20]), None);
+        assert_eq!(event.write_line(&mut out), Some(20));
+    }
+}
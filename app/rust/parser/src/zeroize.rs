@@ -0,0 +1,115 @@
+//! Clearing of the buffers that hold key material, sighashes and
+//! signatures once they are no longer needed.
+//!
+//! A plain `fill(0)` on a buffer that is not read again is a dead store
+//! the compiler is free to remove, the writes here are volatile so they
+//! are always kept.
+
+use core::ops::{Deref, DerefMut};
+use core::sync::atomic::{compiler_fence, Ordering};
+
+/// A value whose contents can be overwritten with zeros
+pub trait Zeroize {
+    fn zeroize(&mut self);
+}
+
+impl Zeroize for [u8] {
+    fn zeroize(&mut self) {
+        for byte in self.iter_mut() {
+            unsafe { core::ptr::write_volatile(byte, 0) }
+        }
+        // keep the writes from being reordered after the buffer is reused
+        compiler_fence(Ordering::SeqCst);
+    }
+}
+
+impl<const N: usize> Zeroize for [u8; N] {
+    fn zeroize(&mut self) {
+        (self as &mut [u8]).zeroize()
+    }
+}
+
+impl Zeroize for u64 {
+    fn zeroize(&mut self) {
+        unsafe { core::ptr::write_volatile(self, 0) }
+        compiler_fence(Ordering::SeqCst);
+    }
+}
+
+impl<const N: usize> Zeroize for [u64; N] {
+    fn zeroize(&mut self) {
+        self.iter_mut().for_each(Zeroize::zeroize)
+    }
+}
+
+/// Wraps a value that is zeroized when it goes out of scope,
+/// including on the early returns of the `?` operator
+pub struct Zeroizing<T: Zeroize>(T);
+
+impl<T: Zeroize> Zeroizing<T> {
+    pub fn new(value: T) -> Self {
+        Self(value)
+    }
+}
+
+impl<T: Zeroize> Deref for Zeroizing<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: Zeroize> DerefMut for Zeroizing<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T: Zeroize> Drop for Zeroizing<T> {
+    fn drop(&mut self) {
+        self.0.zeroize()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_zeroize_array() {
+        let mut buf = [0xffu8; 32];
+        buf.zeroize();
+        assert_eq!(buf, [0u8; 32]);
+
+        let mut words = [u64::MAX; 4];
+        words.zeroize();
+        assert_eq!(words, [0; 4]);
+    }
+
+    #[test]
+    fn test_zeroize_slice() {
+        let mut buf = [0xffu8; 8];
+        buf[2..6].zeroize();
+        assert_eq!(buf, [0xff, 0xff, 0, 0, 0, 0, 0xff, 0xff]);
+    }
+
+    struct Borrowed<'a>(&'a mut [u8; 16]);
+
+    impl Zeroize for Borrowed<'_> {
+        fn zeroize(&mut self) {
+            self.0.zeroize()
+        }
+    }
+
+    #[test]
+    fn test_zeroizing_drop() {
+        let mut buf = [0xffu8; 16];
+        {
+            let wrapped = Zeroizing::new(Borrowed(&mut buf));
+            let Borrowed(inner) = &*wrapped;
+            assert_eq!(**inner, [0xffu8; 16]);
+        }
+        assert_eq!(buf, [0u8; 16]);
+    }
+}
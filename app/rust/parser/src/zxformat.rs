@@ -2,10 +2,14 @@
 
 use core::fmt::{self, Write};
 
-use crate::parser::ParserError;
+use crate::is_stx_units;
+use crate::parser::{ParserError, STX_DECIMALS};
 
-#[cfg(not(any(test, fuzzing)))]
-use crate::parser::fp_uint64_to_str;
+// extern c function for formatting to fixed point number
+#[cfg(all(feature = "device", not(test)))]
+extern "C" {
+    fn fp_uint64_to_str(out: *mut i8, outLen: u16, value: u64, decimals: u8) -> u16;
+}
 
 pub const MAX_STR_BUFF_LEN: usize = 30;
 
@@ -23,13 +27,12 @@ impl<'a> Writer<'a> {
 impl<'a> fmt::Write for Writer<'a> {
     fn write_str(&mut self, s: &str) -> fmt::Result {
         let bytes = s.as_bytes();
-        let remainder = &mut self.buf[self.offset..];
-
-        if remainder.len() < bytes.len() {
-            return Err(core::fmt::Error);
+        let remainder = self
+            .buf
+            .get_mut(self.offset..)
+            .and_then(|remainder| remainder.get_mut(..bytes.len()))
             // overflow wit zero decimals
-        }
-        let remainder = &mut remainder[..bytes.len()];
+            .ok_or(core::fmt::Error)?;
         remainder.copy_from_slice(bytes);
         // Update offset to avoid overwriting
         self.offset += bytes.len();
@@ -47,7 +50,7 @@ macro_rules! num_to_str {
 
             let len;
 
-            #[cfg(any(test, fuzzing))]
+            #[cfg(any(test, not(feature = "device")))]
             {
                 let mut writer = Writer::new(output);
                 core::write!(writer, "{}", number)
@@ -55,7 +58,7 @@ macro_rules! num_to_str {
                 len = writer.offset;
             }
 
-            #[cfg(not(any(test, fuzzing)))]
+            #[cfg(all(feature = "device", not(test)))]
             {
                 // We add this path here because pic issues with the write! trait
                 // so that it is preferable to use the c implementation when running on
@@ -90,7 +93,10 @@ num_to_str!(i64_to_str, i64);
 pub fn fpu64_to_str(out: &mut [u8], value: u64, decimals: u8) -> Result<usize, ParserError> {
     let mut temp = [0u8; MAX_STR_BUFF_LEN];
     let len = u64_to_str(temp.as_mut(), value)?;
-    fpstr_to_str(out, &temp[..len], decimals)
+    let value = temp
+        .get(..len)
+        .ok_or(ParserError::parser_unexpected_buffer_end)?;
+    fpstr_to_str(out, value, decimals)
 }
 
 /// Fixed point u64 number with native/test support
@@ -127,7 +133,44 @@ pub fn fpu64_to_str_check_test(
 pub fn fpi64_to_str(out: &mut [u8], value: i64, decimals: u8) -> Result<usize, ParserError> {
     let mut temp = [0u8; MAX_STR_BUFF_LEN];
     let len = i64_to_str(temp.as_mut(), value)?;
-    fpstr_to_str(out, &temp[..len], decimals)
+    let value = temp
+        .get(..len)
+        .ok_or(ParserError::parser_unexpected_buffer_end)?;
+    fpstr_to_str(out, value, decimals)
+}
+
+/// Writes an amount of uSTX, given as its decimal digits, in the unit
+/// amounts are shown in, uSTX or STX with STX_DECIMALS decimals
+pub fn stx_digits_to_str(out: &mut [u8], digits: &[u8]) -> Result<usize, ParserError> {
+    fpstr_to_str(out, digits, stx_decimals())
+}
+
+/// The decimals of the unit amounts are shown in
+pub fn stx_decimals() -> u8 {
+    if is_stx_units() {
+        STX_DECIMALS
+    } else {
+        0
+    }
+}
+
+/// Writes an amount of uSTX in the unit amounts are shown in
+pub fn stx_amount_to_str(out: &mut [u8], amount: u64) -> Result<usize, ParserError> {
+    let mut temp = [0u8; MAX_STR_BUFF_LEN];
+    let len = u64_to_str(temp.as_mut(), amount)?;
+    let digits = temp
+        .get(..len)
+        .ok_or(ParserError::parser_unexpected_buffer_end)?;
+    stx_digits_to_str(out, digits)
+}
+
+/// Picks the key of an amount item for the unit amounts are shown in
+pub fn stx_label(ustx: &'static str, stx: &'static str) -> &'static str {
+    if is_stx_units() {
+        stx
+    } else {
+        ustx
+    }
 }
 
 pub(crate) fn fpstr_to_str(
@@ -179,53 +222,140 @@ pub(crate) fn fpstr_to_str(
     }
 
     let fp = in_len - decimals as usize;
-    let left = str.get(0..fp).unwrap();
-    let right = str.get(fp..in_len).unwrap();
+    let left = str
+        .get(0..fp)
+        .ok_or(ParserError::parser_unexpected_characters)?;
+    let right = str
+        .get(fp..in_len)
+        .ok_or(ParserError::parser_unexpected_characters)?;
     write!(&mut writer, "{}.{}", left, right)
         .map(|_| writer.offset)
         .map_err(|_| ParserError::parser_unexpected_buffer_end)
 }
 
+/// Returns the number of pages of out_len bytes needed for in_len bytes,
+/// and the range of the input shown in the page_idx page
+fn page_range(
+    out_len: usize,
+    in_len: usize,
+    page_idx: u8,
+) -> Result<(u8, core::ops::Range<usize>), ParserError> {
+    if out_len == 0 || in_len == 0 {
+        return Err(ParserError::parser_no_data);
+    }
+    let mut page_count = (in_len / out_len) as u8;
+    let last_chunk_len = in_len % out_len;
+
+    if last_chunk_len > 0 {
+        page_count += 1;
+    }
+
+    if page_idx >= page_count {
+        return Ok((page_count, 0..0));
+    }
+
+    let idx = page_idx as usize * out_len;
+    let last = if last_chunk_len > 0 && page_idx == page_count - 1 {
+        idx + last_chunk_len
+    } else {
+        idx + out_len
+    };
+    Ok((page_count, idx..last))
+}
+
 #[inline(never)]
 pub fn pageString(out_value: &mut [u8], in_value: &[u8], page_idx: u8) -> Result<u8, ParserError> {
     // Just ensure the buffer is clear
     for i in out_value.iter_mut() {
         *i = 0u8;
     }
-    let mut page_count;
-    let out_len = out_value.len() - 1;
-    let output = &mut out_value[..out_len];
+    let out_len = out_value.len().saturating_sub(1);
 
-    let in_len = in_value.len();
+    let (page_count, range) = page_range(out_len, in_value.len(), page_idx)?;
+    let page = in_value
+        .get(range)
+        .ok_or(ParserError::parser_unexpected_buffer_end)?;
+    out_value
+        .get_mut(..page.len())
+        .ok_or(ParserError::parser_unexpected_buffer_end)?
+        .copy_from_slice(page);
 
-    if out_len == 0 || in_len == 0 {
+    Ok(page_count)
+}
+
+/// Same as pageString, but the input bytes are produced by at on demand
+/// for the in_len positions, so the input never has to be in memory
+pub fn pageWith(
+    out_value: &mut [u8],
+    in_len: usize,
+    page_idx: u8,
+    at: impl Fn(usize) -> u8,
+) -> Result<u8, ParserError> {
+    for i in out_value.iter_mut() {
+        *i = 0u8;
+    }
+    let out_len = out_value.len().saturating_sub(1);
+
+    let (page_count, range) = page_range(out_len, in_len, page_idx)?;
+    for (out, pos) in out_value.iter_mut().zip(range) {
+        *out = at(pos);
+    }
+
+    Ok(page_count)
+}
+
+const HEX_CHARS: &[u8; 16] = b"0123456789abcdef";
+
+/// Writes the page_idx page of the hex encoding of in_value,
+/// pages are split on byte boundaries
+#[inline(never)]
+pub fn pageHex(out_value: &mut [u8], in_value: &[u8], page_idx: u8) -> Result<u8, ParserError> {
+    for i in out_value.iter_mut() {
+        *i = 0u8;
+    }
+
+    // two characters per byte plus the null terminator
+    let bytes_per_page = out_value.len().saturating_sub(1) / 2;
+    if bytes_per_page == 0 || in_value.is_empty() {
         return Err(ParserError::parser_no_data);
     }
-    page_count = (in_len / out_len) as u8;
-    let last_chunk_len = in_len % out_len;
 
-    if last_chunk_len > 0 {
-        page_count += 1;
+    let page_count = in_value.len().div_ceil(bytes_per_page);
+    if page_count > u8::MAX as usize {
+        return Err(ParserError::parser_value_out_of_range);
     }
 
-    if page_idx < page_count {
-        let idx = page_idx as usize * out_len;
-        let last = if last_chunk_len > 0 && page_idx == page_count - 1 {
-            idx + last_chunk_len
-        } else {
-            idx + out_len
-        };
-        let len = last - idx;
-        (output[..len]).copy_from_slice(&in_value[idx..last]);
+    let start = page_idx as usize * bytes_per_page;
+    let chunk = in_value.get(start..).unwrap_or_default();
+    let mut out = out_value.iter_mut();
+    for byte in chunk.iter().take(bytes_per_page) {
+        if let (Some(high), Some(low)) = (out.next(), out.next()) {
+            *high = HEX_CHARS[(byte >> 4) as usize];
+            *low = HEX_CHARS[(byte & 0x0f) as usize];
+        }
     }
 
-    Ok(page_count)
+    Ok(page_count as u8)
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
 
+    #[test]
+    fn test_page_hex() {
+        let input = [0x00u8, 0x1f, 0xab, 0xff, 0x42];
+        let mut output = [0u8; 5];
+
+        assert_eq!(pageHex(output.as_mut(), &input, 0).unwrap(), 3);
+        assert_eq!(&output, b"001f\0");
+        pageHex(output.as_mut(), &input, 1).unwrap();
+        assert_eq!(&output, b"abff\0");
+        pageHex(output.as_mut(), &input, 2).unwrap();
+        assert_eq!(&output, b"42\0\0\0");
+        assert!(pageHex(output.as_mut(), &[], 0).is_err());
+    }
+
     #[test]
     fn test_u64_to_str() {
         let mut output = [0u8; 10];
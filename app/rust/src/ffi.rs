@@ -0,0 +1,1358 @@
+#![allow(non_camel_case_types, non_snake_case, clippy::missing_safety_doc)]
+#![allow(clippy::cast_ptr_alignment)]
+
+use core::convert::TryFrom;
+use core::ffi::{c_char, c_void};
+
+use stacks_ledger_parser::account::write_account_entry;
+use stacks_ledger_parser::btc::key_address;
+use stacks_ledger_parser::hash::{DefaultSha512_256, SHA512_256_LEN};
+use stacks_ledger_parser::multisig::write_multisig_slot;
+use stacks_ledger_parser::parser::{
+    c32::{c32_address, C32Address},
+    signature_auth_field, CodeScan, FunctionAbi, HashMode, ParsedObj, ParserError, SighashStream,
+    SignerId, Tag, TokenInfo, CODE_SCAN_LEN, HASH160_LEN, SIGNATURE_AUTH_FIELD_LEN, SIGNATURE_LEN,
+};
+use stacks_ledger_parser::path::{check_path_policy, is_standard_path, read_path, PathKind};
+use stacks_ledger_parser::settings::{SettingId, Settings, SETTINGS_BLOB_LEN};
+use stacks_ledger_parser::signature::ecdsa::{self, DefaultSecp256k1};
+use stacks_ledger_parser::signature::{schnorr, SCALAR_LEN};
+use stacks_ledger_parser::stack::{self, EntryPoint};
+use stacks_ledger_parser::zeroize::Zeroize;
+
+/// A ParserError, the parser_error_t enum of parser_common.h
+pub type parser_error_t = u32;
+
+/// A Tag, the transaction_type_t enum of parser_common.h
+pub type transaction_type_t = u32;
+
+/// Bytes of the scan of a contract code body for what it can do with assets
+pub const RS_CODE_SCAN_LEN: u16 = 21;
+
+const _: () = assert!(RS_CODE_SCAN_LEN as usize == CODE_SCAN_LEN);
+
+/// Bytes of the settings blob kept in flash
+pub const RS_SETTINGS_BLOB_LEN: u16 = 13;
+
+const _: () = assert!(RS_SETTINGS_BLOB_LEN as usize == SETTINGS_BLOB_LEN);
+
+#[repr(C)]
+pub struct parser_context_t {
+    pub buffer: *const u8,
+    pub bufferLen: u16,
+    pub offset: u16,
+}
+
+#[repr(C)]
+pub struct parse_tx_t {
+    state: *mut u8,
+    len: u16,
+}
+
+/// #Safety
+/// Enough space was allocated to store a ParsedObj
+unsafe fn parsed_obj_from_state<'a>(tx: *mut parse_tx_t) -> Option<&'a mut ParsedObj<'a>> {
+    ((*tx).state as *const u8 as *mut ParsedObj).as_mut()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn _parser_init(
+    ctx: *mut parser_context_t,
+    buffer: *const u8,
+    bufferSize: u16,
+    alloc_size: *mut u16,
+) -> parser_error_t {
+    // Lets the caller know how much memory we need for allocating
+    // our global state
+    if alloc_size.is_null() {
+        return ParserError::parser_no_memory_for_state as u32;
+    }
+    *alloc_size = core::mem::size_of::<ParsedObj>() as u16;
+    parser_init_context(ctx, buffer, bufferSize) as u32
+}
+
+/// #Safety
+/// Called after zb_allocate assign memory
+/// to store the ParsedObj. This memory outlives
+/// the parsed and is deallocated before signing
+/// at such point the rust-parser is not used anymore
+unsafe fn parser_init_context(
+    ctx: *mut parser_context_t,
+    buffer: *const u8,
+    bufferSize: u16,
+) -> ParserError {
+    (*ctx).offset = 0;
+
+    if bufferSize == 0 || buffer.is_null() {
+        (*ctx).buffer = core::ptr::null_mut();
+        (*ctx).bufferLen = 0;
+        return ParserError::parser_init_context_empty;
+    }
+
+    (*ctx).buffer = buffer;
+    (*ctx).bufferLen = bufferSize;
+    ParserError::parser_ok
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn _read(
+    context: *const parser_context_t,
+    parser_state: *mut parse_tx_t,
+) -> parser_error_t {
+    let data = core::slice::from_raw_parts((*context).buffer, (*context).bufferLen as _);
+
+    if let Some(obj) = parsed_obj_from_state(parser_state) {
+        match obj.read(data) {
+            Ok(_) => ParserError::parser_ok as u32,
+            Err(e) => e as u32,
+        }
+    } else {
+        ParserError::parser_no_memory_for_state as u32
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn _getNumItems(
+    _ctx: *const parser_context_t,
+    tx_t: *const parse_tx_t,
+    num_items: *mut u8,
+) -> parser_error_t {
+    if tx_t.is_null() || (*tx_t).state.is_null() || num_items.is_null() {
+        return ParserError::parser_no_data as u32;
+    }
+    if let Some(obj) = parsed_obj_from_state(tx_t as _) {
+        match obj.num_items() {
+            Ok(n) => {
+                *num_items = n;
+                ParserError::parser_ok as u32
+            }
+            Err(e) => e as u32,
+        }
+    } else {
+        ParserError::parser_no_data as u32
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn _getItem(
+    _ctx: *const parser_context_t,
+    displayIdx: u8,
+    outKey: *mut c_char,
+    outKeyLen: u16,
+    outValue: *mut c_char,
+    outValueLen: u16,
+    pageIdx: u8,
+    pageCount: *mut u8,
+    tx_t: *const parse_tx_t,
+) -> parser_error_t {
+    *pageCount = 0u8;
+    let page_count = &mut *pageCount;
+    let key = core::slice::from_raw_parts_mut(outKey as *mut u8, outKeyLen as usize);
+    let value = core::slice::from_raw_parts_mut(outValue as *mut u8, outValueLen as usize);
+    if tx_t.is_null() || (*tx_t).state.is_null() {
+        return ParserError::parser_context_mismatch as _;
+    }
+    if let Some(obj) = parsed_obj_from_state(tx_t as _) {
+        match obj.get_item(displayIdx, key, value, pageIdx) {
+            Ok(page) => {
+                *page_count = page;
+                ParserError::parser_ok as _
+            }
+            Err(e) => e as _,
+        }
+    } else {
+        ParserError::parser_context_mismatch as _
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn _getItemKind(
+    tx_t: *const parse_tx_t,
+    displayIdx: u8,
+    kind: *mut u8,
+) -> parser_error_t {
+    if tx_t.is_null() || (*tx_t).state.is_null() || kind.is_null() {
+        return ParserError::parser_context_mismatch as _;
+    }
+    if let Some(obj) = parsed_obj_from_state(tx_t as _) {
+        match obj.item_kind(displayIdx) {
+            Ok(k) => {
+                *kind = k as u8;
+                ParserError::parser_ok as _
+            }
+            Err(e) => e as _,
+        }
+    } else {
+        ParserError::parser_context_mismatch as _
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn _getItemSection(
+    tx_t: *const parse_tx_t,
+    displayIdx: u8,
+    section: *mut u8,
+) -> parser_error_t {
+    if tx_t.is_null() || (*tx_t).state.is_null() || section.is_null() {
+        return ParserError::parser_context_mismatch as _;
+    }
+    if let Some(obj) = parsed_obj_from_state(tx_t as _) {
+        match obj.item_section(displayIdx) {
+            Ok(s) => {
+                *section = s as u8;
+                ParserError::parser_ok as _
+            }
+            Err(e) => e as _,
+        }
+    } else {
+        ParserError::parser_context_mismatch as _
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn _auth_flag(tx_t: *const parse_tx_t, auth_flag: *mut u8) -> parser_error_t {
+    if let Some(tx) = parsed_obj_from_state(tx_t as _).and_then(|obj| obj.transaction()) {
+        *auth_flag = tx.auth_flag() as u8;
+        ParserError::parser_ok as _
+    } else {
+        ParserError::parser_context_mismatch as _
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn _fee_bytes(tx_t: *const parse_tx_t, fee: *mut u8, fee_len: u16) -> u8 {
+    if let Some(tx) = parsed_obj_from_state(tx_t as _).and_then(|obj| obj.transaction()) {
+        let fee_bytes = if let Some(fee) = tx.fee() {
+            fee.to_be_bytes()
+        } else {
+            return 0;
+        };
+
+        if fee_bytes.len() <= fee_len as usize {
+            fee.copy_from(fee_bytes.as_ptr(), fee_bytes.len());
+            return fee_bytes.len() as u8;
+        }
+    }
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn _nonce_bytes(
+    tx_t: *const parse_tx_t,
+    nonce: *mut u8,
+    nonce_len: u16,
+) -> u8 {
+    if let Some(tx) = parsed_obj_from_state(tx_t as _).and_then(|obj| obj.transaction()) {
+        let nonce_bytes = if let Some(nonce) = tx.nonce() {
+            nonce.to_be_bytes()
+        } else {
+            return 0;
+        };
+
+        if nonce_bytes.len() <= nonce_len as usize {
+            nonce.copy_from(nonce_bytes.as_ptr(), nonce_bytes.len());
+            return nonce_bytes.len() as u8;
+        }
+    }
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn _check_pubkey_hash(
+    tx_t: *const parse_tx_t,
+    pubKey: *const u8,
+    pubKeyLen: u16,
+) -> parser_error_t {
+    if let Some(tx) = parsed_obj_from_state(tx_t as _).and_then(|obj| obj.transaction()) {
+        if pubKey.is_null() {
+            return ParserError::parser_no_data as _;
+        }
+        let pk = core::slice::from_raw_parts(pubKey, pubKeyLen as _);
+        tx.check_signer_pk_hash(pk) as _
+    } else {
+        ParserError::parser_context_mismatch as _
+    }
+}
+
+/// Checks pubKey, the compressed key of this device, against a multisig
+/// origin, account is the hash160 of the last template the key was found
+/// in, if any. A key that can not be matched to the origin is warned about
+#[no_mangle]
+pub unsafe extern "C" fn _check_multisig_signer(
+    tx_t: *const parse_tx_t,
+    pubKey: *const u8,
+    pubKeyLen: u16,
+    account: *const u8,
+    accountLen: u16,
+) -> parser_error_t {
+    if pubKey.is_null() {
+        return ParserError::parser_no_data as _;
+    }
+    if let Some(tx) = parsed_obj_from_state(tx_t as _).and_then(|obj| obj.transaction()) {
+        let pk = core::slice::from_raw_parts(pubKey, pubKeyLen as _);
+        let account = (!account.is_null())
+            .then(|| core::slice::from_raw_parts(account, accountLen as _));
+        tx.check_multisig_signer(pk, account) as _
+    } else {
+        ParserError::parser_context_mismatch as _
+    }
+}
+
+/// Writes the index of the auth field of the multisig origin the signature
+/// of pubKey, the compressed key of this device, goes in
+#[no_mangle]
+pub unsafe extern "C" fn _multisig_auth_field_index(
+    tx_t: *const parse_tx_t,
+    pubKey: *const u8,
+    pubKeyLen: u16,
+    index: *mut u32,
+) -> parser_error_t {
+    if pubKey.is_null() || index.is_null() {
+        return ParserError::parser_no_data as _;
+    }
+    if let Some(tx) = parsed_obj_from_state(tx_t as _).and_then(|obj| obj.transaction()) {
+        let pk = core::slice::from_raw_parts(pubKey, pubKeyLen as _);
+        match tx.multisig_auth_field_index(pk) {
+            Ok(idx) => {
+                *index = idx;
+                ParserError::parser_ok as _
+            }
+            Err(e) => e as _,
+        }
+    } else {
+        ParserError::parser_context_mismatch as _
+    }
+}
+
+/// Checks the sponsor is the key whose hash160 is given, after the origin
+/// was checked with _check_pubkey_hash, for a device signing as both
+#[no_mangle]
+pub unsafe extern "C" fn _check_sponsor_pubkey_hash(
+    tx_t: *const parse_tx_t,
+    pubKey: *const u8,
+    pubKeyLen: u16,
+) -> parser_error_t {
+    if let Some(tx) = parsed_obj_from_state(tx_t as _).and_then(|obj| obj.transaction()) {
+        if pubKey.is_null() {
+            return ParserError::parser_no_data as _;
+        }
+        let pk = core::slice::from_raw_parts(pubKey, pubKeyLen as _);
+        tx.check_sponsor_pk_hash(pk) as _
+    } else {
+        ParserError::parser_context_mismatch as _
+    }
+}
+
+/// Writes the auth flag, fee and nonce the origin, role 0, or the sponsor,
+/// role 1, appends to the hash it signs. Returns the bytes written, 0 on error
+#[no_mangle]
+pub unsafe extern "C" fn _role_auth_data(
+    tx_t: *const parse_tx_t,
+    role: u8,
+    buf: *mut u8,
+    bufLen: u16,
+) -> u16 {
+    if buf.is_null() {
+        return 0;
+    }
+    let role = match role {
+        0 => SignerId::Origin,
+        1 => SignerId::Sponsor,
+        _ => return 0,
+    };
+    let buffer = core::slice::from_raw_parts_mut(buf, bufLen as usize);
+    parsed_obj_from_state(tx_t as _)
+        .and_then(|obj| obj.transaction())
+        .and_then(|tx| tx.role_auth_data(role, buffer).ok())
+        .map_or(0, |len| len as u16)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn _check_path(
+    tx_t: *const parse_tx_t,
+    path: *const u32,
+    pathLen: u8,
+) -> parser_error_t {
+    if let Some(tx) = parsed_obj_from_state(tx_t as _).and_then(|obj| obj.transaction()) {
+        if path.is_null() {
+            return ParserError::parser_no_data as _;
+        }
+        let path = core::slice::from_raw_parts(path, pathLen as _);
+        tx.check_path(path);
+        ParserError::parser_ok as _
+    } else {
+        ParserError::parser_context_mismatch as _
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn _is_nonstandard_path(tx_t: *const parse_tx_t) -> u8 {
+    if let Some(tx) = parsed_obj_from_state(tx_t as _).and_then(|obj| obj.transaction()) {
+        tx.is_nonstandard_path() as _
+    } else {
+        false as _
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn _check_network(tx_t: *const parse_tx_t, testnet: u8) -> parser_error_t {
+    if let Some(tx) = parsed_obj_from_state(tx_t as _).and_then(|obj| obj.transaction()) {
+        tx.check_network(testnet > 0) as _
+    } else {
+        ParserError::parser_context_mismatch as _
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn _check_denylist(
+    tx_t: *const parse_tx_t,
+    entries: *const u8,
+    entriesLen: u16,
+) -> parser_error_t {
+    if let Some(tx) = parsed_obj_from_state(tx_t as _).and_then(|obj| obj.transaction()) {
+        if entries.is_null() {
+            return tx.check_denylist::<DefaultSha512_256>(&[]) as _;
+        }
+        let entries = core::slice::from_raw_parts(entries, entriesLen as _);
+        tx.check_denylist::<DefaultSha512_256>(entries) as _
+    } else {
+        ParserError::parser_context_mismatch as _
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn _is_denylisted(tx_t: *const parse_tx_t) -> u8 {
+    if let Some(tx) = parsed_obj_from_state(tx_t as _).and_then(|obj| obj.transaction()) {
+        tx.is_denylisted() as _
+    } else {
+        false as _
+    }
+}
+
+/// Shows what the code of a contract deploy can do with assets, scan is
+/// the scan of the whole code body if part of it was dropped, NULL otherwise
+#[no_mangle]
+pub unsafe extern "C" fn _check_code(
+    tx_t: *const parse_tx_t,
+    scan: *const u8,
+    scanLen: u16,
+) -> parser_error_t {
+    if let Some(tx) = parsed_obj_from_state(tx_t as _).and_then(|obj| obj.transaction()) {
+        if scan.is_null() {
+            return tx.check_code(None) as _;
+        }
+        let scan = core::slice::from_raw_parts(scan, scanLen as _);
+        match CodeScan::from_bytes(scan) {
+            Ok(scan) => tx.check_code(Some(scan)) as _,
+            Err(e) => e as _,
+        }
+    } else {
+        ParserError::parser_context_mismatch as _
+    }
+}
+
+/// Reviews the arguments of the contract call by the names of the
+/// provisioned function signature abi, if it is the one called
+#[no_mangle]
+pub unsafe extern "C" fn _check_abi(
+    tx_t: *const parse_tx_t,
+    abi: *const u8,
+    abiLen: u16,
+) -> parser_error_t {
+    if let Some(tx) = parsed_obj_from_state(tx_t as _).and_then(|obj| obj.transaction()) {
+        if abi.is_null() {
+            return ParserError::parser_ok as _;
+        }
+        let abi = core::slice::from_raw_parts(abi, abiLen as _);
+        tx.check_abi(abi) as _
+    } else {
+        ParserError::parser_context_mismatch as _
+    }
+}
+
+/// Shows the token amounts of the post conditions after the provisioned
+/// token metadata info, for the tokens it describes
+#[no_mangle]
+pub unsafe extern "C" fn _check_token_info(
+    tx_t: *const parse_tx_t,
+    info: *const u8,
+    infoLen: u16,
+) -> parser_error_t {
+    if let Some(tx) = parsed_obj_from_state(tx_t as _).and_then(|obj| obj.transaction()) {
+        if info.is_null() {
+            return ParserError::parser_ok as _;
+        }
+        let info = core::slice::from_raw_parts(info, infoLen as _);
+        tx.check_token_info(info) as _
+    } else {
+        ParserError::parser_context_mismatch as _
+    }
+}
+
+/// Writes into path the path of the Bitcoin key the rewards of a stack-stx
+/// call would be paid to if they go to this device, pathLen is set to its
+/// number of levels, 0 if there is none
+#[no_mangle]
+pub unsafe extern "C" fn _reward_key_path(
+    tx_t: *const parse_tx_t,
+    signerPath: *const u32,
+    signerPathLen: u8,
+    path: *mut u32,
+    pathLen: *mut u8,
+    maxPathLen: u8,
+) -> parser_error_t {
+    if let Some(tx) = parsed_obj_from_state(tx_t as _).and_then(|obj| obj.transaction()) {
+        if signerPath.is_null() || path.is_null() || pathLen.is_null() {
+            return ParserError::parser_no_data as _;
+        }
+        let signer_path = core::slice::from_raw_parts(signerPath, signerPathLen as _);
+        let out = core::slice::from_raw_parts_mut(path, maxPathLen as _);
+        *pathLen = 0;
+        if let Some(key_path) = tx.reward_key_path(signer_path) {
+            if out.len() < key_path.len() {
+                return ParserError::parser_unexpected_buffer_end as _;
+            }
+            out.iter_mut().zip(key_path.iter()).for_each(|(dst, src)| *dst = *src);
+            *pathLen = key_path.len() as u8;
+        }
+        ParserError::parser_ok as _
+    } else {
+        ParserError::parser_context_mismatch as _
+    }
+}
+
+/// Checks the reward address of a stack-stx call against the hash160 of the
+/// key at _reward_key_path, keyHash is null if there was no such path
+#[no_mangle]
+pub unsafe extern "C" fn _check_reward_address(
+    tx_t: *const parse_tx_t,
+    keyHash: *const u8,
+) -> parser_error_t {
+    if let Some(tx) = parsed_obj_from_state(tx_t as _).and_then(|obj| obj.transaction()) {
+        let key_hash = if keyHash.is_null() {
+            None
+        } else {
+            Some(core::slice::from_raw_parts(keyHash, HASH160_LEN))
+        };
+        tx.check_reward_address(key_hash);
+        ParserError::parser_ok as _
+    } else {
+        ParserError::parser_context_mismatch as _
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn _presig_hash_data(
+    tx_t: *const parse_tx_t,
+    buf: *mut u8,
+    bufLen: u16,
+) -> u16 {
+    let buffer = core::slice::from_raw_parts_mut(buf, bufLen as usize);
+
+    if let Some(tx) = parsed_obj_from_state(tx_t as _).and_then(|obj| obj.transaction()) {
+        if let Ok(len) = tx.transaction_auth.initial_sighash_auth(buffer) {
+            return len as _;
+        }
+    }
+    0
+}
+
+/// Returns the error a transaction fails to parse with, or parser_ok,
+/// along with the section and offset it was found at
+#[no_mangle]
+pub unsafe extern "C" fn _parse_diagnostic(
+    data: *const u8,
+    dataLen: u16,
+    section: *mut u8,
+    offset: *mut u32,
+) -> parser_error_t {
+    if data.is_null() || section.is_null() || offset.is_null() {
+        return ParserError::parser_no_data as _;
+    }
+    let data = core::slice::from_raw_parts(data, dataLen as usize);
+    match ParsedObj::diagnose(data) {
+        Some(diagnostic) => {
+            *section = diagnostic.section as u8;
+            *offset = diagnostic.offset;
+            diagnostic.error as _
+        }
+        None => ParserError::parser_ok as _,
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn _initial_sighash(
+    tx_t: *const parse_tx_t,
+    out: *mut u8,
+    outLen: u16,
+) -> parser_error_t {
+    if out.is_null() || (outLen as usize) < SHA512_256_LEN {
+        return ParserError::parser_unexpected_buffer_end as _;
+    }
+    let output = &mut *(out as *mut [u8; SHA512_256_LEN]);
+
+    if let Some(tx) = parsed_obj_from_state(tx_t as _).and_then(|obj| obj.transaction()) {
+        return match tx.initial_sighash::<DefaultSha512_256>(output) {
+            Ok(_) => ParserError::parser_ok as _,
+            Err(e) => e as _,
+        };
+    }
+    ParserError::parser_context_mismatch as _
+}
+
+/// Chains hash, the pre_sig_hash of the first signer of a multisig origin,
+/// through the previous signatures up to the one this device signs
+#[no_mangle]
+pub unsafe extern "C" fn _multisig_presig_hash(
+    tx_t: *const parse_tx_t,
+    hash: *mut u8,
+    hashLen: u16,
+) -> parser_error_t {
+    if hash.is_null() || (hashLen as usize) < SHA512_256_LEN {
+        return ParserError::parser_unexpected_buffer_end as _;
+    }
+    let presig = &mut *(hash as *mut [u8; SHA512_256_LEN]);
+
+    if let Some(tx) = parsed_obj_from_state(tx_t as _).and_then(|obj| obj.transaction()) {
+        return match tx.multisig_presig_hash::<DefaultSha512_256>(presig) {
+            Ok(_) => ParserError::parser_ok as _,
+            Err(e) => e as _,
+        };
+    }
+    ParserError::parser_context_mismatch as _
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn _last_block_ptr(
+    tx_t: *const parse_tx_t,
+    block_ptr: *mut *const u8,
+) -> u16 {
+    if let Some(tx) = parsed_obj_from_state(tx_t as _).and_then(|obj| obj.transaction()) {
+        let block = tx.last_transaction_block();
+        *block_ptr = block.as_ptr();
+        return block.len() as _;
+    }
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn _is_multisig(tx_t: *const parse_tx_t) -> u8 {
+    if let Some(tx) = parsed_obj_from_state(tx_t as _).and_then(|obj| obj.transaction()) {
+        tx.is_multisig() as _
+    } else {
+        false as _
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn _is_order_independent(tx_t: *const parse_tx_t) -> u8 {
+    if let Some(tx) = parsed_obj_from_state(tx_t as _).and_then(|obj| obj.transaction()) {
+        tx.is_order_independent() as _
+    } else {
+        false as _
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn _is_sponsored(tx_t: *const parse_tx_t) -> u8 {
+    if let Some(tx) = parsed_obj_from_state(tx_t as _).and_then(|obj| obj.transaction()) {
+        tx.is_sponsored() as _
+    } else {
+        false as _
+    }
+}
+
+/// Writes the null terminated sponsor address. Returns its length, 0 if the
+/// transaction is not sponsored or out is too short
+#[no_mangle]
+pub unsafe extern "C" fn _sponsor_address(
+    tx_t: *const parse_tx_t,
+    out: *mut u8,
+    outLen: u16,
+) -> u16 {
+    if out.is_null() {
+        return 0;
+    }
+    let out = core::slice::from_raw_parts_mut(out, outLen as _);
+    match parsed_obj_from_state(tx_t as _)
+        .and_then(|obj| obj.transaction())
+        .map(|tx| tx.sponsor_address())
+    {
+        Some(Ok(address)) if address.len() < out.len() => {
+            out.iter_mut().for_each(|v| *v = 0);
+            out.iter_mut().zip(address.iter()).for_each(|(dst, src)| *dst = *src);
+            address.len() as u16
+        }
+        _ => 0,
+    }
+}
+
+/// Writes the sponsor fee as big endian bytes. Returns the bytes written,
+/// 0 if the transaction is not sponsored
+#[no_mangle]
+pub unsafe extern "C" fn _sponsor_fee_bytes(
+    tx_t: *const parse_tx_t,
+    fee: *mut u8,
+    fee_len: u16,
+) -> u8 {
+    if fee.is_null() {
+        return 0;
+    }
+    if let Some(fee_bytes) = parsed_obj_from_state(tx_t as _)
+        .and_then(|obj| obj.transaction())
+        .and_then(|tx| tx.sponsor_fee())
+        .map(u64::to_be_bytes)
+    {
+        if fee_bytes.len() <= fee_len as usize {
+            fee.copy_from_nonoverlapping(fee_bytes.as_ptr(), fee_bytes.len());
+            return fee_bytes.len() as u8;
+        }
+    }
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn _payload_kind(tx_t: *const parse_tx_t, kind: *mut u8) -> parser_error_t {
+    if kind.is_null() {
+        return ParserError::parser_no_data as _;
+    }
+    if let Some(tx) = parsed_obj_from_state(tx_t as _).and_then(|obj| obj.transaction()) {
+        *kind = tx.payload_id() as u8;
+        ParserError::parser_ok as _
+    } else {
+        ParserError::parser_context_mismatch as _
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn _recipient_hash160(
+    tx_t: *const parse_tx_t,
+    out: *mut u8,
+    outLen: u16,
+) -> parser_error_t {
+    if out.is_null() || (outLen as usize) < HASH160_LEN {
+        return ParserError::parser_unexpected_buffer_end as _;
+    }
+    if let Some(tx) = parsed_obj_from_state(tx_t as _).and_then(|obj| obj.transaction()) {
+        return match tx.payload.recipient_hash160() {
+            Some(hash) => {
+                out.copy_from_nonoverlapping(hash.as_ptr(), HASH160_LEN);
+                ParserError::parser_ok as _
+            }
+            None => ParserError::parser_unexpected_type as _,
+        };
+    }
+    ParserError::parser_context_mismatch as _
+}
+
+/// Writes the null terminated address of the token transfer recipient.
+/// Returns its length, 0 if there is none or out is too short
+#[no_mangle]
+pub unsafe extern "C" fn _recipient_address(
+    tx_t: *const parse_tx_t,
+    out: *mut u8,
+    outLen: u16,
+) -> u16 {
+    if out.is_null() {
+        return 0;
+    }
+    let out = core::slice::from_raw_parts_mut(out, outLen as _);
+    match parsed_obj_from_state(tx_t as _)
+        .and_then(|obj| obj.transaction())
+        .and_then(|tx| tx.payload.recipient_address())
+    {
+        Some(address) if address.len() < out.len() => {
+            out.iter_mut().for_each(|v| *v = 0);
+            out.iter_mut().zip(address.iter()).for_each(|(dst, src)| *dst = *src);
+            address.len() as u16
+        }
+        _ => 0,
+    }
+}
+
+/// Gets the token transfer amount in uSTX split in two 32-bit halves
+#[no_mangle]
+pub unsafe extern "C" fn _amount(
+    tx_t: *const parse_tx_t,
+    amount_hi: *mut u32,
+    amount_lo: *mut u32,
+) -> parser_error_t {
+    if amount_hi.is_null() || amount_lo.is_null() {
+        return ParserError::parser_no_data as _;
+    }
+    if let Some(tx) = parsed_obj_from_state(tx_t as _).and_then(|obj| obj.transaction()) {
+        return match tx.payload.amount() {
+            Some(amount) => {
+                *amount_hi = (amount >> 32) as u32;
+                *amount_lo = amount as u32;
+                ParserError::parser_ok as _
+            }
+            None => ParserError::parser_unexpected_type as _,
+        };
+    }
+    ParserError::parser_context_mismatch as _
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn _num_post_conditions(
+    tx_t: *const parse_tx_t,
+    num: *mut u8,
+) -> parser_error_t {
+    if num.is_null() {
+        return ParserError::parser_no_data as _;
+    }
+    if let Some(tx) = parsed_obj_from_state(tx_t as _).and_then(|obj| obj.transaction()) {
+        *num = tx.num_post_conditions();
+        ParserError::parser_ok as _
+    } else {
+        ParserError::parser_context_mismatch as _
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn _multisig_threshold(
+    tx_t: *const parse_tx_t,
+    threshold: *mut u16,
+) -> parser_error_t {
+    if threshold.is_null() {
+        return ParserError::parser_no_data as _;
+    }
+    if let Some(tx) = parsed_obj_from_state(tx_t as _).and_then(|obj| obj.transaction()) {
+        return match tx.multisig_threshold() {
+            Some(required) => {
+                *threshold = required;
+                ParserError::parser_ok as _
+            }
+            None => ParserError::parser_invalid_auth_type as _,
+        };
+    }
+    ParserError::parser_context_mismatch as _
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn _is_high_fee(tx_t: *const parse_tx_t) -> u8 {
+    if let Some(tx) = parsed_obj_from_state(tx_t as _).and_then(|obj| obj.transaction()) {
+        tx.is_high_fee() as _
+    } else {
+        false as _
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn _transaction_type(tx_t: *const parse_tx_t) -> transaction_type_t {
+    if let Some(obj) = parsed_obj_from_state(tx_t as _) {
+        obj.get_type() as _
+    } else {
+        Tag::Invalid as _
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn _previous_signer_data(
+    tx_t: *const parse_tx_t,
+    data: *mut *const u8,
+) -> u16 {
+    if let Some(tx) = parsed_obj_from_state(tx_t as _).and_then(|obj| obj.transaction()) {
+        if let Some(slice) = tx.previous_signer_data() {
+            *data = slice.as_ptr();
+            return slice.len() as _;
+        }
+    }
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn _structured_msg_hash(
+    tx_t: *const parse_tx_t,
+    out: *mut u8,
+    out_len: u16,
+) -> parser_error_t {
+    if let Some(tx) = parsed_obj_from_state(tx_t as _).and_then(|obj| obj.structured_msg()) {
+        let output = core::slice::from_raw_parts_mut(out, out_len as _);
+        if tx.get_hash(output).is_ok() {
+            return ParserError::parser_ok as _;
+        }
+    }
+    ParserError::parser_unexpected_error as _
+}
+
+/// Length of the SIP018 prefix and domain of a structured message, 0 if it
+/// is not one
+#[no_mangle]
+pub unsafe extern "C" fn _structured_msg_header_len(tx_t: *const parse_tx_t) -> u16 {
+    parsed_obj_from_state(tx_t as _)
+        .and_then(|obj| obj.structured_msg())
+        .and_then(|msg| msg.header_len().ok())
+        .and_then(|len| u16::try_from(len).ok())
+        .unwrap_or(0)
+}
+
+#[no_mangle]
+pub extern "C" fn _stack_watermark(entry: u8) -> u32 {
+    EntryPoint::from_u8(entry)
+        .map(stack::watermark)
+        .unwrap_or(0)
+}
+
+#[no_mangle]
+pub extern "C" fn _stack_watermark_reset() {
+    stack::reset()
+}
+
+#[no_mangle]
+pub extern "C" fn rs_c32_address(
+    input: *const u8,
+    version: u8,
+    output: *mut u8,
+    outLen: u16,
+) -> u16 {
+    if input.is_null() | output.is_null() {
+        return 0;
+    }
+    unsafe {
+        let raw =
+            // We expect hash160 raw data
+            core::slice::from_raw_parts(input, HASH160_LEN);
+        if let Ok(res) = c32_address(version, raw) {
+            let encoded_len = res.len();
+            if (outLen as usize) < encoded_len {
+                return 0;
+            }
+            // Initialize our output with null bytes
+            output.write_bytes(0, outLen as usize);
+
+            output.copy_from_nonoverlapping(res.as_ptr(), encoded_len);
+            return encoded_len as u16;
+        }
+    }
+    0
+}
+
+/// Writes the pageIdx page of the c32 address of version and hash160 as
+/// addresses being verified are shown, see C32Address::page_full
+#[no_mangle]
+pub unsafe extern "C" fn rs_c32_page_address(
+    hash160: *const u8,
+    version: u8,
+    outValue: *mut c_char,
+    outValueLen: u16,
+    pageIdx: u8,
+    pageCount: *mut u8,
+) -> parser_error_t {
+    if hash160.is_null() || outValue.is_null() || pageCount.is_null() {
+        return ParserError::parser_no_data as _;
+    }
+    *pageCount = 0;
+    let hash160 = core::slice::from_raw_parts(hash160, HASH160_LEN);
+    let value = core::slice::from_raw_parts_mut(outValue as *mut u8, outValueLen as usize);
+    match C32Address::new(version, hash160).and_then(|address| address.page_full(value, pageIdx)) {
+        Ok(pages) => {
+            *pageCount = pages;
+            ParserError::parser_ok as _
+        }
+        Err(e) => e as _,
+    }
+}
+
+/// Writes the account discovery entry of the account at index, see
+/// stacks_ledger_parser::account. Returns its length, 0 on error
+#[no_mangle]
+pub unsafe extern "C" fn rs_account_entry(
+    index: u32,
+    pubkey: *const u8,
+    pubkeyLen: u16,
+    hash160: *const u8,
+    out: *mut u8,
+    outLen: u16,
+) -> u16 {
+    if pubkey.is_null() || hash160.is_null() || out.is_null() {
+        return 0;
+    }
+    let pubkey = core::slice::from_raw_parts(pubkey, pubkeyLen as _);
+    let hash160 = core::slice::from_raw_parts(hash160, HASH160_LEN);
+    let out = core::slice::from_raw_parts_mut(out, outLen as _);
+    write_account_entry(index, pubkey, hash160, out)
+        .map(|len| len as u16)
+        .unwrap_or(0)
+}
+
+/// Writes the slot of pubkey in the multisig account of tmpl followed by the
+/// key, see stacks_ledger_parser::multisig. Returns its length, 0 on error
+#[no_mangle]
+pub unsafe extern "C" fn rs_multisig_slot(
+    tmpl: *const u8,
+    tmplLen: u16,
+    pubkey: *const u8,
+    pubkeyLen: u16,
+    out: *mut u8,
+    outLen: u16,
+) -> u16 {
+    if tmpl.is_null() || pubkey.is_null() || out.is_null() {
+        return 0;
+    }
+    let tmpl = core::slice::from_raw_parts(tmpl, tmplLen as _);
+    let pubkey = core::slice::from_raw_parts(pubkey, pubkeyLen as _);
+    let out = core::slice::from_raw_parts_mut(out, outLen as _);
+    write_multisig_slot(tmpl, pubkey, out)
+        .map(|len| len as u16)
+        .unwrap_or(0)
+}
+
+/// Returns 1 when the multisig accounts of hash mode take the hash of the
+/// witness program of their script instead of the script itself
+#[no_mangle]
+pub unsafe extern "C" fn rs_hash_mode_is_segwit(mode: u8) -> u8 {
+    HashMode::try_from(mode).map_or(0, |mode| mode.is_segwit() as u8)
+}
+
+/// Writes the Bitcoin address of the key whose hash160 is given, derived at
+/// path, see stacks_ledger_parser::btc. Returns its length, 0 on error
+#[no_mangle]
+pub unsafe extern "C" fn rs_btc_address(
+    path: *const u32,
+    pathLen: u8,
+    hash160: *const u8,
+    out: *mut u8,
+    outLen: u16,
+) -> u16 {
+    if path.is_null() || hash160.is_null() || out.is_null() {
+        return 0;
+    }
+    let path = core::slice::from_raw_parts(path, pathLen as _);
+    let hash160 = core::slice::from_raw_parts(hash160, HASH160_LEN);
+    let out = core::slice::from_raw_parts_mut(out, outLen as _);
+    match key_address(path, hash160) {
+        // null terminated
+        Ok(address) if address.len() < out.len() => {
+            out.iter_mut().for_each(|v| *v = 0);
+            out.iter_mut().zip(address.iter()).for_each(|(dst, src)| *dst = *src);
+            address.len() as u16
+        }
+        _ => 0,
+    }
+}
+
+/// Reads the path sent by the host into path if it is allowed for kind,
+/// see stacks_ledger_parser::path, pathLen is set to its number of levels
+#[no_mangle]
+pub unsafe extern "C" fn rs_read_path(
+    data: *const u8,
+    dataLen: u16,
+    kind: u8,
+    path: *mut u32,
+    pathLen: *mut u8,
+    maxPathLen: u8,
+) -> parser_error_t {
+    if data.is_null() || path.is_null() || pathLen.is_null() {
+        return ParserError::parser_no_data as _;
+    }
+    let kind = match PathKind::try_from(kind) {
+        Ok(kind) => kind,
+        Err(e) => return e as _,
+    };
+    let data = core::slice::from_raw_parts(data, dataLen as _);
+    let out = core::slice::from_raw_parts_mut(path, maxPathLen as _);
+    match read_path(data, kind, out) {
+        Ok(len) => {
+            *pathLen = len as u8;
+            ParserError::parser_ok as _
+        }
+        Err(e) => e as _,
+    }
+}
+
+/// Writes the number of settings of blob followed by the id and value of
+/// each, see stacks_ledger_parser::settings. Returns its length, 0 on error
+#[no_mangle]
+pub unsafe extern "C" fn rs_settings_entries(
+    blob: *const u8,
+    blobLen: u16,
+    out: *mut u8,
+    outLen: u16,
+) -> u16 {
+    if blob.is_null() || out.is_null() {
+        return 0;
+    }
+    let blob = core::slice::from_raw_parts(blob, blobLen as _);
+    let out = core::slice::from_raw_parts_mut(out, outLen as _);
+    Settings::read(blob)
+        .write_entries(out)
+        .map(|len| len as u16)
+        .unwrap_or(0)
+}
+
+/// Reads the setting id, a setting_id_e, of blob into value
+#[no_mangle]
+pub unsafe extern "C" fn rs_settings_get(
+    blob: *const u8,
+    blobLen: u16,
+    id: u8,
+    value: *mut u64,
+) -> parser_error_t {
+    if blob.is_null() || value.is_null() {
+        return ParserError::parser_no_data as _;
+    }
+    let id = match SettingId::try_from(id) {
+        Ok(id) => id,
+        Err(e) => return e as _,
+    };
+    let blob = core::slice::from_raw_parts(blob, blobLen as _);
+    *value = Settings::read(blob).get(id);
+    ParserError::parser_ok as _
+}
+
+/// Writes the settings of blob with the setting id set to value as a blob of
+/// the current version. Returns its length, 0 on error
+#[no_mangle]
+pub unsafe extern "C" fn rs_settings_set(
+    blob: *const u8,
+    blobLen: u16,
+    id: u8,
+    value: u64,
+    out: *mut u8,
+    outLen: u16,
+) -> u16 {
+    if blob.is_null() || out.is_null() {
+        return 0;
+    }
+    let blob = core::slice::from_raw_parts(blob, blobLen as _);
+    let out = core::slice::from_raw_parts_mut(out, outLen as _);
+    let mut settings = Settings::read(blob);
+    SettingId::try_from(id)
+        .and_then(|id| settings.set(id, value))
+        .and_then(|_| settings.write_blob(out))
+        .map(|len| len as u16)
+        .unwrap_or(0)
+}
+
+// the initial sighash of the transaction being received, see sighash_stream.c
+fn with_sighash_stream<R>(f: impl FnOnce(&mut SighashStream<DefaultSha512_256>) -> R) -> R {
+    static mut STREAM: SighashStream<DefaultSha512_256> = SighashStream::new();
+    // safety: the app is single threaded and f does not reenter
+    unsafe { f(&mut *core::ptr::addr_of_mut!(STREAM)) }
+}
+
+/// Drops the initial sighash computed so far
+#[no_mangle]
+pub extern "C" fn rs_sighash_stream_reset() {
+    with_sighash_stream(|stream| stream.reset())
+}
+
+/// Hashes the bytes of the transaction buffer data received since the
+/// last call
+#[no_mangle]
+pub unsafe extern "C" fn rs_sighash_stream_update(data: *const u8, dataLen: u32) {
+    if data.is_null() {
+        return;
+    }
+    let data = core::slice::from_raw_parts(data, dataLen as _);
+    with_sighash_stream(|stream| stream.update(data))
+}
+
+/// Hashes and drops chunk, part of a contract code body that does not fit
+/// the transaction buffer data
+#[no_mangle]
+pub unsafe extern "C" fn rs_sighash_stream_skip(
+    data: *const u8,
+    dataLen: u32,
+    chunk: *const u8,
+    chunkLen: u32,
+) -> parser_error_t {
+    if data.is_null() || chunk.is_null() {
+        return ParserError::parser_no_data as _;
+    }
+    let data = core::slice::from_raw_parts(data, dataLen as _);
+    let chunk = core::slice::from_raw_parts(chunk, chunkLen as _);
+    match with_sighash_stream(|stream| stream.skip(data, chunk)) {
+        Ok(()) => ParserError::parser_ok as _,
+        Err(e) => e as _,
+    }
+}
+
+/// Returns how many bytes of the transaction were hashed and dropped
+#[no_mangle]
+pub extern "C" fn rs_sighash_stream_skipped() -> u32 {
+    with_sighash_stream(|stream| stream.skipped() as u32)
+}
+
+/// Writes the RS_CODE_SCAN_LEN bytes of the scan of the dropped code body
+/// into out, returns 0 if nothing was dropped
+#[no_mangle]
+pub unsafe extern "C" fn rs_sighash_stream_code_scan(out: *mut u8, outLen: u16) -> u16 {
+    if out.is_null() {
+        return 0;
+    }
+    let out = core::slice::from_raw_parts_mut(out, outLen as _);
+    with_sighash_stream(|stream| match stream.code_scan() {
+        Some(scan) if scan.write(out).is_ok() => RS_CODE_SCAN_LEN,
+        _ => 0,
+    })
+}
+
+/// Completes the initial sighash with the last transaction block, found by
+/// the parser at offset lastBlock of the transaction buffer data
+#[no_mangle]
+pub unsafe extern "C" fn rs_sighash_stream_finish(
+    data: *const u8,
+    dataLen: u32,
+    lastBlock: u32,
+    lastBlockLen: u16,
+    hash: *mut u8,
+    hashLen: u16,
+) -> parser_error_t {
+    if data.is_null() || hash.is_null() || (hashLen as usize) < SHA512_256_LEN {
+        return ParserError::parser_no_data as _;
+    }
+    let data = core::slice::from_raw_parts(data, dataLen as _);
+    let mut out = [0u8; SHA512_256_LEN];
+    let res = with_sighash_stream(|stream| {
+        stream.finish(data, lastBlock as _, lastBlockLen as _, &mut out)
+    });
+    match res {
+        Ok(()) => {
+            core::slice::from_raw_parts_mut(hash, SHA512_256_LEN).copy_from_slice(&out);
+            ParserError::parser_ok as _
+        }
+        Err(e) => e as _,
+    }
+}
+
+/// Checks that abi is a well formed function signature, before it is
+/// provisioned
+#[no_mangle]
+pub unsafe extern "C" fn rs_check_abi(abi: *const u8, abiLen: u16) -> parser_error_t {
+    if abi.is_null() {
+        return ParserError::parser_no_data as _;
+    }
+    let abi = core::slice::from_raw_parts(abi, abiLen as _);
+    match FunctionAbi::from_bytes(abi) {
+        Ok(_) => ParserError::parser_ok as _,
+        Err(e) => e as _,
+    }
+}
+
+/// Checks that info is well formed token metadata, before it is provisioned
+#[no_mangle]
+pub unsafe extern "C" fn rs_check_token_info(info: *const u8, infoLen: u16) -> parser_error_t {
+    if info.is_null() {
+        return ParserError::parser_no_data as _;
+    }
+    let info = core::slice::from_raw_parts(info, infoLen as _);
+    match TokenInfo::from_bytes(info) {
+        Ok(_) => ParserError::parser_ok as _,
+        Err(e) => e as _,
+    }
+}
+
+/// Checks a path already read against the policies of kind
+#[no_mangle]
+pub unsafe extern "C" fn rs_check_path(path: *const u32, pathLen: u8, kind: u8) -> parser_error_t {
+    if path.is_null() {
+        return ParserError::parser_no_data as _;
+    }
+    let kind = match PathKind::try_from(kind) {
+        Ok(kind) => kind,
+        Err(e) => return e as _,
+    };
+    let path = core::slice::from_raw_parts(path, pathLen as _);
+    match check_path_policy(path, kind) {
+        Ok(()) => ParserError::parser_ok as _,
+        Err(e) => e as _,
+    }
+}
+
+/// Whether the path is one of the account paths wallets derive,
+/// see stacks_ledger_parser::path
+#[no_mangle]
+pub unsafe extern "C" fn rs_is_standard_path(path: *const u32, pathLen: u8) -> u8 {
+    if path.is_null() {
+        return false as _;
+    }
+    let path = core::slice::from_raw_parts(path, pathLen as _);
+    is_standard_path(path) as _
+}
+
+/// Signs the 32 bytes digest with secret, writing the low-S signature as
+/// (r, s, v) and DER encoded into der. It is verified against the key of
+/// secret before it is returned, returns the DER length or 0 on error
+#[no_mangle]
+pub unsafe extern "C" fn rs_ecdsa_sign(
+    secret: *const u8,
+    digest: *const u8,
+    r: *mut u8,
+    s: *mut u8,
+    v: *mut u8,
+    der: *mut u8,
+    derLen: u16,
+) -> u16 {
+    if secret.is_null()
+        || digest.is_null()
+        || r.is_null()
+        || s.is_null()
+        || v.is_null()
+        || der.is_null()
+    {
+        return 0;
+    }
+    let secret = &*(secret as *const [u8; ecdsa::SECRET_KEY_LEN]);
+    let digest = &*(digest as *const [u8; ecdsa::DIGEST_LEN]);
+    let r = &mut *(r as *mut [u8; SCALAR_LEN]);
+    let s = &mut *(s as *mut [u8; SCALAR_LEN]);
+    let der = core::slice::from_raw_parts_mut(der, derLen as usize);
+    ecdsa::sign::<DefaultSecp256k1>(secret, digest, r, s, &mut *v, der).unwrap_or(0) as u16
+}
+
+/// Returns 1 if the signature (r, s, v) of the 32 bytes digest recovers the
+/// uncompressed publicKey and s is low, 0 otherwise
+#[no_mangle]
+pub unsafe extern "C" fn rs_ecdsa_verify(
+    publicKey: *const u8,
+    digest: *const u8,
+    r: *const u8,
+    s: *const u8,
+    v: u8,
+) -> u8 {
+    if publicKey.is_null() || digest.is_null() || r.is_null() || s.is_null() {
+        return 0;
+    }
+    let public_key = &*(publicKey as *const [u8; ecdsa::UNCOMPRESSED_PUBKEY_LEN]);
+    let digest = &*(digest as *const [u8; ecdsa::DIGEST_LEN]);
+    let r = &*(r as *const [u8; SCALAR_LEN]);
+    let s = &*(s as *const [u8; SCALAR_LEN]);
+    ecdsa::verify::<DefaultSecp256k1>(public_key, digest, r, s, v) as u8
+}
+
+/// Writes the auth field of the signature (r, s, v) by the compressed key of
+/// this device, the bytes a coordinator puts in the auth fields of a
+/// multisig transaction. Returns its length, 0 if out is too short
+#[no_mangle]
+pub unsafe extern "C" fn rs_signature_auth_field(rsv: *const u8, out: *mut u8, outLen: u16) -> u16 {
+    if rsv.is_null() || out.is_null() || (outLen as usize) < SIGNATURE_AUTH_FIELD_LEN {
+        return 0;
+    }
+    let rsv = &*(rsv as *const [u8; SIGNATURE_LEN]);
+    let out = &mut *(out as *mut [u8; SIGNATURE_AUTH_FIELD_LEN]);
+    signature_auth_field(rsv, out);
+    SIGNATURE_AUTH_FIELD_LEN as _
+}
+
+/// Returns 1 if the 64 bytes at signature are a BIP-340 signature encoding,
+/// checked on the signatures the device returns, 0 otherwise
+#[no_mangle]
+pub unsafe extern "C" fn rs_is_schnorr_signature(signature: *const u8, signatureLen: u16) -> u8 {
+    if signature.is_null() {
+        return 0;
+    }
+    let signature = core::slice::from_raw_parts(signature, signatureLen as usize);
+    schnorr::is_well_formed(signature) as u8
+}
+
+/// Clears key material, sighashes and signatures once they are no longer
+/// needed, unlike MEMZERO these writes can not be optimized away
+#[no_mangle]
+pub unsafe extern "C" fn rs_zeroize(buffer: *mut c_void, len: u16) {
+    if buffer.is_null() {
+        return;
+    }
+    core::slice::from_raw_parts_mut(buffer as *mut u8, len as usize).zeroize()
+}
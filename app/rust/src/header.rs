@@ -0,0 +1,43 @@
+//! include/rslib.h is generated by cbindgen from the functions, types and
+//! constants of ffi.rs, see cbindgen.toml. The checked in header is what the
+//! C side is built against, so it is compared with the one cbindgen produces
+//! now and any change to the interface shows up as a diff, run the tests
+//! with UPDATE_HEADER=1 to accept it.
+
+extern crate std;
+
+use std::path::PathBuf;
+use std::vec::Vec;
+
+#[test]
+fn header_matches_ffi() {
+    let mut root = PathBuf::new();
+    root.push(env!("CARGO_MANIFEST_DIR"));
+
+    let config = cbindgen::Config::from_file(root.join("cbindgen.toml")).unwrap();
+    let bindings = cbindgen::Builder::new()
+        .with_config(config)
+        .with_src(root.join("src").join("ffi.rs"))
+        .generate()
+        .unwrap();
+    let mut generated = Vec::new();
+    bindings.write(&mut generated);
+
+    let path = root.join("include").join("rslib.h");
+    if std::env::var_os("UPDATE_HEADER").is_some() {
+        std::fs::write(&path, &generated).unwrap();
+        return;
+    }
+
+    let expected = std::fs::read(&path).unwrap_or_default();
+    if expected != generated {
+        std::println!(
+            "generated:\n{}",
+            std::string::String::from_utf8_lossy(&generated)
+        );
+    }
+    assert!(
+        expected == generated,
+        "include/rslib.h does not match src/ffi.rs, run with UPDATE_HEADER=1 to accept it"
+    );
+}
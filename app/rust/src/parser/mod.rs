@@ -1,29 +0,0 @@
-mod c32;
-mod error;
-mod ffi;
-mod jwt;
-mod message;
-mod parsed_obj;
-mod parser_common;
-mod post_condition;
-mod principal;
-mod spending_condition;
-mod structured_msg;
-mod transaction;
-mod transaction_auth;
-mod transaction_payload;
-mod utils;
-mod value;
-pub use error::ParserError;
-pub use ffi::{_getItem, _getNumItems, _parser_init, _read, fp_uint64_to_str};
-pub use jwt::Jwt;
-pub use message::{ByteString, Message};
-pub use parsed_obj::{ParsedObj, Tag};
-pub use parser_common::*;
-pub use post_condition::{FungibleConditionCode, TransactionPostCondition};
-pub use principal::*;
-pub use structured_msg::{Domain, StructuredMsg};
-pub use transaction::Transaction;
-pub use transaction_auth::TransactionAuth;
-pub use utils::*;
-pub use value::{Int128, Tuple, UInt128, Value, ValueId};
@@ -21,12 +21,38 @@
 #include "zxformat.h"
 #include "app_mode.h"
 #include "crypto.h"
+#include "labels.h"
+#include "settings.h"
+#include "rslib.h"
+
+// The review of a non-standard path starts with a warning showing it, on
+// Stax and Flex the warning is a screen of its own shown before the review
+static bool addr_path_warning() {
+#if defined(TARGET_STAX) || defined(TARGET_FLEX)
+    return false;
+#else
+    return !isStandardPath();
+#endif
+}
+
+// Pages the address of the derived key on the network of addressVersion as
+// the rust parser formats it, in groups of 4 characters unless the user chose
+// not to. The address returned over APDU is left as it is
+static zxerr_t pageAddress(char *outVal, uint16_t outValLen, uint8_t addressVersion, uint8_t pageIdx, uint8_t *pageCount) {
+    if (rs_c32_page_address(crypto_addressHash(), addressVersion, outVal, outValLen, pageIdx, pageCount) != parser_ok) {
+        return zxerr_unknown;
+    }
+    return zxerr_ok;
+}
 
 zxerr_t addr_getNumItems(uint8_t *num_items) {
     zemu_log_stack("addr_getNumItems");
-    *num_items = 1;
+    *num_items = 2;
     if (app_mode_expert()) {
-        *num_items = 2;
+        *num_items = 3;
+    }
+    if (addr_path_warning()) {
+        *num_items += 1;
     }
     return zxerr_ok;
 }
@@ -36,19 +62,65 @@ zxerr_t addr_getItem(int8_t displayIdx,
                      char *outVal, uint16_t outValLen,
                      uint8_t pageIdx, uint8_t *pageCount) {
     zemu_log_stack("addr_getItem");
+    if (addr_path_warning()) {
+        if (displayIdx == 0) {
+            snprintf(outKey, outKeyLen, LABEL_NONSTANDARD_PATH);
+            char buffer[300];
+            bip32_to_str(buffer, sizeof(buffer), hdPath, hdPath_len);
+            pageString(outVal, outValLen, buffer, pageIdx, pageCount);
+            return zxerr_ok;
+        }
+        displayIdx--;
+    }
+
     switch (displayIdx) {
         case 0:
-            snprintf(outKey, outKeyLen, "Address");
-            pageString(outVal, outValLen, (char *) (G_io_apdu_buffer + VIEW_ADDRESS_OFFSET_SECP256K1), pageIdx, pageCount);
-            return zxerr_ok;
-        case 1: {
+            snprintf(outKey, outKeyLen, LABEL_ADDRESS);
+            return pageAddress(outVal, outValLen, version, pageIdx, pageCount);
+        case 1:
+            // same key on the other network
+            snprintf(outKey, outKeyLen, version == COIN_VERSION_MAINNET_SINGLESIG ? LABEL_TESTNET_ADDRESS : LABEL_MAINNET_ADDRESS);
+            return pageAddress(outVal, outValLen,
+                               version == COIN_VERSION_MAINNET_SINGLESIG ? COIN_VERSION_TESTNET_SINGLESIG : COIN_VERSION_MAINNET_SINGLESIG,
+                               pageIdx, pageCount);
+        case 2: {
             if (!app_mode_expert()) {
                 return zxerr_no_data;
             }
 
-            snprintf(outKey, outKeyLen, "Path");
+            snprintf(outKey, outKeyLen, LABEL_PATH);
+            char buffer[300];
+            bip32_to_str(buffer, sizeof(buffer), hdPath, hdPath_len);
+            pageString(outVal, outValLen, buffer, pageIdx, pageCount);
+            return zxerr_ok;
+        }
+        default:
+            return zxerr_no_data;
+    }
+}
+
+// The Bitcoin address of hdPath, the one PoX rewards can be paid to,
+// and the path so the account can be told apart
+zxerr_t btc_addr_getNumItems(uint8_t *num_items) {
+    zemu_log_stack("btc_addr_getNumItems");
+    *num_items = 2;
+    return zxerr_ok;
+}
+
+zxerr_t btc_addr_getItem(int8_t displayIdx,
+                         char *outKey, uint16_t outKeyLen,
+                         char *outVal, uint16_t outValLen,
+                         uint8_t pageIdx, uint8_t *pageCount) {
+    zemu_log_stack("btc_addr_getItem");
+    switch (displayIdx) {
+        case 0:
+            snprintf(outKey, outKeyLen, LABEL_BTC_ADDRESS);
+            pageString(outVal, outValLen, (char *) (G_io_apdu_buffer + VIEW_ADDRESS_OFFSET_SECP256K1), pageIdx, pageCount);
+            return zxerr_ok;
+        case 1: {
+            snprintf(outKey, outKeyLen, LABEL_PATH);
             char buffer[300];
-            bip32_to_str(buffer, sizeof(buffer), hdPath, HDPATH_LEN_DEFAULT);
+            bip32_to_str(buffer, sizeof(buffer), hdPath, hdPath_len);
             pageString(outVal, outValLen, buffer, pageIdx, pageCount);
             return zxerr_ok;
         }
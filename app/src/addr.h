@@ -29,6 +29,15 @@ zxerr_t addr_getItem(int8_t displayIdx,
                      char *outValue, uint16_t outValueLen,
                      uint8_t pageIdx, uint8_t *pageCount);
 
+/// Return the number of items in the Bitcoin address view
+zxerr_t btc_addr_getNumItems(uint8_t *num_items);
+
+/// Gets an specific item from the Bitcoin address view (including paging)
+zxerr_t btc_addr_getItem(int8_t displayIdx,
+                         char *outKey, uint16_t outKeyLen,
+                         char *outValue, uint16_t outValueLen,
+                         uint8_t pageIdx, uint8_t *pageCount);
+
 #ifdef __cplusplus
 }
 #endif
@@ -17,6 +17,7 @@
 
 #include "app_main.h"
 
+#include <stdio.h>
 #include <string.h>
 #include <os_io_seproxyhal.h>
 #include <os.h>
@@ -26,23 +27,144 @@
 #include "tx.h"
 #include "addr.h"
 #include "crypto.h"
+#include "sign_session.h"
+#include "auth_field.h"
+#include "batch.h"
+#include "msg_batch.h"
+#include "sighash_stream.h"
+#include "progress.h"
+#include "denylist.h"
+#include "function_abi.h"
+#include "token_info.h"
+#include "identity.h"
+#include "schnorr.h"
+#include "settings.h"
+#include "settings_review.h"
+#include "rslib.h"
+#include "review_nbgl.h"
+#include "labels.h"
 #include "coin.h"
 #include "zxmacros.h"
+#include "zxformat.h"
 #include "view_internal.h"
 
 static bool tx_initialized = false;
+// the last chunk of the transaction being signed was received
+static bool tx_received = false;
+// the chunks of the transaction being signed carry their sequence number
+static bool chunk_sequenced = false;
+// sequence number, payload type and digest of the last chunk received
+static uint8_t chunk_sequence = 0;
+static uint8_t chunk_type = 0;
+static uint8_t chunk_digest[CX_SHA256_SIZE];
+// the last chunk received was sent again, it was not appended
+static bool chunk_retransmitted = false;
+// flags sent along with the path of the batch transaction being received
+static uint8_t batch_flags = 0;
 
-__Z_INLINE void extractHDPath(uint32_t rx, uint32_t offset, uint32_t path_len) {
+// Reads the path at offset, it is the rest of the APDU data, into hdPath
+// if the policies of kind allow it
+__Z_INLINE void extract_path(uint32_t rx, uint32_t offset, path_kind_e kind) {
+    if (rx < offset) {
+        THROW(APDU_CODE_WRONG_LENGTH);
+    }
 
-    if ((rx - offset) < sizeof(uint32_t) * path_len) {
+    uint8_t path_len = 0;
+    switch (rs_read_path(G_io_apdu_buffer + offset, rx - offset, kind,
+                         hdPath, &path_len, HDPATH_LEN_MAX)) {
+        case parser_ok:
+            hdPath_len = path_len;
+            return;
+        case parser_unexpected_buffer_end:
             THROW(APDU_CODE_WRONG_LENGTH);
+        default:
+            THROW(APDU_CODE_DATA_INVALID);
+    }
+}
+
+__Z_INLINE void append_chunk(uint32_t rx) {
+    const uint32_t len = rx - OFFSET_DATA;
+    // Once part of the code body was dropped the rest is dropped as well,
+    // the buffer has to end where the hashed and scanned bytes do
+    if (sighash_stream_skipped() == 0 &&
+        tx_append(&(G_io_apdu_buffer[OFFSET_DATA]), len) == len) {
+        return;
+    }
+
+    // The buffer is full, the code body of a smart contract deploy can
+    // still be hashed and dropped as it is only reviewed through what it
+    // can do with assets. Any other payload has to fit the buffer
+    if (sighash_stream_skip(tx_get_buffer() + CRYPTO_BLOB_SKIP_BYTES,
+                            tx_get_buffer_length() - CRYPTO_BLOB_SKIP_BYTES,
+                            &(G_io_apdu_buffer[OFFSET_DATA]), len) == zxerr_ok) {
+        return;
+    }
+
+    tx_initialized = false;
+    progress_finish();
+    THROW(APDU_CODE_OUTPUT_BUFFER_TOO_SMALL);
+}
+
+// The init chunk of a transaction ends with its length when P2 is
+// P2_DECLARED_LENGTH, it is read and left out of rx so that the paths
+// before it are read as usual. Without it no progress is shown
+__Z_INLINE uint32_t read_declared_length(uint32_t rx) {
+    if (rx < OFFSET_DATA || G_io_apdu_buffer[OFFSET_PAYLOAD_TYPE] != 0) {
+        return rx;
+    }
+    if ((G_io_apdu_buffer[OFFSET_P2] & P2_DECLARED_LENGTH) == 0) {
+        progress_reset(0);
+        return rx;
+    }
+    if (rx < OFFSET_DATA + DECLARED_LENGTH_LEN) {
+        THROW(APDU_CODE_WRONG_LENGTH);
     }
 
-    MEMCPY(hdPath, G_io_apdu_buffer + offset, sizeof(uint32_t) * path_len);
-    hdPath_len = path_len;
+    const uint8_t *len = G_io_apdu_buffer + rx - DECLARED_LENGTH_LEN;
+    progress_reset(((uint32_t) len[0] << 24) | ((uint32_t) len[1] << 16) |
+                   ((uint32_t) len[2] << 8) | (uint32_t) len[3]);
+    return rx - DECLARED_LENGTH_LEN;
 }
 
+// Whether the chunk is the last one received, sent again by a host that did
+// not get its reply. With P2_SEQUENCED on the init chunk, the chunks that
+// follow carry their sequence number in P2, from 1 up to 255 and then 1 again
+__Z_INLINE bool is_retransmission(uint32_t rx) {
+    const uint8_t payloadType = G_io_apdu_buffer[OFFSET_PAYLOAD_TYPE];
+    const uint8_t sequence = G_io_apdu_buffer[OFFSET_P2];
+
+    if (!chunk_sequenced) {
+        if (sequence != 0) {
+            THROW(APDU_CODE_INVALIDP1P2);
+        }
+        return false;
+    }
+
+    uint8_t digest[CX_SHA256_SIZE];
+    cx_hash_sha256(G_io_apdu_buffer + OFFSET_DATA, rx - OFFSET_DATA, digest, CX_SHA256_SIZE);
+
+    if (chunk_sequence != 0 && sequence == chunk_sequence) {
+        // the same number for other data can not be told apart from a lost chunk
+        if (payloadType != chunk_type || MEMCMP(digest, chunk_digest, CX_SHA256_SIZE) != 0) {
+            tx_initialized = false;
+            progress_finish();
+            THROW(APDU_CODE_DATA_INVALID);
+        }
+        return true;
+    }
+
+    const uint8_t expected = chunk_sequence == 0xFF ? 1 : chunk_sequence + 1;
+    if (sequence != expected) {
+        tx_initialized = false;
+        progress_finish();
+        THROW(APDU_CODE_DATA_INVALID);
+    }
 
+    chunk_sequence = sequence;
+    chunk_type = payloadType;
+    MEMCPY(chunk_digest, digest, CX_SHA256_SIZE);
+    return false;
+}
 
 __Z_INLINE bool process_chunk(uint32_t rx) {
     const uint8_t payloadType = G_io_apdu_buffer[OFFSET_PAYLOAD_TYPE];
@@ -51,73 +173,59 @@ __Z_INLINE bool process_chunk(uint32_t rx) {
         THROW(APDU_CODE_WRONG_LENGTH);
     }
 
-    if (G_io_apdu_buffer[OFFSET_P2] != 0) {
-        THROW(APDU_CODE_INVALIDP1P2);
-    }
-
+    chunk_retransmitted = false;
 
-    uint32_t added;
     switch (payloadType) {
         case 0:
+            // only the init chunk can declare the transaction length
+            if ((G_io_apdu_buffer[OFFSET_P2] & ~(P2_DECLARED_LENGTH | P2_SEQUENCED)) != 0) {
+                THROW(APDU_CODE_INVALIDP1P2);
+            }
             tx_initialize();
             tx_reset();
+            sighash_stream_reset();
             tx_initialized = true;
+            tx_received = false;
+            chunk_sequenced = (G_io_apdu_buffer[OFFSET_P2] & P2_SEQUENCED) != 0;
+            chunk_sequence = 0;
             return false;
         case 1:
             if (!tx_initialized) {
                 THROW(APDU_CODE_TX_NOT_INITIALIZED);
             }
-            added = tx_append(&(G_io_apdu_buffer[OFFSET_DATA]), rx - OFFSET_DATA);
-            if (added != rx - OFFSET_DATA) {
-                tx_initialized = false;
-                THROW(APDU_CODE_OUTPUT_BUFFER_TOO_SMALL);
+            if (is_retransmission(rx)) {
+                // it was already appended, only its reply was lost
+                chunk_retransmitted = true;
+                return false;
             }
+            append_chunk(rx);
+            // Hashed as it arrives, so that signing does not hash the whole
+            // transaction at once. The reply only goes out once it is done
+            sighash_stream_update(tx_get_buffer() + CRYPTO_BLOB_SKIP_BYTES,
+                                  tx_get_buffer_length() - CRYPTO_BLOB_SKIP_BYTES);
+            progress_update(tx_get_buffer_length() + sighash_stream_skipped());
             return false;
         case 2:
             if (!tx_initialized) {
                 THROW(APDU_CODE_TX_NOT_INITIALIZED);
             }
-            added = tx_append(&(G_io_apdu_buffer[OFFSET_DATA]), rx - OFFSET_DATA);
-            if (added != rx - OFFSET_DATA) {
-                tx_initialized = false;
-                THROW(APDU_CODE_OUTPUT_BUFFER_TOO_SMALL);
+            if (is_retransmission(rx)) {
+                // the whole transaction is in the buffer, it is parsed again
+                chunk_retransmitted = true;
+                return true;
             }
+            append_chunk(rx);
+            sighash_stream_update(tx_get_buffer() + CRYPTO_BLOB_SKIP_BYTES,
+                                  tx_get_buffer_length() - CRYPTO_BLOB_SKIP_BYTES);
+            progress_finish();
+            tx_received = true;
             return true;
     }
     tx_initialized = false;
+    progress_finish();
     THROW(APDU_CODE_INVALIDP1P2);
 }
 
-__Z_INLINE void extract_default_path(uint32_t rx, uint32_t offset) {
-
-    extractHDPath(rx, offset, HDPATH_LEN_DEFAULT);
-
-    // validate
-    bool mainnet = hdPath[0] == HDPATH_0_DEFAULT &&
-                   hdPath[1] == HDPATH_1_DEFAULT;
-
-    mainnet |= (hdPath[0] == HDPATH_0_ALTERNATIVE);
-
-    const bool testnet = hdPath[0] == HDPATH_0_TESTNET &&
-                         hdPath[1] == HDPATH_1_TESTNET;
-
-    if (!mainnet && !testnet)
-        THROW(APDU_CODE_DATA_INVALID);
-
-}
-
-__Z_INLINE void extract_identity_path(uint32_t rx, uint32_t offset) {
-
-    extractHDPath(rx, offset, HDPATH_LEN_AUTH);
-
-    // validate
-    const bool identity_path = hdPath[0] == HDPATH_0_AUTH &&
-                               hdPath[1] == HDPATH_1_AUTH;
-    if (!identity_path)
-        THROW(APDU_CODE_DATA_INVALID);
-
-}
-
 __Z_INLINE void handle_getversion(volatile uint32_t *flags, volatile uint32_t *tx, uint32_t rx) {
     UNUSED(flags);
     UNUSED(rx);
@@ -136,41 +244,304 @@ __Z_INLINE void handle_getversion(volatile uint32_t *flags, volatile uint32_t *t
     G_io_apdu_buffer[7] = (TARGET_ID >> 8) & 0xFF;
     G_io_apdu_buffer[8] = (TARGET_ID >> 0) & 0xFF;
 
-    *tx += 9;
+    G_io_apdu_buffer[9] = PROTOCOL_VERSION;
+
+    const uint32_t max_tx_len = tx_get_max_buffer_length();
+    G_io_apdu_buffer[10] = (max_tx_len >> 24) & 0xFF;
+    G_io_apdu_buffer[11] = (max_tx_len >> 16) & 0xFF;
+    G_io_apdu_buffer[12] = (max_tx_len >> 8) & 0xFF;
+    G_io_apdu_buffer[13] = (max_tx_len >> 0) & 0xFF;
+
+    G_io_apdu_buffer[14] = VERSION_FLAG_WINDOWED_CONTRACT_DEPLOY | VERSION_FLAG_DECLARED_LENGTH |
+                           VERSION_FLAG_SEQUENCED_CHUNKS;
+
+    *tx += 15;
+    THROW(APDU_CODE_OK);
+}
+
+__Z_INLINE void handleGetStatus(__Z_UNUSED volatile uint32_t *flags, volatile uint32_t *tx, __Z_UNUSED uint32_t rx) {
+    // Chunks are answered before they are hashed, so a host that timed out
+    // waiting for a reply can poll how much was received and resume the
+    // session instead of starting over
+    uint8_t status = STATUS_IDLE;
+    if (tx_initialized) {
+        status = tx_received ? STATUS_RECEIVED : STATUS_RECEIVING;
+    }
+    const uint32_t received = tx_initialized ? tx_get_buffer_length() + sighash_stream_skipped() : 0;
+    const uint32_t declared = progress_declared_length();
+
+    G_io_apdu_buffer[0] = status;
+    G_io_apdu_buffer[1] = (received >> 24) & 0xFF;
+    G_io_apdu_buffer[2] = (received >> 16) & 0xFF;
+    G_io_apdu_buffer[3] = (received >> 8) & 0xFF;
+    G_io_apdu_buffer[4] = (received >> 0) & 0xFF;
+    G_io_apdu_buffer[5] = (declared >> 24) & 0xFF;
+    G_io_apdu_buffer[6] = (declared >> 16) & 0xFF;
+    G_io_apdu_buffer[7] = (declared >> 8) & 0xFF;
+    G_io_apdu_buffer[8] = (declared >> 0) & 0xFF;
+
+    *tx = 9;
     THROW(APDU_CODE_OK);
 }
 
+#if defined(TARGET_STAX) || defined(TARGET_FLEX)
+static void review_address() {
+    review_nbgl_address_show((const char *) (G_io_apdu_buffer + VIEW_ADDRESS_OFFSET_SECP256K1),
+                             addr_getItem,
+                             addr_getNumItems,
+                             app_reply_address,
+                             app_reject);
+}
+#endif
+
 __Z_INLINE void handleGetAddrSecp256K1(volatile uint32_t *flags, volatile uint32_t *tx, uint32_t rx) {
-    extract_default_path(rx, OFFSET_DATA);
+    extract_path(rx, OFFSET_DATA, path_kind_account);
 
     uint8_t requireConfirmation = G_io_apdu_buffer[OFFSET_P1];
-    uint8_t network = G_io_apdu_buffer[OFFSET_P2];
+    uint8_t network = G_io_apdu_buffer[OFFSET_P2] & ~ADDR_FLAG_UNCOMPRESSED;
+    const bool uncompressed = (G_io_apdu_buffer[OFFSET_P2] & ADDR_FLAG_UNCOMPRESSED) != 0;
 
     // Set the address version
     if (!set_network_version(network))
         return THROW(APDU_CODE_DATA_INVALID);
 
     if (requireConfirmation) {
-        app_fill_address(addr_secp256k1);
+        app_fill_address(addr_secp256k1, uncompressed);
 
+#if defined(TARGET_STAX) || defined(TARGET_FLEX)
+        if (!isStandardPath()) {
+            review_nbgl_warning_show(LABEL_NONSTANDARD_PATH,
+                                     LABEL_NONSTANDARD_PATH_MESSAGE,
+                                     review_address,
+                                     app_reject);
+        } else {
+            review_address();
+        }
+#else
         view_review_init(addr_getItem, addr_getNumItems, app_reply_address);
         view_review_show(REVIEW_ADDRESS);
+#endif
 
         *flags |= IO_ASYNCH_REPLY;
         return;
     }
 
-    *tx = app_fill_address(addr_secp256k1);
+    *tx = app_fill_address(addr_secp256k1, uncompressed);
     THROW(APDU_CODE_OK);
 }
 
 __Z_INLINE void handleGetAuthPubKey(__Z_UNUSED volatile uint32_t *flags, volatile uint32_t *tx, uint32_t rx) {
-    extract_identity_path(rx, OFFSET_DATA);
+    extract_path(rx, OFFSET_DATA, path_kind_identity);
 
     *tx = app_fill_auth_pubkey(addr_secp256k1);
     THROW(APDU_CODE_OK);
 }
 
+__Z_INLINE void handleGetBtcAddress(volatile uint32_t *flags, volatile uint32_t *tx, uint32_t rx) {
+    extract_path(rx, OFFSET_DATA, path_kind_bitcoin);
+
+    const uint8_t requireConfirmation = G_io_apdu_buffer[OFFSET_P1];
+    if (app_fill_btc_address() == 0) {
+        THROW(APDU_CODE_EXECUTION_ERROR);
+    }
+
+    if (requireConfirmation) {
+#if defined(TARGET_STAX) || defined(TARGET_FLEX)
+        review_nbgl_address_show((const char *) (G_io_apdu_buffer + VIEW_ADDRESS_OFFSET_SECP256K1),
+                                 btc_addr_getItem,
+                                 btc_addr_getNumItems,
+                                 app_reply_address,
+                                 app_reject);
+#else
+        view_review_init(btc_addr_getItem, btc_addr_getNumItems, app_reply_address);
+        view_review_show(REVIEW_ADDRESS);
+#endif
+        *flags |= IO_ASYNCH_REPLY;
+        return;
+    }
+
+    *tx = action_addr_len;
+    THROW(APDU_CODE_OK);
+}
+
+__Z_INLINE void handleGetAccounts(__Z_UNUSED volatile uint32_t *flags, volatile uint32_t *tx, uint32_t rx) {
+    if (rx < OFFSET_DATA + 5) {
+        THROW(APDU_CODE_WRONG_LENGTH);
+    }
+
+    const uint8_t network = G_io_apdu_buffer[OFFSET_P1];
+    if (network > 1) {
+        THROW(APDU_CODE_INVALIDP1P2);
+    }
+
+    // first account index, big endian, and how many accounts from it
+    const uint8_t *data = G_io_apdu_buffer + OFFSET_DATA;
+    const uint32_t start = ((uint32_t) data[0] << 24) | ((uint32_t) data[1] << 16) |
+                           ((uint32_t) data[2] << 8) | (uint32_t) data[3];
+    const uint8_t count = data[4];
+
+    *tx = crypto_fillAccounts(start, count, network == 1, G_io_apdu_buffer, IO_APDU_BUFFER_SIZE - 2);
+    if (*tx == 0) {
+        THROW(APDU_CODE_DATA_INVALID);
+    }
+    THROW(APDU_CODE_OK);
+}
+
+__Z_INLINE void handleGetMultisigSlot(volatile uint32_t *flags, volatile uint32_t *tx, uint32_t rx) {
+    // the init chunk carries the path of the key, the next ones the template
+    if (G_io_apdu_buffer[OFFSET_PAYLOAD_TYPE] == 0) {
+        extract_path(rx, OFFSET_DATA, path_kind_account);
+    }
+
+    if (!process_chunk(rx)) {
+        THROW(APDU_CODE_OK);
+    }
+
+    if (tx_get_buffer_length() > UINT16_MAX) {
+        THROW(APDU_CODE_DATA_INVALID);
+    }
+    *tx = crypto_fillMultisigSlot(tx_get_buffer(), (uint16_t) tx_get_buffer_length(),
+                                  G_io_apdu_buffer, IO_APDU_BUFFER_SIZE - 2);
+    if (*tx == 0) {
+        THROW(APDU_CODE_DATA_INVALID);
+    }
+    THROW(APDU_CODE_OK);
+}
+
+// Reports the settings so hosts can point users to the one to change, or
+// updates one of them once the user approves the change on the device
+__Z_INLINE void handleSettings(volatile uint32_t *flags, volatile uint32_t *tx, uint32_t rx) {
+    switch (G_io_apdu_buffer[OFFSET_P1]) {
+        case SETTINGS_QUERY:
+            *tx = settings_fill(G_io_apdu_buffer, IO_APDU_BUFFER_SIZE - 2);
+            if (*tx == 0) {
+                THROW(APDU_CODE_EXECUTION_ERROR);
+            }
+            THROW(APDU_CODE_OK);
+        case SETTINGS_UPDATE:
+            break;
+        default:
+            THROW(APDU_CODE_INVALIDP1P2);
+    }
+
+    if (rx != OFFSET_DATA + SETTINGS_UPDATE_LEN) {
+        THROW(APDU_CODE_WRONG_LENGTH);
+    }
+    const uint8_t *data = G_io_apdu_buffer + OFFSET_DATA;
+    uint64_t value = 0;
+    for (uint8_t i = 1; i < SETTINGS_UPDATE_LEN; i++) {
+        value = (value << 8) | data[i];
+    }
+    if (settings_review_load((setting_id_e) data[0], value) != zxerr_ok) {
+        THROW(APDU_CODE_DATA_INVALID);
+    }
+
+    CHECK_APP_CANARY()
+#if defined(TARGET_STAX) || defined(TARGET_FLEX)
+    review_nbgl_show(settings_review_getItem, settings_review_getNumItems,
+                     settings_review_getItemSection, settings_review_getItemKind,
+                     LABEL_UPDATE_SETTING_CONFIRM, app_update_setting, app_reject);
+#else
+    view_review_init(settings_review_getItem, settings_review_getNumItems, app_update_setting);
+    view_review_show(REVIEW_TXN);
+#endif
+    *flags |= IO_ASYNCH_REPLY;
+}
+
+// The warnings that apply are shown one after the other before the
+// transaction is reviewed, each one has to be acknowledged
+#define TX_WARNING_STEPS 3
+
+static uint8_t tx_warning_step;
+
+// The review shown once every warning is acknowledged
+static void (*tx_warnings_review)();
+
+static void review_transaction() {
+#if defined(TARGET_STAX) || defined(TARGET_FLEX)
+    review_nbgl_show(tx_getItem, tx_getNumItems, tx_getItemSection, tx_getItemKind,
+                     LABEL_SIGN_TX, app_sign, app_reject);
+#else
+    view_review_init(tx_getItem, tx_getNumItems, app_sign);
+    view_review_show(REVIEW_TXN);
+#endif
+}
+
+#if !defined(TARGET_STAX) && !defined(TARGET_FLEX)
+// On Nano a warning is a review of its own, approving it acknowledges it
+static const char *tx_warning_title;
+static const char *tx_warning_message;
+
+static zxerr_t tx_warning_getNumItems(uint8_t *num_items) {
+    *num_items = 1;
+    return zxerr_ok;
+}
+
+static zxerr_t tx_warning_getItem(int8_t displayIdx,
+                                  char *outKey, uint16_t outKeyLen,
+                                  char *outValue, uint16_t outValueLen,
+                                  uint8_t pageIdx, uint8_t *pageCount) {
+    if (displayIdx != 0) {
+        return zxerr_no_data;
+    }
+    snprintf(outKey, outKeyLen, "%s", tx_warning_title);
+    pageString(outValue, outValueLen, tx_warning_message, pageIdx, pageCount);
+    return zxerr_ok;
+}
+#endif
+
+static void review_transaction_warnings();
+
+static void show_transaction_warning(const char *title, const char *message) {
+#if defined(TARGET_STAX) || defined(TARGET_FLEX)
+    review_nbgl_warning_show(title, message, review_transaction_warnings, app_reject);
+#else
+    tx_warning_title = title;
+    tx_warning_message = message;
+    view_review_init(tx_warning_getItem, tx_warning_getNumItems, review_transaction_warnings);
+    view_review_show(REVIEW_TXN);
+#endif
+}
+
+static void review_transaction_warnings() {
+    while (tx_warning_step < TX_WARNING_STEPS) {
+        switch (tx_warning_step++) {
+#if defined(TARGET_STAX) || defined(TARGET_FLEX)
+            // on Nano the path is the first item of the review
+            case 0:
+                if (tx_is_nonstandard_path() == 1) {
+                    show_transaction_warning(LABEL_NONSTANDARD_PATH, LABEL_NONSTANDARD_PATH_MESSAGE);
+                    return;
+                }
+                break;
+#endif
+            case 1:
+                if (tx_is_denylisted() == 1) {
+                    show_transaction_warning(LABEL_DENYLISTED, LABEL_DENYLISTED_MESSAGE);
+                    return;
+                }
+                break;
+            case 2:
+                // a batch warns if any of its transactions has a high fee
+                if (tx_is_high_fee() == 1 || batch_high_fee()) {
+                    show_transaction_warning(LABEL_HIGH_FEE, LABEL_HIGH_FEE_MESSAGE);
+                    return;
+                }
+                break;
+            default:
+                break;
+        }
+    }
+    tx_warnings_review();
+}
+
+// Shows the warnings of the parsed transaction, then its review
+static void review_transaction_warnings_then(void (*review)()) {
+    tx_warning_step = 0;
+    tx_warnings_review = review;
+    review_transaction_warnings();
+}
+
 __Z_INLINE void SignSecp256K1(volatile uint32_t *flags, volatile uint32_t *tx, uint32_t rx) {
     // process the rest of the chunk as usual
     if (!process_chunk(rx)) {
@@ -179,6 +550,11 @@ __Z_INLINE void SignSecp256K1(volatile uint32_t *flags, volatile uint32_t *tx, u
 
     const char *error_msg = tx_parse();
 
+    if (error_msg == NULL && tx_get_transaction_type() == AuthChallenge) {
+        // the domain a login is for is reviewed with the key it signs with
+        error_msg = "Authentication challenges have their own command";
+    }
+
     if (error_msg != NULL) {
         int error_msg_length = strlen(error_msg);
         MEMCPY(G_io_apdu_buffer, error_msg, error_msg_length);
@@ -189,82 +565,860 @@ __Z_INLINE void SignSecp256K1(volatile uint32_t *flags, volatile uint32_t *tx, u
     zemu_log_stack("tx_parse done\n");
 
     CHECK_APP_CANARY()
-    view_review_init(tx_getItem, tx_getNumItems, app_sign);
-    view_review_show(REVIEW_TXN);
+    review_transaction_warnings_then(review_transaction);
     *flags |= IO_ASYNCH_REPLY;
 }
 
 
 __Z_INLINE void handleSignSecp256K1(volatile uint32_t *flags, volatile uint32_t *tx, uint32_t rx) {
+    rx = read_declared_length(rx);
+
     // check first for the expected path at initialization
     if (G_io_apdu_buffer[OFFSET_PAYLOAD_TYPE] == 0) {
-        extract_default_path(rx, OFFSET_DATA);
+        sign_session_reset();
+        batch_reset();
+        msg_batch_reset();
+        extract_path(rx, OFFSET_DATA, path_kind_account);
     }
 
     SignSecp256K1(flags, tx, rx);
 }
 
-__Z_INLINE void handleSignJwtSecp256K1(volatile uint32_t *flags, volatile uint32_t *tx, uint32_t rx) {
-    // check first for the expected path at initialization
+static void review_multipath() {
+    view_review_init(sign_session_getItem, sign_session_getNumItems, app_sign_multipath);
+    view_review_show(REVIEW_TXN);
+}
+
+__Z_INLINE void handleSignMultipathSecp256K1(volatile uint32_t *flags, volatile uint32_t *tx, uint32_t rx) {
+    rx = read_declared_length(rx);
+
+    // the init chunk carries the number of paths followed by the paths
     if (G_io_apdu_buffer[OFFSET_PAYLOAD_TYPE] == 0) {
-        extract_identity_path(rx, OFFSET_DATA);
+        if (rx < OFFSET_DATA) {
+            THROW(APDU_CODE_WRONG_LENGTH);
+        }
+        batch_reset();
+        msg_batch_reset();
+        if (sign_session_set_paths(G_io_apdu_buffer + OFFSET_DATA, rx - OFFSET_DATA) != zxerr_ok) {
+            THROW(APDU_CODE_DATA_INVALID);
+        }
     }
 
-    SignSecp256K1(flags, tx, rx);
+    if (!process_chunk(rx)) {
+        THROW(APDU_CODE_OK);
+    }
+
+    const char *error_msg = tx_parse();
+
+    if (error_msg == NULL && tx_is_multisig() != 1) {
+        // every path is a different slot of the same multisig origin
+        error_msg = "Multi-path signing requires a multisig transaction";
+    }
+
+    if (error_msg != NULL) {
+        sign_session_reset();
+        int error_msg_length = strlen(error_msg);
+        MEMCPY(G_io_apdu_buffer, error_msg, error_msg_length);
+        *tx += (error_msg_length);
+        THROW(APDU_CODE_DATA_INVALID);
+    }
+
+    CHECK_APP_CANARY()
+    review_transaction_warnings_then(review_multipath);
+    *flags |= IO_ASYNCH_REPLY;
 }
 
-void handleApdu(volatile uint32_t *flags, volatile uint32_t *tx, uint32_t rx) {
-    uint16_t sw = 0;
+static void review_sponsored() {
+    view_review_init(sign_session_getItem, sign_session_getNumItems, app_sign_sponsored);
+    view_review_show(REVIEW_TXN);
+}
 
-    BEGIN_TRY
-    {
-        TRY
-        {
-            if (G_io_apdu_buffer[OFFSET_CLA] != CLA) {
-                THROW(APDU_CODE_CLA_NOT_SUPPORTED);
-            }
+__Z_INLINE void handleSignSponsoredSecp256K1(volatile uint32_t *flags, volatile uint32_t *tx, uint32_t rx) {
+    rx = read_declared_length(rx);
 
-            if (rx < APDU_MIN_LENGTH) {
-                THROW(APDU_CODE_WRONG_LENGTH);
-            }
+    // the init chunk carries the origin path followed by the sponsor path
+    if (G_io_apdu_buffer[OFFSET_PAYLOAD_TYPE] == 0) {
+        if (rx < OFFSET_DATA) {
+            THROW(APDU_CODE_WRONG_LENGTH);
+        }
+        batch_reset();
+        msg_batch_reset();
+        if (sign_session_set_sponsor_paths(G_io_apdu_buffer + OFFSET_DATA, rx - OFFSET_DATA) != zxerr_ok) {
+            THROW(APDU_CODE_DATA_INVALID);
+        }
+    }
 
-            switch (G_io_apdu_buffer[OFFSET_INS]) {
-                case INS_GET_VERSION: {
-                    handle_getversion(flags, tx, rx);
-                    break;
-                }
+    if (!process_chunk(rx)) {
+        THROW(APDU_CODE_OK);
+    }
 
-                case INS_GET_ADDR_SECP256K1: {
-                    if (os_global_pin_is_validated() != BOLOS_UX_OK) {
-                        THROW(APDU_CODE_COMMAND_NOT_ALLOWED);
-                    }
-                    handleGetAddrSecp256K1(flags, tx, rx);
-                    break;
-                }
+    const char *error_msg = tx_parse();
 
-                case INS_GET_AUTH_PUBKEY: {
-                    if (os_global_pin_is_validated() != BOLOS_UX_OK) {
-                        THROW(APDU_CODE_COMMAND_NOT_ALLOWED);
-                    }
-                    handleGetAuthPubKey(flags, tx, rx);
-                    break;
-                }
+    if (error_msg == NULL &&
+        tx_check_sponsor(sign_session_sponsor_path(), HDPATH_LEN_DEFAULT) != zxerr_ok) {
+        // the origin was already checked against hdPath by tx_parse
+        error_msg = "Sponsor key does not match the transaction";
+    }
 
-                case INS_SIGN_SECP256K1: {
-                    if (os_global_pin_is_validated() != BOLOS_UX_OK) {
-                        THROW(APDU_CODE_COMMAND_NOT_ALLOWED);
-                    }
-                    handleSignSecp256K1(flags, tx, rx);
-                    break;
-                }
+    if (error_msg != NULL) {
+        sign_session_reset();
+        int error_msg_length = strlen(error_msg);
+        MEMCPY(G_io_apdu_buffer, error_msg, error_msg_length);
+        *tx += (error_msg_length);
+        THROW(APDU_CODE_DATA_INVALID);
+    }
 
-                case SIGN_JWT_SECP256K1: {
-                    if (os_global_pin_is_validated() != BOLOS_UX_OK) {
-                        THROW(APDU_CODE_COMMAND_NOT_ALLOWED);
-                    }
-                    handleSignJwtSecp256K1(flags, tx, rx);
-                    break;
-                }
+    CHECK_APP_CANARY()
+    review_transaction_warnings_then(review_sponsored);
+    *flags |= IO_ASYNCH_REPLY;
+}
+
+static void review_batch() {
+    view_review_init(batch_getItem, batch_getNumItems, app_sign_batch);
+    view_review_show(REVIEW_TXN);
+}
+
+__Z_INLINE void handleSignBatchSecp256K1(volatile uint32_t *flags, volatile uint32_t *tx, uint32_t rx) {
+    rx = read_declared_length(rx);
+
+    // the init chunk of every transaction carries the batch flags followed by the path
+    if (G_io_apdu_buffer[OFFSET_PAYLOAD_TYPE] == 0) {
+        if (rx <= OFFSET_DATA) {
+            THROW(APDU_CODE_WRONG_LENGTH);
+        }
+        batch_flags = G_io_apdu_buffer[OFFSET_DATA];
+        if ((batch_flags & BATCH_FLAG_FIRST) != 0) {
+            sign_session_reset();
+            batch_reset();
+            msg_batch_reset();
+        } else if (batch_num_transactions() == 0) {
+            THROW(APDU_CODE_DATA_INVALID);
+        }
+        extract_path(rx, OFFSET_DATA + 1, path_kind_account);
+    }
+
+    if (!process_chunk(rx)) {
+        THROW(APDU_CODE_OK);
+    }
+
+    if (chunk_retransmitted && (batch_flags & BATCH_FLAG_LAST) == 0) {
+        // the transaction was already added, only its reply was lost
+        G_io_apdu_buffer[0] = batch_num_transactions();
+        *tx = 1;
+        THROW(APDU_CODE_OK);
+    }
+
+    const char *error_msg = tx_parse();
+
+    if (error_msg == NULL) {
+        payload_kind_t kind;
+        // the summaries only describe single-sig STX transfers
+        if (tx_payload_kind(&kind) != zxerr_ok || kind != payload_kind_token_transfer ||
+            tx_is_multisig() || tx_is_sponsored()) {
+            error_msg = "Batches only sign single-sig STX transfers";
+        }
+    }
+
+    if (error_msg == NULL) {
+        switch (app_batch_load()) {
+            case zxerr_ok:
+                break;
+            case zxerr_buffer_too_small:
+                error_msg = "Too many transactions in the batch";
+                break;
+            case zxerr_out_of_bounds:
+                error_msg = "Batch nonces must be consecutive";
+                break;
+            case zxerr_invalid_crypto_settings:
+                error_msg = "Batch transactions must use the same path";
+                break;
+            default:
+                error_msg = "Could not add the transaction to the batch";
+                break;
+        }
+    }
+
+    if (error_msg != NULL) {
+        batch_reset();
+        int error_msg_length = strlen(error_msg);
+        MEMCPY(G_io_apdu_buffer, error_msg, error_msg_length);
+        *tx += (error_msg_length);
+        THROW(APDU_CODE_DATA_INVALID);
+    }
+
+    if ((batch_flags & BATCH_FLAG_LAST) == 0) {
+        // more transactions follow, reply with how many were loaded
+        G_io_apdu_buffer[0] = batch_num_transactions();
+        *tx = 1;
+        THROW(APDU_CODE_OK);
+    }
+
+    CHECK_APP_CANARY()
+    review_transaction_warnings_then(review_batch);
+    *flags |= IO_ASYNCH_REPLY;
+}
+
+__Z_INLINE void handleSignMsgBatchSecp256K1(volatile uint32_t *flags, volatile uint32_t *tx, uint32_t rx) {
+    rx = read_declared_length(rx);
+
+    // the init chunk of every message carries the batch flags followed by the path
+    const bool init = G_io_apdu_buffer[OFFSET_PAYLOAD_TYPE] == 0;
+    if (init) {
+        if (rx <= OFFSET_DATA) {
+            THROW(APDU_CODE_WRONG_LENGTH);
+        }
+        batch_flags = G_io_apdu_buffer[OFFSET_DATA];
+        if ((batch_flags & BATCH_FLAG_FIRST) != 0) {
+            sign_session_reset();
+            batch_reset();
+            msg_batch_reset();
+        } else if (msg_batch_num_messages() == 0) {
+            THROW(APDU_CODE_DATA_INVALID);
+        }
+        extract_path(rx, OFFSET_DATA + 1, path_kind_account);
+    }
+
+    if (!process_chunk(rx)) {
+        // only the first message carries the domain, the
+        // messages that follow are put after it
+        uint16_t header_len = 0;
+        const uint8_t *header = msg_batch_header(&header_len);
+        if (init && header != NULL && tx_append((unsigned char *) header, header_len) != header_len) {
+            THROW(APDU_CODE_OUTPUT_BUFFER_TOO_SMALL);
+        }
+        THROW(APDU_CODE_OK);
+    }
+
+    if (chunk_retransmitted && (batch_flags & BATCH_FLAG_LAST) == 0) {
+        // the message was already added, only its reply was lost
+        G_io_apdu_buffer[0] = msg_batch_num_messages();
+        *tx = 1;
+        THROW(APDU_CODE_OK);
+    }
+
+    const char *error_msg = tx_parse();
+
+    if (error_msg == NULL && tx_get_transaction_type() != StructuredMsg) {
+        error_msg = "Message batches only sign structured messages";
+    }
+
+    if (error_msg == NULL && app_msg_batch_load() != zxerr_ok) {
+        error_msg = "Could not add the message to the batch";
+    }
+
+    if (error_msg != NULL) {
+        msg_batch_reset();
+        int error_msg_length = strlen(error_msg);
+        MEMCPY(G_io_apdu_buffer, error_msg, error_msg_length);
+        *tx += (error_msg_length);
+        THROW(APDU_CODE_DATA_INVALID);
+    }
+
+    if ((batch_flags & BATCH_FLAG_LAST) == 0) {
+        // more messages follow, reply with how many were loaded
+        G_io_apdu_buffer[0] = msg_batch_num_messages();
+        *tx = 1;
+        THROW(APDU_CODE_OK);
+    }
+
+    CHECK_APP_CANARY()
+    view_review_init(msg_batch_getItem, msg_batch_getNumItems, app_sign_msg_batch);
+    view_review_show(REVIEW_TXN);
+    *flags |= IO_ASYNCH_REPLY;
+}
+
+__Z_INLINE void handleGetSessionSignature(__Z_UNUSED volatile uint32_t *flags, volatile uint32_t *tx, __Z_UNUSED uint32_t rx) {
+    uint16_t replyLen = 0;
+    // an approved batch is signed the same way as the paths of a session
+    zxerr_t err = zxerr_no_data;
+    if (batch_pending()) {
+        err = batch_next(G_io_apdu_buffer, IO_APDU_BUFFER_SIZE - 3, &replyLen);
+    } else if (msg_batch_pending()) {
+        err = msg_batch_next(G_io_apdu_buffer, IO_APDU_BUFFER_SIZE - 3, &replyLen);
+    } else {
+        err = sign_session_next(G_io_apdu_buffer, IO_APDU_BUFFER_SIZE - 3, &replyLen);
+    }
+    if (err != zxerr_ok || replyLen == 0) {
+        THROW(APDU_CODE_DATA_INVALID);
+    }
+
+    *tx = replyLen;
+    THROW(APDU_CODE_OK);
+}
+
+// Returns the auth field of the last multisig signature and the index of the
+// field it goes in, so coordinators splice it without guessing. Nothing is shown
+__Z_INLINE void handleGetAuthField(__Z_UNUSED volatile uint32_t *flags, volatile uint32_t *tx, __Z_UNUSED uint32_t rx) {
+    uint16_t replyLen = 0;
+    if (auth_field_get(G_io_apdu_buffer, IO_APDU_BUFFER_SIZE - 3, &replyLen) != zxerr_ok || replyLen == 0) {
+        THROW(APDU_CODE_DATA_INVALID);
+    }
+
+    *tx = replyLen;
+    THROW(APDU_CODE_OK);
+}
+
+static void review_fee_bump() {
+    view_review_init(fee_bump_getItem, fee_bump_getNumItems, app_sign);
+    view_review_show(REVIEW_TXN);
+}
+
+__Z_INLINE void handleSignFeeBumpSecp256K1(volatile uint32_t *flags, volatile uint32_t *tx, uint32_t rx) {
+    rx = read_declared_length(rx);
+
+    // check first for the expected path at initialization
+    if (G_io_apdu_buffer[OFFSET_PAYLOAD_TYPE] == 0) {
+        // a leftover batch would add its fees to the high fee warning
+        batch_reset();
+        extract_path(rx, OFFSET_DATA, path_kind_account);
+    }
+
+    if (!process_chunk(rx)) {
+        THROW(APDU_CODE_OK);
+    }
+
+    const char *error_msg = tx_parse();
+
+    if (error_msg == NULL && app_check_fee_bump() != zxerr_ok) {
+        error_msg = "Transaction does not match the last signed one";
+    }
+
+    if (error_msg != NULL) {
+        int error_msg_length = strlen(error_msg);
+        MEMCPY(G_io_apdu_buffer, error_msg, error_msg_length);
+        *tx += (error_msg_length);
+        THROW(APDU_CODE_DATA_INVALID);
+    }
+
+    CHECK_APP_CANARY()
+    // the new fee can be high even if the one first signed was not
+    review_transaction_warnings_then(review_fee_bump);
+    *flags |= IO_ASYNCH_REPLY;
+}
+
+__Z_INLINE void handleSignJwtSecp256K1(volatile uint32_t *flags, volatile uint32_t *tx, uint32_t rx) {
+    rx = read_declared_length(rx);
+
+    // check first for the expected path at initialization
+    if (G_io_apdu_buffer[OFFSET_PAYLOAD_TYPE] == 0) {
+        extract_path(rx, OFFSET_DATA, path_kind_identity);
+    }
+
+    if (!process_chunk(rx)) {
+        THROW(APDU_CODE_OK);
+    }
+
+    const char *error_msg = tx_parse();
+
+    if (error_msg == NULL && tx_get_transaction_type() != Jwt) {
+        // identity keys sign authentication and profile tokens only
+        error_msg = "Identity keys only sign JWT tokens";
+    }
+
+    if (error_msg != NULL) {
+        int error_msg_length = strlen(error_msg);
+        MEMCPY(G_io_apdu_buffer, error_msg, error_msg_length);
+        *tx += (error_msg_length);
+        THROW(APDU_CODE_DATA_INVALID);
+    }
+
+    CHECK_APP_CANARY()
+#if defined(TARGET_STAX) || defined(TARGET_FLEX)
+    review_nbgl_show(identity_getItem, identity_getNumItems, identity_getItemSection, identity_getItemKind,
+                     LABEL_SIGN_IDENTITY_CONFIRM, app_sign, app_reject);
+#else
+    view_review_init(identity_getItem, identity_getNumItems, app_sign);
+    view_review_show(REVIEW_TXN);
+#endif
+    *flags |= IO_ASYNCH_REPLY;
+}
+
+// Signs a Stacks Connect authentication challenge, a dApp login, with the
+// identity key or an account key, whichever the path sent is for
+__Z_INLINE void handleSignAuthSecp256K1(volatile uint32_t *flags, volatile uint32_t *tx, uint32_t rx) {
+    rx = read_declared_length(rx);
+
+    // check first for the expected path at initialization
+    if (G_io_apdu_buffer[OFFSET_PAYLOAD_TYPE] == 0) {
+        if (rx < OFFSET_DATA) {
+            THROW(APDU_CODE_WRONG_LENGTH);
+        }
+        uint8_t path_len = 0;
+        if (rs_read_path(G_io_apdu_buffer + OFFSET_DATA, rx - OFFSET_DATA, path_kind_identity,
+                         hdPath, &path_len, HDPATH_LEN_MAX) == parser_ok) {
+            hdPath_len = path_len;
+        } else {
+            extract_path(rx, OFFSET_DATA, path_kind_account);
+        }
+    }
+
+    if (!process_chunk(rx)) {
+        THROW(APDU_CODE_OK);
+    }
+
+    const char *error_msg = tx_parse();
+
+    if (error_msg == NULL && tx_get_transaction_type() != AuthChallenge) {
+        error_msg = "Only authentication challenges are signed";
+    }
+
+    if (error_msg != NULL) {
+        int error_msg_length = strlen(error_msg);
+        MEMCPY(G_io_apdu_buffer, error_msg, error_msg_length);
+        *tx += (error_msg_length);
+        THROW(APDU_CODE_DATA_INVALID);
+    }
+
+    CHECK_APP_CANARY()
+#if defined(TARGET_STAX) || defined(TARGET_FLEX)
+    review_nbgl_show(identity_getItem, identity_getNumItems, identity_getItemSection, identity_getItemKind,
+                     LABEL_SIGN_IDENTITY_CONFIRM, app_sign, app_reject);
+#else
+    view_review_init(identity_getItem, identity_getNumItems, app_sign);
+    view_review_show(REVIEW_TXN);
+#endif
+    *flags |= IO_ASYNCH_REPLY;
+}
+
+__Z_INLINE void handleSignSchnorr(volatile uint32_t *flags, __Z_UNUSED volatile uint32_t *tx, uint32_t rx) {
+    if (!app_settings_schnorr()) {
+        THROW(APDU_CODE_COMMAND_NOT_ALLOWED);
+    }
+
+    // the message comes first, the path is the rest of the data
+    if (rx < OFFSET_DATA + SCHNORR_MESSAGE_LEN) {
+        THROW(APDU_CODE_WRONG_LENGTH);
+    }
+    extract_path(rx, OFFSET_DATA + SCHNORR_MESSAGE_LEN, path_kind_schnorr);
+
+    if (schnorr_load(G_io_apdu_buffer + OFFSET_DATA, SCHNORR_MESSAGE_LEN) != zxerr_ok) {
+        THROW(APDU_CODE_DATA_INVALID);
+    }
+
+    CHECK_APP_CANARY()
+#if defined(TARGET_STAX) || defined(TARGET_FLEX)
+    review_nbgl_show(schnorr_getItem, schnorr_getNumItems, schnorr_getItemSection, schnorr_getItemKind,
+                     LABEL_SIGN_SCHNORR_CONFIRM, app_sign_schnorr, app_reject);
+#else
+    view_review_init(schnorr_getItem, schnorr_getNumItems, app_sign_schnorr);
+    view_review_show(REVIEW_TXN);
+#endif
+    *flags |= IO_ASYNCH_REPLY;
+}
+
+// Tells whether a signature of a digest is valid for the key of a path, so
+// cosigner signatures can be checked without exporting keys. Nothing is shown
+__Z_INLINE void handleVerifySignature(__Z_UNUSED volatile uint32_t *flags, volatile uint32_t *tx, uint32_t rx) {
+    // the digest and the signature come first, the path is the rest of the data
+    if (rx < OFFSET_DATA + VERIFY_DIGEST_LEN + SIGNATURE_RSV_LEN) {
+        THROW(APDU_CODE_WRONG_LENGTH);
+    }
+    extract_path(rx, OFFSET_DATA + VERIFY_DIGEST_LEN + SIGNATURE_RSV_LEN, path_kind_account);
+
+    const uint8_t *digest = G_io_apdu_buffer + OFFSET_DATA;
+    const uint8_t *signature = digest + VERIFY_DIGEST_LEN;
+    bool valid = false;
+    if (crypto_verify(digest, VERIFY_DIGEST_LEN, signature, SIGNATURE_RSV_LEN, &valid) != zxerr_ok) {
+        THROW(APDU_CODE_EXECUTION_ERROR);
+    }
+
+    G_io_apdu_buffer[0] = valid ? 1 : 0;
+    *tx = 1;
+    THROW(APDU_CODE_OK);
+}
+
+__Z_INLINE void handleProvisionDenylist(__Z_UNUSED volatile uint32_t *flags, __Z_UNUSED volatile uint32_t *tx, uint32_t rx) {
+    if (!denylist_supported()) {
+        THROW(APDU_CODE_INS_NOT_SUPPORTED);
+    }
+
+    if (rx < OFFSET_DATA) {
+        THROW(APDU_CODE_WRONG_LENGTH);
+    }
+
+    const uint8_t *data = G_io_apdu_buffer + OFFSET_DATA;
+    const uint16_t dataLen = rx - OFFSET_DATA;
+
+    switch (G_io_apdu_buffer[OFFSET_PAYLOAD_TYPE]) {
+        case 0:
+            denylist_reset();
+            THROW(APDU_CODE_OK);
+        case 1:
+            if (denylist_append(data, dataLen) != zxerr_ok) {
+                denylist_reset();
+                THROW(APDU_CODE_DATA_INVALID);
+            }
+            THROW(APDU_CODE_OK);
+        case 2:
+            // the last chunk carries the signature of the whole list
+            if (denylist_activate(data, dataLen) != zxerr_ok) {
+                THROW(APDU_CODE_DATA_INVALID);
+            }
+            THROW(APDU_CODE_OK);
+    }
+    THROW(APDU_CODE_INVALIDP1P2);
+}
+
+__Z_INLINE void handleProvisionAbi(__Z_UNUSED volatile uint32_t *flags, __Z_UNUSED volatile uint32_t *tx, uint32_t rx) {
+    if (rx < OFFSET_DATA) {
+        THROW(APDU_CODE_WRONG_LENGTH);
+    }
+
+    const uint8_t *data = G_io_apdu_buffer + OFFSET_DATA;
+    const uint16_t dataLen = rx - OFFSET_DATA;
+
+    switch (G_io_apdu_buffer[OFFSET_PAYLOAD_TYPE]) {
+        case 0:
+            function_abi_reset();
+            THROW(APDU_CODE_OK);
+        case 1:
+            if (function_abi_append(data, dataLen) != zxerr_ok) {
+                function_abi_reset();
+                THROW(APDU_CODE_DATA_INVALID);
+            }
+            THROW(APDU_CODE_OK);
+        case 2:
+            // the last chunk carries the DER signature of the function
+            // signature, empty if it is not signed
+            if (function_abi_activate(data, dataLen) != zxerr_ok) {
+                THROW(APDU_CODE_DATA_INVALID);
+            }
+            THROW(APDU_CODE_OK);
+    }
+    THROW(APDU_CODE_INVALIDP1P2);
+}
+
+__Z_INLINE void handleProvisionTokenInfo(__Z_UNUSED volatile uint32_t *flags, __Z_UNUSED volatile uint32_t *tx, uint32_t rx) {
+    if (rx < OFFSET_DATA) {
+        THROW(APDU_CODE_WRONG_LENGTH);
+    }
+
+    const uint8_t *data = G_io_apdu_buffer + OFFSET_DATA;
+    const uint16_t dataLen = rx - OFFSET_DATA;
+
+    switch (G_io_apdu_buffer[OFFSET_PAYLOAD_TYPE]) {
+        case 0:
+            token_info_reset();
+            THROW(APDU_CODE_OK);
+        case 1:
+            if (token_info_append(data, dataLen) != zxerr_ok) {
+                token_info_reset();
+                THROW(APDU_CODE_DATA_INVALID);
+            }
+            THROW(APDU_CODE_OK);
+        case 2:
+            // the last chunk carries the DER signature of the token
+            // metadata, empty if it is not signed
+            if (token_info_activate(data, dataLen) != zxerr_ok) {
+                THROW(APDU_CODE_DATA_INVALID);
+            }
+            THROW(APDU_CODE_OK);
+    }
+    THROW(APDU_CODE_INVALIDP1P2);
+}
+
+__Z_INLINE void handleClearProvisioned(__Z_UNUSED volatile uint32_t *flags, __Z_UNUSED volatile uint32_t *tx, __Z_UNUSED uint32_t rx) {
+    // everything provisioned for the session, so an unrelated signing
+    // flow starts from the same state as after a restart
+    denylist_reset();
+    function_abi_reset();
+    token_info_reset();
+    THROW(APDU_CODE_OK);
+}
+
+#ifdef APP_TESTING
+__Z_INLINE void handleGetReviewItem(__Z_UNUSED volatile uint32_t *flags, volatile uint32_t *tx, __Z_UNUSED uint32_t rx) {
+    // one item of the review of the transaction last sent for signing,
+    // so tests can assert on it without reading the screen
+    const uint8_t displayIdx = G_io_apdu_buffer[OFFSET_P1];
+    const uint8_t pageIdx = G_io_apdu_buffer[OFFSET_P2];
+
+    uint8_t numItems = 0;
+    if (tx_getNumItems(&numItems) != zxerr_ok || displayIdx >= numItems) {
+        THROW(APDU_CODE_DATA_INVALID);
+    }
+
+    item_kind_t kind = item_kind_text;
+    if (tx_getItemKind((int8_t) displayIdx, &kind) != zxerr_ok) {
+        THROW(APDU_CODE_DATA_INVALID);
+    }
+
+    // count, kind and page count, then the title and the value page
+    char *title = (char *) G_io_apdu_buffer + 3;
+    char *value = title + REVIEW_ITEM_TITLE_LEN;
+    const uint16_t valueLen = IO_APDU_BUFFER_SIZE - 2 - 3 - REVIEW_ITEM_TITLE_LEN;
+    uint8_t pageCount = 0;
+    if (tx_getItem((int8_t) displayIdx, title, REVIEW_ITEM_TITLE_LEN, value, valueLen, pageIdx, &pageCount) != zxerr_ok) {
+        THROW(APDU_CODE_DATA_INVALID);
+    }
+
+    G_io_apdu_buffer[0] = numItems;
+    G_io_apdu_buffer[1] = (uint8_t) kind;
+    G_io_apdu_buffer[2] = pageCount;
+
+    // pack the value right after the title
+    const uint16_t titleSize = strnlen(title, REVIEW_ITEM_TITLE_LEN - 1) + 1;
+    const uint16_t valueSize = strnlen(value, valueLen - 1) + 1;
+    memmove(title + titleSize, value, valueSize);
+
+    *tx = 3 + titleSize + valueSize;
+    THROW(APDU_CODE_OK);
+}
+
+__Z_INLINE void handleGetParseDiagnostic(__Z_UNUSED volatile uint32_t *flags, volatile uint32_t *tx, __Z_UNUSED uint32_t rx) {
+    // where the transaction last sent for signing fails to parse
+    parse_section_t section = parse_section_header;
+    uint32_t offset = 0;
+    const parser_error_t err = tx_parse_diagnostic(&section, &offset);
+
+    G_io_apdu_buffer[0] = (uint8_t) err;
+    G_io_apdu_buffer[1] = (uint8_t) section;
+    G_io_apdu_buffer[2] = (offset >> 24) & 0xFF;
+    G_io_apdu_buffer[3] = (offset >> 16) & 0xFF;
+    G_io_apdu_buffer[4] = (offset >> 8) & 0xFF;
+    G_io_apdu_buffer[5] = (offset >> 0) & 0xFF;
+
+    *tx = 6;
+    THROW(APDU_CODE_OK);
+}
+
+__Z_INLINE void handleGetStackWatermark(__Z_UNUSED volatile uint32_t *flags, volatile uint32_t *tx, __Z_UNUSED uint32_t rx) {
+    // the most stack used by each parser entry point, big endian
+    for (uint8_t entry = 0; entry < STACK_WATERMARK_ENTRY_POINTS; entry++) {
+        const uint32_t used = tx_stack_watermark(entry);
+        G_io_apdu_buffer[4 * entry] = (used >> 24) & 0xFF;
+        G_io_apdu_buffer[4 * entry + 1] = (used >> 16) & 0xFF;
+        G_io_apdu_buffer[4 * entry + 2] = (used >> 8) & 0xFF;
+        G_io_apdu_buffer[4 * entry + 3] = (used >> 0) & 0xFF;
+    }
+
+    if (G_io_apdu_buffer[OFFSET_P1] == 1) {
+        tx_stack_watermark_reset();
+    }
+
+    *tx = 4 * STACK_WATERMARK_ENTRY_POINTS;
+    THROW(APDU_CODE_OK);
+}
+#endif
+
+void handleApdu(volatile uint32_t *flags, volatile uint32_t *tx, uint32_t rx) {
+    uint16_t sw = 0;
+
+    BEGIN_TRY
+    {
+        TRY
+        {
+            if (G_io_apdu_buffer[OFFSET_CLA] != CLA) {
+                THROW(APDU_CODE_CLA_NOT_SUPPORTED);
+            }
+
+            if (rx < APDU_MIN_LENGTH) {
+                THROW(APDU_CODE_WRONG_LENGTH);
+            }
+
+            switch (G_io_apdu_buffer[OFFSET_INS]) {
+                case INS_GET_VERSION: {
+                    handle_getversion(flags, tx, rx);
+                    break;
+                }
+
+                case INS_GET_STATUS: {
+                    handleGetStatus(flags, tx, rx);
+                    break;
+                }
+
+                case INS_GET_ADDR_SECP256K1: {
+                    if (os_global_pin_is_validated() != BOLOS_UX_OK) {
+                        THROW(APDU_CODE_COMMAND_NOT_ALLOWED);
+                    }
+                    handleGetAddrSecp256K1(flags, tx, rx);
+                    break;
+                }
+
+                case INS_GET_AUTH_PUBKEY: {
+                    if (os_global_pin_is_validated() != BOLOS_UX_OK) {
+                        THROW(APDU_CODE_COMMAND_NOT_ALLOWED);
+                    }
+                    handleGetAuthPubKey(flags, tx, rx);
+                    break;
+                }
+
+                case INS_SIGN_SECP256K1: {
+                    if (os_global_pin_is_validated() != BOLOS_UX_OK) {
+                        THROW(APDU_CODE_COMMAND_NOT_ALLOWED);
+                    }
+                    handleSignSecp256K1(flags, tx, rx);
+                    break;
+                }
+
+                case SIGN_JWT_SECP256K1: {
+                    if (os_global_pin_is_validated() != BOLOS_UX_OK) {
+                        THROW(APDU_CODE_COMMAND_NOT_ALLOWED);
+                    }
+                    handleSignJwtSecp256K1(flags, tx, rx);
+                    break;
+                }
+
+                case INS_SIGN_AUTH_SECP256K1: {
+                    if (os_global_pin_is_validated() != BOLOS_UX_OK) {
+                        THROW(APDU_CODE_COMMAND_NOT_ALLOWED);
+                    }
+                    handleSignAuthSecp256K1(flags, tx, rx);
+                    break;
+                }
+
+                case INS_SIGN_MULTIPATH_SECP256K1: {
+                    if (os_global_pin_is_validated() != BOLOS_UX_OK) {
+                        THROW(APDU_CODE_COMMAND_NOT_ALLOWED);
+                    }
+                    handleSignMultipathSecp256K1(flags, tx, rx);
+                    break;
+                }
+
+                case INS_SIGN_SPONSORED_SECP256K1: {
+                    if (os_global_pin_is_validated() != BOLOS_UX_OK) {
+                        THROW(APDU_CODE_COMMAND_NOT_ALLOWED);
+                    }
+                    handleSignSponsoredSecp256K1(flags, tx, rx);
+                    break;
+                }
+
+                case INS_SIGN_BATCH_SECP256K1: {
+                    if (os_global_pin_is_validated() != BOLOS_UX_OK) {
+                        THROW(APDU_CODE_COMMAND_NOT_ALLOWED);
+                    }
+                    handleSignBatchSecp256K1(flags, tx, rx);
+                    break;
+                }
+
+                case INS_SIGN_MSG_BATCH_SECP256K1: {
+                    if (os_global_pin_is_validated() != BOLOS_UX_OK) {
+                        THROW(APDU_CODE_COMMAND_NOT_ALLOWED);
+                    }
+                    handleSignMsgBatchSecp256K1(flags, tx, rx);
+                    break;
+                }
+
+                case INS_GET_SESSION_SIGNATURE: {
+                    if (os_global_pin_is_validated() != BOLOS_UX_OK) {
+                        THROW(APDU_CODE_COMMAND_NOT_ALLOWED);
+                    }
+                    handleGetSessionSignature(flags, tx, rx);
+                    break;
+                }
+
+                case INS_GET_AUTH_FIELD: {
+                    if (os_global_pin_is_validated() != BOLOS_UX_OK) {
+                        THROW(APDU_CODE_COMMAND_NOT_ALLOWED);
+                    }
+                    handleGetAuthField(flags, tx, rx);
+                    break;
+                }
+
+                case INS_SIGN_FEE_BUMP_SECP256K1: {
+                    if (os_global_pin_is_validated() != BOLOS_UX_OK) {
+                        THROW(APDU_CODE_COMMAND_NOT_ALLOWED);
+                    }
+                    handleSignFeeBumpSecp256K1(flags, tx, rx);
+                    break;
+                }
+
+                case INS_PROVISION_DENYLIST: {
+                    if (os_global_pin_is_validated() != BOLOS_UX_OK) {
+                        THROW(APDU_CODE_COMMAND_NOT_ALLOWED);
+                    }
+                    handleProvisionDenylist(flags, tx, rx);
+                    break;
+                }
+
+                case INS_CLEAR_PROVISIONED: {
+                    if (os_global_pin_is_validated() != BOLOS_UX_OK) {
+                        THROW(APDU_CODE_COMMAND_NOT_ALLOWED);
+                    }
+                    handleClearProvisioned(flags, tx, rx);
+                    break;
+                }
+
+                case INS_PROVISION_ABI: {
+                    if (os_global_pin_is_validated() != BOLOS_UX_OK) {
+                        THROW(APDU_CODE_COMMAND_NOT_ALLOWED);
+                    }
+                    handleProvisionAbi(flags, tx, rx);
+                    break;
+                }
+
+                case INS_PROVISION_TOKEN_INFO: {
+                    if (os_global_pin_is_validated() != BOLOS_UX_OK) {
+                        THROW(APDU_CODE_COMMAND_NOT_ALLOWED);
+                    }
+                    handleProvisionTokenInfo(flags, tx, rx);
+                    break;
+                }
+
+                case INS_GET_BTC_ADDR: {
+                    if (os_global_pin_is_validated() != BOLOS_UX_OK) {
+                        THROW(APDU_CODE_COMMAND_NOT_ALLOWED);
+                    }
+                    handleGetBtcAddress(flags, tx, rx);
+                    break;
+                }
+
+                case INS_SIGN_SCHNORR: {
+                    if (os_global_pin_is_validated() != BOLOS_UX_OK) {
+                        THROW(APDU_CODE_COMMAND_NOT_ALLOWED);
+                    }
+                    handleSignSchnorr(flags, tx, rx);
+                    break;
+                }
+
+                case INS_VERIFY_SIGNATURE: {
+                    if (os_global_pin_is_validated() != BOLOS_UX_OK) {
+                        THROW(APDU_CODE_COMMAND_NOT_ALLOWED);
+                    }
+                    handleVerifySignature(flags, tx, rx);
+                    break;
+                }
+
+                case INS_GET_ACCOUNTS: {
+                    if (os_global_pin_is_validated() != BOLOS_UX_OK) {
+                        THROW(APDU_CODE_COMMAND_NOT_ALLOWED);
+                    }
+                    handleGetAccounts(flags, tx, rx);
+                    break;
+                }
+
+                case INS_GET_MULTISIG_SLOT: {
+                    if (os_global_pin_is_validated() != BOLOS_UX_OK) {
+                        THROW(APDU_CODE_COMMAND_NOT_ALLOWED);
+                    }
+                    handleGetMultisigSlot(flags, tx, rx);
+                    break;
+                }
+
+                case INS_SETTINGS: {
+                    if (os_global_pin_is_validated() != BOLOS_UX_OK) {
+                        THROW(APDU_CODE_COMMAND_NOT_ALLOWED);
+                    }
+                    handleSettings(flags, tx, rx);
+                    break;
+                }
+
+#ifdef APP_TESTING
+                case INS_GET_REVIEW_ITEM: {
+                    handleGetReviewItem(flags, tx, rx);
+                    break;
+                }
+
+                case INS_GET_PARSE_DIAGNOSTIC: {
+                    handleGetParseDiagnostic(flags, tx, rx);
+                    break;
+                }
+
+                case INS_GET_STACK_WATERMARK: {
+                    handleGetStackWatermark(flags, tx, rx);
+                    break;
+                }
+#endif
 
                 default:
                     THROW(APDU_CODE_INS_NOT_SUPPORTED);
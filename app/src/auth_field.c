@@ -0,0 +1,61 @@
+/*******************************************************************************
+*   (c) 2026 Zondax AG
+*
+*  Licensed under the Apache License, Version 2.0 (the "License");
+*  you may not use this file except in compliance with the License.
+*  You may obtain a copy of the License at
+*
+*      http://www.apache.org/licenses/LICENSE-2.0
+*
+*  Unless required by applicable law or agreed to in writing, software
+*  distributed under the License is distributed on an "AS IS" BASIS,
+*  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+*  See the License for the specific language governing permissions and
+*  limitations under the License.
+********************************************************************************/
+
+#include "auth_field.h"
+#include "rslib.h"
+#include "zxmacros.h"
+
+typedef struct {
+    bool valid;
+    uint8_t reply[AUTH_FIELD_REPLY_LEN];
+} auth_field_t;
+
+static auth_field_t last_field;
+
+void auth_field_reset() {
+    MEMZERO(&last_field, sizeof(last_field));
+}
+
+zxerr_t auth_field_store(uint32_t index, const uint8_t *rsv) {
+    auth_field_reset();
+    if (rsv == NULL) {
+        return zxerr_no_data;
+    }
+
+    last_field.reply[0] = (uint8_t) (index >> 24);
+    last_field.reply[1] = (uint8_t) (index >> 16);
+    last_field.reply[2] = (uint8_t) (index >> 8);
+    last_field.reply[3] = (uint8_t) index;
+    if (rs_signature_auth_field(rsv, last_field.reply + 4, sizeof(last_field.reply) - 4) == 0) {
+        auth_field_reset();
+        return zxerr_encoding_failed;
+    }
+    last_field.valid = true;
+    return zxerr_ok;
+}
+
+zxerr_t auth_field_get(uint8_t *buffer, uint16_t bufferLen, uint16_t *replyLen) {
+    *replyLen = 0;
+    if (!last_field.valid) {
+        return zxerr_no_data;
+    }
+    if (bufferLen < sizeof(last_field.reply)) {
+        return zxerr_buffer_too_small;
+    }
+    MEMCPY(buffer, last_field.reply, sizeof(last_field.reply));
+    *replyLen = sizeof(last_field.reply);
+    return zxerr_ok;
+}
@@ -0,0 +1,43 @@
+/*******************************************************************************
+*   (c) 2026 Zondax AG
+*
+*  Licensed under the Apache License, Version 2.0 (the "License");
+*  you may not use this file except in compliance with the License.
+*  You may obtain a copy of the License at
+*
+*      http://www.apache.org/licenses/LICENSE-2.0
+*
+*  Unless required by applicable law or agreed to in writing, software
+*  distributed under the License is distributed on an "AS IS" BASIS,
+*  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+*  See the License for the specific language governing permissions and
+*  limitations under the License.
+********************************************************************************/
+
+#pragma once
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#include <stdint.h>
+#include <stdbool.h>
+#include "zxerror.h"
+
+// 4-byte big endian field index, 1-byte auth field id and the 65-byte
+// signature, the recovery id first
+#define AUTH_FIELD_REPLY_LEN    (4 + 1 + 65)
+
+/// Forgets the auth field of the last signature
+void auth_field_reset();
+
+/// Keeps the auth field of rsv, the signature (r, s, v) the device just
+/// returned, along with the index of the field of the multisig origin it goes in
+zxerr_t auth_field_store(uint32_t index, const uint8_t *rsv);
+
+/// Writes the index and auth field of the last multisig signature
+zxerr_t auth_field_get(uint8_t *buffer, uint16_t bufferLen, uint16_t *replyLen);
+
+#ifdef __cplusplus
+}
+#endif
@@ -0,0 +1,211 @@
+/*******************************************************************************
+*   (c) 2026 Zondax AG
+*
+*  Licensed under the Apache License, Version 2.0 (the "License");
+*  you may not use this file except in compliance with the License.
+*  You may obtain a copy of the License at
+*
+*      http://www.apache.org/licenses/LICENSE-2.0
+*
+*  Unless required by applicable law or agreed to in writing, software
+*  distributed under the License is distributed on an "AS IS" BASIS,
+*  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+*  See the License for the specific language governing permissions and
+*  limitations under the License.
+********************************************************************************/
+
+#include <stdio.h>
+#include "batch.h"
+#include "labels.h"
+#include "crypto.h"
+#include "zxmacros.h"
+#include "zxformat.h"
+#include "rslib.h"
+#include "settings.h"
+
+typedef struct {
+    // hashed as soon as the transaction is parsed, so the
+    // parser buffer can receive the next one
+    uint8_t presig_hash[BATCH_HASH_LEN];
+    char recipient[BATCH_ADDRESS_LEN];
+    uint64_t amount;
+    uint64_t fee;
+    uint64_t nonce;
+} batch_entry_t;
+
+typedef struct {
+    uint32_t path[HDPATH_LEN_DEFAULT];
+    batch_entry_t entries[BATCH_MAX_TRANSACTIONS];
+    uint8_t num_transactions;
+    uint8_t next_transaction;
+    bool approved;
+    // amounts and fees of every transaction
+    uint64_t total;
+} batch_t;
+
+static batch_t batch;
+
+void batch_reset() {
+    rs_zeroize(&batch, sizeof(batch));
+}
+
+uint8_t batch_num_transactions() {
+    return batch.num_transactions;
+}
+
+zxerr_t batch_add(const uint8_t *presig_hash, const char *recipient,
+                  uint64_t amount, uint64_t fee, uint64_t nonce) {
+    if (batch.approved) {
+        return zxerr_unknown;
+    }
+    if (batch.num_transactions >= BATCH_MAX_TRANSACTIONS) {
+        return zxerr_buffer_too_small;
+    }
+    if (hdPath_len != HDPATH_LEN_DEFAULT) {
+        return zxerr_invalid_crypto_settings;
+    }
+
+    if (batch.num_transactions == 0) {
+        MEMCPY(batch.path, hdPath, sizeof(batch.path));
+    } else {
+        // the batch is a series of transactions from one account
+        const batch_entry_t *last = &batch.entries[batch.num_transactions - 1];
+        if (MEMCMP(batch.path, hdPath, sizeof(batch.path)) != 0) {
+            return zxerr_invalid_crypto_settings;
+        }
+        if (last->nonce == UINT64_MAX || nonce != last->nonce + 1) {
+            return zxerr_out_of_bounds;
+        }
+    }
+
+    if (amount > UINT64_MAX - fee || batch.total > UINT64_MAX - amount - fee) {
+        return zxerr_out_of_bounds;
+    }
+
+    batch_entry_t *entry = &batch.entries[batch.num_transactions];
+    MEMCPY(entry->presig_hash, presig_hash, BATCH_HASH_LEN);
+    snprintf(entry->recipient, sizeof(entry->recipient), "%s", recipient);
+    entry->amount = amount;
+    entry->fee = fee;
+    entry->nonce = nonce;
+
+    batch.total += amount + fee;
+    batch.num_transactions++;
+    return zxerr_ok;
+}
+
+bool batch_high_fee() {
+    const uint64_t threshold = app_settings_fee_threshold();
+    for (uint8_t i = 0; i < batch.num_transactions; i++) {
+        if (batch.entries[i].fee > threshold) {
+            return true;
+        }
+    }
+    return false;
+}
+
+zxerr_t batch_approve() {
+    if (batch.num_transactions == 0) {
+        return zxerr_no_data;
+    }
+
+    batch.next_transaction = 0;
+    batch.approved = true;
+    return zxerr_ok;
+}
+
+bool batch_pending() {
+    return batch.approved && batch.next_transaction < batch.num_transactions;
+}
+
+zxerr_t batch_next(uint8_t *buffer, uint16_t bufferLen, uint16_t *replyLen) {
+    *replyLen = 0;
+
+    if (!batch_pending()) {
+        return zxerr_no_data;
+    }
+
+    MEMCPY(hdPath, batch.path, sizeof(batch.path));
+    hdPath_len = HDPATH_LEN_DEFAULT;
+
+    const uint8_t *presig_hash = batch.entries[batch.next_transaction].presig_hash;
+    zxerr_t err = crypto_sign(buffer, bufferLen, presig_hash, BATCH_HASH_LEN, replyLen);
+    if (err == zxerr_ok) {
+        err = crypto_post_sighash(presig_hash, BATCH_HASH_LEN, buffer, bufferLen);
+    }
+    if (err != zxerr_ok) {
+        batch_reset();
+        *replyLen = 0;
+        return err;
+    }
+
+    batch.next_transaction++;
+    if (batch.next_transaction >= batch.num_transactions) {
+        // nothing else to sign
+        batch_reset();
+    }
+
+    return zxerr_ok;
+}
+
+zxerr_t batch_getNumItems(uint8_t *num_items) {
+    // the path, every transaction and the total
+    *num_items = batch.num_transactions == 0 ? 0 : batch.num_transactions + 2;
+    return zxerr_ok;
+}
+
+static zxerr_t print_entry(const batch_entry_t *entry, char *outValue, uint16_t outValueLen,
+                           uint8_t pageIdx, uint8_t *pageCount) {
+    // the digits of a u64 along with the decimal point
+    char amount[22];
+    char fee[22];
+    char nonce[21];
+    char buffer[120];
+
+    const uint8_t decimals = settings_amount_decimals();
+    if (fpuint64_to_str(amount, sizeof(amount), entry->amount, decimals) == 0 ||
+        fpuint64_to_str(fee, sizeof(fee), entry->fee, decimals) == 0 ||
+        fpuint64_to_str(nonce, sizeof(nonce), entry->nonce, 0) == 0) {
+        return zxerr_encoding_failed;
+    }
+
+    snprintf(buffer, sizeof(buffer), app_settings_stx_units() ? LABEL_BATCH_SUMMARY_STX : LABEL_BATCH_SUMMARY,
+             amount, entry->recipient, fee, nonce);
+    pageString(outValue, outValueLen, buffer, pageIdx, pageCount);
+    return zxerr_ok;
+}
+
+zxerr_t batch_getItem(int8_t displayIdx,
+                      char *outKey, uint16_t outKeyLen,
+                      char *outValue, uint16_t outValueLen,
+                      uint8_t pageIdx, uint8_t *pageCount) {
+    if (displayIdx < 0 || batch.num_transactions == 0) {
+        return zxerr_no_data;
+    }
+
+    if (displayIdx == 0) {
+        snprintf(outKey, outKeyLen, LABEL_PATH);
+        char buffer[100];
+        bip32_to_str(buffer, sizeof(buffer), batch.path, HDPATH_LEN_DEFAULT);
+        pageString(outValue, outValueLen, buffer, pageIdx, pageCount);
+        return zxerr_ok;
+    }
+
+    const uint8_t txIdx = displayIdx - 1;
+    if (txIdx < batch.num_transactions) {
+        snprintf(outKey, outKeyLen, LABEL_BATCH_TX, txIdx + 1, batch.num_transactions);
+        return print_entry(&batch.entries[txIdx], outValue, outValueLen, pageIdx, pageCount);
+    }
+
+    if (txIdx == batch.num_transactions) {
+        char total[22];
+        if (fpuint64_to_str(total, sizeof(total), batch.total, settings_amount_decimals()) == 0) {
+            return zxerr_encoding_failed;
+        }
+        snprintf(outKey, outKeyLen, app_settings_stx_units() ? LABEL_BATCH_TOTAL_STX : LABEL_BATCH_TOTAL);
+        pageString(outValue, outValueLen, total, pageIdx, pageCount);
+        return zxerr_ok;
+    }
+
+    return zxerr_no_data;
+}
@@ -0,0 +1,78 @@
+/*******************************************************************************
+*   (c) 2026 Zondax AG
+*
+*  Licensed under the Apache License, Version 2.0 (the "License");
+*  you may not use this file except in compliance with the License.
+*  You may obtain a copy of the License at
+*
+*      http://www.apache.org/licenses/LICENSE-2.0
+*
+*  Unless required by applicable law or agreed to in writing, software
+*  distributed under the License is distributed on an "AS IS" BASIS,
+*  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+*  See the License for the specific language governing permissions and
+*  limitations under the License.
+********************************************************************************/
+
+#pragma once
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#include <stdint.h>
+#include <stdbool.h>
+#include "coin.h"
+#include "zxerror.h"
+
+// Maximum number of transactions that can be signed
+// with a single approval
+#if defined(TARGET_NANOS)
+#define BATCH_MAX_TRANSACTIONS      4
+#else
+#define BATCH_MAX_TRANSACTIONS      8
+#endif
+
+#define BATCH_HASH_LEN              32
+#define BATCH_ADDRESS_LEN           48
+
+// Flags of the init chunk of every batch transaction
+#define BATCH_FLAG_FIRST            0x01
+#define BATCH_FLAG_LAST             0x02
+
+/// Drops the batch being loaded or signed
+void batch_reset();
+
+/// Returns the number of transactions in the batch
+uint8_t batch_num_transactions();
+
+/// Adds a token transfer signed with hdPath to the batch. Every transaction
+/// must be signed with the same path and use the nonce after the previous one
+zxerr_t batch_add(const uint8_t *presig_hash, const char *recipient,
+                  uint64_t amount, uint64_t fee, uint64_t nonce);
+
+/// Returns true if the fee of any transaction in the batch is above the high fee threshold
+bool batch_high_fee();
+
+/// Marks the whole batch as approved by the user
+zxerr_t batch_approve();
+
+/// Returns true if the batch was approved and some signatures were not returned yet
+bool batch_pending();
+
+/// Signs the next transaction of an approved batch, the reply has the same
+/// format as a single transaction signature
+zxerr_t batch_next(uint8_t *buffer, uint16_t bufferLen, uint16_t *replyLen);
+
+/// Return the number of items in the batch review
+zxerr_t batch_getNumItems(uint8_t *num_items);
+
+/// Gets an specific item from the batch review (including paging)
+zxerr_t batch_getItem(int8_t displayIdx,
+                      char *outKey, uint16_t outKeyLen,
+                      char *outValue, uint16_t outValueLen,
+                      uint8_t pageIdx, uint8_t *pageCount);
+
+#ifdef __cplusplus
+}
+#endif
@@ -3,6 +3,8 @@
 #include <zxformat.h>
 #include "os.h"
 #include "cx.h"
+#include <sigutils.h>
+#include "rslib.h"
 
 
 uint16_t fp_uint64_to_str(char *out, uint16_t outLen, const uint64_t value, uint8_t decimals) {
@@ -17,8 +19,89 @@ void _zemu_log_stack(char *buffer) {
     zemu_log_stack(buffer);
 }
 
+void _zemu_log(const char *buffer) {
+    zemu_log(buffer);
+}
+
 // If out length is less than CX_SHA256_SIZE
 // this function will throw an exception
 void hash_sha256(uint8_t *in, uint32_t in_len, uint8_t *out) {
     cx_hash_sha256(in, in_len, out, CX_SHA256_SIZE);
 }
+
+/******* secp256k1, see rust/parser/src/signature/ecdsa.rs ***********************************/
+
+#define SECP256K1_SCALAR_LEN 32
+#define SECP256K1_POINT_LEN 65
+#define SECP256K1_MAX_DER_LEN 73
+
+// Generator and order of secp256k1, to compute the nonce point of a signature
+static const uint8_t SECP256K1_G[SECP256K1_POINT_LEN] = {
+    0x04,
+    0x79, 0xbe, 0x66, 0x7e, 0xf9, 0xdc, 0xbb, 0xac, 0x55, 0xa0, 0x62, 0x95, 0xce, 0x87, 0x0b, 0x07,
+    0x02, 0x9b, 0xfc, 0xdb, 0x2d, 0xce, 0x28, 0xd9, 0x59, 0xf2, 0x81, 0x5b, 0x16, 0xf8, 0x17, 0x98,
+    0x48, 0x3a, 0xda, 0x77, 0x26, 0xa3, 0xc4, 0x65, 0x5d, 0xa4, 0xfb, 0xfc, 0x0e, 0x11, 0x08, 0xa8,
+    0xfd, 0x17, 0xb4, 0x48, 0xa6, 0x85, 0x54, 0x19, 0x9c, 0x47, 0xd0, 0x8f, 0xfb, 0x10, 0xd4, 0xb8,
+};
+static const uint8_t SECP256K1_N[SECP256K1_SCALAR_LEN] = {
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xfe,
+    0xba, 0xae, 0xdc, 0xe6, 0xaf, 0x48, 0xa0, 0x3b, 0xbf, 0xd2, 0x5e, 0x8c, 0xd0, 0x36, 0x41, 0x41,
+};
+
+bool crypto_secp256k1_public_key(const uint8_t *secret, uint8_t *publicKey) {
+    cx_ecfp_private_key_t privateKey;
+    cx_ecfp_public_key_t cx_publicKey;
+
+    const bool ok = cx_ecfp_init_private_key_no_throw(CX_CURVE_256K1, secret, SECP256K1_SCALAR_LEN, &privateKey) == CX_OK &&
+                    cx_ecfp_generate_pair_no_throw(CX_CURVE_256K1, &cx_publicKey, &privateKey, 1) == CX_OK;
+    if (ok) {
+        MEMCPY(publicKey, cx_publicKey.W, SECP256K1_POINT_LEN);
+    }
+    rs_zeroize(&privateKey, sizeof(privateKey));
+    return ok;
+}
+
+bool crypto_secp256k1_sign(const uint8_t *secret, const uint8_t *digest, uint8_t *r, uint8_t *s, uint8_t *v) {
+    cx_ecfp_private_key_t privateKey;
+    uint8_t der[SECP256K1_MAX_DER_LEN];
+    size_t derLen = sizeof(der);
+    uint32_t info = 0;
+
+    const bool ok = cx_ecfp_init_private_key_no_throw(CX_CURVE_256K1, secret, SECP256K1_SCALAR_LEN, &privateKey) == CX_OK &&
+                    cx_ecdsa_sign_no_throw(&privateKey, CX_RND_RFC6979 | CX_LAST, CX_SHA256,
+                                           digest, CX_SHA256_SIZE, der, &derLen, &info) == CX_OK &&
+                    convertDERtoRSV(der, info, r, s, v) == no_error;
+    rs_zeroize(&privateKey, sizeof(privateKey));
+    return ok;
+}
+
+bool crypto_secp256k1_verify(const uint8_t *publicKey, const uint8_t *digest, const uint8_t *der, uint16_t derLen) {
+    cx_ecfp_public_key_t cx_publicKey;
+    return cx_ecfp_init_public_key_no_throw(CX_CURVE_256K1, publicKey, SECP256K1_POINT_LEN, &cx_publicKey) == CX_OK &&
+           cx_ecdsa_verify_no_throw(&cx_publicKey, digest, CX_SHA256_SIZE, der, derLen);
+}
+
+// Computes the nonce point R = (z * G + r * Q) / s of the signature (r, s) of
+// digest by the key Q, R has r as x for a valid signature
+bool crypto_secp256k1_nonce_point(const uint8_t *publicKey, const uint8_t *digest,
+                                  const uint8_t *r, const uint8_t *s, uint8_t *point) {
+    uint8_t z[SECP256K1_SCALAR_LEN];
+    uint8_t sInv[SECP256K1_SCALAR_LEN];
+    uint8_t u1[SECP256K1_SCALAR_LEN];
+    uint8_t u2[SECP256K1_SCALAR_LEN];
+    uint8_t keyPoint[SECP256K1_POINT_LEN];
+
+    MEMCPY(z, digest, sizeof(z));
+    MEMCPY(point, SECP256K1_G, SECP256K1_POINT_LEN);
+    MEMCPY(keyPoint, publicKey, sizeof(keyPoint));
+
+    const bool ok = cx_math_modm_no_throw(z, sizeof(z), SECP256K1_N, sizeof(SECP256K1_N)) == CX_OK &&
+                    cx_math_invprimem_no_throw(sInv, s, SECP256K1_N, sizeof(sInv)) == CX_OK &&
+                    cx_math_multm_no_throw(u1, z, sInv, SECP256K1_N, sizeof(u1)) == CX_OK &&
+                    cx_math_multm_no_throw(u2, r, sInv, SECP256K1_N, sizeof(u2)) == CX_OK &&
+                    cx_ecfp_scalar_mult_no_throw(CX_CURVE_256K1, point, u1, sizeof(u1)) == CX_OK &&
+                    cx_ecfp_scalar_mult_no_throw(CX_CURVE_256K1, keyPoint, u2, sizeof(u2)) == CX_OK &&
+                    cx_ecfp_add_point_no_throw(CX_CURVE_256K1, point, point, keyPoint) == CX_OK;
+    rs_zeroize(z, sizeof(z));
+    return ok;
+}
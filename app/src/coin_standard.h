@@ -25,6 +25,8 @@ extern "C" {
 #define CLA                  0x09
 
 #define HDPATH_LEN_DEFAULT   5
+// deepest path any policy allows, see rust/parser/src/path.rs
+#define HDPATH_LEN_MAX       10
 // support m/888'/0'/<account> path
 #define HDPATH_LEN_AUTH      3
 
@@ -42,15 +44,45 @@ extern "C" {
 #define HDPATH_0_TESTNET     (0x80000000u | 0x2cu)
 #define HDPATH_1_TESTNET     (0x80000000u | 0x1u)
 
+// highest account index, accounts are hardened levels
+#define HDPATH_ACCOUNT_MAX   0x7FFFFFFFu
+
 // compressed key
 #define PK_LEN_SECP256K1            33u
+// uncompressed key
+#define PK_LEN_SECP256K1_UNCOMPRESSED   65u
+// x-only key of BIP-340 signatures
+#define PK_LEN_XONLY                32u
+
+// BIP-340 message and signature, see rust/parser/src/signature/schnorr.rs
+#define SCHNORR_MESSAGE_LEN         32u
+#define SCHNORR_SIGNATURE_LEN       64u
+
+// ECDSA digest and signature as INS_VERIFY_SIGNATURE takes them: r, s and the recovery id
+#define VERIFY_DIGEST_LEN           32u
+#define SIGNATURE_RSV_LEN           65u
+
+// Settings update as INS_SETTINGS takes it: the setting id and its big endian value
+#define SETTINGS_UPDATE_LEN         9u
+
+// Modes of INS_SETTINGS, in P1
+#define SETTINGS_QUERY              0x00
+#define SETTINGS_UPDATE             0x01
 
 typedef enum {
     addr_secp256k1 = 0,
 } address_kind_e;
 
+// What a key is requested for, each has its own allowed paths
+typedef enum {
+    path_kind_account = 0,
+    path_kind_identity = 1,
+    path_kind_bitcoin = 2,
+    path_kind_schnorr = 3,
+} path_kind_e;
+
 #define VIEW_ADDRESS_OFFSET_SECP256K1       PK_LEN_SECP256K1
-#define VIEW_ADDRESS_ITEM_COUNT             2
+#define VIEW_ADDRESS_ITEM_COUNT             3
 #define VIEW_ADDRESS_LAST_PAGE_DEFAULT      255
 
 #define MENU_MAIN_APP_LINE1             "Stacks"
@@ -66,11 +98,70 @@ typedef enum {
 #define COIN_VERSION_MAINNET_SINGLESIG   22
 #define COIN_VERSION_TESTNET_SINGLESIG   26
 
+// Version of the parsing/signing protocol reported by GET_VERSION,
+// it is increased when the accepted payloads or APDUs change, the
+// changes of each version are listed in docs/APDUSPEC.md. FLAGS only
+// tell the optional capabilities of a version
+#define PROTOCOL_VERSION                2
+
+// Capabilities reported by GET_VERSION
+// the code body of a smart contract deploy is not bound by the transaction
+// buffer size, any other payload still has to fit it
+#define VERSION_FLAG_WINDOWED_CONTRACT_DEPLOY   0x01
+// the init chunk of a transaction can declare its length to show a progress screen
+#define VERSION_FLAG_DECLARED_LENGTH            0x02
+// chunks can carry a sequence number so that a chunk sent again is not appended twice
+#define VERSION_FLAG_SEQUENCED_CHUNKS           0x04
+
+// Init chunk P2 bit telling the data ends with the big endian length of the transaction
+#define P2_DECLARED_LENGTH              0x01
+#define DECLARED_LENGTH_LEN             4
+// Init chunk P2 bit telling every other chunk carries its sequence number in P2
+#define P2_SEQUENCED                    0x02
+
+// Signing session states returned by INS_GET_STATUS
+#define STATUS_IDLE                     0x00
+#define STATUS_RECEIVING                0x01
+#define STATUS_RECEIVED                 0x02
+
+// GET_ADDR P2 bit asking for the uncompressed key too, the rest is the network version
+#define ADDR_FLAG_UNCOMPRESSED          0x80
+
 #define INS_GET_VERSION                 0x00
 #define INS_GET_ADDR_SECP256K1          0x01
 #define INS_SIGN_SECP256K1              0x02
 #define INS_GET_AUTH_PUBKEY             0x03
 #define SIGN_JWT_SECP256K1              0x04
+// 0x05 is reserved for structured messages on the host side
+#define INS_SIGN_MULTIPATH_SECP256K1    0x06
+#define INS_GET_SESSION_SIGNATURE       0x07
+#define INS_SIGN_FEE_BUMP_SECP256K1     0x08
+#define INS_PROVISION_DENYLIST          0x09
+#define INS_GET_ACCOUNTS                0x0A
+#define INS_GET_BTC_ADDR                0x0B
+#define INS_SIGN_SCHNORR                0x0C
+#define INS_SIGN_SPONSORED_SECP256K1    0x0D
+#define INS_SIGN_BATCH_SECP256K1        0x0E
+#define INS_PROVISION_ABI               0x0F
+#define INS_GET_STATUS                  0x10
+#define INS_CLEAR_PROVISIONED           0x11
+#define INS_SIGN_MSG_BATCH_SECP256K1    0x12
+#define INS_VERIFY_SIGNATURE            0x13
+#define INS_PROVISION_TOKEN_INFO        0x14
+#define INS_SIGN_AUTH_SECP256K1         0x15
+#define INS_GET_AUTH_FIELD              0x16
+#define INS_GET_MULTISIG_SLOT           0x17
+#define INS_SETTINGS                    0x18
+// testing builds only
+#define INS_GET_REVIEW_ITEM             0x7D
+#define INS_GET_PARSE_DIAGNOSTIC        0x7E
+#define INS_GET_STACK_WATERMARK         0x7F
+
+// Parser entry points measured by INS_GET_STACK_WATERMARK
+#define STACK_WATERMARK_ENTRY_POINTS    3
+
+// Title buffer returned by INS_GET_REVIEW_ITEM
+#define REVIEW_ITEM_TITLE_LEN           40
 
 #ifdef __cplusplus
 }
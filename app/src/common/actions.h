@@ -25,95 +25,105 @@
 #include "coin.h"
 #include "zxformat.h"
 #include "sha512.h"
-
-// The initial tx hash is done in 3 blocks
-// this is the length in bytes of the first block
-// which includes:
-// 1-byte transaction version
-// 4-byte chainID
-// 1-byte authorization type
-// 21-byte origin hash-mode and public-key hash
-#define TRANSACTION_FIRST_BLOCK_LEN 27
-
-// The length in bytes of the auth fields
-// after zeroizing them for the initial transaction hash
-// which is required as part of the signing algorithm
-#define INITIAL_SIGHASH_AUTH_LEN 185
-
-// The presign data that includes:
-// 32-byte full transaction hash
+#include "sign_session.h"
+#include "fee_bump.h"
+#include "auth_field.h"
+#include "batch.h"
+#include "msg_batch.h"
+#include "schnorr.h"
+#include "settings_review.h"
+#include "sighash_stream.h"
+#include "rslib.h"
+
+// The auth flag, fee and nonce appended to the transaction hash:
 // 1-byte auth flag
 // 8-byte big-endian fee
 // 8-byte big-endian nonce
-#define PRESIG_DATA_LEN CX_SHA256_SIZE + 1 + 8 + 8
-
-// The data required to calculate the post_sighash hash
-// 32-byte presig_hash calculated above
-// 1-byte publicKey encoding. It seems to be 0x00(compressed)
-// according to the stacks's rust implementation
-#define POST_SIGNHASH_DATA_LEN CX_SHA256_SIZE + 1
-
-// The previous signer signature data and post_sig_hash
-// that should be treated as the pre_sig_hash for this signer
-// this includes:
-// 32-byte previous signer post_sig_hash
-// 1-byte pubkey type(compressed/uncompressed)
-// 65-byte previous signer signature(vrs)
-#define PREVIOUS_SIGNER_DATA_LEN CX_SHA256_SIZE + 1 + 65
+#define AUTH_FEE_NONCE_LEN SIGN_SESSION_AUTH_DATA_LEN
+
+// The presign data that includes:
+// 32-byte full transaction hash
+// and the auth flag, fee and nonce
+#define PRESIG_DATA_LEN CX_SHA256_SIZE + AUTH_FEE_NONCE_LEN
 
 extern uint8_t action_addr_len;
 
 // helper function to get the presig_hash of the transaction being signed
 __Z_INLINE zxerr_t get_presig_hash(uint8_t* hash, uint16_t hashLen);
 
+// helper function to get the transaction hash with its auth fields cleared, it does not
+// depend on the fee and nonce
+__Z_INLINE zxerr_t get_initial_sighash(uint8_t* hash, uint16_t hashLen);
+
+// Helper function that writes the transaction auth_type, fee and nonce
+__Z_INLINE zxerr_t get_auth_fee_nonce(uint8_t *buf, uint16_t bufLen);
+
 // Helper function that appends the transaction auth_type, fee and  nonce getting the hash of the result
 __Z_INLINE zxerr_t append_fee_nonce_auth_hash(uint8_t* input_hash, uint16_t input_hashLen, uint8_t* hash, uint16_t hashLen);
 
-// Helper function to verify the previous signer post_sig_hash in a multisig transaction
-__Z_INLINE zxerr_t validate_post_sig_hash(uint8_t *current_pre_sig_hash, uint16_t hash_len, uint8_t *signer_data, uint16_t signer_data_len);
+// Helper function that appends the auth_type, fee and nonce of a signer role getting the hash of the result
+__Z_INLINE zxerr_t append_auth_data_hash(const uint8_t* input_hash, const uint8_t *auth_data, uint8_t* hash, uint16_t hashLen);
+
 
 __Z_INLINE void app_sign() {
     uint8_t presig_hash[CX_SHA256_SIZE];
-    uint8_t post_sighash_data[POST_SIGNHASH_DATA_LEN];
     zxerr_t err = zxerr_ok;
 
     const uint8_t transaction_type = tx_get_transaction_type();
 
+    // The buffer must be the one that was reviewed
+    err = tx_check_digest();
+
+    // The signer was checked when the transaction was parsed, it is checked
+    // again against the path the signature is about to be produced with
+    if (err == zxerr_ok) {
+        err = tx_check_signer();
+    }
+
     // Get the current transaction presig_hash
-    err = get_presig_hash(presig_hash, CX_SHA256_SIZE);
+    if (err == zxerr_ok) {
+        err = get_presig_hash(presig_hash, CX_SHA256_SIZE);
+    }
 
-    // Check if this is a multisig transaction, If so, checks that there is a previous
-    // signer data, in that case we know we have to use that signers's data as our
-    // pre_sig_hash. Otherwise, we are the first signer in a multisig transaction
+    // In a multisig transaction the pre_sig_hash follows the signatures of the
+    // previous signers, those in the auth fields as stacks.js serializes them
+    // or the previous signer data appended by the host. Without any, this
+    // device is the first signer
     if (tx_is_multisig() && err == zxerr_ok) {
-        // Get the previous signer data to do the verification and to sign it
-        uint8_t *data = NULL;
-        uint8_t **previous_signer_data = &data;
-        uint16_t len = tx_previous_signer_data(previous_signer_data);
-
-        if (data != NULL && len >= PREVIOUS_SIGNER_DATA_LEN) {
-            // Check postsig_hash and append the authfield, fee and nonce, after that the result is copied to the presig_hash
-            // buffer
-            err = validate_post_sig_hash(presig_hash, CX_SHA256_SIZE, data, len);
-            if(err == zxerr_ok) {
-                err = append_fee_nonce_auth_hash(data, CX_SHA256_SIZE, presig_hash, CX_SHA256_SIZE);
-            }
-        }
+        err = tx_multisig_presig_hash(presig_hash, CX_SHA256_SIZE);
     }
 
     if (err != zxerr_ok) {
+        rs_zeroize(presig_hash, sizeof(presig_hash));
         uint8_t errLen = getErrorMessage((char *) G_io_apdu_buffer, IO_APDU_BUFFER_SIZE - 2, err);
         set_code(G_io_apdu_buffer, errLen, APDU_CODE_SIGN_VERIFY_ERROR);
         io_exchange(CHANNEL_APDU | IO_RETURN_AFTER_TX, errLen + 2);
         return;
     }
 
+    // Keep track of the transaction so that a later fee bump
+    // can be confirmed without a full review
+    uint8_t initial_sighash[CX_SHA256_SIZE];
+    uint8_t fee[8];
+    uint8_t nonce[8];
+    const bool track_fee_bump = transaction_type == Transaction &&
+                                get_initial_sighash(initial_sighash, CX_SHA256_SIZE) == zxerr_ok &&
+                                tx_fee(fee, sizeof(fee)) == sizeof(fee) &&
+                                tx_nonce(nonce, sizeof(nonce)) == sizeof(nonce);
+
+    // The auth field the signature of a multisig transaction goes in is kept
+    // along with it, so coordinators can fetch it with INS_GET_AUTH_FIELD
+    uint32_t auth_field_index = 0;
+    const bool track_auth_field = transaction_type == Transaction && tx_is_multisig() &&
+                                  tx_multisig_auth_field_index(&auth_field_index) == zxerr_ok;
+
     // Take "ownership" of the memory used by the transaction parser
     tx_reset_state();
 
     uint16_t replyLen;
     err = crypto_sign(G_io_apdu_buffer, IO_APDU_BUFFER_SIZE - 3, presig_hash, CX_SHA256_SIZE, &replyLen);
     if (err != zxerr_ok) {
+        rs_zeroize(presig_hash, sizeof(presig_hash));
         uint8_t errLen = getErrorMessage((char *) G_io_apdu_buffer, IO_APDU_BUFFER_SIZE - 2, err);
         set_code(G_io_apdu_buffer, errLen, APDU_CODE_SIGN_VERIFY_ERROR);
         io_exchange(CHANNEL_APDU | IO_RETURN_AFTER_TX, errLen + 2);
@@ -122,41 +132,28 @@ __Z_INLINE void app_sign() {
 
     switch (transaction_type) {
         case Transaction: {
-            // Calculates the post_sighash
-            memcpy(post_sighash_data, presig_hash, CX_SHA256_SIZE);
-
-            // set the signing public key's encoding byte, it is compressed(it is our device pubkey)
-            post_sighash_data[CX_SHA256_SIZE] = 0x00;
-
-            // Now gets the post_sighash from the data and write it down to the first 32-byte of the  G_io_apdu_buffer
-            uint8_t hash_temp[SHA512_DIGEST_LENGTH];
-
-            // Now get the presig_hash
-            sha512_256_ctx ctx;
-            SHA512_256_init(&ctx);
-            SHA512_256_starts(&ctx);
-
-            // sighash + pubkey encoding
-            SHA512_256_update(&ctx, post_sighash_data, POST_SIGNHASH_DATA_LEN);
-            // the signature's v value
-            SHA512_256_update(&ctx, &G_io_apdu_buffer[96], 1);
-
-            // the signature's rs values
-            SHA512_256_update(&ctx, &G_io_apdu_buffer[32], 64);
-            SHA512_256_finish(&ctx, hash_temp);
-            memcpy(G_io_apdu_buffer, hash_temp, CX_SHA256_SIZE);
+            // Calculates the post_sighash and write it down to the first 32-byte of the G_io_apdu_buffer
+            err = crypto_post_sighash(presig_hash, CX_SHA256_SIZE, G_io_apdu_buffer, IO_APDU_BUFFER_SIZE - 3);
+            if (err != zxerr_ok) {
+                rs_zeroize(presig_hash, sizeof(presig_hash));
+                uint8_t errLen = getErrorMessage((char *) G_io_apdu_buffer, IO_APDU_BUFFER_SIZE - 2, err);
+                set_code(G_io_apdu_buffer, errLen, APDU_CODE_SIGN_VERIFY_ERROR);
+                io_exchange(CHANNEL_APDU | IO_RETURN_AFTER_TX, errLen + 2);
+                return;
+            }
             break;
-
         }
         case Message:
         case Jwt:
-        case StructuredMsg: {
+        case StructuredMsg:
+        case AuthChallenge: {
             // just return as a post_sighash the presig_hash
             // which is the hash that is signed allowing for  easy signature verification
             memcpy(G_io_apdu_buffer, presig_hash, CX_SHA256_SIZE);
             break;
                             }
         default: {
+            rs_zeroize(presig_hash, sizeof(presig_hash));
             err = zxerr_no_data;
             uint8_t errLen = getErrorMessage((char *) G_io_apdu_buffer, IO_APDU_BUFFER_SIZE - 2, err);
             set_code(G_io_apdu_buffer, errLen, APDU_CODE_SIGN_VERIFY_ERROR);
@@ -164,6 +161,7 @@ __Z_INLINE void app_sign() {
             return;
         }
     }
+    rs_zeroize(presig_hash, sizeof(presig_hash));
 
     if (replyLen == 0) {
         uint8_t errLen = getErrorMessage((char *) G_io_apdu_buffer, IO_APDU_BUFFER_SIZE - 2, zxerr_no_data);
@@ -172,24 +170,298 @@ __Z_INLINE void app_sign() {
         return;
     }
 
+    if (track_fee_bump) {
+        fee_bump_store(initial_sighash, fee, nonce);
+    } else {
+        fee_bump_reset();
+    }
+
+    // the signature (r, s, v) follows the post_sig_hash
+    if (track_auth_field) {
+        auth_field_store(auth_field_index, G_io_apdu_buffer + CX_SHA256_SIZE);
+    } else {
+        auth_field_reset();
+    }
+
+    set_code(G_io_apdu_buffer, replyLen, APDU_CODE_OK);
+    io_exchange(CHANNEL_APDU | IO_RETURN_AFTER_TX, replyLen + 2);
+}
+
+// Checks that the transaction being signed only differs from
+// the last signed one in its fee and nonce
+__Z_INLINE zxerr_t app_check_fee_bump() {
+    uint8_t initial_sighash[CX_SHA256_SIZE];
+    uint8_t fee[8];
+    uint8_t nonce[8];
+
+    zxerr_t err = get_initial_sighash(initial_sighash, CX_SHA256_SIZE);
+    if (err != zxerr_ok) {
+        return err;
+    }
+
+    if (tx_fee(fee, sizeof(fee)) != sizeof(fee) || tx_nonce(nonce, sizeof(nonce)) != sizeof(nonce)) {
+        return zxerr_no_data;
+    }
+
+    return fee_bump_check(initial_sighash, fee, nonce);
+}
+
+__Z_INLINE void app_sign_multipath() {
+    uint8_t presig_hash[CX_SHA256_SIZE];
+    uint8_t auth_data[AUTH_FEE_NONCE_LEN];
+
+    // only the auth field of a single signature is kept
+    auth_field_reset();
+
+    // The buffer must be the one that was reviewed
+    zxerr_t err = tx_check_digest();
+
+    // Only the first path can follow a previous signer, the rest
+    // are chained by the session using their own post_sig_hash
+    if (err == zxerr_ok) {
+        err = get_presig_hash(presig_hash, CX_SHA256_SIZE);
+    }
+
+    if (err == zxerr_ok) {
+        err = tx_multisig_presig_hash(presig_hash, CX_SHA256_SIZE);
+    }
+
+    if (err == zxerr_ok) {
+        err = get_auth_fee_nonce(auth_data, AUTH_FEE_NONCE_LEN);
+    }
+    const bool order_independent = tx_is_order_independent() == 1;
+
+    // Take "ownership" of the memory used by the transaction parser
+    tx_reset_state();
+
+    if (err == zxerr_ok) {
+        err = sign_session_approve(presig_hash, CX_SHA256_SIZE, auth_data, AUTH_FEE_NONCE_LEN,
+                                   order_independent);
+    }
+    // the session keeps its own copy
+    rs_zeroize(presig_hash, sizeof(presig_hash));
+    rs_zeroize(auth_data, sizeof(auth_data));
+
+    uint16_t replyLen = 0;
+    if (err == zxerr_ok) {
+        err = sign_session_next(G_io_apdu_buffer, IO_APDU_BUFFER_SIZE - 3, &replyLen);
+    }
+
+    if (err != zxerr_ok || replyLen == 0) {
+        sign_session_reset();
+        uint8_t errLen = getErrorMessage((char *) G_io_apdu_buffer, IO_APDU_BUFFER_SIZE - 2, err != zxerr_ok ? err : zxerr_no_data);
+        set_code(G_io_apdu_buffer, errLen, APDU_CODE_SIGN_VERIFY_ERROR);
+        io_exchange(CHANNEL_APDU | IO_RETURN_AFTER_TX, errLen + 2);
+        return;
+    }
+
+    set_code(G_io_apdu_buffer, replyLen, APDU_CODE_OK);
+    io_exchange(CHANNEL_APDU | IO_RETURN_AFTER_TX, replyLen + 2);
+}
+
+__Z_INLINE void app_sign_sponsored() {
+    uint8_t initial_sighash[CX_SHA256_SIZE];
+    uint8_t presig_hash[CX_SHA256_SIZE];
+    uint8_t origin_auth[AUTH_FEE_NONCE_LEN];
+    uint8_t sponsor_auth[AUTH_FEE_NONCE_LEN];
+
+    // only the auth field of a single signature is kept
+    auth_field_reset();
+
+    // The buffer must be the one that was reviewed
+    zxerr_t err = tx_check_digest();
+
+    // hdPath holds the origin path, the sponsor one was checked
+    // against the transaction when it was parsed
+    if (err == zxerr_ok) {
+        err = tx_check_signer();
+    }
+
+    // The origin signs the initial sighash with its own auth data, the
+    // sponsor then signs over the origin post_sig_hash with the sponsor one
+    if (err == zxerr_ok) {
+        err = get_initial_sighash(initial_sighash, CX_SHA256_SIZE);
+    }
+    if (err == zxerr_ok) {
+        err = tx_role_auth_data(signer_role_origin, origin_auth, AUTH_FEE_NONCE_LEN);
+    }
+    if (err == zxerr_ok) {
+        err = append_auth_data_hash(initial_sighash, origin_auth, presig_hash, CX_SHA256_SIZE);
+    }
+    if (err == zxerr_ok) {
+        err = tx_role_auth_data(signer_role_sponsor, sponsor_auth, AUTH_FEE_NONCE_LEN);
+    }
+    rs_zeroize(initial_sighash, sizeof(initial_sighash));
+
+    // Take "ownership" of the memory used by the transaction parser
+    tx_reset_state();
+
+    if (err == zxerr_ok) {
+        err = sign_session_approve(presig_hash, CX_SHA256_SIZE, sponsor_auth, AUTH_FEE_NONCE_LEN, false);
+    }
+    // the session keeps its own copy
+    rs_zeroize(presig_hash, sizeof(presig_hash));
+    rs_zeroize(sponsor_auth, sizeof(sponsor_auth));
+
+    // the origin signature, the sponsor one is returned by INS_GET_SESSION_SIGNATURE
+    uint16_t replyLen = 0;
+    if (err == zxerr_ok) {
+        err = sign_session_next(G_io_apdu_buffer, IO_APDU_BUFFER_SIZE - 3, &replyLen);
+    }
+
+    if (err != zxerr_ok || replyLen == 0) {
+        sign_session_reset();
+        uint8_t errLen = getErrorMessage((char *) G_io_apdu_buffer, IO_APDU_BUFFER_SIZE - 2, err != zxerr_ok ? err : zxerr_no_data);
+        set_code(G_io_apdu_buffer, errLen, APDU_CODE_SIGN_VERIFY_ERROR);
+        io_exchange(CHANNEL_APDU | IO_RETURN_AFTER_TX, errLen + 2);
+        return;
+    }
+
+    set_code(G_io_apdu_buffer, replyLen, APDU_CODE_OK);
+    io_exchange(CHANNEL_APDU | IO_RETURN_AFTER_TX, replyLen + 2);
+}
+
+// Adds the parsed transaction to the batch being loaded, its presig_hash is
+// computed now as the parser buffer receives the next transaction
+__Z_INLINE zxerr_t app_batch_load() {
+    uint8_t presig_hash[CX_SHA256_SIZE];
+    char recipient[BATCH_ADDRESS_LEN];
+    uint8_t fee[8];
+    uint8_t nonce[8];
+    uint64_t amount = 0;
+
+    zxerr_t err = tx_amount(&amount);
+    if (err == zxerr_ok) {
+        err = tx_recipient_address(recipient, sizeof(recipient));
+    }
+    if (err == zxerr_ok && (tx_fee(fee, sizeof(fee)) != sizeof(fee) ||
+                            tx_nonce(nonce, sizeof(nonce)) != sizeof(nonce))) {
+        err = zxerr_no_data;
+    }
+    if (err == zxerr_ok) {
+        err = get_presig_hash(presig_hash, CX_SHA256_SIZE);
+    }
+    if (err == zxerr_ok) {
+        uint64_t fee_value = 0;
+        uint64_t nonce_value = 0;
+        for (uint8_t i = 0; i < sizeof(fee); i++) {
+            fee_value = (fee_value << 8) | fee[i];
+            nonce_value = (nonce_value << 8) | nonce[i];
+        }
+        err = batch_add(presig_hash, recipient, amount, fee_value, nonce_value);
+    }
+
+    rs_zeroize(presig_hash, sizeof(presig_hash));
+    tx_reset_state();
+    return err;
+}
+
+__Z_INLINE void app_sign_batch() {
+    // only the auth field of a single signature is kept
+    auth_field_reset();
+
+    // the first signature, the others are returned by INS_GET_SESSION_SIGNATURE
+    uint16_t replyLen = 0;
+    zxerr_t err = batch_approve();
+    if (err == zxerr_ok) {
+        err = batch_next(G_io_apdu_buffer, IO_APDU_BUFFER_SIZE - 3, &replyLen);
+    }
+
+    if (err != zxerr_ok || replyLen == 0) {
+        batch_reset();
+        uint8_t errLen = getErrorMessage((char *) G_io_apdu_buffer, IO_APDU_BUFFER_SIZE - 2, err != zxerr_ok ? err : zxerr_no_data);
+        set_code(G_io_apdu_buffer, errLen, APDU_CODE_SIGN_VERIFY_ERROR);
+        io_exchange(CHANNEL_APDU | IO_RETURN_AFTER_TX, errLen + 2);
+        return;
+    }
+
+    set_code(G_io_apdu_buffer, replyLen, APDU_CODE_OK);
+    io_exchange(CHANNEL_APDU | IO_RETURN_AFTER_TX, replyLen + 2);
+}
+
+__Z_INLINE zxerr_t app_msg_batch_load() {
+    uint8_t hash[CX_SHA256_SIZE];
+
+    const uint16_t header_len = tx_structured_msg_header_len();
+    zxerr_t err = header_len == 0 ? zxerr_no_data : tx_structured_msg_hash(hash, CX_SHA256_SIZE);
+    if (err == zxerr_ok) {
+        err = msg_batch_add(tx_get_buffer() + CRYPTO_BLOB_SKIP_BYTES, header_len, hash);
+    }
+
+    // the parser state is kept, the review shows the domain of the last message
+    rs_zeroize(hash, sizeof(hash));
+    return err;
+}
+
+__Z_INLINE void app_sign_msg_batch() {
+    // the first signature, the others are returned by INS_GET_SESSION_SIGNATURE
+    uint16_t replyLen = 0;
+    zxerr_t err = msg_batch_approve();
+    if (err == zxerr_ok) {
+        err = msg_batch_next(G_io_apdu_buffer, IO_APDU_BUFFER_SIZE - 3, &replyLen);
+    }
+    tx_reset_state();
+
+    if (err != zxerr_ok || replyLen == 0) {
+        msg_batch_reset();
+        uint8_t errLen = getErrorMessage((char *) G_io_apdu_buffer, IO_APDU_BUFFER_SIZE - 2, err != zxerr_ok ? err : zxerr_no_data);
+        set_code(G_io_apdu_buffer, errLen, APDU_CODE_SIGN_VERIFY_ERROR);
+        io_exchange(CHANNEL_APDU | IO_RETURN_AFTER_TX, errLen + 2);
+        return;
+    }
+
+    set_code(G_io_apdu_buffer, replyLen, APDU_CODE_OK);
+    io_exchange(CHANNEL_APDU | IO_RETURN_AFTER_TX, replyLen + 2);
+}
+
+__Z_INLINE void app_sign_schnorr() {
+    uint16_t replyLen = 0;
+    const zxerr_t err = schnorr_sign(G_io_apdu_buffer, IO_APDU_BUFFER_SIZE - 3, &replyLen);
+
+    if (err != zxerr_ok || replyLen == 0) {
+        uint8_t errLen = getErrorMessage((char *) G_io_apdu_buffer, IO_APDU_BUFFER_SIZE - 2, err != zxerr_ok ? err : zxerr_no_data);
+        set_code(G_io_apdu_buffer, errLen, APDU_CODE_SIGN_VERIFY_ERROR);
+        io_exchange(CHANNEL_APDU | IO_RETURN_AFTER_TX, errLen + 2);
+        return;
+    }
+
     set_code(G_io_apdu_buffer, replyLen, APDU_CODE_OK);
     io_exchange(CHANNEL_APDU | IO_RETURN_AFTER_TX, replyLen + 2);
 }
 
+__Z_INLINE void app_update_setting() {
+    const zxerr_t err = settings_review_apply();
+
+    if (err != zxerr_ok) {
+        uint8_t errLen = getErrorMessage((char *) G_io_apdu_buffer, IO_APDU_BUFFER_SIZE - 2, err);
+        set_code(G_io_apdu_buffer, errLen, APDU_CODE_EXECUTION_ERROR);
+        io_exchange(CHANNEL_APDU | IO_RETURN_AFTER_TX, errLen + 2);
+        return;
+    }
+
+    set_code(G_io_apdu_buffer, 0, APDU_CODE_OK);
+    io_exchange(CHANNEL_APDU | IO_RETURN_AFTER_TX, 2);
+}
+
 __Z_INLINE void app_reject() {
     tx_reset_state();
+    sign_session_reset();
+    batch_reset();
+    msg_batch_reset();
+    schnorr_reset();
+    settings_review_reset();
 
     set_code(G_io_apdu_buffer, 0, APDU_CODE_COMMAND_NOT_ALLOWED);
     io_exchange(CHANNEL_APDU | IO_RETURN_AFTER_TX, 2);
 }
 
-__Z_INLINE uint8_t app_fill_address(address_kind_e kind) {
+__Z_INLINE uint8_t app_fill_address(address_kind_e kind, bool uncompressed) {
     // Put data directly in the apdu buffer
     MEMZERO(G_io_apdu_buffer, IO_APDU_BUFFER_SIZE);
 
     switch (kind) {
         case addr_secp256k1:
-            action_addr_len = crypto_fillAddress_secp256k1(G_io_apdu_buffer, IO_APDU_BUFFER_SIZE - 2);
+            action_addr_len = crypto_fillAddress_secp256k1(G_io_apdu_buffer, IO_APDU_BUFFER_SIZE - 2, uncompressed);
             break;
         default:
             action_addr_len = 0;
@@ -216,6 +488,13 @@ __Z_INLINE uint8_t app_fill_auth_pubkey(address_kind_e kind) {
 }
 
 
+__Z_INLINE uint8_t app_fill_btc_address() {
+    // Put data directly in the apdu buffer
+    MEMZERO(G_io_apdu_buffer, IO_APDU_BUFFER_SIZE);
+    action_addr_len = crypto_fillBtcAddress(G_io_apdu_buffer, IO_APDU_BUFFER_SIZE - 2);
+    return action_addr_len;
+}
+
 __Z_INLINE void app_reply_address() {
     set_code(G_io_apdu_buffer, action_addr_len, APDU_CODE_OK);
     io_exchange(CHANNEL_APDU | IO_RETURN_AFTER_TX, action_addr_len + 2);
@@ -226,76 +505,62 @@ __Z_INLINE void app_reply_error() {
     io_exchange(CHANNEL_APDU | IO_RETURN_AFTER_TX, 2);
 }
 
-__Z_INLINE zxerr_t validate_post_sig_hash(uint8_t *current_pre_sig_hash, uint16_t hash_len, uint8_t *signer_data, uint16_t signer_data_len) {
+__Z_INLINE zxerr_t get_initial_sighash(uint8_t* hash, uint16_t hashLen) {
+    if (hashLen < CX_SHA256_SIZE || tx_get_transaction_type() != Transaction)
+        return zxerr_no_data;
+
+    const uint8_t *data = tx_get_buffer() + CRYPTO_BLOB_SKIP_BYTES;
+
+    const uint8_t *last_block = NULL;
+    const uint8_t **last_block_ptr = &last_block;
 
-    if (signer_data_len < PREVIOUS_SIGNER_DATA_LEN) {
+    uint16_t last_block_len = tx_last_tx_block(last_block_ptr);
+    if (last_block == NULL || last_block_len == 0) {
         return zxerr_no_data;
     }
-    // get the previous signer post_sig_hash and validate it
-    uint8_t reconstructed_post_sig_hash[CX_SHA512_SIZE];
 
-    sha512_256_ctx ctx;
-    SHA512_256_init(&ctx);
-    SHA512_256_starts(&ctx);
-    SHA512_256_update(&ctx, current_pre_sig_hash, hash_len);
-    // include the previous signer pubkey type(compressed/uncompressed) and its signature(vrs)
-    // those bytes are the last 66-bytes in the previous signer data
-    SHA512_256_update(&ctx, signer_data + CX_SHA256_SIZE, PREVIOUS_SIGNER_DATA_LEN - CX_SHA256_SIZE);
-    SHA512_256_finish(&ctx, reconstructed_post_sig_hash);
-
-    // now compare
-    for (unsigned int i = 0; i < CX_SHA256_SIZE; ++i) {
-        if ( reconstructed_post_sig_hash[i] != signer_data[i]) {
-            zemu_log_stack("Invalid post_sig_hash\n");
-            return zxerr_no_data;
-        }
+    // Most of the transaction was already hashed while it was received
+    if (sighash_stream_finish(data, tx_get_buffer_length() - CRYPTO_BLOB_SKIP_BYTES,
+                              last_block, last_block_len, hash, hashLen) == zxerr_ok) {
+        return zxerr_ok;
     }
-    return zxerr_ok;
+
+    // Part of the code body was dropped, the buffer can not be hashed again
+    if (sighash_stream_skipped() > 0) {
+        return zxerr_no_data;
+    }
+
+    // Otherwise hash it from the parsed transaction, with the auth
+    // field cleared and the sponsor set to the signing sentinel
+    return tx_initial_sighash(hash, hashLen);
 }
 
 __Z_INLINE zxerr_t get_presig_hash(uint8_t* hash, uint16_t hashLen) {
     zemu_log_stack("computing presig_hash");
 
-    uint8_t tx_auth[INITIAL_SIGHASH_AUTH_LEN];
-    MEMZERO(tx_auth, INITIAL_SIGHASH_AUTH_LEN);
-    uint8_t hash_temp[SHA512_DIGEST_LENGTH];
-
     transaction_type_t tx_typ = tx_get_transaction_type();
 
-
-    // Init the hasher
-    sha512_256_ctx ctx;
-    SHA512_256_init(&ctx);
-    SHA512_256_starts(&ctx);
-
     const uint8_t *data = tx_get_buffer() + CRYPTO_BLOB_SKIP_BYTES;
     const uint16_t data_len = tx_get_buffer_length() - CRYPTO_BLOB_SKIP_BYTES;
 
     switch (tx_typ) {
         case Transaction: {
-        // Before hashing the transaction the auth field should be cleared
-        // and the sponsor set to signing sentinel.
-        uint16_t auth_len = 0;
-        auth_len = tx_presig_hash_data(tx_auth, INITIAL_SIGHASH_AUTH_LEN);
-        // prepare the last transaction block to be hashed
-        SHA512_256_update(&ctx, data, TRANSACTION_FIRST_BLOCK_LEN);
-        SHA512_256_update(&ctx, tx_auth, auth_len);
-        uint8_t *last_block = NULL;
-        uint8_t **last_block_ptr = &last_block;
-
-        uint16_t last_block_len = tx_last_tx_block(last_block_ptr);
-        if (last_block == NULL || last_block_len == 0) {
-            return zxerr_no_data;
+        // The initial sighash does not depend on the fee and nonce,
+        // those are appended afterwards along with the auth flag
+        uint8_t initial_sighash[CX_SHA256_SIZE];
+        zxerr_t err = get_initial_sighash(initial_sighash, CX_SHA256_SIZE);
+        if (err == zxerr_ok) {
+            err = append_fee_nonce_auth_hash(initial_sighash, CX_SHA256_SIZE, hash, hashLen);
         }
-
-        SHA512_256_update(&ctx, last_block, last_block_len);
-        SHA512_256_finish(&ctx, hash_temp);
-        return append_fee_nonce_auth_hash(hash_temp, CX_SHA256_SIZE, hash, hashLen);
+        rs_zeroize(initial_sighash, sizeof(initial_sighash));
+        return err;
 
                           }
     case Message:
-    case Jwt: {
-        // we have byteString or JWT messages. The hash is the same for both types
+    case Jwt:
+    case AuthChallenge: {
+        // we have byteString, JWT messages or authentication challenges,
+        // the hash is the same for all of them
         cx_hash_sha256(data, data_len, hash, CX_SHA256_SIZE);
         return zxerr_ok;
     }
@@ -308,37 +573,58 @@ __Z_INLINE zxerr_t get_presig_hash(uint8_t* hash, uint16_t hashLen) {
     }
 }
 
-__Z_INLINE zxerr_t append_fee_nonce_auth_hash(uint8_t* input_hash, uint16_t input_hashLen, uint8_t* hash, uint16_t hashLen) {
-    uint8_t presig_data[PRESIG_DATA_LEN];
-    // uint8_t hash_temp[SHA512_DIGEST_LENGTH];
+__Z_INLINE zxerr_t get_auth_fee_nonce(uint8_t *buf, uint16_t bufLen) {
+    if (bufLen < AUTH_FEE_NONCE_LEN)
+        return zxerr_buffer_too_small;
 
-    if ( input_hashLen != CX_SHA256_SIZE )
+    uint8_t idx = 0;
+
+    // the tx auth type
+    if (tx_auth_flag(&buf[idx++]) != zxerr_ok)
         return zxerr_no_data;
 
-    memcpy(presig_data, input_hash, input_hashLen);
+    // the 8-byte transaction fee
+    idx += tx_fee(&buf[idx], 8);
 
-    // now append the auth-flag, fee and nonce
-    uint8_t idx = CX_SHA256_SIZE;
+    // the 8-byte transaction nonce
+    idx += tx_nonce(&buf[idx], 8);
 
-    // append the tx auth type
-    if (tx_auth_flag(&presig_data[idx++]) != zxerr_ok)
+    if (idx != AUTH_FEE_NONCE_LEN)
         return zxerr_no_data;
 
-    // append the 8-byte transaction fee
-    idx += tx_fee(&presig_data[idx], 8);
+    return zxerr_ok;
+}
 
-    // append the 8-byte transaction nonce
-    idx += tx_nonce(&presig_data[idx], 8);
+__Z_INLINE zxerr_t append_auth_data_hash(const uint8_t* input_hash, const uint8_t *auth_data, uint8_t* hash, uint16_t hashLen) {
+    uint8_t presig_data[PRESIG_DATA_LEN];
 
-    if (hashLen < CX_SHA256_SIZE || idx != PRESIG_DATA_LEN)
+    if (hashLen < CX_SHA256_SIZE)
         return zxerr_no_data;
 
+    memcpy(presig_data, input_hash, CX_SHA256_SIZE);
+    memcpy(&presig_data[CX_SHA256_SIZE], auth_data, AUTH_FEE_NONCE_LEN);
+
     // Now get the hash
     sha512_256_ctx ctx;
     SHA512_256_init(&ctx);
     SHA512_256_starts(&ctx);
     SHA512_256_update(&ctx, presig_data, PRESIG_DATA_LEN);
     SHA512_256_finish(&ctx, hash);
+    rs_zeroize(presig_data, sizeof(presig_data));
+    rs_zeroize(&ctx, sizeof(ctx));
     return zxerr_ok;
 }
 
+__Z_INLINE zxerr_t append_fee_nonce_auth_hash(uint8_t* input_hash, uint16_t input_hashLen, uint8_t* hash, uint16_t hashLen) {
+    uint8_t auth_data[AUTH_FEE_NONCE_LEN];
+
+    if ( input_hashLen != CX_SHA256_SIZE || hashLen < CX_SHA256_SIZE )
+        return zxerr_no_data;
+
+    // now append the auth-flag, fee and nonce
+    if (get_auth_fee_nonce(auth_data, AUTH_FEE_NONCE_LEN) != zxerr_ok) {
+        return zxerr_no_data;
+    }
+
+    return append_auth_data_hash(input_hash, auth_data, hash, hashLen);
+}
@@ -20,8 +20,9 @@
 #include "parser.h"
 #include <string.h>
 #include "zxmacros.h"
+#include "cx.h"
 
-#if defined(TARGET_NANOX) || defined(TARGET_NANOS2) || defined(TARGET_STAX)
+#if defined(TARGET_NANOX) || defined(TARGET_NANOS2) || defined(TARGET_STAX) || defined(TARGET_FLEX)
 #define RAM_BUFFER_SIZE 8192
 #define FLASH_BUFFER_SIZE 16384
 #elif defined(TARGET_NANOS)
@@ -37,13 +38,21 @@ typedef struct {
     uint8_t buffer[FLASH_BUFFER_SIZE];
 } storage_t;
 
-#if defined(TARGET_NANOS) || defined(TARGET_NANOX) || defined(TARGET_NANOS2) || defined(TARGET_STAX)
+#if defined(TARGET_NANOS) || defined(TARGET_NANOX) || defined(TARGET_NANOS2) || defined(TARGET_STAX) || defined(TARGET_FLEX)
 storage_t NV_CONST N_appdata_impl __attribute__ ((aligned(64)));
 #define N_appdata (*(NV_VOLATILE storage_t *)PIC(&N_appdata_impl))
 #endif
 
 static parser_context_t ctx_parsed_tx;
 
+// SHA-256 of the buffer the review was built from, the host must
+// not be able to change what is signed once it was reviewed
+static uint8_t reviewed_digest[CX_SHA256_SIZE];
+
+static void tx_buffer_digest(uint8_t *digest) {
+    cx_hash_sha256(tx_get_buffer(), tx_get_buffer_length(), digest, CX_SHA256_SIZE);
+}
+
 void tx_initialize() {
     buffering_init(
             ram_buffer,
@@ -55,6 +64,7 @@ void tx_initialize() {
 
 void tx_reset() {
     buffering_reset();
+    MEMZERO(reviewed_digest, sizeof(reviewed_digest));
 }
 
 void tx_reset_state() {
@@ -73,6 +83,10 @@ uint8_t *tx_get_buffer() {
     return buffering_get_buffer()->data;
 }
 
+uint32_t tx_get_max_buffer_length() {
+    return RAM_BUFFER_SIZE > FLASH_BUFFER_SIZE ? RAM_BUFFER_SIZE : FLASH_BUFFER_SIZE;
+}
+
 const char *tx_parse() {
     uint8_t err = parser_parse(
         &ctx_parsed_tx,
@@ -90,9 +104,21 @@ const char *tx_parse() {
         return parser_getErrorDescription(err);
     }
 
+    tx_buffer_digest(reviewed_digest);
     return NULL;
 }
 
+zxerr_t tx_check_digest() {
+    uint8_t digest[CX_SHA256_SIZE];
+    tx_buffer_digest(digest);
+
+    if (memcmp(digest, reviewed_digest, CX_SHA256_SIZE) != 0) {
+        zemu_log_stack("Buffer changed after review\n");
+        return zxerr_unknown;
+    }
+    return zxerr_ok;
+}
+
 zxerr_t tx_getNumItems(uint8_t *num_items) {
     parser_error_t err = parser_getNumItems(&ctx_parsed_tx, num_items);
 
@@ -133,6 +159,30 @@ zxerr_t tx_getItem(int8_t displayIdx,
     return zxerr_ok;
 }
 
+zxerr_t tx_getItemKind(int8_t displayIdx, item_kind_t *kind) {
+    if (displayIdx < 0) {
+        return zxerr_no_data;
+    }
+
+    if (parser_getItemKind(displayIdx, kind) != parser_ok) {
+        return zxerr_no_data;
+    }
+
+    return zxerr_ok;
+}
+
+zxerr_t tx_getItemSection(int8_t displayIdx, item_section_t *section) {
+    if (displayIdx < 0) {
+        return zxerr_no_data;
+    }
+
+    if (parser_getItemSection(displayIdx, section) != parser_ok) {
+        return zxerr_no_data;
+    }
+
+    return zxerr_ok;
+}
+
 zxerr_t tx_auth_flag(uint8_t *flag) {
     if (parser_tx_auth_flag(flag) != parser_ok)
         return zxerr_unknown;
@@ -147,11 +197,101 @@ uint8_t tx_nonce(uint8_t *nonce, uint16_t nonce_len) {
     return parser_tx_nonce(nonce, nonce_len);
 }
 
+zxerr_t tx_payload_kind(payload_kind_t *kind) {
+    if (parser_tx_payload_kind(kind) != parser_ok)
+        return zxerr_no_data;
+    return zxerr_ok;
+}
+
+zxerr_t tx_recipient_hash160(uint8_t *out, uint16_t outLen) {
+    if (parser_tx_recipient_hash160(out, outLen) != parser_ok)
+        return zxerr_no_data;
+    return zxerr_ok;
+}
+
+zxerr_t tx_recipient_address(char *out, uint16_t outLen) {
+    if (parser_tx_recipient_address(out, outLen) == 0)
+        return zxerr_no_data;
+    return zxerr_ok;
+}
+
+zxerr_t tx_amount(uint64_t *amount) {
+    if (parser_tx_amount(amount) != parser_ok)
+        return zxerr_no_data;
+    return zxerr_ok;
+}
+
+zxerr_t tx_num_post_conditions(uint8_t *num) {
+    if (parser_tx_num_post_conditions(num) != parser_ok)
+        return zxerr_no_data;
+    return zxerr_ok;
+}
+
+zxerr_t tx_multisig_threshold(uint16_t *threshold) {
+    if (parser_tx_multisig_threshold(threshold) != parser_ok)
+        return zxerr_no_data;
+    return zxerr_ok;
+}
+
 uint16_t tx_presig_hash_data(uint8_t *buf, uint16_t bufLen) {
     return parser_presig_hash_data(buf, bufLen);
 }
 
-uint16_t tx_last_tx_block(uint8_t ** last_tx_block) {
+uint32_t tx_stack_watermark(uint8_t entry) {
+    return parser_stack_watermark(entry);
+}
+
+void tx_stack_watermark_reset() {
+    parser_stack_watermark_reset();
+}
+
+parser_error_t tx_parse_diagnostic(parse_section_t *section, uint32_t *offset) {
+    return parser_parse_diagnostic(tx_get_buffer(), tx_get_buffer_length(), section, offset);
+}
+
+zxerr_t tx_initial_sighash(uint8_t *hash, uint16_t hashLen) {
+    if (parser_initial_sighash(hash, hashLen) != parser_ok) {
+        return zxerr_no_data;
+    }
+    return zxerr_ok;
+}
+
+zxerr_t tx_multisig_presig_hash(uint8_t *hash, uint16_t hashLen) {
+    if (parser_multisig_presig_hash(hash, hashLen) != parser_ok) {
+        return zxerr_no_data;
+    }
+    return zxerr_ok;
+}
+
+zxerr_t tx_multisig_auth_field_index(uint32_t *index) {
+    if (index == NULL || parser_multisig_auth_field_index(index) != parser_ok) {
+        return zxerr_no_data;
+    }
+    return zxerr_ok;
+}
+
+zxerr_t tx_check_signer() {
+    if (parser_check_signer() != parser_ok) {
+        return zxerr_invalid_crypto_settings;
+    }
+    return zxerr_ok;
+}
+
+zxerr_t tx_check_sponsor(const uint32_t *path, uint32_t path_len) {
+    if (path == NULL || parser_check_sponsor(path, path_len) != parser_ok) {
+        return zxerr_invalid_crypto_settings;
+    }
+    return zxerr_ok;
+}
+
+zxerr_t tx_role_auth_data(uint8_t role, uint8_t *buf, uint16_t bufLen) {
+    if (parser_role_auth_data(role, buf, bufLen) != bufLen) {
+        return zxerr_unknown;
+    }
+    return zxerr_ok;
+}
+
+uint16_t tx_last_tx_block(const uint8_t **last_tx_block) {
     return parser_last_transaction_block(last_tx_block);
 }
 
@@ -159,7 +299,35 @@ int8_t tx_is_multisig() {
     return parser_is_transaction_multisig();
 }
 
-uint16_t tx_previous_signer_data(uint8_t **data) {
+int8_t tx_is_order_independent() {
+    return parser_is_order_independent();
+}
+
+int8_t tx_is_sponsored() {
+    return parser_is_sponsored();
+}
+
+uint16_t tx_sponsor_address(char *out, uint16_t outLen) {
+    return parser_sponsor_address(out, outLen);
+}
+
+uint8_t tx_sponsor_fee(uint8_t *fee, uint16_t fee_len) {
+    return parser_sponsor_fee(fee, fee_len);
+}
+
+int8_t tx_is_high_fee() {
+    return parser_is_high_fee();
+}
+
+int8_t tx_is_denylisted() {
+    return parser_is_denylisted();
+}
+
+int8_t tx_is_nonstandard_path() {
+    return parser_is_nonstandard_path();
+}
+
+uint16_t tx_previous_signer_data(const uint8_t **data) {
     return parser_previous_signer_data(data);
 }
 
@@ -170,3 +338,7 @@ transaction_type_t tx_get_transaction_type(){
 zxerr_t tx_structured_msg_hash(uint8_t *out, uint16_t out_len){
     return parser_structured_msg_hash(out, out_len);
 }
+
+uint16_t tx_structured_msg_header_len() {
+    return parser_structured_msg_header_len();
+}
@@ -42,11 +42,20 @@ uint32_t tx_get_buffer_length();
 /// \return
 uint8_t *tx_get_buffer();
 
+/// Returns the maximum size of a transaction/message that fits in the buffer
+/// \return
+uint32_t tx_get_max_buffer_length();
+
 /// Parse message stored in transaction buffer
 /// This function should be called as soon as full buffer data is loaded.
 /// \return It returns NULL if data is valid or error message otherwise.
 const char *tx_parse();
 
+/// Hashes the buffer again and compares it with the digest taken when it
+/// was parsed, so that what is signed is what was reviewed.
+/// \return zxerr_ok if the buffer did not change
+zxerr_t tx_check_digest();
+
 /// Return the number of items in the transaction
 zxerr_t tx_getNumItems(uint8_t *num_items);
 
@@ -56,28 +65,113 @@ zxerr_t tx_getItem(int8_t displayIdx,
                    char *outValue, uint16_t outValueLen,
                    uint8_t pageIdx, uint8_t *pageCount);
 
+/// Gets the kind of value an specific item holds
+zxerr_t tx_getItemKind(int8_t displayIdx, item_kind_t *kind);
+
+/// Gets the review section of an specific item
+zxerr_t tx_getItemSection(int8_t displayIdx, item_section_t *section);
+
 // Gets the transaction authorization type
 zxerr_t tx_auth_flag(uint8_t *flag);
 
 // Returns 1 if the transaction is multisig, 0 otherwise, returns -1 in case of error
 int8_t tx_is_multisig();
 
+// Returns 1 if the origin is an order independent multisig account, whose
+// signers all sign the same hash, 0 otherwise
+int8_t tx_is_order_independent();
+
+// Returns 1 if the transaction is sponsored, 0 otherwise
+int8_t tx_is_sponsored();
+
+// Writes the null terminated sponsor address, returns its length or 0
+// if the transaction is not sponsored
+uint16_t tx_sponsor_address(char *out, uint16_t outLen);
+
+// Gets the sponsor fee as bytes, returns 0 if the transaction is not sponsored
+uint8_t tx_sponsor_fee(uint8_t *fee, uint16_t fee_len);
+
+// Returns 1 if the signer fee is above the configured threshold, 0 otherwise
+int8_t tx_is_high_fee();
+
+// Returns 1 if the contract called is in the provisioned denylist, 0 otherwise
+int8_t tx_is_denylisted();
+
+// Returns 1 if the transaction is signed with a non-standard path, 0 otherwise
+int8_t tx_is_nonstandard_path();
+
 // Gets the origin fee as bytes
 uint8_t tx_fee(uint8_t *fee, uint16_t fee_len);
 
 // Gets the origin nonce as bytes
 uint8_t tx_nonce(uint8_t *nonce, uint16_t nonce_len);
 
+// Gets the kind of the transaction payload
+zxerr_t tx_payload_kind(payload_kind_t *kind);
+
+// Gets the hash160 of the token transfer recipient
+zxerr_t tx_recipient_hash160(uint8_t *out, uint16_t outLen);
+
+// Gets the null terminated address of the token transfer recipient
+zxerr_t tx_recipient_address(char *out, uint16_t outLen);
+
+// Gets the token transfer amount in uSTX
+zxerr_t tx_amount(uint64_t *amount);
+
+// Gets the number of post conditions
+zxerr_t tx_num_post_conditions(uint8_t *num);
+
+// Gets the signatures required by a multisig origin
+zxerr_t tx_multisig_threshold(uint16_t *threshold);
+
 // Writes in buf, the auth fields used for the initial transaction hash
 uint16_t tx_presig_hash_data(uint8_t *buf, uint16_t bufLen);
 
+// Returns the most stack used by a parser entry point since the last reset,
+// the entry points are parsing, counting items and getting an item
+uint32_t tx_stack_watermark(uint8_t entry);
+
+void tx_stack_watermark_reset();
+
+// Returns the error the transaction in the buffer fails to parse with, or
+// parser_ok, and the section and byte offset where it was found
+parser_error_t tx_parse_diagnostic(parse_section_t *section, uint32_t *offset);
+
+// Computes the initial sighash, the hash of the transaction with
+// the auth fields cleared, by walking the parsed transaction
+zxerr_t tx_initial_sighash(uint8_t *hash, uint16_t hashLen);
+
+// Turns hash, the pre_sig_hash of the first signer of a multisig transaction,
+// into the one of this signer. The signatures of the previous signers are
+// taken from the auth fields and the previous signer data, if any
+zxerr_t tx_multisig_presig_hash(uint8_t *hash, uint16_t hashLen);
+
+// The index of the auth field of the multisig origin the signature of this
+// signer goes in, the field of its key or a new one after the last field
+zxerr_t tx_multisig_auth_field_index(uint32_t *index);
+
+// Checks again, right before signing, that the key derived from
+// hdPath is the origin or the sponsor of the parsed transaction
+zxerr_t tx_check_signer();
+
+// Checks that the key derived from path is the sponsor of the parsed
+// transaction, whose origin is the key derived from hdPath
+zxerr_t tx_check_sponsor(const uint32_t *path, uint32_t path_len);
+
+// Gets the auth flag, fee and nonce signed by the origin or the sponsor,
+// role is a signer_role_e
+zxerr_t tx_role_auth_data(uint8_t role, uint8_t *buf, uint16_t bufLen);
+
 // Gets a pointer to the last block in the transaction and returns its lenght
-uint16_t tx_last_tx_block(uint8_t ** last_tx_block);
+uint16_t tx_last_tx_block(const uint8_t **last_tx_block);
 
 // Gets the pointer to the previous signer signature and required data
 // for signing a multisig transaction
-uint16_t tx_previous_signer_data(uint8_t **data);
+uint16_t tx_previous_signer_data(const uint8_t **data);
 
 transaction_type_t tx_get_transaction_type();
 
 zxerr_t tx_structured_msg_hash(uint8_t *out, uint16_t out_len);
+
+// Length of the SIP018 prefix and domain of the parsed structured message, 0 if it is not one
+uint16_t tx_structured_msg_header_len();
@@ -24,7 +24,7 @@
 
 uint8_t version;
 
-uint32_t hdPath[HDPATH_LEN_DEFAULT];
+uint32_t hdPath[HDPATH_LEN_MAX];
 uint32_t hdPath_len;
 
 bool isTestnet() {
@@ -32,7 +32,15 @@ bool isTestnet() {
            hdPath[1] == HDPATH_1_TESTNET;
 }
 
-#if defined(TARGET_NANOS) || defined(TARGET_NANOX) || defined(TARGET_NANOS2) || defined(TARGET_STAX)
+bool isStandardPath() {
+    return rs_is_standard_path(hdPath, (uint8_t) hdPath_len) == 1;
+}
+
+bool isAllowedPath(const uint32_t *path, uint32_t path_len, path_kind_e kind) {
+    return rs_check_path(path, (uint8_t) path_len, kind) == parser_ok;
+}
+
+#if defined(TARGET_NANOS) || defined(TARGET_NANOX) || defined(TARGET_NANOS2) || defined(TARGET_STAX) || defined(TARGET_FLEX)
 #include "cx.h"
 
 bool ripemd160(uint8_t *in, uint16_t inLen, uint8_t *out) {
@@ -61,6 +69,10 @@ typedef struct {
 
 bool is_valid_network_version(uint8_t ver);
 
+// The hash160 of the last derived key, its address on
+// both networks is shown when verifying an address
+static uint8_t address_hash[CX_RIPEMD160_SIZE];
+
 // Set the network version to be used when getting the address from
 // the device public key.
 bool set_network_version(uint8_t network) {
@@ -83,15 +95,15 @@ bool is_valid_network_version(uint8_t ver) {
     return true;
 }
 
-uint16_t crypto_fillAddress_secp256k1(uint8_t *buffer, uint16_t buffer_len) {
-    if (buffer_len < sizeof(answer_t)) {
+uint16_t crypto_fillAddress_secp256k1(uint8_t *buffer, uint16_t buffer_len, bool uncompressed) {
+    if (buffer_len < sizeof(answer_t) + PK_LEN_SECP256K1_UNCOMPRESSED) {
         return 0;
     }
 
     MEMZERO(buffer, buffer_len);
     answer_t *const answer = (answer_t *) buffer;
 
-    if(crypto_extractPublicKey(hdPath, HDPATH_LEN_DEFAULT, answer->publicKey, sizeof_field(answer_t, publicKey)) != zxerr_ok) {
+    if(crypto_extractPublicKey(hdPath, hdPath_len, answer->publicKey, sizeof_field(answer_t, publicKey)) != zxerr_ok) {
         return 0;
     }
 
@@ -105,10 +117,52 @@ uint16_t crypto_fillAddress_secp256k1(uint8_t *buffer, uint16_t buffer_len) {
     if ( !is_valid_network_version(version) )
         version = COIN_VERSION_MAINNET_SINGLESIG;
     outLen = rs_c32_address(address_temp.hash_ripe, version, answer->address, outLen);
+    MEMCPY(address_hash, address_temp.hash_ripe, sizeof(address_hash));
+
+    if (!uncompressed) {
+        return PK_LEN_SECP256K1 + outLen;
+    }
+
+    // the address is followed by its null terminator so that it is
+    // still shown as is, the uncompressed key goes last
+    uint8_t *const uncompressedKey = answer->address + outLen + 1;
+    if (crypto_extractUncompressedPublicKey(hdPath, hdPath_len, uncompressedKey, PK_LEN_SECP256K1_UNCOMPRESSED) != zxerr_ok) {
+        return 0;
+    }
+
+    return PK_LEN_SECP256K1 + outLen + 1 + PK_LEN_SECP256K1_UNCOMPRESSED;
+}
+
+uint16_t crypto_fillBtcAddress(uint8_t *buffer, uint16_t buffer_len) {
+    if (buffer_len < sizeof(answer_t)) {
+        return 0;
+    }
+
+    MEMZERO(buffer, buffer_len);
+    answer_t *const answer = (answer_t *) buffer;
+
+    if(crypto_extractPublicKey(hdPath, hdPath_len, answer->publicKey, sizeof_field(answer_t, publicKey)) != zxerr_ok) {
+        return 0;
+    }
+
+    address_temp_t address_temp;
+    if(!crypto_extractPublicKeyHash(address_temp.hash_ripe, CX_RIPEMD160_SIZE)) {
+        return 0;
+    }
+
+    const uint16_t outLen = rs_btc_address(hdPath, (uint8_t) hdPath_len, address_temp.hash_ripe,
+                                           answer->address, sizeof_field(answer_t, address));
+    if (outLen == 0) {
+        return 0;
+    }
 
     return PK_LEN_SECP256K1 + outLen;
 }
 
+const uint8_t *crypto_addressHash() {
+    return address_hash;
+}
+
 uint16_t crypto_fillAuthkey_secp256k1(uint8_t *buffer, uint16_t buffer_len) {
     if (buffer_len < sizeof(answer_t)) {
         return 0;
@@ -117,7 +171,7 @@ uint16_t crypto_fillAuthkey_secp256k1(uint8_t *buffer, uint16_t buffer_len) {
     MEMZERO(buffer, buffer_len);
     answer_t *const answer = (answer_t *) buffer;
 
-    if(crypto_extractPublicKey(hdPath, HDPATH_LEN_AUTH, answer->publicKey, sizeof_field(answer_t, publicKey)) != zxerr_ok) {
+    if(crypto_extractPublicKey(hdPath, hdPath_len, answer->publicKey, sizeof_field(answer_t, publicKey)) != zxerr_ok) {
         return 0;
     }
 
@@ -125,13 +179,13 @@ uint16_t crypto_fillAuthkey_secp256k1(uint8_t *buffer, uint16_t buffer_len) {
 }
 
 
-zxerr_t crypto_extractPublicKey(const uint32_t *path, uint32_t path_len, uint8_t *pubKey, uint16_t pubKeyLen) {
-    cx_ecfp_public_key_t cx_publicKey;
+// Derives the key of path, W holds 0x04 followed by its x and y coordinates
+static zxerr_t derivePublicKey(const uint32_t *path, uint32_t path_len, cx_ecfp_public_key_t *cx_publicKey) {
     cx_ecfp_private_key_t cx_privateKey;
     uint8_t privateKeyData[32];
-    MEMZERO(&cx_publicKey, sizeof(cx_publicKey));
+    MEMZERO(cx_publicKey, sizeof(*cx_publicKey));
 
-    if (pubKeyLen < PK_LEN_SECP256K1 || path_len == 0) {
+    if (path_len == 0) {
         return zxerr_invalid_crypto_settings;
     }
 
@@ -145,25 +199,15 @@ zxerr_t crypto_extractPublicKey(const uint32_t *path, uint32_t path_len, uint8_t
                                        privateKeyData, NULL);
 
             cx_ecfp_init_private_key(CX_CURVE_256K1, privateKeyData, 32, &cx_privateKey);
-            cx_ecfp_init_public_key(CX_CURVE_256K1, NULL, 0, &cx_publicKey);
-            cx_ecfp_generate_pair(CX_CURVE_256K1, &cx_publicKey, &cx_privateKey, 1);
-
-            // Format pubkey
-            for (int i = 0; i < 32; i++) {
-                pubKey[i] = cx_publicKey.W[64 - i];
-            }
-            cx_publicKey.W[0] = cx_publicKey.W[64] & 1 ? 0x03 : 0x02; // "Compress" public key in place
-            if ((cx_publicKey.W[32] & 1) != 0) {
-                pubKey[31] |= 0x80;
-            }
-            MEMCPY(pubKey, cx_publicKey.W, PK_LEN_SECP256K1);
+            cx_ecfp_init_public_key(CX_CURVE_256K1, NULL, 0, cx_publicKey);
+            cx_ecfp_generate_pair(CX_CURVE_256K1, cx_publicKey, &cx_privateKey, 1);
         }
         CATCH_ALL {
             err = zxerr_ledger_api_error;
         }
         FINALLY {
-            MEMZERO(&cx_privateKey, sizeof(cx_privateKey));
-            MEMZERO(privateKeyData, 32);
+            rs_zeroize(&cx_privateKey, sizeof(cx_privateKey));
+            rs_zeroize(privateKeyData, sizeof(privateKeyData));
         }
     }
     END_TRY;
@@ -171,7 +215,52 @@ zxerr_t crypto_extractPublicKey(const uint32_t *path, uint32_t path_len, uint8_t
     return err;
 }
 
+zxerr_t crypto_extractPublicKey(const uint32_t *path, uint32_t path_len, uint8_t *pubKey, uint16_t pubKeyLen) {
+    cx_ecfp_public_key_t cx_publicKey;
+
+    if (pubKeyLen < PK_LEN_SECP256K1) {
+        return zxerr_invalid_crypto_settings;
+    }
+
+    const zxerr_t err = derivePublicKey(path, path_len, &cx_publicKey);
+    if (err != zxerr_ok) {
+        return err;
+    }
+
+    cx_publicKey.W[0] = cx_publicKey.W[64] & 1 ? 0x03 : 0x02; // "Compress" public key in place
+    MEMCPY(pubKey, cx_publicKey.W, PK_LEN_SECP256K1);
+    return zxerr_ok;
+}
+
+zxerr_t crypto_extractUncompressedPublicKey(const uint32_t *path, uint32_t path_len, uint8_t *pubKey, uint16_t pubKeyLen) {
+    cx_ecfp_public_key_t cx_publicKey;
+
+    if (pubKeyLen < PK_LEN_SECP256K1_UNCOMPRESSED) {
+        return zxerr_invalid_crypto_settings;
+    }
+
+    const zxerr_t err = derivePublicKey(path, path_len, &cx_publicKey);
+    if (err != zxerr_ok) {
+        return err;
+    }
+
+    MEMCPY(pubKey, cx_publicKey.W, PK_LEN_SECP256K1_UNCOMPRESSED);
+    return zxerr_ok;
+}
+
+// calculates the sha256 + ripemd160 of a compressed public key
+static bool hashPublicKey(const uint8_t *publicKey, uint8_t *hash160) {
+    address_temp_t address_temp;
+
+    cx_hash_sha256(publicKey, PK_LEN_SECP256K1, address_temp.hash_sha256, CX_SHA256_SIZE);
+    return ripemd160(address_temp.hash_sha256, CX_SHA256_SIZE, hash160);         // RIPEMD-160
+}
+
 bool crypto_extractPublicKeyHash(uint8_t *pubKeyHash, uint16_t pubKeyLen) {
+    return crypto_pathPublicKeyHash(hdPath, hdPath_len, pubKeyHash, pubKeyLen);
+}
+
+bool crypto_pathPublicKeyHash(const uint32_t *path, uint32_t path_len, uint8_t *pubKeyHash, uint16_t pubKeyLen) {
 
     if (pubKeyLen < CX_RIPEMD160_SIZE || pubKeyHash == NULL)
         return false;
@@ -179,7 +268,7 @@ bool crypto_extractPublicKeyHash(uint8_t *pubKeyHash, uint16_t pubKeyLen) {
     // gets the raw public key
     uint8_t publicKey[PK_LEN_SECP256K1];
 
-    if (crypto_extractPublicKey(hdPath, HDPATH_LEN_DEFAULT, publicKey, PK_LEN_SECP256K1) != zxerr_ok) {
+    if (crypto_extractPublicKey(path, path_len, publicKey, PK_LEN_SECP256K1) != zxerr_ok) {
         return false;
     }
 
@@ -191,11 +280,141 @@ bool crypto_extractPublicKeyHash(uint8_t *pubKeyHash, uint16_t pubKeyLen) {
         zemu_log("\n");
     }
 
-    // calculates the sha256 + ripemd160
-    address_temp_t address_temp;
+    return hashPublicKey(publicKey, pubKeyHash);
+}
 
-    cx_hash_sha256(publicKey, PK_LEN_SECP256K1, address_temp.hash_sha256, CX_SHA256_SIZE);
-    return ripemd160(address_temp.hash_sha256, CX_SHA256_SIZE, pubKeyHash);         // RIPEMD-160
+uint16_t crypto_fillAccounts(uint32_t start, uint8_t count, bool testnet, uint8_t *buffer, uint16_t buffer_len) {
+    if (buffer_len < 1 || count == 0 || start > HDPATH_ACCOUNT_MAX || count - 1u > HDPATH_ACCOUNT_MAX - start) {
+        return 0;
+    }
+
+    MEMZERO(buffer, buffer_len);
+    uint32_t path[HDPATH_LEN_DEFAULT] = {
+        HDPATH_0_DEFAULT,
+        testnet ? HDPATH_1_TESTNET : HDPATH_1_DEFAULT,
+        0,
+        HDPATH_3_DEFAULT,
+        HDPATH_4_DEFAULT,
+    };
+
+    // the first byte is the number of entries that follow, as many
+    // as fit in the buffer, the host asks again for the rest
+    uint16_t offset = 1;
+    uint8_t filled = 0;
+    for (; filled < count; filled++) {
+        uint8_t publicKey[PK_LEN_SECP256K1];
+        uint8_t hash160[CX_RIPEMD160_SIZE];
+        path[2] = 0x80000000u | (start + filled);
+
+        if (crypto_extractPublicKey(path, HDPATH_LEN_DEFAULT, publicKey, sizeof(publicKey)) != zxerr_ok ||
+            !hashPublicKey(publicKey, hash160)) {
+            return 0;
+        }
+
+        const uint16_t len = rs_account_entry(start + filled, publicKey, sizeof(publicKey), hash160,
+                                              buffer + offset, buffer_len - offset);
+        if (len == 0) {
+            break;
+        }
+        offset += len;
+    }
+
+    if (filled == 0) {
+        return 0;
+    }
+    buffer[0] = filled;
+    return offset;
+}
+
+// hash mode (1), threshold (2) and number of keys (1) of a multisig template
+#define MULTISIG_TEMPLATE_HEADER_LEN    4
+#define OP_CHECKMULTISIG                0xae
+
+// The account of the last multisig template the key of hdPath was found in
+static struct {
+    bool valid;
+    uint8_t hash160[CX_RIPEMD160_SIZE];
+} multisig_account;
+
+// hashes value the way Bitcoin scripts push small integers: OP_0, OP_1 to
+// OP_16, or a push of its minimal little endian encoding with the sign bit clear
+static bool hashScriptInt(cx_sha256_t *ctx, uint8_t value) {
+    uint8_t push[3];
+    uint8_t len = 0;
+    if (value == 0) {
+        push[len++] = 0x00;
+    } else if (value <= 16) {
+        push[len++] = 0x50 + value;
+    } else {
+        const bool signByte = (value & 0x80) != 0;
+        push[len++] = signByte ? 2 : 1;
+        push[len++] = value;
+        if (signByte) {
+            push[len++] = 0x00;
+        }
+    }
+    return cx_hash_no_throw(&ctx->header, 0, push, len, NULL, 0) == CX_OK;
+}
+
+// calculates the hash160 of the multisig account of tmpl, a template
+// rs_multisig_slot took, the way stacks-core derives multisig addresses:
+// of its redeem script, or of the witness program of the script for the
+// segwit hash modes
+static bool hashMultisigAccount(const uint8_t *tmpl, uint16_t tmplLen, uint8_t *hash160) {
+    if (tmplLen < MULTISIG_TEMPLATE_HEADER_LEN) {
+        return false;
+    }
+    // the threshold is at most the number of keys
+    const uint8_t threshold = tmpl[2];
+    const uint8_t numKeys = tmpl[3];
+    if (tmpl[1] != 0 || tmplLen != MULTISIG_TEMPLATE_HEADER_LEN + numKeys * PK_LEN_SECP256K1) {
+        return false;
+    }
+
+    cx_sha256_t ctx;
+    uint8_t digest[CX_SHA256_SIZE];
+    const uint8_t keyPush = PK_LEN_SECP256K1;
+    const uint8_t checkMultisig = OP_CHECKMULTISIG;
+    if (cx_sha256_init_no_throw(&ctx) != CX_OK || !hashScriptInt(&ctx, threshold)) {
+        return false;
+    }
+    const uint8_t *key = tmpl + MULTISIG_TEMPLATE_HEADER_LEN;
+    for (uint8_t i = 0; i < numKeys; i++, key += PK_LEN_SECP256K1) {
+        if (cx_hash_no_throw(&ctx.header, 0, &keyPush, 1, NULL, 0) != CX_OK ||
+            cx_hash_no_throw(&ctx.header, 0, key, PK_LEN_SECP256K1, NULL, 0) != CX_OK) {
+            return false;
+        }
+    }
+    if (!hashScriptInt(&ctx, numKeys) ||
+        cx_hash_no_throw(&ctx.header, CX_LAST, &checkMultisig, 1, digest, sizeof(digest)) != CX_OK) {
+        return false;
+    }
+
+    if (rs_hash_mode_is_segwit(tmpl[0])) {
+        // version 0 followed by the 32 bytes hash of the script
+        uint8_t program[2 + CX_SHA256_SIZE] = {0x00, CX_SHA256_SIZE};
+        MEMCPY(program + 2, digest, CX_SHA256_SIZE);
+        cx_hash_sha256(program, sizeof(program), digest, sizeof(digest));
+    }
+    return ripemd160(digest, sizeof(digest), hash160);
+}
+
+uint16_t crypto_fillMultisigSlot(const uint8_t *tmpl, uint16_t tmplLen, uint8_t *buffer, uint16_t bufferLen) {
+    if (tmpl == NULL || buffer == NULL) {
+        return 0;
+    }
+
+    uint8_t publicKey[PK_LEN_SECP256K1];
+    if (crypto_extractPublicKey(hdPath, hdPath_len, publicKey, sizeof(publicKey)) != zxerr_ok) {
+        return 0;
+    }
+    const uint16_t len = rs_multisig_slot(tmpl, tmplLen, publicKey, sizeof(publicKey), buffer, bufferLen);
+    multisig_account.valid = len != 0 && hashMultisigAccount(tmpl, tmplLen, multisig_account.hash160);
+    return len;
+}
+
+const uint8_t *crypto_multisigAccount() {
+    return multisig_account.valid ? multisig_account.hash160 : NULL;
 }
 
 typedef struct {
@@ -225,10 +444,8 @@ zxerr_t crypto_sign(uint8_t *buffer, uint16_t signatureMaxlen, const uint8_t *me
         ZEMU_LOGF(100, "Digest: *** %s\n", tmpBuff)
     #endif
 
-    cx_ecfp_private_key_t cx_privateKey;
     uint8_t privateKeyData[32];
-    int signatureLength = 0;
-    unsigned int info = 0;
+    uint16_t signatureLength = 0;
 
     signature_t *const signature = (signature_t *) buffer;
 
@@ -242,39 +459,32 @@ zxerr_t crypto_sign(uint8_t *buffer, uint16_t signatureMaxlen, const uint8_t *me
                                        hdPath,
                                        hdPath_len,
                                        privateKeyData, NULL);
-
-            cx_ecfp_init_private_key(CX_CURVE_256K1, privateKeyData, 32, &cx_privateKey);
-
-            // Sign
-            signatureLength = cx_ecdsa_sign(&cx_privateKey,
-                                            CX_RND_RFC6979 | CX_LAST,
-                                            CX_SHA256,
-                                            message,
-                                            CX_SHA256_SIZE,
-                                            signature->der_signature,
-                                            sizeof_field(signature_t, der_signature),
-                                            &info);
-
             zxerr = zxerr_ok;
         }
         CATCH_ALL {
-            signatureLength = 0;
             zxerr = zxerr_ledger_api_error;
         };
         FINALLY {
-            MEMZERO(&cx_privateKey, sizeof(cx_privateKey));
-            MEMZERO(privateKeyData, 32);
         }
     }
     END_TRY;
 
-    if(zxerr != zxerr_ok) {
-        return zxerr;
+    // The nonce is deterministic (RFC6979), the low-S form that verifiers
+    // accept is returned in both RSV and DER once it verifies against the
+    // key, see rust/parser/src/signature/ecdsa.rs
+    if (zxerr == zxerr_ok) {
+        MEMZERO(signature->der_signature, sizeof_field(signature_t, der_signature));
+        signatureLength = rs_ecdsa_sign(privateKeyData, message,
+                                        signature->r, signature->s, &signature->v,
+                                        signature->der_signature,
+                                        sizeof_field(signature_t, der_signature));
+        zxerr = signatureLength > 0 ? zxerr_ok : zxerr_ledger_api_error;
     }
+    rs_zeroize(privateKeyData, sizeof(privateKeyData));
 
-    err_convert_e err = convertDERtoRSV(signature->der_signature, info,  signature->r, signature->s, &signature->v);
-    if (err != no_error) {
-        return zxerr_encoding_failed;
+    if (zxerr != zxerr_ok) {
+        rs_zeroize(signature, sizeof(signature_t));
+        return zxerr;
     }
 
     // return actual size using value from signatureLength
@@ -287,6 +497,131 @@ zxerr_t crypto_sign(uint8_t *buffer, uint16_t signatureMaxlen, const uint8_t *me
     return zxerr;
 }
 
+zxerr_t crypto_verify(const uint8_t *digest, uint16_t digestLen,
+                      const uint8_t *signature, uint16_t signatureLen, bool *valid) {
+    if (digestLen != CX_SHA256_SIZE || signatureLen != SIGNATURE_RSV_LEN || valid == NULL) {
+        return zxerr_out_of_bounds;
+    }
+    *valid = false;
+
+    uint8_t publicKey[PK_LEN_SECP256K1_UNCOMPRESSED];
+    const zxerr_t err = crypto_extractUncompressedPublicKey(hdPath, hdPath_len, publicKey, sizeof(publicKey));
+    if (err != zxerr_ok) {
+        return err;
+    }
+
+    const uint8_t *r = signature;
+    const uint8_t *s = r + sizeof_field(signature_t, r);
+    const uint8_t v = s[sizeof_field(signature_t, s)];
+    *valid = rs_ecdsa_verify(publicKey, digest, r, s, v) == 1;
+    return zxerr_ok;
+}
+
+// Verifies the BIP-340 signature against the key of the path before it
+// leaves the device, as done for the ECDSA ones
+static zxerr_t verifySchnorrSignature(const uint8_t *message, const uint8_t *signature) {
+    cx_ecfp_public_key_t cx_publicKey;
+    zxerr_t err = derivePublicKey(hdPath, hdPath_len, &cx_publicKey);
+    if (err != zxerr_ok) {
+        return err;
+    }
+
+    if (!cx_ecschnorr_verify(&cx_publicKey, CX_ECSCHNORR_BIP0340, CX_SHA256,
+                             message, SCHNORR_MESSAGE_LEN, signature, SCHNORR_SIGNATURE_LEN)) {
+        err = zxerr_ledger_api_error;
+    }
+    MEMZERO(&cx_publicKey, sizeof(cx_publicKey));
+    return err;
+}
+
+zxerr_t crypto_sign_schnorr(const uint8_t *message, uint16_t messageLen, uint8_t *signature, uint16_t signatureLen) {
+    if (messageLen != SCHNORR_MESSAGE_LEN) {
+        return zxerr_out_of_bounds;
+    }
+    if (signatureLen < SCHNORR_SIGNATURE_LEN) {
+        return zxerr_buffer_too_small;
+    }
+
+    cx_ecfp_private_key_t cx_privateKey;
+    uint8_t privateKeyData[32];
+    size_t signatureLength = SCHNORR_SIGNATURE_LEN;
+
+    zxerr_t zxerr = zxerr_unknown;
+    BEGIN_TRY
+    {
+        TRY
+        {
+            os_perso_derive_node_bip32(CX_CURVE_256K1,
+                                       hdPath,
+                                       hdPath_len,
+                                       privateKeyData, NULL);
+
+            cx_ecfp_init_private_key(CX_CURVE_256K1, privateKeyData, 32, &cx_privateKey);
+
+            // BIP-340 with fresh auxiliary randomness, the key is not tweaked
+            zxerr = cx_ecschnorr_sign_no_throw(&cx_privateKey,
+                                               CX_ECSCHNORR_BIP0340 | CX_RND_TRNG,
+                                               CX_SHA256,
+                                               message,
+                                               SCHNORR_MESSAGE_LEN,
+                                               signature,
+                                               &signatureLength) == CX_OK ? zxerr_ok : zxerr_ledger_api_error;
+        }
+        CATCH_ALL {
+            zxerr = zxerr_ledger_api_error;
+        };
+        FINALLY {
+            rs_zeroize(&cx_privateKey, sizeof(cx_privateKey));
+            rs_zeroize(privateKeyData, sizeof(privateKeyData));
+        }
+    }
+    END_TRY;
+
+    if (zxerr == zxerr_ok &&
+        (signatureLength != SCHNORR_SIGNATURE_LEN ||
+         rs_is_schnorr_signature(signature, SCHNORR_SIGNATURE_LEN) != 1)) {
+        zxerr = zxerr_encoding_failed;
+    }
+
+    if (zxerr == zxerr_ok) {
+        zxerr = verifySchnorrSignature(message, signature);
+    }
+
+    if (zxerr != zxerr_ok) {
+        rs_zeroize(signature, signatureLen);
+    }
+    return zxerr;
+}
+
+zxerr_t crypto_post_sighash(const uint8_t *presig_hash, uint16_t presig_hashLen, uint8_t *buffer, uint16_t bufferLen) {
+    if (presig_hashLen != CX_SHA256_SIZE || bufferLen < sizeof(signature_t) - sizeof_field(signature_t, der_signature)) {
+        return zxerr_buffer_too_small;
+    }
+
+    signature_t *const signature = (signature_t *) buffer;
+
+    // set the signing public key's encoding byte, it is compressed(it is our device pubkey)
+    const uint8_t pubkey_encoding = 0x00;
+    uint8_t hash_temp[SHA512_DIGEST_LENGTH];
+
+    sha512_256_ctx ctx;
+    SHA512_256_init(&ctx);
+    SHA512_256_starts(&ctx);
+    // sighash + pubkey encoding
+    SHA512_256_update(&ctx, presig_hash, presig_hashLen);
+    SHA512_256_update(&ctx, &pubkey_encoding, 1);
+    // the signature's v value
+    SHA512_256_update(&ctx, &signature->v, 1);
+    // the signature's rs values
+    SHA512_256_update(&ctx, signature->r, sizeof_field(signature_t, r) + sizeof_field(signature_t, s));
+    SHA512_256_finish(&ctx, hash_temp);
+
+    MEMCPY(signature->post_sighash, hash_temp, sizeof_field(signature_t, post_sighash));
+    rs_zeroize(hash_temp, sizeof(hash_temp));
+    rs_zeroize(&ctx, sizeof(ctx));
+    return zxerr_ok;
+}
+
 #endif
 
 
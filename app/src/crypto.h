@@ -28,7 +28,7 @@ extern "C" {
 
 #define CHECKSUM_LENGTH             4
 
-extern uint32_t hdPath[HDPATH_LEN_DEFAULT];
+extern uint32_t hdPath[HDPATH_LEN_MAX];
 extern uint32_t hdPath_len;
 
 extern address_kind_e addressKind;
@@ -39,19 +39,72 @@ bool set_network_version(uint8_t version);
 
 bool isTestnet();
 
+// Whether hdPath is m/44'/5757'/x'/0/y or m/44'/1'/x'/0/y,
+// others are derived but warned about
+bool isStandardPath();
+
+// Checks a path against the policies of kind, see rust/parser/src/path.rs
+bool isAllowedPath(const uint32_t *path, uint32_t path_len, path_kind_e kind);
+
 zxerr_t crypto_extractPublicKey(const uint32_t *path, uint32_t path_len, uint8_t *pubKey, uint16_t pubKeyLen);
 
+// Same key as crypto_extractPublicKey, 0x04 followed by its x and y coordinates
+zxerr_t crypto_extractUncompressedPublicKey(const uint32_t *path, uint32_t path_len, uint8_t *pubKey, uint16_t pubKeyLen);
+
 bool crypto_extractPublicKeyHash(uint8_t *pubKey, uint16_t pubKeyLen);
 
-uint16_t crypto_fillAddress_secp256k1(uint8_t *buffer, uint16_t bufferLen);
+// Same as crypto_extractPublicKeyHash for the key at path instead of hdPath
+bool crypto_pathPublicKeyHash(const uint32_t *path, uint32_t path_len, uint8_t *pubKeyHash, uint16_t pubKeyLen);
+
+// Fills buffer with the compressed key of hdPath and its address, followed
+// by a null byte and the uncompressed key if it is requested
+uint16_t crypto_fillAddress_secp256k1(uint8_t *buffer, uint16_t bufferLen, bool uncompressed);
+
+// Returns the hash160 of the key whose address was filled by
+// crypto_fillAddress_secp256k1, so it can be shown on either network
+const uint8_t *crypto_addressHash();
 uint16_t crypto_fillAuthkey_secp256k1(uint8_t *buffer, uint16_t bufferLen);
 
+// Fills buffer with the compressed key of hdPath, a Bitcoin path, and its
+// P2PKH or P2WPKH address, see rust/parser/src/btc.rs
+uint16_t crypto_fillBtcAddress(uint8_t *buffer, uint16_t bufferLen);
+
+// Fills buffer with the accounts m/44'/5757'/i'/0/0, or m/44'/1'/i'/0/0 on
+// testnet, from start on: a count byte followed by one entry per account,
+// see rust/parser/src/account.rs. Stops early when the buffer is full
+uint16_t crypto_fillAccounts(uint32_t start, uint8_t count, bool testnet, uint8_t *buffer, uint16_t bufferLen);
+
+// Fills buffer with the slot the compressed key of hdPath takes in the
+// multisig account of tmpl, followed by the key, see
+// rust/parser/src/multisig.rs. Returns 0 if the key is not in it
+uint16_t crypto_fillMultisigSlot(const uint8_t *tmpl, uint16_t tmplLen, uint8_t *buffer, uint16_t bufferLen);
+
+// The hash160 of the multisig account of the last template
+// crypto_fillMultisigSlot found the key in, NULL if there is none
+const uint8_t *crypto_multisigAccount();
+
+// ECDSA signature of a 32 bytes digest with the key of hdPath. The
+// signature is verified against the key before it is returned, on a
+// mismatch the buffer is cleared and an error returned
 zxerr_t crypto_sign(uint8_t *buffer,
                     uint16_t signatureMaxlen,
                     const uint8_t *message,
                     uint16_t messageLen,
                     uint16_t *sigSize);
 
+// Checks whether the 65 bytes signature, r, s and v as crypto_sign writes
+// them, is a valid signature of the 32 bytes digest by the key of hdPath
+zxerr_t crypto_verify(const uint8_t *digest, uint16_t digestLen,
+                      const uint8_t *signature, uint16_t signatureLen, bool *valid);
+
+// BIP-340 signature of a 32 bytes message with the key of hdPath,
+// written as r followed by s. It is verified as the ECDSA ones
+zxerr_t crypto_sign_schnorr(const uint8_t *message, uint16_t messageLen, uint8_t *signature, uint16_t signatureLen);
+
+// Computes the post_sig_hash of a signature written by crypto_sign
+// and stores it in the first 32-bytes of the buffer
+zxerr_t crypto_post_sighash(const uint8_t *presig_hash, uint16_t presig_hashLen, uint8_t *buffer, uint16_t bufferLen);
+
 #ifdef __cplusplus
 }
 #endif
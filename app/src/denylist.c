@@ -0,0 +1,111 @@
+/*******************************************************************************
+*   (c) 2026 Zondax AG
+*
+*  Licensed under the Apache License, Version 2.0 (the "License");
+*  you may not use this file except in compliance with the License.
+*  You may obtain a copy of the License at
+*
+*      http://www.apache.org/licenses/LICENSE-2.0
+*
+*  Unless required by applicable law or agreed to in writing, software
+*  distributed under the License is distributed on an "AS IS" BASIS,
+*  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+*  See the License for the specific language governing permissions and
+*  limitations under the License.
+********************************************************************************/
+
+#include "denylist.h"
+#include "sha512.h"
+#include "zxmacros.h"
+#include "cx.h"
+
+#define DENYLIST_HASH_LEN       32
+#define SECP256K1_PK_LEN        65
+
+// Prepended to the entries when hashing, so that a list signature
+// can not be taken for a signature of something else
+#define DENYLIST_DOMAIN         "STX-CONTRACT-DENYLIST"
+
+typedef struct {
+    bool active;
+    uint16_t num_entries;
+    sha512_256_ctx ctx;
+    uint8_t entries[DENYLIST_MAX_ENTRIES * DENYLIST_ENTRY_LEN];
+} denylist_t;
+
+// Only kept in RAM, every session starts without a list
+static denylist_t denylist;
+
+#if defined(DENYLIST_PUBKEY)
+// Uncompressed secp256k1 key the lists are signed with, set at build time
+static const uint8_t denylist_pubkey[SECP256K1_PK_LEN] = { DENYLIST_PUBKEY };
+#endif
+
+bool denylist_supported() {
+#if defined(DENYLIST_PUBKEY)
+    return true;
+#else
+    return false;
+#endif
+}
+
+void denylist_reset() {
+    MEMZERO(&denylist, sizeof(denylist));
+    SHA512_256_init(&denylist.ctx);
+    SHA512_256_starts(&denylist.ctx);
+    SHA512_256_update(&denylist.ctx, (const uint8_t *) DENYLIST_DOMAIN, sizeof(DENYLIST_DOMAIN) - 1);
+}
+
+zxerr_t denylist_append(const uint8_t *entries, uint16_t entriesLen) {
+    if (entries == NULL || denylist.active || entriesLen % DENYLIST_ENTRY_LEN != 0) {
+        return zxerr_unknown;
+    }
+
+    const uint16_t count = entriesLen / DENYLIST_ENTRY_LEN;
+    if (count > DENYLIST_MAX_ENTRIES - denylist.num_entries) {
+        return zxerr_buffer_too_small;
+    }
+
+    MEMCPY(denylist.entries + denylist.num_entries * DENYLIST_ENTRY_LEN, entries, entriesLen);
+    SHA512_256_update(&denylist.ctx, entries, entriesLen);
+    denylist.num_entries += count;
+    return zxerr_ok;
+}
+
+zxerr_t denylist_activate(const uint8_t *signature, uint16_t signatureLen) {
+    if (signature == NULL || denylist.active || !denylist_supported()) {
+        denylist_reset();
+        return zxerr_unknown;
+    }
+
+    // only the first DENYLIST_HASH_LEN bytes are the SHA-512/256
+    uint8_t hash[SHA512_DIGEST_LENGTH];
+    SHA512_256_finish(&denylist.ctx, hash);
+
+    bool valid = false;
+#if defined(DENYLIST_PUBKEY)
+    cx_ecfp_public_key_t pubkey;
+    valid = cx_ecfp_init_public_key_no_throw(CX_CURVE_256K1, denylist_pubkey, SECP256K1_PK_LEN, &pubkey) == CX_OK &&
+            cx_ecdsa_verify_no_throw(&pubkey, hash, DENYLIST_HASH_LEN, signature, signatureLen);
+#endif
+
+    if (!valid) {
+        denylist_reset();
+        return zxerr_invalid_crypto_settings;
+    }
+
+    denylist.active = true;
+    return zxerr_ok;
+}
+
+const uint8_t *denylist_entries(uint16_t *entriesLen) {
+    if (entriesLen == NULL) {
+        return NULL;
+    }
+    *entriesLen = 0;
+    if (!denylist.active) {
+        return NULL;
+    }
+    *entriesLen = denylist.num_entries * DENYLIST_ENTRY_LEN;
+    return denylist.entries;
+}
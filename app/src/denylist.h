@@ -0,0 +1,51 @@
+/*******************************************************************************
+*   (c) 2026 Zondax AG
+*
+*  Licensed under the Apache License, Version 2.0 (the "License");
+*  you may not use this file except in compliance with the License.
+*  You may obtain a copy of the License at
+*
+*      http://www.apache.org/licenses/LICENSE-2.0
+*
+*  Unless required by applicable law or agreed to in writing, software
+*  distributed under the License is distributed on an "AS IS" BASIS,
+*  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+*  See the License for the specific language governing permissions and
+*  limitations under the License.
+********************************************************************************/
+
+#pragma once
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#include <stdint.h>
+#include <stdbool.h>
+#include "zxerror.h"
+
+// The first bytes of the SHA-512/256 of a serialized contract
+// principal, the address followed by the length prefixed name
+#define DENYLIST_ENTRY_LEN      20
+#define DENYLIST_MAX_ENTRIES    16
+
+/// Drops the list in use and starts receiving a new one,
+/// no contract is denylisted until it is complete
+void denylist_reset();
+
+/// Appends entries to the list being received
+zxerr_t denylist_append(const uint8_t *entries, uint16_t entriesLen);
+
+/// Checks the DER signature of the list received against the denylist
+/// signing key and starts using it. The list is dropped if it is not valid
+zxerr_t denylist_activate(const uint8_t *signature, uint16_t signatureLen);
+
+/// Whether a signing key was set at build time, without it lists can not be provisioned
+bool denylist_supported();
+
+/// Returns the entries of the list in use, NULL if there is none
+const uint8_t *denylist_entries(uint16_t *entriesLen);
+
+#ifdef __cplusplus
+}
+#endif
@@ -0,0 +1,123 @@
+/*******************************************************************************
+*   (c) 2026 Zondax AG
+*
+*  Licensed under the Apache License, Version 2.0 (the "License");
+*  you may not use this file except in compliance with the License.
+*  You may obtain a copy of the License at
+*
+*      http://www.apache.org/licenses/LICENSE-2.0
+*
+*  Unless required by applicable law or agreed to in writing, software
+*  distributed under the License is distributed on an "AS IS" BASIS,
+*  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+*  See the License for the specific language governing permissions and
+*  limitations under the License.
+********************************************************************************/
+
+#include <stdio.h>
+#include "fee_bump.h"
+#include "labels.h"
+#include "crypto.h"
+#include "zxmacros.h"
+#include "zxformat.h"
+#include "settings.h"
+
+typedef struct {
+    bool valid;
+    uint8_t initial_sighash[FEE_BUMP_HASH_LEN];
+    uint32_t path[HDPATH_LEN_DEFAULT];
+    uint8_t fee[FEE_BUMP_FIELD_LEN];
+    uint8_t nonce[FEE_BUMP_FIELD_LEN];
+    // the values being reviewed
+    uint8_t new_fee[FEE_BUMP_FIELD_LEN];
+    uint8_t new_nonce[FEE_BUMP_FIELD_LEN];
+} fee_bump_t;
+
+static fee_bump_t last_review;
+
+static uint64_t be_bytes_to_u64(const uint8_t *bytes) {
+    uint64_t value = 0;
+    for (uint8_t i = 0; i < FEE_BUMP_FIELD_LEN; i++) {
+        value = (value << 8) | bytes[i];
+    }
+    return value;
+}
+
+static zxerr_t print_change(char *outValue, uint16_t outValueLen,
+                            const uint8_t *old_value, const uint8_t *new_value, uint8_t decimals,
+                            uint8_t pageIdx, uint8_t *pageCount) {
+    // the digits of a u64 along with the decimal point
+    char old_str[22];
+    char new_str[22];
+    char buffer[60];
+
+    if (fpuint64_to_str(old_str, sizeof(old_str), be_bytes_to_u64(old_value), decimals) == 0 ||
+        fpuint64_to_str(new_str, sizeof(new_str), be_bytes_to_u64(new_value), decimals) == 0) {
+        return zxerr_encoding_failed;
+    }
+
+    if (MEMCMP(old_value, new_value, FEE_BUMP_FIELD_LEN) == 0) {
+        snprintf(buffer, sizeof(buffer), "%s (unchanged)", new_str);
+    } else {
+        snprintf(buffer, sizeof(buffer), "%s to %s", old_str, new_str);
+    }
+    pageString(outValue, outValueLen, buffer, pageIdx, pageCount);
+    return zxerr_ok;
+}
+
+void fee_bump_reset() {
+    MEMZERO(&last_review, sizeof(last_review));
+}
+
+void fee_bump_store(const uint8_t *initial_sighash, const uint8_t *fee, const uint8_t *nonce) {
+    MEMCPY(last_review.initial_sighash, initial_sighash, FEE_BUMP_HASH_LEN);
+    MEMCPY(last_review.path, hdPath, sizeof(last_review.path));
+    MEMCPY(last_review.fee, fee, FEE_BUMP_FIELD_LEN);
+    MEMCPY(last_review.nonce, nonce, FEE_BUMP_FIELD_LEN);
+    MEMZERO(last_review.new_fee, FEE_BUMP_FIELD_LEN);
+    MEMZERO(last_review.new_nonce, FEE_BUMP_FIELD_LEN);
+    last_review.valid = true;
+}
+
+zxerr_t fee_bump_check(const uint8_t *initial_sighash, const uint8_t *fee, const uint8_t *nonce) {
+    if (!last_review.valid) {
+        return zxerr_no_data;
+    }
+
+    if (hdPath_len != HDPATH_LEN_DEFAULT ||
+        MEMCMP(last_review.path, hdPath, sizeof(last_review.path)) != 0 ||
+        MEMCMP(last_review.initial_sighash, initial_sighash, FEE_BUMP_HASH_LEN) != 0) {
+        return zxerr_invalid_crypto_settings;
+    }
+
+    MEMCPY(last_review.new_fee, fee, FEE_BUMP_FIELD_LEN);
+    MEMCPY(last_review.new_nonce, nonce, FEE_BUMP_FIELD_LEN);
+    return zxerr_ok;
+}
+
+zxerr_t fee_bump_getNumItems(uint8_t *num_items) {
+    *num_items = last_review.valid ? 2 : 0;
+    return zxerr_ok;
+}
+
+zxerr_t fee_bump_getItem(int8_t displayIdx,
+                         char *outKey, uint16_t outKeyLen,
+                         char *outValue, uint16_t outValueLen,
+                         uint8_t pageIdx, uint8_t *pageCount) {
+    if (!last_review.valid) {
+        return zxerr_no_data;
+    }
+
+    switch (displayIdx) {
+        case 0:
+            snprintf(outKey, outKeyLen, app_settings_stx_units() ? LABEL_FEE_STX : LABEL_FEE);
+            return print_change(outValue, outValueLen, last_review.fee, last_review.new_fee,
+                                settings_amount_decimals(), pageIdx, pageCount);
+        case 1:
+            snprintf(outKey, outKeyLen, LABEL_NONCE);
+            return print_change(outValue, outValueLen, last_review.nonce, last_review.new_nonce, 0,
+                                pageIdx, pageCount);
+        default:
+            return zxerr_no_data;
+    }
+}
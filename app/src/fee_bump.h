@@ -0,0 +1,53 @@
+/*******************************************************************************
+*   (c) 2026 Zondax AG
+*
+*  Licensed under the Apache License, Version 2.0 (the "License");
+*  you may not use this file except in compliance with the License.
+*  You may obtain a copy of the License at
+*
+*      http://www.apache.org/licenses/LICENSE-2.0
+*
+*  Unless required by applicable law or agreed to in writing, software
+*  distributed under the License is distributed on an "AS IS" BASIS,
+*  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+*  See the License for the specific language governing permissions and
+*  limitations under the License.
+********************************************************************************/
+
+#pragma once
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#include <stdint.h>
+#include <stdbool.h>
+#include "coin.h"
+#include "zxerror.h"
+
+#define FEE_BUMP_HASH_LEN       32
+#define FEE_BUMP_FIELD_LEN      8
+
+/// Forgets the last reviewed transaction
+void fee_bump_reset();
+
+/// Remembers a signed transaction by its initial sighash, which does not
+/// depend on the fee and nonce, along with its fee, nonce and signing path(hdPath)
+void fee_bump_store(const uint8_t *initial_sighash, const uint8_t *fee, const uint8_t *nonce);
+
+/// Checks the initial sighash and hdPath against the last signed transaction
+/// and loads the new fee and nonce to be reviewed
+zxerr_t fee_bump_check(const uint8_t *initial_sighash, const uint8_t *fee, const uint8_t *nonce);
+
+/// Return the number of items in the fee bump review
+zxerr_t fee_bump_getNumItems(uint8_t *num_items);
+
+/// Gets an specific item from the fee bump review (including paging)
+zxerr_t fee_bump_getItem(int8_t displayIdx,
+                         char *outKey, uint16_t outKeyLen,
+                         char *outValue, uint16_t outValueLen,
+                         uint8_t pageIdx, uint8_t *pageCount);
+
+#ifdef __cplusplus
+}
+#endif
@@ -0,0 +1,107 @@
+/*******************************************************************************
+*   (c) 2026 Zondax AG
+*
+*  Licensed under the Apache License, Version 2.0 (the "License");
+*  you may not use this file except in compliance with the License.
+*  You may obtain a copy of the License at
+*
+*      http://www.apache.org/licenses/LICENSE-2.0
+*
+*  Unless required by applicable law or agreed to in writing, software
+*  distributed under the License is distributed on an "AS IS" BASIS,
+*  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+*  See the License for the specific language governing permissions and
+*  limitations under the License.
+********************************************************************************/
+
+#include "function_abi.h"
+#include "sha512.h"
+#include "app_mode.h"
+#include "rslib.h"
+#include "zxmacros.h"
+#include "cx.h"
+
+#define FUNCTION_ABI_HASH_LEN   32
+#define SECP256K1_PK_LEN        65
+
+// Prepended to the signature when hashing, so that its DER signature
+// can not be taken for a signature of something else
+#define FUNCTION_ABI_DOMAIN     "STX-CONTRACT-ABI"
+
+typedef struct {
+    bool active;
+    // whether it was signed with the signing key
+    bool verified;
+    uint16_t len;
+    sha512_256_ctx ctx;
+    uint8_t data[FUNCTION_ABI_MAX_LEN];
+} function_abi_t;
+
+// Only kept in RAM, every session starts without a signature
+static function_abi_t function_abi;
+
+#if defined(FUNCTION_ABI_PUBKEY)
+// Uncompressed secp256k1 key the signatures are signed with, set at build time
+static const uint8_t function_abi_pubkey[SECP256K1_PK_LEN] = { FUNCTION_ABI_PUBKEY };
+#endif
+
+void function_abi_reset() {
+    MEMZERO(&function_abi, sizeof(function_abi));
+    SHA512_256_init(&function_abi.ctx);
+    SHA512_256_starts(&function_abi.ctx);
+    SHA512_256_update(&function_abi.ctx, (const uint8_t *) FUNCTION_ABI_DOMAIN, sizeof(FUNCTION_ABI_DOMAIN) - 1);
+}
+
+zxerr_t function_abi_append(const uint8_t *data, uint16_t dataLen) {
+    if (data == NULL || function_abi.active) {
+        return zxerr_unknown;
+    }
+    if (dataLen > FUNCTION_ABI_MAX_LEN - function_abi.len) {
+        return zxerr_buffer_too_small;
+    }
+
+    MEMCPY(function_abi.data + function_abi.len, data, dataLen);
+    SHA512_256_update(&function_abi.ctx, data, dataLen);
+    function_abi.len += dataLen;
+    return zxerr_ok;
+}
+
+zxerr_t function_abi_activate(const uint8_t *signature, uint16_t signatureLen) {
+    if (function_abi.active || rs_check_abi(function_abi.data, function_abi.len) != parser_ok) {
+        function_abi_reset();
+        return zxerr_unknown;
+    }
+
+    bool verified = false;
+    if (signature != NULL && signatureLen > 0) {
+        // only the first FUNCTION_ABI_HASH_LEN bytes are the SHA-512/256
+        uint8_t hash[SHA512_DIGEST_LENGTH];
+        SHA512_256_finish(&function_abi.ctx, hash);
+#if defined(FUNCTION_ABI_PUBKEY)
+        cx_ecfp_public_key_t pubkey;
+        verified = cx_ecfp_init_public_key_no_throw(CX_CURVE_256K1, function_abi_pubkey, SECP256K1_PK_LEN, &pubkey) == CX_OK &&
+                   cx_ecdsa_verify_no_throw(&pubkey, hash, FUNCTION_ABI_HASH_LEN, signature, signatureLen);
+#endif
+        if (!verified) {
+            function_abi_reset();
+            return zxerr_invalid_crypto_settings;
+        }
+    }
+
+    function_abi.active = true;
+    function_abi.verified = verified;
+    return zxerr_ok;
+}
+
+const uint8_t *function_abi_data(uint16_t *dataLen) {
+    if (dataLen == NULL) {
+        return NULL;
+    }
+    *dataLen = 0;
+    // names the host did not sign are only trusted in expert mode
+    if (!function_abi.active || (!function_abi.verified && !app_mode_expert())) {
+        return NULL;
+    }
+    *dataLen = function_abi.len;
+    return function_abi.data;
+}
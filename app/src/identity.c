@@ -0,0 +1,73 @@
+/*******************************************************************************
+*   (c) 2026 Zondax AG
+*
+*  Licensed under the Apache License, Version 2.0 (the "License");
+*  you may not use this file except in compliance with the License.
+*  You may obtain a copy of the License at
+*
+*      http://www.apache.org/licenses/LICENSE-2.0
+*
+*  Unless required by applicable law or agreed to in writing, software
+*  distributed under the License is distributed on an "AS IS" BASIS,
+*  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+*  See the License for the specific language governing permissions and
+*  limitations under the License.
+********************************************************************************/
+
+#include <stdio.h>
+#include "identity.h"
+#include "labels.h"
+#include "crypto.h"
+#include "tx.h"
+#include "zxmacros.h"
+#include "zxformat.h"
+
+// the identity item comes before the ones of the token
+#define IDENTITY_ITEMS 1
+
+zxerr_t identity_getNumItems(uint8_t *num_items) {
+    uint8_t token_items = 0;
+    const zxerr_t err = tx_getNumItems(&token_items);
+    if (err != zxerr_ok) {
+        return err;
+    }
+    if (token_items > INT8_MAX - IDENTITY_ITEMS) {
+        return zxerr_out_of_bounds;
+    }
+    *num_items = token_items + IDENTITY_ITEMS;
+    return zxerr_ok;
+}
+
+zxerr_t identity_getItem(int8_t displayIdx,
+                         char *outKey, uint16_t outKeyLen,
+                         char *outValue, uint16_t outValueLen,
+                         uint8_t pageIdx, uint8_t *pageCount) {
+    if (displayIdx >= IDENTITY_ITEMS) {
+        return tx_getItem(displayIdx - IDENTITY_ITEMS, outKey, outKeyLen, outValue, outValueLen, pageIdx, pageCount);
+    }
+    if (displayIdx < 0) {
+        return zxerr_no_data;
+    }
+
+    snprintf(outKey, outKeyLen, LABEL_SIGN_IDENTITY);
+    char buffer[100];
+    bip32_to_str(buffer, sizeof(buffer), hdPath, hdPath_len);
+    pageString(outValue, outValueLen, buffer, pageIdx, pageCount);
+    return zxerr_ok;
+}
+
+zxerr_t identity_getItemKind(int8_t displayIdx, item_kind_t *kind) {
+    if (displayIdx >= IDENTITY_ITEMS) {
+        return tx_getItemKind(displayIdx - IDENTITY_ITEMS, kind);
+    }
+    *kind = item_kind_text;
+    return zxerr_ok;
+}
+
+zxerr_t identity_getItemSection(int8_t displayIdx, item_section_t *section) {
+    if (displayIdx >= IDENTITY_ITEMS) {
+        return tx_getItemSection(displayIdx - IDENTITY_ITEMS, section);
+    }
+    *section = item_section_details;
+    return zxerr_ok;
+}
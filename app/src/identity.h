@@ -0,0 +1,47 @@
+/*******************************************************************************
+*   (c) 2026 Zondax AG
+*
+*  Licensed under the Apache License, Version 2.0 (the "License");
+*  you may not use this file except in compliance with the License.
+*  You may obtain a copy of the License at
+*
+*      http://www.apache.org/licenses/LICENSE-2.0
+*
+*  Unless required by applicable law or agreed to in writing, software
+*  distributed under the License is distributed on an "AS IS" BASIS,
+*  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+*  See the License for the specific language governing permissions and
+*  limitations under the License.
+********************************************************************************/
+#pragma once
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#include <stdint.h>
+#include "coin.h"
+#include "zxerror.h"
+#include "parser_common.h"
+
+// The review of a token signed with an identity key, m/888'/0'/n'. It is
+// the review of the token preceded by an item naming the identity
+
+/// Return the number of items in the identity challenge review
+zxerr_t identity_getNumItems(uint8_t *num_items);
+
+/// Gets an specific item from the identity challenge review (including paging)
+zxerr_t identity_getItem(int8_t displayIdx,
+                         char *outKey, uint16_t outKeyLen,
+                         char *outValue, uint16_t outValueLen,
+                         uint8_t pageIdx, uint8_t *pageCount);
+
+/// Gets the kind of value an specific item holds
+zxerr_t identity_getItemKind(int8_t displayIdx, item_kind_t *kind);
+
+/// Gets the review section of an specific item
+zxerr_t identity_getItemSection(int8_t displayIdx, item_section_t *section);
+
+#ifdef __cplusplus
+}
+#endif
@@ -0,0 +1,158 @@
+/*******************************************************************************
+*   (c) 2026 Zondax AG
+*
+*  Licensed under the Apache License, Version 2.0 (the "License");
+*  you may not use this file except in compliance with the License.
+*  You may obtain a copy of the License at
+*
+*      http://www.apache.org/licenses/LICENSE-2.0
+*
+*  Unless required by applicable law or agreed to in writing, software
+*  distributed under the License is distributed on an "AS IS" BASIS,
+*  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+*  See the License for the specific language governing permissions and
+*  limitations under the License.
+********************************************************************************/
+
+#pragma once
+
+// User-facing labels of the C side of the review, the parser
+// labels live in rust/parser/src/lang. Both are selected with APP_LANG
+
+#if defined(LANG_ES)
+
+#define LABEL_ADDRESS               "Direccion"
+#define LABEL_MAINNET_ADDRESS       "Direccion mainnet"
+#define LABEL_TESTNET_ADDRESS       "Direccion testnet"
+#define LABEL_BTC_ADDRESS           "Direccion BTC"
+#define LABEL_PATH                  "Ruta"
+#define LABEL_PATH_N                "Ruta %d/%d"
+#define LABEL_ORIGIN_PATH           "Ruta del origen"
+#define LABEL_SPONSOR_PATH          "Ruta del patrocinador"
+#define LABEL_FEE                   "Comision (uSTX)"
+#define LABEL_FEE_STX               "Comision (STX)"
+#define LABEL_NONCE                 "Nonce"
+
+#define LABEL_SECTION_DETAILS       "Detalles"
+#define LABEL_SECTION_AMOUNTS       "Montos"
+#define LABEL_SECTION_RECIPIENT     "Destinatario"
+#define LABEL_SECTION_FEES          "Comisiones"
+#define LABEL_SECTION_POST_CONDS    "Post condiciones"
+
+#define LABEL_HIGH_FEE              "Comision alta"
+#define LABEL_HIGH_FEE_MESSAGE      "La comision supera el limite configurado"
+#define LABEL_DENYLISTED            "Contrato bloqueado"
+#define LABEL_DENYLISTED_MESSAGE    "El contrato esta listado como malicioso"
+#define LABEL_NONSTANDARD_PATH      "Ruta no estandar"
+#define LABEL_NONSTANDARD_PATH_MESSAGE "Las wallets no usan esta ruta, los fondos enviados a ella pueden perderse"
+#define LABEL_CONTINUE              "Entiendo, continuar"
+#define LABEL_REJECT_TX             "Rechazar transaccion"
+#define LABEL_TX_SIGNED             "TRANSACCION\nFIRMADA"
+#define LABEL_TX_REJECTED           "Transaccion\nrechazada"
+#define LABEL_REVIEW_FAILED         "No se pudo mostrar\nla revision"
+#define LABEL_REVIEW_COMPLETE       "Revision completa"
+#define LABEL_REVIEW_AGAIN          "Revisar de nuevo"
+#define LABEL_SIGN_TX               "Firmar transaccion?"
+#define LABEL_HOLD_TO_SIGN          "Mantener para firmar"
+#define LABEL_SIGN_IDENTITY         "Firmar desafio de identidad"
+#define LABEL_SIGN_IDENTITY_CONFIRM "Firmar desafio de identidad?"
+#define LABEL_SIGN_SCHNORR          "Firma Schnorr"
+#define LABEL_SIGN_SCHNORR_CONFIRM  "Firmar mensaje Schnorr?"
+#define LABEL_SCHNORR_WARNING       "Solo para herramientas sBTC o Taproot de confianza"
+#define LABEL_SCHNORR_MESSAGE       "Mensaje"
+#define LABEL_VERIFY_ADDRESS        "Verificar direccion STX"
+#define LABEL_ADDRESS_VERIFIED      "DIRECCION\nVERIFICADA"
+#define LABEL_ADDRESS_REJECTED      "Direccion\nrechazada"
+#define LABEL_BATCH_TX              "Tx %d/%d"
+#define LABEL_BATCH_SUMMARY         "%s uSTX a %s, comision %s, nonce %s"
+#define LABEL_BATCH_SUMMARY_STX     "%s STX a %s, comision %s, nonce %s"
+#define LABEL_BATCH_TOTAL           "Total (uSTX)"
+#define LABEL_BATCH_TOTAL_STX       "Total (STX)"
+#define LABEL_MSG_BATCH_HASH        "Hash mensaje %d/%d"
+#define LABEL_RECEIVING_TX          "Recibiendo transaccion..."
+#define LABEL_UPDATE_SETTING_CONFIRM "Cambiar ajuste?"
+#define LABEL_SETTING               "Ajuste"
+#define LABEL_SETTING_VALUE         "Valor"
+#define LABEL_SETTING_CHANGE        "%s a %s"
+#define LABEL_SETTING_ON            "Activado"
+#define LABEL_SETTING_OFF           "Desactivado"
+#define LABEL_SETTING_RAW_HEX       "Items en hex"
+#define LABEL_SETTING_FEE_THRESHOLD "Limite de comision (uSTX)"
+#define LABEL_SETTING_HIDE_NONCE    "Ocultar nonce"
+#define LABEL_SETTING_HIDE_EMPTY_MEMO "Ocultar memos vacios"
+#define LABEL_SETTING_STRICT_NETWORK "Rechazar otra red"
+#define LABEL_SETTING_STRICT_DENYLIST "Rechazar contratos bloqueados"
+#define LABEL_SETTING_SCHNORR       "Firma Schnorr"
+#define LABEL_SETTING_STX_UNITS     "Montos en STX"
+#define LABEL_SETTING_SHORT_ADDRESSES "Direcciones cortas"
+#define LABEL_SETTING_GROUPED_ADDRESSES "Direcciones agrupadas"
+
+#else
+
+#define LABEL_ADDRESS               "Address"
+#define LABEL_MAINNET_ADDRESS       "Mainnet address"
+#define LABEL_TESTNET_ADDRESS       "Testnet address"
+#define LABEL_BTC_ADDRESS           "BTC address"
+#define LABEL_PATH                  "Path"
+#define LABEL_PATH_N                "Path %d/%d"
+#define LABEL_ORIGIN_PATH           "Origin path"
+#define LABEL_SPONSOR_PATH          "Sponsor path"
+#define LABEL_FEE                   "Fee (uSTX)"
+#define LABEL_FEE_STX               "Fee (STX)"
+#define LABEL_NONCE                 "Nonce"
+
+#define LABEL_SECTION_DETAILS       "Details"
+#define LABEL_SECTION_AMOUNTS       "Amounts"
+#define LABEL_SECTION_RECIPIENT     "Recipient"
+#define LABEL_SECTION_FEES          "Fees"
+#define LABEL_SECTION_POST_CONDS    "Post conditions"
+
+#define LABEL_HIGH_FEE              "High fee"
+#define LABEL_HIGH_FEE_MESSAGE      "The fee is above the configured threshold"
+#define LABEL_DENYLISTED            "Blocked contract"
+#define LABEL_DENYLISTED_MESSAGE    "The contract is listed as malicious"
+#define LABEL_NONSTANDARD_PATH      "Non-standard path"
+#define LABEL_NONSTANDARD_PATH_MESSAGE "Wallets do not use this path, funds sent to it can be stranded"
+#define LABEL_CONTINUE              "I understand, continue"
+#define LABEL_REJECT_TX             "Reject transaction"
+#define LABEL_TX_SIGNED             "TRANSACTION\nSIGNED"
+#define LABEL_TX_REJECTED           "Transaction\nrejected"
+#define LABEL_REVIEW_FAILED         "Review could not\nbe shown"
+#define LABEL_REVIEW_COMPLETE       "Review complete"
+#define LABEL_REVIEW_AGAIN          "Review again"
+#define LABEL_SIGN_TX               "Sign transaction?"
+#define LABEL_HOLD_TO_SIGN          "Hold to sign"
+#define LABEL_SIGN_IDENTITY         "Sign identity challenge"
+#define LABEL_SIGN_IDENTITY_CONFIRM "Sign identity challenge?"
+#define LABEL_SIGN_SCHNORR          "Schnorr signature"
+#define LABEL_SIGN_SCHNORR_CONFIRM  "Sign Schnorr message?"
+#define LABEL_SCHNORR_WARNING       "Only for sBTC or Taproot tools you trust"
+#define LABEL_SCHNORR_MESSAGE       "Message"
+#define LABEL_VERIFY_ADDRESS        "Verify STX address"
+#define LABEL_ADDRESS_VERIFIED      "ADDRESS\nVERIFIED"
+#define LABEL_ADDRESS_REJECTED      "Address\nrejected"
+#define LABEL_BATCH_TX              "Tx %d/%d"
+#define LABEL_BATCH_SUMMARY         "%s uSTX to %s, fee %s, nonce %s"
+#define LABEL_BATCH_SUMMARY_STX     "%s STX to %s, fee %s, nonce %s"
+#define LABEL_BATCH_TOTAL           "Total (uSTX)"
+#define LABEL_BATCH_TOTAL_STX       "Total (STX)"
+#define LABEL_MSG_BATCH_HASH        "Message Hash %d/%d"
+#define LABEL_RECEIVING_TX          "Receiving transaction..."
+#define LABEL_UPDATE_SETTING_CONFIRM "Update setting?"
+#define LABEL_SETTING               "Setting"
+#define LABEL_SETTING_VALUE         "Value"
+#define LABEL_SETTING_CHANGE        "%s to %s"
+#define LABEL_SETTING_ON            "On"
+#define LABEL_SETTING_OFF           "Off"
+#define LABEL_SETTING_RAW_HEX       "Raw hex items"
+#define LABEL_SETTING_FEE_THRESHOLD "High fee threshold (uSTX)"
+#define LABEL_SETTING_HIDE_NONCE    "Hide nonce"
+#define LABEL_SETTING_HIDE_EMPTY_MEMO "Hide empty memos"
+#define LABEL_SETTING_STRICT_NETWORK "Refuse other network"
+#define LABEL_SETTING_STRICT_DENYLIST "Refuse blocked contracts"
+#define LABEL_SETTING_SCHNORR       "Schnorr signing"
+#define LABEL_SETTING_STX_UNITS     "Amounts in STX"
+#define LABEL_SETTING_SHORT_ADDRESSES "Short addresses"
+#define LABEL_SETTING_GROUPED_ADDRESSES "Grouped addresses"
+
+#endif
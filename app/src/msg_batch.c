@@ -0,0 +1,162 @@
+/*******************************************************************************
+*   (c) 2026 Zondax AG
+*
+*  Licensed under the Apache License, Version 2.0 (the "License");
+*  you may not use this file except in compliance with the License.
+*  You may obtain a copy of the License at
+*
+*      http://www.apache.org/licenses/LICENSE-2.0
+*
+*  Unless required by applicable law or agreed to in writing, software
+*  distributed under the License is distributed on an "AS IS" BASIS,
+*  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+*  See the License for the specific language governing permissions and
+*  limitations under the License.
+********************************************************************************/
+
+#include <stdio.h>
+#include "msg_batch.h"
+#include "labels.h"
+#include "crypto.h"
+#include "tx.h"
+#include "zxmacros.h"
+#include "zxformat.h"
+#include "rslib.h"
+
+// name, version and chain-id
+#define MSG_BATCH_DOMAIN_ITEMS      3
+
+typedef struct {
+    uint32_t path[HDPATH_LEN_DEFAULT];
+    uint8_t header[MSG_BATCH_HEADER_MAX_LEN];
+    uint16_t header_len;
+    // hashed as soon as the message is parsed, so the
+    // parser buffer can receive the next one
+    uint8_t hashes[MSG_BATCH_MAX_MESSAGES][MSG_BATCH_HASH_LEN];
+    uint8_t num_messages;
+    uint8_t next_message;
+    bool approved;
+} msg_batch_t;
+
+static msg_batch_t msg_batch;
+
+void msg_batch_reset() {
+    rs_zeroize(&msg_batch, sizeof(msg_batch));
+}
+
+uint8_t msg_batch_num_messages() {
+    return msg_batch.num_messages;
+}
+
+const uint8_t *msg_batch_header(uint16_t *headerLen) {
+    *headerLen = msg_batch.header_len;
+    return msg_batch.num_messages == 0 ? NULL : msg_batch.header;
+}
+
+zxerr_t msg_batch_add(const uint8_t *header, uint16_t headerLen, const uint8_t *hash) {
+    if (msg_batch.approved) {
+        return zxerr_unknown;
+    }
+    if (msg_batch.num_messages >= MSG_BATCH_MAX_MESSAGES) {
+        return zxerr_buffer_too_small;
+    }
+    if (hdPath_len != HDPATH_LEN_DEFAULT) {
+        return zxerr_invalid_crypto_settings;
+    }
+
+    if (msg_batch.num_messages == 0) {
+        if (headerLen == 0 || headerLen > sizeof(msg_batch.header)) {
+            return zxerr_buffer_too_small;
+        }
+        MEMCPY(msg_batch.path, hdPath, sizeof(msg_batch.path));
+        MEMCPY(msg_batch.header, header, headerLen);
+        msg_batch.header_len = headerLen;
+    } else {
+        // the domain is reviewed once, for every message
+        if (MEMCMP(msg_batch.path, hdPath, sizeof(msg_batch.path)) != 0) {
+            return zxerr_invalid_crypto_settings;
+        }
+        if (headerLen != msg_batch.header_len || MEMCMP(msg_batch.header, header, headerLen) != 0) {
+            return zxerr_unknown;
+        }
+    }
+
+    MEMCPY(msg_batch.hashes[msg_batch.num_messages], hash, MSG_BATCH_HASH_LEN);
+    msg_batch.num_messages++;
+    return zxerr_ok;
+}
+
+zxerr_t msg_batch_approve() {
+    if (msg_batch.num_messages == 0) {
+        return zxerr_no_data;
+    }
+
+    msg_batch.next_message = 0;
+    msg_batch.approved = true;
+    return zxerr_ok;
+}
+
+bool msg_batch_pending() {
+    return msg_batch.approved && msg_batch.next_message < msg_batch.num_messages;
+}
+
+zxerr_t msg_batch_next(uint8_t *buffer, uint16_t bufferLen, uint16_t *replyLen) {
+    *replyLen = 0;
+
+    if (!msg_batch_pending() || bufferLen < MSG_BATCH_HASH_LEN) {
+        return zxerr_no_data;
+    }
+
+    MEMCPY(hdPath, msg_batch.path, sizeof(msg_batch.path));
+    hdPath_len = HDPATH_LEN_DEFAULT;
+
+    const uint8_t *hash = msg_batch.hashes[msg_batch.next_message];
+    const zxerr_t err = crypto_sign(buffer, bufferLen, hash, MSG_BATCH_HASH_LEN, replyLen);
+    if (err != zxerr_ok) {
+        msg_batch_reset();
+        *replyLen = 0;
+        return err;
+    }
+    // the signed hash goes where a transaction has its post_sighash
+    MEMCPY(buffer, hash, MSG_BATCH_HASH_LEN);
+
+    msg_batch.next_message++;
+    if (msg_batch.next_message >= msg_batch.num_messages) {
+        // nothing else to sign
+        msg_batch_reset();
+    }
+
+    return zxerr_ok;
+}
+
+zxerr_t msg_batch_getNumItems(uint8_t *num_items) {
+    // the domain and every message
+    *num_items = msg_batch.num_messages == 0 ? 0 : MSG_BATCH_DOMAIN_ITEMS + msg_batch.num_messages;
+    return zxerr_ok;
+}
+
+zxerr_t msg_batch_getItem(int8_t displayIdx,
+                          char *outKey, uint16_t outKeyLen,
+                          char *outValue, uint16_t outValueLen,
+                          uint8_t pageIdx, uint8_t *pageCount) {
+    if (displayIdx < 0 || msg_batch.num_messages == 0) {
+        return zxerr_no_data;
+    }
+
+    if (displayIdx < MSG_BATCH_DOMAIN_ITEMS) {
+        return tx_getItem(displayIdx, outKey, outKeyLen, outValue, outValueLen, pageIdx, pageCount);
+    }
+
+    const uint8_t msgIdx = displayIdx - MSG_BATCH_DOMAIN_ITEMS;
+    if (msgIdx < msg_batch.num_messages) {
+        char buffer[2 * MSG_BATCH_HASH_LEN + 1];
+        snprintf(outKey, outKeyLen, LABEL_MSG_BATCH_HASH, msgIdx + 1, msg_batch.num_messages);
+        if (array_to_hexstr(buffer, sizeof(buffer), msg_batch.hashes[msgIdx], MSG_BATCH_HASH_LEN) == 0) {
+            return zxerr_encoding_failed;
+        }
+        pageString(outValue, outValueLen, buffer, pageIdx, pageCount);
+        return zxerr_ok;
+    }
+
+    return zxerr_no_data;
+}
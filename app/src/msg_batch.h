@@ -0,0 +1,77 @@
+/*******************************************************************************
+*   (c) 2026 Zondax AG
+*
+*  Licensed under the Apache License, Version 2.0 (the "License");
+*  you may not use this file except in compliance with the License.
+*  You may obtain a copy of the License at
+*
+*      http://www.apache.org/licenses/LICENSE-2.0
+*
+*  Unless required by applicable law or agreed to in writing, software
+*  distributed under the License is distributed on an "AS IS" BASIS,
+*  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+*  See the License for the specific language governing permissions and
+*  limitations under the License.
+********************************************************************************/
+
+
+#pragma once
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#include <stdint.h>
+#include <stdbool.h>
+#include "coin.h"
+#include "zxerror.h"
+
+// Maximum number of structured messages that can be signed
+// with a single approval
+#if defined(TARGET_NANOS)
+#define MSG_BATCH_MAX_MESSAGES      4
+#else
+#define MSG_BATCH_MAX_MESSAGES      8
+#endif
+
+#define MSG_BATCH_HASH_LEN          32
+// The SIP018 prefix and the domain tuple shared by every message
+#define MSG_BATCH_HEADER_MAX_LEN    128
+
+/// Drops the batch being loaded or signed
+void msg_batch_reset();
+
+/// Returns the number of messages in the batch
+uint8_t msg_batch_num_messages();
+
+/// Returns the SIP018 prefix and domain of the first message, every
+/// message that follows is put after them. NULL if there is none yet
+const uint8_t *msg_batch_header(uint16_t *headerLen);
+
+/// Adds a structured message signed with hdPath to the batch. Every message
+/// must be signed with the same path and share the header of the first one
+zxerr_t msg_batch_add(const uint8_t *header, uint16_t headerLen, const uint8_t *hash);
+
+/// Marks the whole batch as approved by the user
+zxerr_t msg_batch_approve();
+
+/// Returns true if the batch was approved and some signatures were not returned yet
+bool msg_batch_pending();
+
+/// Signs the next message of an approved batch, the reply has the same
+/// format as a single structured message signature
+zxerr_t msg_batch_next(uint8_t *buffer, uint16_t bufferLen, uint16_t *replyLen);
+
+/// Return the number of items in the batch review
+zxerr_t msg_batch_getNumItems(uint8_t *num_items);
+
+/// Gets an specific item from the batch review (including paging). The
+/// domain is shown from the last message parsed, which shares it
+zxerr_t msg_batch_getItem(int8_t displayIdx,
+                          char *outKey, uint16_t outKeyLen,
+                          char *outValue, uint16_t outValueLen,
+                          uint8_t pageIdx, uint8_t *pageCount);
+
+#ifdef __cplusplus
+}
+#endif
@@ -20,6 +20,11 @@
 #include "parser.h"
 #include "coin.h"
 #include "rslib.h"
+#include "settings.h"
+#include "denylist.h"
+#include "function_abi.h"
+#include "token_info.h"
+#include "sighash_stream.h"
 
 static zxerr_t parser_allocate();
 static zxerr_t parser_deallocate();
@@ -27,8 +32,9 @@ static zxerr_t parser_deallocate();
 parser_tx_t parser_state;
 // This buffer will store parser_state.
 // Its size corresponds to ParsedObj (Rust struct)
-// Maximum required size: 212 bytes
-#define PARSER_BUFFER_SIZE 256
+// which includes a 181-byte scratch buffer for formatting items
+// and the offsets of post conditions and contract call arguments
+#define PARSER_BUFFER_SIZE 416
 static uint8_t parser_buffer[PARSER_BUFFER_SIZE];
 
 parser_error_t parser_parse(parser_context_t *ctx, const uint8_t *data, size_t dataLen) {
@@ -48,18 +54,145 @@ parser_error_t parser_parse(parser_context_t *ctx, const uint8_t *data, size_t d
     return err;
 }
 
-parser_error_t parser_validate(const parser_context_t *ctx) {
+parser_error_t parser_check_signer() {
+    // Only transactions have a signer to check
+    if (parser_get_transaction_type() != Transaction) {
+        return parser_ok;
+    }
 
     uint8_t pubKeyHash[CX_RIPEMD160_SIZE];
+    if (!crypto_extractPublicKeyHash(pubKeyHash, CX_RIPEMD160_SIZE)) {
+        return parser_crypto_error;
+    }
 
-    crypto_extractPublicKeyHash(pubKeyHash, CX_RIPEMD160_SIZE);
+    CHECK_PARSER_ERR(_check_pubkey_hash(&parser_state, pubKeyHash, CX_RIPEMD160_SIZE))
+    if (_is_multisig(&parser_state) == 0) {
+        return parser_ok;
+    }
 
-    // Checks if the data being processed is a transaction and if so, verify this device is allowed to sign this transaction
-    if ( parser_get_transaction_type() == Transaction ) {
-        if (_check_pubkey_hash(&parser_state, pubKeyHash, CX_RIPEMD160_SIZE) != parser_ok)
-        return parser_invalid_auth_type;
+    // the hash of a multisig origin is that of all its keys, the key is
+    // matched to the auth fields or to the template of the account
+    uint8_t pubKey[PK_LEN_SECP256K1];
+    if (crypto_extractPublicKey(hdPath, hdPath_len, pubKey, sizeof(pubKey)) != zxerr_ok) {
+        return parser_crypto_error;
+    }
+    const uint8_t *account = crypto_multisigAccount();
+    return _check_multisig_signer(&parser_state, pubKey, sizeof(pubKey),
+                                  account, account == NULL ? 0 : CX_RIPEMD160_SIZE);
+}
+
+parser_error_t parser_check_sponsor(const uint32_t *path, uint32_t path_len) {
+    if (parser_get_transaction_type() != Transaction) {
+        return parser_unexpected_type;
+    }
+
+    uint8_t pubKeyHash[CX_RIPEMD160_SIZE];
+    if (!crypto_pathPublicKeyHash(path, path_len, pubKeyHash, CX_RIPEMD160_SIZE)) {
+        return parser_crypto_error;
+    }
+
+    return _check_sponsor_pubkey_hash(&parser_state, pubKeyHash, CX_RIPEMD160_SIZE);
+}
+
+parser_error_t parser_check_path() {
+    if (parser_get_transaction_type() != Transaction) {
+        return parser_ok;
+    }
+
+    // A non-standard path is only a warning at the start of the review
+    return _check_path(&parser_state, hdPath, (uint8_t) hdPath_len);
+}
+
+parser_error_t parser_check_network() {
+    if (parser_get_transaction_type() != Transaction) {
+        return parser_ok;
+    }
+
+    const parser_error_t err = _check_network(&parser_state, isTestnet());
+    if (err == parser_network_mismatch && !app_settings_strict_network()) {
+        // Signed anyway, the review starts with a warning instead
+        return parser_ok;
+    }
+    return err;
+}
+
+parser_error_t parser_check_denylist() {
+    if (parser_get_transaction_type() != Transaction) {
+        return parser_ok;
+    }
+
+    uint16_t entriesLen = 0;
+    const uint8_t *entries = denylist_entries(&entriesLen);
+    const parser_error_t err = _check_denylist(&parser_state, entries, entriesLen);
+    if (err == parser_denylisted_contract && !app_settings_strict_denylist()) {
+        // Signed anyway, the review starts with a warning instead
+        return parser_ok;
+    }
+    return err;
+}
+
+parser_error_t parser_check_code() {
+    if (parser_get_transaction_type() != Transaction) {
+        return parser_ok;
+    }
+
+    uint8_t scan[RS_CODE_SCAN_LEN];
+    const uint16_t scanLen = sighash_stream_code_scan(scan, sizeof(scan));
+    return _check_code(&parser_state, scanLen > 0 ? scan : NULL, scanLen);
+}
+
+parser_error_t parser_check_abi() {
+    if (parser_get_transaction_type() != Transaction) {
+        return parser_ok;
+    }
+
+    uint16_t abiLen = 0;
+    const uint8_t *abi = function_abi_data(&abiLen);
+    return _check_abi(&parser_state, abi, abiLen);
+}
+
+parser_error_t parser_check_token_info() {
+    if (parser_get_transaction_type() != Transaction) {
+        return parser_ok;
     }
 
+    uint16_t infoLen = 0;
+    const uint8_t *info = token_info_data(&infoLen);
+    return _check_token_info(&parser_state, info, infoLen);
+}
+
+parser_error_t parser_check_reward_address() {
+    if (parser_get_transaction_type() != Transaction) {
+        return parser_ok;
+    }
+
+    uint32_t path[HDPATH_LEN_MAX];
+    uint8_t path_len = 0;
+    CHECK_PARSER_ERR(_reward_key_path(&parser_state, hdPath, (uint8_t) hdPath_len,
+                                      path, &path_len, HDPATH_LEN_MAX))
+    if (path_len == 0) {
+        // rewards paid to a script, or a signing key whose account is not known
+        return _check_reward_address(&parser_state, NULL);
+    }
+
+    uint8_t keyHash[CX_RIPEMD160_SIZE];
+    if (!crypto_pathPublicKeyHash(path, path_len, keyHash, sizeof(keyHash))) {
+        return parser_unexpected_error;
+    }
+    return _check_reward_address(&parser_state, keyHash);
+}
+
+parser_error_t parser_validate(const parser_context_t *ctx) {
+    // Checks if the data being processed is a transaction and if so, verify this device is allowed to sign this transaction
+    CHECK_PARSER_ERR(parser_check_signer())
+    CHECK_PARSER_ERR(parser_check_path())
+    CHECK_PARSER_ERR(parser_check_network())
+    CHECK_PARSER_ERR(parser_check_denylist())
+    CHECK_PARSER_ERR(parser_check_abi())
+    CHECK_PARSER_ERR(parser_check_token_info())
+    CHECK_PARSER_ERR(parser_check_code())
+    CHECK_PARSER_ERR(parser_check_reward_address())
+
 
     uint8_t numItems = 0;
     CHECK_PARSER_ERR(parser_getNumItems(ctx, &numItems));
@@ -102,10 +235,28 @@ parser_error_t parser_getItem(const parser_context_t *ctx,
     return parser_ok;
 }
 
+parser_error_t parser_getItemKind(uint8_t displayIdx, item_kind_t *kind) {
+    uint8_t raw_kind = 0;
+    CHECK_PARSER_ERR(_getItemKind(&parser_state, displayIdx, &raw_kind))
+    *kind = (item_kind_t) raw_kind;
+    return parser_ok;
+}
+
+parser_error_t parser_getItemSection(uint8_t displayIdx, item_section_t *section) {
+    uint8_t raw_section = 0;
+    CHECK_PARSER_ERR(_getItemSection(&parser_state, displayIdx, &raw_section))
+    *section = (item_section_t) raw_section;
+    return parser_ok;
+}
+
 parser_error_t parser_tx_auth_flag(uint8_t *flag) {
     return _auth_flag(&parser_state, flag);
 }
 
+uint16_t parser_role_auth_data(uint8_t role, uint8_t *buf, uint16_t bufLen) {
+    return _role_auth_data(&parser_state, role, buf, bufLen);
+}
+
 uint8_t parser_tx_fee(uint8_t *fee, uint16_t fee_len) {
     return _fee_bytes(&parser_state, fee, fee_len);
 }
@@ -114,11 +265,75 @@ uint8_t parser_tx_nonce(uint8_t *nonce, uint16_t nonce_len) {
     return _nonce_bytes(&parser_state, nonce, nonce_len);
 }
 
+parser_error_t parser_tx_payload_kind(payload_kind_t *kind) {
+    uint8_t raw_kind = 0;
+    CHECK_PARSER_ERR(_payload_kind(&parser_state, &raw_kind))
+    *kind = (payload_kind_t) raw_kind;
+    return parser_ok;
+}
+
+parser_error_t parser_tx_recipient_hash160(uint8_t *out, uint16_t outLen) {
+    return _recipient_hash160(&parser_state, out, outLen);
+}
+
+uint16_t parser_tx_recipient_address(char *out, uint16_t outLen) {
+    return _recipient_address(&parser_state, (uint8_t *) out, outLen);
+}
+
+parser_error_t parser_tx_amount(uint64_t *amount) {
+    uint32_t amount_hi = 0;
+    uint32_t amount_lo = 0;
+    CHECK_PARSER_ERR(_amount(&parser_state, &amount_hi, &amount_lo))
+    *amount = ((uint64_t) amount_hi << 32) | amount_lo;
+    return parser_ok;
+}
+
+parser_error_t parser_tx_num_post_conditions(uint8_t *num) {
+    return _num_post_conditions(&parser_state, num);
+}
+
+parser_error_t parser_tx_multisig_threshold(uint16_t *threshold) {
+    return _multisig_threshold(&parser_state, threshold);
+}
+
 uint16_t parser_presig_hash_data(uint8_t *buf, uint16_t bufLen) {
     return _presig_hash_data(&parser_state, buf, bufLen);
 }
 
-uint16_t parser_last_transaction_block(uint8_t **last_block) {
+uint32_t parser_stack_watermark(uint8_t entry) {
+    return _stack_watermark(entry);
+}
+
+void parser_stack_watermark_reset() {
+    _stack_watermark_reset();
+}
+
+parser_error_t parser_parse_diagnostic(const uint8_t *data, uint16_t dataLen,
+                                       parse_section_t *section, uint32_t *offset) {
+    uint8_t raw_section = 0;
+    *offset = 0;
+    const parser_error_t err = (parser_error_t) _parse_diagnostic(data, dataLen, &raw_section, offset);
+    *section = (parse_section_t) raw_section;
+    return err;
+}
+
+parser_error_t parser_initial_sighash(uint8_t *hash, uint16_t hashLen) {
+    return (parser_error_t) _initial_sighash(&parser_state, hash, hashLen);
+}
+
+parser_error_t parser_multisig_presig_hash(uint8_t *hash, uint16_t hashLen) {
+    return _multisig_presig_hash(&parser_state, hash, hashLen);
+}
+
+parser_error_t parser_multisig_auth_field_index(uint32_t *index) {
+    uint8_t pubKey[PK_LEN_SECP256K1];
+    if (crypto_extractPublicKey(hdPath, hdPath_len, pubKey, sizeof(pubKey)) != zxerr_ok) {
+        return parser_crypto_error;
+    }
+    return _multisig_auth_field_index(&parser_state, pubKey, sizeof(pubKey), index);
+}
+
+uint16_t parser_last_transaction_block(const uint8_t **last_block) {
     return _last_block_ptr(&parser_state, last_block);
 }
 
@@ -126,7 +341,35 @@ int8_t parser_is_transaction_multisig() {
     return _is_multisig(&parser_state);
 }
 
-uint16_t parser_previous_signer_data(uint8_t **data) {
+int8_t parser_is_order_independent() {
+    return _is_order_independent(&parser_state);
+}
+
+int8_t parser_is_sponsored() {
+    return _is_sponsored(&parser_state);
+}
+
+uint16_t parser_sponsor_address(char *out, uint16_t outLen) {
+    return _sponsor_address(&parser_state, (uint8_t *) out, outLen);
+}
+
+uint8_t parser_sponsor_fee(uint8_t *fee, uint16_t fee_len) {
+    return _sponsor_fee_bytes(&parser_state, fee, fee_len);
+}
+
+int8_t parser_is_high_fee() {
+    return _is_high_fee(&parser_state);
+}
+
+int8_t parser_is_denylisted() {
+    return _is_denylisted(&parser_state);
+}
+
+int8_t parser_is_nonstandard_path() {
+    return _is_nonstandard_path(&parser_state);
+}
+
+uint16_t parser_previous_signer_data(const uint8_t **data) {
     return _previous_signer_data(&parser_state, data);
 }
 
@@ -137,6 +380,10 @@ zxerr_t parser_structured_msg_hash(uint8_t *out, uint16_t out_len){
     return zxerr_ok;
 }
 
+uint16_t parser_structured_msg_header_len() {
+    return _structured_msg_header_len(&parser_state);
+}
+
 zxerr_t parser_allocate() {
     if (parser_state.len % 4 != 0) {
         parser_state.len += parser_state.len % 4;
@@ -258,6 +505,26 @@ const char *parser_getErrorDescription(parser_error_t err) {
             return "Recursion limit reached while parsing";
         case parser_invalid_token_transfer_principal:
             return "Invalid token transfer principal";
+        case parser_crypto_error:
+            return "Could not derive the device key";
+        case parser_signer_mismatch:
+            return "Signer does not match the device key";
+        case parser_network_mismatch:
+            return "Transaction network does not match the key path";
+        case parser_denylisted_contract:
+            return "Contract is listed as malicious";
+        case parser_limit_exceeded:
+            return "Transaction exceeds the device limits";
+        case parser_invalid_path:
+            return "Derivation path not allowed";
+        case parser_trailing_bytes:
+            return "Unexpected data after the transaction";
+        case parser_too_many_signatures:
+            return "More signatures than the account requires";
+        case parser_fee_too_high:
+            return "Fee above the STX supply, check its unit";
+        case parser_invalid_auth_challenge:
+            return "Invalid authentication challenge";
         default:
             return "Unrecognized error code";
     }
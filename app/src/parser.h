@@ -36,6 +36,43 @@ parser_error_t parser_parse(parser_context_t *ctx,
 //// verifies tx fields
 parser_error_t parser_validate(const parser_context_t *ctx);
 
+// Checks that the key derived from hdPath is the origin or the sponsor
+// of the transaction. The hash of a multisig origin can not be checked from
+// one key, the review warns about a key its auth fields do not list unless
+// the template of the account was checked with INS_GET_MULTISIG_SLOT
+parser_error_t parser_check_signer();
+
+// Checks that the key derived from path is the sponsor of the transaction,
+// for a device signing it as both the origin, hdPath, and the sponsor
+parser_error_t parser_check_sponsor(const uint32_t *path, uint32_t path_len);
+
+// Checks hdPath against the paths wallets use, a non-standard
+// path is shown as a warning at the start of the review
+parser_error_t parser_check_path();
+
+// Checks the transaction network against the one of hdPath, a mismatch
+// is refused in strict mode and shown as a warning otherwise
+parser_error_t parser_check_network();
+
+// Checks the contract called against the provisioned denylist, a listed
+// contract is refused in strict mode and shown as a warning otherwise
+parser_error_t parser_check_denylist();
+
+// Scans the code of a contract deploy, the review shows what it can do with assets
+parser_error_t parser_check_code();
+
+// Names the arguments of the contract call after the provisioned function
+// signature, if it is the one called
+parser_error_t parser_check_abi();
+
+// Shows the token amounts of the post conditions after the provisioned
+// token metadata, sats and BTC for tokens pegged to bitcoin
+parser_error_t parser_check_token_info();
+
+// Checks whether the rewards of a stack-stx call go to the Bitcoin key of the
+// signing account, the review tells whether they are paid to this device
+parser_error_t parser_check_reward_address();
+
 //// returns the number of items in the current parsing context
 parser_error_t parser_getNumItems(const parser_context_t *ctx, uint8_t *num_items);
 
@@ -46,31 +83,100 @@ parser_error_t parser_getItem(const parser_context_t *ctx,
                               char *outValue, uint16_t outValueLen,
                               uint8_t pageIdx, uint8_t *pageCount);
 
+// retrieves the kind of value an item holds
+parser_error_t parser_getItemKind(uint8_t displayIdx, item_kind_t *kind);
+
+// retrieves the review section an item belongs to
+parser_error_t parser_getItemSection(uint8_t displayIdx, item_section_t *section);
 
 /// Gets the transaction authorization type
 parser_error_t parser_tx_auth_flag(uint8_t *flag);
 
+/// Gets the auth flag, fee and nonce signed by the origin, role 0, or the sponsor, role 1
+uint16_t parser_role_auth_data(uint8_t role, uint8_t *buf, uint16_t bufLen);
+
 /// Gets the origin fee as bytes
 uint8_t parser_tx_fee(uint8_t *fee, uint16_t fee_len);
 
 /// Gets the origin nonce as bytes
 uint8_t parser_tx_nonce(uint8_t *nonce, uint16_t nonce_len);
 
+/// Gets the kind of the transaction payload
+parser_error_t parser_tx_payload_kind(payload_kind_t *kind);
+
+/// Gets the hash160 of the token transfer recipient
+parser_error_t parser_tx_recipient_hash160(uint8_t *out, uint16_t outLen);
+
+/// Writes the null terminated address of the token transfer recipient, returns its length
+uint16_t parser_tx_recipient_address(char *out, uint16_t outLen);
+
+/// Gets the token transfer amount in uSTX
+parser_error_t parser_tx_amount(uint64_t *amount);
+
+/// Gets the number of post conditions
+parser_error_t parser_tx_num_post_conditions(uint8_t *num);
+
+/// Gets the signatures required by a multisig origin
+parser_error_t parser_tx_multisig_threshold(uint16_t *threshold);
+
 // Writes in buf the authorization fields that are zeroize according
 // to the documentation. returns the amount of bytes written
 // the passes_in buffer is the second block for hashing
 uint16_t parser_presig_hash_data(uint8_t *buf, uint16_t bufLen);
 
+// Returns the most stack used by a parser entry point since the last reset
+uint32_t parser_stack_watermark(uint8_t entry);
+
+void parser_stack_watermark_reset();
+
+// Returns the error a transaction fails to parse with, or parser_ok,
+// and the section and byte offset where it was found
+parser_error_t parser_parse_diagnostic(const uint8_t *data, uint16_t dataLen,
+                                       parse_section_t *section, uint32_t *offset);
+
+// Computes the initial sighash of the parsed transaction
+parser_error_t parser_initial_sighash(uint8_t *hash, uint16_t hashLen);
+
+// Chains hash, the pre_sig_hash of the first signer of a multisig origin,
+// through the signatures of the previous signers
+parser_error_t parser_multisig_presig_hash(uint8_t *hash, uint16_t hashLen);
+
+// The index of the auth field of the multisig origin the signature of the
+// key of hdPath goes in
+parser_error_t parser_multisig_auth_field_index(uint32_t *index);
+
 // When signing the full transaction, The transaction hash has to be done in blocks.
 // this function returns a pointer to the last transaction block and its lenght
-uint16_t parser_last_transaction_block(uint8_t ** last_tx_block);
+uint16_t parser_last_transaction_block(const uint8_t **last_tx_block);
 
 // Returns 1 if the transaction is multisig, 0 otherwise, returns -1 in case of error
 int8_t parser_is_transaction_multisig();
 
+// Returns 1 if the origin is an order independent multisig account, 0 otherwise
+int8_t parser_is_order_independent();
+
+// Returns 1 if the transaction is sponsored, 0 otherwise
+int8_t parser_is_sponsored();
+
+// Writes the null terminated sponsor address, returns its length or 0
+// if the transaction is not sponsored
+uint16_t parser_sponsor_address(char *out, uint16_t outLen);
+
+// Gets the sponsor fee as bytes, returns 0 if the transaction is not sponsored
+uint8_t parser_sponsor_fee(uint8_t *fee, uint16_t fee_len);
+
+// Returns 1 if the signer fee is above the configured threshold, 0 otherwise
+int8_t parser_is_high_fee();
+
+// Returns 1 if the contract called is in the provisioned denylist, 0 otherwise
+int8_t parser_is_denylisted();
+
+// Returns 1 if the transaction is signed with a non-standard path, 0 otherwise
+int8_t parser_is_nonstandard_path();
+
 // Gets a pointer to the previous signer signature, post_sig_hash and pubkey type
 // that is the last part of a lultisig transaction buffer
-uint16_t parser_previous_signer_data(uint8_t **data);
+uint16_t parser_previous_signer_data(const uint8_t **data);
 
 void parser_resetState();
 
@@ -78,6 +184,8 @@ transaction_type_t parser_get_transaction_type();
 
 zxerr_t parser_structured_msg_hash(uint8_t *out, uint16_t out_len);
 
+uint16_t parser_structured_msg_header_len();
+
 #ifdef __cplusplus
 }
 #endif
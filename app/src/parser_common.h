@@ -77,6 +77,15 @@ typedef enum {
     parser_crypto_error,
     parser_invalid_token_transfer_principal,
     parser_recursion_limit,
+    parser_signer_mismatch,
+    parser_network_mismatch,
+    parser_denylisted_contract,
+    parser_limit_exceeded,
+    parser_invalid_path,
+    parser_trailing_bytes,
+    parser_too_many_signatures,
+    parser_fee_too_high,
+    parser_invalid_auth_challenge,
 } parser_error_t;
 
 typedef struct {
@@ -90,9 +99,46 @@ typedef enum _TransactionType {
     Message,
     Jwt,
     StructuredMsg,
+    AuthChallenge,
     Invalid
 } transaction_type_t;
 
+// Payload of a transaction, keep in sync with TransactionPayloadId in rust
+typedef enum {
+    payload_kind_token_transfer = 0,
+    payload_kind_smart_contract,
+    payload_kind_contract_call,
+    payload_kind_versioned_smart_contract = 6,
+} payload_kind_t;
+
+// What an item value holds, keep in sync with ItemKind in rust
+typedef enum {
+    item_kind_text = 0,
+    item_kind_amount,
+    item_kind_address,
+    item_kind_contract_name,
+    item_kind_warning,
+    item_kind_raw_hex,
+    item_kind_memo,
+} item_kind_t;
+
+// Review section of an item, keep in sync with ItemSection in rust
+typedef enum {
+    item_section_details = 0,
+    item_section_amounts,
+    item_section_recipient,
+    item_section_fees,
+    item_section_post_conditions,
+} item_section_t;
+
+// Transaction section a parse error was found in, keep in sync with ParseSection in rust
+typedef enum {
+    parse_section_header = 0,
+    parse_section_auth,
+    parse_section_post_conditions,
+    parse_section_payload,
+} parse_section_t;
+
 
 #ifdef __cplusplus
 }
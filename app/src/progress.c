@@ -0,0 +1,80 @@
+/*******************************************************************************
+*   (c) 2026 Zondax AG
+*
+*  Licensed under the Apache License, Version 2.0 (the "License");
+*  you may not use this file except in compliance with the License.
+*  You may obtain a copy of the License at
+*
+*      http://www.apache.org/licenses/LICENSE-2.0
+*
+*  Unless required by applicable law or agreed to in writing, software
+*  distributed under the License is distributed on an "AS IS" BASIS,
+*  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+*  See the License for the specific language governing permissions and
+*  limitations under the License.
+********************************************************************************/
+
+#include "progress.h"
+#include <stdio.h>
+#include "view.h"
+#include "labels.h"
+#include "zxmacros.h"
+
+#if defined(TARGET_STAX) || defined(TARGET_FLEX)
+#include "nbgl_use_case.h"
+#endif
+
+// Redrawing takes longer than hashing a chunk, so it is done in steps
+#define PROGRESS_STEP       10
+#define PROGRESS_NOT_SHOWN  0xFF
+
+typedef struct {
+    uint32_t total_len;
+    uint8_t shown_percent;
+    char text[40];
+} progress_t;
+
+static progress_t progress = {.total_len = 0, .shown_percent = PROGRESS_NOT_SHOWN};
+
+void progress_reset(uint32_t totalLen) {
+    progress_finish();
+    progress.total_len = totalLen;
+}
+
+void progress_update(uint32_t receivedLen) {
+    if (progress.total_len == 0) {
+        return;
+    }
+
+    // the host might have declared less than it sends, the last
+    // chunk hides the screen so it never reads as complete
+    uint64_t percent = ((uint64_t) receivedLen * 100) / progress.total_len;
+    if (percent > 99) {
+        percent = 99;
+    }
+    percent -= percent % PROGRESS_STEP;
+    if (progress.shown_percent != PROGRESS_NOT_SHOWN && percent <= progress.shown_percent) {
+        return;
+    }
+    progress.shown_percent = (uint8_t) percent;
+
+#if defined(TARGET_STAX) || defined(TARGET_FLEX)
+    snprintf(progress.text, sizeof(progress.text), "%s %d%%", LABEL_RECEIVING_TX, progress.shown_percent);
+    nbgl_useCaseSpinner(progress.text);
+#else
+    snprintf(progress.text, sizeof(progress.text), "%d%%", progress.shown_percent);
+    view_message_show((char *) LABEL_RECEIVING_TX, progress.text);
+#endif
+}
+
+uint32_t progress_declared_length() {
+    return progress.total_len;
+}
+
+void progress_finish() {
+    if (progress.shown_percent != PROGRESS_NOT_SHOWN) {
+        view_idle_show(0, NULL);
+    }
+    progress.total_len = 0;
+    progress.shown_percent = PROGRESS_NOT_SHOWN;
+}
@@ -0,0 +1,43 @@
+/*******************************************************************************
+*   (c) 2026 Zondax AG
+*
+*  Licensed under the Apache License, Version 2.0 (the "License");
+*  you may not use this file except in compliance with the License.
+*  You may obtain a copy of the License at
+*
+*      http://www.apache.org/licenses/LICENSE-2.0
+*
+*  Unless required by applicable law or agreed to in writing, software
+*  distributed under the License is distributed on an "AS IS" BASIS,
+*  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+*  See the License for the specific language governing permissions and
+*  limitations under the License.
+********************************************************************************/
+
+#pragma once
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#include <stdint.h>
+
+/// Starts tracking the reception of a transaction the host declared
+/// totalLen bytes long, 0 if it did not and no progress is shown
+void progress_reset(uint32_t totalLen);
+
+/// Shows how much of the transaction was received, the screen is
+/// only redrawn when another PROGRESS_STEP percent was received
+void progress_update(uint32_t receivedLen);
+
+/// Returns the length the host declared for the transaction being
+/// received, 0 if it did not
+uint32_t progress_declared_length();
+
+/// Hides the progress screen, once the whole transaction was
+/// received or its reception failed
+void progress_finish();
+
+#ifdef __cplusplus
+}
+#endif
@@ -0,0 +1,429 @@
+/*******************************************************************************
+*   (c) 2026 Zondax AG
+*
+*  Licensed under the Apache License, Version 2.0 (the "License");
+*  you may not use this file except in compliance with the License.
+*  You may obtain a copy of the License at
+*
+*      http://www.apache.org/licenses/LICENSE-2.0
+*
+*  Unless required by applicable law or agreed to in writing, software
+*  distributed under the License is distributed on an "AS IS" BASIS,
+*  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+*  See the License for the specific language governing permissions and
+*  limitations under the License.
+********************************************************************************/
+
+#include "review_nbgl.h"
+
+#if defined(TARGET_STAX) || defined(TARGET_FLEX)
+
+#include <stdbool.h>
+#include <stdio.h>
+#include <string.h>
+#include "nbgl_use_case.h"
+#include "view.h"
+#include "zxmacros.h"
+#include "labels.h"
+#include "settings.h"
+
+// post conditions take up to 4 items each, 16 of them plus
+// the origin and payload items fit here
+#define REVIEW_MAX_ITEMS        96
+// NBGL counts the pairs of a list with an uint8_t
+#define REVIEW_MAX_PAIRS        255
+#define REVIEW_NUM_SECTIONS     5
+// NBGL asks for the pairs of one page at a time
+#define REVIEW_PAIRS_PER_PAGE   4
+#define REVIEW_KEY_LEN          40
+// values are read in chunks and concatenated, so that long values
+// are not split across several pairs. Longer ones are split in
+// parts of REVIEW_MAX_CHUNKS chunks, each one shown as a pair
+#define REVIEW_CHUNK_LEN        100
+#define REVIEW_MAX_CHUNKS       4
+#define REVIEW_VALUE_LEN        (REVIEW_MAX_CHUNKS * (REVIEW_CHUNK_LEN - 1) + 1)
+// a c32 address followed by a contract name
+#define REVIEW_ADDRESS_LEN      (2 * (REVIEW_CHUNK_LEN - 1) + 1)
+
+#define REVIEW_SIGN_TOKEN       1
+#define REVIEW_AGAIN_TOKEN      2
+
+static const char *const section_titles[REVIEW_NUM_SECTIONS] = {
+    LABEL_SECTION_DETAILS,
+    LABEL_SECTION_AMOUNTS,
+    LABEL_SECTION_RECIPIENT,
+    LABEL_SECTION_FEES,
+    LABEL_SECTION_POST_CONDS,
+};
+
+// the order sections are shown in
+static const item_section_t section_order[REVIEW_NUM_SECTIONS] = {
+    item_section_amounts,
+    item_section_recipient,
+    item_section_details,
+    item_section_post_conditions,
+    item_section_fees,
+};
+
+typedef struct {
+    review_nbgl_get_item_t get_item;
+    review_nbgl_get_kind_t get_kind;
+    review_nbgl_callback_t accept;
+    review_nbgl_callback_t reject;
+
+    // the display index and part shown by each pair, sorted by section
+    uint8_t pair_item[REVIEW_MAX_PAIRS];
+    uint8_t pair_part[REVIEW_MAX_PAIRS];
+    uint8_t num_pairs;
+    uint8_t item_pages[REVIEW_MAX_ITEMS];
+    // set if an item could not be read while paging, the review
+    // can not be accepted then
+    bool failed;
+    // the addresses of the items are written as the QR code encodes them,
+    // so that they look like the address being verified
+    bool plain_addresses;
+
+    // a centered title plus a tag/value list per non empty section,
+    // the review again page and the final hold to sign page
+    nbgl_content_t contents[2 * REVIEW_NUM_SECTIONS + 2];
+    nbgl_genericContents_t generic;
+
+    nbgl_contentTagValue_t pairs[REVIEW_PAIRS_PER_PAGE];
+    // lets address values be shown as a QR code
+    nbgl_contentValueExt_t pairs_ext[REVIEW_PAIRS_PER_PAGE];
+    // the address of those values as the QR code encodes it
+    char addresses[REVIEW_PAIRS_PER_PAGE][REVIEW_ADDRESS_LEN];
+    char keys[REVIEW_PAIRS_PER_PAGE][REVIEW_KEY_LEN];
+    char values[REVIEW_PAIRS_PER_PAGE][REVIEW_VALUE_LEN];
+
+    // extra pairs shown next to an address being verified
+    nbgl_contentTagValueList_t address_pairs;
+} review_nbgl_t;
+
+static review_nbgl_t review;
+
+static void review_idle() {
+    view_idle_show(0, NULL);
+}
+
+static zxerr_t review_get_item(uint8_t displayIdx,
+                               char *key, uint16_t keyLen,
+                               char *value, uint16_t valueLen,
+                               uint8_t pageIdx, uint8_t *pageCount) {
+    settings_set_plain_addresses(review.plain_addresses);
+    const zxerr_t err = review.get_item((int8_t) displayIdx,
+                                        key, keyLen,
+                                        value, valueLen,
+                                        pageIdx, pageCount);
+    settings_set_plain_addresses(false);
+    return err;
+}
+
+static uint8_t review_item_parts(uint8_t displayIdx) {
+    const uint8_t pages = review.item_pages[displayIdx];
+    return pages == 0 ? 1 : (pages + REVIEW_MAX_CHUNKS - 1) / REVIEW_MAX_CHUNKS;
+}
+
+// reads every page of an item and adds a pair for each of its parts
+static bool review_add_item(uint8_t displayIdx) {
+    char *key = review.keys[0];
+    char *value = review.values[0];
+    uint8_t pageCount = 1;
+
+    for (uint8_t pageIdx = 0; pageIdx < pageCount; pageIdx++) {
+        if (review_get_item(displayIdx,
+                            key, REVIEW_KEY_LEN,
+                            value, REVIEW_CHUNK_LEN,
+                            pageIdx, &pageCount) != zxerr_ok) {
+            return false;
+        }
+    }
+    review.item_pages[displayIdx] = pageCount;
+
+    const uint8_t parts = review_item_parts(displayIdx);
+    if (parts > REVIEW_MAX_PAIRS - review.num_pairs) {
+        return false;
+    }
+    for (uint8_t part = 0; part < parts; part++) {
+        review.pair_item[review.num_pairs] = displayIdx;
+        review.pair_part[review.num_pairs] = part;
+        review.num_pairs++;
+    }
+    return true;
+}
+
+// reads an address item in full into out, the value shown
+// can be abbreviated or have its characters grouped
+static bool review_read_address(uint8_t displayIdx, char *out, uint16_t outLen) {
+    char key[REVIEW_KEY_LEN];
+    uint8_t pageCount = 1;
+    uint16_t offset = 0;
+    bool ok = true;
+
+    MEMZERO(out, outLen);
+    settings_set_plain_addresses(true);
+    for (uint8_t pageIdx = 0; ok && pageIdx < pageCount; pageIdx++) {
+        ok = outLen - offset >= REVIEW_CHUNK_LEN &&
+             review.get_item((int8_t) displayIdx,
+                             key, sizeof(key),
+                             out + offset, REVIEW_CHUNK_LEN,
+                             pageIdx, &pageCount) == zxerr_ok;
+        if (ok) {
+            offset += strlen(out + offset);
+        }
+    }
+    settings_set_plain_addresses(false);
+    return ok;
+}
+
+static nbgl_contentTagValue_t *review_get_pair(uint8_t pairIndex) {
+    const uint8_t slot = pairIndex % REVIEW_PAIRS_PER_PAGE;
+    nbgl_contentTagValue_t *pair = &review.pairs[slot];
+    char *key = review.keys[slot];
+    char *value = review.values[slot];
+
+    MEMZERO(pair, sizeof(*pair));
+    MEMZERO(key, REVIEW_KEY_LEN);
+    MEMZERO(value, REVIEW_VALUE_LEN);
+    pair->item = key;
+    pair->value = value;
+
+    if (pairIndex >= review.num_pairs) {
+        return pair;
+    }
+
+    const uint8_t displayIdx = review.pair_item[pairIndex];
+    const uint8_t part = review.pair_part[pairIndex];
+    const uint8_t parts = review_item_parts(displayIdx);
+    const uint8_t pages = review.item_pages[displayIdx];
+    // an item without pages is still read once
+    const uint16_t first = part * REVIEW_MAX_CHUNKS;
+    uint16_t last = first + REVIEW_MAX_CHUNKS;
+    if (last > pages) {
+        last = pages > first ? pages : first + 1;
+    }
+    uint16_t offset = 0;
+
+    for (uint16_t pageIdx = first; pageIdx < last; pageIdx++) {
+        uint8_t pageCount = 0;
+        if (review_get_item(displayIdx,
+                            key, REVIEW_KEY_LEN,
+                            value + offset, REVIEW_CHUNK_LEN,
+                            (uint8_t) pageIdx, &pageCount) != zxerr_ok ||
+            pageCount != pages) {
+            review.failed = true;
+            break;
+        }
+        offset += strlen(value + offset);
+    }
+
+    if (parts > 1) {
+        const size_t keyLen = strlen(key);
+        snprintf(key + keyLen, REVIEW_KEY_LEN - keyLen, " (%d/%d)", part + 1, parts);
+    }
+
+    item_kind_t kind = item_kind_text;
+    if (parts == 1 &&
+        review.get_kind != NULL &&
+        review.get_kind((int8_t) displayIdx, &kind) == zxerr_ok &&
+        kind == item_kind_address) {
+        char *address = review.addresses[slot];
+        if (!review_read_address(displayIdx, address, REVIEW_ADDRESS_LEN)) {
+            review.failed = true;
+            return pair;
+        }
+        nbgl_contentValueExt_t *ext = &review.pairs_ext[slot];
+        MEMZERO(ext, sizeof(*ext));
+        ext->aliasType = QR_CODE_ALIAS;
+        ext->fullValue = address;
+        ext->title = key;
+        pair->aliasValue = 1;
+        pair->extension = ext;
+    }
+
+    return pair;
+}
+
+static void review_reject() {
+    review.reject();
+    nbgl_useCaseStatus(LABEL_TX_REJECTED, false, review_idle);
+}
+
+// a review that could not be shown in full is rejected
+static void review_fail() {
+    review.reject();
+    nbgl_useCaseStatus(LABEL_REVIEW_FAILED, false, review_idle);
+}
+
+static void review_action(int token, uint8_t index, int page) {
+    UNUSED(index);
+    UNUSED(page);
+
+    if (token == REVIEW_AGAIN_TOKEN) {
+        // start over from the first page, the APDU is still pending
+        nbgl_useCaseGenericReview(&review.generic, LABEL_REJECT_TX, review_reject);
+        return;
+    }
+
+    if (token != REVIEW_SIGN_TOKEN) {
+        return;
+    }
+
+    if (review.failed) {
+        review_fail();
+        return;
+    }
+
+    review.accept();
+    nbgl_useCaseStatus(LABEL_TX_SIGNED, true, review_idle);
+}
+
+void review_nbgl_show(review_nbgl_get_item_t get_item,
+                      review_nbgl_num_items_t get_num_items,
+                      review_nbgl_get_section_t get_section,
+                      review_nbgl_get_kind_t get_kind,
+                      const char *sign_text,
+                      review_nbgl_callback_t accept,
+                      review_nbgl_callback_t reject) {
+    MEMZERO(&review, sizeof(review));
+    review.get_item = get_item;
+    review.get_kind = get_kind;
+    review.accept = accept;
+    review.reject = reject;
+
+    uint8_t num_items = 0;
+    if (get_num_items(&num_items) != zxerr_ok || num_items > REVIEW_MAX_ITEMS) {
+        review_fail();
+        return;
+    }
+
+    uint8_t num_contents = 0;
+
+    for (uint8_t s = 0; s < REVIEW_NUM_SECTIONS; s++) {
+        const uint8_t start = review.num_pairs;
+
+        for (uint8_t idx = 0; idx < num_items; idx++) {
+            item_section_t section = item_section_details;
+            if (get_section((int8_t) idx, &section) != zxerr_ok) {
+                section = item_section_details;
+            }
+            if (section == section_order[s] && !review_add_item(idx)) {
+                review_fail();
+                return;
+            }
+        }
+
+        if (review.num_pairs == start) {
+            continue;
+        }
+
+        nbgl_content_t *title = &review.contents[num_contents++];
+        title->type = CENTERED_INFO;
+        title->content.centeredInfo.text1 = section_titles[section_order[s]];
+        title->content.centeredInfo.style = LARGE_CASE_INFO;
+
+        nbgl_content_t *list = &review.contents[num_contents++];
+        list->type = TAG_VALUE_LIST;
+        list->content.tagValueList.callback = review_get_pair;
+        list->content.tagValueList.startIndex = start;
+        list->content.tagValueList.nbPairs = review.num_pairs - start;
+        list->content.tagValueList.wrapping = true;
+    }
+
+    nbgl_content_t *again = &review.contents[num_contents++];
+    again->type = INFO_BUTTON;
+    again->content.infoButton.text = LABEL_REVIEW_COMPLETE;
+    again->content.infoButton.icon = &C_Review_64px;
+    again->content.infoButton.buttonText = LABEL_REVIEW_AGAIN;
+    again->content.infoButton.buttonToken = REVIEW_AGAIN_TOKEN;
+    again->contentActionCallback = review_action;
+
+    nbgl_content_t *confirm = &review.contents[num_contents++];
+    confirm->type = INFO_LONG_PRESS;
+    confirm->content.infoLongPress.text = sign_text;
+    confirm->content.infoLongPress.longPressText = LABEL_HOLD_TO_SIGN;
+    confirm->content.infoLongPress.longPressToken = REVIEW_SIGN_TOKEN;
+    confirm->contentActionCallback = review_action;
+
+    review.generic.callbackCallNeeded = false;
+    review.generic.contentsList = review.contents;
+    review.generic.nbContents = num_contents;
+
+    nbgl_useCaseGenericReview(&review.generic, LABEL_REJECT_TX, review_reject);
+}
+
+static void review_warning_choice(bool confirm) {
+    if (confirm) {
+        review.accept();
+    } else {
+        review.reject();
+        nbgl_useCaseStatus(LABEL_TX_REJECTED, false, review_idle);
+    }
+}
+
+void review_nbgl_warning_show(const char *title,
+                              const char *message,
+                              review_nbgl_callback_t proceed,
+                              review_nbgl_callback_t reject) {
+    MEMZERO(&review, sizeof(review));
+    review.accept = proceed;
+    review.reject = reject;
+
+    nbgl_useCaseChoice(&C_Warning_64px,
+                       title,
+                       message,
+                       LABEL_CONTINUE,
+                       LABEL_REJECT_TX,
+                       review_warning_choice);
+}
+
+static void review_address_choice(bool confirm) {
+    if (confirm && review.failed) {
+        review_fail();
+    } else if (confirm) {
+        review.accept();
+        nbgl_useCaseStatus(LABEL_ADDRESS_VERIFIED, true, review_idle);
+    } else {
+        review.reject();
+        nbgl_useCaseStatus(LABEL_ADDRESS_REJECTED, false, review_idle);
+    }
+}
+
+void review_nbgl_address_show(const char *address,
+                              review_nbgl_get_item_t get_item,
+                              review_nbgl_num_items_t get_num_items,
+                              review_nbgl_callback_t accept,
+                              review_nbgl_callback_t reject) {
+    MEMZERO(&review, sizeof(review));
+    review.get_item = get_item;
+    review.accept = accept;
+    review.reject = reject;
+    review.plain_addresses = true;
+
+    uint8_t num_items = 0;
+    if (get_num_items(&num_items) != zxerr_ok || num_items > REVIEW_MAX_ITEMS) {
+        review_fail();
+        return;
+    }
+
+    // the first item is the address itself, which
+    // nbgl_useCaseAddressReview shows along with its QR code
+    for (uint8_t idx = 1; idx < num_items; idx++) {
+        if (!review_add_item(idx)) {
+            review_fail();
+            return;
+        }
+    }
+    review.address_pairs.callback = review_get_pair;
+    review.address_pairs.startIndex = 0;
+    review.address_pairs.nbPairs = review.num_pairs;
+
+    nbgl_useCaseAddressReview(address,
+                              review.address_pairs.nbPairs > 0 ? &review.address_pairs : NULL,
+                              NULL,
+                              LABEL_VERIFY_ADDRESS,
+                              NULL,
+                              review_address_choice);
+}
+
+#endif
@@ -0,0 +1,72 @@
+/*******************************************************************************
+*   (c) 2026 Zondax AG
+*
+*  Licensed under the Apache License, Version 2.0 (the "License");
+*  you may not use this file except in compliance with the License.
+*  You may obtain a copy of the License at
+*
+*      http://www.apache.org/licenses/LICENSE-2.0
+*
+*  Unless required by applicable law or agreed to in writing, software
+*  distributed under the License is distributed on an "AS IS" BASIS,
+*  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+*  See the License for the specific language governing permissions and
+*  limitations under the License.
+********************************************************************************/
+
+#pragma once
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#include <stdint.h>
+#include "parser_common.h"
+#include "zxerror.h"
+
+#if defined(TARGET_STAX) || defined(TARGET_FLEX)
+
+typedef zxerr_t (*review_nbgl_num_items_t)(uint8_t *num_items);
+typedef zxerr_t (*review_nbgl_get_item_t)(int8_t displayIdx,
+                                          char *outKey, uint16_t outKeyLen,
+                                          char *outValue, uint16_t outValueLen,
+                                          uint8_t pageIdx, uint8_t *pageCount);
+typedef zxerr_t (*review_nbgl_get_section_t)(int8_t displayIdx, item_section_t *section);
+typedef zxerr_t (*review_nbgl_get_kind_t)(int8_t displayIdx, item_kind_t *kind);
+typedef void (*review_nbgl_callback_t)();
+
+/// Shows the items as tag/value pairs grouped by section, followed by
+/// sign_text on the hold to sign page. accept/reject are called once the
+/// user holds to sign or rejects. Address items can also be shown as a QR code.
+/// Values too long for one pair are split in several, and the review is
+/// rejected with an error if any item can not be read
+void review_nbgl_show(review_nbgl_get_item_t get_item,
+                      review_nbgl_num_items_t get_num_items,
+                      review_nbgl_get_section_t get_section,
+                      review_nbgl_get_kind_t get_kind,
+                      const char *sign_text,
+                      review_nbgl_callback_t accept,
+                      review_nbgl_callback_t reject);
+
+/// Shows a warning that has to be acknowledged before continuing,
+/// proceed is called if the user acknowledges it and reject otherwise
+void review_nbgl_warning_show(const char *title,
+                              const char *message,
+                              review_nbgl_callback_t proceed,
+                              review_nbgl_callback_t reject);
+
+/// Shows an address to be verified along with its QR code,
+/// any item after the first one is shown as an extra pair, with addresses
+/// in full like the one being verified. Like review_nbgl_show, it is
+/// rejected if any item can not be read
+void review_nbgl_address_show(const char *address,
+                              review_nbgl_get_item_t get_item,
+                              review_nbgl_num_items_t get_num_items,
+                              review_nbgl_callback_t accept,
+                              review_nbgl_callback_t reject);
+
+#endif
+
+#ifdef __cplusplus
+}
+#endif
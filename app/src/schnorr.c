@@ -0,0 +1,106 @@
+/*******************************************************************************
+*   (c) 2026 Zondax AG
+*
+*  Licensed under the Apache License, Version 2.0 (the "License");
+*  you may not use this file except in compliance with the License.
+*  You may obtain a copy of the License at
+*
+*      http://www.apache.org/licenses/LICENSE-2.0
+*
+*  Unless required by applicable law or agreed to in writing, software
+*  distributed under the License is distributed on an "AS IS" BASIS,
+*  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+*  See the License for the specific language governing permissions and
+*  limitations under the License.
+********************************************************************************/
+
+#include <stdio.h>
+#include "schnorr.h"
+#include "labels.h"
+#include "crypto.h"
+#include "zxmacros.h"
+#include "zxformat.h"
+
+#define SCHNORR_ITEMS 3
+
+typedef struct {
+    bool valid;
+    uint8_t message[SCHNORR_MESSAGE_LEN];
+} schnorr_review_t;
+
+static schnorr_review_t review;
+
+void schnorr_reset() {
+    MEMZERO(&review, sizeof(review));
+}
+
+zxerr_t schnorr_load(const uint8_t *message, uint16_t messageLen) {
+    schnorr_reset();
+    if (messageLen != SCHNORR_MESSAGE_LEN) {
+        return zxerr_out_of_bounds;
+    }
+    MEMCPY(review.message, message, SCHNORR_MESSAGE_LEN);
+    review.valid = true;
+    return zxerr_ok;
+}
+
+zxerr_t schnorr_getNumItems(uint8_t *num_items) {
+    *num_items = review.valid ? SCHNORR_ITEMS : 0;
+    return zxerr_ok;
+}
+
+zxerr_t schnorr_getItem(int8_t displayIdx,
+                        char *outKey, uint16_t outKeyLen,
+                        char *outValue, uint16_t outValueLen,
+                        uint8_t pageIdx, uint8_t *pageCount) {
+    if (!review.valid) {
+        return zxerr_no_data;
+    }
+
+    char buffer[100];
+    switch (displayIdx) {
+        case 0:
+            snprintf(outKey, outKeyLen, LABEL_SIGN_SCHNORR);
+            pageString(outValue, outValueLen, LABEL_SCHNORR_WARNING, pageIdx, pageCount);
+            return zxerr_ok;
+        case 1:
+            snprintf(outKey, outKeyLen, LABEL_SCHNORR_MESSAGE);
+            if (array_to_hexstr(buffer, sizeof(buffer), review.message, SCHNORR_MESSAGE_LEN) == 0) {
+                return zxerr_encoding_failed;
+            }
+            pageString(outValue, outValueLen, buffer, pageIdx, pageCount);
+            return zxerr_ok;
+        case 2:
+            snprintf(outKey, outKeyLen, LABEL_PATH);
+            bip32_to_str(buffer, sizeof(buffer), hdPath, hdPath_len);
+            pageString(outValue, outValueLen, buffer, pageIdx, pageCount);
+            return zxerr_ok;
+        default:
+            return zxerr_no_data;
+    }
+}
+
+zxerr_t schnorr_getItemKind(int8_t displayIdx, item_kind_t *kind) {
+    *kind = displayIdx == 0 ? item_kind_warning : item_kind_text;
+    return zxerr_ok;
+}
+
+zxerr_t schnorr_getItemSection(__Z_UNUSED int8_t displayIdx, item_section_t *section) {
+    *section = item_section_details;
+    return zxerr_ok;
+}
+
+zxerr_t schnorr_sign(uint8_t *buffer, uint16_t bufferLen, uint16_t *replyLen) {
+    *replyLen = 0;
+    if (!review.valid) {
+        return zxerr_no_data;
+    }
+
+    const zxerr_t err = crypto_sign_schnorr(review.message, SCHNORR_MESSAGE_LEN, buffer, bufferLen);
+    schnorr_reset();
+    if (err != zxerr_ok) {
+        return err;
+    }
+    *replyLen = SCHNORR_SIGNATURE_LEN;
+    return zxerr_ok;
+}
@@ -0,0 +1,58 @@
+/*******************************************************************************
+*   (c) 2026 Zondax AG
+*
+*  Licensed under the Apache License, Version 2.0 (the "License");
+*  you may not use this file except in compliance with the License.
+*  You may obtain a copy of the License at
+*
+*      http://www.apache.org/licenses/LICENSE-2.0
+*
+*  Unless required by applicable law or agreed to in writing, software
+*  distributed under the License is distributed on an "AS IS" BASIS,
+*  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+*  See the License for the specific language governing permissions and
+*  limitations under the License.
+********************************************************************************/
+
+#pragma once
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#include <stdint.h>
+#include "coin.h"
+#include "zxerror.h"
+#include "parser_common.h"
+
+// The review of a BIP-340 signature: the message is an opaque 32 bytes
+// hash, so it is shown in hex along with the path of the key
+
+/// Forgets the message being reviewed
+void schnorr_reset();
+
+/// Loads the message to sign with the key of hdPath
+zxerr_t schnorr_load(const uint8_t *message, uint16_t messageLen);
+
+/// Return the number of items in the Schnorr review
+zxerr_t schnorr_getNumItems(uint8_t *num_items);
+
+/// Gets an specific item from the Schnorr review (including paging)
+zxerr_t schnorr_getItem(int8_t displayIdx,
+                        char *outKey, uint16_t outKeyLen,
+                        char *outValue, uint16_t outValueLen,
+                        uint8_t pageIdx, uint8_t *pageCount);
+
+/// Gets the kind of value an specific item holds
+zxerr_t schnorr_getItemKind(int8_t displayIdx, item_kind_t *kind);
+
+/// Gets the review section of an specific item
+zxerr_t schnorr_getItemSection(int8_t displayIdx, item_section_t *section);
+
+/// Signs the reviewed message, writing the 64 bytes signature,
+/// and forgets it
+zxerr_t schnorr_sign(uint8_t *buffer, uint16_t bufferLen, uint16_t *replyLen);
+
+#ifdef __cplusplus
+}
+#endif
@@ -0,0 +1,118 @@
+/*******************************************************************************
+*   (c) 2026 Zondax AG
+*
+*  Licensed under the Apache License, Version 2.0 (the "License");
+*  you may not use this file except in compliance with the License.
+*  You may obtain a copy of the License at
+*
+*      http://www.apache.org/licenses/LICENSE-2.0
+*
+*  Unless required by applicable law or agreed to in writing, software
+*  distributed under the License is distributed on an "AS IS" BASIS,
+*  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+*  See the License for the specific language governing permissions and
+*  limitations under the License.
+********************************************************************************/
+
+#include "settings.h"
+#include "app_mode.h"
+#include "rslib.h"
+#include "zxmacros.h"
+
+// The settings are a versioned blob read and written by the rust parser,
+// see rust/parser/src/settings.rs. Flash is zeroed on install, which reads
+// as the defaults
+typedef struct {
+    uint8_t blob[RS_SETTINGS_BLOB_LEN];
+} settings_t;
+
+#if defined(TARGET_NANOS) || defined(TARGET_NANOX) || defined(TARGET_NANOS2) || defined(TARGET_STAX) || defined(TARGET_FLEX)
+settings_t NV_CONST N_settings_impl __attribute__ ((aligned(64)));
+#define N_settings (*(NV_VOLATILE settings_t *)PIC(&N_settings_impl))
+#endif
+
+// Set while the value of a QR code, or the pairs shown next to it, are
+// read. It is kept in RAM only
+static bool plain_addresses;
+
+uint64_t settings_get(setting_id_e id) {
+    uint64_t value = 0;
+    if (rs_settings_get((const uint8_t *) N_settings.blob, RS_SETTINGS_BLOB_LEN, id, &value) != parser_ok) {
+        return 0;
+    }
+    return value;
+}
+
+zxerr_t settings_set(setting_id_e id, uint64_t value) {
+    uint8_t blob[RS_SETTINGS_BLOB_LEN];
+    if (rs_settings_set((const uint8_t *) N_settings.blob, sizeof(blob), id, value, blob, sizeof(blob)) != sizeof(blob)) {
+        return zxerr_out_of_bounds;
+    }
+    MEMCPY_NV((void *) N_settings.blob, blob, sizeof(blob));
+    return zxerr_ok;
+}
+
+uint16_t settings_fill(uint8_t *buffer, uint16_t bufferLen) {
+    if (buffer == NULL || bufferLen < 1) {
+        return 0;
+    }
+    // expert mode is kept by the SDK, it is reported along as some
+    // settings only take effect with it
+    buffer[0] = app_mode_expert() ? 1 : 0;
+    const uint16_t len = rs_settings_entries((const uint8_t *) N_settings.blob, RS_SETTINGS_BLOB_LEN,
+                                             buffer + 1, bufferLen - 1);
+    return len == 0 ? 0 : len + 1;
+}
+
+bool settings_is_valid(setting_id_e id, uint64_t value) {
+    uint8_t blob[RS_SETTINGS_BLOB_LEN];
+    return rs_settings_set((const uint8_t *) N_settings.blob, sizeof(blob), id, value, blob, sizeof(blob)) == sizeof(blob);
+}
+
+uint8_t app_settings_raw_hex() {
+    return app_mode_expert() && settings_get(setting_raw_hex);
+}
+
+uint64_t app_settings_fee_threshold() {
+    return settings_get(setting_fee_threshold);
+}
+
+uint8_t app_settings_hide_nonce() {
+    return !app_mode_expert() && settings_get(setting_hide_nonce);
+}
+
+uint8_t app_settings_hide_empty_memo() {
+    return !app_mode_expert() && settings_get(setting_hide_empty_memo);
+}
+
+uint8_t app_settings_stx_units() {
+    return settings_get(setting_stx_units) != 0;
+}
+
+uint8_t settings_amount_decimals() {
+    return app_settings_stx_units() ? SETTINGS_STX_DECIMALS : 0;
+}
+
+uint8_t app_settings_short_addresses() {
+    return !plain_addresses && !app_mode_expert() && settings_get(setting_short_addresses);
+}
+
+uint8_t app_settings_grouped_addresses() {
+    return !plain_addresses && settings_get(setting_grouped_addresses) != 0;
+}
+
+void settings_set_plain_addresses(bool plain) {
+    plain_addresses = plain;
+}
+
+uint8_t app_settings_strict_network() {
+    return settings_get(setting_strict_network) != 0;
+}
+
+uint8_t app_settings_strict_denylist() {
+    return settings_get(setting_strict_denylist) != 0;
+}
+
+uint8_t app_settings_schnorr() {
+    return app_mode_expert() && settings_get(setting_schnorr);
+}
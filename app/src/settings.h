@@ -0,0 +1,113 @@
+/*******************************************************************************
+*   (c) 2026 Zondax AG
+*
+*  Licensed under the Apache License, Version 2.0 (the "License");
+*  you may not use this file except in compliance with the License.
+*  You may obtain a copy of the License at
+*
+*      http://www.apache.org/licenses/LICENSE-2.0
+*
+*  Unless required by applicable law or agreed to in writing, software
+*  distributed under the License is distributed on an "AS IS" BASIS,
+*  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+*  See the License for the specific language governing permissions and
+*  limitations under the License.
+********************************************************************************/
+
+#pragma once
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#include <stdint.h>
+#include <stdbool.h>
+#include "zxerror.h"
+
+// The settings kept in flash, the SettingId enum of rust/parser/src/settings.rs.
+// All but the fee threshold are toggles whose value is 0 or 1
+typedef enum {
+    setting_raw_hex = 0,
+    setting_fee_threshold = 1,
+    setting_hide_nonce = 2,
+    setting_hide_empty_memo = 3,
+    setting_strict_network = 4,
+    setting_strict_denylist = 5,
+    setting_schnorr = 6,
+    setting_stx_units = 7,
+    setting_short_addresses = 8,
+    setting_grouped_addresses = 9,
+} setting_id_e;
+
+/// Returns the stored value of a setting, whatever the expert mode
+uint64_t settings_get(setting_id_e id);
+
+/// Stores the value of a setting in flash, toggles take 0 or 1
+zxerr_t settings_set(setting_id_e id, uint64_t value);
+
+/// Fills buffer with whether expert mode is on, the number of settings
+/// and the id and big endian value of each. Returns 0 on error
+uint16_t settings_fill(uint8_t *buffer, uint16_t bufferLen);
+
+/// Whether value can be stored for the setting id
+bool settings_is_valid(setting_id_e id, uint64_t value);
+
+/// Returns 1 when raw hex items should be appended to the transaction
+/// review, they are only shown in expert mode. Called from the rust parser
+uint8_t app_settings_raw_hex();
+
+// 1 STX, DEFAULT_FEE_THRESHOLD of rust/parser/src/settings.rs
+#define SETTINGS_DEFAULT_FEE_THRESHOLD      1000000
+
+/// Returns the fee in uSTX above which the fee item is shown
+/// as a warning. Called from the rust parser
+uint64_t app_settings_fee_threshold();
+
+/// Returns 1 when the nonce item should be left out of the review,
+/// it is always shown in expert mode. Called from the rust parser
+uint8_t app_settings_hide_nonce();
+
+/// Returns 1 when all-zero memos should be left out of the review,
+/// they are always shown in expert mode. Called from the rust parser
+uint8_t app_settings_hide_empty_memo();
+
+// Decimals of STX amounts shown in STX, they are shown in uSTX by default
+#define SETTINGS_STX_DECIMALS               6
+
+/// Returns 1 when amounts and fees are shown in STX instead
+/// of uSTX. Called from the rust parser
+uint8_t app_settings_stx_units();
+
+/// Returns the decimals amounts in uSTX are formatted with,
+/// SETTINGS_STX_DECIMALS when they are shown in STX
+uint8_t settings_amount_decimals();
+
+/// Returns 1 when addresses are shown abbreviated in the review, their
+/// head and tail, they are always shown in full in expert mode.
+/// Called from the rust parser
+uint8_t app_settings_short_addresses();
+
+/// Returns 1 when addresses shown in full are split in groups of 4
+/// characters, the default. Called from the rust parser
+uint8_t app_settings_grouped_addresses();
+
+/// While set, addresses are written in full and without groups whatever
+/// the settings, as the value of a QR code has to be
+void settings_set_plain_addresses(bool plain);
+
+/// Returns 1 when transactions for the other network than the
+/// derivation path are refused, by default they are signed
+/// after a warning
+uint8_t app_settings_strict_network();
+
+/// Returns 1 when calls to denylisted contracts are refused,
+/// by default they are signed after a warning
+uint8_t app_settings_strict_denylist();
+
+/// Returns 1 when BIP-340 Schnorr signing is allowed, it is only
+/// offered in expert mode
+uint8_t app_settings_schnorr();
+
+#ifdef __cplusplus
+}
+#endif
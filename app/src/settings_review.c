@@ -0,0 +1,141 @@
+/*******************************************************************************
+*   (c) 2026 Zondax AG
+*
+*  Licensed under the Apache License, Version 2.0 (the "License");
+*  you may not use this file except in compliance with the License.
+*  You may obtain a copy of the License at
+*
+*      http://www.apache.org/licenses/LICENSE-2.0
+*
+*  Unless required by applicable law or agreed to in writing, software
+*  distributed under the License is distributed on an "AS IS" BASIS,
+*  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+*  See the License for the specific language governing permissions and
+*  limitations under the License.
+********************************************************************************/
+
+#include <stdio.h>
+#include "settings_review.h"
+#include "labels.h"
+#include "zxmacros.h"
+#include "zxformat.h"
+
+#define SETTINGS_REVIEW_ITEMS 2
+
+typedef struct {
+    bool valid;
+    setting_id_e id;
+    uint64_t value;
+} settings_review_t;
+
+static settings_review_t review;
+
+static const char *setting_name(setting_id_e id) {
+    switch (id) {
+        case setting_raw_hex:
+            return LABEL_SETTING_RAW_HEX;
+        case setting_fee_threshold:
+            return LABEL_SETTING_FEE_THRESHOLD;
+        case setting_hide_nonce:
+            return LABEL_SETTING_HIDE_NONCE;
+        case setting_hide_empty_memo:
+            return LABEL_SETTING_HIDE_EMPTY_MEMO;
+        case setting_strict_network:
+            return LABEL_SETTING_STRICT_NETWORK;
+        case setting_strict_denylist:
+            return LABEL_SETTING_STRICT_DENYLIST;
+        case setting_schnorr:
+            return LABEL_SETTING_SCHNORR;
+        case setting_stx_units:
+            return LABEL_SETTING_STX_UNITS;
+        case setting_short_addresses:
+            return LABEL_SETTING_SHORT_ADDRESSES;
+        case setting_grouped_addresses:
+            return LABEL_SETTING_GROUPED_ADDRESSES;
+        default:
+            return NULL;
+    }
+}
+
+static zxerr_t print_value(char *out, uint16_t outLen, setting_id_e id, uint64_t value) {
+    if (id == setting_fee_threshold) {
+        return fpuint64_to_str(out, outLen, value, 0) == 0 ? zxerr_encoding_failed : zxerr_ok;
+    }
+    snprintf(out, outLen, "%s", value ? LABEL_SETTING_ON : LABEL_SETTING_OFF);
+    return zxerr_ok;
+}
+
+void settings_review_reset() {
+    MEMZERO(&review, sizeof(review));
+}
+
+zxerr_t settings_review_load(setting_id_e id, uint64_t value) {
+    settings_review_reset();
+    if (setting_name(id) == NULL || !settings_is_valid(id, value)) {
+        return zxerr_out_of_bounds;
+    }
+    // a zero threshold restores the default, show the value stored
+    if (id == setting_fee_threshold && value == 0) {
+        value = SETTINGS_DEFAULT_FEE_THRESHOLD;
+    }
+    review.id = id;
+    review.value = value;
+    review.valid = true;
+    return zxerr_ok;
+}
+
+zxerr_t settings_review_getNumItems(uint8_t *num_items) {
+    *num_items = review.valid ? SETTINGS_REVIEW_ITEMS : 0;
+    return zxerr_ok;
+}
+
+zxerr_t settings_review_getItem(int8_t displayIdx,
+                                char *outKey, uint16_t outKeyLen,
+                                char *outValue, uint16_t outValueLen,
+                                uint8_t pageIdx, uint8_t *pageCount) {
+    if (!review.valid) {
+        return zxerr_no_data;
+    }
+
+    // the digits of a u64
+    char old_str[22];
+    char new_str[22];
+    char buffer[60];
+    switch (displayIdx) {
+        case 0:
+            snprintf(outKey, outKeyLen, LABEL_SETTING);
+            pageString(outValue, outValueLen, setting_name(review.id), pageIdx, pageCount);
+            return zxerr_ok;
+        case 1:
+            snprintf(outKey, outKeyLen, LABEL_SETTING_VALUE);
+            if (print_value(old_str, sizeof(old_str), review.id, settings_get(review.id)) != zxerr_ok ||
+                print_value(new_str, sizeof(new_str), review.id, review.value) != zxerr_ok) {
+                return zxerr_encoding_failed;
+            }
+            snprintf(buffer, sizeof(buffer), LABEL_SETTING_CHANGE, old_str, new_str);
+            pageString(outValue, outValueLen, buffer, pageIdx, pageCount);
+            return zxerr_ok;
+        default:
+            return zxerr_no_data;
+    }
+}
+
+zxerr_t settings_review_getItemKind(__Z_UNUSED int8_t displayIdx, item_kind_t *kind) {
+    *kind = item_kind_text;
+    return zxerr_ok;
+}
+
+zxerr_t settings_review_getItemSection(__Z_UNUSED int8_t displayIdx, item_section_t *section) {
+    *section = item_section_details;
+    return zxerr_ok;
+}
+
+zxerr_t settings_review_apply() {
+    if (!review.valid) {
+        return zxerr_no_data;
+    }
+
+    const zxerr_t err = settings_set(review.id, review.value);
+    settings_review_reset();
+    return err;
+}
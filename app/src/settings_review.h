@@ -0,0 +1,57 @@
+/*******************************************************************************
+*   (c) 2026 Zondax AG
+*
+*  Licensed under the Apache License, Version 2.0 (the "License");
+*  you may not use this file except in compliance with the License.
+*  You may obtain a copy of the License at
+*
+*      http://www.apache.org/licenses/LICENSE-2.0
+*
+*  Unless required by applicable law or agreed to in writing, software
+*  distributed under the License is distributed on an "AS IS" BASIS,
+*  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+*  See the License for the specific language governing permissions and
+*  limitations under the License.
+********************************************************************************/
+
+#pragma once
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#include <stdint.h>
+#include "zxerror.h"
+#include "parser_common.h"
+#include "settings.h"
+
+// The review of a settings update requested by the host: the setting
+// is only changed once the user approves it on the device
+
+/// Forgets the setting update being reviewed
+void settings_review_reset();
+
+/// Loads the update of setting id to value
+zxerr_t settings_review_load(setting_id_e id, uint64_t value);
+
+/// Return the number of items in the settings review
+zxerr_t settings_review_getNumItems(uint8_t *num_items);
+
+/// Gets an specific item from the settings review (including paging)
+zxerr_t settings_review_getItem(int8_t displayIdx,
+                                char *outKey, uint16_t outKeyLen,
+                                char *outValue, uint16_t outValueLen,
+                                uint8_t pageIdx, uint8_t *pageCount);
+
+/// Gets the kind of value an specific item holds
+zxerr_t settings_review_getItemKind(int8_t displayIdx, item_kind_t *kind);
+
+/// Gets the review section of an specific item
+zxerr_t settings_review_getItemSection(int8_t displayIdx, item_section_t *section);
+
+/// Stores the reviewed value of the setting and forgets the update
+zxerr_t settings_review_apply();
+
+#ifdef __cplusplus
+}
+#endif
@@ -0,0 +1,60 @@
+/*******************************************************************************
+*   (c) 2026 Zondax AG
+*
+*  Licensed under the Apache License, Version 2.0 (the "License");
+*  you may not use this file except in compliance with the License.
+*  You may obtain a copy of the License at
+*
+*      http://www.apache.org/licenses/LICENSE-2.0
+*
+*  Unless required by applicable law or agreed to in writing, software
+*  distributed under the License is distributed on an "AS IS" BASIS,
+*  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+*  See the License for the specific language governing permissions and
+*  limitations under the License.
+********************************************************************************/
+
+#include "sighash_stream.h"
+#include "rslib.h"
+#include "zxmacros.h"
+
+// The hash state and the scan of a dropped code body are kept on the Rust
+// side, see parser/src/parser/sighash_stream.rs
+
+void sighash_stream_reset() {
+    rs_sighash_stream_reset();
+}
+
+void sighash_stream_update(const uint8_t *data, uint32_t dataLen) {
+    rs_sighash_stream_update(data, dataLen);
+}
+
+zxerr_t sighash_stream_skip(const uint8_t *data, uint32_t dataLen,
+                            const uint8_t *chunk, uint32_t chunkLen) {
+    if (rs_sighash_stream_skip(data, dataLen, chunk, chunkLen) != parser_ok) {
+        return zxerr_no_data;
+    }
+    return zxerr_ok;
+}
+
+uint32_t sighash_stream_skipped() {
+    return rs_sighash_stream_skipped();
+}
+
+uint16_t sighash_stream_code_scan(uint8_t *scan, uint16_t scanLen) {
+    return rs_sighash_stream_code_scan(scan, scanLen);
+}
+
+zxerr_t sighash_stream_finish(const uint8_t *data, uint32_t dataLen,
+                              const uint8_t *last_block, uint16_t last_block_len,
+                              uint8_t *hash, uint16_t hashLen) {
+    if (data == NULL || last_block == NULL || last_block < data) {
+        return zxerr_no_data;
+    }
+
+    const uint32_t offset = (uint32_t) (last_block - data);
+    if (rs_sighash_stream_finish(data, dataLen, offset, last_block_len, hash, hashLen) != parser_ok) {
+        return zxerr_no_data;
+    }
+    return zxerr_ok;
+}
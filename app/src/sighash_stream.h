@@ -0,0 +1,59 @@
+/*******************************************************************************
+*   (c) 2026 Zondax AG
+*
+*  Licensed under the Apache License, Version 2.0 (the "License");
+*  you may not use this file except in compliance with the License.
+*  You may obtain a copy of the License at
+*
+*      http://www.apache.org/licenses/LICENSE-2.0
+*
+*  Unless required by applicable law or agreed to in writing, software
+*  distributed under the License is distributed on an "AS IS" BASIS,
+*  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+*  See the License for the specific language governing permissions and
+*  limitations under the License.
+********************************************************************************/
+
+#pragma once
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#include <stdint.h>
+#include "zxerror.h"
+
+/// Drops the hash computed so far
+void sighash_stream_reset();
+
+/// Hashes the bytes received since the last call. The header and auth
+/// fields are hashed once they are complete, zeroized as for the initial
+/// sighash, the rest of the transaction is hashed as it arrives.
+void sighash_stream_update(const uint8_t *data, uint32_t dataLen);
+
+/// Hashes a chunk that does not fit in the transaction buffer and drops it.
+/// This is only possible within the code body of a smart contract deploy,
+/// which is not shown, once everything the review needs is resident in the
+/// buffer. Every chunk after the first one dropped has to be dropped too.
+/// The body is scanned for what it can do with assets before it is dropped.
+zxerr_t sighash_stream_skip(const uint8_t *data, uint32_t dataLen,
+                            const uint8_t *chunk, uint32_t chunkLen);
+
+/// Returns how many bytes of the transaction were hashed and dropped
+uint32_t sighash_stream_skipped();
+
+/// Writes the scan of the whole code body for what it can do with assets,
+/// returns 0 if nothing was dropped and the body can be scanned from the
+/// buffer
+uint16_t sighash_stream_code_scan(uint8_t *scan, uint16_t scanLen);
+
+/// Completes the initial sighash with the last transaction block found by
+/// the parser. Fails if the streamed hash does not cover the same blocks,
+/// in that case the hash has to be computed from the whole buffer.
+zxerr_t sighash_stream_finish(const uint8_t *data, uint32_t dataLen,
+                              const uint8_t *last_block, uint16_t last_block_len,
+                              uint8_t *hash, uint16_t hashLen);
+
+#ifdef __cplusplus
+}
+#endif
@@ -0,0 +1,228 @@
+/*******************************************************************************
+*   (c) 2026 Zondax AG
+*
+*  Licensed under the Apache License, Version 2.0 (the "License");
+*  you may not use this file except in compliance with the License.
+*  You may obtain a copy of the License at
+*
+*      http://www.apache.org/licenses/LICENSE-2.0
+*
+*  Unless required by applicable law or agreed to in writing, software
+*  distributed under the License is distributed on an "AS IS" BASIS,
+*  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+*  See the License for the specific language governing permissions and
+*  limitations under the License.
+********************************************************************************/
+
+#include <stdio.h>
+#include "sign_session.h"
+#include "labels.h"
+#include "crypto.h"
+#include "sha512.h"
+#include "tx.h"
+#include "zxmacros.h"
+#include "zxformat.h"
+#include "rslib.h"
+
+#define SIGN_SESSION_HASH_LEN  32
+
+typedef struct {
+    uint32_t paths[SIGN_SESSION_MAX_PATHS][HDPATH_LEN_DEFAULT];
+    uint8_t num_paths;
+    uint8_t next_path;
+    bool approved;
+    // the paths are the origin and the sponsor of the transaction
+    // instead of slots of a multisig origin
+    bool sponsored;
+    // every path signs the same pre_sig_hash
+    bool order_independent;
+    // the pre_sig_hash for the next path, it is chained
+    // with the post_sig_hash of every produced signature
+    uint8_t presig_hash[SIGN_SESSION_HASH_LEN];
+    uint8_t auth_data[SIGN_SESSION_AUTH_DATA_LEN];
+} sign_session_t;
+
+static sign_session_t session;
+
+void sign_session_reset() {
+    // the chained presig_hash is cleared along with the paths
+    rs_zeroize(&session, sizeof(session));
+}
+
+zxerr_t sign_session_set_paths(const uint8_t *data, uint16_t dataLen) {
+    sign_session_reset();
+
+    if (data == NULL || dataLen < 1) {
+        return zxerr_no_data;
+    }
+
+    const uint8_t num_paths = data[0];
+    const uint16_t path_size = sizeof(uint32_t) * HDPATH_LEN_DEFAULT;
+
+    if (num_paths == 0 || num_paths > SIGN_SESSION_MAX_PATHS) {
+        return zxerr_out_of_bounds;
+    }
+
+    if (dataLen < 1 + num_paths * path_size) {
+        return zxerr_buffer_too_small;
+    }
+
+    for (uint8_t i = 0; i < num_paths; i++) {
+        MEMCPY(session.paths[i], data + 1 + i * path_size, path_size);
+        if (!isAllowedPath(session.paths[i], HDPATH_LEN_DEFAULT, path_kind_account)) {
+            sign_session_reset();
+            return zxerr_invalid_crypto_settings;
+        }
+    }
+    session.num_paths = num_paths;
+
+    // the first path is used to check the transaction signer
+    MEMCPY(hdPath, session.paths[0], path_size);
+    hdPath_len = HDPATH_LEN_DEFAULT;
+
+    return zxerr_ok;
+}
+
+zxerr_t sign_session_set_sponsor_paths(const uint8_t *data, uint16_t dataLen) {
+    sign_session_reset();
+
+    const uint16_t path_size = sizeof(uint32_t) * HDPATH_LEN_DEFAULT;
+    if (data == NULL || dataLen != 2 * path_size) {
+        return zxerr_buffer_too_small;
+    }
+
+    for (uint8_t i = 0; i < 2; i++) {
+        MEMCPY(session.paths[i], data + i * path_size, path_size);
+        if (!isAllowedPath(session.paths[i], HDPATH_LEN_DEFAULT, path_kind_account)) {
+            sign_session_reset();
+            return zxerr_invalid_crypto_settings;
+        }
+    }
+    session.num_paths = 2;
+    session.sponsored = true;
+
+    // the origin path is used to check the transaction signer
+    MEMCPY(hdPath, session.paths[signer_role_origin], path_size);
+    hdPath_len = HDPATH_LEN_DEFAULT;
+
+    return zxerr_ok;
+}
+
+const uint32_t *sign_session_sponsor_path() {
+    return session.sponsored ? session.paths[signer_role_sponsor] : NULL;
+}
+
+uint8_t sign_session_num_paths() {
+    return session.num_paths;
+}
+
+zxerr_t sign_session_getNumItems(uint8_t *num_items) {
+    uint8_t tx_items = 0;
+    zxerr_t err = tx_getNumItems(&tx_items);
+    if (err != zxerr_ok) {
+        return err;
+    }
+
+    if (tx_items > UINT8_MAX - session.num_paths) {
+        return zxerr_out_of_bounds;
+    }
+
+    *num_items = tx_items + session.num_paths;
+    return zxerr_ok;
+}
+
+zxerr_t sign_session_getItem(int8_t displayIdx,
+                             char *outKey, uint16_t outKeyLen,
+                             char *outValue, uint16_t outValueLen,
+                             uint8_t pageIdx, uint8_t *pageCount) {
+    if (displayIdx < 0) {
+        return zxerr_no_data;
+    }
+
+    if (displayIdx >= session.num_paths) {
+        return tx_getItem(displayIdx - session.num_paths,
+                          outKey, outKeyLen,
+                          outValue, outValueLen,
+                          pageIdx, pageCount);
+    }
+
+    if (session.sponsored) {
+        snprintf(outKey, outKeyLen, "%s", displayIdx == signer_role_origin ? LABEL_ORIGIN_PATH : LABEL_SPONSOR_PATH);
+    } else {
+        snprintf(outKey, outKeyLen, LABEL_PATH_N, displayIdx + 1, session.num_paths);
+    }
+    char buffer[100];
+    bip32_to_str(buffer, sizeof(buffer), session.paths[displayIdx], HDPATH_LEN_DEFAULT);
+    pageString(outValue, outValueLen, buffer, pageIdx, pageCount);
+    return zxerr_ok;
+}
+
+zxerr_t sign_session_approve(const uint8_t *presig_hash, uint16_t presig_hashLen,
+                             const uint8_t *auth_data, uint16_t auth_dataLen,
+                             bool order_independent) {
+    if (session.num_paths == 0) {
+        return zxerr_no_data;
+    }
+
+    if (presig_hashLen != SIGN_SESSION_HASH_LEN || auth_dataLen != SIGN_SESSION_AUTH_DATA_LEN) {
+        return zxerr_out_of_bounds;
+    }
+
+    MEMCPY(session.presig_hash, presig_hash, SIGN_SESSION_HASH_LEN);
+    MEMCPY(session.auth_data, auth_data, SIGN_SESSION_AUTH_DATA_LEN);
+    session.next_path = 0;
+    session.order_independent = order_independent;
+    session.approved = true;
+
+    return zxerr_ok;
+}
+
+zxerr_t sign_session_next(uint8_t *buffer, uint16_t bufferLen, uint16_t *replyLen) {
+    *replyLen = 0;
+
+    if (!session.approved || session.next_path >= session.num_paths) {
+        return zxerr_no_data;
+    }
+
+    MEMCPY(hdPath, session.paths[session.next_path], sizeof(uint32_t) * HDPATH_LEN_DEFAULT);
+    hdPath_len = HDPATH_LEN_DEFAULT;
+
+    zxerr_t err = crypto_sign(buffer, bufferLen, session.presig_hash, SIGN_SESSION_HASH_LEN, replyLen);
+    if (err != zxerr_ok) {
+        sign_session_reset();
+        return err;
+    }
+
+    err = crypto_post_sighash(session.presig_hash, SIGN_SESSION_HASH_LEN, buffer, bufferLen);
+    if (err != zxerr_ok) {
+        sign_session_reset();
+        *replyLen = 0;
+        return err;
+    }
+
+    session.next_path++;
+    if (session.next_path >= session.num_paths) {
+        // nothing else to sign
+        sign_session_reset();
+        return zxerr_ok;
+    }
+
+    if (session.order_independent) {
+        return zxerr_ok;
+    }
+
+    // The next signer signs over this signature's post_sig_hash
+    // followed by the auth flag, fee and nonce
+    sha512_256_ctx ctx;
+    uint8_t hash_temp[SHA512_DIGEST_LENGTH];
+    SHA512_256_init(&ctx);
+    SHA512_256_starts(&ctx);
+    SHA512_256_update(&ctx, buffer, SIGN_SESSION_HASH_LEN);
+    SHA512_256_update(&ctx, session.auth_data, SIGN_SESSION_AUTH_DATA_LEN);
+    SHA512_256_finish(&ctx, hash_temp);
+    MEMCPY(session.presig_hash, hash_temp, SIGN_SESSION_HASH_LEN);
+    rs_zeroize(hash_temp, sizeof(hash_temp));
+    rs_zeroize(&ctx, sizeof(ctx));
+
+    return zxerr_ok;
+}
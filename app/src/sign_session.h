@@ -0,0 +1,83 @@
+/*******************************************************************************
+*   (c) 2026 Zondax AG
+*
+*  Licensed under the Apache License, Version 2.0 (the "License");
+*  you may not use this file except in compliance with the License.
+*  You may obtain a copy of the License at
+*
+*      http://www.apache.org/licenses/LICENSE-2.0
+*
+*  Unless required by applicable law or agreed to in writing, software
+*  distributed under the License is distributed on an "AS IS" BASIS,
+*  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+*  See the License for the specific language governing permissions and
+*  limitations under the License.
+********************************************************************************/
+
+#pragma once
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#include <stdint.h>
+#include <stdbool.h>
+#include "coin.h"
+#include "zxerror.h"
+
+// Maximum number of derivation paths that can be signed
+// with a single approval
+#define SIGN_SESSION_MAX_PATHS          4
+
+// 1-byte auth flag, 8-byte fee and 8-byte nonce which are
+// appended to every signer post_sig_hash in a multisig transaction
+#define SIGN_SESSION_AUTH_DATA_LEN      (1 + 8 + 8)
+
+// Who a session path signs as
+typedef enum {
+    signer_role_origin = 0,
+    signer_role_sponsor = 1,
+} signer_role_e;
+
+/// Drops any pending session, paths and signatures
+void sign_session_reset();
+
+/// Loads the list of paths to sign with. The expected format is a 1-byte
+/// number of paths followed by that many 5-element BIP32 paths.
+/// The first path is also loaded in hdPath.
+zxerr_t sign_session_set_paths(const uint8_t *data, uint16_t dataLen);
+
+/// Loads the origin path followed by the sponsor path, both 5-element
+/// BIP32 paths, for a device that signs a sponsored transaction as both.
+/// The origin path is also loaded in hdPath.
+zxerr_t sign_session_set_sponsor_paths(const uint8_t *data, uint16_t dataLen);
+
+/// Returns the sponsor path of the current session, NULL if there is none
+const uint32_t *sign_session_sponsor_path();
+
+/// Returns the number of paths loaded in the current session
+uint8_t sign_session_num_paths();
+
+/// Returns the number of items in the review, paths plus transaction items
+zxerr_t sign_session_getNumItems(uint8_t *num_items);
+
+/// Gets an specific item from the review, the session paths come first
+zxerr_t sign_session_getItem(int8_t displayIdx,
+                             char *outKey, uint16_t outKeyLen,
+                             char *outValue, uint16_t outValueLen,
+                             uint8_t pageIdx, uint8_t *pageCount);
+
+/// Marks the session as approved. The pre_sig_hash is the one for the first
+/// path, the auth data is used to chain the following signers. The signers of
+/// an order independent multisig account all sign the first pre_sig_hash
+zxerr_t sign_session_approve(const uint8_t *presig_hash, uint16_t presig_hashLen,
+                             const uint8_t *auth_data, uint16_t auth_dataLen,
+                             bool order_independent);
+
+/// Signs with the next pending path writing the same layout as a regular
+/// transaction signature: post_sig_hash, rsv and DER signature.
+zxerr_t sign_session_next(uint8_t *buffer, uint16_t bufferLen, uint16_t *replyLen);
+
+#ifdef __cplusplus
+}
+#endif
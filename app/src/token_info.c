@@ -0,0 +1,107 @@
+/*******************************************************************************
+*   (c) 2026 Zondax AG
+*
+*  Licensed under the Apache License, Version 2.0 (the "License");
+*  you may not use this file except in compliance with the License.
+*  You may obtain a copy of the License at
+*
+*      http://www.apache.org/licenses/LICENSE-2.0
+*
+*  Unless required by applicable law or agreed to in writing, software
+*  distributed under the License is distributed on an "AS IS" BASIS,
+*  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+*  See the License for the specific language governing permissions and
+*  limitations under the License.
+********************************************************************************/
+
+#include "token_info.h"
+#include "sha512.h"
+#include "app_mode.h"
+#include "rslib.h"
+#include "zxmacros.h"
+#include "cx.h"
+
+#define TOKEN_INFO_HASH_LEN     32
+#define SECP256K1_PK_LEN        65
+
+// Prepended to the metadata when hashing, so that its DER signature
+// can not be taken for a signature of something else
+#define TOKEN_INFO_DOMAIN       "STX-TOKEN-INFO"
+
+typedef struct {
+    bool active;
+    // whether it was signed with the signing key
+    bool verified;
+    uint16_t len;
+    sha512_256_ctx ctx;
+    uint8_t data[TOKEN_INFO_MAX_LEN];
+} token_info_t;
+
+// Only kept in RAM, every session starts without metadata
+static token_info_t token_info;
+
+#if defined(TOKEN_INFO_PUBKEY)
+// Uncompressed secp256k1 key the metadata is signed with, set at build time
+static const uint8_t token_info_pubkey[SECP256K1_PK_LEN] = { TOKEN_INFO_PUBKEY };
+#endif
+
+void token_info_reset() {
+    MEMZERO(&token_info, sizeof(token_info));
+    SHA512_256_init(&token_info.ctx);
+    SHA512_256_starts(&token_info.ctx);
+    SHA512_256_update(&token_info.ctx, (const uint8_t *) TOKEN_INFO_DOMAIN, sizeof(TOKEN_INFO_DOMAIN) - 1);
+}
+
+zxerr_t token_info_append(const uint8_t *data, uint16_t dataLen) {
+    if (data == NULL || token_info.active) {
+        return zxerr_unknown;
+    }
+    if (dataLen > TOKEN_INFO_MAX_LEN - token_info.len) {
+        return zxerr_buffer_too_small;
+    }
+
+    MEMCPY(token_info.data + token_info.len, data, dataLen);
+    SHA512_256_update(&token_info.ctx, data, dataLen);
+    token_info.len += dataLen;
+    return zxerr_ok;
+}
+
+zxerr_t token_info_activate(const uint8_t *signature, uint16_t signatureLen) {
+    if (token_info.active || rs_check_token_info(token_info.data, token_info.len) != parser_ok) {
+        token_info_reset();
+        return zxerr_unknown;
+    }
+
+    bool verified = false;
+    if (signature != NULL && signatureLen > 0) {
+        // only the first TOKEN_INFO_HASH_LEN bytes are the SHA-512/256
+        uint8_t hash[SHA512_DIGEST_LENGTH];
+        SHA512_256_finish(&token_info.ctx, hash);
+#if defined(TOKEN_INFO_PUBKEY)
+        cx_ecfp_public_key_t pubkey;
+        verified = cx_ecfp_init_public_key_no_throw(CX_CURVE_256K1, token_info_pubkey, SECP256K1_PK_LEN, &pubkey) == CX_OK &&
+                   cx_ecdsa_verify_no_throw(&pubkey, hash, TOKEN_INFO_HASH_LEN, signature, signatureLen);
+#endif
+        if (!verified) {
+            token_info_reset();
+            return zxerr_invalid_crypto_settings;
+        }
+    }
+
+    token_info.active = true;
+    token_info.verified = verified;
+    return zxerr_ok;
+}
+
+const uint8_t *token_info_data(uint16_t *dataLen) {
+    if (dataLen == NULL) {
+        return NULL;
+    }
+    *dataLen = 0;
+    // metadata the host did not sign is only trusted in expert mode
+    if (!token_info.active || (!token_info.verified && !app_mode_expert())) {
+        return NULL;
+    }
+    *dataLen = token_info.len;
+    return token_info.data;
+}
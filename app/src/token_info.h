@@ -0,0 +1,50 @@
+/*******************************************************************************
+*   (c) 2026 Zondax AG
+*
+*  Licensed under the Apache License, Version 2.0 (the "License");
+*  you may not use this file except in compliance with the License.
+*  You may obtain a copy of the License at
+*
+*      http://www.apache.org/licenses/LICENSE-2.0
+*
+*  Unless required by applicable law or agreed to in writing, software
+*  distributed under the License is distributed on an "AS IS" BASIS,
+*  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+*  See the License for the specific language governing permissions and
+*  limitations under the License.
+********************************************************************************/
+
+#pragma once
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#include <stdint.h>
+#include <stdbool.h>
+#include "zxerror.h"
+
+// Serialized token metadata, the number of tokens and the asset
+// and flags of each of them
+#if defined(TARGET_NANOS)
+#define TOKEN_INFO_MAX_LEN      256
+#else
+#define TOKEN_INFO_MAX_LEN      512
+#endif
+
+/// Drops the metadata in use and starts receiving new metadata
+void token_info_reset();
+
+/// Appends data to the metadata being received
+zxerr_t token_info_append(const uint8_t *data, uint16_t dataLen);
+
+/// Starts using the metadata received. A DER signature of it is checked
+/// against the signing key, without one it is only used in expert mode
+zxerr_t token_info_activate(const uint8_t *signature, uint16_t signatureLen);
+
+/// Returns the metadata in use, NULL if there is none
+const uint8_t *token_info_data(uint16_t *dataLen);
+
+#ifdef __cplusplus
+}
+#endif
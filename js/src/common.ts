@@ -11,6 +11,9 @@ export const INS = {
   GET_AUTH_PUBKEY: 0x03,
   SIGN_JWT_SECP256K1: 0x04,
   SIGN_STRUCTURED_MSG: 0x05,
+  SIGN_MULTIPATH_SECP256K1: 0x06,
+  GET_SESSION_SIGNATURE: 0x07,
+  SIGN_FEE_BUMP_SECP256K1: 0x08,
 };
 
 export const PAYLOAD_TYPE = {
@@ -24,7 +27,16 @@ export const P1_VALUES = {
   SHOW_ADDRESS_IN_DEVICE: 0x01,
 };
 
+// Capabilities reported by GET_VERSION
+export const VERSION_FLAGS = {
+  WINDOWED_CONTRACT_DEPLOY: 0x01,
+};
+
+// GET_ADDR P2 bit asking for the uncompressed key too
+export const ADDR_FLAG_UNCOMPRESSED = 0x80;
+
 export const PKLEN = 33;
+export const UNCOMPRESSED_PKLEN = 65;
 
 export enum LedgerError {
   U2FUnknown = 1,
@@ -127,6 +139,19 @@ export async function getVersion(transport: Transport) {
       /* eslint-enable no-bitwise */
     }
 
+    // Older firmware does not report these fields
+    let protocolVersion = 0;
+    let maxTxSize = 0;
+    if (response.length >= 16) {
+      protocolVersion = response[9];
+      maxTxSize = response.readUInt32BE(10);
+    }
+    let windowedContractDeploy = false;
+    if (response.length >= 17) {
+      // eslint-disable-next-line no-bitwise
+      windowedContractDeploy = (response[14] & VERSION_FLAGS.WINDOWED_CONTRACT_DEPLOY) !== 0;
+    }
+
     return {
       returnCode,
       errorMessage: errorCodeToString(returnCode),
@@ -136,6 +161,9 @@ export async function getVersion(transport: Transport) {
       patch: response[3],
       deviceLocked: response[4] === 1,
       targetId: targetId.toString(16),
+      protocolVersion,
+      maxTxSize,
+      windowedContractDeploy,
     };
   }, processErrorResponse);
 }
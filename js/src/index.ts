@@ -18,6 +18,7 @@ import Transport from '@ledgerhq/hw-transport';
 import { serializePath } from './helper';
 import { ResponseAddress, ResponseAppInfo, ResponseSign, ResponseVersion } from './types';
 import {
+  ADDR_FLAG_UNCOMPRESSED,
   CHUNK_SIZE,
   CLA,
   errorCodeToString,
@@ -28,6 +29,7 @@ import {
   PAYLOAD_TYPE,
   PKLEN,
   processErrorResponse,
+  UNCOMPRESSED_PKLEN,
 } from './common';
 import { encode } from 'varuint-bitcoin';
 
@@ -55,6 +57,65 @@ function processGetAddrResponse(response: Buffer) {
   };
 }
 
+// The address is followed by a null byte and the uncompressed key
+function processGetAddrUncompressedResponse(response: Buffer) {
+  if (response.length < PKLEN + 1 + UNCOMPRESSED_PKLEN + 2) {
+    return processGetAddrResponse(response);
+  }
+
+  const keyStart = response.length - 2 - UNCOMPRESSED_PKLEN;
+  return {
+    ...processGetAddrResponse(
+      Buffer.concat([response.slice(0, keyStart - 1), response.slice(-2)])
+    ),
+    uncompressedPublicKey: Buffer.from(response.slice(keyStart, -2)),
+  };
+}
+
+function processSignResponse(response: Buffer) {
+  const errorCodeData = response.slice(-2);
+  const returnCode = errorCodeData[0] * 256 + errorCodeData[1];
+  let errorMessage = errorCodeToString(returnCode);
+  let errorDescription = '';
+
+  let postSignHash = Buffer.alloc(0);
+  let signatureCompact = Buffer.alloc(0);
+  let signatureVRS = Buffer.alloc(0);
+  let signatureDER = Buffer.alloc(0);
+
+  if (
+    returnCode === LedgerError.BadKeyHandle ||
+    returnCode === LedgerError.DataIsInvalid ||
+    returnCode === LedgerError.SignVerifyError
+  ) {
+    errorMessage = `${errorMessage} : ${response
+      .slice(0, response.length - 2)
+      .toString('ascii')}`;
+  }
+
+  if (returnCode === LedgerError.NoErrors && response.length > 2) {
+    postSignHash = response.slice(0, 32);
+    signatureCompact = response.slice(32, 97);
+    signatureVRS = Buffer.alloc(65);
+    signatureVRS[0] = signatureCompact[signatureCompact.length - 1];
+    Buffer.from(signatureCompact).copy(signatureVRS, 1, 0, 64);
+    signatureDER = response.slice(97, response.length - 2);
+    return {
+      postSignHash,
+      signatureCompact,
+      signatureVRS,
+      signatureDER,
+      returnCode: returnCode,
+      errorMessage: errorMessage,
+    };
+  }
+
+  return {
+    returnCode: returnCode,
+    errorMessage: errorMessage,
+  };
+}
+
 export default class StacksApp {
   transport;
 
@@ -149,6 +210,24 @@ export default class StacksApp {
       .then(processGetAddrResponse, processErrorResponse);
   }
 
+  async getAddressAndUncompressedPubKey(
+    path: string,
+    version: AddressVersion
+  ): Promise<ResponseAddress> {
+    const serializedPath = serializePath(path);
+    return this.transport
+      .send(
+        CLA,
+        INS.GET_ADDR_SECP256K1,
+        P1_VALUES.ONLY_RETRIEVE,
+        // eslint-disable-next-line no-bitwise
+        version | ADDR_FLAG_UNCOMPRESSED,
+        serializedPath,
+        [0x9000]
+      )
+      .then(processGetAddrUncompressedResponse, processErrorResponse);
+  }
+
   async getIdentityPubKey(path: string): Promise<ResponseAddress> {
     const serializedPath = serializePath(path);
     return this.transport
@@ -191,49 +270,7 @@ export default class StacksApp {
         LedgerError.BadKeyHandle,
         LedgerError.SignVerifyError,
       ])
-      .then((response: Buffer) => {
-        const errorCodeData = response.slice(-2);
-        const returnCode = errorCodeData[0] * 256 + errorCodeData[1];
-        let errorMessage = errorCodeToString(returnCode);
-        let errorDescription = '';
-
-        let postSignHash = Buffer.alloc(0);
-        let signatureCompact = Buffer.alloc(0);
-        let signatureVRS = Buffer.alloc(0);
-        let signatureDER = Buffer.alloc(0);
-
-        if (
-          returnCode === LedgerError.BadKeyHandle ||
-          returnCode === LedgerError.DataIsInvalid ||
-          returnCode === LedgerError.SignVerifyError
-        ) {
-          errorMessage = `${errorMessage} : ${response
-            .slice(0, response.length - 2)
-            .toString('ascii')}`;
-        }
-
-        if (returnCode === LedgerError.NoErrors && response.length > 2) {
-          postSignHash = response.slice(0, 32);
-          signatureCompact = response.slice(32, 97);
-          signatureVRS = Buffer.alloc(65);
-          signatureVRS[0] = signatureCompact[signatureCompact.length - 1];
-          Buffer.from(signatureCompact).copy(signatureVRS, 1, 0, 64);
-          signatureDER = response.slice(97, response.length - 2);
-          return {
-            postSignHash,
-            signatureCompact,
-            signatureVRS,
-            signatureDER,
-            returnCode: returnCode,
-            errorMessage: errorMessage,
-          };
-        }
-
-        return {
-          returnCode: returnCode,
-          errorMessage: errorMessage,
-        };
-      }, processErrorResponse);
+      .then(processSignResponse, processErrorResponse);
   }
 
   async sign(path: string, message: Buffer) {
@@ -261,6 +298,72 @@ export default class StacksApp {
     }, processErrorResponse);
   }
 
+  // Re-signs the last signed transaction with a new fee and/or nonce,
+  // the device only asks to confirm the changed values
+  async sign_fee_bump(path: string, message: Buffer) {
+    const ins = INS.SIGN_FEE_BUMP_SECP256K1;
+    return this.signGetChunks(path, message).then(chunks => {
+      return this.signSendChunk(1, chunks.length, chunks[0], ins).then(async response => {
+        let result = {
+          returnCode: response.returnCode,
+          errorMessage: response.errorMessage,
+          postSignHash: null as null | Buffer,
+          signatureCompact: null as null | Buffer,
+          signatureDER: null as null | Buffer,
+        };
+        for (let i = 1; i < chunks.length; i += 1) {
+          // eslint-disable-next-line no-await-in-loop
+          result = await this.signSendChunk(1 + i, chunks.length, chunks[i], ins);
+          if (result.returnCode !== LedgerError.NoErrors) {
+            break;
+          }
+        }
+        return result;
+      }, processErrorResponse);
+    }, processErrorResponse);
+  }
+
+  async signMultipathGetChunks(paths: string[], message: Buffer) {
+    const serializedPaths = Buffer.concat([
+      Buffer.from([paths.length]),
+      ...paths.map(path => serializePath(path)),
+    ]);
+    return StacksApp.prepareChunks(serializedPaths, message);
+  }
+
+  async getSessionSignature(): Promise<ResponseSign> {
+    return this.transport
+      .send(CLA, INS.GET_SESSION_SIGNATURE, 0, 0, Buffer.alloc(0), [
+        LedgerError.NoErrors,
+        LedgerError.DataIsInvalid,
+      ])
+      .then(processSignResponse, processErrorResponse);
+  }
+
+  // Signs a multisig transaction with every path in a single review.
+  // Signatures are chained in the given order, one per multisig slot.
+  async sign_multipath(paths: string[], message: Buffer): Promise<ResponseSign[]> {
+    const ins = INS.SIGN_MULTIPATH_SECP256K1;
+    const chunks = await this.signMultipathGetChunks(paths, message);
+
+    let result = await this.signSendChunk(1, chunks.length, chunks[0], ins);
+    for (let i = 1; i < chunks.length; i += 1) {
+      // eslint-disable-next-line no-await-in-loop
+      result = await this.signSendChunk(1 + i, chunks.length, chunks[i], ins);
+      if (result.returnCode !== LedgerError.NoErrors) {
+        return [result];
+      }
+    }
+
+    const results = [result];
+    for (let i = 1; i < paths.length && result.returnCode === LedgerError.NoErrors; i += 1) {
+      // eslint-disable-next-line no-await-in-loop
+      result = await this.getSessionSignature();
+      results.push(result);
+    }
+    return results;
+  }
+
   async sign_msg(path: string, message: string) {
     const len = encode(message.length);
     const stacks_message = '\x17Stacks Signed Message:\n';
@@ -6,6 +6,7 @@ export interface ResponseBase {
 export interface ResponseAddress extends ResponseBase {
   publicKey: Buffer;
   address: string;
+  uncompressedPublicKey?: Buffer;
 }
 
 export interface ResponseVersion extends ResponseBase {
@@ -15,6 +16,11 @@ export interface ResponseVersion extends ResponseBase {
   patch: number;
   deviceLocked: boolean;
   targetId: string;
+  protocolVersion: number;
+  maxTxSize: number;
+  // the code body of a smart contract deploy is not bound by maxTxSize,
+  // any other payload still is
+  windowedContractDeploy: boolean;
 }
 
 export interface ResponseAppInfo extends ResponseBase {
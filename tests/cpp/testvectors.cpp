@@ -29,7 +29,8 @@ GTEST_ALLOW_UNINSTANTIATED_PARAMETERIZED_TEST(VerifyTestVectors);
 using ::testing::TestWithParam;
 using ::testing::Values;
 
-uint32_t hdPath[HDPATH_LEN_DEFAULT];
+uint32_t hdPath[HDPATH_LEN_MAX];
+uint32_t hdPath_len = HDPATH_LEN_DEFAULT;
 
 void check_testcase(const testcase_t &testcase) {
     auto tc = ReadTestCaseData(testcase.testcases, testcase.index);
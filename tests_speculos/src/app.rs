@@ -0,0 +1,153 @@
+//! The APDUs of the app, see docs/APDUSPEC.md.
+
+use std::convert::TryInto;
+
+use crate::speculos::{Button, Speculos};
+use crate::Result;
+
+const CLA: u8 = 0x09;
+const INS_GET_ADDR_SECP256K1: u8 = 0x01;
+const INS_SIGN_SECP256K1: u8 = 0x02;
+
+const CHUNK_SIZE: usize = 250;
+const CHUNK_INIT: u8 = 0;
+const CHUNK_ADD: u8 = 1;
+const CHUNK_LAST: u8 = 2;
+
+const SW_OK: u16 = 0x9000;
+const PUBKEY_LEN: usize = 33;
+
+// a review longer than this is stuck on a screen
+const MAX_SCREENS: usize = 128;
+
+pub const TESTNET_SINGLESIG: u8 = 26;
+
+/// The reply to INS_SIGN_SECP256K1
+pub struct Signature {
+    pub post_sighash: [u8; 32],
+    pub r: [u8; 32],
+    pub s: [u8; 32],
+    pub v: u8,
+    pub der: Vec<u8>,
+}
+
+/// A signed review, with a screenshot of every screen walked through
+pub struct SignedReview {
+    pub signature: Signature,
+    pub screens: Vec<Vec<u8>>,
+}
+
+pub struct App<'a> {
+    sim: &'a Speculos,
+}
+
+// a BIP32 path as the device reads it, little endian children
+pub fn serialize_path(path: &[u32]) -> Vec<u8> {
+    path.iter().flat_map(|child| child.to_le_bytes()).collect()
+}
+
+fn apdu(ins: u8, p1: u8, p2: u8, data: &[u8]) -> Vec<u8> {
+    let mut apdu = vec![CLA, ins, p1, p2, data.len() as u8];
+    apdu.extend_from_slice(data);
+    apdu
+}
+
+// splits off the status word, failing on anything other than OK
+fn check_sw(mut response: Vec<u8>) -> Result<Vec<u8>> {
+    if response.len() < 2 {
+        return Err("response without status word".into());
+    }
+    let sw = response.split_off(response.len() - 2);
+    let sw = u16::from_be_bytes([sw[0], sw[1]]);
+    if sw != SW_OK {
+        let msg = String::from_utf8_lossy(&response);
+        return Err(format!("status word {:04x}: {}", sw, msg).into());
+    }
+    Ok(response)
+}
+
+impl<'a> App<'a> {
+    pub fn new(sim: &'a Speculos) -> Self {
+        Self { sim }
+    }
+
+    /// The compressed public key and the address of path
+    pub fn get_address(&self, path: &[u32], version: u8) -> Result<([u8; PUBKEY_LEN], String)> {
+        let response = self.sim.exchange(&apdu(
+            INS_GET_ADDR_SECP256K1,
+            0,
+            version,
+            &serialize_path(path),
+        ))?;
+        let response = check_sw(response)?;
+        if response.len() <= PUBKEY_LEN {
+            return Err("short address response".into());
+        }
+        let pubkey = response[..PUBKEY_LEN].try_into()?;
+        let address = String::from_utf8(response[PUBKEY_LEN..].to_vec())?;
+        Ok((pubkey, address))
+    }
+
+    /// Sends blob in chunks, walks the review taking a screenshot of every
+    /// screen and approves it
+    pub fn sign(&self, path: &[u32], blob: &[u8]) -> Result<SignedReview> {
+        let idle = self.sim.screenshot()?;
+
+        check_sw(self.sim.exchange(&apdu(
+            INS_SIGN_SECP256K1,
+            CHUNK_INIT,
+            0,
+            &serialize_path(path),
+        ))?)?;
+
+        let chunks: Vec<_> = blob.chunks(CHUNK_SIZE).collect();
+        let (last, rest) = chunks.split_last().ok_or("empty blob")?;
+        for chunk in rest {
+            check_sw(
+                self.sim
+                    .exchange(&apdu(INS_SIGN_SECP256K1, CHUNK_ADD, 0, chunk))?,
+            )?;
+        }
+        let reply = self
+            .sim
+            .exchange_in_background(&apdu(INS_SIGN_SECP256K1, CHUNK_LAST, 0, last));
+
+        let screens = self.approve(idle)?;
+        let response = reply.join().map_err(|_| "the sign request panicked")??;
+        let response = check_sw(response)?;
+        if response.len() < 97 {
+            return Err("short signature response".into());
+        }
+
+        let signature = Signature {
+            post_sighash: response[..32].try_into()?,
+            r: response[32..64].try_into()?,
+            s: response[64..96].try_into()?,
+            v: response[96],
+            der: response[97..].to_vec(),
+        };
+        Ok(SignedReview { signature, screens })
+    }
+
+    // moves right until the approve screen and confirms it
+    fn approve(&self, idle: Vec<u8>) -> Result<Vec<Vec<u8>>> {
+        let mut screen = self.sim.wait_screen_change(&idle)?;
+        let mut screens = Vec::new();
+
+        for _ in 0..MAX_SCREENS {
+            screens.push(screen.clone());
+            let approve = self
+                .sim
+                .screen_text()?
+                .iter()
+                .any(|text| text.eq_ignore_ascii_case("approve"));
+            if approve {
+                self.sim.press(Button::Both)?;
+                return Ok(screens);
+            }
+            self.sim.press(Button::Right)?;
+            screen = self.sim.wait_screen_change(&screen)?;
+        }
+        Err("the review has no approve screen".into())
+    }
+}
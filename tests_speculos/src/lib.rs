@@ -0,0 +1,187 @@
+//! End to end tests of the app running under Speculos.
+//!
+//! The transaction vectors of the parser are signed on an emulated device:
+//! the APDUs are sent as a host would, every review screen is walked
+//! through and captured, and the review is approved. The returned
+//! signature is checked against the device key and the presig hash
+//! computed on the host, and the screens against the snapshots in
+//! snapshots/<model>-<vector>, so a change in the parser or in the UI
+//! shows up in the same run. Run the tests with UPDATE_SNAPSHOTS=1 to
+//! record the screens again.
+
+pub mod app;
+pub mod speculos;
+
+use std::path::{Path, PathBuf};
+
+use k256::ecdsa::signature::hazmat::PrehashVerifier;
+use k256::ecdsa::{Signature as EcdsaSignature, VerifyingKey};
+use ripemd::Ripemd160;
+use sha2::{Digest, Sha256, Sha512_256};
+
+use stacks_ledger_parser::hash::DefaultSha512_256;
+use stacks_ledger_parser::parser::{ParserError, Transaction};
+
+use crate::app::Signature;
+
+pub type Result<T> = std::result::Result<T, Box<dyn std::error::Error + Send + Sync>>;
+
+/// m/44'/5757'/0'/0/0
+pub const PATH: [u32; 5] = [0x8000_002c, 0x8000_167d, 0x8000_0000, 0, 0];
+
+// the origin signer follows the version, chain id, auth type and hash mode
+const ORIGIN_SIGNER: std::ops::Range<usize> = 7..27;
+
+/// A transaction vector of the parser tests
+pub struct Vector {
+    pub name: String,
+    pub raw: Vec<u8>,
+}
+
+/// The vectors in app/rust/parser/tests, sorted by name
+pub fn vectors() -> Result<Vec<Vector>> {
+    let dir: PathBuf = [
+        env!("CARGO_MANIFEST_DIR"),
+        "..",
+        "app",
+        "rust",
+        "parser",
+        "tests",
+    ]
+    .iter()
+    .collect();
+
+    let mut vectors = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if !path.extension().is_some_and(|ext| ext == "json") {
+            continue;
+        }
+        let json: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&path)?)?;
+        let raw = json["raw"].as_str().ok_or("vector without raw")?;
+        vectors.push(Vector {
+            name: path.file_stem().unwrap().to_string_lossy().into_owned(),
+            raw: hex::decode(raw)?,
+        });
+    }
+    vectors.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(vectors)
+}
+
+pub fn hash160(pubkey: &[u8]) -> [u8; 20] {
+    Ripemd160::digest(Sha256::digest(pubkey)).into()
+}
+
+/// The vector with the device as its origin, so the app accepts to sign it.
+/// Multisig vectors need the data of a previous signer and are left out
+pub fn as_origin(raw: &[u8], signer: &[u8; 20]) -> Result<Option<Vec<u8>>> {
+    if Transaction::from_bytes(raw)
+        .map_err(parser_error)?
+        .is_multisig()
+    {
+        return Ok(None);
+    }
+    let mut raw = raw.to_vec();
+    raw[ORIGIN_SIGNER].copy_from_slice(signer);
+    Ok(Some(raw))
+}
+
+/// The hash the device signs: the initial sighash followed by the auth
+/// type, fee and nonce of the signer
+pub fn presig_hash(raw: &[u8], signer: &[u8; 20]) -> Result<[u8; 32]> {
+    let mut tx = Transaction::from_bytes(raw).map_err(parser_error)?;
+    tx.read(raw).map_err(parser_error)?;
+    if tx.check_signer_pk_hash(signer) != ParserError::parser_ok {
+        return Err("the device is not a signer".into());
+    }
+
+    let mut initial = [0u8; 32];
+    tx.initial_sighash::<DefaultSha512_256>(&mut initial)
+        .map_err(parser_error)?;
+
+    let mut hasher = Sha512_256::new();
+    hasher.update(initial);
+    hasher.update([tx.auth_flag() as u8]);
+    hasher.update(tx.fee().ok_or("no fee")?.to_be_bytes());
+    hasher.update(tx.nonce().ok_or("no nonce")?.to_be_bytes());
+    Ok(hasher.finalize().into())
+}
+
+/// Checks the signature is the device key signing presig, and that the
+/// post sighash covers it
+pub fn verify(signature: &Signature, pubkey: &[u8], presig: &[u8; 32]) -> Result<()> {
+    let key = VerifyingKey::from_sec1_bytes(pubkey)?;
+    let mut rs = [0u8; 64];
+    rs[..32].copy_from_slice(&signature.r);
+    rs[32..].copy_from_slice(&signature.s);
+    let ecdsa = EcdsaSignature::from_slice(&rs)?;
+    let ecdsa = ecdsa.normalize_s().unwrap_or(ecdsa);
+    key.verify_prehash(presig, &ecdsa)?;
+
+    if EcdsaSignature::from_der(&signature.der)? != EcdsaSignature::from_slice(&rs)? {
+        return Err("the DER and compact signatures differ".into());
+    }
+
+    // presig hash, compressed key encoding, v, r and s
+    let mut hasher = Sha512_256::new();
+    hasher.update(presig);
+    hasher.update([0x00, signature.v]);
+    hasher.update(rs);
+    if hasher.finalize().as_slice() != signature.post_sighash {
+        return Err("wrong post sighash".into());
+    }
+    Ok(())
+}
+
+/// Compares the screens with the snapshots in dir, or records them when
+/// UPDATE_SNAPSHOTS is set. Returns the screens that differ
+pub fn compare_snapshots(dir: &Path, screens: &[Vec<u8>]) -> Result<Vec<String>> {
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        if dir.exists() {
+            std::fs::remove_dir_all(dir)?;
+        }
+        std::fs::create_dir_all(dir)?;
+        for (idx, screen) in screens.iter().enumerate() {
+            std::fs::write(dir.join(format!("{:05}.png", idx)), screen)?;
+        }
+        return Ok(Vec::new());
+    }
+
+    let mut expected: Vec<_> = match std::fs::read_dir(dir) {
+        Ok(entries) => entries
+            .map(|entry| entry.map(|e| e.path()))
+            .collect::<std::io::Result<_>>()?,
+        Err(_) => return Ok(vec![format!("{} has no snapshots", dir.display())]),
+    };
+    expected.sort();
+
+    let mut mismatches = Vec::new();
+    if expected.len() != screens.len() {
+        mismatches.push(format!(
+            "{}: {} screens, expected {}",
+            dir.display(),
+            screens.len(),
+            expected.len()
+        ));
+    }
+    for (path, screen) in expected.iter().zip(screens) {
+        if std::fs::read(path)? != *screen {
+            mismatches.push(path.display().to_string());
+        }
+    }
+    Ok(mismatches)
+}
+
+pub fn snapshots_dir(prefix: &str, name: &str) -> PathBuf {
+    [
+        env!("CARGO_MANIFEST_DIR"),
+        "snapshots",
+        &format!("{}-{}", prefix, name),
+    ]
+    .iter()
+    .collect()
+}
+
+fn parser_error(e: ParserError) -> Box<dyn std::error::Error + Send + Sync> {
+    format!("{:?}", e).into()
+}
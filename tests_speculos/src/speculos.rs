@@ -0,0 +1,209 @@
+//! A Speculos instance running the app, driven through its REST API.
+
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use reqwest::blocking::Client;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::Result;
+
+// the seed of the zemu tests, so both see the same keys
+pub const APP_SEED: &str = "equip will roof matter pink blind book anxiety banner elbow sun young";
+
+const BOOT_TIMEOUT: Duration = Duration::from_secs(30);
+const SCREEN_TIMEOUT: Duration = Duration::from_secs(5);
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// The devices the review is driven on, all of them navigated with buttons
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Model {
+    NanoS,
+    NanoX,
+    NanoSP,
+}
+
+impl Model {
+    /// The model name Speculos expects
+    pub fn name(self) -> &'static str {
+        match self {
+            Model::NanoS => "nanos",
+            Model::NanoX => "nanox",
+            Model::NanoSP => "nanosp",
+        }
+    }
+
+    /// The snapshot prefix, the same as in tests_zemu/snapshots
+    pub fn prefix(self) -> &'static str {
+        match self {
+            Model::NanoS => "s",
+            Model::NanoX => "x",
+            Model::NanoSP => "sp",
+        }
+    }
+
+    pub fn elf(self) -> PathBuf {
+        let name = match self {
+            Model::NanoS => "app_s.elf",
+            Model::NanoX => "app_x.elf",
+            Model::NanoSP => "app_s2.elf",
+        };
+        [env!("CARGO_MANIFEST_DIR"), "..", "app", "output", name]
+            .iter()
+            .collect()
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum Button {
+    Left,
+    Right,
+    Both,
+}
+
+#[derive(Deserialize)]
+struct ApduResponse {
+    data: String,
+}
+
+#[derive(Deserialize)]
+struct Event {
+    text: String,
+}
+
+#[derive(Deserialize)]
+struct Events {
+    events: Vec<Event>,
+}
+
+pub struct Speculos {
+    child: Child,
+    url: String,
+    client: Client,
+}
+
+impl Speculos {
+    /// Boots the app of model with the test seed, serving the API on port.
+    /// The speculos binary is taken from $SPECULOS if it is set
+    pub fn start(model: Model, port: u16) -> Result<Self> {
+        let elf = model.elf();
+        if !elf.exists() {
+            return Err(format!("{} not found, build the app first", elf.display()).into());
+        }
+
+        let speculos = std::env::var("SPECULOS").unwrap_or_else(|_| "speculos".into());
+        let child = Command::new(speculos)
+            .arg("--model")
+            .arg(model.name())
+            .arg("--display")
+            .arg("headless")
+            .arg("--api-port")
+            .arg(port.to_string())
+            .arg("--apdu-port")
+            .arg("0")
+            .arg("--seed")
+            .arg(APP_SEED)
+            .arg(&elf)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()?;
+
+        let speculos = Self {
+            child,
+            url: format!("http://127.0.0.1:{}", port),
+            client: Client::builder().timeout(None).build()?,
+        };
+
+        // the API answers once the app is on its main menu
+        let start = Instant::now();
+        while speculos.screen_text().is_err() {
+            if start.elapsed() > BOOT_TIMEOUT {
+                return Err(format!("speculos did not start on port {}", port).into());
+            }
+            thread::sleep(POLL_INTERVAL);
+        }
+        Ok(speculos)
+    }
+
+    /// Exchanges an APDU, the response ends with the status word
+    pub fn exchange(&self, apdu: &[u8]) -> Result<Vec<u8>> {
+        exchange(&self.client, &self.url, apdu)
+    }
+
+    /// Sends an APDU that only gets a response once the review is over
+    pub fn exchange_in_background(&self, apdu: &[u8]) -> JoinHandle<Result<Vec<u8>>> {
+        let client = self.client.clone();
+        let url = self.url.clone();
+        let apdu = apdu.to_vec();
+        thread::spawn(move || exchange(&client, &url, &apdu))
+    }
+
+    pub fn press(&self, button: Button) -> Result<()> {
+        let name = match button {
+            Button::Left => "left",
+            Button::Right => "right",
+            Button::Both => "both",
+        };
+        self.client
+            .post(format!("{}/button/{}", self.url, name))
+            .json(&json!({ "action": "press-and-release" }))
+            .send()?
+            .error_for_status()?;
+        Ok(())
+    }
+
+    /// The current screen as a PNG
+    pub fn screenshot(&self) -> Result<Vec<u8>> {
+        let png = self
+            .client
+            .get(format!("{}/screenshot", self.url))
+            .send()?
+            .error_for_status()?
+            .bytes()?;
+        Ok(png.to_vec())
+    }
+
+    /// The lines of text on the current screen
+    pub fn screen_text(&self) -> Result<Vec<String>> {
+        let events: Events = self
+            .client
+            .get(format!("{}/events?currentscreenonly=true", self.url))
+            .send()?
+            .error_for_status()?
+            .json()?;
+        Ok(events.events.into_iter().map(|e| e.text).collect())
+    }
+
+    /// Waits until the screen is no longer previous and returns it. Two
+    /// items can look the same, so after a while the screen is taken as is
+    pub fn wait_screen_change(&self, previous: &[u8]) -> Result<Vec<u8>> {
+        let start = Instant::now();
+        loop {
+            let screen = self.screenshot()?;
+            if screen != previous || start.elapsed() > SCREEN_TIMEOUT {
+                return Ok(screen);
+            }
+            thread::sleep(POLL_INTERVAL);
+        }
+    }
+}
+
+impl Drop for Speculos {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+fn exchange(client: &Client, url: &str, apdu: &[u8]) -> Result<Vec<u8>> {
+    let response: ApduResponse = client
+        .post(format!("{}/apdu", url))
+        .json(&json!({ "data": hex::encode(apdu) }))
+        .send()?
+        .error_for_status()?
+        .json()?;
+    Ok(hex::decode(response.data)?)
+}
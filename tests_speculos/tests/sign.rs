@@ -0,0 +1,61 @@
+use speculos_e2e::app::{App, TESTNET_SINGLESIG};
+use speculos_e2e::speculos::{Model, Speculos};
+use speculos_e2e::{
+    as_origin, compare_snapshots, hash160, presig_hash, snapshots_dir, vectors, verify, PATH,
+};
+
+// each model gets its own API port so the tests can run in parallel
+fn sign_vectors(model: Model, port: u16) {
+    let sim = Speculos::start(model, port).unwrap();
+    let app = App::new(&sim);
+    let (pubkey, _) = app.get_address(&PATH, TESTNET_SINGLESIG).unwrap();
+    let signer = hash160(&pubkey);
+
+    let mut failures = Vec::new();
+    for vector in vectors().unwrap() {
+        let raw = match as_origin(&vector.raw, &signer).unwrap() {
+            Some(raw) => raw,
+            None => continue,
+        };
+
+        let review = match app.sign(&PATH, &raw) {
+            Ok(review) => review,
+            Err(e) => {
+                failures.push(format!("{}: {}", vector.name, e));
+                continue;
+            }
+        };
+
+        let presig = presig_hash(&raw, &signer).unwrap();
+        if let Err(e) = verify(&review.signature, &pubkey, &presig) {
+            failures.push(format!("{}: {}", vector.name, e));
+        }
+
+        let dir = snapshots_dir(model.prefix(), &vector.name);
+        for mismatch in compare_snapshots(&dir, &review.screens).unwrap() {
+            failures.push(format!("{}: {} differs", vector.name, mismatch));
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "{}:\n{}\nrun with UPDATE_SNAPSHOTS=1 if the new screens are intended",
+        model.name(),
+        failures.join("\n")
+    );
+}
+
+#[test]
+fn sign_vectors_nanos() {
+    sign_vectors(Model::NanoS, 5001);
+}
+
+#[test]
+fn sign_vectors_nanox() {
+    sign_vectors(Model::NanoX, 5002);
+}
+
+#[test]
+fn sign_vectors_nanosp() {
+    sign_vectors(Model::NanoSP, 5003);
+}
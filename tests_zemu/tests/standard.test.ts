@@ -91,6 +91,8 @@ describe('Standard', function () {
       expect(resp).toHaveProperty('major')
       expect(resp).toHaveProperty('minor')
       expect(resp).toHaveProperty('patch')
+      expect(resp.protocolVersion).toBeGreaterThan(0)
+      expect(resp.maxTxSize).toBeGreaterThan(0)
     } finally {
       await sim.close()
     }